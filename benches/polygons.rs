@@ -0,0 +1,122 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Compares [`CompactPolygon`], which stores triangles and quads inline, against
+//! [`SimplePolygon`], which makes one heap allocation per polygon.
+//!
+//! Run with `cargo bench --bench polygons`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use obj::{CompactPolygon, ObjData, SimplePolygon};
+
+/// Counts allocations and allocated bytes, to measure what building each representation costs.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// The number of allocations made and bytes still held after running `f`.
+fn allocated<T>(f: impl FnOnce() -> T) -> (T, usize, usize) {
+    let (allocations, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), BYTES.load(Ordering::Relaxed));
+    let value = f();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    (value, allocations, BYTES.load(Ordering::Relaxed) - bytes)
+}
+
+/// A grid of quads, with every other row split into triangles like typical exported meshes.
+fn grid(size: usize) -> String {
+    let mut source = String::new();
+    for y in 0..=size {
+        for x in 0..=size {
+            writeln!(source, "v {} {} 0", x, y).unwrap();
+        }
+    }
+    for y in 0..size {
+        for x in 0..size {
+            let (a, b) = (y * (size + 1) + x + 1, (y + 1) * (size + 1) + x + 1);
+            if y % 2 == 0 {
+                writeln!(source, "f {} {} {} {}", a, a + 1, b + 1, b).unwrap();
+            } else {
+                writeln!(source, "f {} {} {}\nf {} {} {}", a, a + 1, b + 1, a, b + 1, b).unwrap();
+            }
+        }
+    }
+    source
+}
+
+fn time<T>(name: &str, runs: u32, mut f: impl FnMut() -> T) {
+    let mut best = Duration::MAX;
+    for _ in 0..runs {
+        let start = Instant::now();
+        black_box(f());
+        best = best.min(start.elapsed());
+    }
+    println!("{:<40} {:>10.2?}", name, best);
+}
+
+fn main() {
+    let source = grid(500);
+    let data = ObjData::load_buf(source.as_bytes()).unwrap();
+    let group = &data.objects[0].groups[0];
+    let (simple, simple_allocations, simple_bytes) = allocated(|| group.polys.clone());
+    let (compact, compact_allocations, compact_bytes) = allocated(|| group.compact_polygons());
+    println!("{} polygons", simple.len());
+    for (name, allocations, bytes) in [
+        ("CompactPolygon", compact_allocations, compact_bytes),
+        ("SimplePolygon", simple_allocations, simple_bytes),
+    ] {
+        println!("{:<40} {:>10} KiB in {} allocations", name, bytes / 1024, allocations);
+    }
+
+    time("parse", 5, || ObjData::load_buf(source.as_bytes()).unwrap());
+    time("iterate CompactPolygon", 20, || {
+        compact.iter().flat_map(|p| p.iter()).map(|t| t.position).sum::<usize>()
+    });
+    time("iterate SimplePolygon", 20, || {
+        simple
+            .iter()
+            .flat_map(|p| p.0.iter())
+            .map(|t| t.position)
+            .sum::<usize>()
+    });
+    time("clone CompactPolygon", 5, || compact.clone());
+    time("clone SimplePolygon", 5, || simple.clone());
+    time("convert to CompactPolygon", 5, || {
+        simple.iter().map(CompactPolygon::from).collect::<Vec<_>>()
+    });
+    time("convert to SimplePolygon", 5, || {
+        compact.iter().map(SimplePolygon::from).collect::<Vec<_>>()
+    });
+}
@@ -0,0 +1,111 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Comparison of meshes within a tolerance.
+
+use crate::obj::{Group, IndexTuple, ObjData, Object, Scalar};
+
+fn close<A: Copy + Into<f64>, B: Copy + Into<f64>>(a: &[A], b: &[B], epsilon: f64) -> bool {
+    a.iter().zip(b).all(|(&a, &b)| (a.into() - b.into()).abs() <= epsilon)
+}
+
+fn close_option<const N: usize>(a: Option<[f32; N]>, b: Option<[f32; N]>, epsilon: f64) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => close(&a, &b, epsilon),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Pair up items with the same key in order of appearance, failing if the keys don't match up.
+fn pair_by_key<'a, T, K: PartialEq>(a: &'a [T], b: &'a [T], key: impl Fn(&T) -> K) -> Option<Vec<(&'a T, &'a T)>> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let mut used = vec![false; b.len()];
+    a.iter()
+        .map(|x| {
+            let j = (0..b.len()).find(|&j| !used[j] && key(&b[j]) == key(x))?;
+            used[j] = true;
+            Some((x, &b[j]))
+        })
+        .collect()
+}
+
+impl<S: Scalar + Into<f64>> ObjData<S> {
+    /// Whether `other` describes the same mesh, with attribute values differing by at most
+    /// `epsilon` per component.
+    ///
+    /// Polygons and lines are compared by the values their vertices refer to, so the order of
+    /// the attribute lists doesn't matter, and a polygon may start at any of its vertices as long
+    /// as the winding is the same. Objects and groups are matched by name, and groups also by
+    /// material name, in any order. Everything else, such as the order of polygons within a group,
+    /// has to match exactly.
+    ///
+    /// Material libraries are not compared. Out of range indices make the meshes unequal.
+    pub fn approx_eq(&self, other: &ObjData<S>, epsilon: f64) -> bool {
+        let objects = match pair_by_key(&self.objects, &other.objects, |o| o.name.clone()) {
+            Some(objects) => objects,
+            None => return false,
+        };
+        objects
+            .into_iter()
+            .all(|(a, b)| self.objects_approx_eq(a, other, b, epsilon))
+    }
+
+    fn objects_approx_eq(&self, a: &Object, other: &ObjData<S>, b: &Object, epsilon: f64) -> bool {
+        let key = |g: &Group| (g.name.clone(), g.material.as_ref().map(|m| m.name().to_string()));
+        let groups = match pair_by_key(&a.groups, &b.groups, key) {
+            Some(groups) => groups,
+            None => return false,
+        };
+        groups.into_iter().all(|(a, b)| {
+            a.polys.len() == b.polys.len()
+                && a.lines.len() == b.lines.len()
+                && a.polys.iter().zip(&b.polys).all(|(pa, pb)| {
+                    pa.0.len() == pb.0.len()
+                        && (0..pb.0.len().max(1)).any(|start| {
+                            let rotated = pb.0[start..].iter().chain(&pb.0[..start]);
+                            self.tuples_approx_eq(&pa.0, other, rotated, epsilon)
+                        })
+                })
+                && a.lines
+                    .iter()
+                    .zip(&b.lines)
+                    .all(|(la, lb)| la.0.len() == lb.0.len() && self.tuples_approx_eq(&la.0, other, &lb.0, epsilon))
+        })
+    }
+
+    fn tuples_approx_eq<'a>(
+        &self,
+        a: &[IndexTuple],
+        other: &ObjData<S>,
+        b: impl IntoIterator<Item = &'a IndexTuple>,
+        epsilon: f64,
+    ) -> bool {
+        a.iter().zip(b).all(|(ta, tb)| {
+            let (va, vb) = match (self.resolve(ta), other.resolve(tb)) {
+                (Some(va), Some(vb)) => (va, vb),
+                _ => return false,
+            };
+            let white = [1.0; 3];
+            let ca = self.color.get(ta.position).unwrap_or(&white);
+            let cb = other.color.get(tb.position).unwrap_or(&white);
+            close(&va.position, &vb.position, epsilon)
+                && close(ca, cb, epsilon)
+                && close_option(va.texture, vb.texture, epsilon)
+                && close_option(va.normal, vb.normal, epsilon)
+        })
+    }
+}
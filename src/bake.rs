@@ -0,0 +1,162 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Baking of vertex colors into materials.
+
+use std::sync::Arc;
+
+use crate::mtl::Material;
+use crate::obj::{Group, IndexTuple, ObjData, ObjMaterial};
+
+/// Rounds of refinement of the palette in [`quantize`].
+const ITERATIONS: usize = 16;
+
+fn distance2(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (0..3).map(|i| (a[i] - b[i]) * (a[i] - b[i])).sum()
+}
+
+fn nearest(palette: &[[f32; 3]], color: [f32; 3]) -> usize {
+    (0..palette.len())
+        .min_by(|&a, &b| distance2(palette[a], color).total_cmp(&distance2(palette[b], color)))
+        .unwrap()
+}
+
+/// Find up to `count` colors representing `colors` by k-means, starting from colors that are far
+/// apart so the result doesn't depend on chance.
+fn quantize(colors: &[[f32; 3]], count: usize) -> Vec<[f32; 3]> {
+    let mut palette: Vec<[f32; 3]> = Vec::new();
+    while palette.len() < count {
+        let farthest = colors.iter().copied().max_by(|&a, &b| {
+            let d = |c| palette.iter().map(|&p| distance2(p, c)).fold(f32::INFINITY, f32::min);
+            d(a).total_cmp(&d(b))
+        });
+        match farthest {
+            Some(c) if !palette.contains(&c) => palette.push(c),
+            _ => break,
+        }
+    }
+
+    for _ in 0..ITERATIONS {
+        let mut sums = vec![([0.0f32; 3], 0usize); palette.len()];
+        for &color in colors {
+            let (sum, n) = &mut sums[nearest(&palette, color)];
+            for i in 0..3 {
+                sum[i] += color[i];
+            }
+            *n += 1;
+        }
+        let next: Vec<_> = sums
+            .into_iter()
+            .filter(|&(_, n)| n > 0)
+            .map(|(sum, n)| sum.map(|s| s / n as f32))
+            .collect();
+        if next == palette {
+            break;
+        }
+        palette = next;
+    }
+    palette
+}
+
+impl ObjData {
+    /// Assign every polygon and line the material of the closest of at most `count` colors
+    /// representing the vertex colors, for renderers that ignore vertex colors.
+    ///
+    /// The color of an element is the average color of its vertices. The colors are reduced to a
+    /// palette by k-means, and each palette color becomes a material named `vertex_color_<i>`
+    /// with the color as its diffuse color, which is returned in palette order. Groups are split
+    /// into consecutive ranges of the same name, one per material, like by
+    /// [`Object::merge_groups_by`](crate::Object::merge_groups_by), replacing the materials they
+    /// had before. The vertex colors are kept. Without vertex colors nothing is changed and no
+    /// materials are returned.
+    ///
+    /// The materials have to be added to a material library for files written afterwards to
+    /// refer to them.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn bake_vertex_colors(&mut self, count: usize) -> Vec<Arc<Material>> {
+        if self.color.is_empty() || count == 0 {
+            return Vec::new();
+        }
+        let color = &self.color;
+        let average = |tuples: &[IndexTuple]| {
+            let mut sum = [0.0; 3];
+            for tuple in tuples {
+                let c = color.get(tuple.position).copied().unwrap_or([1.0; 3]);
+                for i in 0..3 {
+                    sum[i] += c[i];
+                }
+            }
+            sum.map(|s| s / tuples.len().max(1) as f32)
+        };
+        let colors: Vec<_> = self
+            .objects
+            .iter()
+            .flat_map(|o| &o.groups)
+            .flat_map(|g| g.polys.iter().map(|p| &p.0[..]).chain(g.lines.iter().map(|l| &l.0[..])))
+            .map(average)
+            .collect();
+        let palette = quantize(&colors, count);
+        let materials: Vec<_> = palette
+            .iter()
+            .enumerate()
+            .map(|(i, &kd)| {
+                let mut material = Material::new(format!("vertex_color_{}", i));
+                material.kd = Some(kd);
+                Arc::new(material)
+            })
+            .collect();
+
+        let mut colors = colors.into_iter().map(|c| nearest(&palette, c));
+        for object in &mut self.objects {
+            let mut groups: Vec<Group> = Vec::new();
+            for group in object.groups.drain(..) {
+                // The ranges of the group, by material.
+                let mut ranges: Vec<(usize, Group)> = Vec::new();
+                let elements = group.polys.len() + group.lines.len();
+                for (e, m) in colors.by_ref().take(elements).enumerate() {
+                    let i = match ranges.iter().position(|&(r, _)| r == m) {
+                        Some(i) => i,
+                        None => {
+                            let mut range = Group::new(group.name.clone());
+                            range.material = Some(ObjMaterial::Mtl(materials[m].clone()));
+                            ranges.push((m, range));
+                            ranges.len() - 1
+                        }
+                    };
+                    let range = &mut ranges[i].1;
+                    match group.polys.get(e) {
+                        Some(poly) => {
+                            range.set_render_state(group.poly_render_state(e).clone());
+                            range.polys.push(poly.clone());
+                        }
+                        None => {
+                            let l = e - group.polys.len();
+                            range.set_render_state(group.line_render_state(l).clone());
+                            range.lines.push(group.lines[l].clone());
+                        }
+                    }
+                }
+                groups.extend(ranges.into_iter().map(|(_, range)| range));
+            }
+            for i in 0..groups.len() {
+                groups[i].index = match i.checked_sub(1) {
+                    Some(prev) if groups[prev].name == groups[i].name => groups[prev].index + 1,
+                    _ => 0,
+                };
+            }
+            object.groups = groups;
+        }
+        materials
+    }
+}
@@ -0,0 +1,264 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Loading .obj files as Bevy assets.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use bevy::app::{App, Plugin};
+use bevy::asset::io::Reader;
+use bevy::asset::{
+    Asset, AssetApp, AssetLoader, AssetPath, Handle, LoadContext, ParseAssetPathError, ReadAssetBytesError,
+    RenderAssetUsages,
+};
+use bevy::color::{Color, LinearRgba};
+use bevy::material::AlphaMode;
+use bevy::mesh::{Indices, Mesh, PrimitiveTopology};
+use bevy::pbr::StandardMaterial;
+use bevy::reflect::TypePath;
+
+use crate::buffers::{Submesh, Vertex};
+use crate::obj::{MtlLibsLoadError, Obj, ObjData, ObjError, ObjMaterial};
+
+/// The meshes and materials loaded from an .obj file by [`ObjAssetLoader`].
+///
+/// There is one mesh per material used by the file, paired with the material at the same index.
+/// Both are also available as labeled assets named `Mesh{index}` and `Material{index}`.
+#[derive(Asset, TypePath, Debug)]
+pub struct ObjAsset {
+    #[dependency]
+    pub meshes: Vec<Handle<Mesh>>,
+    #[dependency]
+    pub materials: Vec<Handle<StandardMaterial>>,
+}
+
+/// Errors loading an .obj file as a Bevy asset.
+#[derive(Debug)]
+pub enum ObjAssetError {
+    Io(std::io::Error),
+    Obj(ObjError),
+    /// A referenced .mtl file or texture path is invalid.
+    Path(ParseAssetPathError),
+    /// A referenced .mtl file could not be read.
+    ReadMtl(ReadAssetBytesError),
+    Mtl(MtlLibsLoadError),
+}
+
+impl std::error::Error for ObjAssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjAssetError::Io(err) => Some(err),
+            ObjAssetError::Obj(err) => Some(err),
+            ObjAssetError::Path(err) => Some(err),
+            ObjAssetError::ReadMtl(err) => Some(err),
+            ObjAssetError::Mtl(err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for ObjAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjAssetError::Io(err) => write!(f, "I/O error loading an .obj asset: {}", err),
+            ObjAssetError::Obj(err) => write!(f, "{}", err),
+            ObjAssetError::Path(err) => write!(f, "Invalid asset path: {}", err),
+            ObjAssetError::ReadMtl(err) => write!(f, "Failed to read a .mtl file: {}", err),
+            ObjAssetError::Mtl(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<std::io::Error> for ObjAssetError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ObjError> for ObjAssetError {
+    fn from(e: ObjError) -> Self {
+        Self::Obj(e)
+    }
+}
+
+impl From<ParseAssetPathError> for ObjAssetError {
+    fn from(e: ParseAssetPathError) -> Self {
+        Self::Path(e)
+    }
+}
+
+impl From<ReadAssetBytesError> for ObjAssetError {
+    fn from(e: ReadAssetBytesError) -> Self {
+        Self::ReadMtl(e)
+    }
+}
+
+impl From<MtlLibsLoadError> for ObjAssetError {
+    fn from(e: MtlLibsLoadError) -> Self {
+        Self::Mtl(e)
+    }
+}
+
+/// A Bevy `AssetLoader` for .obj files and the .mtl files they reference.
+///
+/// Texture maps are loaded through the asset server, relative to their .mtl file.
+#[derive(Default, TypePath)]
+pub struct ObjAssetLoader;
+
+/// Registers [`ObjAsset`] and [`ObjAssetLoader`] with a Bevy app.
+#[derive(Default)]
+pub struct ObjPlugin;
+
+impl Plugin for ObjPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<ObjAsset>().init_asset_loader::<ObjAssetLoader>();
+    }
+}
+
+fn submesh_to_mesh(vertices: &[Vertex], submesh: &Submesh, has_texture: bool, has_normal: bool) -> Mesh {
+    // Every submesh gets its own vertex buffer with only the vertices it uses.
+    let mut remap = HashMap::new();
+    let mut used = Vec::new();
+    let indices = submesh
+        .indices
+        .iter()
+        .map(|&i| {
+            *remap.entry(i).or_insert_with(|| {
+                used.push(vertices[i as usize]);
+                used.len() as u32 - 1
+            })
+        })
+        .collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        used.iter().map(|v| v.position).collect::<Vec<_>>(),
+    );
+    if has_texture {
+        // Bevy puts the origin of texture coordinates at the top left.
+        let uvs: Vec<_> = used.iter().map(|v| [v.texture[0], 1.0 - v.texture[1]]).collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
+    mesh.insert_indices(Indices::U32(indices));
+    if has_normal {
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_NORMAL,
+            used.iter().map(|v| v.normal).collect::<Vec<_>>(),
+        );
+    } else {
+        mesh.compute_smooth_normals();
+    }
+    mesh
+}
+
+fn material_to_standard(
+    material: Option<&ObjMaterial>,
+    texture_base: &HashMap<String, AssetPath<'static>>,
+    load_context: &mut LoadContext,
+) -> Result<StandardMaterial, ObjAssetError> {
+    let material = match material {
+        Some(ObjMaterial::Mtl(material)) => material,
+        _ => return Ok(StandardMaterial::default()),
+    };
+    let pbr = material.to_pbr();
+    let base = texture_base.get(&material.name);
+    let mut texture = |path: &Option<String>| -> Result<_, ObjAssetError> {
+        match (path, base) {
+            (Some(path), Some(base)) => Ok(Some(load_context.load(base.resolve_embed_str(path)?))),
+            _ => Ok(None),
+        }
+    };
+    let [r, g, b, a] = pbr.base_color;
+    let [er, eg, eb] = pbr.emissive;
+    Ok(StandardMaterial {
+        base_color: Color::linear_rgba(r, g, b, a),
+        base_color_texture: texture(&pbr.base_color_texture)?,
+        emissive: LinearRgba::rgb(er, eg, eb),
+        emissive_texture: texture(&pbr.emissive_texture)?,
+        normal_map_texture: texture(&pbr.normal_texture)?,
+        metallic: pbr.metallic,
+        perceptual_roughness: pbr.roughness,
+        alpha_mode: if pbr.transparent {
+            AlphaMode::Blend
+        } else {
+            AlphaMode::Opaque
+        },
+        ..StandardMaterial::default()
+    })
+}
+
+impl AssetLoader for ObjAssetLoader {
+    type Asset = ObjAsset;
+    type Settings = ();
+    type Error = ObjAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<ObjAsset, ObjAssetError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let data = ObjData::load_buf(&bytes[..])?;
+
+        let mut mtl_bytes = HashMap::new();
+        let mut mtl_paths = HashMap::new();
+        for mtl in &data.material_libs {
+            let path = load_context.path().resolve_embed_str(&mtl.filename)?;
+            mtl_bytes.insert(mtl.filename.clone(), load_context.read_asset_bytes(&path).await?);
+            mtl_paths.insert(mtl.filename.clone(), path);
+        }
+        let mut obj = Obj {
+            data,
+            path: PathBuf::new(),
+        };
+        obj.load_mtls_fn(|_, filename| Ok(&mtl_bytes[filename][..]))?;
+
+        // Textures are resolved relative to the library defining their material.
+        let mut texture_base = HashMap::new();
+        for mtl in &obj.data.material_libs {
+            for material in &mtl.materials {
+                texture_base
+                    .entry(material.name.clone())
+                    .or_insert_with(|| mtl_paths[&mtl.filename].clone());
+            }
+        }
+
+        let buffers = obj.data.to_indexed_buffers_by_material();
+        let (has_texture, has_normal) = (!obj.data.texture.is_empty(), !obj.data.normal.is_empty());
+        let mut asset = ObjAsset {
+            meshes: Vec::new(),
+            materials: Vec::new(),
+        };
+        for submesh in buffers.submeshes.iter().filter(|s| !s.indices.is_empty()) {
+            let index = asset.meshes.len();
+            let mesh = submesh_to_mesh(&buffers.vertices, submesh, has_texture, has_normal);
+            let material = material_to_standard(submesh.material.as_ref(), &texture_base, load_context)?;
+            asset
+                .meshes
+                .push(load_context.add_labeled_asset(format!("Mesh{}", index), mesh));
+            asset
+                .materials
+                .push(load_context.add_labeled_asset(format!("Material{}", index), material));
+        }
+        Ok(asset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["obj"]
+    }
+}
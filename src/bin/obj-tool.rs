@@ -0,0 +1,173 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Command line access to the loading, checking and conversion functions of this crate.
+//!
+//! Built with the `cli` feature, e.g. `cargo run --features cli --bin obj-tool -- stats mesh.obj`.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use obj::{BoundingBox, LoadConfig, ObjData};
+
+const USAGE: &str = "\
+usage: obj-tool <command> <input> [<output>]
+
+commands:
+    validate <input.obj>             load in strict mode and check indices and topology
+    stats <input>                    print element counts
+    triangulate <input> <output>     split all polygons into triangles
+    convert <input> <output>         convert between formats, chosen by file extension
+    normalize <input> <output>       center on the origin and scale to fit a unit cube
+
+Supported extensions are obj, stl, ply and off, as well as gltf when built with the `gltf`
+feature. Material libraries of .obj files are referenced, not copied.";
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+fn load(path: &Path, config: LoadConfig) -> Result<ObjData> {
+    Ok(match extension(path).as_str() {
+        "obj" => ObjData::load_buf_with(File::open(path)?, config)?,
+        "stl" => obj::stl::load(path)?,
+        "ply" => obj::ply::load(path)?,
+        "off" => obj::off::load(path)?,
+        ext => return Err(format!("unsupported input format `{}`", ext).into()),
+    })
+}
+
+fn save(data: &ObjData, path: &Path) -> Result<()> {
+    match extension(path).as_str() {
+        // Only the .obj itself is written, so existing material libraries are left untouched.
+        "obj" => data.write_to_buf(&mut BufWriter::new(File::create(path)?))?,
+        "stl" => {
+            // STL only holds triangles.
+            let mut data = data.clone();
+            data.to_triangles();
+            obj::stl::save(&data, path, obj::stl::StlFormat::Binary)?
+        }
+        "ply" => obj::ply::save(data, path, obj::ply::PlyFormat::BinaryLittleEndian)?,
+        "off" => obj::off::save(data, path)?,
+        #[cfg(feature = "gltf")]
+        "gltf" => data.save_gltf(path)?,
+        ext => return Err(format!("unsupported output format `{}`", ext).into()),
+    }
+    Ok(())
+}
+
+/// Returns whether the file is valid.
+fn validate(path: &Path) -> Result<bool> {
+    let data = match load(path, LoadConfig::new().strict(true)) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("error: {}", err);
+            return Ok(false);
+        }
+    };
+    let mut valid = true;
+    let stats = data.stats();
+    let ranges = [
+        ("position", stats.position_index_range, stats.positions),
+        ("texture coordinate", stats.texture_index_range, stats.texture_coords),
+        ("normal", stats.normal_index_range, stats.normals),
+    ];
+    for (name, range, count) in ranges {
+        if let Some((_, max)) = range.filter(|&(_, max)| max >= count) {
+            println!("error: {} index {} is out of range, there are {}", name, max + 1, count);
+            valid = false;
+        }
+    }
+    if stats.degenerate_polygons != 0 {
+        println!(
+            "warning: {} polygons have fewer than three vertices",
+            stats.degenerate_polygons
+        );
+    }
+    if valid {
+        let manifold = data.check_manifold();
+        if !manifold.non_manifold_edges.is_empty() {
+            println!("warning: {} non-manifold edges", manifold.non_manifold_edges.len());
+        }
+        if !manifold.inconsistent_winding.is_empty() {
+            println!(
+                "warning: {} edges with inconsistent winding",
+                manifold.inconsistent_winding.len()
+            );
+        }
+    }
+    if valid {
+        println!("ok");
+    }
+    Ok(valid)
+}
+
+fn normalize(data: &mut ObjData) {
+    if let Some(bounds) = BoundingBox::from_points(&data.position) {
+        let center = bounds.center();
+        data.translate([-center[0], -center[1], -center[2]]);
+        let size = bounds.size();
+        let extent = size[0].max(size[1]).max(size[2]);
+        if extent > 0.0 {
+            data.scale([1.0 / extent; 3]);
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<bool> {
+    let paths: Vec<PathBuf> = args.iter().skip(1).map(PathBuf::from).collect();
+    let (command, input, output) = match (args.first(), paths.as_slice()) {
+        (Some(command), [input]) => (command.as_str(), input, None),
+        (Some(command), [input, output]) => (command.as_str(), input, Some(output)),
+        _ => return Err(USAGE.into()),
+    };
+    let output = || output.ok_or_else(|| Box::<dyn Error>::from(USAGE));
+
+    match command {
+        "validate" => return validate(input),
+        "stats" => println!("{}", load(input, LoadConfig::default())?.stats()),
+        "triangulate" => {
+            let mut data = load(input, LoadConfig::default())?;
+            data.to_triangles();
+            save(&data, output()?)?;
+        }
+        "convert" => save(&load(input, LoadConfig::default())?, output()?)?,
+        "normalize" => {
+            let mut data = load(input, LoadConfig::default())?;
+            normalize(&mut data);
+            save(&data, output()?)?;
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
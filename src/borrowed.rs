@@ -0,0 +1,312 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Parsing of in-memory .obj files without copying names out of the source.
+
+use std::borrow::Cow;
+
+use crate::mtl::Mtl;
+use crate::obj::{
+    change_render_state, parse_index_tuple, Group, IndexTuple, Interner, Line, LoadConfig, ObjData, ObjError,
+    ObjMaterial, ObjMetadata, Object, RenderState, SimplePolygon, StateChange, DEFAULT_GROUP, DEFAULT_OBJECT,
+};
+
+/// An [`Object`] whose names borrow from the parsed source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectRef<'a> {
+    /// Name of the object assigned by the `o ...` command.
+    pub name: Cow<'a, str>,
+    /// Groups belonging to this object.
+    pub groups: Vec<GroupRef<'a>>,
+}
+
+/// A [`Group`] whose names borrow from the parsed source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupRef<'a> {
+    /// Name of the group assigned by the `g ...` command.
+    pub name: Cow<'a, str>,
+    /// Tells groups apart that share the same name, see [`Group::index`].
+    pub index: usize,
+    /// Material name given by the `usemtl ...` command.
+    pub material: Option<Cow<'a, str>>,
+    /// A list of polygons appearing as `f ...`.
+    pub polys: Vec<SimplePolygon>,
+    /// A list of polylines appearing as `l ...`.
+    pub lines: Vec<Line>,
+    /// See [`Group::render_state`].
+    pub render_state: RenderState,
+    /// See [`Group::state_changes`].
+    pub state_changes: Vec<StateChange>,
+}
+
+/// A borrowed counterpart of [`ObjData`], parsed from a string slice.
+///
+/// Object, group, material and material library names point into the source wherever possible,
+/// avoiding an allocation per name. Use [`ObjRef::into_owned`] to get an [`ObjData`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjRef<'a> {
+    pub position: Vec<[f32; 3]>,
+    /// Either empty or as long as `position`, see [`ObjData::color`].
+    pub color: Vec<[f32; 3]>,
+    pub texture: Vec<[f32; 2]>,
+    pub normal: Vec<[f32; 3]>,
+    pub objects: Vec<ObjectRef<'a>>,
+    /// File names of the `mtllib` references.
+    pub material_libs: Vec<Cow<'a, str>>,
+    /// See [`ObjData::shadow_obj`].
+    pub shadow_obj: Option<&'a str>,
+    /// See [`ObjData::trace_obj`].
+    pub trace_obj: Option<&'a str>,
+    /// See [`ObjData::metadata`].
+    pub metadata: ObjMetadata,
+}
+
+impl<'a> GroupRef<'a> {
+    fn new(name: Cow<'a, str>, render_state: &RenderState) -> Self {
+        GroupRef {
+            name,
+            index: 0,
+            material: None,
+            polys: Vec::new(),
+            lines: Vec::new(),
+            render_state: render_state.clone(),
+            state_changes: Vec::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.polys.is_empty() && self.lines.is_empty()
+    }
+
+    /// See [`Group::continue_empty`].
+    fn continue_empty(&mut self) {
+        self.index += 1;
+        if let Some(change) = self.state_changes.pop() {
+            self.render_state = change.state;
+        }
+        self.state_changes.clear();
+        self.polys.clear();
+        self.lines.clear();
+    }
+
+    fn into_owned(self, names: &mut Interner) -> Group {
+        Group {
+            name: names.intern(&self.name),
+            index: self.index,
+            material: self.material.map(|m| ObjMaterial::Ref(names.intern(&m))),
+            polys: self.polys,
+            lines: self.lines,
+            render_state: self.render_state,
+            state_changes: self.state_changes,
+        }
+    }
+}
+
+impl<'a> ObjectRef<'a> {
+    fn new(name: Cow<'a, str>) -> Self {
+        ObjectRef {
+            name,
+            groups: Vec::new(),
+        }
+    }
+
+    fn into_owned(self, names: &mut Interner) -> Object {
+        Object {
+            name: names.intern(&self.name),
+            groups: self.groups.into_iter().map(|g| g.into_owned(names)).collect(),
+        }
+    }
+}
+
+impl<'a> ObjRef<'a> {
+    /// Parse an .obj file from a string slice.
+    ///
+    /// This accepts the same input as [`ObjData::load_buf`].
+    pub fn parse(source: &'a str) -> Result<Self, ObjError> {
+        Self::parse_with_config(source, LoadConfig::default())
+    }
+
+    pub fn parse_with_config(source: &'a str, config: LoadConfig) -> Result<Self, ObjError> {
+        let mut dat = ObjRef::default();
+        let mut object = ObjectRef::new(Cow::Borrowed(DEFAULT_OBJECT));
+        let mut group: Option<GroupRef<'a>> = None;
+        let mut state = RenderState::default();
+        let mut header = true;
+
+        for (idx, line) in source.lines().enumerate() {
+            match line.trim_start().strip_prefix('#') {
+                Some(comment) if header => dat.metadata.read_comment(comment),
+                Some(_) => (),
+                None => header &= line.trim().is_empty(),
+            }
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => {
+                    let (v0, v1, v2) = (words.next(), words.next(), words.next());
+                    dat.position.push(ObjData::<f32>::parse_three(idx, v0, v1, v2)?);
+                    let (r, g, b) = (words.next(), words.next(), words.next());
+                    if r.is_some() && g.is_some() {
+                        let color = ObjData::<f32>::parse_three(idx, r, g, b)?;
+                        dat.color.resize(dat.position.len() - 1, [1.0; 3]);
+                        dat.color.push(color);
+                    } else if !dat.color.is_empty() {
+                        dat.color.push([1.0; 3]);
+                    }
+                }
+                Some("vt") => {
+                    let (t0, t1) = (words.next(), words.next());
+                    dat.texture.push(ObjData::<f32>::parse_two(idx, t0, t1)?);
+                }
+                Some("vn") => {
+                    let (n0, n1, n2) = (words.next(), words.next(), words.next());
+                    dat.normal.push(ObjData::<f32>::parse_three(idx, n0, n1, n2)?);
+                }
+                Some("f") => {
+                    let poly = words
+                        .map(|w| dat.parse_tuple(idx, w))
+                        .collect::<Result<_, _>>()
+                        .map(SimplePolygon)?;
+                    group
+                        .get_or_insert_with(|| GroupRef::new(Cow::Borrowed(DEFAULT_GROUP), &state))
+                        .polys
+                        .push(poly);
+                }
+                Some("l") => {
+                    let line = words
+                        .map(|w| {
+                            dat.parse_tuple(idx, w)
+                                .map(|t| IndexTuple::new(t.position, t.texture, None))
+                        })
+                        .collect::<Result<_, _>>()
+                        .map(Line)?;
+                    group
+                        .get_or_insert_with(|| GroupRef::new(Cow::Borrowed(DEFAULT_GROUP), &state))
+                        .lines
+                        .push(line);
+                }
+                Some("o") => {
+                    if let Some(g) = group.take() {
+                        object.groups.push(g);
+                        dat.objects.push(object);
+                    }
+                    object = if line.len() > 2 {
+                        ObjectRef::new(Cow::Borrowed(line[1..].trim()))
+                    } else {
+                        ObjectRef::new(Cow::Borrowed(DEFAULT_OBJECT))
+                    };
+                }
+                Some("g") => {
+                    let current_material = group.as_ref().and_then(|g| g.material.clone());
+                    object.groups.extend(group.take());
+                    let name = match line[1..].trim() {
+                        "" if !config.bare_group_keeps_material || current_material.is_none() => continue,
+                        "" => DEFAULT_GROUP,
+                        name => name,
+                    };
+                    let mut g = GroupRef::new(Cow::Borrowed(name), &state);
+                    g.material = current_material;
+                    group = Some(g);
+                }
+                Some("mtllib") => {
+                    let first = words.next().ok_or(ObjError::MissingMTLName { line_number: idx })?;
+                    // Names with spaces are joined with single spaces like `ObjData::load_buf` does,
+                    // which only needs a copy if the source separates the words differently.
+                    let start = first.as_ptr() as usize - line.as_ptr() as usize;
+                    let rest = line[start..].trim_end();
+                    let name = if rest.split_whitespace().eq(rest.split(' ')) {
+                        Cow::Borrowed(rest)
+                    } else {
+                        Cow::Owned(rest.split_whitespace().collect::<Vec<_>>().join(" "))
+                    };
+                    dat.material_libs.push(name);
+                }
+                Some(keyword @ "shadow_obj") | Some(keyword @ "trace_obj") => {
+                    let file = line.trim_start()[keyword.len()..].trim();
+                    if file.is_empty() {
+                        return Err(ObjError::ArgumentListFailure {
+                            line_number: idx,
+                            list: String::new(),
+                        });
+                    }
+                    if keyword == "shadow_obj" {
+                        dat.shadow_obj = Some(file);
+                    } else {
+                        dat.trace_obj = Some(file);
+                    }
+                }
+                Some("usemtl") => {
+                    let mut g = group.unwrap_or_else(|| GroupRef::new(Cow::Borrowed(DEFAULT_GROUP), &state));
+                    if !g.is_empty() {
+                        object.groups.push(g.clone());
+                        g.continue_empty();
+                    }
+                    g.material = config.usemtl_name(words.next()).map(Cow::Borrowed);
+                    group = Some(g);
+                }
+                Some(keyword @ ("s" | "bevel" | "c_interp" | "d_interp" | "lod" | "usemap")) => {
+                    // Malformed settings are skipped like by `ObjData`, see `RenderState`.
+                    if state.apply(idx, keyword, words.next(), |map| map.into()).is_ok() {
+                        if let Some(g) = group.as_mut() {
+                            let (polys, lines) = (g.polys.len(), g.lines.len());
+                            change_render_state(&mut g.render_state, &mut g.state_changes, polys, lines, state.clone());
+                        }
+                    }
+                }
+                Some(other) if config.strict && !other.starts_with('#') => {
+                    return Err(ObjError::UnexpectedCommand {
+                        line_number: idx,
+                        command: other.to_string(),
+                    });
+                }
+                Some(_) | None => (),
+            }
+        }
+
+        if let Some(g) = group {
+            object.groups.push(g);
+        }
+        dat.objects.push(object);
+        Ok(dat)
+    }
+
+    fn parse_tuple(&self, line_number: usize, group: &str) -> Result<IndexTuple, ObjError> {
+        parse_index_tuple(
+            line_number,
+            group,
+            [self.position.len(), self.texture.len(), self.normal.len()],
+        )
+    }
+
+    /// Copy the borrowed names to get an [`ObjData`].
+    pub fn into_owned(self) -> ObjData {
+        let mut names = Interner::default();
+        ObjData {
+            position: self.position,
+            color: self.color,
+            texture: self.texture,
+            normal: self.normal,
+            objects: self.objects.into_iter().map(|o| o.into_owned(&mut names)).collect(),
+            material_libs: self
+                .material_libs
+                .into_iter()
+                .map(|name| Mtl::new(name.into_owned()))
+                .collect(),
+            shadow_obj: self.shadow_obj.map(str::to_string),
+            trace_obj: self.trace_obj.map(str::to_string),
+            default_material: None,
+            metadata: self.metadata,
+            layout: None,
+        }
+    }
+}
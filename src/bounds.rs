@@ -0,0 +1,245 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Bounding volumes of whole meshes, objects and groups.
+
+use crate::math::{add, length, scale, sub};
+use crate::obj::{Group, ObjData, Object};
+
+/// An axis-aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    /// Minimum coordinate along each axis.
+    pub min: [f32; 3],
+    /// Maximum coordinate along each axis.
+    pub max: [f32; 3],
+}
+
+impl BoundingBox {
+    /// Compute the bounding box of the given points, or `None` if there are none.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a [f32; 3]>) -> Option<BoundingBox> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        let mut bbox = BoundingBox { min: first, max: first };
+        for p in points {
+            bbox.insert(*p);
+        }
+        Some(bbox)
+    }
+
+    /// Grow the box to contain the given point.
+    pub fn insert(&mut self, p: [f32; 3]) {
+        for (i, &x) in p.iter().enumerate() {
+            self.min[i] = self.min[i].min(x);
+            self.max[i] = self.max[i].max(x);
+        }
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let mut bbox = *self;
+        bbox.insert(other.min);
+        bbox.insert(other.max);
+        bbox
+    }
+
+    /// The center point of the box.
+    pub fn center(&self) -> [f32; 3] {
+        scale(add(self.min, self.max), 0.5)
+    }
+
+    /// The extent of the box along each axis.
+    pub fn size(&self) -> [f32; 3] {
+        sub(self.max, self.min)
+    }
+
+    /// Check whether the given point lies inside or on the boundary of the box.
+    pub fn contains(&self, p: [f32; 3]) -> bool {
+        (0..3).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+
+    /// Check whether the boxes overlap or touch.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    /// The distance from the given point to the nearest point of the box, 0 inside of it.
+    pub(crate) fn distance(&self, p: [f32; 3]) -> f32 {
+        let mut d = [0.0; 3];
+        for i in 0..3 {
+            d[i] = (self.min[i] - p[i]).max(0.0).max(p[i] - self.max[i]);
+        }
+        length(d)
+    }
+}
+
+/// The range of texture coordinates used by a mesh or group.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UvBounds {
+    /// Minimum `u` and `v`.
+    pub min: [f32; 2],
+    /// Maximum `u` and `v`.
+    pub max: [f32; 2],
+}
+
+impl UvBounds {
+    /// Compute the bounds of the given texture coordinates, or `None` if there are none.
+    pub fn from_coordinates<'a>(uvs: impl IntoIterator<Item = &'a [f32; 2]>) -> Option<UvBounds> {
+        let mut uvs = uvs.into_iter();
+        let first = *uvs.next()?;
+        let mut bounds = UvBounds { min: first, max: first };
+        for uv in uvs {
+            for (i, &x) in uv.iter().enumerate() {
+                bounds.min[i] = bounds.min[i].min(x);
+                bounds.max[i] = bounds.max[i].max(x);
+            }
+        }
+        Some(bounds)
+    }
+
+    /// Whether any coordinate lies outside of `[0, 1]`, so the texture is repeated or clamped
+    /// depending on the sampler.
+    pub fn exceeds_unit_square(&self) -> bool {
+        self.min.iter().any(|&x| x < 0.0) || self.max.iter().any(|&x| x > 1.0)
+    }
+
+    /// The number of times the texture repeats along `u` and `v` across the bounds, which is 1 for
+    /// bounds within the unit square.
+    pub fn tiles(&self) -> [f32; 2] {
+        [0, 1].map(|i| (self.max[i].ceil() - self.min[i].floor()).max(1.0))
+    }
+}
+
+/// A bounding sphere.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingSphere {
+    pub center: [f32; 3],
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    /// Compute a bounding sphere of the given points, or `None` if there are none.
+    ///
+    /// This uses Ritter's algorithm, which produces a sphere that is usually within a few percent
+    /// of the minimal one.
+    pub fn from_points<'a, I>(points: I) -> Option<BoundingSphere>
+    where
+        I: IntoIterator<Item = &'a [f32; 3]>,
+        I::IntoIter: Clone,
+    {
+        let points = points.into_iter();
+        let first = *points.clone().next()?;
+        let farthest_from = |from: [f32; 3]| {
+            points.clone().fold(from, |best, &p| {
+                if length(sub(p, from)) > length(sub(best, from)) {
+                    p
+                } else {
+                    best
+                }
+            })
+        };
+
+        let a = farthest_from(first);
+        let b = farthest_from(a);
+        let mut center = scale(add(a, b), 0.5);
+        let mut radius = length(sub(b, a)) * 0.5;
+
+        for &p in points {
+            let dist = length(sub(p, center));
+            if dist > radius {
+                // Grow the sphere just enough to include the outlier.
+                let new_radius = (radius + dist) * 0.5;
+                center = add(center, scale(sub(p, center), (new_radius - radius) / dist));
+                radius = new_radius;
+            }
+        }
+
+        Some(BoundingSphere { center, radius })
+    }
+}
+
+fn referenced_positions<'a>(
+    data: &'a ObjData,
+    groups: impl Iterator<Item = &'a Group> + Clone + 'a,
+) -> impl Iterator<Item = &'a [f32; 3]> + Clone + 'a {
+    groups
+        .flat_map(|group| {
+            let polys = group.polys.iter().flat_map(|poly| poly.0.iter());
+            polys.chain(group.lines.iter().flat_map(|line| line.0.iter()))
+        })
+        .map(move |t| &data.position[t.position])
+}
+
+fn referenced_uvs<'a>(data: &'a ObjData, group: &'a Group) -> impl Iterator<Item = &'a [f32; 2]> + 'a {
+    let polys = group.polys.iter().flat_map(|poly| poly.0.iter());
+    polys
+        .chain(group.lines.iter().flat_map(|line| line.0.iter()))
+        .filter_map(move |t| Some(&data.texture[t.texture?]))
+}
+
+impl ObjData {
+    /// The bounds of the texture coordinates referenced by any polygon or line, or `None` if
+    /// there are none.
+    ///
+    /// Unlike [`ObjData::bounding_box`], unreferenced entries of [`ObjData::texture`] are ignored.
+    pub fn uv_bounds(&self) -> Option<UvBounds> {
+        let groups = self.objects.iter().flat_map(|o| &o.groups);
+        UvBounds::from_coordinates(groups.flat_map(|g| referenced_uvs(self, g)))
+    }
+
+    /// The bounding box of all vertex positions, or `None` if there are none.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(&self.position)
+    }
+
+    /// A bounding sphere of all vertex positions, or `None` if there are none.
+    ///
+    /// See [`BoundingSphere::from_points`] for the accuracy of the result.
+    pub fn bounding_sphere(&self) -> Option<BoundingSphere> {
+        BoundingSphere::from_points(&self.position)
+    }
+}
+
+impl Object {
+    /// The bounding box of the positions referenced by this object's polygons and lines.
+    pub fn bounding_box(&self, data: &ObjData) -> Option<BoundingBox> {
+        BoundingBox::from_points(referenced_positions(data, self.groups.iter()))
+    }
+
+    /// A bounding sphere of the positions referenced by this object's polygons and lines.
+    pub fn bounding_sphere(&self, data: &ObjData) -> Option<BoundingSphere> {
+        BoundingSphere::from_points(referenced_positions(data, self.groups.iter()))
+    }
+}
+
+impl Group {
+    /// The bounds of the texture coordinates referenced by this group's polygons and lines, or
+    /// `None` if there are none.
+    ///
+    /// Renderers can use [`UvBounds::exceeds_unit_square`] to choose between repeating and
+    /// clamping samplers, and atlas packers to find groups that can't share a texture.
+    pub fn uv_bounds(&self, data: &ObjData) -> Option<UvBounds> {
+        UvBounds::from_coordinates(referenced_uvs(data, self))
+    }
+
+    /// The bounding box of the positions referenced by this group's polygons and lines.
+    pub fn bounding_box(&self, data: &ObjData) -> Option<BoundingBox> {
+        BoundingBox::from_points(referenced_positions(data, std::iter::once(self)))
+    }
+
+    /// A bounding sphere of the positions referenced by this group's polygons and lines.
+    pub fn bounding_sphere(&self, data: &ObjData) -> Option<BoundingSphere> {
+        BoundingSphere::from_points(referenced_positions(data, std::iter::once(self)))
+    }
+}
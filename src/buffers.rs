@@ -0,0 +1,244 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Indexed, interleaved vertex buffers ready for GPU upload.
+
+use std::{collections::HashMap, convert::TryFrom};
+
+use crate::obj::{Group, IndexTuple, ObjData, ObjMaterial, SimplePolygon};
+use crate::tangent::generate_tangents;
+
+/// An interleaved vertex with all attributes resolved.
+///
+/// Missing texture coordinates and normals are zero.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct Vertex {
+    pub position: [f32; 3],
+    pub texture: [f32; 2],
+    pub normal: [f32; 3],
+}
+
+/// A vertex format holding only the position.
+///
+/// See [`IndexedBuffers::into_format`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct Position {
+    pub position: [f32; 3],
+}
+
+/// A vertex format holding the position and texture coordinates.
+///
+/// See [`IndexedBuffers::into_format`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct PositionTexture {
+    pub position: [f32; 3],
+    pub texture: [f32; 2],
+}
+
+/// A vertex format holding the position, texture coordinates and normal, which is [`Vertex`]
+/// itself.
+///
+/// See [`IndexedBuffers::into_format`].
+pub type PositionTextureNormal = Vertex;
+
+impl From<Vertex> for Position {
+    fn from(v: Vertex) -> Self {
+        Position { position: v.position }
+    }
+}
+
+impl From<Vertex> for PositionTexture {
+    fn from(v: Vertex) -> Self {
+        PositionTexture {
+            position: v.position,
+            texture: v.texture,
+        }
+    }
+}
+
+/// A range of triangles sharing one material.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Submesh {
+    /// Name of the object the triangles came from, or of the first one when merged by material.
+    pub object: String,
+    /// Name of the group the triangles came from, or of the first one when merged by material.
+    pub group: String,
+    /// The material used by these triangles.
+    pub material: Option<ObjMaterial>,
+    /// Three indices into [`IndexedBuffers::vertices`] per triangle.
+    pub indices: Vec<u32>,
+}
+
+/// A deduplicated vertex buffer shared by one index buffer per submesh.
+///
+/// The builders produce [`Vertex`] buffers, which can be converted to other formats with
+/// [`IndexedBuffers::into_format`]. With the `bytemuck` feature, all vertex formats of this crate
+/// implement `Pod`, so the buffers can be uploaded with `bytemuck::cast_slice`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexedBuffers<V = Vertex> {
+    pub vertices: Vec<V>,
+    pub submeshes: Vec<Submesh>,
+}
+
+impl IndexedBuffers {
+    /// Convert the vertices into another format, e.g. one dropping unused attributes.
+    ///
+    /// Vertices that became identical are not merged.
+    pub fn into_format<V: From<Vertex>>(self) -> IndexedBuffers<V> {
+        IndexedBuffers {
+            vertices: self.vertices.into_iter().map(V::from).collect(),
+            submeshes: self.submeshes,
+        }
+    }
+
+    /// Generate a tangent for every vertex, see [`generate_tangents`].
+    ///
+    /// The result is aligned with [`IndexedBuffers::vertices`].
+    pub fn generate_tangents(&self) -> Vec<[f32; 4]> {
+        let positions: Vec<_> = self.vertices.iter().map(|v| v.position).collect();
+        let normals: Vec<_> = self.vertices.iter().map(|v| v.normal).collect();
+        let texcoords: Vec<_> = self.vertices.iter().map(|v| v.texture).collect();
+        let indices: Vec<_> = self.submeshes.iter().flat_map(|s| s.indices.iter().cloned()).collect();
+        generate_tangents(&positions, &normals, &texcoords, &indices)
+    }
+}
+
+/// Split a polygon into a fan of triangles around its first vertex.
+pub(crate) fn fan(poly: &SimplePolygon) -> impl Iterator<Item = [IndexTuple; 3]> + '_ {
+    let first = poly.0.first().copied();
+    poly.0
+        .windows(2)
+        .skip(1)
+        .filter_map(move |w| first.map(|first| [first, w[0], w[1]]))
+}
+
+struct Builder<'a> {
+    data: &'a ObjData,
+    vertices: Vec<Vertex>,
+    lookup: HashMap<IndexTuple, u32>,
+}
+
+impl<'a> Builder<'a> {
+    fn vertex(&mut self, idx: IndexTuple) -> u32 {
+        let Builder { data, vertices, lookup } = self;
+        *lookup.entry(idx).or_insert_with(|| {
+            let IndexTuple {
+                position: p,
+                texture: t,
+                normal: n,
+            } = idx;
+            vertices.push(Vertex {
+                position: data.position[p],
+                texture: t.map(|t| data.texture[t]).unwrap_or_default(),
+                normal: n.map(|n| data.normal[n]).unwrap_or_default(),
+            });
+            u32::try_from(vertices.len() - 1).expect("too many vertices for 32-bit indices")
+        })
+    }
+
+    fn triangulate(&mut self, group: &Group, indices: &mut Vec<u32>) {
+        let data = self.data;
+        for poly in group.polys.iter().filter(|poly| data.has_attributes(&poly.0)) {
+            for tri in fan(poly) {
+                for &idx in &tri {
+                    let v = self.vertex(idx);
+                    indices.push(v);
+                }
+            }
+        }
+    }
+}
+
+impl ObjData {
+    /// Build a deduplicated interleaved vertex buffer with one index buffer per group.
+    ///
+    /// Each unique combination of position, texture and normal indices becomes one vertex, so
+    /// vertices are split where attributes differ, e.g. at UV seams and hard edges. Polygons are
+    /// triangulated as fans around their first vertex. Polygons referring to missing attributes,
+    /// which [`ObjData::validate_indices`] reports, are left out.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if more than `u32::MAX` distinct vertices are produced.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn to_indexed_buffers(&self) -> IndexedBuffers {
+        let mut builder = Builder {
+            data: self,
+            vertices: Vec::new(),
+            lookup: HashMap::new(),
+        };
+        let mut submeshes = Vec::new();
+        for object in &self.objects {
+            for group in &object.groups {
+                let mut indices = Vec::new();
+                builder.triangulate(group, &mut indices);
+                submeshes.push(Submesh {
+                    object: object.name.to_string(),
+                    group: group.name.to_string(),
+                    material: group.material.clone(),
+                    indices,
+                });
+            }
+        }
+        IndexedBuffers {
+            vertices: builder.vertices,
+            submeshes,
+        }
+    }
+
+    /// Build a deduplicated interleaved vertex buffer with one index buffer per material.
+    ///
+    /// This is the same as [`ObjData::to_indexed_buffers`], except that groups sharing a
+    /// material are combined into one submesh, in order of first use.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn to_indexed_buffers_by_material(&self) -> IndexedBuffers {
+        let mut builder = Builder {
+            data: self,
+            vertices: Vec::new(),
+            lookup: HashMap::new(),
+        };
+        let mut submeshes: Vec<Submesh> = Vec::new();
+        for object in &self.objects {
+            for group in &object.groups {
+                let name = group.material.as_ref().map(|m| m.name());
+                let pos = submeshes
+                    .iter()
+                    .position(|s| s.material.as_ref().map(|m| m.name()) == name);
+                let submesh = match pos {
+                    Some(pos) => &mut submeshes[pos],
+                    None => {
+                        submeshes.push(Submesh {
+                            object: object.name.to_string(),
+                            group: group.name.to_string(),
+                            material: group.material.clone(),
+                            indices: Vec::new(),
+                        });
+                        submeshes.last_mut().unwrap()
+                    }
+                };
+                builder.triangulate(group, &mut submesh.indices);
+            }
+        }
+        IndexedBuffers {
+            vertices: builder.vertices,
+            submeshes,
+        }
+    }
+}
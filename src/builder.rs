@@ -0,0 +1,194 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Incremental construction of an `ObjData`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::mtl::{Material, Mtl};
+use crate::obj::{
+    Group, IndexTuple, Interner, Line, ObjData, ObjMaterial, Object, SimplePolygon, DEFAULT_GROUP, DEFAULT_OBJECT,
+};
+
+/// A vertex added to an [`ObjBuilder`], to be used in faces and lines.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct VertexHandle(IndexTuple);
+
+impl From<VertexHandle> for IndexTuple {
+    fn from(handle: VertexHandle) -> Self {
+        handle.0
+    }
+}
+
+/// Builds an `ObjData` from vertex attribute values, taking care of the indices.
+///
+/// Objects, groups and materials follow the semantics of the `o`, `g` and `usemtl` commands: the
+/// current group continues until a new one is started and keeps its material, and assigning a
+/// material to a group that already has faces continues it as a new group of the same name.
+///
+/// Attribute values are deduplicated by their exact bits, so adding the same position twice
+/// refers to a single `v` entry.
+pub struct ObjBuilder {
+    data: ObjData,
+    object: Object,
+    group: Option<Group>,
+    names: Interner,
+    position: HashMap<[u32; 3], usize>,
+    texture: HashMap<[u32; 2], usize>,
+    normal: HashMap<[u32; 3], usize>,
+}
+
+impl Default for ObjBuilder {
+    fn default() -> Self {
+        let mut names = Interner::default();
+        ObjBuilder {
+            data: ObjData::default(),
+            object: Object::new(names.intern(DEFAULT_OBJECT)),
+            group: None,
+            names,
+            position: HashMap::new(),
+            texture: HashMap::new(),
+            normal: HashMap::new(),
+        }
+    }
+}
+
+fn bits<const N: usize>(values: [f32; N]) -> [u32; N] {
+    let mut out = [0; N];
+    for (o, v) in out.iter_mut().zip(&values) {
+        *o = v.to_bits();
+    }
+    out
+}
+
+fn insert<const N: usize>(lookup: &mut HashMap<[u32; N], usize>, values: &mut Vec<[f32; N]>, value: [f32; N]) -> usize {
+    *lookup.entry(bits(value)).or_insert_with(|| {
+        values.push(value);
+        values.len() - 1
+    })
+}
+
+impl ObjBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new object, like `o name`.
+    pub fn object(&mut self, name: &str) -> &mut Self {
+        if let Some(group) = self.group.take() {
+            self.object.groups.push(group);
+        }
+        let object = Object::new(self.names.intern(name));
+        let previous = std::mem::replace(&mut self.object, object);
+        if !previous.groups.is_empty() {
+            self.data.objects.push(previous);
+        }
+        self
+    }
+
+    /// Start a new group in the current object, like `g name`.
+    pub fn group(&mut self, name: &str) -> &mut Self {
+        let material = self.group.as_ref().and_then(|g| g.material.clone());
+        self.object.groups.extend(self.group.take());
+        let mut group = Group::new(self.names.intern(name));
+        group.material = material;
+        self.group = Some(group);
+        self
+    }
+
+    /// Assign a material by name to the following faces and lines, like `usemtl name`.
+    pub fn material(&mut self, name: &str) -> &mut Self {
+        let material = ObjMaterial::Ref(self.names.intern(name));
+        self.set_material(material)
+    }
+
+    /// Assign a loaded material to the following faces and lines.
+    ///
+    /// It is not added to a material library, see [`ObjBuilder::material_lib`].
+    pub fn loaded_material(&mut self, material: Arc<Material>) -> &mut Self {
+        self.set_material(ObjMaterial::Mtl(material))
+    }
+
+    fn set_material(&mut self, material: ObjMaterial) -> &mut Self {
+        let names = &mut self.names;
+        let group = self
+            .group
+            .get_or_insert_with(|| Group::new(names.intern(DEFAULT_GROUP)));
+        if !group.is_empty() {
+            let mut next = group.clone();
+            next.continue_empty();
+            self.object.groups.push(std::mem::replace(group, next));
+        }
+        group.material = Some(material);
+        self
+    }
+
+    /// Add a material library, like `mtllib filename`.
+    pub fn material_lib(&mut self, mtl: Mtl) -> &mut Self {
+        self.data.material_libs.push(mtl);
+        self
+    }
+
+    /// Add a vertex with the given attributes, reusing equal attribute values added before.
+    pub fn vertex(&mut self, position: [f32; 3], texture: Option<[f32; 2]>, normal: Option<[f32; 3]>) -> VertexHandle {
+        let ObjBuilder {
+            data,
+            position: positions,
+            texture: textures,
+            normal: normals,
+            ..
+        } = self;
+        VertexHandle(IndexTuple::new(
+            insert(positions, &mut data.position, position),
+            texture.map(|t| insert(textures, &mut data.texture, t)),
+            normal.map(|n| insert(normals, &mut data.normal, n)),
+        ))
+    }
+
+    /// Add a polygon through the given vertices to the current group, like `f`.
+    pub fn face(&mut self, vertices: impl IntoIterator<Item = VertexHandle>) -> &mut Self {
+        let poly = SimplePolygon(vertices.into_iter().map(|v| v.0).collect());
+        self.current_group().polys.push(poly);
+        self
+    }
+
+    /// Add a polyline through the given vertices to the current group, like `l`.
+    ///
+    /// Normals of the vertices are dropped, since lines only refer to positions and texture
+    /// coordinates.
+    pub fn line(&mut self, vertices: impl IntoIterator<Item = VertexHandle>) -> &mut Self {
+        let line = Line(
+            vertices
+                .into_iter()
+                .map(|VertexHandle(t)| IndexTuple::new(t.position, t.texture, None))
+                .collect(),
+        );
+        self.current_group().lines.push(line);
+        self
+    }
+
+    fn current_group(&mut self) -> &mut Group {
+        let names = &mut self.names;
+        self.group
+            .get_or_insert_with(|| Group::new(names.intern(DEFAULT_GROUP)))
+    }
+
+    /// Finish building.
+    pub fn build(mut self) -> ObjData {
+        self.object.groups.extend(self.group);
+        self.data.objects.push(self.object);
+        self.data
+    }
+}
@@ -0,0 +1,309 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Bounding volume hierarchy for ray casts and closest-point queries.
+//!
+//! ```
+//! use obj::{bvh::{Bvh, Ray}, ObjData};
+//!
+//! let data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).unwrap();
+//! let bvh = Bvh::new(&data);
+//! let ray = Ray { origin: [0.25, 0.25, 1.0], direction: [0.0, 0.0, -1.0] };
+//! assert_eq!(bvh.cast_ray(&ray, f32::INFINITY).unwrap().distance, 1.0);
+//! ```
+
+use crate::bounds::BoundingBox;
+use crate::buffers::fan;
+use crate::math::{add, closest_point_on_triangle, cross, dot, length, scale, sub, Vec3};
+use crate::obj::ObjData;
+use crate::quads::ear_clip;
+pub use crate::spatial::ClosestPoint;
+
+/// Maximum number of triangles stored in a leaf node.
+const LEAF_SIZE: usize = 4;
+
+/// A half-line starting at `origin`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: [f32; 3],
+    /// The direction of the ray. Distances are measured in multiples of its length.
+    pub direction: [f32; 3],
+}
+
+/// The first intersection of a ray with the mesh.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit {
+    /// The distance along the ray, in multiples of the direction length.
+    pub distance: f32,
+    /// The intersection point.
+    pub point: [f32; 3],
+    /// The polygon hit, numbered consecutively across all groups of all objects.
+    pub polygon: usize,
+    /// The barycentric coordinates of the hit point with respect to `triangle`.
+    pub barycentric: [f32; 3],
+    /// The position indices of the triangle hit, which is part of the polygon.
+    pub triangle: [usize; 3],
+}
+
+#[derive(Clone, Debug)]
+struct Triangle {
+    points: [Vec3; 3],
+    indices: [usize; 3],
+    polygon: usize,
+}
+
+impl Triangle {
+    fn centroid(&self) -> Vec3 {
+        scale(add(add(self.points[0], self.points[1]), self.points[2]), 1.0 / 3.0)
+    }
+
+    /// Möller-Trumbore intersection, returning the distance and barycentric coordinates.
+    fn intersect(&self, ray: &Ray) -> Option<(f32, [f32; 3])> {
+        let [a, b, c] = self.points;
+        let (e1, e2) = (sub(b, a), sub(c, a));
+        let p = cross(ray.direction, e2);
+        let det = dot(e1, p);
+        if det.abs() <= f32::EPSILON * length(e1) * length(e2) {
+            return None;
+        }
+        let inv = 1.0 / det;
+        let s = sub(ray.origin, a);
+        let u = dot(s, p) * inv;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = cross(s, e1);
+        let v = dot(ray.direction, q) * inv;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = dot(e2, q) * inv;
+        if t >= 0.0 {
+            Some((t, [1.0 - u - v, u, v]))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    /// The indices of both children.
+    Inner(usize, usize),
+    /// A range of triangles.
+    Leaf(usize, usize),
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    bbox: BoundingBox,
+    kind: NodeKind,
+}
+
+/// The distance at which the ray enters the box, if it hits it.
+fn ray_box(ray: &Ray, inv_dir: Vec3, bbox: &BoundingBox) -> Option<f32> {
+    let (mut near, mut far) = (0.0f32, f32::INFINITY);
+    for (i, inv) in inv_dir.iter().enumerate() {
+        let t0 = (bbox.min[i] - ray.origin[i]) * inv;
+        let t1 = (bbox.max[i] - ray.origin[i]) * inv;
+        // NaN from a zero direction component within the slab is ignored by min/max.
+        near = near.max(t0.min(t1));
+        far = far.min(t0.max(t1));
+    }
+    if near <= far {
+        Some(near)
+    } else {
+        None
+    }
+}
+
+/// A bounding volume hierarchy over the triangulated polygons of an `ObjData`.
+///
+/// The hierarchy holds a copy of the geometry, so it stays valid when the mesh is modified
+/// afterwards, but won't reflect the changes.
+#[derive(Clone, Debug)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    triangles: Vec<Triangle>,
+}
+
+impl Bvh {
+    /// Build a hierarchy over all polygons of the mesh.
+    ///
+    /// Polygons are triangulated, and those with fewer than three vertices are ignored.
+    pub fn new(data: &ObjData) -> Bvh {
+        let mut triangles = Vec::new();
+        for (polygon, poly) in data.polys().enumerate() {
+            let tris = ear_clip(poly, &data.position).unwrap_or_else(|| fan(poly).collect());
+            for tri in tris {
+                let indices = tri.map(|t| t.position);
+                triangles.push(Triangle {
+                    points: indices.map(|i| data.position[i]),
+                    indices,
+                    polygon,
+                });
+            }
+        }
+        let mut bvh = Bvh {
+            nodes: Vec::new(),
+            triangles,
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.build(0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    /// Append the subtree over the given triangle range, returning its node index.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let tris = &mut self.triangles[start..end];
+        let bbox = BoundingBox::from_points(tris.iter().flat_map(|t| t.points.iter())).unwrap();
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            bbox,
+            kind: NodeKind::Leaf(start, end),
+        });
+        if tris.len() <= LEAF_SIZE {
+            return node;
+        }
+
+        // Split at the median centroid along the longest axis.
+        let centroids: Vec<Vec3> = tris.iter().map(Triangle::centroid).collect();
+        let size = BoundingBox::from_points(&centroids).unwrap().size();
+        let axis = (0..3).max_by(|&a, &b| size[a].total_cmp(&size[b])).unwrap();
+        let mid = tris.len() / 2;
+        tris.select_nth_unstable_by(mid, |a, b| a.centroid()[axis].total_cmp(&b.centroid()[axis]));
+
+        let left = self.build(start, start + mid);
+        let right = self.build(start + mid, end);
+        self.nodes[node].kind = NodeKind::Inner(left, right);
+        node
+    }
+
+    /// Find the nearest intersection of the ray with the mesh within `max_distance`.
+    ///
+    /// Both sides of every triangle are considered.
+    pub fn cast_ray(&self, ray: &Ray, max_distance: f32) -> Option<RayHit> {
+        let mut best: Option<RayHit> = None;
+        self.traverse_ray(ray, max_distance, |tri, t, barycentric| {
+            best = Some(RayHit {
+                distance: t,
+                point: add(ray.origin, scale(ray.direction, t)),
+                polygon: tri.polygon,
+                barycentric,
+                triangle: tri.indices,
+            });
+            true
+        });
+        best
+    }
+
+    /// Check whether the ray hits the mesh within `max_distance`.
+    ///
+    /// This stops at the first intersection found, which is faster than [`Bvh::cast_ray`].
+    pub fn occluded(&self, ray: &Ray, max_distance: f32) -> bool {
+        let mut found = false;
+        self.traverse_ray(ray, max_distance, |_, _, _| {
+            found = true;
+            false
+        });
+        found
+    }
+
+    /// Visit the triangles hit by the ray within the distance limit, nearest nodes first.
+    ///
+    /// Each hit lowers the limit to its distance, so later hits are always closer. The visitor
+    /// returns whether to continue searching.
+    fn traverse_ray(&self, ray: &Ray, max_distance: f32, mut visit: impl FnMut(&Triangle, f32, [f32; 3]) -> bool) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let inv_dir = ray.direction.map(|d| 1.0 / d);
+        let mut limit = max_distance;
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            match ray_box(ray, inv_dir, &node.bbox) {
+                Some(near) if near <= limit => {}
+                _ => continue,
+            }
+            match node.kind {
+                NodeKind::Leaf(start, end) => {
+                    for tri in &self.triangles[start..end] {
+                        match tri.intersect(ray) {
+                            Some((t, barycentric)) if t <= limit => {
+                                limit = t;
+                                if !visit(tri, t, barycentric) {
+                                    return;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                NodeKind::Inner(left, right) => {
+                    // Push the farther child first, so that the nearer one is visited first.
+                    let near = |i: usize| ray_box(ray, inv_dir, &self.nodes[i].bbox).unwrap_or(f32::INFINITY);
+                    if near(left) < near(right) {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Find the point of the mesh closest to `point`.
+    ///
+    /// Returns `None` if the mesh has no polygons.
+    pub fn closest_point(&self, point: [f32; 3]) -> Option<ClosestPoint> {
+        let mut best: Option<ClosestPoint> = None;
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if best.is_some_and(|best| node.bbox.distance(point) > best.distance) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf(start, end) => {
+                    for tri in &self.triangles[start..end] {
+                        let candidate = closest_point_on_triangle(point, tri.points);
+                        let distance = length(sub(candidate, point));
+                        if best.is_none_or(|best| distance < best.distance) {
+                            best = Some(ClosestPoint {
+                                point: candidate,
+                                distance,
+                                polygon: tri.polygon,
+                            });
+                        }
+                    }
+                }
+                NodeKind::Inner(left, right) => {
+                    let dist = |i: usize| self.nodes[i].bbox.distance(point);
+                    if dist(left) < dist(right) {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+            }
+        }
+        best
+    }
+}
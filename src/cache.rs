@@ -0,0 +1,435 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! A binary snapshot format for caching parsed files.
+//!
+//! All numbers are little-endian. After the signature and the format version follow the
+//! attribute arrays, the material libraries, the other file references, the metadata and the
+//! objects. Strings and arrays are prefixed by their length as a `u64`, and materials are stored
+//! as .mtl source.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::sync::Arc;
+
+use crate::mtl::{Material, Mtl};
+use crate::obj::{
+    Axis, Group, IndexTuple, Line, ObjData, ObjMaterial, ObjMetadata, Object, RenderState, SimplePolygon, StateChange,
+};
+
+const SIGNATURE: &[u8; 8] = b"OBJCACHE";
+const VERSION: u32 = 3;
+const AXES: [Axis; 6] = [Axis::PosX, Axis::NegX, Axis::PosY, Axis::NegY, Axis::PosZ, Axis::NegZ];
+
+/// Errors reading or writing a cache, see [`ObjData::write_cache`].
+#[derive(Debug)]
+pub enum CacheError {
+    Io(io::Error),
+    /// The input doesn't start with the signature of a cache.
+    NotACache,
+    /// The cache was written in a newer version of the format.
+    UnsupportedVersion(u32),
+    /// The cache is corrupt or truncated.
+    Malformed,
+}
+
+impl std::error::Error for CacheError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CacheError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Io(err) => write!(f, "I/O error reading or writing a cache: {}", err),
+            CacheError::NotACache => write!(f, "Input is not an obj cache."),
+            CacheError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported obj cache version. (version: {})", version)
+            }
+            CacheError::Malformed => write!(f, "Obj cache is corrupt or truncated."),
+        }
+    }
+}
+
+impl From<io::Error> for CacheError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => Self::Malformed,
+            _ => Self::Io(e),
+        }
+    }
+}
+
+struct Encoder<W> {
+    out: W,
+}
+
+impl<W: Write> Encoder<W> {
+    fn u8(&mut self, value: u8) -> io::Result<()> {
+        self.out.write_all(&[value])
+    }
+
+    fn u32(&mut self, value: u32) -> io::Result<()> {
+        self.out.write_all(&value.to_le_bytes())
+    }
+
+    fn len(&mut self, value: usize) -> io::Result<()> {
+        self.out.write_all(&(value as u64).to_le_bytes())
+    }
+
+    fn floats(&mut self, values: &[f32]) -> io::Result<()> {
+        for value in values {
+            self.out.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn str(&mut self, value: &str) -> io::Result<()> {
+        self.len(value.len())?;
+        self.out.write_all(value.as_bytes())
+    }
+
+    fn opt_str(&mut self, value: Option<&str>) -> io::Result<()> {
+        match value {
+            Some(value) => {
+                self.u8(1)?;
+                self.str(value)
+            }
+            None => self.u8(0),
+        }
+    }
+
+    /// Write an optional index as 0 for `None` and `index + 1` otherwise.
+    fn opt_index(&mut self, value: Option<usize>) -> io::Result<()> {
+        self.len(value.map_or(0, |i| i + 1))
+    }
+
+    /// Write the unit size as a flag and a float, and the up axis as 0 for `None` and its
+    /// position in [`AXES`] plus 1 otherwise.
+    fn metadata(&mut self, metadata: &ObjMetadata) -> io::Result<()> {
+        match metadata.meters_per_unit {
+            Some(meters) => {
+                self.u8(1)?;
+                self.floats(&[meters])?;
+            }
+            None => self.u8(0)?,
+        }
+        let axis = metadata.up_axis.and_then(|axis| AXES.iter().position(|a| *a == axis));
+        self.u8(axis.map_or(0, |i| i as u8 + 1))?;
+        self.opt_str(metadata.application.as_deref())
+    }
+
+    fn tuples(&mut self, tuples: &[IndexTuple]) -> io::Result<()> {
+        self.len(tuples.len())?;
+        for tuple in tuples {
+            self.len(tuple.position)?;
+            self.opt_index(tuple.texture)?;
+            self.opt_index(tuple.normal)?;
+        }
+        Ok(())
+    }
+
+    fn render_state(&mut self, state: &RenderState) -> io::Result<()> {
+        self.u32(state.smoothing_group)?;
+        self.u8(state.bevel as u8 | (state.c_interp as u8) << 1 | (state.d_interp as u8) << 2)?;
+        self.u32(state.lod)?;
+        self.opt_str(state.texture_map.as_deref())
+    }
+
+    fn materials(&mut self, materials: &[Arc<Material>]) -> io::Result<()> {
+        let mtl = Mtl {
+            filename: String::new(),
+            materials: materials.to_vec(),
+        };
+        let mut source = String::new();
+        // Writing to a string can't fail.
+        mtl.write_to_fmt(&mut source).unwrap();
+        self.str(&source)
+    }
+}
+
+struct Decoder<R> {
+    input: R,
+}
+
+impl<R: Read> Decoder<R> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], CacheError> {
+        let mut buf = [0; N];
+        self.input.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8, CacheError> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, CacheError> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn len(&mut self) -> Result<usize, CacheError> {
+        let value = u64::from_le_bytes(self.bytes()?);
+        usize::try_from(value).map_err(|_| CacheError::Malformed)
+    }
+
+    fn floats<const N: usize>(&mut self) -> Result<[f32; N], CacheError> {
+        let mut values = [0.0; N];
+        for value in &mut values {
+            *value = f32::from_le_bytes(self.bytes()?);
+        }
+        Ok(values)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<Vec<[f32; N]>, CacheError> {
+        let len = self.len()?;
+        (0..len).map(|_| self.floats()).collect()
+    }
+
+    fn string(&mut self) -> Result<String, CacheError> {
+        let len = self.len()?;
+        let mut bytes = Vec::new();
+        (&mut self.input).take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(CacheError::Malformed);
+        }
+        String::from_utf8(bytes).map_err(|_| CacheError::Malformed)
+    }
+
+    fn opt_string(&mut self) -> Result<Option<String>, CacheError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => self.string().map(Some),
+            _ => Err(CacheError::Malformed),
+        }
+    }
+
+    fn opt_index(&mut self) -> Result<Option<usize>, CacheError> {
+        Ok(self.len()?.checked_sub(1))
+    }
+
+    fn metadata(&mut self) -> Result<ObjMetadata, CacheError> {
+        let meters_per_unit = match self.u8()? {
+            0 => None,
+            1 => Some(self.floats::<1>()?[0]),
+            _ => return Err(CacheError::Malformed),
+        };
+        let up_axis = match self.u8()? {
+            0 => None,
+            i => Some(*AXES.get(i as usize - 1).ok_or(CacheError::Malformed)?),
+        };
+        Ok(ObjMetadata {
+            meters_per_unit,
+            up_axis,
+            application: self.opt_string()?,
+        })
+    }
+
+    fn tuples(&mut self) -> Result<Vec<IndexTuple>, CacheError> {
+        let len = self.len()?;
+        (0..len)
+            .map(|_| Ok(IndexTuple::new(self.len()?, self.opt_index()?, self.opt_index()?)))
+            .collect()
+    }
+
+    fn render_state(&mut self) -> Result<RenderState, CacheError> {
+        let smoothing_group = self.u32()?;
+        let flags = self.u8()?;
+        Ok(RenderState {
+            smoothing_group,
+            bevel: flags & 1 != 0,
+            c_interp: flags & 2 != 0,
+            d_interp: flags & 4 != 0,
+            lod: self.u32()?,
+            texture_map: self.opt_string()?.map(Into::into),
+        })
+    }
+
+    fn materials(&mut self, filename: String) -> Result<Mtl, CacheError> {
+        let source = self.string()?;
+        let mut mtl = Mtl::new(filename);
+        mtl.reload_str(&source).map_err(|_| CacheError::Malformed)?;
+        Ok(mtl)
+    }
+}
+
+impl ObjData {
+    /// Write a binary snapshot of this data, which [`ObjData::read_cache`] loads much faster
+    /// than parsing the .obj source.
+    ///
+    /// The snapshot holds the attribute arrays, objects, groups, material libraries and other
+    /// file references, but not the [`ObjData::layout`]. Materials are kept as .mtl source, so
+    /// only what .mtl files can express survives.
+    pub fn write_cache(&self, out: &mut impl Write) -> Result<(), CacheError> {
+        let mut e = Encoder {
+            out: BufWriter::new(out),
+        };
+        e.out.write_all(SIGNATURE)?;
+        e.u32(VERSION)?;
+
+        for (len, floats) in [
+            (self.position.len(), self.position.concat()),
+            (self.color.len(), self.color.concat()),
+            (self.texture.len(), self.texture.concat()),
+            (self.normal.len(), self.normal.concat()),
+        ] {
+            e.len(len)?;
+            e.floats(&floats)?;
+        }
+
+        e.len(self.material_libs.len())?;
+        for mtl in &self.material_libs {
+            e.str(&mtl.filename)?;
+            e.materials(&mtl.materials)?;
+        }
+        e.opt_str(self.shadow_obj.as_deref())?;
+        e.opt_str(self.trace_obj.as_deref())?;
+        e.materials(self.default_material.as_slice())?;
+        e.metadata(&self.metadata)?;
+
+        // Loaded materials of groups, shared by index.
+        let mut loaded: Vec<Arc<Material>> = Vec::new();
+        for group in self.objects.iter().flat_map(|o| &o.groups) {
+            if let Some(ObjMaterial::Mtl(material)) = &group.material {
+                if !loaded.iter().any(|m| Arc::ptr_eq(m, material)) {
+                    loaded.push(material.clone());
+                }
+            }
+        }
+        e.materials(&loaded)?;
+
+        e.len(self.objects.len())?;
+        for object in &self.objects {
+            e.str(&object.name)?;
+            e.len(object.groups.len())?;
+            for group in &object.groups {
+                e.str(&group.name)?;
+                e.len(group.index)?;
+                match &group.material {
+                    None => e.u8(0)?,
+                    Some(ObjMaterial::Ref(name)) => {
+                        e.u8(1)?;
+                        e.str(name)?;
+                    }
+                    Some(ObjMaterial::Mtl(material)) => {
+                        e.u8(2)?;
+                        e.len(loaded.iter().position(|m| Arc::ptr_eq(m, material)).unwrap())?;
+                    }
+                }
+                e.render_state(&group.render_state)?;
+                e.len(group.state_changes.len())?;
+                for change in &group.state_changes {
+                    e.len(change.polys)?;
+                    e.len(change.lines)?;
+                    e.render_state(&change.state)?;
+                }
+                e.len(group.polys.len())?;
+                for poly in &group.polys {
+                    e.tuples(&poly.0)?;
+                }
+                e.len(group.lines.len())?;
+                for line in &group.lines {
+                    e.tuples(&line.0)?;
+                }
+            }
+        }
+        e.out.flush()?;
+        Ok(())
+    }
+
+    /// Load a snapshot written by [`ObjData::write_cache`].
+    ///
+    /// Snapshots written by other versions of the format are rejected with
+    /// [`CacheError::UnsupportedVersion`], so callers can fall back to parsing the source.
+    pub fn read_cache(input: impl Read) -> Result<ObjData, CacheError> {
+        let mut d = Decoder {
+            input: BufReader::new(input),
+        };
+        match d.bytes::<8>() {
+            Ok(signature) if signature == *SIGNATURE => (),
+            Ok(_) | Err(CacheError::Malformed) => return Err(CacheError::NotACache),
+            Err(err) => return Err(err),
+        }
+        match d.u32()? {
+            VERSION => (),
+            version => return Err(CacheError::UnsupportedVersion(version)),
+        }
+
+        let mut data = ObjData {
+            position: d.array()?,
+            color: d.array()?,
+            texture: d.array()?,
+            normal: d.array()?,
+            ..ObjData::default()
+        };
+        for _ in 0..d.len()? {
+            let filename = d.string()?;
+            let mtl = d.materials(filename)?;
+            data.material_libs.push(mtl);
+        }
+        data.shadow_obj = d.opt_string()?;
+        data.trace_obj = d.opt_string()?;
+        data.default_material = d.materials(String::new())?.materials.pop();
+        data.metadata = d.metadata()?;
+
+        // Share materials that are equal to one of a library, as after resolving them.
+        let loaded: Vec<Arc<Material>> = d
+            .materials(String::new())?
+            .materials
+            .into_iter()
+            .map(|material| {
+                let mut libs = data.material_libs.iter().flat_map(|mtl| &mtl.materials);
+                libs.find(|m| ***m == *material).cloned().unwrap_or(material)
+            })
+            .collect();
+
+        for _ in 0..d.len()? {
+            let mut object = Object::new(d.string()?);
+            for _ in 0..d.len()? {
+                let mut group = Group::new(d.string()?);
+                group.index = d.len()?;
+                group.material = match d.u8()? {
+                    0 => None,
+                    1 => Some(ObjMaterial::Ref(d.string()?.into())),
+                    2 => Some(ObjMaterial::Mtl(
+                        loaded.get(d.len()?).cloned().ok_or(CacheError::Malformed)?,
+                    )),
+                    _ => return Err(CacheError::Malformed),
+                };
+                group.render_state = d.render_state()?;
+                for _ in 0..d.len()? {
+                    group.state_changes.push(StateChange {
+                        polys: d.len()?,
+                        lines: d.len()?,
+                        state: d.render_state()?,
+                    });
+                }
+                for _ in 0..d.len()? {
+                    let tuples = d.tuples()?;
+                    group.polys.push(SimplePolygon(tuples));
+                }
+                for _ in 0..d.len()? {
+                    group.lines.push(Line(d.tuples()?));
+                }
+                object.groups.push(group);
+            }
+            data.objects.push(object);
+        }
+        Ok(data)
+    }
+}
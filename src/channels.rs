@@ -0,0 +1,241 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Named per-vertex attributes kept alongside the data.
+
+use std::fmt;
+use std::io::Write;
+
+use crate::compact::RemapReport;
+use crate::obj::{write_io, ObjData, ObjError};
+use crate::raw::statements;
+
+/// A named attribute with a fixed number of values for each position, see [`AttributeChannels`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeChannel {
+    /// The name, which doesn't contain whitespace.
+    pub name: String,
+    /// The number of values of each vertex, such as 4 for bone weights.
+    pub components: usize,
+    /// The values of all vertices one after the other, in the order of [`ObjData::position`].
+    pub values: Vec<f32>,
+}
+
+impl AttributeChannel {
+    /// The values of the position at `index`.
+    pub fn vertex(&self, index: usize) -> &[f32] {
+        &self.values[index * self.components..(index + 1) * self.components]
+    }
+
+    /// The values of the position at `index`, for changing them.
+    pub fn vertex_mut(&mut self, index: usize) -> &mut [f32] {
+        &mut self.values[index * self.components..(index + 1) * self.components]
+    }
+}
+
+/// Per-vertex attributes that .obj has no statements for, such as bone weights or ambient
+/// occlusion, stored for the positions of an [`ObjData`].
+///
+/// The channels are kept in sync with the data by passing the [`RemapReport`] of every edit to
+/// [`AttributeChannels::apply`]. They can be written after the data as comments, which other
+/// readers ignore, and read back with [`AttributeChannels::parse`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttributeChannels {
+    len: usize,
+    channels: Vec<AttributeChannel>,
+}
+
+impl AttributeChannels {
+    /// No channels, for the positions of `data`.
+    pub fn new(data: &ObjData) -> Self {
+        AttributeChannels {
+            len: data.position.len(),
+            channels: Vec::new(),
+        }
+    }
+
+    /// The number of vertices the channels have values for.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the channels are for no vertices at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a channel with `components` values for each vertex, all zero, replacing any channel
+    /// of the same name, and return it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty or contains whitespace, since it couldn't be written.
+    pub fn register(&mut self, name: impl Into<String>, components: usize) -> &mut AttributeChannel {
+        let name = name.into();
+        assert!(
+            !name.is_empty() && !name.contains(char::is_whitespace),
+            "invalid channel name {:?}",
+            name
+        );
+        let channel = AttributeChannel {
+            values: vec![0.0; self.len * components],
+            name,
+            components,
+        };
+        match self.channels.iter().position(|c| c.name == channel.name) {
+            Some(i) => {
+                self.channels[i] = channel;
+                &mut self.channels[i]
+            }
+            None => {
+                self.channels.push(channel);
+                self.channels.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// The channel named `name`.
+    pub fn get(&self, name: &str) -> Option<&AttributeChannel> {
+        self.channels.iter().find(|c| c.name == name)
+    }
+
+    /// The channel named `name`, for changing its values.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut AttributeChannel> {
+        self.channels.iter_mut().find(|c| c.name == name)
+    }
+
+    /// Remove the channel named `name`, returning it.
+    pub fn remove(&mut self, name: &str) -> Option<AttributeChannel> {
+        let i = self.channels.iter().position(|c| c.name == name)?;
+        Some(self.channels.remove(i))
+    }
+
+    /// All channels, in the order they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = &AttributeChannel> {
+        self.channels.iter()
+    }
+
+    /// Rearrange the values after an edit of `data` described by `report`.
+    ///
+    /// Positions that were merged, such as by welding, keep the values of the first of them.
+    /// Positions beyond those covered by `report`, such as ones added by subdivision, get zeros.
+    pub fn apply(&mut self, data: &ObjData, report: &RemapReport) {
+        let len = data.position.len();
+        for channel in &mut self.channels {
+            let n = channel.components;
+            let mut values = vec![0.0; len * n];
+            let mut filled = vec![false; len];
+            for (old, new) in report.attributes.position.iter().enumerate() {
+                match *new {
+                    Some(new) if new < len && old < self.len && !filled[new] => {
+                        values[new * n..(new + 1) * n].copy_from_slice(&channel.values[old * n..(old + 1) * n]);
+                        filled[new] = true;
+                    }
+                    _ => (),
+                }
+            }
+            channel.values = values;
+        }
+        self.len = len;
+    }
+
+    /// Read the channels written as comments by [`AttributeChannels::write_to_buf`].
+    ///
+    /// Every channel starts with a `#channel` comment giving its name and number of components,
+    /// followed by a `#cv` comment with the values of each vertex. All other lines are ignored.
+    pub fn parse(source: &str) -> Result<Self, ObjError> {
+        let mut channels = AttributeChannels::default();
+        // The line of the `#channel` comment of each channel, for reporting a wrong vertex count.
+        let mut starts = Vec::new();
+        for statement in statements(source) {
+            let line_number = statement.line_number();
+            let invalid = || ObjError::ArgumentListFailure {
+                line_number,
+                list: statement.rest().to_string(),
+            };
+            match statement.keyword() {
+                "#channel" => {
+                    let mut args = statement.args();
+                    let (name, components) = match (args.next(), args.next(), args.next()) {
+                        (Some(name), Some(components), None) => (name, components),
+                        _ => return Err(invalid()),
+                    };
+                    channels.channels.push(AttributeChannel {
+                        name: name.to_string(),
+                        components: components.parse().map_err(|_| invalid())?,
+                        values: Vec::new(),
+                    });
+                    starts.push(line_number);
+                }
+                "#cv" => {
+                    let channel = channels.channels.last_mut().ok_or_else(invalid)?;
+                    let start = channel.values.len();
+                    for value in statement.args() {
+                        channel.values.push(value.parse().map_err(|_| invalid())?);
+                    }
+                    if channel.values.len() - start != channel.components {
+                        return Err(invalid());
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let vertices = |c: &AttributeChannel| c.values.len().checked_div(c.components).unwrap_or(0);
+        channels.len = channels.channels.first().map_or(0, vertices);
+        for (channel, &line_number) in channels.channels.iter().zip(&starts) {
+            if vertices(channel) != channels.len {
+                return Err(ObjError::ArgumentListFailure {
+                    line_number,
+                    list: format!("{} {}", channel.name, channel.components),
+                });
+            }
+        }
+        Ok(channels)
+    }
+
+    /// Write the channels as comments, see [`AttributeChannels::parse`].
+    pub fn write_to_buf(&self, out: &mut impl Write) -> Result<(), ObjError> {
+        write_io(out, |out| self.write_to_fmt(out))?;
+        Ok(())
+    }
+
+    fn write_to_fmt(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        for channel in &self.channels {
+            writeln!(out, "#channel {} {}", channel.name, channel.components)?;
+            for vertex in 0..self.len {
+                write!(out, "#cv")?;
+                for value in channel.vertex(vertex) {
+                    write!(out, " {}", value)?;
+                }
+                writeln!(out)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ObjData {
+    /// Channels for the positions of this data, see [`AttributeChannels`].
+    pub fn attribute_channels(&self) -> AttributeChannels {
+        AttributeChannels::new(self)
+    }
+
+    /// Serialize this `Obj` into the given writer like [`ObjData::write_to_buf`], followed by
+    /// `channels` as comments.
+    pub fn write_with_channels(&self, out: &mut impl Write, channels: &AttributeChannels) -> Result<(), ObjError> {
+        self.write_to_buf(out)?;
+        channels.write_to_buf(out)
+    }
+}
@@ -0,0 +1,207 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Removal of invalid or redundant polygons.
+
+use std::collections::HashSet;
+
+use crate::compact::{AttributeRemap, RemapReport};
+use crate::math::{length, newell_normal};
+use crate::obj::{ObjData, SimplePolygon};
+
+/// The number of polygons removed by [`ObjData::remove_degenerate_faces`], by reason.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DegenerateReport {
+    /// Polygons with fewer than three vertices.
+    pub too_few_vertices: usize,
+    /// Polygons referencing the same position more than once.
+    pub repeated_indices: usize,
+    /// Polygons with an area not exceeding the given epsilon.
+    pub zero_area: usize,
+}
+
+impl DegenerateReport {
+    /// Total number of removed polygons.
+    pub fn total(&self) -> usize {
+        self.too_few_vertices + self.repeated_indices + self.zero_area
+    }
+}
+
+/// The polygons removed from one group by [`ObjData::dedup_faces`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemovedFaces {
+    /// Index of the object in [`ObjData::objects`].
+    pub object: usize,
+    /// Index of the group in [`Object::groups`](crate::Object::groups).
+    pub group: usize,
+    /// Indices of the removed polygons in the group's polygon list before removal.
+    pub polygons: Vec<usize>,
+}
+
+/// The polygons removed by [`ObjData::dedup_faces`], listing only groups that lost polygons.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DuplicateReport {
+    pub groups: Vec<RemovedFaces>,
+}
+
+impl DuplicateReport {
+    /// Total number of removed polygons.
+    pub fn total(&self) -> usize {
+        self.groups.iter().map(|g| g.polygons.len()).sum()
+    }
+}
+
+/// The position indices of a polygon, rotated and possibly reversed into a canonical order that
+/// is the same for all polygons covering the same face.
+fn canonical_face(poly: &SimplePolygon) -> Vec<usize> {
+    let indices: Vec<usize> = poly.0.iter().map(|t| t.position).collect();
+    let n = indices.len();
+    let mut reversed = indices.clone();
+    reversed.reverse();
+    (0..n)
+        .flat_map(|start| {
+            let rotate = move |seq: &Vec<usize>| (0..n).map(|i| seq[(start + i) % n]).collect::<Vec<_>>();
+            vec![rotate(&indices), rotate(&reversed)]
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+enum Degeneracy {
+    TooFewVertices,
+    RepeatedIndices,
+    ZeroArea,
+}
+
+fn degeneracy(poly: &SimplePolygon, position: &[[f32; 3]], epsilon: f32) -> Option<Degeneracy> {
+    if poly.0.len() < 3 {
+        return Some(Degeneracy::TooFewVertices);
+    }
+    let mut indices: Vec<usize> = poly.0.iter().map(|t| t.position).collect();
+    indices.sort_unstable();
+    if indices.windows(2).any(|w| w[0] == w[1]) {
+        return Some(Degeneracy::RepeatedIndices);
+    }
+    let points: Vec<_> = poly.0.iter().map(|t| position[t.position]).collect();
+    let area = 0.5 * length(newell_normal(&points));
+    if area <= epsilon || area.is_nan() {
+        return Some(Degeneracy::ZeroArea);
+    }
+    None
+}
+
+impl ObjData {
+    /// Remove polygons that have fewer than three vertices, reference the same position more
+    /// than once, or have an area of at most `epsilon`.
+    ///
+    /// Attribute arrays are left untouched, use [`ObjData::compact`] to drop the attributes that
+    /// are no longer referenced.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn remove_degenerate_faces(&mut self, epsilon: f32) -> DegenerateReport {
+        let mut report = DegenerateReport::default();
+        let position = &self.position;
+        for object in &mut self.objects {
+            for group in &mut object.groups {
+                group.retain_polys(|poly| match degeneracy(poly, position, epsilon) {
+                    Some(Degeneracy::TooFewVertices) => {
+                        report.too_few_vertices += 1;
+                        false
+                    }
+                    Some(Degeneracy::RepeatedIndices) => {
+                        report.repeated_indices += 1;
+                        false
+                    }
+                    Some(Degeneracy::ZeroArea) => {
+                        report.zero_area += 1;
+                        false
+                    }
+                    None => true,
+                });
+            }
+        }
+        report
+    }
+
+    /// Remove polygons referring to missing attributes, which [`ObjData::validate_indices`]
+    /// reports, returning how many were removed.
+    pub(crate) fn remove_invalid_faces(&mut self) -> usize {
+        let mut removed = 0;
+        let mut objects = std::mem::take(&mut self.objects);
+        for group in objects.iter_mut().flat_map(|object| &mut object.groups) {
+            group.retain_polys(|poly| {
+                let valid = self.has_attributes(&poly.0);
+                removed += usize::from(!valid);
+                valid
+            });
+        }
+        self.objects = objects;
+        removed
+    }
+
+    /// Remove polygons covering the same positions as an earlier polygon, in the same cyclic
+    /// order or reversed.
+    ///
+    /// The first polygon of each set of duplicates is kept, even if the others are in different
+    /// groups. Texture coordinates and normals are not compared.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn dedup_faces(&mut self) -> DuplicateReport {
+        let mut report = DuplicateReport::default();
+        let mut seen = HashSet::new();
+        for (o, object) in self.objects.iter_mut().enumerate() {
+            for (g, group) in object.groups.iter_mut().enumerate() {
+                let mut removed = RemovedFaces {
+                    object: o,
+                    group: g,
+                    polygons: Vec::new(),
+                };
+                let mut i = 0;
+                group.retain_polys(|poly| {
+                    i += 1;
+                    let unique = seen.insert(canonical_face(poly));
+                    if !unique {
+                        removed.polygons.push(i - 1);
+                    }
+                    unique
+                });
+                if !removed.polygons.is_empty() {
+                    report.groups.push(removed);
+                }
+            }
+        }
+        report
+    }
+
+    /// Like [`ObjData::dedup_faces`], also returning where each polygon went, which keeps all
+    /// attributes in place.
+    pub fn dedup_faces_with_remap(&mut self) -> (DuplicateReport, RemapReport) {
+        let lens: Vec<Vec<usize>> = self
+            .objects
+            .iter()
+            .map(|o| o.groups.iter().map(|g| g.polys.len()).collect())
+            .collect();
+        let report = self.dedup_faces();
+        let mut counts = Vec::new();
+        for (o, groups) in lens.iter().enumerate() {
+            for (g, &len) in groups.iter().enumerate() {
+                let start = counts.len();
+                counts.resize(start + len, 1);
+                let removed = report.groups.iter().find(|r| (r.object, r.group) == (o, g));
+                for &p in removed.iter().flat_map(|r| &r.polygons) {
+                    counts[start + p] = 0;
+                }
+            }
+        }
+        (report, RemapReport::new(AttributeRemap::identity(self), counts))
+    }
+}
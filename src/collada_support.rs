@@ -0,0 +1,438 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Export to COLLADA 1.4.1 (.dae).
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::mtl::strip_texture_options;
+use crate::obj::{Group, IndexTuple, ObjData, ObjError, ObjMaterial, Object};
+
+/// Escape a string for use in XML text and attribute values.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn join<T: Display>(values: impl IntoIterator<Item = T>) -> String {
+    values.into_iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+/// The materials referenced by the groups, in order of first use.
+fn collect_materials(data: &ObjData) -> Vec<&ObjMaterial> {
+    let mut materials: Vec<&ObjMaterial> = Vec::new();
+    for group in data.objects.iter().flat_map(|o| &o.groups) {
+        if let Some(material) = &group.material {
+            if !materials.iter().any(|m| m.name() == material.name()) {
+                materials.push(material);
+            }
+        }
+    }
+    materials
+}
+
+fn write_color(out: &mut impl Write, element: &str, color: [f32; 3]) -> Result<(), ObjError> {
+    writeln!(
+        out,
+        "          <{0}><color>{1} {2} {3} 1</color></{0}>",
+        element, color[0], color[1], color[2]
+    )?;
+    Ok(())
+}
+
+fn write_materials(out: &mut impl Write, materials: &[&ObjMaterial]) -> Result<(), ObjError> {
+    let textures: Vec<Option<&str>> = materials
+        .iter()
+        .map(|m| match m {
+            ObjMaterial::Mtl(m) => m.map_kd.as_deref().map(strip_texture_options),
+            ObjMaterial::Ref(_) => None,
+        })
+        .collect();
+
+    if textures.iter().any(Option::is_some) {
+        writeln!(out, "  <library_images>")?;
+        for (i, path) in textures.iter().enumerate() {
+            if let Some(path) = path {
+                writeln!(
+                    out,
+                    "    <image id=\"image{}\"><init_from>{}</init_from></image>",
+                    i,
+                    escape(path)
+                )?;
+            }
+        }
+        writeln!(out, "  </library_images>")?;
+    }
+
+    writeln!(out, "  <library_effects>")?;
+    for (i, (material, texture)) in materials.iter().zip(&textures).enumerate() {
+        writeln!(out, "    <effect id=\"effect{}\">", i)?;
+        writeln!(out, "      <profile_COMMON>")?;
+        if texture.is_some() {
+            writeln!(out, "        <newparam sid=\"surface{}\">", i)?;
+            writeln!(
+                out,
+                "          <surface type=\"2D\"><init_from>image{}</init_from></surface>",
+                i
+            )?;
+            writeln!(out, "        </newparam>")?;
+            writeln!(out, "        <newparam sid=\"sampler{}\">", i)?;
+            writeln!(out, "          <sampler2D><source>surface{}</source></sampler2D>", i)?;
+            writeln!(out, "        </newparam>")?;
+        }
+        writeln!(out, "        <technique sid=\"common\">")?;
+        writeln!(out, "          <phong>")?;
+        match material {
+            ObjMaterial::Mtl(m) => {
+                if let Some(ke) = m.ke {
+                    write_color(out, "emission", ke)?;
+                }
+                if let Some(ka) = m.ka {
+                    write_color(out, "ambient", ka)?;
+                }
+                if texture.is_some() {
+                    writeln!(
+                        out,
+                        "          <diffuse><texture texture=\"sampler{}\" texcoord=\"UVMap\"/></diffuse>",
+                        i
+                    )?;
+                } else if let Some(kd) = m.kd {
+                    write_color(out, "diffuse", kd)?;
+                }
+                if let Some(ks) = m.ks {
+                    write_color(out, "specular", ks)?;
+                }
+                if let Some(ns) = m.ns {
+                    writeln!(out, "          <shininess><float>{}</float></shininess>", ns)?;
+                }
+                if let Some(d) = m.d.or_else(|| m.tr.map(|tr| 1.0 - tr)) {
+                    writeln!(out, "          <transparency><float>{}</float></transparency>", d)?;
+                }
+            }
+            ObjMaterial::Ref(_) => write_color(out, "diffuse", [0.8; 3])?,
+        }
+        writeln!(out, "          </phong>")?;
+        writeln!(out, "        </technique>")?;
+        writeln!(out, "      </profile_COMMON>")?;
+        writeln!(out, "    </effect>")?;
+    }
+    writeln!(out, "  </library_effects>")?;
+
+    writeln!(out, "  <library_materials>")?;
+    for (i, material) in materials.iter().enumerate() {
+        writeln!(
+            out,
+            "    <material id=\"material{0}\" name=\"{1}\"><instance_effect url=\"#effect{0}\"/></material>",
+            i,
+            escape(material.name())
+        )?;
+    }
+    writeln!(out, "  </library_materials>")?;
+    Ok(())
+}
+
+/// The attributes of an object, renumbered to only include the ones it uses.
+#[derive(Default)]
+struct Attributes {
+    remap: [HashMap<usize, usize>; 3],
+    order: [Vec<usize>; 3],
+}
+
+impl Attributes {
+    fn index(&mut self, attribute: usize, old: usize) -> usize {
+        let Attributes { remap, order } = self;
+        *remap[attribute].entry(old).or_insert_with(|| {
+            order[attribute].push(old);
+            order[attribute].len() - 1
+        })
+    }
+}
+
+fn write_source<const N: usize>(
+    out: &mut impl Write,
+    id: &str,
+    values: impl ExactSizeIterator<Item = [f32; N]>,
+    params: [&str; N],
+) -> Result<(), ObjError> {
+    let count = values.len();
+    writeln!(out, "        <source id=\"{}\">", id)?;
+    writeln!(
+        out,
+        "          <float_array id=\"{}-array\" count=\"{}\">{}</float_array>",
+        id,
+        count * N,
+        join(values.flat_map(|v| v.to_vec()))
+    )?;
+    writeln!(out, "          <technique_common>")?;
+    writeln!(
+        out,
+        "            <accessor source=\"#{}-array\" count=\"{}\" stride=\"{}\">",
+        id, count, N
+    )?;
+    for param in &params {
+        writeln!(out, "              <param name=\"{}\" type=\"float\"/>", param)?;
+    }
+    writeln!(out, "            </accessor>")?;
+    writeln!(out, "          </technique_common>")?;
+    writeln!(out, "        </source>")?;
+    Ok(())
+}
+
+fn write_polylist(
+    out: &mut impl Write,
+    id: &str,
+    group: &Group,
+    material: Option<usize>,
+    attributes: &mut Attributes,
+) -> Result<(), ObjError> {
+    let tuples = || group.polys.iter().flat_map(|p| &p.0);
+    let has_texture = tuples().all(|t| t.texture.is_some());
+    let has_normal = tuples().all(|t| t.normal.is_some());
+    let mut p = Vec::new();
+    for IndexTuple {
+        position: v,
+        texture: t,
+        normal: n,
+    } in tuples()
+    {
+        p.push(attributes.index(0, *v));
+        if let (true, Some(n)) = (has_normal, *n) {
+            p.push(attributes.index(2, n));
+        }
+        if let (true, Some(t)) = (has_texture, *t) {
+            p.push(attributes.index(1, t));
+        }
+    }
+
+    match material {
+        Some(m) => writeln!(
+            out,
+            "        <polylist material=\"material{}\" count=\"{}\">",
+            m,
+            group.polys.len()
+        )?,
+        None => writeln!(out, "        <polylist count=\"{}\">", group.polys.len())?,
+    }
+    let mut offset = 0;
+    writeln!(
+        out,
+        "          <input semantic=\"VERTEX\" source=\"#{}-vertices\" offset=\"0\"/>",
+        id
+    )?;
+    if has_normal {
+        offset += 1;
+        writeln!(
+            out,
+            "          <input semantic=\"NORMAL\" source=\"#{}-normals\" offset=\"{}\"/>",
+            id, offset
+        )?;
+    }
+    if has_texture {
+        offset += 1;
+        writeln!(
+            out,
+            "          <input semantic=\"TEXCOORD\" source=\"#{}-texcoords\" offset=\"{}\" set=\"0\"/>",
+            id, offset
+        )?;
+    }
+    writeln!(
+        out,
+        "          <vcount>{}</vcount>",
+        join(group.polys.iter().map(|p| p.0.len()))
+    )?;
+    writeln!(out, "          <p>{}</p>", join(p))?;
+    writeln!(out, "        </polylist>")?;
+    Ok(())
+}
+
+fn write_geometry(
+    out: &mut impl Write,
+    data: &ObjData,
+    id: &str,
+    object: &Object,
+    materials: &[&ObjMaterial],
+) -> Result<(), ObjError> {
+    let material_index = |group: &Group| {
+        let name = group.material.as_ref()?.name();
+        materials.iter().position(|m| m.name() == name)
+    };
+
+    // Write the primitives first to learn which attributes they use.
+    let mut attributes = Attributes::default();
+    let mut primitives = Vec::new();
+    for group in &object.groups {
+        if !group.polys.is_empty() {
+            write_polylist(&mut primitives, id, group, material_index(group), &mut attributes)?;
+        }
+        if !group.lines.is_empty() {
+            let strips: Vec<String> = group
+                .lines
+                .iter()
+                .map(|line| join(line.0.iter().map(|t| attributes.index(0, t.position))))
+                .collect();
+            match material_index(group) {
+                Some(m) => writeln!(
+                    primitives,
+                    "        <linestrips material=\"material{}\" count=\"{}\">",
+                    m,
+                    strips.len()
+                )?,
+                None => writeln!(primitives, "        <linestrips count=\"{}\">", strips.len())?,
+            }
+            writeln!(
+                primitives,
+                "          <input semantic=\"VERTEX\" source=\"#{}-vertices\" offset=\"0\"/>",
+                id
+            )?;
+            for strip in strips {
+                writeln!(primitives, "          <p>{}</p>", strip)?;
+            }
+            writeln!(primitives, "        </linestrips>")?;
+        }
+    }
+
+    writeln!(out, "    <geometry id=\"{}\" name=\"{}\">", id, escape(&object.name))?;
+    writeln!(out, "      <mesh>")?;
+    let [positions, texture, normals] = &attributes.order;
+    write_source(
+        out,
+        &format!("{}-positions", id),
+        positions.iter().map(|&i| data.position[i]),
+        ["X", "Y", "Z"],
+    )?;
+    if !normals.is_empty() {
+        write_source(
+            out,
+            &format!("{}-normals", id),
+            normals.iter().map(|&i| data.normal[i]),
+            ["X", "Y", "Z"],
+        )?;
+    }
+    if !texture.is_empty() {
+        write_source(
+            out,
+            &format!("{}-texcoords", id),
+            texture.iter().map(|&i| data.texture[i]),
+            ["S", "T"],
+        )?;
+    }
+    writeln!(out, "        <vertices id=\"{}-vertices\">", id)?;
+    writeln!(
+        out,
+        "          <input semantic=\"POSITION\" source=\"#{}-positions\"/>",
+        id
+    )?;
+    writeln!(out, "        </vertices>")?;
+    out.write_all(&primitives)?;
+    writeln!(out, "      </mesh>")?;
+    writeln!(out, "    </geometry>")?;
+    Ok(())
+}
+
+impl ObjData {
+    /// Write the geometry and materials of this mesh as a COLLADA 1.4.1 document.
+    ///
+    /// Every object becomes a geometry instanced by a node of the same name, with one `polylist`
+    /// per group keeping the polygons as they are and one `linestrips` element for its lines.
+    /// Normals and texture coordinates are bound to a group only if all of its polygon vertices
+    /// have them. Loaded materials become Phong effects using `Ka`, `Kd`, `Ks`, `Ke`, `Ns` and
+    /// `d`, with the diffuse texture replacing `Kd` if set; unloaded materials get a grey
+    /// effect.
+    pub fn write_collada(&self, out: &mut impl Write) -> Result<(), ObjError> {
+        let materials = collect_materials(self);
+        writeln!(out, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+        writeln!(
+            out,
+            "<COLLADA xmlns=\"http://www.collada.org/2005/11/COLLADASchema\" version=\"1.4.1\">"
+        )?;
+        writeln!(out, "  <asset>")?;
+        writeln!(
+            out,
+            "    <contributor><authoring_tool>obj (https://crates.io/crates/obj)</authoring_tool></contributor>"
+        )?;
+        writeln!(out, "    <up_axis>Y_UP</up_axis>")?;
+        writeln!(out, "  </asset>")?;
+        if !materials.is_empty() {
+            write_materials(out, &materials)?;
+        }
+
+        writeln!(out, "  <library_geometries>")?;
+        for (i, object) in self.objects.iter().enumerate() {
+            write_geometry(out, self, &format!("geometry{}", i), object, &materials)?;
+        }
+        writeln!(out, "  </library_geometries>")?;
+
+        writeln!(out, "  <library_visual_scenes>")?;
+        writeln!(out, "    <visual_scene id=\"scene\">")?;
+        for (i, object) in self.objects.iter().enumerate() {
+            writeln!(out, "      <node id=\"node{}\" name=\"{}\">", i, escape(&object.name))?;
+            writeln!(out, "        <instance_geometry url=\"#geometry{}\">", i)?;
+            let used: Vec<usize> = (0..materials.len())
+                .filter(|&m| {
+                    object
+                        .groups
+                        .iter()
+                        .any(|g| g.material.as_ref().map(|gm| gm.name()) == Some(materials[m].name()))
+                })
+                .collect();
+            if !used.is_empty() {
+                writeln!(out, "          <bind_material>")?;
+                writeln!(out, "            <technique_common>")?;
+                for m in used {
+                    writeln!(
+                        out,
+                        "              <instance_material symbol=\"material{0}\" target=\"#material{0}\">",
+                        m
+                    )?;
+                    writeln!(
+                        out,
+                        "                <bind_vertex_input semantic=\"UVMap\" input_semantic=\"TEXCOORD\" input_set=\"0\"/>"
+                    )?;
+                    writeln!(out, "              </instance_material>")?;
+                }
+                writeln!(out, "            </technique_common>")?;
+                writeln!(out, "          </bind_material>")?;
+            }
+            writeln!(out, "        </instance_geometry>")?;
+            writeln!(out, "      </node>")?;
+        }
+        writeln!(out, "    </visual_scene>")?;
+        writeln!(out, "  </library_visual_scenes>")?;
+        writeln!(out, "  <scene><instance_visual_scene url=\"#scene\"/></scene>")?;
+        writeln!(out, "</COLLADA>")?;
+        Ok(())
+    }
+
+    /// Save this mesh as a COLLADA document at the given path, see [`ObjData::write_collada`].
+    pub fn save_collada(&self, path: impl AsRef<Path>) -> Result<(), ObjError> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_collada(&mut out)?;
+        out.flush()?;
+        Ok(())
+    }
+}
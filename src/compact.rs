@@ -0,0 +1,212 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Removal of unreferenced vertex attributes, and of objects and groups along with them.
+
+use std::ops::Range;
+
+use crate::obj::{Group, IndexTuple, ObjData, Object};
+
+/// Old-to-new index tables for the attribute arrays of an `ObjData`.
+///
+/// Entry `i` of each table holds the new index of the attribute previously stored at index `i`,
+/// or `None` if it was removed. This allows callers to keep their own per-vertex data in sync.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttributeRemap {
+    pub position: Vec<Option<usize>>,
+    pub texture: Vec<Option<usize>>,
+    pub normal: Vec<Option<usize>>,
+}
+
+impl AttributeRemap {
+    /// Tables that keep every attribute of `data` in place.
+    pub(crate) fn identity(data: &ObjData) -> Self {
+        let identity = |len: usize| (0..len).map(Some).collect();
+        AttributeRemap {
+            position: identity(data.position.len()),
+            texture: identity(data.texture.len()),
+            normal: identity(data.normal.len()),
+        }
+    }
+}
+
+/// Old-to-new index tables for the attributes and polygons of an `ObjData`, returned by the
+/// `_with_remap` variants of processing passes such as [`ObjData::compact_with_remap`].
+///
+/// Callers keeping their own per-vertex or per-face data, such as skin weights or IDs, can
+/// rearrange it with these tables to stay in sync with the processed mesh.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemapReport {
+    /// The new indices of the attributes.
+    pub attributes: AttributeRemap,
+    /// For each polygon before the pass, in the order of [`ObjData::polygons`], the range of
+    /// polygons it became in that order.
+    ///
+    /// The range is empty for removed polygons and holds several polygons for ones that were
+    /// split, such as the triangles of a simplified polygon.
+    pub polygons: Vec<Range<usize>>,
+}
+
+impl RemapReport {
+    /// A report for a pass that changed the attributes as given by `attributes`, given the number
+    /// of new polygons each old polygon became.
+    pub(crate) fn new(attributes: AttributeRemap, polygon_counts: impl IntoIterator<Item = usize>) -> Self {
+        let mut next = 0;
+        let polygons = polygon_counts
+            .into_iter()
+            .map(|count| {
+                next += count;
+                next - count..next
+            })
+            .collect();
+        RemapReport { attributes, polygons }
+    }
+
+    /// The new index of every polygon before the pass, or `None` if it was removed or split.
+    pub fn polygon_table(&self) -> Vec<Option<usize>> {
+        self.polygons
+            .iter()
+            .map(|range| if range.len() == 1 { Some(range.start) } else { None })
+            .collect()
+    }
+}
+
+/// Build an old-to-new index table keeping only the `used` entries, in their original order.
+fn compaction_table(used: &[bool]) -> Vec<Option<usize>> {
+    let mut next = 0;
+    used.iter()
+        .map(|&used| {
+            if used {
+                next += 1;
+                Some(next - 1)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn retain_used<T>(values: &mut Vec<T>, table: &[Option<usize>]) {
+    let mut i = 0;
+    values.retain(|_| {
+        i += 1;
+        table[i - 1].is_some()
+    });
+}
+
+impl ObjData {
+    /// Drop all positions, texture coordinates and normals not referenced by any polygon or line
+    /// and rewrite indices accordingly.
+    ///
+    /// The remaining attributes keep their relative order. Indices referring to missing
+    /// attributes, which [`ObjData::validate_indices`] reports, keep referring to missing ones.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn compact(&mut self) -> AttributeRemap {
+        self.compact_attributes(true)
+    }
+
+    /// Drop the texture coordinates and normals not referenced by any polygon or line, keeping
+    /// all positions, see [`WriteConfig::prune_unreferenced`](crate::WriteConfig::prune_unreferenced).
+    pub(crate) fn prune_unreferenced(&mut self) {
+        self.compact_attributes(false);
+    }
+
+    fn compact_attributes(&mut self, positions: bool) -> AttributeRemap {
+        let mut used = [
+            vec![!positions; self.position.len()],
+            vec![false; self.texture.len()],
+            vec![false; self.normal.len()],
+        ];
+        for &IndexTuple {
+            position: p,
+            texture: t,
+            normal: n,
+        } in self.index_tuples()
+        {
+            // Indices past the end are left for `remap_indices` to keep past the end.
+            for (used, index) in used.iter_mut().zip([Some(p), t, n]) {
+                if let Some(used) = index.and_then(|i| used.get_mut(i)) {
+                    *used = true;
+                }
+            }
+        }
+
+        let remap = AttributeRemap {
+            position: compaction_table(&used[0]),
+            texture: compaction_table(&used[1]),
+            normal: compaction_table(&used[2]),
+        };
+        retain_used(&mut self.position, &remap.position);
+        if !self.color.is_empty() {
+            retain_used(&mut self.color, &remap.position);
+        }
+        retain_used(&mut self.texture, &remap.texture);
+        retain_used(&mut self.normal, &remap.normal);
+
+        // Every referenced entry has a new index, so the placeholder for dropped entries is never read.
+        let dense = |table: &[Option<usize>]| table.iter().map(|i| i.unwrap_or(usize::MAX)).collect::<Vec<_>>();
+        self.remap_indices(
+            Some(&dense(&remap.position)),
+            Some(&dense(&remap.texture)),
+            Some(&dense(&remap.normal)),
+        );
+        remap
+    }
+
+    /// Like [`ObjData::compact`], returning the tables as a [`RemapReport`], which keeps all
+    /// polygons in place.
+    pub fn compact_with_remap(&mut self) -> RemapReport {
+        let polygons = self.polys().count();
+        RemapReport::new(self.compact(), vec![1; polygons])
+    }
+
+    /// Keep only the objects for which `predicate` returns `true`, then [`compact`](Self::compact)
+    /// the vertex attributes.
+    pub fn retain_objects<F>(&mut self, predicate: F) -> AttributeRemap
+    where
+        F: FnMut(&Object) -> bool,
+    {
+        self.objects.retain(predicate);
+        self.compact()
+    }
+
+    /// Keep only the groups for which `predicate(object, group)` returns `true`, then
+    /// [`compact`](Self::compact) the vertex attributes.
+    ///
+    /// Objects left without any group are kept.
+    pub fn retain_groups<F>(&mut self, mut predicate: F) -> AttributeRemap
+    where
+        F: FnMut(&Object, &Group) -> bool,
+    {
+        for object in &mut self.objects {
+            let mut groups = std::mem::take(&mut object.groups);
+            groups.retain(|group| predicate(object, group));
+            object.groups = groups;
+        }
+        self.compact()
+    }
+}
+
+impl Object {
+    /// Keep only the groups for which `predicate` returns `true`.
+    ///
+    /// The vertex attributes live in the `ObjData`, so the ones only the dropped groups
+    /// referenced stay until [`ObjData::compact`] is called, or use [`ObjData::retain_groups`].
+    pub fn retain_groups<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&Group) -> bool,
+    {
+        self.groups.retain(predicate);
+    }
+}
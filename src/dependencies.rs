@@ -0,0 +1,200 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Listing and verification of the external files an `Obj` references.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::mtl::TextureSlot;
+use crate::obj::Obj;
+
+/// What an external file is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+    /// A material library given by `mtllib`.
+    MaterialLibrary,
+    /// A texture map of a material in one of the material libraries.
+    Texture(TextureSlot),
+    /// The file given by `shadow_obj`.
+    ShadowObject,
+    /// The file given by `trace_obj`.
+    TraceObject,
+}
+
+/// An external file referenced by an `Obj`.
+///
+/// Returned by [`Obj::dependencies`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dependency {
+    /// What the file is used for.
+    pub kind: DependencyKind,
+    /// The file name as written in the .obj or .mtl file, without texture options.
+    pub reference: String,
+    /// The resolved path of the file.
+    ///
+    /// Relative references are resolved against the directory of the file containing them, and
+    /// made absolute if the file exists.
+    pub path: PathBuf,
+}
+
+/// A referenced file that doesn't exist as written but may be meant to be another file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AmbiguousAsset {
+    /// The reference, with the path it resolves to as written.
+    pub dependency: Dependency,
+    /// Existing files whose path only differs from the reference in the case of its components
+    /// or in using `\` as the separator, or failing that, files with the same name in the
+    /// directory the reference is resolved against.
+    pub candidates: Vec<PathBuf>,
+}
+
+/// Whether the files an `Obj` references exist.
+///
+/// Returned by [`Obj::verify_assets`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssetReport {
+    /// References to existing files.
+    pub found: Vec<Dependency>,
+    /// References to files that don't exist and have no candidates.
+    pub missing: Vec<Dependency>,
+    /// References to files that don't exist, but have candidates that probably are meant.
+    pub ambiguous: Vec<AmbiguousAsset>,
+}
+
+impl AssetReport {
+    /// Whether all referenced files exist.
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.ambiguous.is_empty()
+    }
+}
+
+fn resolve(base_dir: &Path, reference: &str) -> PathBuf {
+    let path = base_dir.join(reference);
+    path.canonicalize().unwrap_or(path)
+}
+
+/// Existing paths that match `reference` relative to `base_dir` up to case and separators.
+fn candidates(base_dir: &Path, reference: &str) -> Vec<PathBuf> {
+    let path = base_dir.join(reference.replace('\\', "/"));
+    let mut matches = vec![PathBuf::new()];
+    for component in path.components() {
+        let name = match component {
+            Component::Normal(name) => name,
+            other => {
+                for path in &mut matches {
+                    path.push(other);
+                }
+                continue;
+            }
+        };
+        matches = matches
+            .iter()
+            .flat_map(|dir| {
+                if dir.join(name).exists() {
+                    return vec![dir.join(name)];
+                }
+                let name = name.to_string_lossy();
+                fs::read_dir(if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    dir
+                })
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case(&name))
+                .map(|entry| dir.join(entry.file_name()))
+                .collect()
+            })
+            .collect();
+    }
+    if matches.is_empty() {
+        if let Some(name) = path.file_name() {
+            let in_base_dir = base_dir.join(name);
+            if in_base_dir.exists() {
+                matches.push(in_base_dir);
+            }
+        }
+    }
+    matches.sort();
+    matches
+}
+
+impl Obj {
+    /// List every external file this `Obj` references, each at most once and in order of first
+    /// reference.
+    ///
+    /// Texture maps can only be listed for material libraries that have been loaded, e.g. with
+    /// [`Obj::load_mtls`].
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies_in(&self.path).into_iter().map(|(d, _)| d).collect()
+    }
+
+    /// Check that every file listed by [`Obj::dependencies`] exists, resolving the references of
+    /// the .obj file against `base_dir` instead of [`Obj::path`].
+    pub fn verify_assets(&self, base_dir: impl AsRef<Path>) -> AssetReport {
+        let mut report = AssetReport::default();
+        for (dependency, dir) in self.dependencies_in(base_dir.as_ref()) {
+            if dependency.path.exists() {
+                report.found.push(dependency);
+                continue;
+            }
+            let candidates = candidates(&dir, &dependency.reference);
+            if candidates.is_empty() {
+                report.missing.push(dependency);
+            } else {
+                report.ambiguous.push(AmbiguousAsset { dependency, candidates });
+            }
+        }
+        report
+    }
+
+    /// The dependencies together with the directory each reference is relative to.
+    fn dependencies_in(&self, base_dir: &Path) -> Vec<(Dependency, PathBuf)> {
+        let data = &self.data;
+        let mut dependencies: Vec<(Dependency, PathBuf)> = Vec::new();
+        let mut push = |kind, dir: &Path, reference: &str| {
+            let path = resolve(dir, reference);
+            if dependencies.iter().all(|(d, _)| d.path != path) {
+                let dependency = Dependency {
+                    kind,
+                    reference: reference.to_string(),
+                    path,
+                };
+                dependencies.push((dependency, dir.to_path_buf()));
+            }
+        };
+
+        for mtl in &data.material_libs {
+            push(DependencyKind::MaterialLibrary, base_dir, &mtl.filename);
+        }
+        for mtl in &data.material_libs {
+            let mtl_path = base_dir.join(&mtl.filename);
+            let mtl_dir = mtl_path.parent().unwrap_or(base_dir);
+            for (slot, texture) in mtl.materials.iter().flat_map(|m| m.texture_paths()) {
+                push(DependencyKind::Texture(slot), mtl_dir, texture);
+            }
+        }
+        if let Some(shadow_obj) = &data.shadow_obj {
+            push(DependencyKind::ShadowObject, base_dir, shadow_obj);
+        }
+        if let Some(trace_obj) = &data.trace_obj {
+            push(DependencyKind::TraceObject, base_dir, trace_obj);
+        }
+        dependencies
+    }
+}
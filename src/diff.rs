@@ -0,0 +1,251 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Structured comparison of two meshes.
+
+use std::{fmt, sync::Arc};
+
+use crate::obj::{Group, ObjData};
+
+/// Identifies a group by the object it belongs to, its name and its index among groups of the
+/// same name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroupId {
+    pub object: Arc<str>,
+    pub group: Arc<str>,
+    /// See [`Group::index`].
+    pub index: usize,
+}
+
+impl fmt::Display for GroupId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.object, self.group)?;
+        if self.index != 0 {
+            write!(f, "#{}", self.index)?;
+        }
+        Ok(())
+    }
+}
+
+/// A group present in both meshes whose `usemtl` material differs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterialChange {
+    pub group: GroupId,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// How one vertex attribute list differs between two meshes.
+///
+/// Entries are compared by index, up to the length of the shorter list.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttributeDiff {
+    /// Number of entries in the first mesh.
+    pub before: usize,
+    /// Number of entries in the second mesh.
+    pub after: usize,
+    /// Number of compared entries that are not equal.
+    pub changed: usize,
+    /// Largest euclidean distance between compared entries.
+    pub max_delta: f32,
+    /// Average euclidean distance between compared entries.
+    pub mean_delta: f32,
+}
+
+impl AttributeDiff {
+    fn new<const N: usize>(before: &[[f32; N]], after: &[[f32; N]]) -> Self {
+        let mut diff = AttributeDiff {
+            before: before.len(),
+            after: after.len(),
+            ..AttributeDiff::default()
+        };
+        let mut sum = 0.0;
+        for (a, b) in before.iter().zip(after) {
+            let delta = a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum::<f32>().sqrt();
+            if a != b {
+                diff.changed += 1;
+            }
+            diff.max_delta = diff.max_delta.max(delta);
+            sum += delta;
+        }
+        let compared = before.len().min(after.len());
+        if compared != 0 {
+            diff.mean_delta = sum / compared as f32;
+        }
+        diff
+    }
+
+    /// Whether the attribute list differs in length or content.
+    pub fn is_changed(&self) -> bool {
+        self.before != self.after || self.changed != 0
+    }
+}
+
+/// The result of [`diff`].
+///
+/// Objects are matched by name and groups by [`GroupId`]. The `Display` implementation lists
+/// the differences one per line, for reviewing asset changes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObjDiff {
+    /// Objects only present in the second mesh.
+    pub added_objects: Vec<Arc<str>>,
+    /// Objects only present in the first mesh.
+    pub removed_objects: Vec<Arc<str>>,
+    /// Groups of objects present in both meshes that are only present in the second mesh.
+    pub added_groups: Vec<GroupId>,
+    /// Groups of objects present in both meshes that are only present in the first mesh.
+    pub removed_groups: Vec<GroupId>,
+    pub material_changes: Vec<MaterialChange>,
+    /// Total number of polygons in the first and second mesh.
+    pub polygons: (usize, usize),
+    /// Total number of lines in the first and second mesh.
+    pub lines: (usize, usize),
+    pub position: AttributeDiff,
+    pub texture: AttributeDiff,
+    pub normal: AttributeDiff,
+    pub color: AttributeDiff,
+}
+
+impl ObjDiff {
+    /// Whether no differences were found.
+    pub fn is_empty(&self) -> bool {
+        self.added_objects.is_empty()
+            && self.removed_objects.is_empty()
+            && self.added_groups.is_empty()
+            && self.removed_groups.is_empty()
+            && self.material_changes.is_empty()
+            && self.polygons.0 == self.polygons.1
+            && self.lines.0 == self.lines.1
+            && !self.position.is_changed()
+            && !self.texture.is_changed()
+            && !self.normal.is_changed()
+            && !self.color.is_changed()
+    }
+}
+
+impl fmt::Display for ObjDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        for name in &self.added_objects {
+            writeln!(f, "+ object {}", name)?;
+        }
+        for name in &self.removed_objects {
+            writeln!(f, "- object {}", name)?;
+        }
+        for id in &self.added_groups {
+            writeln!(f, "+ group {}", id)?;
+        }
+        for id in &self.removed_groups {
+            writeln!(f, "- group {}", id)?;
+        }
+        for change in &self.material_changes {
+            writeln!(
+                f,
+                "~ material {}: {} -> {}",
+                change.group,
+                change.before.as_deref().unwrap_or("(none)"),
+                change.after.as_deref().unwrap_or("(none)")
+            )?;
+        }
+        for (name, (before, after)) in [("polygons", self.polygons), ("lines", self.lines)] {
+            if before != after {
+                writeln!(f, "{}: {} -> {}", name, before, after)?;
+            }
+        }
+        let attributes = [
+            ("positions", &self.position),
+            ("texture coordinates", &self.texture),
+            ("normals", &self.normal),
+            ("colors", &self.color),
+        ];
+        for (name, diff) in attributes.iter().filter(|(_, diff)| diff.is_changed()) {
+            writeln!(
+                f,
+                "{}: {} -> {}, {} changed, max delta {}, mean delta {}",
+                name, diff.before, diff.after, diff.changed, diff.max_delta, diff.mean_delta
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn group_ids<'a>(object: &'a Arc<str>, groups: &'a [Group]) -> impl Iterator<Item = (GroupId, &'a Group)> + 'a {
+    groups.iter().map(move |group| {
+        let id = GroupId {
+            object: object.clone(),
+            group: group.name.clone(),
+            index: group.index,
+        };
+        (id, group)
+    })
+}
+
+fn line_count(data: &ObjData) -> usize {
+    data.objects.iter().flat_map(|o| &o.groups).map(|g| g.lines.len()).sum()
+}
+
+/// Compare two meshes, reporting the changes going from `before` to `after`.
+pub fn diff(before: &ObjData, after: &ObjData) -> ObjDiff {
+    let mut diff = ObjDiff {
+        position: AttributeDiff::new(&before.position, &after.position),
+        texture: AttributeDiff::new(&before.texture, &after.texture),
+        normal: AttributeDiff::new(&before.normal, &after.normal),
+        color: AttributeDiff::new(&before.color, &after.color),
+        polygons: (before.polys().count(), after.polys().count()),
+        lines: (line_count(before), line_count(after)),
+        ..ObjDiff::default()
+    };
+
+    for old in &before.objects {
+        let new = match after.objects.iter().find(|o| o.name == old.name) {
+            Some(new) => new,
+            None => {
+                diff.removed_objects.push(old.name.clone());
+                continue;
+            }
+        };
+        let new_groups: Vec<_> = group_ids(&new.name, &new.groups).collect();
+        for (id, old_group) in group_ids(&old.name, &old.groups) {
+            match new_groups.iter().find(|(new_id, _)| *new_id == id) {
+                Some((_, new_group)) => {
+                    let before = old_group.material.as_ref().map(|m| m.name().to_string());
+                    let after = new_group.material.as_ref().map(|m| m.name().to_string());
+                    if before != after {
+                        diff.material_changes.push(MaterialChange {
+                            group: id,
+                            before,
+                            after,
+                        });
+                    }
+                }
+                None => diff.removed_groups.push(id),
+            }
+        }
+        let old_groups: Vec<_> = group_ids(&old.name, &old.groups).map(|(id, _)| id).collect();
+        diff.added_groups.extend(
+            new_groups
+                .into_iter()
+                .map(|(id, _)| id)
+                .filter(|id| !old_groups.contains(id)),
+        );
+    }
+    for new in &after.objects {
+        if before.objects.iter().all(|o| o.name != new.name) {
+            diff.added_objects.push(new.name.clone());
+        }
+    }
+    diff
+}
@@ -0,0 +1,70 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Extraction of mesh edges as line elements.
+
+use std::collections::HashMap;
+
+use crate::math::{angle_between, newell_normal, normalize, Vec3};
+use crate::obj::{Group, IndexTuple, Line, ObjData};
+
+/// Name of the group returned by [`ObjData::extract_edges`].
+const EDGE_GROUP: &str = "edges";
+
+impl ObjData {
+    /// Collect the unique edges of all polygons as two-point lines.
+    ///
+    /// With `feature_angle` set, only feature edges are kept: edges where the normals of the
+    /// adjacent faces differ by more than the given angle in radians, as well as boundary and
+    /// non-manifold edges. Edges are identified by position indices, and are returned in order
+    /// of first appearance in a new group named `edges`, which can be added to any object for
+    /// writing wireframe overlays as `l` statements.
+    pub fn extract_edges(&self, feature_angle: Option<f32>) -> Group {
+        // For every undirected edge, the normals of the faces using it.
+        let mut edges: Vec<([usize; 2], Vec<Option<Vec3>>)> = Vec::new();
+        let mut lookup: HashMap<[usize; 2], usize> = HashMap::new();
+        for poly in self.polys() {
+            let points: Vec<_> = poly.0.iter().map(|t| self.position[t.position]).collect();
+            let normal = normalize(newell_normal(&points));
+            let n = poly.0.len();
+            for k in 0..n {
+                let (a, b) = (poly.0[k].position, poly.0[(k + 1) % n].position);
+                if a == b {
+                    continue;
+                }
+                let key = [a.min(b), a.max(b)];
+                let i = *lookup.entry(key).or_insert_with(|| {
+                    edges.push((key, Vec::new()));
+                    edges.len() - 1
+                });
+                edges[i].1.push(normal);
+            }
+        }
+
+        let is_feature = |normals: &[Option<Vec3>]| match (feature_angle, normals) {
+            (None, _) => true,
+            (Some(angle), &[Some(a), Some(b)]) => angle_between(a, b) > angle,
+            // Boundary and non-manifold edges, or edges of degenerate faces.
+            (Some(_), _) => true,
+        };
+
+        let mut group = Group::new(EDGE_GROUP.to_string());
+        group.lines = edges
+            .into_iter()
+            .filter(|(_, normals)| is_feature(normals))
+            .map(|([a, b], _)| Line(vec![IndexTuple::new(a, None, None), IndexTuple::new(b, None, None)]))
+            .collect();
+        group
+    }
+}
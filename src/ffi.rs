@@ -0,0 +1,421 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! A C interface for loading .obj files and reading their contents.
+//!
+//! All functions are `extern "C"` and take opaque pointers, so `cbindgen` can generate a header
+//! for them. Build the crate as a `cdylib` or `staticlib` with the `ffi` feature enabled, e.g.
+//! with `cargo rustc --release --features ffi --crate-type cdylib`.
+//!
+//! Functions reporting failure with a null pointer or `false` store a description of the error,
+//! which can be retrieved with [`obj_last_error`].
+//!
+//! Strings are copied into caller provided buffers: the functions return the length of the
+//! string, excluding the terminating NUL, and write as much of it as fits into `buf_len` bytes,
+//! always terminated with a NUL if `buf_len` is not zero.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::Arc;
+
+use crate::buffers::{IndexedBuffers, Vertex};
+use crate::mtl::Material;
+use crate::obj::{Obj, ObjData, ObjMaterial};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(err: impl std::fmt::Display) {
+    let message = CString::new(err.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Copy `s` into the buffer as a NUL-terminated string, returning its full length.
+unsafe fn copy_str(s: &str, buf: *mut c_char, buf_len: usize) -> usize {
+    if !buf.is_null() && buf_len > 0 {
+        let len = s.len().min(buf_len - 1);
+        ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, buf, len);
+        *buf.add(len) = 0;
+    }
+    s.len()
+}
+
+/// The description of the most recent error on this thread, or null if there was none.
+///
+/// The string is valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn obj_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// A loaded .obj file along with the materials of its material libraries.
+pub struct ObjFile {
+    data: ObjData,
+    materials: Vec<Arc<Material>>,
+}
+
+impl ObjFile {
+    fn new(data: ObjData) -> *mut ObjFile {
+        let materials = data
+            .material_libs
+            .iter()
+            .flat_map(|mtl| mtl.materials.iter().cloned())
+            .collect();
+        Box::into_raw(Box::new(ObjFile { data, materials }))
+    }
+}
+
+/// Load an .obj file and the .mtl files it references.
+///
+/// Returns null on failure. Material libraries that fail to load are skipped and reported
+/// through [`obj_last_error`] without failing the call.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn obj_load(path: *const c_char) -> *mut ObjFile {
+    let path = match CStr::from_ptr(path).to_str() {
+        Ok(path) => path,
+        Err(err) => {
+            set_last_error(err);
+            return ptr::null_mut();
+        }
+    };
+    let mut obj = match Obj::load(path) {
+        Ok(obj) => obj,
+        Err(err) => {
+            set_last_error(err);
+            return ptr::null_mut();
+        }
+    };
+    if let Err(err) = obj.load_mtls() {
+        set_last_error(err);
+    }
+    ObjFile::new(obj.data)
+}
+
+/// Parse an .obj file from memory, without loading material libraries.
+///
+/// Returns null on failure.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn obj_load_buf(data: *const u8, len: usize) -> *mut ObjFile {
+    let bytes = std::slice::from_raw_parts(data, len);
+    match ObjData::load_buf(bytes) {
+        Ok(data) => ObjFile::new(data),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Free a file returned by [`obj_load`] or [`obj_load_buf`].
+///
+/// # Safety
+///
+/// `obj` must be null or a pointer returned by one of the load functions that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn obj_free(obj: *mut ObjFile) {
+    if !obj.is_null() {
+        drop(Box::from_raw(obj));
+    }
+}
+
+/// The number of vertex positions.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_position_count(obj: *const ObjFile) -> usize {
+    (&*obj).data.position.len()
+}
+
+/// The vertex positions as three consecutive floats each.
+///
+/// The pointer is valid until the file is freed.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_positions(obj: *const ObjFile) -> *const f32 {
+    (&*obj).data.position.as_ptr() as *const f32
+}
+
+/// The number of texture coordinates.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_texture_count(obj: *const ObjFile) -> usize {
+    (&*obj).data.texture.len()
+}
+
+/// The texture coordinates as two consecutive floats each.
+///
+/// The pointer is valid until the file is freed.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_texture_coords(obj: *const ObjFile) -> *const f32 {
+    (&*obj).data.texture.as_ptr() as *const f32
+}
+
+/// The number of normals.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_normal_count(obj: *const ObjFile) -> usize {
+    (&*obj).data.normal.len()
+}
+
+/// The normals as three consecutive floats each.
+///
+/// The pointer is valid until the file is freed.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_normals(obj: *const ObjFile) -> *const f32 {
+    (&*obj).data.normal.as_ptr() as *const f32
+}
+
+/// The number of materials in all loaded material libraries.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_material_count(obj: *const ObjFile) -> usize {
+    (&*obj).materials.len()
+}
+
+/// The material at the given index, or null if it is out of range.
+///
+/// The pointer is valid until the file is freed.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_material(obj: *const ObjFile, index: usize) -> *const Material {
+    (&*obj).materials.get(index).map_or(ptr::null(), Arc::as_ptr)
+}
+
+/// Copy the name of a material into `buf`.
+///
+/// # Safety
+///
+/// `material` must be a valid material pointer and `buf` must be null or point to `buf_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn obj_material_name(material: *const Material, buf: *mut c_char, buf_len: usize) -> usize {
+    copy_str(&(&*material).name, buf, buf_len)
+}
+
+/// A color property of a material.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ObjColor {
+    /// `Ka`
+    Ambient,
+    /// `Kd`
+    Diffuse,
+    /// `Ks`
+    Specular,
+    /// `Ke`
+    Emissive,
+}
+
+/// Write a color of the material to three consecutive floats at `out`, returning `false` and
+/// leaving `out` untouched if the material does not set it.
+///
+/// # Safety
+///
+/// `material` must be a valid material pointer and `out` must point to three writable floats.
+#[no_mangle]
+pub unsafe extern "C" fn obj_material_color(material: *const Material, color: ObjColor, out: *mut f32) -> bool {
+    let material = &*material;
+    let value = match color {
+        ObjColor::Ambient => material.ka,
+        ObjColor::Diffuse => material.kd,
+        ObjColor::Specular => material.ks,
+        ObjColor::Emissive => material.ke,
+    };
+    match value {
+        Some(value) => {
+            ptr::copy_nonoverlapping(value.as_ptr(), out, 3);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Copy the diffuse texture path of the material, without texture options, into `buf`.
+///
+/// Returns 0 if the material has no diffuse texture.
+///
+/// # Safety
+///
+/// `material` must be a valid material pointer and `buf` must be null or point to `buf_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn obj_material_diffuse_map(
+    material: *const Material,
+    buf: *mut c_char,
+    buf_len: usize,
+) -> usize {
+    let path = (&*material).to_pbr().base_color_texture.unwrap_or_default();
+    copy_str(&path, buf, buf_len)
+}
+
+/// Triangulated, indexed buffers with one index range per material.
+pub struct ObjBuffers {
+    buffers: IndexedBuffers,
+    /// The index into the file's materials for each submesh, or -1.
+    materials: Vec<isize>,
+}
+
+/// An interleaved vertex of [`ObjBuffers`].
+pub type ObjVertex = Vertex;
+
+/// Build indexed buffers from the file, see [`ObjData::to_indexed_buffers_by_material`].
+///
+/// Returns null if the file refers to missing attributes, see [`ObjData::validate_indices`], or
+/// if the mesh needs more than `u32::MAX` vertices.
+///
+/// # Safety
+///
+/// `obj` must be a valid pointer returned by one of the load functions.
+#[no_mangle]
+pub unsafe extern "C" fn obj_buffers_new(obj: *const ObjFile) -> *mut ObjBuffers {
+    let obj = &*obj;
+    if let Err(err) = obj.data.validate_indices() {
+        set_last_error(err);
+        return ptr::null_mut();
+    }
+    let buffers = match std::panic::catch_unwind(|| obj.data.to_indexed_buffers_by_material()) {
+        Ok(buffers) => buffers,
+        Err(_) => {
+            set_last_error("failed to build buffers");
+            return ptr::null_mut();
+        }
+    };
+    let materials = buffers
+        .submeshes
+        .iter()
+        .map(|s| match &s.material {
+            Some(ObjMaterial::Mtl(m)) => obj
+                .materials
+                .iter()
+                .position(|o| Arc::ptr_eq(o, m))
+                .map_or(-1, |i| i as isize),
+            _ => -1,
+        })
+        .collect();
+    Box::into_raw(Box::new(ObjBuffers { buffers, materials }))
+}
+
+/// Free buffers returned by [`obj_buffers_new`].
+///
+/// # Safety
+///
+/// `buffers` must be null or a pointer returned by [`obj_buffers_new`] that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn obj_buffers_free(buffers: *mut ObjBuffers) {
+    if !buffers.is_null() {
+        drop(Box::from_raw(buffers));
+    }
+}
+
+/// The number of vertices.
+///
+/// # Safety
+///
+/// `buffers` must be a valid pointer returned by [`obj_buffers_new`].
+#[no_mangle]
+pub unsafe extern "C" fn obj_buffers_vertex_count(buffers: *const ObjBuffers) -> usize {
+    (&*buffers).buffers.vertices.len()
+}
+
+/// The interleaved vertices, valid until the buffers are freed.
+///
+/// # Safety
+///
+/// `buffers` must be a valid pointer returned by [`obj_buffers_new`].
+#[no_mangle]
+pub unsafe extern "C" fn obj_buffers_vertices(buffers: *const ObjBuffers) -> *const ObjVertex {
+    (&*buffers).buffers.vertices.as_ptr()
+}
+
+/// The number of submeshes, i.e. index ranges.
+///
+/// # Safety
+///
+/// `buffers` must be a valid pointer returned by [`obj_buffers_new`].
+#[no_mangle]
+pub unsafe extern "C" fn obj_buffers_submesh_count(buffers: *const ObjBuffers) -> usize {
+    (&*buffers).buffers.submeshes.len()
+}
+
+/// The indices of a submesh, three per triangle, with their number written to `count`.
+///
+/// Returns null if `index` is out of range. The pointer is valid until the buffers are freed.
+///
+/// # Safety
+///
+/// `buffers` must be a valid pointer returned by [`obj_buffers_new`] and `count` must be
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn obj_buffers_submesh_indices(
+    buffers: *const ObjBuffers,
+    index: usize,
+    count: *mut usize,
+) -> *const u32 {
+    match (&*buffers).buffers.submeshes.get(index) {
+        Some(submesh) => {
+            *count = submesh.indices.len();
+            submesh.indices.as_ptr()
+        }
+        None => {
+            *count = 0;
+            ptr::null()
+        }
+    }
+}
+
+/// The index of the submesh's material for [`obj_material`], or -1 if it has no loaded material.
+///
+/// # Safety
+///
+/// `buffers` must be a valid pointer returned by [`obj_buffers_new`].
+#[no_mangle]
+pub unsafe extern "C" fn obj_buffers_submesh_material(buffers: *const ObjBuffers, index: usize) -> isize {
+    (&*buffers).materials.get(index).copied().unwrap_or(-1)
+}
@@ -0,0 +1,41 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversions to [`glam`] math types.
+
+use crate::obj::ObjData;
+
+impl ObjData {
+    /// Iterate over the vertex positions as `glam` vectors.
+    pub fn glam_positions(&self) -> impl ExactSizeIterator<Item = glam::Vec3> + '_ {
+        self.position.iter().copied().map(glam::Vec3::from)
+    }
+
+    /// Iterate over the texture coordinates as `glam` vectors.
+    pub fn glam_texture_coords(&self) -> impl ExactSizeIterator<Item = glam::Vec2> + '_ {
+        self.texture.iter().copied().map(glam::Vec2::from)
+    }
+
+    /// Iterate over the vertex normals as `glam` vectors.
+    pub fn glam_normals(&self) -> impl ExactSizeIterator<Item = glam::Vec3> + '_ {
+        self.normal.iter().copied().map(glam::Vec3::from)
+    }
+
+    /// Apply an affine transformation given as a `glam` matrix to all positions and normals.
+    ///
+    /// See [`ObjData::transform`].
+    pub fn transform_glam(&mut self, matrix: &glam::Mat4) {
+        self.transform(&matrix.to_cols_array_2d());
+    }
+}
@@ -0,0 +1,227 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Export to glTF 2.0.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use serde_json::{json, Map, Value};
+
+use crate::bounds::BoundingBox;
+use crate::obj::{ObjData, ObjError, ObjMaterial};
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const VERTEX_STRIDE: usize = 32;
+
+/// Collects images and textures, sharing them between materials using the same file.
+#[derive(Default)]
+struct Textures {
+    images: Vec<Value>,
+    textures: Vec<Value>,
+    lookup: HashMap<String, usize>,
+}
+
+impl Textures {
+    fn get(&mut self, uri: &str) -> Value {
+        let Textures {
+            images,
+            textures,
+            lookup,
+        } = self;
+        let index = *lookup.entry(uri.to_string()).or_insert_with(|| {
+            images.push(json!({ "uri": uri }));
+            textures.push(json!({ "source": images.len() - 1 }));
+            textures.len() - 1
+        });
+        json!({ "index": index })
+    }
+}
+
+fn material_json(material: &ObjMaterial, textures: &mut Textures) -> Value {
+    let pbr = match material {
+        ObjMaterial::Ref(name) => return json!({ "name": &**name }),
+        ObjMaterial::Mtl(material) => material.to_pbr(),
+    };
+    let mut metallic_roughness = Map::new();
+    metallic_roughness.insert("baseColorFactor".into(), json!(pbr.base_color));
+    metallic_roughness.insert("metallicFactor".into(), json!(pbr.metallic));
+    metallic_roughness.insert("roughnessFactor".into(), json!(pbr.roughness));
+    if let Some(uri) = &pbr.base_color_texture {
+        metallic_roughness.insert("baseColorTexture".into(), textures.get(uri));
+    }
+
+    let mut out = Map::new();
+    out.insert("name".into(), json!(pbr.name));
+    out.insert("pbrMetallicRoughness".into(), Value::Object(metallic_roughness));
+    out.insert("emissiveFactor".into(), json!(pbr.emissive));
+    if let Some(uri) = &pbr.emissive_texture {
+        out.insert("emissiveTexture".into(), textures.get(uri));
+    }
+    if let Some(uri) = &pbr.normal_texture {
+        out.insert("normalTexture".into(), textures.get(uri));
+    }
+    if pbr.transparent {
+        out.insert("alphaMode".into(), json!("BLEND"));
+    }
+    Value::Object(out)
+}
+
+impl ObjData {
+    /// Write this mesh as a glTF 2.0 asset, with the JSON document going to `gltf` and the binary
+    /// buffer going to `buffer`, which the document references as `buffer_uri`.
+    ///
+    /// The vertices of [`ObjData::to_indexed_buffers_by_material`] are stored interleaved, and
+    /// every material range becomes one primitive of a single mesh. Materials are converted with
+    /// [`Material::to_pbr`](crate::Material::to_pbr), keeping texture paths as image URIs, while
+    /// unloaded materials only keep their name. Texture coordinates are flipped vertically, since
+    /// glTF puts their origin at the top left. Normals and texture coordinates are only exported
+    /// if the mesh has any. Lines are not exported.
+    pub fn write_gltf(&self, gltf: &mut impl Write, buffer: &mut impl Write, buffer_uri: &str) -> Result<(), ObjError> {
+        let buffers = self.to_indexed_buffers_by_material();
+        let mut bin: Vec<u8> = Vec::with_capacity(buffers.vertices.len() * VERTEX_STRIDE);
+        for v in &buffers.vertices {
+            let [u, t] = v.texture;
+            for c in v.position.iter().chain(&[u, 1.0 - t]).chain(&v.normal) {
+                bin.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        let vertex_len = bin.len();
+
+        let mut accessors = Vec::new();
+        let mut attributes = Map::new();
+        if let Some(bounds) = BoundingBox::from_points(buffers.vertices.iter().map(|v| &v.position)) {
+            accessors.push(json!({
+                "bufferView": 0,
+                "byteOffset": 0,
+                "componentType": FLOAT,
+                "count": buffers.vertices.len(),
+                "type": "VEC3",
+                "min": bounds.min,
+                "max": bounds.max,
+            }));
+            attributes.insert("POSITION".into(), json!(0));
+            if !self.texture.is_empty() {
+                accessors.push(json!({
+                    "bufferView": 0,
+                    "byteOffset": 12,
+                    "componentType": FLOAT,
+                    "count": buffers.vertices.len(),
+                    "type": "VEC2",
+                }));
+                attributes.insert("TEXCOORD_0".into(), json!(accessors.len() - 1));
+            }
+            if !self.normal.is_empty() {
+                accessors.push(json!({
+                    "bufferView": 0,
+                    "byteOffset": 20,
+                    "componentType": FLOAT,
+                    "count": buffers.vertices.len(),
+                    "type": "VEC3",
+                }));
+                attributes.insert("NORMAL".into(), json!(accessors.len() - 1));
+            }
+        }
+
+        let mut textures = Textures::default();
+        let mut materials = Vec::new();
+        let mut primitives = Vec::new();
+        for submesh in buffers.submeshes.iter().filter(|s| !s.indices.is_empty()) {
+            accessors.push(json!({
+                "bufferView": 1,
+                "byteOffset": bin.len() - vertex_len,
+                "componentType": UNSIGNED_INT,
+                "count": submesh.indices.len(),
+                "type": "SCALAR",
+            }));
+            for i in &submesh.indices {
+                bin.extend_from_slice(&i.to_le_bytes());
+            }
+            let mut primitive = Map::new();
+            primitive.insert("attributes".into(), Value::Object(attributes.clone()));
+            primitive.insert("indices".into(), json!(accessors.len() - 1));
+            primitive.insert("mode".into(), json!(4));
+            if let Some(material) = &submesh.material {
+                materials.push(material_json(material, &mut textures));
+                primitive.insert("material".into(), json!(materials.len() - 1));
+            }
+            primitives.push(Value::Object(primitive));
+        }
+
+        let mut document = Map::new();
+        document.insert(
+            "asset".into(),
+            json!({ "version": "2.0", "generator": "obj (https://crates.io/crates/obj)" }),
+        );
+        document.insert(
+            "buffers".into(),
+            json!([{ "byteLength": bin.len(), "uri": buffer_uri }]),
+        );
+        document.insert(
+            "bufferViews".into(),
+            json!([
+                { "buffer": 0, "byteOffset": 0, "byteLength": vertex_len, "byteStride": VERTEX_STRIDE, "target": ARRAY_BUFFER },
+                { "buffer": 0, "byteOffset": vertex_len, "byteLength": bin.len() - vertex_len, "target": ELEMENT_ARRAY_BUFFER },
+            ]),
+        );
+        document.insert("accessors".into(), Value::Array(accessors));
+        if !primitives.is_empty() {
+            document.insert("meshes".into(), json!([{ "primitives": primitives }]));
+            document.insert("nodes".into(), json!([{ "mesh": 0 }]));
+        } else {
+            document.insert("nodes".into(), json!([{}]));
+        }
+        document.insert("scenes".into(), json!([{ "nodes": [0] }]));
+        document.insert("scene".into(), json!(0));
+        if !materials.is_empty() {
+            document.insert("materials".into(), Value::Array(materials));
+        }
+        if !textures.images.is_empty() {
+            document.insert("images".into(), Value::Array(textures.images));
+            document.insert("textures".into(), Value::Array(textures.textures));
+        }
+
+        serde_json::to_writer_pretty(&mut *gltf, &Value::Object(document)).map_err(io::Error::from)?;
+        buffer.write_all(&bin)?;
+        Ok(())
+    }
+
+    /// Save this mesh as a glTF 2.0 asset at the given path, with the binary buffer stored next
+    /// to it under the same name with a `.bin` extension.
+    ///
+    /// See [`ObjData::write_gltf`].
+    pub fn save_gltf(&self, path: impl AsRef<Path>) -> Result<(), ObjError> {
+        let path = path.as_ref();
+        let buffer_name = match path.file_name() {
+            Some(name) => Path::new(name).with_extension("bin"),
+            None => {
+                let message = format!("{} does not name a file", path.display());
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, message).into());
+            }
+        };
+        let buffer_path = path.with_file_name(&buffer_name);
+        let buffer_uri = buffer_name.to_string_lossy().into_owned();
+        let mut gltf = BufWriter::new(File::create(path)?);
+        let mut buffer = BufWriter::new(File::create(&buffer_path)?);
+        self.write_gltf(&mut gltf, &mut buffer, &buffer_uri)?;
+        gltf.flush()?;
+        buffer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Half-edge adjacency of the polygons of a mesh.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::obj::{IndexTuple, ObjData};
+
+/// A directed edge of a polygon, see [`HalfEdgeMesh`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HalfEdge {
+    /// The polygon corner the edge starts at.
+    pub tuple: IndexTuple,
+    /// The polygon the edge belongs to.
+    pub face: usize,
+    /// The next half-edge around the polygon, starting where this one ends.
+    pub next: usize,
+    /// The previous half-edge around the polygon.
+    pub prev: usize,
+    /// The half-edge of the neighboring polygon running the other way along the same edge.
+    ///
+    /// This is `None` on the boundary, and for edges that are used by more than two polygons,
+    /// traversed in the same direction by two polygons or connect a position to itself.
+    pub twin: Option<usize>,
+}
+
+/// The polygons of an [`ObjData`] as half-edges, see [`ObjData::to_half_edge`].
+///
+/// Vertices are the position indices of the data. Polygons are numbered consecutively across all
+/// groups of all objects, in order, and half-edges are numbered in the order of the polygon
+/// corners they start at.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HalfEdgeMesh {
+    half_edges: Vec<HalfEdge>,
+    /// The half-edges of each face, which are consecutive.
+    faces: Vec<Range<usize>>,
+    /// The half-edges leaving each vertex, given by `outgoing_start`.
+    outgoing: Vec<usize>,
+    outgoing_start: Vec<usize>,
+}
+
+impl HalfEdgeMesh {
+    /// Build the half-edges of the polygons of `data`.
+    pub fn new(data: &ObjData) -> Self {
+        let mut half_edges = Vec::new();
+        let mut faces = Vec::new();
+        for (face, poly) in data.polys().enumerate() {
+            let start = half_edges.len();
+            let n = poly.0.len();
+            half_edges.extend(poly.0.iter().enumerate().map(|(k, &tuple)| HalfEdge {
+                tuple,
+                face,
+                next: start + (k + 1) % n,
+                prev: start + (k + n - 1) % n,
+                twin: None,
+            }));
+            faces.push(start..half_edges.len());
+        }
+
+        // The half-edges along each directed edge.
+        let mut directed: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+        for (h, edge) in half_edges.iter().enumerate() {
+            let end = half_edges[edge.next].tuple.position;
+            if edge.tuple.position != end {
+                directed.entry([edge.tuple.position, end]).or_default().push(h);
+            }
+        }
+        for (&[a, b], uses) in &directed {
+            if let (&[h], Some(&[twin])) = (&uses[..], directed.get(&[b, a]).map(|t| &t[..])) {
+                half_edges[h].twin = Some(twin);
+            }
+        }
+
+        // Half-edges starting at missing positions are left out of the outgoing lists.
+        let vertices = data.position.len();
+        let mut outgoing_start = vec![0; vertices + 1];
+        for edge in half_edges.iter().filter(|edge| edge.tuple.position < vertices) {
+            outgoing_start[edge.tuple.position + 1] += 1;
+        }
+        for v in 0..vertices {
+            outgoing_start[v + 1] += outgoing_start[v];
+        }
+        let mut fill = outgoing_start.clone();
+        let mut outgoing = vec![0; outgoing_start[vertices]];
+        for (h, edge) in half_edges.iter().enumerate() {
+            if edge.tuple.position >= vertices {
+                continue;
+            }
+            outgoing[fill[edge.tuple.position]] = h;
+            fill[edge.tuple.position] += 1;
+        }
+
+        HalfEdgeMesh {
+            half_edges,
+            faces,
+            outgoing,
+            outgoing_start,
+        }
+    }
+
+    /// All half-edges.
+    pub fn half_edges(&self) -> &[HalfEdge] {
+        &self.half_edges
+    }
+
+    /// The half-edge with index `h`.
+    pub fn half_edge(&self, h: usize) -> &HalfEdge {
+        &self.half_edges[h]
+    }
+
+    /// The position index the half-edge `h` ends at.
+    pub fn end(&self, h: usize) -> usize {
+        self.half_edges[self.half_edges[h].next].tuple.position
+    }
+
+    /// The number of vertices, which is the number of positions of the data.
+    pub fn vertex_count(&self) -> usize {
+        self.outgoing_start.len() - 1
+    }
+
+    /// The number of polygons.
+    pub fn face_count(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// The vertices, as position indices.
+    pub fn vertices(&self) -> Range<usize> {
+        0..self.vertex_count()
+    }
+
+    /// The half-edges of the polygon `face`, in order around it.
+    pub fn face_half_edges(&self, face: usize) -> Range<usize> {
+        self.faces[face].clone()
+    }
+
+    /// The polygons sharing an edge with `face`, once for each shared edge.
+    pub fn face_neighbors(&self, face: usize) -> impl Iterator<Item = usize> + '_ {
+        self.face_half_edges(face)
+            .filter_map(move |h| self.half_edges[h].twin)
+            .map(move |twin| self.half_edges[twin].face)
+    }
+
+    /// The half-edges starting at `vertex`, in the order of their polygons.
+    pub fn outgoing(&self, vertex: usize) -> &[usize] {
+        &self.outgoing[self.outgoing_start[vertex]..self.outgoing_start[vertex + 1]]
+    }
+
+    /// The vertices connected to `vertex` by an edge, each once, in order of first appearance.
+    pub fn vertex_neighbors(&self, vertex: usize) -> Vec<usize> {
+        let mut neighbors = Vec::new();
+        for &h in self.outgoing(vertex) {
+            let edge = &self.half_edges[h];
+            for other in [self.end(h), self.half_edges[edge.prev].tuple.position] {
+                if other != vertex && !neighbors.contains(&other) {
+                    neighbors.push(other);
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// Every edge once, as the half-edge with the lower index of a pair of twins or the single
+    /// half-edge without twin.
+    ///
+    /// Half-edges without twin, such as those of non-manifold edges, are each returned, so an
+    /// edge shared by more than two polygons appears several times.
+    pub fn edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.half_edges.len()).filter(move |&h| self.half_edges[h].twin.is_none_or(|twin| h < twin))
+    }
+
+    /// Whether the half-edge `h` has no twin, see [`HalfEdge::twin`].
+    pub fn is_boundary(&self, h: usize) -> bool {
+        self.half_edges[h].twin.is_none()
+    }
+}
+
+impl ObjData {
+    /// Build the half-edge adjacency of the polygons, see [`HalfEdgeMesh`].
+    ///
+    /// Lines are not part of it. The result refers to the current indices and polygons, so it
+    /// has to be built again after the data is changed. Half-edges starting at missing positions,
+    /// which [`ObjData::validate_indices`] reports, belong to their polygon but leave no vertex,
+    /// see [`HalfEdgeMesh::outgoing`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn to_half_edge(&self) -> HalfEdgeMesh {
+        HalfEdgeMesh::new(self)
+    }
+}
@@ -0,0 +1,121 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Stable hashing of the geometry and topology of an `ObjData`.
+
+use std::fmt::{self, Write};
+
+use crate::obj::{IndexTuple, ObjData, Scalar};
+
+/// 64-bit FNV-1a, chosen over `std::hash` because its output must not change between platforms
+/// or compiler versions.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn count(&mut self, n: usize) {
+        self.bytes(&(n as u64).to_le_bytes());
+    }
+
+    fn option(&mut self, index: Option<usize>) {
+        self.count(index.map_or(0, |i| i + 1));
+    }
+
+    fn str(&mut self, s: &str) {
+        self.count(s.len());
+        self.bytes(s.as_bytes());
+    }
+
+    /// Hash values by their shortest round-tripping decimal representation, which is exact and
+    /// available for any `Scalar`.
+    fn values<T: fmt::Display>(&mut self, values: &[T]) {
+        for value in values {
+            // Writing into the hasher itself can't fail.
+            let _ = write!(self, "{} ", value);
+        }
+    }
+
+    fn tuples(&mut self, tuples: &[IndexTuple]) {
+        self.count(tuples.len());
+        for tuple in tuples {
+            self.count(tuple.position);
+            self.option(tuple.texture);
+            self.option(tuple.normal);
+        }
+    }
+}
+
+impl Write for Fnv1a {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<S: Scalar> ObjData<S> {
+    /// A digest of the geometry and topology that is stable across runs, platforms and versions
+    /// of this crate.
+    ///
+    /// Covers vertex attributes, the names of objects, groups and materials, and all polygons and
+    /// lines. Formatting, comments, material libraries and the `shadow_obj` and `trace_obj` files
+    /// are not part of it, so writing and loading a mesh again yields the same hash.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hasher.count(self.position.len());
+        for position in &self.position {
+            hasher.values(position);
+        }
+        hasher.count(self.color.len());
+        for color in &self.color {
+            hasher.values(color);
+        }
+        hasher.count(self.texture.len());
+        for texture in &self.texture {
+            hasher.values(texture);
+        }
+        hasher.count(self.normal.len());
+        for normal in &self.normal {
+            hasher.values(normal);
+        }
+
+        hasher.count(self.objects.len());
+        for object in &self.objects {
+            hasher.str(&object.name);
+            hasher.count(object.groups.len());
+            for group in &object.groups {
+                hasher.str(&group.name);
+                hasher.str(group.material.as_ref().map_or("", |m| m.name()));
+                hasher.count(group.polys.len());
+                for poly in &group.polys {
+                    hasher.tuples(&poly.0);
+                }
+                hasher.count(group.lines.len());
+                for line in &group.lines {
+                    hasher.tuples(&line.0);
+                }
+            }
+        }
+        hasher.0
+    }
+}
@@ -0,0 +1,89 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Hierarchies encoded in group names, such as `car/wheel/rim`.
+
+use crate::obj::ObjData;
+
+/// A node of the tree of group names, see [`ObjData::group_tree`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupNode<'a> {
+    /// The last part of the path, such as `rim` for `car/wheel/rim`.
+    pub name: &'a str,
+    /// The group name up to and including this node, such as `car/wheel`.
+    pub path: &'a str,
+    /// The groups named exactly `path`, as indices into [`ObjData::objects`] and
+    /// [`Object::groups`](crate::Object::groups).
+    ///
+    /// Empty for nodes that only appear as the parent of other groups.
+    pub groups: Vec<(usize, usize)>,
+    /// The nodes one level below, in order of first appearance.
+    pub children: Vec<GroupNode<'a>>,
+}
+
+impl<'a> GroupNode<'a> {
+    /// The groups of this node and all nodes below it, parents first.
+    pub fn all_groups(&self) -> Vec<(usize, usize)> {
+        let mut groups = self.groups.clone();
+        for child in &self.children {
+            groups.extend(child.all_groups());
+        }
+        groups
+    }
+}
+
+/// Add the group `id` named `name` below `nodes`, with the part of `name` from `start` on still
+/// to be placed.
+fn insert<'a>(nodes: &mut Vec<GroupNode<'a>>, name: &'a str, start: usize, separator: &str, id: (usize, usize)) {
+    let end = match separator {
+        "" => None,
+        separator => name[start..].find(separator).map(|i| start + i),
+    };
+    let part = &name[start..end.unwrap_or(name.len())];
+    let node = match nodes.iter().position(|node| node.name == part) {
+        Some(i) => &mut nodes[i],
+        None => {
+            nodes.push(GroupNode {
+                name: part,
+                path: &name[..start + part.len()],
+                groups: Vec::new(),
+                children: Vec::new(),
+            });
+            nodes.last_mut().unwrap()
+        }
+    };
+    match end {
+        Some(end) => insert(&mut node.children, name, end + separator.len(), separator, id),
+        None => node.groups.push(id),
+    }
+}
+
+impl ObjData {
+    /// Arrange the groups of all objects into trees by splitting their names at `separator`.
+    ///
+    /// Many exporters flatten a scene hierarchy into group names such as `car/wheel` or
+    /// `car.wheel`. The returned root nodes are in order of first appearance, and nodes for
+    /// parents without a group of their own are created as needed. Groups with the same name, such
+    /// as the material ranges of a group or equally named groups of different objects, share a
+    /// node. An empty `separator` leaves every name in one piece.
+    pub fn group_tree<'a>(&'a self, separator: &str) -> Vec<GroupNode<'a>> {
+        let mut roots = Vec::new();
+        for (o, object) in self.objects.iter().enumerate() {
+            for (g, group) in object.groups.iter().enumerate() {
+                insert(&mut roots, &group.name, 0, separator, (o, g));
+            }
+        }
+        roots
+    }
+}
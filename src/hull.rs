@@ -0,0 +1,234 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Convex hulls of vertex positions.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::obj::{Group, IndexTuple, ObjData, Object, SimplePolygon, DEFAULT_OBJECT};
+
+/// Name of the group returned by [`ObjData::convex_hull`].
+const HULL_GROUP: &str = "hull";
+
+type DVec3 = [f64; 3];
+
+fn sub(a: DVec3, b: DVec3) -> DVec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: DVec3, b: DVec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: DVec3, b: DVec3) -> DVec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// A triangle of the hull, wound counter-clockwise seen from outside.
+struct Face {
+    vertices: [usize; 3],
+    normal: DVec3,
+    offset: f64,
+    /// Points above this face, assigned to no other face.
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl Face {
+    fn new(points: &[DVec3], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|v| points[v]);
+        let n = cross(sub(b, a), sub(c, a));
+        let len = dot(n, n).sqrt();
+        let normal = if len > 0.0 { n.map(|x| x / len) } else { n };
+        Face {
+            vertices,
+            normal,
+            offset: dot(normal, a),
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    fn distance(&self, p: DVec3) -> f64 {
+        dot(self.normal, p) - self.offset
+    }
+}
+
+impl ObjData {
+    /// The convex hull of the positions used by all objects, see [`ObjData::convex_hull_of`].
+    pub fn convex_hull(&self) -> ObjData {
+        self.convex_hull_of(|_| true)
+    }
+
+    /// The convex hull of the positions used by the polygons and lines of the objects for which
+    /// `predicate` returns `true`.
+    ///
+    /// The hull is returned as outward facing triangles in a group named `hull`, referencing only
+    /// the positions on the hull. It has no faces if the positions are all coplanar. Indices of
+    /// missing positions, which [`ObjData::validate_indices`] reports, are ignored.
+    pub fn convex_hull_of<F>(&self, mut predicate: F) -> ObjData
+    where
+        F: FnMut(&Object) -> bool,
+    {
+        let mut used = HashSet::new();
+        let mut indices = Vec::new();
+        for object in self.objects.iter().filter(|object| predicate(object)) {
+            for group in &object.groups {
+                let polys = group.polys.iter().flat_map(|poly| &poly.0);
+                for tuple in polys.chain(group.lines.iter().flat_map(|line| &line.0)) {
+                    if tuple.position < self.position.len() && used.insert(tuple.position) {
+                        indices.push(tuple.position);
+                    }
+                }
+            }
+        }
+        let points: Vec<DVec3> = indices.iter().map(|&i| self.position[i].map(f64::from)).collect();
+
+        let mut hull = ObjData::default();
+        let faces = match quickhull(&points) {
+            Some(faces) => faces,
+            None => return hull,
+        };
+        let mut remap = HashMap::new();
+        let mut group = Group::new(HULL_GROUP);
+        for face in faces {
+            let tuples = face.iter().map(|&v| {
+                let next = remap.len();
+                let index = *remap.entry(v).or_insert_with(|| {
+                    hull.position.push(self.position[indices[v]]);
+                    next
+                });
+                IndexTuple::new(index, None, None)
+            });
+            group.polys.push(tuples.collect::<SimplePolygon>());
+        }
+        let mut object = Object::new(DEFAULT_OBJECT);
+        object.groups.push(group);
+        hull.objects.push(object);
+        hull
+    }
+}
+
+/// The triangles of the convex hull of `points`, or `None` if they span no volume.
+fn quickhull(points: &[DVec3]) -> Option<Vec<[usize; 3]>> {
+    let extent = points.iter().flatten().fold(0.0f64, |extent, &x| extent.max(x.abs()));
+    let eps = extent * 1e-9;
+
+    // Start with a tetrahedron of far apart points.
+    let (a, b) = {
+        let min = (0..points.len()).min_by(|&i, &j| points[i][0].total_cmp(&points[j][0]))?;
+        let max = (0..points.len()).max_by(|&i, &j| points[i][0].total_cmp(&points[j][0]))?;
+        let far = (0..points.len()).max_by(|&i, &j| {
+            let d = |k: usize| dot(sub(points[k], points[min]), sub(points[k], points[min]));
+            d(i).total_cmp(&d(j))
+        })?;
+        (min, if points[max] != points[min] { max } else { far })
+    };
+    let ab = sub(points[b], points[a]);
+    let line_distance = |k: usize| {
+        let c = cross(ab, sub(points[k], points[a]));
+        dot(c, c)
+    };
+    let c = (0..points.len()).max_by(|&i, &j| line_distance(i).total_cmp(&line_distance(j)))?;
+    if line_distance(c).sqrt() <= eps * dot(ab, ab).sqrt() {
+        return None;
+    }
+    let base = Face::new(points, [a, b, c]);
+    let d = (0..points.len()).max_by(|&i, &j| {
+        base.distance(points[i])
+            .abs()
+            .total_cmp(&base.distance(points[j]).abs())
+    })?;
+    if base.distance(points[d]).abs() <= eps {
+        return None;
+    }
+
+    let tetrahedron = if base.distance(points[d]) > 0.0 {
+        [[a, c, b], [a, b, d], [b, c, d], [c, a, d]]
+    } else {
+        [[a, b, c], [a, d, b], [b, d, c], [c, d, a]]
+    };
+    let mut faces: Vec<Face> = tetrahedron.iter().map(|&v| Face::new(points, v)).collect();
+    let corners = [a, b, c, d];
+    assign(
+        points,
+        &mut faces,
+        0,
+        (0..points.len()).filter(|p| !corners.contains(p)),
+        eps,
+    );
+
+    while let Some(f) = faces.iter().position(|face| face.alive && !face.outside.is_empty()) {
+        let apex = *faces[f]
+            .outside
+            .iter()
+            .max_by(|&&i, &&j| faces[f].distance(points[i]).total_cmp(&faces[f].distance(points[j])))?;
+
+        let visible: Vec<usize> = (0..faces.len())
+            .filter(|&i| faces[i].alive && faces[i].distance(points[apex]) > eps)
+            .collect();
+        let mut edges = HashSet::new();
+        for &i in &visible {
+            let [u, v, w] = faces[i].vertices;
+            edges.extend([(u, v), (v, w), (w, u)]);
+        }
+
+        let mut orphans = Vec::new();
+        for &i in &visible {
+            faces[i].alive = false;
+            orphans.append(&mut faces[i].outside);
+        }
+        let first_new = faces.len();
+        for &i in &visible {
+            let [u, v, w] = faces[i].vertices;
+            for (from, to) in [(u, v), (v, w), (w, u)] {
+                // An edge is on the horizon if the face across it is not visible.
+                if !edges.contains(&(to, from)) {
+                    faces.push(Face::new(points, [from, to, apex]));
+                }
+            }
+        }
+        assign(
+            points,
+            &mut faces,
+            first_new,
+            orphans.into_iter().filter(|&p| p != apex),
+            eps,
+        );
+    }
+
+    Some(
+        faces
+            .into_iter()
+            .filter(|face| face.alive)
+            .map(|face| face.vertices)
+            .collect(),
+    )
+}
+
+/// Add each point to the outside set of the first face from `first` on it lies above.
+fn assign(points: &[DVec3], faces: &mut [Face], first: usize, candidates: impl Iterator<Item = usize>, eps: f64) {
+    for p in candidates {
+        if let Some(face) = faces[first..]
+            .iter_mut()
+            .find(|face| face.alive && face.distance(points[p]) > eps)
+        {
+            face.outside.push(p);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Handles to vertices and polygons that stay valid across edits.
+
+use crate::compact::RemapReport;
+use crate::obj::ObjData;
+
+/// A handle to a vertex position, see [`StableIds`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexId {
+    slot: usize,
+    generation: u32,
+}
+
+/// A handle to a polygon, see [`StableIds`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PolyId {
+    slot: usize,
+    generation: u32,
+}
+
+/// Slots mapping handles to current indices, reused for new elements once their element is
+/// removed.
+#[derive(Clone, Debug, Default)]
+struct Slots {
+    /// The generation and current index of each slot.
+    slots: Vec<(u32, Option<usize>)>,
+    /// The slot of each current index.
+    current: Vec<usize>,
+    /// Slots whose element was removed.
+    free: Vec<usize>,
+}
+
+impl Slots {
+    fn new(len: usize) -> Self {
+        Slots {
+            slots: (0..len).map(|i| (0, Some(i))).collect(),
+            current: (0..len).collect(),
+            free: Vec::new(),
+        }
+    }
+
+    fn id(&self, index: usize) -> Option<(usize, u32)> {
+        let slot = *self.current.get(index)?;
+        Some((slot, self.slots[slot].0))
+    }
+
+    fn index(&self, (slot, generation): (usize, u32)) -> Option<usize> {
+        match self.slots.get(slot) {
+            Some(&(g, index)) if g == generation => index,
+            _ => None,
+        }
+    }
+
+    /// Move every slot to the new index of its element given by `new_index`, and give slots to
+    /// new indices up to `len`.
+    fn remap(&mut self, len: usize, new_index: impl Fn(usize) -> Option<usize>) {
+        let mut current = vec![usize::MAX; len];
+        for (old, &slot) in self.current.iter().enumerate() {
+            match new_index(old).filter(|&new| new < len) {
+                Some(new) => {
+                    self.slots[slot].1 = Some(new);
+                    if current[new] == usize::MAX {
+                        current[new] = slot;
+                    }
+                }
+                None => {
+                    self.slots[slot] = (self.slots[slot].0.wrapping_add(1), None);
+                    self.free.push(slot);
+                }
+            }
+        }
+        for (new, slot) in current.iter_mut().enumerate() {
+            if *slot == usize::MAX {
+                *slot = match self.free.pop() {
+                    Some(free) => free,
+                    None => {
+                        self.slots.push((0, None));
+                        self.slots.len() - 1
+                    }
+                };
+                self.slots[*slot].1 = Some(new);
+            }
+        }
+        self.current = current;
+    }
+}
+
+/// Stable handles to the vertex positions and polygons of an `ObjData`.
+///
+/// Indices into the position array and into [`ObjData::polygons`] shift when elements are
+/// removed, so tools editing a mesh interactively can hand out [`VertexId`]s and [`PolyId`]s
+/// instead. After each edit, the handles are brought up to date with the [`RemapReport`] of the
+/// edit, such as the one returned by [`ObjData::weld_vertices_with_remap`], or with
+/// [`StableIds::extend`] for elements that were only appended.
+///
+/// Handles of removed elements no longer resolve, even once their slot is reused for a new
+/// element, as every reuse bumps the generation counter of the slot. Handles of vertices merged
+/// into another vertex resolve to the merged vertex.
+#[derive(Clone, Debug, Default)]
+pub struct StableIds {
+    vertex: Slots,
+    polygon: Slots,
+}
+
+impl StableIds {
+    /// Handles for the current positions and polygons of `data`.
+    pub fn new(data: &ObjData) -> Self {
+        StableIds {
+            vertex: Slots::new(data.position.len()),
+            polygon: Slots::new(data.polys().count()),
+        }
+    }
+
+    /// The handle of the position at `index`, or `None` if it is out of range.
+    pub fn vertex_id(&self, index: usize) -> Option<VertexId> {
+        let (slot, generation) = self.vertex.id(index)?;
+        Some(VertexId { slot, generation })
+    }
+
+    /// The handle of the polygon at `index` in the order of [`ObjData::polygons`], or `None` if
+    /// it is out of range.
+    pub fn poly_id(&self, index: usize) -> Option<PolyId> {
+        let (slot, generation) = self.polygon.id(index)?;
+        Some(PolyId { slot, generation })
+    }
+
+    /// The current index of the position with the given handle, or `None` if it was removed.
+    pub fn vertex_index(&self, id: VertexId) -> Option<usize> {
+        self.vertex.index((id.slot, id.generation))
+    }
+
+    /// The current index of the polygon with the given handle, or `None` if it was removed.
+    pub fn poly_index(&self, id: PolyId) -> Option<usize> {
+        self.polygon.index((id.slot, id.generation))
+    }
+
+    /// Update the handles after an edit of `data` described by `report`.
+    ///
+    /// Polygons that were split into several polygons count as removed, and the new polygons as
+    /// well as positions beyond those covered by `report` get new handles.
+    pub fn apply(&mut self, data: &ObjData, report: &RemapReport) {
+        let position = &report.attributes.position;
+        self.vertex
+            .remap(data.position.len(), |old| position.get(old).copied().flatten());
+        self.polygon.remap(data.polys().count(), |old| {
+            report.polygons.get(old).filter(|r| r.len() == 1).map(|r| r.start)
+        });
+    }
+
+    /// Give handles to positions and polygons appended to `data` since the last update.
+    pub fn extend(&mut self, data: &ObjData) {
+        self.vertex.remap(data.position.len(), Some);
+        self.polygon.remap(data.polys().count(), Some);
+    }
+}
+
+impl ObjData {
+    /// Stable handles to the current positions and polygons, see [`StableIds`].
+    pub fn stable_ids(&self) -> StableIds {
+        StableIds::new(self)
+    }
+}
@@ -0,0 +1,66 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Decoding of texture maps with the `image` crate.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::mtl::{Material, TextureSlot};
+use crate::obj::Obj;
+
+/// A texture map of a material, decoded by [`Material::load_textures`].
+#[derive(Debug)]
+pub struct LoadedTexture {
+    pub slot: TextureSlot,
+    /// The resolved path of the image file.
+    pub path: PathBuf,
+    /// The decoded image, or the error reading or decoding it.
+    pub image: Result<image::DynamicImage, image::ImageError>,
+}
+
+impl Material {
+    /// Decode every texture map of this material, resolving relative paths against `base_dir`.
+    ///
+    /// Each map is loaded independently, so a missing or broken file only fails its own entry.
+    /// Use `DynamicImage::to_rgba8` on the result for a raw RGBA buffer.
+    pub fn load_textures(&self, base_dir: impl AsRef<Path>) -> Vec<LoadedTexture> {
+        let base_dir = base_dir.as_ref();
+        self.texture_paths()
+            .map(|(slot, path)| {
+                let path = base_dir.join(path);
+                let image = image::open(&path);
+                LoadedTexture { slot, path, image }
+            })
+            .collect()
+    }
+}
+
+impl Obj {
+    /// Decode the texture maps of all materials in the loaded material libraries.
+    ///
+    /// Texture paths are resolved relative to the directory of the .mtl file referencing them.
+    /// Materials are returned in library order, including those without textures.
+    pub fn load_textures(&self) -> Vec<(Arc<Material>, Vec<LoadedTexture>)> {
+        let mut textures = Vec::new();
+        for mtl in &self.data.material_libs {
+            let mtl_dir = self.path.join(&mtl.filename);
+            let base_dir = mtl_dir.parent().unwrap_or(&self.path);
+            for material in &mtl.materials {
+                textures.push((material.clone(), material.load_textures(base_dir)));
+            }
+        }
+        textures
+    }
+}
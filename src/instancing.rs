@@ -0,0 +1,201 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Detection of objects that repeat the same mesh at different places.
+
+use std::collections::HashMap;
+
+use crate::math::{add, any_perpendicular, cross, length, mat3_mul_vec, normalize, sub, Mat3, Vec3};
+use crate::obj::{IndexTuple, ObjData, Object};
+
+/// Objects found by [`ObjData::find_instances`] to be copies of the same mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceGroup {
+    /// Index of the first object with this mesh in [`ObjData::objects`].
+    pub prototype: usize,
+    /// Indices of the later objects with the same mesh, each with the rigid transformation that
+    /// maps the prototype onto it, as a column-major matrix as taken by [`ObjData::transform`].
+    pub instances: Vec<(usize, [[f32; 4]; 4])>,
+}
+
+/// The attributes of an object in the order they are first referenced, with its elements
+/// reduced to a key that is the same for all objects with the same topology and materials.
+struct LocalMesh<'a> {
+    key: (Vec<Option<&'a str>>, Vec<usize>),
+    position: Vec<usize>,
+    texture: Vec<usize>,
+    normal: Vec<usize>,
+}
+
+fn local_index(map: &mut HashMap<usize, usize>, list: &mut Vec<usize>, index: usize) -> usize {
+    *map.entry(index).or_insert_with(|| {
+        list.push(index);
+        list.len() - 1
+    })
+}
+
+impl<'a> LocalMesh<'a> {
+    fn new(object: &'a Object) -> Self {
+        let mut mesh = LocalMesh {
+            key: (Vec::new(), Vec::new()),
+            position: Vec::new(),
+            texture: Vec::new(),
+            normal: Vec::new(),
+        };
+        let mut maps = [HashMap::new(), HashMap::new(), HashMap::new()];
+        let mut push_tuples = |mesh: &mut LocalMesh, tuples: &[IndexTuple]| {
+            mesh.key.1.push(tuples.len());
+            for t in tuples {
+                let p = local_index(&mut maps[0], &mut mesh.position, t.position);
+                let vt = t
+                    .texture
+                    .map_or(0, |i| local_index(&mut maps[1], &mut mesh.texture, i) + 1);
+                let vn = t
+                    .normal
+                    .map_or(0, |i| local_index(&mut maps[2], &mut mesh.normal, i) + 1);
+                mesh.key.1.extend([p, vt, vn]);
+            }
+        };
+        for group in &object.groups {
+            mesh.key.0.push(group.material.as_ref().map(|m| m.name()));
+            mesh.key.1.extend([group.polys.len(), group.lines.len()]);
+            for poly in &group.polys {
+                push_tuples(&mut mesh, &poly.0);
+            }
+            for line in &group.lines {
+                push_tuples(&mut mesh, &line.0);
+            }
+        }
+        mesh
+    }
+
+    /// An orthonormal frame spanned by the first position, the one farthest from it and the one
+    /// farthest from the line through both, along with the indices of these positions.
+    fn frame(&self, data: &ObjData) -> ([usize; 3], Mat3) {
+        let p = |i: usize| data.position[self.position[i]];
+        let farthest = |distance: &dyn Fn(Vec3) -> f32| {
+            let distances = (0..self.position.len()).map(|i| (i, distance(p(i))));
+            distances.fold((0, 0.0), |best, next| if next.1 > best.1 { next } else { best })
+        };
+        let a = farthest(&|q| length(sub(q, p(0)))).0;
+        let x = normalize(sub(p(a), p(0)));
+        let b = match x {
+            Some(x) => farthest(&|q| length(cross(x, sub(q, p(0))))).0,
+            None => 0,
+        };
+        (
+            [0, a, b],
+            self.frame_at(data, [0, a, b])
+                .unwrap_or([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+        )
+    }
+
+    /// The orthonormal frame spanned by the given local positions, completed arbitrarily if they
+    /// are collinear, or `None` if they coincide.
+    fn frame_at(&self, data: &ObjData, [o, a, b]: [usize; 3]) -> Option<Mat3> {
+        let p = |i: usize| data.position[self.position[i]];
+        let x = normalize(sub(p(a), p(o)))?;
+        let z = normalize(cross(x, sub(p(b), p(o)))).unwrap_or_else(|| any_perpendicular(x));
+        Some([x, cross(z, x), z])
+    }
+}
+
+/// The rotation taking the frame `from` onto the frame `to`.
+fn rotation_between(from: &Mat3, to: &Mat3) -> Mat3 {
+    // Both frames are orthonormal, so the rotation is `to * from^T`.
+    [0, 1, 2].map(|c| [0, 1, 2].map(|r| (0..3).map(|k| to[k][r] * from[k][c]).sum()))
+}
+
+impl ObjData {
+    /// Find objects whose meshes are copies of an earlier object, moved and rotated.
+    ///
+    /// Two objects have the same mesh if their groups use the same materials in the same order,
+    /// if their polygons and lines connect their vertices in the same way and order, and if a
+    /// rotation and translation maps the positions and normals of one onto the other, within
+    /// `tolerance`. Texture coordinates must be equal within `tolerance`. Object and group names
+    /// are ignored, and mirrored or scaled copies are not detected.
+    ///
+    /// Only meshes with at least one copy are reported, ordered by their prototype. The objects
+    /// can be converted into instanced draws of the prototype with the returned transformations.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn find_instances(&self, tolerance: f32) -> Vec<InstanceGroup> {
+        let meshes: Vec<_> = self.objects.iter().map(LocalMesh::new).collect();
+        let mut prototypes: HashMap<_, Vec<usize>> = HashMap::new();
+        let mut groups: Vec<InstanceGroup> = Vec::new();
+        for (object, mesh) in meshes.iter().enumerate() {
+            if mesh.position.is_empty() {
+                continue;
+            }
+            let candidates = prototypes.entry(&mesh.key).or_default();
+            let found = candidates.iter().find_map(|&group| {
+                let prototype = &meshes[groups[group].prototype];
+                self.instance_transform(prototype, mesh, tolerance)
+                    .map(|matrix| (group, matrix))
+            });
+            match found {
+                Some((group, matrix)) => groups[group].instances.push((object, matrix)),
+                None => {
+                    candidates.push(groups.len());
+                    groups.push(InstanceGroup {
+                        prototype: object,
+                        instances: Vec::new(),
+                    });
+                }
+            }
+        }
+        groups.retain(|group| !group.instances.is_empty());
+        groups
+    }
+
+    /// The rigid transformation mapping `from` onto `to`, if there is one within `tolerance`.
+    fn instance_transform(&self, from: &LocalMesh, to: &LocalMesh, tolerance: f32) -> Option<[[f32; 4]; 4]> {
+        let (points, from_frame) = from.frame(self);
+        let to_frame = match to.frame_at(self, points) {
+            Some(frame) => frame,
+            None if points == [0; 3] => from_frame,
+            None => return None,
+        };
+        let rotation = rotation_between(&from_frame, &to_frame);
+        let translation = sub(
+            self.position[to.position[0]],
+            mat3_mul_vec(&rotation, self.position[from.position[0]]),
+        );
+        let close = |a: Vec3, b: Vec3| length(sub(a, b)) <= tolerance;
+        let positions = from.position.iter().zip(&to.position).all(|(&a, &b)| {
+            close(
+                add(mat3_mul_vec(&rotation, self.position[a]), translation),
+                self.position[b],
+            )
+        });
+        let normals = from
+            .normal
+            .iter()
+            .zip(&to.normal)
+            .all(|(&a, &b)| close(mat3_mul_vec(&rotation, self.normal[a]), self.normal[b]));
+        let textures = from.texture.iter().zip(&to.texture).all(|(&a, &b)| {
+            let (a, b) = (self.texture[a], self.texture[b]);
+            (a[0] - b[0]).hypot(a[1] - b[1]) <= tolerance
+        });
+        if !(positions && normals && textures) {
+            return None;
+        }
+        let [x, y, z] = rotation;
+        Some([
+            [x[0], x[1], x[2], 0.0],
+            [y[0], y[1], y[2], 0.0],
+            [z[0], z[1], z[2], 0.0],
+            [translation[0], translation[1], translation[2], 1.0],
+        ])
+    }
+}
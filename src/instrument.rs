@@ -0,0 +1,71 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Timing of the phases of loading, reported with the `tracing` feature.
+//!
+//! Processing passes are instrumented where they are defined, with spans named after them.
+
+#[cfg(feature = "tracing")]
+use std::time::{Duration, Instant};
+
+/// The time spent reading the source and parsing it, which alternate while loading.
+///
+/// Without the `tracing` feature, this does nothing.
+#[derive(Default)]
+pub(crate) struct PhaseTimes {
+    #[cfg(feature = "tracing")]
+    io: Duration,
+    #[cfg(feature = "tracing")]
+    parse: Duration,
+}
+
+#[cfg(feature = "tracing")]
+impl PhaseTimes {
+    /// Run `read`, counting its time as reading.
+    pub(crate) fn io<T>(&mut self, read: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = read();
+        self.io += start.elapsed();
+        result
+    }
+
+    /// Run `parse`, counting its time as parsing.
+    pub(crate) fn parse<T>(&mut self, parse: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = parse();
+        self.parse += start.elapsed();
+        result
+    }
+
+    /// Emit an event with the times counted so far.
+    pub(crate) fn report(&self) {
+        tracing::debug!(io = ?self.io, parse = ?self.parse, "read source");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+impl PhaseTimes {
+    #[inline]
+    pub(crate) fn io<T>(&mut self, read: impl FnOnce() -> T) -> T {
+        read()
+    }
+
+    #[inline]
+    pub(crate) fn parse<T>(&mut self, parse: impl FnOnce() -> T) -> T {
+        parse()
+    }
+
+    #[inline]
+    pub(crate) fn report(&self) {}
+}
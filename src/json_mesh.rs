@@ -0,0 +1,198 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! A flat mesh description for JSON consumers such as web viewers.
+
+use serde::{Deserialize, Serialize};
+
+use crate::mtl::{Material, TextureSlot};
+use crate::obj::{ObjData, ObjMaterial};
+
+/// The version of the schema written to [`JsonMesh::version`].
+const VERSION: u32 = 1;
+
+/// A triangulated mesh with one vertex buffer and one index buffer per material.
+///
+/// [`ObjData::to_json_mesh`] produces this mesh, which serializes to the following schema,
+/// independent of the internal structure of [`ObjData`]:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "positions": [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0],
+///   "normals": [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+///   "uvs": [0.0, 0.0, 1.0, 0.0, 1.0, 1.0],
+///   "primitives": [{ "material": 0, "indices": [0, 1, 2] }],
+///   "materials": [{ "name": "red", "diffuse": [1.0, 0.0, 0.0], "diffuse_texture": "red.png" }]
+/// }
+/// ```
+///
+/// `positions`, `normals` and `uvs` hold three, three and two numbers per vertex. `normals` and
+/// `uvs` are left out if no polygon has them. Each primitive is a list of triangles, three
+/// indices into the vertices each, sharing the material at the given index of `materials`, or
+/// no material if `material` is left out. Material parameters without a value in the .mtl file
+/// are left out as well. Fields are only ever added in a later `version`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonMesh {
+    /// The version of the schema, currently 1.
+    pub version: u32,
+    /// Three coordinates per vertex.
+    pub positions: Vec<f32>,
+    /// Three components per vertex, or none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normals: Vec<f32>,
+    /// Two texture coordinates per vertex, or none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uvs: Vec<f32>,
+    pub primitives: Vec<JsonPrimitive>,
+    pub materials: Vec<JsonMaterial>,
+}
+
+/// The triangles of a [`JsonMesh`] using one material.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonPrimitive {
+    /// Index into [`JsonMesh::materials`], or `None` for triangles without material.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<usize>,
+    /// Three vertex indices per triangle.
+    pub indices: Vec<u32>,
+}
+
+/// The parameters of a material of a [`JsonMesh`].
+///
+/// Materials that were referenced but not loaded only have a name. Texture paths are given
+/// without their options.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct JsonMaterial {
+    pub name: String,
+    /// `Ka`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ambient: Option<[f32; 3]>,
+    /// `Kd`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diffuse: Option<[f32; 3]>,
+    /// `Ks`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub specular: Option<[f32; 3]>,
+    /// `Ke`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emissive: Option<[f32; 3]>,
+    /// `Ns`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shininess: Option<f32>,
+    /// `d`, or one minus `Tr` if `d` is missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f32>,
+    /// `Ni`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refraction_index: Option<f32>,
+    /// `illum`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub illumination: Option<i32>,
+    /// `map_Kd`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diffuse_texture: Option<String>,
+    /// `map_Ks`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub specular_texture: Option<String>,
+    /// `map_Ke`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emissive_texture: Option<String>,
+    /// `map_d`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity_texture: Option<String>,
+    /// `map_Bump`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bump_texture: Option<String>,
+}
+
+impl JsonMaterial {
+    fn new(material: &ObjMaterial) -> Self {
+        let mut json = JsonMaterial {
+            name: material.name().to_string(),
+            ..JsonMaterial::default()
+        };
+        let material: &Material = match material.as_material() {
+            Some(material) => material,
+            None => return json,
+        };
+        json.ambient = material.ka;
+        json.diffuse = material.kd;
+        json.specular = material.ks;
+        json.emissive = material.ke;
+        json.shininess = material.ns;
+        json.opacity = material.d.or(material.tr.map(|tr| 1.0 - tr));
+        json.refraction_index = material.ni;
+        json.illumination = material.illum;
+        for (slot, path) in material.texture_paths() {
+            let texture = match slot {
+                TextureSlot::Diffuse => &mut json.diffuse_texture,
+                TextureSlot::Specular => &mut json.specular_texture,
+                TextureSlot::Emissive => &mut json.emissive_texture,
+                TextureSlot::Dissolve => &mut json.opacity_texture,
+                TextureSlot::Bump => &mut json.bump_texture,
+                _ => continue,
+            };
+            *texture = Some(path.to_string());
+        }
+        json
+    }
+}
+
+impl ObjData {
+    /// Convert the polygons into a [`JsonMesh`], ready to be serialized with e.g. `serde_json`.
+    ///
+    /// Vertices are deduplicated and polygons triangulated as by
+    /// [`ObjData::to_indexed_buffers_by_material`]. Lines are left out.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if more than `u32::MAX` distinct vertices are produced.
+    pub fn to_json_mesh(&self) -> JsonMesh {
+        let buffers = self.to_indexed_buffers_by_material();
+        let tuples = || self.polys().flat_map(|p| p.0.iter());
+        let (has_normals, has_uvs) = (
+            tuples().any(|t| t.normal.is_some()),
+            tuples().any(|t| t.texture.is_some()),
+        );
+        let mut mesh = JsonMesh {
+            version: VERSION,
+            positions: buffers.vertices.iter().flat_map(|v| v.position).collect(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            primitives: Vec::new(),
+            materials: Vec::new(),
+        };
+        if has_normals {
+            mesh.normals = buffers.vertices.iter().flat_map(|v| v.normal).collect();
+        }
+        if has_uvs {
+            mesh.uvs = buffers.vertices.iter().flat_map(|v| v.texture).collect();
+        }
+        for submesh in buffers.submeshes {
+            if submesh.indices.is_empty() {
+                continue;
+            }
+            let material = submesh.material.as_ref().map(|material| {
+                mesh.materials.push(JsonMaterial::new(material));
+                mesh.materials.len() - 1
+            });
+            mesh.primitives.push(JsonPrimitive {
+                material,
+                indices: submesh.indices,
+            });
+        }
+        mesh
+    }
+}
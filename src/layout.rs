@@ -0,0 +1,310 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! The line by line layout of a loaded .obj file, for writing it back faithfully.
+
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
+
+use crate::obj::{IndexTuple, ObjData, ObjMaterial, Scalar, WriteToFmt, DEFAULT_GROUP, DEFAULT_OBJECT};
+use crate::raw::Statement;
+
+/// The lines of a file loaded with [`LoadConfig::faithful`](crate::LoadConfig::faithful).
+///
+/// While the layout covers all elements of the data, [`ObjData::write_to_fmt`] writes the lines
+/// in their original order instead of the regular output. Statements whose element is unchanged
+/// are written as they were read, the others are formatted from the current data. Adding or
+/// removing elements falls back to the regular output, since their place in the file is unknown.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceLayout {
+    pub lines: Vec<SourceLine>,
+    /// Whether lines end with `\r\n` instead of `\n`, as the first line of the file did.
+    pub crlf: bool,
+}
+
+/// A single line of a [`SourceLayout`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SourceLine {
+    /// A comment, blank line or statement without a counterpart in the data, such as `s`.
+    ///
+    /// This includes the statements of free-form curves and surfaces, which are kept in their
+    /// original order when [`LoadConfig::strict`](crate::LoadConfig::strict) is off.
+    Verbatim(String),
+    /// A statement that produced `element`, with its original `text`.
+    Element { element: SourceElement, text: String },
+}
+
+/// The part of an `ObjData` a statement produced, by index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SourceElement {
+    /// A `v` statement and its entry in `position` and `color`.
+    Position(usize),
+    /// A `vt` statement.
+    Texture(usize),
+    /// A `vn` statement.
+    Normal(usize),
+    /// The `o` statement of an object.
+    Object(usize),
+    /// The `g` statement of a group.
+    Group { object: usize, group: usize },
+    /// The `usemtl` statement setting the material of a group.
+    Material { object: usize, group: usize },
+    /// An `f` statement.
+    Poly { object: usize, group: usize, index: usize },
+    /// An `l` statement.
+    Line { object: usize, group: usize, index: usize },
+    /// A `mtllib` statement.
+    MaterialLib(usize),
+    /// The `shadow_obj` statement.
+    ShadowObj,
+    /// The `trace_obj` statement.
+    TraceObj,
+}
+
+impl SourceLayout {
+    /// Whether every element of `data` has a line, and every line refers to an existing element.
+    pub fn covers<S>(&self, data: &ObjData<S>) -> bool {
+        let mut counts = [0; 4];
+        let mut elements = 0;
+        let mut groups: Vec<Vec<bool>> = data.objects.iter().map(|o| vec![false; o.groups.len()]).collect();
+        let mut named = vec![false; data.objects.len()];
+        let (mut shadow_obj, mut trace_obj) = (false, false);
+        let mut mark = |object: usize, group: usize| match groups.get_mut(object).and_then(|g| g.get_mut(group)) {
+            Some(seen) => {
+                *seen = true;
+                true
+            }
+            None => false,
+        };
+        for line in &self.lines {
+            let element = match line {
+                SourceLine::Element { element, .. } => *element,
+                SourceLine::Verbatim(_) => continue,
+            };
+            let valid = match element {
+                SourceElement::Position(i) => {
+                    counts[0] += 1;
+                    i < data.position.len()
+                }
+                SourceElement::Texture(i) => {
+                    counts[1] += 1;
+                    i < data.texture.len()
+                }
+                SourceElement::Normal(i) => {
+                    counts[2] += 1;
+                    i < data.normal.len()
+                }
+                SourceElement::MaterialLib(i) => {
+                    counts[3] += 1;
+                    i < data.material_libs.len()
+                }
+                SourceElement::Object(object) => named.get_mut(object).map(|named| *named = true).is_some(),
+                SourceElement::Group { object, group } | SourceElement::Material { object, group } => {
+                    mark(object, group)
+                }
+                SourceElement::Poly { object, group, index } => {
+                    elements += 1;
+                    mark(object, group) && index < data.objects[object].groups[group].polys.len()
+                }
+                SourceElement::Line { object, group, index } => {
+                    elements += 1;
+                    mark(object, group) && index < data.objects[object].groups[group].lines.len()
+                }
+                SourceElement::ShadowObj => {
+                    shadow_obj = true;
+                    true
+                }
+                SourceElement::TraceObj => {
+                    trace_obj = true;
+                    true
+                }
+            };
+            if !valid {
+                return false;
+            }
+        }
+        let lens = [
+            data.position.len(),
+            data.texture.len(),
+            data.normal.len(),
+            data.material_libs.len(),
+        ];
+        let total: usize = data
+            .objects
+            .iter()
+            .flat_map(|o| &o.groups)
+            .map(|g| g.polys.len() + g.lines.len())
+            .sum();
+        counts == lens
+            && elements == total
+            && groups.iter().flatten().all(|&seen| seen)
+            && data
+                .objects
+                .iter()
+                .zip(named)
+                .all(|(o, named)| named || !o.groups.is_empty() || &*o.name == DEFAULT_OBJECT)
+            && (shadow_obj || data.shadow_obj.is_none())
+            && (trace_obj || data.trace_obj.is_none())
+    }
+
+    /// Write the lines, assuming that [`SourceLayout::covers`] holds.
+    pub(crate) fn write<S: Scalar>(&self, data: &ObjData<S>, out: &mut impl fmt::Write) -> fmt::Result {
+        // The number of positions, texture coordinates and normals before each line, which
+        // relative indices refer to.
+        let mut lens = [0; 3];
+        let ending = if self.crlf { "\r\n" } else { "\n" };
+        for line in &self.lines {
+            let (element, text) = match line {
+                SourceLine::Verbatim(text) => {
+                    write!(out, "{}{}", text, ending)?;
+                    continue;
+                }
+                SourceLine::Element { element, text } => (*element, text),
+            };
+            match Statement::parse(0, text) {
+                Some(statement) if is_unchanged(element, &statement, data, lens) => write!(out, "{}{}", text, ending)?,
+                _ => {
+                    let mut formatted = String::new();
+                    write_element(element, data, &mut formatted)?;
+                    for line in formatted.lines() {
+                        write!(out, "{}{}", line, ending)?;
+                    }
+                }
+            }
+            match element {
+                SourceElement::Position(_) => lens[0] += 1,
+                SourceElement::Texture(_) => lens[1] += 1,
+                SourceElement::Normal(_) => lens[2] += 1,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the references to objects to their new places, given as `(object, first group)` for
+    /// each old object.
+    pub(crate) fn remap_objects(&mut self, map: &[(usize, usize)]) {
+        for line in &mut self.lines {
+            if let SourceLine::Element { element, .. } = line {
+                match element {
+                    SourceElement::Object(object) => *object = map[*object].0,
+                    SourceElement::Group { object, group }
+                    | SourceElement::Material { object, group }
+                    | SourceElement::Poly { object, group, .. }
+                    | SourceElement::Line { object, group, .. } => {
+                        *group += map[*object].1;
+                        *object = map[*object].0;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Whether `element` still has the value `statement` was read as.
+fn is_unchanged<S: Scalar>(
+    element: SourceElement,
+    statement: &Statement<'_>,
+    data: &ObjData<S>,
+    lens: [usize; 3],
+) -> bool {
+    let mut args = statement.args();
+    match element {
+        SourceElement::Position(i) => {
+            let position = args.vec3::<S>().ok();
+            let color = args.vec3::<f32>().ok();
+            position == Some(data.position[i])
+                && match data.color.get(i) {
+                    Some(c) => color.unwrap_or([1.0; 3]) == *c,
+                    None => color.is_none(),
+                }
+        }
+        SourceElement::Texture(i) => args.vec2().ok() == Some(data.texture[i]),
+        SourceElement::Normal(i) => args.vec3::<f32>().ok() == Some(data.normal[i]),
+        SourceElement::Object(object) => match statement.rest() {
+            "" => &*data.objects[object].name == DEFAULT_OBJECT,
+            name => *data.objects[object].name == *name,
+        },
+        SourceElement::Group { object, group } => match statement.rest() {
+            "" => &*data.objects[object].groups[group].name == DEFAULT_GROUP,
+            name => *data.objects[object].groups[group].name == *name,
+        },
+        SourceElement::Material { object, group } => {
+            match (&data.objects[object].groups[group].material, args.next()) {
+                (Some(material), Some(name)) => material.name() == name,
+                // `usemtl None` only clears the material if the file was loaded that way.
+                (None, None) | (None, Some("None")) => true,
+                _ => false,
+            }
+        }
+        SourceElement::Poly { object, group, index } => {
+            let poly = &data.objects[object].groups[group].polys[index];
+            let read: Option<Vec<IndexTuple>> = args.map(|word| parse_tuple(word, lens)).collect();
+            read.as_deref() == Some(&poly.0[..])
+        }
+        SourceElement::Line { object, group, index } => {
+            let line = &data.objects[object].groups[group].lines[index];
+            let read: Option<Vec<IndexTuple>> = args
+                .map(|word| parse_tuple(word, lens).map(|t| IndexTuple::new(t.position, t.texture, None)))
+                .collect();
+            read.as_deref() == Some(&line.0[..])
+        }
+        SourceElement::MaterialLib(i) => {
+            let filename = &data.material_libs[i].filename;
+            args.eq(filename.split(' ').filter(|word| !word.is_empty()))
+        }
+        SourceElement::ShadowObj => data.shadow_obj.as_deref() == Some(statement.rest()),
+        SourceElement::TraceObj => data.trace_obj.as_deref() == Some(statement.rest()),
+    }
+}
+
+fn parse_tuple(word: &str, lens: [usize; 3]) -> Option<IndexTuple> {
+    crate::obj::parse_index_tuple(0, word, lens).ok()
+}
+
+/// Format the statement of `element` from the current data.
+fn write_element<S: Scalar>(element: SourceElement, data: &ObjData<S>, out: &mut impl fmt::Write) -> fmt::Result {
+    match element {
+        SourceElement::Position(i) => data.write_positions(i..i + 1, out),
+        SourceElement::Texture(i) => data.write_textures(i..i + 1, out),
+        SourceElement::Normal(i) => data.write_normals(i..i + 1, out),
+        SourceElement::Object(object) => writeln!(out, "o {}", data.objects[object].name),
+        SourceElement::Group { object, group } => writeln!(out, "g {}", data.objects[object].groups[group].name),
+        SourceElement::Material { object, group } => match &data.objects[object].groups[group].material {
+            Some(ObjMaterial::Ref(name)) => writeln!(out, "usemtl {}", name),
+            Some(ObjMaterial::Mtl(material)) => writeln!(out, "usemtl {}", material.name),
+            None => writeln!(out, "usemtl"),
+        },
+        SourceElement::Poly { object, group, index } => {
+            data.objects[object].groups[group].polys[index].write_to_fmt(out)
+        }
+        SourceElement::Line { object, group, index } => {
+            data.objects[object].groups[group].lines[index].write_to_fmt(out)
+        }
+        SourceElement::MaterialLib(i) => writeln!(out, "mtllib {}", data.material_libs[i].filename),
+        // A removed file drops the statement.
+        SourceElement::ShadowObj => match &data.shadow_obj {
+            Some(file) => writeln!(out, "shadow_obj {}", file),
+            None => Ok(()),
+        },
+        SourceElement::TraceObj => match &data.trace_obj {
+            Some(file) => writeln!(out, "trace_obj {}", file),
+            None => Ok(()),
+        },
+    }
+}
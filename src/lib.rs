@@ -12,10 +12,263 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
-pub use self::mtl::{Material, Mtl, MtlError, MtlMissingType};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "bevy")]
+pub use self::bevy_support::{ObjAsset, ObjAssetError, ObjAssetLoader, ObjPlugin};
+#[cfg(feature = "std")]
+pub use self::borrowed::{GroupRef, ObjRef, ObjectRef};
+#[cfg(feature = "std")]
+pub use self::bounds::{BoundingBox, BoundingSphere, UvBounds};
+#[cfg(feature = "std")]
+pub use self::buffers::{IndexedBuffers, Position, PositionTexture, PositionTextureNormal, Submesh, Vertex};
+#[cfg(feature = "std")]
+pub use self::builder::{ObjBuilder, VertexHandle};
+#[cfg(feature = "std")]
+pub use self::cache::CacheError;
+#[cfg(feature = "std")]
+pub use self::channels::{AttributeChannel, AttributeChannels};
+#[cfg(feature = "std")]
+pub use self::cleanup::{DegenerateReport, DuplicateReport, RemovedFaces};
+#[cfg(feature = "std")]
+pub use self::compact::{AttributeRemap, RemapReport};
+#[cfg(feature = "std")]
+pub use self::dependencies::{AmbiguousAsset, AssetReport, Dependency, DependencyKind};
+#[cfg(feature = "std")]
+pub use self::diff::{diff, AttributeDiff, GroupId, MaterialChange, ObjDiff};
+#[cfg(feature = "std")]
+pub use self::half_edge::{HalfEdge, HalfEdgeMesh};
+#[cfg(feature = "std")]
+pub use self::hierarchy::GroupNode;
+#[cfg(feature = "std")]
+pub use self::ids::{PolyId, StableIds, VertexId};
+#[cfg(feature = "image")]
+pub use self::image_support::LoadedTexture;
+#[cfg(feature = "std")]
+pub use self::instancing::InstanceGroup;
+#[cfg(feature = "serde")]
+pub use self::json_mesh::{JsonMaterial, JsonMesh, JsonPrimitive};
+pub use self::layout::{SourceElement, SourceLayout, SourceLine};
+#[cfg(feature = "std")]
+pub use self::lookup::{NameIndex, UnresolvedMaterial};
+#[cfg(feature = "std")]
+pub use self::manifold::{EdgeIssue, ManifoldReport, WindingReport};
+#[cfg(feature = "std")]
+pub use self::material_dedup::MaterialMerge;
+#[cfg(feature = "std")]
+pub use self::material_usage::{MaterialUsage, MaterialUse, UnusedMaterial};
+#[cfg(feature = "std")]
+pub use self::memory::MemoryUsage;
+#[cfg(feature = "std")]
+pub use self::merge::NameCollision;
+pub use self::mtl::{IllumModel, Material, Mtl, MtlError, MtlMissingType, TextureSlot};
+#[cfg(feature = "std")]
+pub use self::normals::{InvertedNormals, NormalRepairConfig, NormalRepairReport};
 pub use self::obj::{
-    Group, IndexTuple, LoadConfig, MtlLibsLoadError, Obj, ObjData, ObjError, ObjMaterial, Object, SimplePolygon,
+    Axis, DuplicateObjectPolicy, Group, ImplicitObjectName, IndexTuple, Line, LoadConfig, LoadWarning, ObjData,
+    ObjData64, ObjError, ObjMaterial, ObjMetadata, Object, OutOfRangePolicy, RenderState, Scalar, SimplePolygon,
+    StateChange, StatementHook, TracedStatement, TracedValue, WriteToFmt,
 };
+#[cfg(feature = "genmesh")]
+pub use self::obj::{GenmeshPolygon, GenmeshPrimitive};
+#[cfg(feature = "std")]
+pub use self::obj::{MtlLibsLoadError, Obj, WriteConfig, WriteToBuf};
+#[cfg(feature = "std")]
+pub use self::packed::{CompactPolygon, PackedIndexTuple, PackedPolygons};
+#[cfg(feature = "std")]
+pub use self::pbr::{ColorSpace, PbrMaterial};
+#[cfg(feature = "std")]
+pub use self::pipeline::Pipeline;
+#[cfg(feature = "std")]
+pub use self::polygon::PolygonView;
+pub use self::push_parser::PushParser;
+#[cfg(feature = "std")]
+pub use self::quads::{ConversionReport, GroupConversion};
+#[cfg(feature = "std")]
+pub use self::ranges::AttributeRanges;
+#[cfg(feature = "std")]
+pub use self::resolve::VertexData;
+#[cfg(feature = "std")]
+pub use self::rust_source::RustSourceConfig;
+#[cfg(feature = "std")]
+pub use self::sampling::{SamplingMode, SurfaceSample};
+#[cfg(feature = "std")]
+pub use self::sanitize::{Preset, SanitizeReport};
+#[cfg(feature = "std")]
+pub use self::slice::Contour;
+#[cfg(feature = "std")]
+pub use self::spatial::{ClosestPoint, SpatialIndex};
+#[cfg(feature = "std")]
+pub use self::split::{SplitBudget, SplitBy, SplitMtl};
+#[cfg(feature = "std")]
+pub use self::stats::{scan, MeshStats, ScanReport};
+#[cfg(feature = "std")]
+pub use self::subdivide::SubdivisionScheme;
+#[cfg(feature = "mikktspace")]
+pub use self::tangent::generate_mikktspace_tangents;
+#[cfg(feature = "std")]
+pub use self::tangent::generate_tangents;
+#[cfg(feature = "std")]
+pub use self::transform::{CoordinateConvention, Handedness, NormalizeMode};
+#[cfg(feature = "std")]
+pub use self::triangles::ResolvedTriangle;
+#[cfg(feature = "std")]
+pub use self::uv::{AtlasReport, Projection};
+#[cfg(feature = "std")]
+pub use self::vertex_cache::{average_cache_miss_ratio, optimize_vertex_cache};
+#[cfg(feature = "std")]
+pub use self::weld::{WeldConfig, WeldReport};
 
+#[cfg(feature = "std")]
+mod approx;
+#[cfg(feature = "std")]
+mod bake;
+#[cfg(feature = "bevy")]
+mod bevy_support;
+#[cfg(feature = "std")]
+mod borrowed;
+#[cfg(feature = "std")]
+mod bounds;
+#[cfg(feature = "std")]
+mod buffers;
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "bvh")]
+pub mod bvh;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
+mod channels;
+#[cfg(feature = "std")]
+mod cleanup;
+#[cfg(feature = "collada")]
+mod collada_support;
+#[cfg(feature = "std")]
+mod compact;
+#[cfg(feature = "std")]
+mod dependencies;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "std")]
+mod edges;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "glam")]
+mod glam_support;
+#[cfg(feature = "gltf")]
+mod gltf_support;
+#[cfg(feature = "std")]
+mod half_edge;
+#[cfg(feature = "std")]
+mod hash;
+#[cfg(feature = "std")]
+mod hierarchy;
+#[cfg(feature = "std")]
+mod hull;
+#[cfg(feature = "std")]
+mod ids;
+#[cfg(feature = "image")]
+mod image_support;
+#[cfg(feature = "std")]
+mod instancing;
+#[cfg(feature = "std")]
+mod instrument;
+#[cfg(feature = "serde")]
+mod json_mesh;
+mod layout;
+#[cfg(feature = "std")]
+mod lookup;
+#[cfg(feature = "std")]
+mod manifold;
+#[cfg(feature = "std")]
+mod material_dedup;
+#[cfg(feature = "std")]
+mod material_usage;
+#[cfg(feature = "std")]
+mod math;
+#[cfg(feature = "std")]
+mod measure;
+#[cfg(feature = "std")]
+mod memory;
+#[cfg(feature = "std")]
+mod merge;
+#[cfg(feature = "mint")]
+mod mint_support;
 mod mtl;
+#[cfg(feature = "nalgebra")]
+mod nalgebra_support;
+#[cfg(feature = "std")]
+mod normals;
 mod obj;
+#[cfg(feature = "std")]
+pub mod off;
+#[cfg(feature = "std")]
+mod packed;
+#[cfg(feature = "std")]
+mod parallel_write;
+#[cfg(feature = "std")]
+mod pbr;
+#[cfg(feature = "std")]
+mod pipeline;
+#[cfg(feature = "std")]
+pub mod ply;
+#[cfg(feature = "std")]
+mod polygon;
+#[cfg(feature = "std")]
+mod polyline;
+mod push_parser;
+#[cfg(feature = "std")]
+mod quads;
+#[cfg(feature = "std")]
+mod ranges;
+pub mod raw;
+#[cfg(feature = "rayon")]
+mod rayon_support;
+#[cfg(feature = "std")]
+mod reorder;
+#[cfg(feature = "std")]
+mod resolve;
+#[cfg(feature = "std")]
+mod rust_source;
+#[cfg(feature = "std")]
+mod sampling;
+#[cfg(feature = "std")]
+mod sanitize;
+#[cfg(feature = "std")]
+pub mod sequence;
+#[cfg(feature = "std")]
+mod simplify;
+#[cfg(feature = "std")]
+mod slice;
+#[cfg(feature = "std")]
+mod smoothing;
+#[cfg(feature = "std")]
+mod spatial;
+#[cfg(feature = "std")]
+mod split;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+pub mod stl;
+#[cfg(feature = "std")]
+mod subdivide;
+#[cfg(feature = "std")]
+mod tangent;
+#[cfg(feature = "tobj")]
+mod tobj_support;
+#[cfg(feature = "std")]
+mod transform;
+#[cfg(feature = "std")]
+mod triangles;
+#[cfg(feature = "std")]
+mod uv;
+#[cfg(feature = "std")]
+mod vertex_cache;
+#[cfg(feature = "std")]
+mod vertex_layout;
+#[cfg(feature = "std")]
+mod weld;
+#[cfg(feature = "wgpu")]
+mod wgpu_support;
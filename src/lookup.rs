@@ -0,0 +1,274 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Lookup and renaming of objects, groups and materials by name.
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::mtl::{Material, Mtl};
+use crate::obj::{Group, Obj, ObjData, ObjMaterial, Object};
+
+/// A `usemtl` reference that none of the material libraries defines.
+///
+/// Returned by [`ObjData::resolve_materials`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnresolvedMaterial {
+    /// The referenced material name.
+    pub material: Arc<str>,
+    /// The name of the object containing the referencing group.
+    pub object: Arc<str>,
+    /// The name of the referencing group.
+    pub group: Arc<str>,
+}
+
+impl ObjData {
+    /// The first object with the given name.
+    ///
+    /// This scans the objects, use [`ObjData::name_index`] for repeated lookups.
+    pub fn object(&self, name: &str) -> Option<&Object> {
+        self.objects.iter().find(|o| &*o.name == name)
+    }
+
+    pub fn object_mut(&mut self, name: &str) -> Option<&mut Object> {
+        self.objects.iter_mut().find(|o| &*o.name == name)
+    }
+
+    /// The first material with the given name in the loaded material libraries.
+    pub fn material(&self, name: &str) -> Option<&Arc<Material>> {
+        self.material_libs.iter().find_map(|mtl| mtl.material(name))
+    }
+
+    /// Rename every object named `old` to `new`, returning the number of renamed objects.
+    pub fn rename_object(&mut self, old: &str, new: &str) -> usize {
+        rename_all(self.objects.iter_mut().map(|o| &mut o.name), old, new)
+    }
+
+    /// Rename the material `old` to `new` in the material libraries and in every group using it.
+    ///
+    /// Groups referring to a loaded material get the renamed material, and groups that shared a
+    /// material before keep sharing it. Returns whether any material or reference was renamed.
+    pub fn rename_material(&mut self, old: &str, new: &str) -> bool {
+        // Renamed materials by their previous instance.
+        let mut renamed: Vec<(Arc<Material>, Arc<Material>)> = Vec::new();
+        let mut rename = |material: &mut Arc<Material>| {
+            let replacement = match renamed.iter().find(|(prev, _)| Arc::ptr_eq(prev, material)) {
+                Some((_, replacement)) => replacement.clone(),
+                None => {
+                    let mut replacement = Material::clone(material);
+                    replacement.name = new.to_string();
+                    let replacement = Arc::new(replacement);
+                    renamed.push((material.clone(), replacement.clone()));
+                    replacement
+                }
+            };
+            *material = replacement;
+        };
+
+        let mut found = false;
+        for mtl in &mut self.material_libs {
+            for material in mtl.materials.iter_mut().filter(|m| m.name == old) {
+                rename(material);
+                found = true;
+            }
+        }
+        let groups = self.objects.iter_mut().flat_map(|o| o.groups.iter_mut());
+        for material in groups.filter_map(|g| g.material.as_mut()).filter(|m| m.name() == old) {
+            match material {
+                ObjMaterial::Ref(name) => *name = new.into(),
+                ObjMaterial::Mtl(material) => rename(material),
+            }
+            found = true;
+        }
+        found
+    }
+
+    /// Bind every material reference to the first material with its name in the material
+    /// libraries, returning the references no library defines.
+    ///
+    /// Unlike [`Obj::load_mtls`], this doesn't read any files, so the libraries have to be loaded
+    /// already. Groups holding a material that is not in any library keep it and are not reported.
+    pub fn resolve_materials(&mut self) -> Vec<UnresolvedMaterial> {
+        let mut materials = HashMap::new();
+        for material in self.material_libs.iter().flat_map(|mtl| &mtl.materials) {
+            materials
+                .entry(material.name.as_str())
+                .or_insert_with(|| material.clone());
+        }
+
+        let mut unresolved = Vec::new();
+        for object in &mut self.objects {
+            for group in &mut object.groups {
+                let material = match &mut group.material {
+                    Some(material) => material,
+                    None => continue,
+                };
+                match (materials.get(material.name()), &*material) {
+                    (Some(loaded), _) => *material = ObjMaterial::Mtl(loaded.clone()),
+                    (None, ObjMaterial::Ref(name)) => unresolved.push(UnresolvedMaterial {
+                        material: name.clone(),
+                        object: object.name.clone(),
+                        group: group.name.clone(),
+                    }),
+                    (None, ObjMaterial::Mtl(_)) => {}
+                }
+            }
+        }
+        unresolved
+    }
+
+    /// Build an index for looking up names without scanning.
+    pub fn name_index(&self) -> NameIndex<'_> {
+        NameIndex {
+            data: self,
+            objects: OnceCell::new(),
+            groups: OnceCell::new(),
+            materials: OnceCell::new(),
+        }
+    }
+}
+
+impl Object {
+    /// The first group with the given name.
+    ///
+    /// A group continued with a different material has further entries with the same name, see
+    /// [`Object::groups_named`].
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.groups.iter().find(|g| &*g.name == name)
+    }
+
+    pub fn group_mut(&mut self, name: &str) -> Option<&mut Group> {
+        self.groups.iter_mut().find(|g| &*g.name == name)
+    }
+
+    /// Rename every group named `old` to `new`, returning the number of renamed groups.
+    ///
+    /// This includes the further entries of groups continued with a different material.
+    pub fn rename_group(&mut self, old: &str, new: &str) -> usize {
+        rename_all(self.groups.iter_mut().map(|g| &mut g.name), old, new)
+    }
+
+    /// All groups with the given name.
+    pub fn groups_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Group> + 'a {
+        self.groups.iter().filter(move |g| &*g.name == name)
+    }
+}
+
+impl Group {
+    /// The material of this group, borrowed from the group itself if it has been bound, or else
+    /// looked up by name in the material libraries of `data`.
+    ///
+    /// Groups without a material get the [`ObjData::default_material`] of `data`, if any. Unlike
+    /// cloning the `Arc` of [`ObjMaterial::Mtl`], this doesn't touch any reference counts, which is
+    /// cheaper in hot loops.
+    pub fn material_ref<'a>(&'a self, data: &'a ObjData) -> Option<&'a Material> {
+        let material = match &self.material {
+            Some(material) => material,
+            None => return data.default_material.as_deref(),
+        };
+        match material.as_material() {
+            Some(material) => Some(material),
+            None => data.material(material.name()).map(|m| &**m),
+        }
+    }
+}
+
+impl Mtl {
+    /// The first material with the given name.
+    pub fn material(&self, name: &str) -> Option<&Arc<Material>> {
+        self.materials.iter().find(|m| m.name == name)
+    }
+}
+
+impl Obj {
+    /// The first material with the given name in the loaded material libraries.
+    pub fn material(&self, name: &str) -> Option<&Arc<Material>> {
+        self.data.material(name)
+    }
+
+    /// Bind all material references, see [`ObjData::resolve_materials`].
+    pub fn resolve_materials(&mut self) -> Vec<UnresolvedMaterial> {
+        self.data.resolve_materials()
+    }
+
+    /// Rename a material and all references to it, see [`ObjData::rename_material`].
+    pub fn rename_material(&mut self, old: &str, new: &str) -> bool {
+        self.data.rename_material(old, new)
+    }
+}
+
+fn rename_all<'a>(names: impl Iterator<Item = &'a mut Arc<str>>, old: &str, new: &str) -> usize {
+    let new: Arc<str> = new.into();
+    let mut count = 0;
+    for name in names.filter(|name| &***name == old) {
+        *name = new.clone();
+        count += 1;
+    }
+    count
+}
+
+/// Maps names to the objects, groups and materials of an [`ObjData`].
+///
+/// Each map is built on its first use. Lookups return the first entry with a name, like
+/// [`ObjData::object`], [`Object::group`] and [`ObjData::material`].
+pub struct NameIndex<'a> {
+    data: &'a ObjData,
+    objects: OnceCell<HashMap<&'a str, usize>>,
+    groups: OnceCell<HashMap<(usize, &'a str), &'a Group>>,
+    materials: OnceCell<HashMap<&'a str, &'a Arc<Material>>>,
+}
+
+impl<'a> NameIndex<'a> {
+    fn object_index(&self, name: &str) -> Option<usize> {
+        let objects = self.objects.get_or_init(|| {
+            let mut map = HashMap::new();
+            for (i, object) in self.data.objects.iter().enumerate() {
+                map.entry(&*object.name).or_insert(i);
+            }
+            map
+        });
+        objects.get(name).copied()
+    }
+
+    pub fn object(&self, name: &str) -> Option<&'a Object> {
+        self.object_index(name).map(|i| &self.data.objects[i])
+    }
+
+    /// The first group named `group` in the first object named `object`.
+    pub fn group(&self, object: &str, group: &str) -> Option<&'a Group> {
+        let object = self.object_index(object)?;
+        let groups = self.groups.get_or_init(|| {
+            let mut map = HashMap::new();
+            for (i, object) in self.data.objects.iter().enumerate() {
+                for group in &object.groups {
+                    map.entry((i, &*group.name)).or_insert(group);
+                }
+            }
+            map
+        });
+        groups.get(&(object, group)).copied()
+    }
+
+    pub fn material(&self, name: &str) -> Option<&'a Arc<Material>> {
+        let materials = self.materials.get_or_init(|| {
+            let mut map = HashMap::new();
+            for material in self.data.material_libs.iter().flat_map(|mtl| &mtl.materials) {
+                map.entry(material.name.as_str()).or_insert(material);
+            }
+            map
+        });
+        materials.get(name).copied()
+    }
+}
@@ -0,0 +1,183 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Topological checks for closed, consistently oriented meshes.
+
+use std::collections::HashMap;
+
+use crate::obj::ObjData;
+
+/// An edge between two vertex positions and the polygons using it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeIssue {
+    /// The position indices of the edge endpoints, smallest first.
+    pub edge: [usize; 2],
+    /// The polygons containing the edge.
+    ///
+    /// Polygons are numbered consecutively across all groups of all objects, in order.
+    pub polygons: Vec<usize>,
+}
+
+/// The result of [`ObjData::make_winding_consistent`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindingReport {
+    /// The number of polygons whose winding was reversed.
+    pub flipped: usize,
+    /// The number of groups of polygons connected by manifold edges.
+    pub components: usize,
+    /// Components that can't be oriented consistently, such as a Möbius strip, which are left as
+    /// they were.
+    ///
+    /// Polygons are numbered like in [`EdgeIssue::polygons`], and each list is sorted.
+    pub ambiguous: Vec<Vec<usize>>,
+}
+
+/// The result of [`ObjData::check_manifold`].
+///
+/// Each list is sorted by edge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManifoldReport {
+    /// Edges used by a single polygon.
+    pub boundary_edges: Vec<EdgeIssue>,
+    /// Edges shared by more than two polygons.
+    pub non_manifold_edges: Vec<EdgeIssue>,
+    /// Edges shared by two polygons traversing it in the same direction, meaning that one of them
+    /// is flipped relative to the other.
+    pub inconsistent_winding: Vec<EdgeIssue>,
+}
+
+impl ManifoldReport {
+    /// Check whether every edge is shared by at most two consistently oriented polygons.
+    pub fn is_manifold(&self) -> bool {
+        self.non_manifold_edges.is_empty() && self.inconsistent_winding.is_empty()
+    }
+
+    /// Check whether the mesh is manifold and has no boundary, i.e. encloses a volume.
+    pub fn is_watertight(&self) -> bool {
+        self.is_manifold() && self.boundary_edges.is_empty()
+    }
+}
+
+impl ObjData {
+    /// Analyze how polygons are connected along their edges.
+    ///
+    /// Connectivity is determined by position indices only, so vertices that merely coincide are
+    /// considered distinct; see [`ObjData::weld_vertices`]. Edges joining a position to itself
+    /// are ignored.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn check_manifold(&self) -> ManifoldReport {
+        let mut report = ManifoldReport::default();
+        for (edge, uses) in self.edge_uses() {
+            let issue = EdgeIssue {
+                edge,
+                polygons: uses.iter().map(|&(i, _)| i).collect(),
+            };
+            match uses.len() {
+                1 => report.boundary_edges.push(issue),
+                2 if uses[0].1 == uses[1].1 => report.inconsistent_winding.push(issue),
+                2 => {}
+                _ => report.non_manifold_edges.push(issue),
+            }
+        }
+        report.boundary_edges.sort_by_key(|issue| issue.edge);
+        report.non_manifold_edges.sort_by_key(|issue| issue.edge);
+        report.inconsistent_winding.sort_by_key(|issue| issue.edge);
+        report
+    }
+
+    /// Reverse polygons so that neighbors traverse their shared edges in opposite directions.
+    ///
+    /// Orientation is propagated across edges shared by exactly two polygons, connected like in
+    /// [`ObjData::check_manifold`]. Within each connected component the orientation most polygons
+    /// already have is kept, so only the minority is flipped. Like [`ObjData::flip_winding`],
+    /// flipping keeps the first vertex of a polygon in place. Which side is outside is not
+    /// determined, use [`ObjData::flip_winding`] on the result if needed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn make_winding_consistent(&mut self) -> WindingReport {
+        // Polygons sharing an edge, and whether they traverse it in the same direction.
+        let mut neighbors: Vec<Vec<(usize, bool)>> = vec![Vec::new(); self.polys().count()];
+        for uses in self.edge_uses().into_values() {
+            if let [(i, di), (j, dj)] = uses[..] {
+                neighbors[i].push((j, di == dj));
+                neighbors[j].push((i, di == dj));
+            }
+        }
+
+        let mut report = WindingReport::default();
+        let mut flip: Vec<Option<bool>> = vec![None; neighbors.len()];
+        let mut to_flip = vec![false; neighbors.len()];
+        for seed in 0..neighbors.len() {
+            if flip[seed].is_some() {
+                continue;
+            }
+            report.components += 1;
+            flip[seed] = Some(false);
+            let mut component = vec![seed];
+            let mut stack = vec![seed];
+            let mut consistent = true;
+            while let Some(i) = stack.pop() {
+                let fi = flip[i].unwrap();
+                for &(j, same_direction) in &neighbors[i] {
+                    // Traversing the edge the same way means that exactly one of them is flipped.
+                    let fj = fi ^ same_direction;
+                    match flip[j] {
+                        Some(existing) => consistent &= existing == fj,
+                        None => {
+                            flip[j] = Some(fj);
+                            component.push(j);
+                            stack.push(j);
+                        }
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            if !consistent {
+                report.ambiguous.push(component);
+                continue;
+            }
+            let flipped = component.iter().filter(|&&i| flip[i] == Some(true)).count();
+            let invert = 2 * flipped > component.len();
+            for &i in &component {
+                to_flip[i] = flip[i] != Some(invert);
+            }
+            report.flipped += flipped.min(component.len() - flipped);
+        }
+
+        for (poly, flip) in self.polys_mut().zip(to_flip) {
+            if flip {
+                if let Some((_, rest)) = poly.0.split_first_mut() {
+                    rest.reverse();
+                }
+            }
+        }
+        report
+    }
+
+    /// For every undirected edge, the polygons using it and whether they traverse it from the
+    /// smaller to the larger index.
+    fn edge_uses(&self) -> HashMap<[usize; 2], Vec<(usize, bool)>> {
+        let mut edges: HashMap<[usize; 2], Vec<(usize, bool)>> = HashMap::new();
+        for (i, poly) in self.polys().enumerate() {
+            let n = poly.0.len();
+            for k in 0..n {
+                let (a, b) = (poly.0[k].position, poly.0[(k + 1) % n].position);
+                if a != b {
+                    edges.entry([a.min(b), a.max(b)]).or_default().push((i, a < b));
+                }
+            }
+        }
+        edges
+    }
+}
@@ -0,0 +1,124 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Merging of equivalent materials across material libraries.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::mtl::Material;
+use crate::obj::{Obj, ObjMaterial};
+
+/// A material removed by [`Obj::dedup_materials`] in favor of an equivalent one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterialMerge {
+    /// The file name of the library the material was removed from.
+    pub library: String,
+    /// The name of the removed material.
+    pub name: String,
+    /// The name of the material that replaces it.
+    pub merged_into: String,
+}
+
+fn close(a: Option<f32>, b: Option<f32>, tolerance: f32) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() <= tolerance,
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+fn close3(a: Option<[f32; 3]>, b: Option<[f32; 3]>, tolerance: f32) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.iter().zip(&b).all(|(a, b)| (a - b).abs() <= tolerance),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+/// Whether the materials only differ in their names and by at most `tolerance` in each number.
+fn equivalent(a: &Material, b: &Material, tolerance: f32) -> bool {
+    close3(a.ka, b.ka, tolerance)
+        && close3(a.kd, b.kd, tolerance)
+        && close3(a.ks, b.ks, tolerance)
+        && close3(a.ke, b.ke, tolerance)
+        && close3(a.tf, b.tf, tolerance)
+        && close(a.km, b.km, tolerance)
+        && close(a.ns, b.ns, tolerance)
+        && close(a.ni, b.ni, tolerance)
+        && close(a.tr, b.tr, tolerance)
+        && close(a.d, b.d, tolerance)
+        && a.illum == b.illum
+        && a.map_ka == b.map_ka
+        && a.map_kd == b.map_kd
+        && a.map_ks == b.map_ks
+        && a.map_ke == b.map_ke
+        && a.map_ns == b.map_ns
+        && a.map_d == b.map_d
+        && a.map_bump == b.map_bump
+        && a.map_refl == b.map_refl
+        && a.map_disp == b.map_disp
+        && a.map_aat == b.map_aat
+}
+
+impl Obj {
+    /// Remove materials that are equivalent to an earlier one in the material libraries and let
+    /// the groups using them refer to the earlier material instead.
+    ///
+    /// Materials are equivalent if all their properties are equal, with numbers differing by at
+    /// most `tolerance`. Only the first material of each name is merged into others, as later ones
+    /// of the same name are never used. Returns the removed materials in library order.
+    pub fn dedup_materials(&mut self, tolerance: f32) -> Vec<MaterialMerge> {
+        // The first material of each name, which is the one references resolve to.
+        let mut kept: Vec<Arc<Material>> = Vec::new();
+        let mut names: HashSet<String> = HashSet::new();
+        let mut replacements: HashMap<String, Arc<Material>> = HashMap::new();
+        let mut merges = Vec::new();
+
+        for mtl in &mut self.data.material_libs {
+            let library = &mtl.filename;
+            mtl.materials.retain(|material| {
+                let is_first = names.insert(material.name.clone());
+                match kept.iter().find(|k| equivalent(k, material, tolerance)) {
+                    Some(existing) => {
+                        if is_first {
+                            replacements.insert(material.name.clone(), existing.clone());
+                        }
+                        merges.push(MaterialMerge {
+                            library: library.clone(),
+                            name: material.name.clone(),
+                            merged_into: existing.name.clone(),
+                        });
+                        false
+                    }
+                    None => {
+                        if is_first {
+                            kept.push(material.clone());
+                        }
+                        true
+                    }
+                }
+            });
+        }
+
+        let groups = self.data.objects.iter_mut().flat_map(|o| o.groups.iter_mut());
+        for material in groups.filter_map(|g| g.material.as_mut()) {
+            if let Some(replacement) = replacements.get(material.name()) {
+                *material = match material {
+                    ObjMaterial::Ref(_) => ObjMaterial::Ref(replacement.name.as_str().into()),
+                    ObjMaterial::Mtl(_) => ObjMaterial::Mtl(replacement.clone()),
+                };
+            }
+        }
+        merges
+    }
+}
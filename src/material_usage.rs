@@ -0,0 +1,112 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Reporting which materials are used, and by which groups.
+
+use std::collections::HashSet;
+
+use crate::obj::{Obj, ObjData};
+
+/// The groups using one material, see [`ObjData::material_usage`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterialUse {
+    /// The material name.
+    pub name: String,
+    /// Whether one of the material libraries defines the material.
+    pub defined: bool,
+    /// The groups using the material, as indices of the object and of the group within it.
+    pub groups: Vec<(usize, usize)>,
+    /// The number of polygons of those groups.
+    pub polygons: usize,
+    /// The number of lines of those groups.
+    pub lines: usize,
+}
+
+/// A material that no group uses, see [`ObjData::material_usage`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnusedMaterial {
+    /// The file name of the library defining the material.
+    pub library: String,
+    /// The name of the material.
+    pub name: String,
+}
+
+/// The materials used by the groups of an `ObjData`, and those that are never used.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MaterialUsage {
+    /// The used materials, in order of first use.
+    pub used: Vec<MaterialUse>,
+    /// The materials of the material libraries that no group uses, in library order.
+    ///
+    /// This includes later definitions of a name defined by an earlier material, since
+    /// references always resolve to the first one.
+    pub unused: Vec<UnusedMaterial>,
+}
+
+impl ObjData {
+    /// Report which groups use each material, and which materials of the material libraries are
+    /// not used at all.
+    ///
+    /// Materials are told apart by name, whether or not the references have been resolved.
+    pub fn material_usage(&self) -> MaterialUsage {
+        let mut used: Vec<MaterialUse> = Vec::new();
+        for (o, object) in self.objects.iter().enumerate() {
+            for (g, group) in object.groups.iter().enumerate() {
+                let name = match &group.material {
+                    Some(material) => material.name(),
+                    None => continue,
+                };
+                let index = match used.iter().position(|u| u.name == name) {
+                    Some(index) => index,
+                    None => {
+                        used.push(MaterialUse {
+                            name: name.to_string(),
+                            defined: self.material(name).is_some(),
+                            groups: Vec::new(),
+                            polygons: 0,
+                            lines: 0,
+                        });
+                        used.len() - 1
+                    }
+                };
+                let usage = &mut used[index];
+                usage.groups.push((o, g));
+                usage.polygons += group.polys.len();
+                usage.lines += group.lines.len();
+            }
+        }
+
+        let mut defined = HashSet::new();
+        let mut unused = Vec::new();
+        for mtl in &self.material_libs {
+            for material in &mtl.materials {
+                let first = defined.insert(material.name.as_str());
+                if !first || used.iter().all(|u| u.name != material.name) {
+                    unused.push(UnusedMaterial {
+                        library: mtl.filename.clone(),
+                        name: material.name.clone(),
+                    });
+                }
+            }
+        }
+        MaterialUsage { used, unused }
+    }
+}
+
+impl Obj {
+    /// Report which materials are used, see [`ObjData::material_usage`].
+    pub fn material_usage(&self) -> MaterialUsage {
+        self.data.material_usage()
+    }
+}
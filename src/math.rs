@@ -0,0 +1,157 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Small vector helpers shared by the geometry processing passes.
+
+pub(crate) type Vec3 = [f32; 3];
+
+#[inline]
+pub(crate) fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+#[inline]
+pub(crate) fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+#[inline]
+pub(crate) fn scale(a: Vec3, s: f32) -> Vec3 {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+#[inline]
+pub(crate) fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline]
+pub(crate) fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[inline]
+pub(crate) fn length(a: Vec3) -> f32 {
+    dot(a, a).sqrt()
+}
+
+/// Normalize the given vector, returning `None` if it is too short to have a direction.
+#[inline]
+pub(crate) fn normalize(a: Vec3) -> Option<Vec3> {
+    let len = length(a);
+    if len > f32::EPSILON && len.is_finite() {
+        Some(scale(a, 1.0 / len))
+    } else {
+        None
+    }
+}
+
+/// The angle between two vectors in radians.
+#[inline]
+pub(crate) fn angle_between(a: Vec3, b: Vec3) -> f32 {
+    match (normalize(a), normalize(b)) {
+        (Some(a), Some(b)) => dot(a, b).clamp(-1.0, 1.0).acos(),
+        _ => 0.0,
+    }
+}
+
+/// An arbitrary unit vector perpendicular to the given unit vector.
+pub(crate) fn any_perpendicular(n: Vec3) -> Vec3 {
+    let axis = if n[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    normalize(cross(n, axis)).unwrap_or([1.0, 0.0, 0.0])
+}
+
+pub(crate) type Mat3 = [[f32; 3]; 3];
+
+/// Multiply a column-major 3x3 matrix with a vector.
+#[inline]
+pub(crate) fn mat3_mul_vec(m: &Mat3, v: Vec3) -> Vec3 {
+    add(add(scale(m[0], v[0]), scale(m[1], v[1])), scale(m[2], v[2]))
+}
+
+#[inline]
+pub(crate) fn mat3_determinant(m: &Mat3) -> f32 {
+    dot(m[0], cross(m[1], m[2]))
+}
+
+/// The inverse transpose of a column-major 3x3 matrix, or `None` if the matrix is singular.
+pub(crate) fn mat3_inverse_transpose(m: &Mat3) -> Option<Mat3> {
+    let det = mat3_determinant(m);
+    if det.abs() <= f32::EPSILON * f32::EPSILON || !det.is_finite() {
+        return None;
+    }
+    // The columns of the inverse transpose are the cofactors of the original columns.
+    let inv = 1.0 / det;
+    Some([
+        scale(cross(m[1], m[2]), inv),
+        scale(cross(m[2], m[0]), inv),
+        scale(cross(m[0], m[1]), inv),
+    ])
+}
+
+/// The unnormalized normal of a planar polygon computed with Newell's method.
+///
+/// Its length is twice the area of the polygon, and it points along the normal implied by a
+/// counter-clockwise winding.
+pub(crate) fn newell_normal(points: &[Vec3]) -> Vec3 {
+    let mut n = [0.0; 3];
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        n[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        n[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        n[2] += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+    n
+}
+
+/// The point of the triangle closest to `p`, following Ericson's Real-Time Collision Detection.
+pub(crate) fn closest_point_on_triangle(p: Vec3, [a, b, c]: [Vec3; 3]) -> Vec3 {
+    let (ab, ac, ap) = (sub(b, a), sub(c, a), sub(p, a));
+    let (d1, d2) = (dot(ab, ap), dot(ac, ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = sub(p, b);
+    let (d3, d4) = (dot(ab, bp), dot(ac, bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return add(a, scale(ab, d1 / (d1 - d3)));
+    }
+    let cp = sub(p, c);
+    let (d5, d6) = (dot(ab, cp), dot(ac, cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return add(a, scale(ac, d2 / (d2 - d6)));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return add(b, scale(sub(c, b), (d4 - d3) / ((d4 - d3) + (d5 - d6))));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    add(a, add(scale(ab, vb * denom), scale(ac, vc * denom)))
+}
@@ -0,0 +1,84 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Surface area and enclosed volume of meshes and objects.
+
+use crate::buffers::fan;
+use crate::math::{cross, dot, length, newell_normal};
+use crate::obj::{IndexTuple, ObjData, Object, SimplePolygon};
+
+/// The area of the polygon, or zero if it refers to a missing position.
+fn polygon_area(poly: &SimplePolygon, position: &[[f32; 3]]) -> f64 {
+    let points: Option<Vec<_>> = poly.0.iter().map(|t| position.get(t.position).copied()).collect();
+    points.map_or(0.0, |points| 0.5 * f64::from(length(newell_normal(&points))))
+}
+
+/// The signed volume of the cone joining the origin to the polygon, summed over a fan
+/// triangulation, or zero if it refers to a missing position.
+fn polygon_volume(poly: &SimplePolygon, position: &[[f32; 3]]) -> f64 {
+    if poly.0.iter().any(|t| t.position >= position.len()) {
+        return 0.0;
+    }
+    fan(poly)
+        .map(
+            |[IndexTuple { position: a, .. }, IndexTuple { position: b, .. }, IndexTuple { position: c, .. }]| {
+                f64::from(dot(position[a], cross(position[b], position[c]))) / 6.0
+            },
+        )
+        .sum()
+}
+
+impl ObjData {
+    /// The total area of all polygons.
+    ///
+    /// Polygons referring to a missing position, which [`ObjData::validate_indices`] reports,
+    /// are left out.
+    pub fn surface_area(&self) -> f32 {
+        self.polys().map(|poly| polygon_area(poly, &self.position)).sum::<f64>() as f32
+    }
+
+    /// The volume enclosed by all polygons, computed with the divergence theorem.
+    ///
+    /// The result is positive if the polygons wind counter-clockwise when seen from outside and
+    /// negative if the mesh is inside-out. It is only meaningful for closed meshes; see
+    /// [`ObjData::check_manifold`]. Polygons referring to a missing position are left out, as by
+    /// [`ObjData::surface_area`].
+    pub fn signed_volume(&self) -> f32 {
+        self.polys()
+            .map(|poly| polygon_volume(poly, &self.position))
+            .sum::<f64>() as f32
+    }
+}
+
+impl Object {
+    /// The total area of this object's polygons.
+    pub fn surface_area(&self, data: &ObjData) -> f32 {
+        self.groups
+            .iter()
+            .flat_map(|group| &group.polys)
+            .map(|poly| polygon_area(poly, &data.position))
+            .sum::<f64>() as f32
+    }
+
+    /// The volume enclosed by this object's polygons.
+    ///
+    /// See [`ObjData::signed_volume`].
+    pub fn signed_volume(&self, data: &ObjData) -> f32 {
+        self.groups
+            .iter()
+            .flat_map(|group| &group.polys)
+            .map(|poly| polygon_volume(poly, &data.position))
+            .sum::<f64>() as f32
+    }
+}
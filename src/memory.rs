@@ -0,0 +1,154 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Estimation of the heap memory held by an `ObjData`.
+
+use std::{collections::HashSet, mem::size_of, sync::Arc};
+
+use crate::mtl::Material;
+use crate::obj::{ObjData, ObjMaterial, Scalar};
+
+/// Heap bytes used by each part of an `ObjData`, as returned by [`ObjData::memory_usage`].
+///
+/// Sizes are computed from the capacity of each allocation and don't include allocator
+/// overhead. Shared `Arc` allocations are counted once.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Bytes of [`ObjData::position`].
+    pub positions: usize,
+    /// Bytes of [`ObjData::color`].
+    pub colors: usize,
+    /// Bytes of [`ObjData::texture`].
+    pub texture_coords: usize,
+    /// Bytes of [`ObjData::normal`].
+    pub normals: usize,
+    /// Bytes of the polygon lists and their index tuples.
+    pub polygons: usize,
+    /// Bytes of the polyline lists and their index tuples.
+    pub lines: usize,
+    /// Bytes of the object and group lists.
+    pub objects: usize,
+    /// Bytes of object, group and material names, and the `shadow_obj` and `trace_obj` file names.
+    pub strings: usize,
+    /// Bytes of the material libraries and loaded materials.
+    pub materials: usize,
+}
+
+impl MemoryUsage {
+    /// Total number of bytes.
+    pub fn total(&self) -> usize {
+        self.positions
+            + self.colors
+            + self.texture_coords
+            + self.normals
+            + self.polygons
+            + self.lines
+            + self.objects
+            + self.strings
+            + self.materials
+    }
+}
+
+/// Size of the allocation behind an `Arc` holding `value_size` bytes, including its counters.
+fn arc_size(value_size: usize) -> usize {
+    2 * size_of::<usize>() + value_size
+}
+
+fn vec_size<T>(v: &Vec<T>) -> usize {
+    v.capacity() * size_of::<T>()
+}
+
+#[derive(Default)]
+struct Counter {
+    seen: HashSet<*const u8>,
+}
+
+impl Counter {
+    /// Count an `Arc<str>` once, no matter how many times it is shared.
+    fn str(&mut self, s: &Arc<str>) -> usize {
+        if self.seen.insert(s.as_ptr()) {
+            arc_size(s.len())
+        } else {
+            0
+        }
+    }
+
+    fn material(&mut self, material: &Arc<Material>) -> usize {
+        if !self.seen.insert(Arc::as_ptr(material) as *const u8) {
+            return 0;
+        }
+        let maps = [
+            &material.map_ka,
+            &material.map_kd,
+            &material.map_ks,
+            &material.map_ke,
+            &material.map_ns,
+            &material.map_d,
+            &material.map_bump,
+            &material.map_refl,
+            &material.map_disp,
+        ];
+        arc_size(size_of::<Material>())
+            + material.name.capacity()
+            + maps
+                .iter()
+                .flat_map(|map| map.as_ref())
+                .map(String::capacity)
+                .sum::<usize>()
+    }
+}
+
+impl<S: Scalar> ObjData<S> {
+    /// Estimate how many heap bytes this data uses, broken down by kind.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage {
+            positions: vec_size(&self.position),
+            colors: vec_size(&self.color),
+            texture_coords: vec_size(&self.texture),
+            normals: vec_size(&self.normal),
+            objects: vec_size(&self.objects),
+            ..MemoryUsage::default()
+        };
+        let mut counter = Counter::default();
+
+        for mtl in &self.material_libs {
+            usage.materials += mtl.filename.capacity() + vec_size(&mtl.materials);
+            for material in &mtl.materials {
+                usage.materials += counter.material(material);
+            }
+        }
+        usage.materials += vec_size(&self.material_libs);
+        for name in self.shadow_obj.iter().chain(&self.trace_obj) {
+            usage.strings += name.capacity();
+        }
+
+        for object in &self.objects {
+            usage.strings += counter.str(&object.name);
+            usage.objects += vec_size(&object.groups);
+            for group in &object.groups {
+                usage.strings += counter.str(&group.name);
+                match &group.material {
+                    Some(ObjMaterial::Ref(name)) => usage.strings += counter.str(name),
+                    Some(ObjMaterial::Mtl(material)) => usage.materials += counter.material(material),
+                    None => {}
+                }
+                usage.polygons += vec_size(&group.polys);
+                usage.polygons += group.polys.iter().map(|p| vec_size(&p.0)).sum::<usize>();
+                usage.lines += vec_size(&group.lines);
+                usage.lines += group.lines.iter().map(|l| vec_size(&l.0)).sum::<usize>();
+            }
+        }
+        usage
+    }
+}
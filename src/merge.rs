@@ -0,0 +1,190 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Merging of objects and groups to reduce the number of draw calls, and of whole files.
+
+use crate::obj::{Group, ObjData, Object};
+
+/// How [`ObjData::append_with`] treats appended objects whose name is already in use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NameCollision {
+    /// Add the object as is, leaving several objects with the same name.
+    Keep,
+    /// Move the groups of the object into the existing object with the same name.
+    ///
+    /// Groups with the same name and material as an existing group of that object are merged
+    /// into it.
+    Merge,
+    /// Add a `.1`, `.2`, ... suffix to the name of the object until it is unique.
+    Rename,
+}
+
+fn material_name(group: &Group) -> Option<&str> {
+    group.material.as_ref().map(|m| m.name())
+}
+
+/// Whether the elements of both groups can share a group, having the same material.
+fn same_range(a: &Group, b: &Group) -> bool {
+    material_name(a) == material_name(b)
+}
+
+impl ObjData {
+    /// Move the groups of all objects into a single object with the given name.
+    ///
+    /// Groups keep their relative order.
+    pub fn merge_objects(&mut self, name: String) {
+        let mut merged = Object::new(name);
+        for object in self.objects.drain(..) {
+            merged.groups.extend(object.groups);
+        }
+        self.objects.push(merged);
+    }
+
+    /// Append the contents of another file, renaming colliding objects.
+    ///
+    /// See [`ObjData::append_with`].
+    pub fn append(&mut self, other: ObjData) {
+        self.append_with(other, NameCollision::Rename);
+    }
+
+    /// Append the vertex data, objects and material libraries of `other`.
+    ///
+    /// Indices of the appended polygons and lines are offset to refer to the appended vertex
+    /// data, and objects whose name is already in use are handled according to `policy`.
+    ///
+    /// Material libraries with a new file name are added, those sharing the file name of an
+    /// existing library only contribute materials whose name is not in that library yet.
+    /// The `shadow_obj` and `trace_obj` files and the default material of `other` are only used
+    /// if this data has none.
+    pub fn append_with(&mut self, mut other: ObjData, policy: NameCollision) {
+        let offsets = [self.position.len(), self.texture.len(), self.normal.len()];
+        for tuple in other.index_tuples_mut() {
+            tuple.position += offsets[0];
+            tuple.texture = tuple.texture.map(|t| t + offsets[1]);
+            tuple.normal = tuple.normal.map(|n| n + offsets[2]);
+        }
+
+        // Vertex colors are either absent or given for every position.
+        if self.color.is_empty() != other.color.is_empty() {
+            self.color.resize(self.position.len(), [1.0; 3]);
+            other.color.resize(other.position.len(), [1.0; 3]);
+        }
+        self.position.append(&mut other.position);
+        self.color.append(&mut other.color);
+        self.texture.append(&mut other.texture);
+        self.normal.append(&mut other.normal);
+
+        for mut object in other.objects {
+            let existing = self.objects.iter().position(|o| o.name == object.name);
+            match (existing, policy) {
+                (Some(i), NameCollision::Merge) => self.objects[i].merge_groups_from(object),
+                (Some(_), NameCollision::Rename) => {
+                    let name = (1..)
+                        .map(|n| format!("{}.{}", object.name, n))
+                        .find(|name| self.objects.iter().all(|o| *o.name != **name))
+                        .unwrap();
+                    object.name = name.into();
+                    self.objects.push(object);
+                }
+                _ => self.objects.push(object),
+            }
+        }
+
+        for lib in other.material_libs {
+            match self.material_libs.iter_mut().find(|l| l.filename == lib.filename) {
+                Some(existing) => {
+                    for material in lib.materials {
+                        if existing.materials.iter().all(|m| m.name != material.name) {
+                            existing.materials.push(material);
+                        }
+                    }
+                }
+                None => self.material_libs.push(lib),
+            }
+        }
+        self.shadow_obj = self.shadow_obj.take().or(other.shadow_obj);
+        self.trace_obj = self.trace_obj.take().or(other.trace_obj);
+        self.default_material = self.default_material.take().or(other.default_material);
+    }
+
+    /// Merge the groups sharing the same material within every object.
+    ///
+    /// See [`Object::merge_groups_by_material`].
+    pub fn merge_groups_by_material(&mut self) {
+        for object in &mut self.objects {
+            object.merge_groups_by_material();
+        }
+    }
+}
+
+impl Object {
+    fn merge_groups_from(&mut self, other: Object) {
+        for mut group in other.groups {
+            let matches = |g: &Group| g.name == group.name && same_range(g, &group);
+            match self.groups.iter_mut().find(|g| matches(g)) {
+                Some(target) => target.append(group),
+                None => {
+                    let same_name = self.groups.iter().filter(|g| g.name == group.name);
+                    group.index = same_name.map(|g| g.index + 1).max().unwrap_or(0);
+                    self.groups.push(group);
+                }
+            }
+        }
+    }
+
+    /// Merge all groups sharing the same material into the first such group.
+    pub fn merge_groups_by_material(&mut self) {
+        self.merge_groups_by(|a, b| material_name(a) == material_name(b));
+    }
+
+    /// Merge groups for which `predicate(first, other)` returns `true` into `first`.
+    ///
+    /// Groups are visited in order, and each group is merged into the earliest preceding group
+    /// accepting it. Merged groups take the name of that earliest group.
+    ///
+    /// Since a `Group` carries a single material, elements of merged groups using different
+    /// materials are kept in separate material ranges: consecutive groups with the same name and
+    /// increasing `index`, one per distinct material, in order of first use. Render states are
+    /// kept as [`Group::state_changes`] of the ranges.
+    pub fn merge_groups_by<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&Group, &Group) -> bool,
+    {
+        // Each cluster holds the groups merged into its first member.
+        let mut clusters: Vec<Vec<Group>> = Vec::new();
+        for group in self.groups.drain(..) {
+            match clusters.iter_mut().find(|cluster| predicate(&cluster[0], &group)) {
+                Some(cluster) => cluster.push(group),
+                None => clusters.push(vec![group]),
+            }
+        }
+
+        for cluster in clusters {
+            let name = cluster[0].name.clone();
+            let mut ranges: Vec<Group> = Vec::new();
+            for group in cluster {
+                match ranges.iter_mut().find(|range| same_range(range, &group)) {
+                    Some(range) => range.append(group),
+                    None => {
+                        let mut range = group;
+                        range.name = name.clone();
+                        range.index = ranges.len();
+                        ranges.push(range);
+                    }
+                }
+            }
+            self.groups.extend(ranges);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversions to [`mint`] math types.
+
+use crate::obj::ObjData;
+
+impl ObjData {
+    /// Iterate over the vertex positions as `mint` points.
+    pub fn mint_positions(&self) -> impl ExactSizeIterator<Item = mint::Point3<f32>> + '_ {
+        self.position.iter().copied().map(mint::Point3::from)
+    }
+
+    /// Iterate over the texture coordinates as `mint` vectors.
+    pub fn mint_texture_coords(&self) -> impl ExactSizeIterator<Item = mint::Vector2<f32>> + '_ {
+        self.texture.iter().copied().map(mint::Vector2::from)
+    }
+
+    /// Iterate over the vertex normals as `mint` vectors.
+    pub fn mint_normals(&self) -> impl ExactSizeIterator<Item = mint::Vector3<f32>> + '_ {
+        self.normal.iter().copied().map(mint::Vector3::from)
+    }
+
+    /// Append a vertex position given as any type convertible to a `mint` point, returning its
+    /// index.
+    ///
+    /// If the mesh has vertex colors, the new position is colored white.
+    pub fn push_mint_position(&mut self, position: impl Into<mint::Point3<f32>>) -> usize {
+        self.position.push(position.into().into());
+        if !self.color.is_empty() {
+            self.color.push([1.0; 3]);
+        }
+        self.position.len() - 1
+    }
+
+    /// Append a texture coordinate given as any type convertible to a `mint` vector, returning
+    /// its index.
+    pub fn push_mint_texture_coord(&mut self, uv: impl Into<mint::Vector2<f32>>) -> usize {
+        self.texture.push(uv.into().into());
+        self.texture.len() - 1
+    }
+
+    /// Append a vertex normal given as any type convertible to a `mint` vector, returning its
+    /// index.
+    pub fn push_mint_normal(&mut self, normal: impl Into<mint::Vector3<f32>>) -> usize {
+        self.normal.push(normal.into().into());
+        self.normal.len() - 1
+    }
+}
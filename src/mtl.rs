@@ -15,17 +15,26 @@
 //! Parsing and writing of a .mtl file as defined in the
 //! [full spec](http://paulbourke.net/dataformats/mtl/).
 
-use std::{
+use alloc::{
     borrow::Cow,
-    fmt,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+#[cfg(feature = "std")]
+use std::{
     io::{self, BufRead, BufReader, Error, Read, Write},
     path::Path,
-    str::FromStr,
-    sync::Arc,
 };
 
+#[cfg(feature = "std")]
+use crate::obj::write_io;
+
 /// The model of an a single Material as defined in the .mtl spec.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Material {
     pub name: String,
 
@@ -52,6 +61,9 @@ pub struct Material {
     pub map_bump: Option<String>,
     pub map_refl: Option<String>,
     pub map_disp: Option<String>,
+    /// Whether texture maps are anti-aliased, set with `map_aat on`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub map_aat: bool,
 }
 
 impl Material {
@@ -77,9 +89,187 @@ impl Material {
             map_bump: None,
             map_refl: None,
             map_disp: None,
+            map_aat: false,
             illum: None,
         }
     }
+
+    /// The texture maps referenced by this material, as file paths with any leading texture
+    /// options such as `-s 2 2` removed.
+    pub fn texture_paths(&self) -> impl Iterator<Item = (TextureSlot, &str)> + '_ {
+        let maps = [
+            (TextureSlot::Ambient, &self.map_ka),
+            (TextureSlot::Diffuse, &self.map_kd),
+            (TextureSlot::Specular, &self.map_ks),
+            (TextureSlot::Emissive, &self.map_ke),
+            (TextureSlot::SpecularExponent, &self.map_ns),
+            (TextureSlot::Dissolve, &self.map_d),
+            (TextureSlot::Bump, &self.map_bump),
+            (TextureSlot::Reflection, &self.map_refl),
+            (TextureSlot::Displacement, &self.map_disp),
+        ];
+        IntoIterator::into_iter(maps)
+            .filter_map(|(slot, map)| map.as_deref().map(|map| (slot, strip_texture_options(map))))
+    }
+
+    /// Whether any texture map is set.
+    pub fn has_textures(&self) -> bool {
+        self.texture_paths().next().is_some()
+    }
+
+    /// Whether the material lets light through and needs blending.
+    ///
+    /// This is the case if the dissolve `d` is below one, or `Tr` is above zero when `d` is
+    /// missing, if a component of the transmission filter `Tf` is below one, or if there is a
+    /// dissolve map.
+    pub fn is_transparent(&self) -> bool {
+        let opacity = self.d.or(self.tr.map(|tr| 1.0 - tr)).unwrap_or(1.0);
+        let filtered = self.tf.is_some_and(|tf| tf.iter().any(|&c| c < 1.0));
+        opacity < 1.0 || filtered || self.map_d.is_some()
+    }
+
+    /// Whether the material emits light, by a non-black `Ke` or an emissive map.
+    pub fn is_emissive(&self) -> bool {
+        self.ke.is_some_and(|ke| ke.iter().any(|&c| c > 0.0)) || self.map_ke.is_some()
+    }
+
+    /// The illumination model given by `illum`, if any.
+    pub fn illum_model(&self) -> Option<IllumModel> {
+        self.illum.map(IllumModel::from)
+    }
+}
+
+/// The illumination models of the `illum` statement.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IllumModel {
+    /// 0: Color on and ambient off.
+    Color,
+    /// 1: Color on and ambient on.
+    ColorAmbient,
+    /// 2: Highlight on.
+    Highlight,
+    /// 3: Reflection on and ray trace on.
+    ReflectionRayTrace,
+    /// 4: Transparency glass on, reflection ray trace on.
+    GlassRayTrace,
+    /// 5: Reflection Fresnel on and ray trace on.
+    FresnelRayTrace,
+    /// 6: Transparency refraction on, reflection Fresnel off and ray trace on.
+    RefractionRayTrace,
+    /// 7: Transparency refraction on, reflection Fresnel on and ray trace on.
+    FresnelRefractionRayTrace,
+    /// 8: Reflection on and ray trace off.
+    Reflection,
+    /// 9: Transparency glass on, reflection ray trace off.
+    Glass,
+    /// 10: Casts shadows onto invisible surfaces.
+    ShadowMatte,
+    /// A number outside of the models defined by the spec, kept as is.
+    Other(i32),
+}
+
+impl IllumModel {
+    /// Whether the model relies on ray tracing, which real-time renderers usually approximate
+    /// with environment maps.
+    pub fn is_ray_traced(self) -> bool {
+        matches!(
+            self,
+            IllumModel::ReflectionRayTrace
+                | IllumModel::GlassRayTrace
+                | IllumModel::FresnelRayTrace
+                | IllumModel::RefractionRayTrace
+                | IllumModel::FresnelRefractionRayTrace
+        )
+    }
+}
+
+impl From<i32> for IllumModel {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => IllumModel::Color,
+            1 => IllumModel::ColorAmbient,
+            2 => IllumModel::Highlight,
+            3 => IllumModel::ReflectionRayTrace,
+            4 => IllumModel::GlassRayTrace,
+            5 => IllumModel::FresnelRayTrace,
+            6 => IllumModel::RefractionRayTrace,
+            7 => IllumModel::FresnelRefractionRayTrace,
+            8 => IllumModel::Reflection,
+            9 => IllumModel::Glass,
+            10 => IllumModel::ShadowMatte,
+            other => IllumModel::Other(other),
+        }
+    }
+}
+
+impl From<IllumModel> for i32 {
+    fn from(model: IllumModel) -> Self {
+        match model {
+            IllumModel::Color => 0,
+            IllumModel::ColorAmbient => 1,
+            IllumModel::Highlight => 2,
+            IllumModel::ReflectionRayTrace => 3,
+            IllumModel::GlassRayTrace => 4,
+            IllumModel::FresnelRayTrace => 5,
+            IllumModel::RefractionRayTrace => 6,
+            IllumModel::FresnelRefractionRayTrace => 7,
+            IllumModel::Reflection => 8,
+            IllumModel::Glass => 9,
+            IllumModel::ShadowMatte => 10,
+            IllumModel::Other(value) => value,
+        }
+    }
+}
+
+/// The material property a texture map is bound to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextureSlot {
+    /// `map_Ka`
+    Ambient,
+    /// `map_Kd`
+    Diffuse,
+    /// `map_Ks`
+    Specular,
+    /// `map_Ke`
+    Emissive,
+    /// `map_Ns`
+    SpecularExponent,
+    /// `map_d`
+    Dissolve,
+    /// `map_Bump`
+    Bump,
+    /// `refl`
+    Reflection,
+    /// `disp`
+    Displacement,
+}
+
+/// Skip the options preceding the file name of a texture map statement.
+pub(crate) fn strip_texture_options(mut map: &str) -> &str {
+    loop {
+        map = map.trim_start();
+        let option = match map.split_whitespace().next() {
+            Some(option) if option.starts_with('-') && option.parse::<f32>().is_err() => option,
+            _ => return map,
+        };
+        let (min, max) = match option {
+            "-mm" => (2, 2),
+            "-o" | "-s" | "-t" => (1, 3),
+            _ => (1, 1),
+        };
+        map = &map[option.len()..];
+        for i in 0..max {
+            let arg = match map.split_whitespace().next() {
+                Some(arg) => arg,
+                None => return map.trim_start(),
+            };
+            // Optional arguments of vector options are numbers.
+            if i >= min && arg.parse::<f32>().is_err() {
+                break;
+            }
+            map = &map.trim_start()[arg.len()..];
+        }
+    }
 }
 
 /// Indicates type of a missing value
@@ -106,6 +296,7 @@ impl fmt::Display for MtlMissingType {
 /// Errors parsing or loading a .mtl file.
 #[derive(Debug)]
 pub enum MtlError {
+    #[cfg(feature = "std")]
     Io(io::Error),
     /// Given instruction was not in .mtl spec.
     InvalidInstruction(String),
@@ -117,6 +308,7 @@ pub enum MtlError {
     MissingValue(MtlMissingType),
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for MtlError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -129,6 +321,7 @@ impl std::error::Error for MtlError {
 impl fmt::Display for MtlError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            #[cfg(feature = "std")]
             MtlError::Io(err) => write!(f, "I/O error loading a .mtl file: {}", err),
             MtlError::InvalidInstruction(instruction) => write!(f, "Unsupported mtl instruction: {}", instruction),
             MtlError::InvalidValue(val) => write!(f, "Attempted to parse the value '{}' but failed.", val),
@@ -138,6 +331,7 @@ impl fmt::Display for MtlError {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<io::Error> for MtlError {
     fn from(e: Error) -> Self {
         Self::Io(e)
@@ -182,6 +376,15 @@ impl<'a, I: Iterator<Item = &'a str>> Parser<I> {
         }
     }
 
+    fn get_switch(&mut self) -> Result<bool, MtlError> {
+        match self.0.next() {
+            Some("on") => Ok(true),
+            Some("off") => Ok(false),
+            Some(v) => Err(MtlError::InvalidValue(v.to_string())),
+            None => Err(MtlError::MissingValue(MtlMissingType::String)),
+        }
+    }
+
     fn into_string(mut self) -> Result<String, MtlError> {
         match self.0.next() {
             Some(v) => {
@@ -202,6 +405,7 @@ impl<'a, I: Iterator<Item = &'a str>> Parser<I> {
 /// The material name is replaced by the actual material data when the material libraries are
 /// laoded if a match is found.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mtl {
     /// Name of the .mtl file.
     pub filename: String,
@@ -222,6 +426,7 @@ impl Mtl {
     }
 
     /// Load the mtl library from the input buffer generated by the given closure.
+    #[cfg(feature = "std")]
     ///
     /// This function overwrites the contents of this library if it has already been loaded.
     pub fn reload_with<R, F>(&mut self, obj_dir: impl AsRef<Path>, mut resolve: F) -> Result<&mut Self, MtlError>
@@ -235,15 +440,27 @@ impl Mtl {
     /// Load the mtl library from the given input buffer.
     ///
     /// This function overwrites the contents of this library if it has already been loaded.
+    #[cfg(feature = "std")]
     pub fn reload(&mut self, input: impl Read) -> Result<&mut Self, MtlError> {
+        self.reload_lines(BufReader::new(input).lines().map(|line| line.map_err(MtlError::Io)))
+    }
+
+    /// Load the mtl library from a string slice, without requiring `std`.
+    ///
+    /// This function overwrites the contents of this library if it has already been loaded.
+    pub fn reload_str(&mut self, input: &str) -> Result<&mut Self, MtlError> {
+        self.reload_lines(input.lines().map(Ok))
+    }
+
+    fn reload_lines<L: AsRef<str>>(
+        &mut self,
+        lines: impl Iterator<Item = Result<L, MtlError>>,
+    ) -> Result<&mut Self, MtlError> {
         self.materials.clear();
-        let input = BufReader::new(input);
         let mut material = None;
-        for line in input.lines() {
-            let mut parser = match line {
-                Ok(ref line) => Parser(line.split_whitespace().filter(|s| !s.is_empty())),
-                Err(err) => return Err(MtlError::Io(err)),
-            };
+        for line in lines {
+            let line = line?;
+            let mut parser = Parser(line.as_ref().split_whitespace().filter(|s| !s.is_empty()));
             match parser.0.next() {
                 Some("newmtl") => {
                     self.materials.extend(material.take().map(Arc::new));
@@ -346,12 +563,15 @@ impl Mtl {
                         m.map_disp = Some(parser.into_string()?);
                     }
                 }
-                Some(other) => {
-                    if !other.starts_with('#') {
-                        return Err(MtlError::InvalidInstruction(other.to_string()));
+                Some("map_aat") => {
+                    if let Some(ref mut m) = material {
+                        m.map_aat = parser.get_switch()?;
                     }
                 }
-                None => {}
+                Some(other) if !other.starts_with('#') => {
+                    return Err(MtlError::InvalidInstruction(other.to_string()));
+                }
+                Some(_) | None => {}
             }
         }
 
@@ -362,7 +582,13 @@ impl Mtl {
         Ok(self)
     }
 
+    #[cfg(feature = "std")]
     pub fn write_to_buf(&self, out: &mut impl Write) -> Result<(), io::Error> {
+        write_io(out, |out| self.write_to_fmt(out))
+    }
+
+    /// Serialize this library into the given formatter or string, without requiring `std`.
+    pub fn write_to_fmt(&self, out: &mut impl fmt::Write) -> fmt::Result {
         for mtl in &self.materials {
             writeln!(out, "newmtl {}", mtl.name)?;
             if let Some([ka0, ka1, ka2]) = mtl.ka {
@@ -422,6 +648,9 @@ impl Mtl {
             if let Some(map_disp) = &mtl.map_disp {
                 writeln!(out, "disp {}", map_disp)?;
             }
+            if mtl.map_aat {
+                writeln!(out, "map_aat on")?;
+            }
         }
         Ok(())
     }
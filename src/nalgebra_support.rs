@@ -0,0 +1,41 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversions to [`nalgebra`] math types.
+
+use crate::obj::ObjData;
+
+impl ObjData {
+    /// Iterate over the vertex positions as `nalgebra` points.
+    pub fn nalgebra_positions(&self) -> impl ExactSizeIterator<Item = nalgebra::Point3<f32>> + '_ {
+        self.position.iter().copied().map(nalgebra::Point3::from)
+    }
+
+    /// Iterate over the texture coordinates as `nalgebra` vectors.
+    pub fn nalgebra_texture_coords(&self) -> impl ExactSizeIterator<Item = nalgebra::Vector2<f32>> + '_ {
+        self.texture.iter().copied().map(nalgebra::Vector2::from)
+    }
+
+    /// Iterate over the vertex normals as `nalgebra` vectors.
+    pub fn nalgebra_normals(&self) -> impl ExactSizeIterator<Item = nalgebra::Vector3<f32>> + '_ {
+        self.normal.iter().copied().map(nalgebra::Vector3::from)
+    }
+
+    /// Apply an affine transformation given as an `nalgebra` matrix to all positions and normals.
+    ///
+    /// See [`ObjData::transform`].
+    pub fn transform_nalgebra(&mut self, matrix: &nalgebra::Matrix4<f32>) {
+        self.transform(&(*matrix).into());
+    }
+}
@@ -0,0 +1,207 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Repair of invalid or inconsistent vertex normals.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::math::{add, dot, length, newell_normal, normalize, scale};
+use crate::obj::{IndexTuple, ObjData};
+
+/// Tolerance on the length of a normal before it is renormalized.
+const UNIT_TOLERANCE: f32 = 1e-4;
+
+/// Normal repair configuration options.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NormalRepairConfig {
+    /// Flip normals pointing away from the front side of the faces using them, as given by the
+    /// winding order (default `false`).
+    pub reorient: bool,
+}
+
+/// The number of repairs made by [`ObjData::fix_normals`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NormalRepairReport {
+    /// Normals rescaled to unit length.
+    pub renormalized: usize,
+    /// Polygon corners whose zero or NaN normal was replaced by the face normal.
+    pub replaced: usize,
+    /// Polygon corners whose normal was flipped to match the face winding.
+    pub reoriented: usize,
+}
+
+impl NormalRepairReport {
+    /// Total number of repairs.
+    pub fn total(&self) -> usize {
+        self.renormalized + self.replaced + self.reoriented
+    }
+}
+
+/// The normals found by [`ObjData::check_normal_orientation`] to point away from the faces
+/// using them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InvertedNormals {
+    /// Indices into [`ObjData::normal`], in ascending order.
+    pub normals: Vec<usize>,
+    /// Indices into [`ObjData::position`] of the polygon corners using these normals, in
+    /// ascending order.
+    pub positions: Vec<usize>,
+}
+
+impl InvertedNormals {
+    /// Whether no inverted normals were found.
+    pub fn is_empty(&self) -> bool {
+        self.normals.is_empty()
+    }
+}
+
+impl ObjData {
+    /// Repair vertex normals using the default configuration.
+    ///
+    /// See [`ObjData::fix_normals_with_config`].
+    pub fn fix_normals(&mut self) -> NormalRepairReport {
+        self.fix_normals_with_config(NormalRepairConfig::default())
+    }
+
+    /// Renormalize non-unit normals and replace zero or NaN normals with face normals.
+    ///
+    /// Replacement and reoriented normals are appended to the normal array, since the original
+    /// entries may be shared with other polygons. Normals left unreferenced afterwards can be
+    /// dropped with [`ObjData::compact`]. Corners of degenerate polygons, which have no face
+    /// normal, are left unchanged.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn fix_normals_with_config(&mut self, config: NormalRepairConfig) -> NormalRepairReport {
+        let mut report = NormalRepairReport::default();
+        let ObjData {
+            position,
+            normal,
+            objects,
+            ..
+        } = self;
+
+        let mut invalid = vec![false; normal.len()];
+        for (n, invalid) in normal.iter_mut().zip(&mut invalid) {
+            match normalize(*n) {
+                Some(unit) => {
+                    if (length(*n) - 1.0).abs() > UNIT_TOLERANCE {
+                        *n = unit;
+                        report.renormalized += 1;
+                    }
+                }
+                None => *invalid = true,
+            }
+        }
+
+        let mut flipped: HashMap<usize, usize> = HashMap::new();
+        for group in objects.iter_mut().flat_map(|o| o.groups.iter_mut()) {
+            for poly in &mut group.polys {
+                let points: Vec<_> = poly.0.iter().map(|t| position[t.position]).collect();
+                let face = match normalize(newell_normal(&points)) {
+                    Some(face) => face,
+                    None => continue,
+                };
+                let mut face_index = None;
+                for IndexTuple { normal: n, .. } in &mut poly.0 {
+                    let i = match *n {
+                        Some(i) => i,
+                        None => continue,
+                    };
+                    if invalid[i] {
+                        let index = *face_index.get_or_insert_with(|| {
+                            normal.push(face);
+                            normal.len() - 1
+                        });
+                        *n = Some(index);
+                        report.replaced += 1;
+                    } else if config.reorient && dot(normal[i], face) < 0.0 {
+                        let index = *flipped.entry(i).or_insert_with(|| {
+                            normal.push(scale(normal[i], -1.0));
+                            normal.len() - 1
+                        });
+                        *n = Some(index);
+                        report.reoriented += 1;
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+impl ObjData {
+    /// Find the normals pointing opposite the average normal of the polygons using them.
+    ///
+    /// The face normals are weighted by polygon area and their orientation follows the winding
+    /// order. A normal is inverted if it points into the half space behind that average, which
+    /// catches normals flipped by an exporter while tolerating smoothing across sharp edges.
+    /// Normals used only by lines or degenerate polygons are not checked.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn check_normal_orientation(&self) -> InvertedNormals {
+        let mut average = vec![[0.0; 3]; self.normal.len()];
+        for poly in self.polys() {
+            let points: Vec<_> = poly.0.iter().map(|t| self.position[t.position]).collect();
+            let face = newell_normal(&points);
+            for n in poly.0.iter().filter_map(|t| t.normal) {
+                average[n] = add(average[n], face);
+            }
+        }
+        let normals: Vec<usize> = (0..self.normal.len())
+            .filter(|&i| normalize(average[i]).is_some_and(|face| dot(self.normal[i], face) < 0.0))
+            .collect();
+        let inverted: BTreeSet<_> = normals.iter().copied().collect();
+        let positions: BTreeSet<_> = self
+            .polys()
+            .flat_map(|poly| poly.0.iter())
+            .filter(|t| t.normal.is_some_and(|n| inverted.contains(&n)))
+            .map(|t| t.position)
+            .collect();
+        InvertedNormals {
+            normals,
+            positions: positions.into_iter().collect(),
+        }
+    }
+
+    /// Flip the normals found by [`ObjData::check_normal_orientation`], returning them.
+    ///
+    /// Normals are flipped in place, so all polygons sharing an inverted normal are fixed
+    /// together.
+    pub fn fix_normal_orientation(&mut self) -> InvertedNormals {
+        let inverted = self.check_normal_orientation();
+        for &i in &inverted.normals {
+            self.normal[i] = scale(self.normal[i], -1.0);
+        }
+        inverted
+    }
+
+    /// Compute one normal per position by averaging the normals of the polygons using it,
+    /// weighted by their area.
+    ///
+    /// Positions not used by any non-degenerate polygon get a zero normal. Polygons referring to
+    /// missing positions are skipped.
+    pub(crate) fn smooth_normals(&self) -> Vec<[f32; 3]> {
+        let mut normals = vec![[0.0; 3]; self.position.len()];
+        for poly in self.polys() {
+            let points: Option<Vec<_>> = poly.0.iter().map(|t| self.position.get(t.position).copied()).collect();
+            let points = match points {
+                Some(points) => points,
+                None => continue,
+            };
+            let face = newell_normal(&points);
+            for &IndexTuple { position: p, .. } in &poly.0 {
+                normals[p] = add(normals[p], face);
+            }
+        }
+        normals.into_iter().map(|n| normalize(n).unwrap_or([0.0; 3])).collect()
+    }
+}
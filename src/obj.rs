@@ -18,24 +18,62 @@
 #[cfg(feature = "genmesh")]
 pub use genmesh::{Polygon, Quad, Triangle};
 
+use alloc::{collections::BTreeSet, format, string::String, sync::Arc, vec::Vec};
+use core::{
+    fmt,
+    iter::{self, FromIterator},
+    ops::Range,
+    str::FromStr,
+};
+#[cfg(feature = "std")]
 use std::{
     collections::HashMap,
-    fmt,
     fs::File,
-    io::{self, BufRead, BufReader, Error, Read, Write},
+    io::{self, BufRead, BufReader, BufWriter, Error, Read, Write},
     path::{Path, PathBuf},
-    str::FromStr,
-    sync::Arc,
 };
 
-use crate::mtl::{Material, Mtl, MtlError};
-use std::io::BufWriter;
-
-const DEFAULT_OBJECT: &str = "default";
-const DEFAULT_GROUP: &str = "default";
+#[cfg(feature = "std")]
+use crate::instrument::PhaseTimes;
+use crate::layout::SourceLayout;
+#[cfg(feature = "std")]
+use crate::lookup::UnresolvedMaterial;
+#[cfg(feature = "std")]
+use crate::mtl::MtlError;
+use crate::mtl::{Material, Mtl};
+use crate::push_parser::PushParser;
+
+pub(crate) const DEFAULT_OBJECT: &str = "default";
+pub(crate) const DEFAULT_GROUP: &str = "default";
+/// The start of the comment at the top of written files.
+const GENERATED_BY: &str = "Generated by the obj Rust library";
+
+/// Hands out shared copies of names, so that repeated names are only allocated once.
+#[derive(Default)]
+pub(crate) struct Interner(BTreeSet<Arc<str>>);
+
+impl Interner {
+    pub(crate) fn intern(&mut self, name: &str) -> Arc<str> {
+        if let Some(interned) = self.0.get(name) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = name.into();
+        self.0.insert(interned.clone());
+        interned
+    }
+}
 
 /// Load configuration options.
-#[derive(Copy, Clone, Debug)]
+///
+/// Passed to [`ObjData::load_buf_with`], [`Obj::load_with`] and the other parsing functions. Start
+/// from [`LoadConfig::new`] and chain setters, such as `LoadConfig::new().strict(false)`, so that
+/// options only have to be named when they differ from the default. New options may be added in
+/// any release, so the struct can't be built with a literal outside this crate.
+///
+/// This is not `Copy`, as some options own their values, such as
+/// [`LoadConfig::default_material`]. Clone it to load several files with the same options.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct LoadConfig {
     /// Expect a strict spec-compliant `.obj` format.
     ///
@@ -44,40 +82,687 @@ pub struct LoadConfig {
     /// with unrecognized commands.
     ///
     /// This is useful for loading `obj` files that have been extended with third-party commands.
+    ///
+    /// Without strict parsing, lines that are malformed, such as truncated faces, numbers with
+    /// garbage in them or invalid UTF-8, are skipped as a whole and parsing goes on with the next
+    /// line, recording a [`LoadWarning::SkippedLine`]. Errors due to other options, like
+    /// [`LoadConfig::check_indices`], are still returned.
     pub strict: bool,
+    /// Check vertex references of faces and lines while loading.
+    ///
+    /// If this option is set to `true`, zero texture and normal indices, relative indices that
+    /// reach before the first element and indices beyond the number of elements in the whole file
+    /// are reported as errors with the line they appear on. By default (`false`) such references
+    /// are kept as they are and have to be checked by the consumer.
+    pub check_indices: bool,
+    /// Accept `,` as the decimal separator in vertex data.
+    ///
+    /// Some tools write numbers such as `0,5` when running under a locale that uses decimal
+    /// commas. If this option is set to `true`, numbers of `v`, `vt` and `vn` statements that have
+    /// a single comma and no point are read with the comma as the decimal point, and a
+    /// [`LoadWarning::CommaDecimal`] is recorded for the line. Defaults to `false`.
+    pub comma_decimals: bool,
+    /// What to do with numbers that can't be represented.
+    ///
+    /// This covers finite numbers of `v`, `vt` and `vn` statements that overflow to infinity or
+    /// underflow to a subnormal value or zero, such as `1e40`, and indices of faces and lines that
+    /// don't fit in an `isize`. Defaults to [`OutOfRangePolicy::Allow`].
+    pub out_of_range: OutOfRangePolicy,
+    /// The name of the object that holds everything before the first `o` statement.
+    ///
+    /// Many exporters never write `o`, so naming this object after its file keeps objects
+    /// apart when several files are merged. Defaults to [`ImplicitObjectName::Default`].
+    pub implicit_object: ImplicitObjectName,
+    /// Read `usemtl None` as a bare `usemtl`, which clears the current material.
+    ///
+    /// Some exporters write `None` to return to the default material. If this option is set to
+    /// `true` (default), the following faces and lines have no material. Otherwise `None` is
+    /// treated as a material name like any other.
+    pub none_clears_material: bool,
+    /// Keep the current material when a bare `g` switches back to the default group.
+    ///
+    /// A `g` statement without names selects the default group, and like any other group change
+    /// it doesn't affect the material, which is the behavior if this option is set to `true`
+    /// (default). Setting it to `false` restores the handling of older versions, where the
+    /// faces and lines after a bare `g` have no material until the next `usemtl`.
+    pub bare_group_keeps_material: bool,
+    /// The largest number of vertices a face may have.
+    ///
+    /// Larger faces are an [`ObjError::TooManyFaceVertices`] error, unless
+    /// [`LoadConfig::triangulate_large_faces`] is set. Defaults to `None`, which allows any number.
+    pub max_face_vertices: Option<usize>,
+    /// Split faces with more than [`LoadConfig::max_face_vertices`] vertices into triangles.
+    ///
+    /// Faces are split into a fan around their first vertex while loading, which is only correct
+    /// for convex faces. Defaults to `false`.
+    pub triangulate_large_faces: bool,
+    /// The largest number of bytes a line may have, not counting its line terminator.
+    ///
+    /// Longer lines are an [`ObjError::LineTooLong`] error, found as soon as that many bytes of
+    /// the line have been read, so a single huge line can't take more memory than this. Defaults
+    /// to `None`, which allows any length.
+    pub max_line_length: Option<usize>,
+    /// What to do when an `o` statement repeats the name of an earlier object.
+    ///
+    /// Defaults to [`DuplicateObjectPolicy::Keep`].
+    pub duplicate_objects: DuplicateObjectPolicy,
+    /// The [`ObjData::default_material`] of the loaded data. Defaults to `None`.
+    pub default_material: Option<Arc<Material>>,
+    /// Keep comments, blank lines and the order of statements in [`ObjData::layout`], so that
+    /// writing the data reproduces the file apart from the changes made to it.
+    ///
+    /// No layout is kept if [`LoadConfig::include_objects`], [`LoadConfig::exclude_groups`] or
+    /// [`LoadConfig::geometry_only`] leave out parts of the file, or if
+    /// [`LoadConfig::dedup_attributes`] merges attributes. Comments giving the number of
+    /// statements before them are checked as well, see [`LoadWarning::SummaryMismatch`].
+    /// Defaults to `false`.
+    pub faithful: bool,
+    /// Patterns of the names of the objects to keep, or `None` to keep all objects.
+    ///
+    /// In patterns, `*` matches any run of characters and `?` any single character. The faces and
+    /// lines of other objects are skipped while parsing and the objects are left out, while vertex
+    /// data is always read in full, since any element may refer to it. Defaults to `None`.
+    pub include_objects: Option<Vec<String>>,
+    /// Patterns of the names of groups to leave out, like for [`LoadConfig::include_objects`].
+    ///
+    /// Defaults to none.
+    pub exclude_groups: Vec<String>,
+    /// Only keep positions, vertex colors, objects, groups, polygons and lines.
+    ///
+    /// If this option is set to `true`, `vt` and `vn` statements are skipped without being parsed,
+    /// polygons and lines have no texture and normal indices, and `mtllib` and `usemtl`
+    /// statements are ignored, so there are no materials to resolve and groups aren't split by
+    /// material. This saves memory and time for tools such as collision cookers that only need
+    /// the shape. Defaults to `false`.
+    pub geometry_only: bool,
+    /// Store each distinct position, texture coordinate and normal only once.
+    ///
+    /// If this option is set to `true`, attributes exactly equal to an earlier one of the same
+    /// kind are not stored, and the indices of polygons and lines refer to the earlier one
+    /// instead. Positions are only merged if their vertex colors are equal as well, and only for
+    /// scalar types providing [`Scalar::exact_bits`]. This saves memory on files from exporters
+    /// that repeat the attributes of every corner. Defaults to `false`.
+    pub dedup_attributes: bool,
+    /// A callback called with every statement parsed into [`ObjData`], such as by
+    /// [`PushParser`], in the order of the file.
+    ///
+    /// Each statement is reported with what it resolved to, such as the stored index and value of
+    /// a vertex attribute or the polygons added by a face, see [`TracedStatement`]. This helps
+    /// with debugging files and with comparing the results against other parsers. Lines skipped
+    /// for being malformed are not reported. Defaults to `None`.
+    pub on_statement: Option<StatementHook>,
+}
+
+/// A callback for [`LoadConfig::on_statement`].
+#[derive(Clone)]
+pub struct StatementHook(Arc<dyn Fn(&TracedStatement<'_>) + Send + Sync>);
+
+impl StatementHook {
+    pub fn new(callback: impl Fn(&TracedStatement<'_>) + Send + Sync + 'static) -> Self {
+        StatementHook(Arc::new(callback))
+    }
+
+    pub(crate) fn call(&self, statement: &TracedStatement<'_>) {
+        (self.0)(statement)
+    }
+}
+
+impl fmt::Debug for StatementHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StatementHook")
+    }
+}
+
+/// A parsed statement, reported to [`LoadConfig::on_statement`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TracedStatement<'a> {
+    /// The 0-based number of the line.
+    pub line_number: usize,
+    /// The keyword that starts the statement, such as `v` or `usemtl`.
+    pub keyword: &'a str,
+    /// The rest of the line after the keyword.
+    pub args: &'a str,
+    /// What the statement resolved to.
+    pub value: TracedValue<'a>,
+}
+
+/// What a statement resolved to, see [`TracedStatement`].
+///
+/// Indices are 0-based and refer to the attributes in the order of the file, also with
+/// [`LoadConfig::dedup_attributes`], which maps them to the stored attributes once loading
+/// finishes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TracedValue<'a> {
+    /// A `v` statement, with the index of the position, its value if the scalar type provides
+    /// [`Scalar::to_f64`], and the vertex color if the file has any.
+    Position {
+        index: usize,
+        position: Option<[f64; 3]>,
+        color: Option<[f32; 3]>,
+    },
+    /// A `vt` statement, with the index of the texture coordinate.
+    Texture { index: usize, texture: [f32; 2] },
+    /// A `vn` statement, with the index of the normal.
+    Normal { index: usize, normal: [f32; 3] },
+    /// An `f` statement and the polygons it added, which are several if it was triangulated and
+    /// none if its group is left out.
+    Polygons(&'a [SimplePolygon]),
+    /// An `l` statement and the lines it added, if its group is kept.
+    Lines(&'a [Line]),
+    /// An `o` statement and the name of the object it started, see
+    /// [`LoadConfig::duplicate_objects`].
+    Object(&'a str),
+    /// A `g`, `usemtl` or render state statement, and the group the following elements are added
+    /// to, or `None` if that is the default group without a material, which is only created once
+    /// it is needed.
+    Group(Option<&'a Group>),
+    /// An `mtllib` statement and the file name of the material library.
+    MaterialLib(&'a str),
+    /// A comment or another statement without a value, such as `shadow_obj`, or a statement that
+    /// was skipped, such as `vt` with [`LoadConfig::geometry_only`].
+    Other,
+}
+
+/// Options for writing .obj files, see [`ObjData::write_to_buf_with`].
+///
+/// Numbers are written with `.` as the decimal point regardless of the locale of the system,
+/// unless [`WriteConfig::decimal_separator`] says otherwise.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct WriteConfig {
+    /// Merge exactly identical positions, texture coordinates and normals before writing, rewriting
+    /// the indices of polygons and lines to match.
+    ///
+    /// Positions are only merged if their vertex colors are the same as well. This shrinks files of
+    /// unindexed triangles, such as converted STL files. Defaults to `false`.
+    pub weld: bool,
+    /// Leave out texture coordinates and normals that no polygon or line references, rewriting
+    /// the indices of polygons and lines to match.
+    ///
+    /// Positions are always written, as they may be meaningful as a point cloud. Defaults to
+    /// `false`.
+    pub prune_unreferenced: bool,
+    /// Write each object as its own block of vertex attributes, followed by its groups using
+    /// negative indices relative to the end of that block.
+    ///
+    /// Some older importers only resolve indices against the vertices declared since the
+    /// previous `o` statement, while relative indices are read the same way by every importer.
+    /// Attributes shared by several objects are written once for each, and attributes no polygon
+    /// or line references are left out. Data loaded with [`LoadConfig::faithful`] is rewritten
+    /// in this form as well. Defaults to `false`.
+    pub relative_indices: bool,
+    /// Write comments with the number of vertex attributes after each block of them and the
+    /// number of polygons and lines after each object, such as `# 8 vertices` and
+    /// `# 6 elements`.
+    ///
+    /// Loading with [`LoadConfig::faithful`] checks such comments, see
+    /// [`LoadWarning::SummaryMismatch`]. Data loaded with [`LoadConfig::faithful`] is rewritten
+    /// to add them. Defaults to `false`.
+    pub summary_comments: bool,
+    /// The character written instead of `.` in the numbers of `v`, `vt` and `vn` statements.
+    ///
+    /// Files written with another separator, such as `,`, don't follow the format, and are only
+    /// meant for legacy tools that read numbers according to their locale. They can be loaded
+    /// again with [`LoadConfig::comma_decimals`]. Defaults to `None`, which writes `.`.
+    pub decimal_separator: Option<char>,
+}
+
+#[cfg(feature = "std")]
+impl WriteConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set [`WriteConfig::weld`].
+    pub fn weld(mut self, weld: bool) -> Self {
+        self.weld = weld;
+        self
+    }
+
+    /// Set [`WriteConfig::prune_unreferenced`].
+    pub fn prune_unreferenced(mut self, prune_unreferenced: bool) -> Self {
+        self.prune_unreferenced = prune_unreferenced;
+        self
+    }
+
+    /// Set [`WriteConfig::relative_indices`].
+    pub fn relative_indices(mut self, relative_indices: bool) -> Self {
+        self.relative_indices = relative_indices;
+        self
+    }
+
+    /// Set [`WriteConfig::summary_comments`].
+    pub fn summary_comments(mut self, summary_comments: bool) -> Self {
+        self.summary_comments = summary_comments;
+        self
+    }
+
+    /// Set [`WriteConfig::decimal_separator`] to `separator`.
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = Some(separator);
+        self
+    }
 }
 
 impl Default for LoadConfig {
     fn default() -> Self {
-        LoadConfig { strict: true }
+        LoadConfig {
+            strict: true,
+            check_indices: false,
+            comma_decimals: false,
+            out_of_range: OutOfRangePolicy::Allow,
+            implicit_object: ImplicitObjectName::Default,
+            none_clears_material: true,
+            bare_group_keeps_material: true,
+            max_face_vertices: None,
+            triangulate_large_faces: false,
+            max_line_length: None,
+            duplicate_objects: DuplicateObjectPolicy::Keep,
+            default_material: None,
+            faithful: false,
+            include_objects: None,
+            exclude_groups: Vec::new(),
+            geometry_only: false,
+            dedup_attributes: false,
+            on_statement: None,
+        }
     }
 }
 
-/// A tuple of position, texture and normal indices assigned to each polygon vertex.
+/// How the object before the first `o` statement is named, see [`LoadConfig::implicit_object`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImplicitObjectName {
+    /// `default`, which is not written back as an `o` statement.
+    Default,
+    /// The file name without its extension when loading with [`Obj::load_with`], otherwise the
+    /// same as `Default`.
+    FileStem,
+    /// The given name.
+    Named(String),
+}
+
+/// The handling of objects that share a name, see [`LoadConfig::duplicate_objects`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DuplicateObjectPolicy {
+    /// Keep each object separately under the same name.
+    Keep,
+    /// Append the groups of later objects to the first object of the same name.
+    Merge,
+    /// Rename later objects by appending the first free `.001`, `.002`, ... suffix.
+    Suffix,
+    /// Return [`ObjError::DuplicateObject`].
+    Error,
+}
+
+/// The handling of numbers that don't fit their type, see [`LoadConfig::out_of_range`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutOfRangePolicy {
+    /// Keep the value the number parses to, such as infinity. Indices that don't fit are treated as
+    /// malformed, or ignored for texture and normal indices.
+    Allow,
+    /// Replace infinities by the largest finite value of the same sign and subnormal values by
+    /// zero, recording a [`LoadWarning::ClampedNumber`]. Indices that don't fit are an error.
+    Clamp,
+    /// Return [`ObjError::NumberOutOfRange`] for the first such number.
+    Error,
+}
+
+impl LoadConfig {
+    /// The default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set [`LoadConfig::strict`].
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set [`LoadConfig::check_indices`].
+    pub fn check_indices(mut self, check_indices: bool) -> Self {
+        self.check_indices = check_indices;
+        self
+    }
+
+    /// Set [`LoadConfig::comma_decimals`].
+    pub fn comma_decimals(mut self, comma_decimals: bool) -> Self {
+        self.comma_decimals = comma_decimals;
+        self
+    }
+
+    /// Set [`LoadConfig::out_of_range`].
+    pub fn out_of_range(mut self, out_of_range: OutOfRangePolicy) -> Self {
+        self.out_of_range = out_of_range;
+        self
+    }
+
+    /// Set [`LoadConfig::implicit_object`].
+    pub fn implicit_object(mut self, implicit_object: ImplicitObjectName) -> Self {
+        self.implicit_object = implicit_object;
+        self
+    }
+
+    /// Set [`LoadConfig::none_clears_material`].
+    pub fn none_clears_material(mut self, none_clears_material: bool) -> Self {
+        self.none_clears_material = none_clears_material;
+        self
+    }
+
+    /// Set [`LoadConfig::bare_group_keeps_material`].
+    pub fn bare_group_keeps_material(mut self, bare_group_keeps_material: bool) -> Self {
+        self.bare_group_keeps_material = bare_group_keeps_material;
+        self
+    }
+
+    /// Limit [`LoadConfig::max_face_vertices`] to `max`.
+    pub fn max_face_vertices(mut self, max: usize) -> Self {
+        self.max_face_vertices = Some(max);
+        self
+    }
+
+    /// Set [`LoadConfig::triangulate_large_faces`].
+    pub fn triangulate_large_faces(mut self, triangulate_large_faces: bool) -> Self {
+        self.triangulate_large_faces = triangulate_large_faces;
+        self
+    }
+
+    /// Limit [`LoadConfig::max_line_length`] to `max` bytes.
+    pub fn max_line_length(mut self, max: usize) -> Self {
+        self.max_line_length = Some(max);
+        self
+    }
+
+    /// Set [`LoadConfig::duplicate_objects`].
+    pub fn duplicate_objects(mut self, duplicate_objects: DuplicateObjectPolicy) -> Self {
+        self.duplicate_objects = duplicate_objects;
+        self
+    }
+
+    /// Set [`LoadConfig::default_material`].
+    pub fn default_material(mut self, material: impl Into<Arc<Material>>) -> Self {
+        self.default_material = Some(material.into());
+        self
+    }
+
+    /// Set [`LoadConfig::faithful`].
+    pub fn faithful(mut self, faithful: bool) -> Self {
+        self.faithful = faithful;
+        self
+    }
+
+    /// Only keep the objects whose names match one of `patterns`, see
+    /// [`LoadConfig::include_objects`].
+    pub fn include_objects<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.include_objects = Some(patterns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Leave out the groups whose names match one of `patterns`, see
+    /// [`LoadConfig::exclude_groups`].
+    pub fn exclude_groups<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.exclude_groups = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set [`LoadConfig::geometry_only`].
+    pub fn geometry_only(mut self, geometry_only: bool) -> Self {
+        self.geometry_only = geometry_only;
+        self
+    }
+
+    /// Set [`LoadConfig::dedup_attributes`].
+    pub fn dedup_attributes(mut self, dedup_attributes: bool) -> Self {
+        self.dedup_attributes = dedup_attributes;
+        self
+    }
+
+    /// Set [`LoadConfig::on_statement`] to `callback`.
+    pub fn on_statement(mut self, callback: impl Fn(&TracedStatement<'_>) + Send + Sync + 'static) -> Self {
+        self.on_statement = Some(StatementHook::new(callback));
+        self
+    }
+
+    /// Whether objects or groups may be left out.
+    pub(crate) fn filters(&self) -> bool {
+        self.include_objects.is_some() || !self.exclude_groups.is_empty()
+    }
+
+    /// Whether the elements of the group `group` of the object `object` are kept.
+    pub(crate) fn keeps(&self, object: &str, group: &str) -> bool {
+        let included = match &self.include_objects {
+            Some(patterns) => patterns.iter().any(|p| matches_pattern(p, object)),
+            None => true,
+        };
+        included && !self.exclude_groups.iter().any(|p| matches_pattern(p, group))
+    }
+
+    /// The material name given to `usemtl`, or `None` if the statement clears the material.
+    pub(crate) fn usemtl_name<'a>(&self, name: Option<&'a str>) -> Option<&'a str> {
+        match name {
+            Some("None") if self.none_clears_material => None,
+            name => name,
+        }
+    }
+
+    /// The name for the implicit object, if it isn't the default one.
+    pub(crate) fn implicit_object_name(&self) -> &str {
+        match &self.implicit_object {
+            ImplicitObjectName::Named(name) => name,
+            _ => DEFAULT_OBJECT,
+        }
+    }
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters and `?` any single
+/// character.
+pub(crate) fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The last `*` and the position in `name` it was tried to match up to, for backtracking.
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The position, texture and normal indices assigned to each polygon vertex.
 ///
 /// These appear as `/` separated indices in `.obj` files.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, PartialOrd, Eq, Ord)]
-pub struct IndexTuple(pub usize, pub Option<usize>, pub Option<usize>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexTuple {
+    /// Index into [`ObjData::position`].
+    pub position: usize,
+    /// Index into [`ObjData::texture`], if the vertex has texture coordinates.
+    pub texture: Option<usize>,
+    /// Index into [`ObjData::normal`], if the vertex has a normal.
+    pub normal: Option<usize>,
+}
+
+impl IndexTuple {
+    /// Create a tuple from its position index and optional texture and normal indices.
+    pub fn new(position: usize, texture: Option<usize>, normal: Option<usize>) -> Self {
+        IndexTuple {
+            position,
+            texture,
+            normal,
+        }
+    }
+}
+
+impl From<(usize, Option<usize>, Option<usize>)> for IndexTuple {
+    fn from((position, texture, normal): (usize, Option<usize>, Option<usize>)) -> Self {
+        IndexTuple::new(position, texture, normal)
+    }
+}
+
+impl From<IndexTuple> for (usize, Option<usize>, Option<usize>) {
+    fn from(tuple: IndexTuple) -> Self {
+        (tuple.position, tuple.texture, tuple.normal)
+    }
+}
 
 /// A a simple polygon with arbitrary many vertices.
 ///
 /// Each vertex has an associated tuple of `(position, texture, normal)` indices.
 #[derive(Debug, Clone, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimplePolygon(pub Vec<IndexTuple>);
 
+impl From<Vec<IndexTuple>> for SimplePolygon {
+    fn from(vertices: Vec<IndexTuple>) -> Self {
+        SimplePolygon(vertices)
+    }
+}
+
+impl FromIterator<IndexTuple> for SimplePolygon {
+    fn from_iter<I: IntoIterator<Item = IndexTuple>>(vertices: I) -> Self {
+        SimplePolygon(vertices.into_iter().collect())
+    }
+}
+
+/// A polyline through arbitrary many vertices.
+///
+/// Each vertex has an associated tuple of indices like a polygon vertex, but lines carry no
+/// normals, so the normal index is always `None`.
+#[derive(Debug, Clone, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line(pub Vec<IndexTuple>);
+
+#[cfg(feature = "std")]
 pub trait WriteToBuf {
-    type Error: std::fmt::Display;
+    type Error: fmt::Display;
     fn write_to_buf<W: Write>(&self, out: &mut W) -> Result<(), Self::Error>;
 }
 
-impl std::fmt::Display for IndexTuple {
+/// Serialization into a `fmt::Write`, which is available without `std`.
+///
+/// Every type implementing this also implements [`WriteToBuf`] with the `std` feature.
+pub trait WriteToFmt {
+    fn write_to_fmt<W: fmt::Write>(&self, out: &mut W) -> fmt::Result;
+}
+
+#[cfg(feature = "std")]
+impl<T: WriteToFmt> WriteToBuf for T {
+    type Error = ObjError;
+    fn write_to_buf<W: Write>(&self, out: &mut W) -> Result<(), ObjError> {
+        Ok(write_io(out, |out| self.write_to_fmt(out))?)
+    }
+}
+
+/// Adapts an `io::Write` for writers using `fmt::Write`, keeping the first I/O error.
+#[cfg(feature = "std")]
+pub(crate) struct IoWriter<'a, W> {
+    inner: &'a mut W,
+    error: io::Result<()>,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Err(err);
+            fmt::Error
+        })
+    }
+}
+
+/// Run `write` on an adapter around `out`, returning the I/O error that stopped it, if any.
+#[cfg(feature = "std")]
+pub(crate) fn write_io<W: Write>(
+    out: &mut W,
+    write: impl FnOnce(&mut IoWriter<'_, W>) -> fmt::Result,
+) -> io::Result<()> {
+    let mut writer = IoWriter {
+        inner: out,
+        error: Ok(()),
+    };
+    match write(&mut writer) {
+        Ok(()) => Ok(()),
+        Err(fmt::Error) => match writer.error {
+            Err(err) => Err(err),
+            Ok(()) => Err(io::Error::other("formatter error")),
+        },
+    }
+}
+
+/// Replaces the decimal point in the numbers of vertex attribute statements written to it, see
+/// [`WriteConfig::decimal_separator`].
+#[cfg(feature = "std")]
+struct DecimalSeparator<'a, W> {
+    inner: &'a mut W,
+    separator: char,
+    /// The incomplete line written so far.
+    line: String,
+}
+
+#[cfg(feature = "std")]
+impl<W: fmt::Write> DecimalSeparator<'_, W> {
+    fn write_line(&mut self, end: usize) -> fmt::Result {
+        let line: String = self.line.drain(..end).collect();
+        let keyword = line.split_whitespace().next();
+        if matches!(keyword, Some("v" | "vt" | "vn")) {
+            let mut buf = [0; 4];
+            self.inner
+                .write_str(&line.replace('.', self.separator.encode_utf8(&mut buf)))
+        } else {
+            self.inner.write_str(&line)
+        }
+    }
+
+    /// Write the last line if it has no line terminator.
+    fn finish(mut self) -> fmt::Result {
+        self.write_line(self.line.len())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: fmt::Write> fmt::Write for DecimalSeparator<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.line.push_str(s);
+        while let Some(end) = self.line.find('\n') {
+            self.write_line(end + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for IndexTuple {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0 + 1)?;
-        if let Some(idx) = self.1 {
+        write!(f, "{}", self.position + 1)?;
+        if let Some(idx) = self.texture {
             write!(f, "/{}", idx + 1)?;
         }
-        if let Some(idx) = self.2 {
-            if self.1.is_some() {
+        if let Some(idx) = self.normal {
+            if self.texture.is_some() {
                 write!(f, "/{}", idx + 1)?;
             } else {
                 // requires empty texture coordinate index
@@ -91,9 +776,8 @@ impl std::fmt::Display for IndexTuple {
     }
 }
 
-impl WriteToBuf for SimplePolygon {
-    type Error = ObjError;
-    fn write_to_buf<W: Write>(&self, out: &mut W) -> Result<(), ObjError> {
+impl WriteToFmt for SimplePolygon {
+    fn write_to_fmt<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
         write!(out, "f")?;
         for idx in &self.0 {
             write!(out, " {}", idx)?;
@@ -103,6 +787,17 @@ impl WriteToBuf for SimplePolygon {
     }
 }
 
+impl WriteToFmt for Line {
+    fn write_to_fmt<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        write!(out, "l")?;
+        for idx in &self.0 {
+            write!(out, " {}", idx)?;
+        }
+        writeln!(out)?;
+        Ok(())
+    }
+}
+
 #[cfg(feature = "genmesh")]
 impl SimplePolygon {
     /// Convert a `SimplePolygon` into a `genmesh` `Polygon` of `IndexTuple`s.
@@ -127,43 +822,172 @@ impl std::convert::TryFrom<SimplePolygon> for Polygon<IndexTuple> {
     }
 }
 
+#[cfg(feature = "genmesh")]
+impl Line {
+    /// Iterate over the segments of this polyline as `genmesh` `Line`s of `IndexTuple`s.
+    pub fn genmesh_segments(&self) -> impl Iterator<Item = genmesh::Line<IndexTuple>> + '_ {
+        self.0.windows(2).map(|w| genmesh::Line::new(w[0], w[1]))
+    }
+
+    /// Convert this polyline into its segments as `genmesh` `Line`s of `IndexTuple`s.
+    pub fn into_genmesh(self) -> Vec<genmesh::Line<IndexTuple>> {
+        self.genmesh_segments().collect()
+    }
+}
+
+/// A primitive of a group expressed with `genmesh` types.
+#[cfg(feature = "genmesh")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenmeshPrimitive {
+    Polygon(Polygon<IndexTuple>),
+    Line(genmesh::Line<IndexTuple>),
+}
+
+#[cfg(feature = "genmesh")]
+impl Group {
+    /// Convert all polygons and lines of this group into `genmesh` primitives.
+    ///
+    /// Polygons come first, followed by the segments of every line. Unlike converting only
+    /// `polys`, no element is left out: polygons `genmesh` cannot represent are reported as an
+    /// error instead.
+    pub fn genmesh_primitives(&self) -> Result<Vec<GenmeshPrimitive>, ObjError> {
+        let mut primitives = Vec::with_capacity(self.polys.len() + self.lines.len());
+        for poly in &self.polys {
+            primitives.push(GenmeshPrimitive::Polygon(std::convert::TryFrom::try_from(
+                poly.clone(),
+            )?));
+        }
+        for line in &self.lines {
+            primitives.extend(line.genmesh_segments().map(GenmeshPrimitive::Line));
+        }
+        Ok(primitives)
+    }
+}
+
+/// A `genmesh` polygon tagged with its material, see [`ObjData::genmesh_polygons`].
+#[cfg(feature = "genmesh")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenmeshPolygon {
+    pub polygon: Polygon<IndexTuple>,
+    /// The index of the material in the table returned alongside, if the polygon has one.
+    pub material: Option<usize>,
+}
+
+#[cfg(feature = "genmesh")]
+impl ObjData {
+    /// Convert the polygons of all groups into `genmesh` polygons tagged with their material.
+    ///
+    /// Polygons are returned in order, with a table of the materials they refer to, which are
+    /// merged by name in order of first use like in [`ObjData::to_indexed_buffers_by_material`].
+    /// Polygons `genmesh` cannot represent are reported as an error.
+    pub fn genmesh_polygons(&self) -> Result<(Vec<GenmeshPolygon>, Vec<ObjMaterial>), ObjError> {
+        let mut polygons = Vec::new();
+        let mut materials: Vec<ObjMaterial> = Vec::new();
+        for group in self.objects.iter().flat_map(|o| &o.groups) {
+            let material = group.material.as_ref().map(|material| {
+                match materials.iter().position(|m| m.name() == material.name()) {
+                    Some(index) => index,
+                    None => {
+                        materials.push(material.clone());
+                        materials.len() - 1
+                    }
+                }
+            });
+            for poly in &group.polys {
+                polygons.push(GenmeshPolygon {
+                    polygon: std::convert::TryFrom::try_from(poly.clone())?,
+                    material,
+                });
+            }
+        }
+        Ok((polygons, materials))
+    }
+}
+
 /// Errors parsing or loading a .obj file.
 #[derive(Debug)]
 pub enum ObjError {
+    #[cfg(feature = "std")]
     Io(io::Error),
+    /// A line of the input is not valid UTF-8.
+    ///
+    /// Only returned when parsing byte slices, reading invalid UTF-8 from a reader is an `Io`
+    /// error.
+    InvalidUtf8 { line_number: usize },
     /// One of the arguments to `f` is malformed.
-    MalformedFaceGroup {
-        line_number: usize,
-        group: String,
-    },
+    MalformedFaceGroup { line_number: usize, group: String },
     /// An argument list either has unparsable arguments or is
     /// missing one or more arguments.
-    ArgumentListFailure {
-        line_number: usize,
-        list: String,
-    },
+    ArgumentListFailure { line_number: usize, list: String },
     /// Command found that is not in the .obj spec.
-    UnexpectedCommand {
-        line_number: usize,
-        command: String,
-    },
+    UnexpectedCommand { line_number: usize, command: String },
     /// `mtllib` command issued, but no name was specified.
-    MissingMTLName {
-        line_number: usize,
-    },
+    MissingMTLName { line_number: usize },
     /// Vertices are referenced using positive 1-based indices or negative relative indices.
     ///
     /// Zero indices are invalid.
-    ZeroVertexNumber {
+    ZeroVertexNumber { line_number: usize },
+    /// A face or line refers to a vertex attribute that doesn't exist.
+    ///
+    /// `count` is the number of elements of the referenced attribute. Only returned when
+    /// [`LoadConfig::check_indices`] is set.
+    IndexOutOfRange {
+        line_number: usize,
+        index: isize,
+        count: usize,
+    },
+    /// A number can't be represented by the type it is read as.
+    ///
+    /// Only returned with [`OutOfRangePolicy::Error`], or for indices with [`OutOfRangePolicy::Clamp`].
+    NumberOutOfRange { line_number: usize, number: String },
+    /// An object has the same name as an earlier one, see [`DuplicateObjectPolicy::Error`].
+    DuplicateObject { line_number: usize, name: String },
+    /// A face has more vertices than [`LoadConfig::max_face_vertices`] allows.
+    TooManyFaceVertices {
         line_number: usize,
+        count: usize,
+        max: usize,
+    },
+    /// A line is longer than [`LoadConfig::max_line_length`] allows.
+    LineTooLong { line_number: usize, max: usize },
+    /// A polygon or line to be written refers to a vertex attribute that doesn't exist.
+    ///
+    /// `object` and `group` are indices into [`ObjData::objects`] and [`Object::groups`],
+    /// `element` counts the polygons of the group followed by its lines, in the order they are
+    /// written. `index` is the 0-based attribute index and `count` the number of attributes.
+    ElementIndexOutOfRange {
+        object: usize,
+        group: usize,
+        element: usize,
+        index: usize,
+        count: usize,
+    },
+    /// A polygon or line to be written has no vertices, see [`ObjError::ElementIndexOutOfRange`].
+    EmptyElement {
+        object: usize,
+        group: usize,
+        element: usize,
     },
     /// [`genmesh::Polygon`] only supports triangles and squares.
     #[cfg(feature = "genmesh")]
-    GenMeshWrongNumberOfVertsInPolygon {
-        vert_count: usize,
-    },
+    GenMeshWrongNumberOfVertsInPolygon { vert_count: usize },
+}
+
+impl ObjError {
+    /// Whether the error is about the syntax of a single line, which lenient parsing skips.
+    pub(crate) fn is_malformed_line(&self) -> bool {
+        matches!(
+            self,
+            ObjError::InvalidUtf8 { .. }
+                | ObjError::MalformedFaceGroup { .. }
+                | ObjError::ArgumentListFailure { .. }
+                | ObjError::MissingMTLName { .. }
+                | ObjError::ZeroVertexNumber { .. }
+        )
+    }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for ObjError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -176,7 +1000,11 @@ impl std::error::Error for ObjError {
 impl fmt::Display for ObjError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            #[cfg(feature = "std")]
             ObjError::Io(err) => write!(f, "I/O error loading a .obj file: {}", err),
+            ObjError::InvalidUtf8 { line_number } => {
+                write!(f, "Invalid UTF-8 in the .obj source. (line: {})", line_number)
+            }
             ObjError::MalformedFaceGroup { line_number, group } => write!(
                 f,
                 "One of the arguments to `f` is malformed (line: {}, group: {})",
@@ -200,6 +1028,53 @@ impl fmt::Display for ObjError {
             ObjError::ZeroVertexNumber { line_number } => {
                 write!(f, "Zero vertex numbers are invalid. (line: {})", line_number)
             }
+            ObjError::IndexOutOfRange {
+                line_number,
+                index,
+                count,
+            } => write!(
+                f,
+                "Vertex number is out of range. (line: {}, number: {}, count: {})",
+                line_number, index, count
+            ),
+            ObjError::NumberOutOfRange { line_number, number } => {
+                write!(f, "Number is out of range. (line: {}, number: {})", line_number, number)
+            }
+            ObjError::DuplicateObject { line_number, name } => write!(
+                f,
+                "An object with the same name was defined before. (line: {}, name: {})",
+                line_number, name
+            ),
+            ObjError::TooManyFaceVertices {
+                line_number,
+                count,
+                max,
+            } => write!(
+                f,
+                "Face has too many vertices. (line: {}, vertices: {}, maximum: {})",
+                line_number, count, max
+            ),
+            ObjError::LineTooLong { line_number, max } => write!(
+                f,
+                "Line is too long. (line: {}, maximum bytes: {})",
+                line_number, max
+            ),
+            ObjError::ElementIndexOutOfRange {
+                object,
+                group,
+                element,
+                index,
+                count,
+            } => write!(
+                f,
+                "A polygon or line refers to a missing vertex attribute. (object: {}, group: {}, element: {}, index: {}, count: {})",
+                object, group, element, index, count
+            ),
+            ObjError::EmptyElement { object, group, element } => write!(
+                f,
+                "A polygon or line has no vertices. (object: {}, group: {}, element: {})",
+                object, group, element
+            ),
             #[cfg(feature = "genmesh")]
             ObjError::GenMeshWrongNumberOfVertsInPolygon { vert_count } => write!(
                 f,
@@ -210,6 +1085,93 @@ impl fmt::Display for ObjError {
     }
 }
 
+/// A recoverable problem found while loading, see [`PushParser::warnings`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadWarning {
+    /// Numbers on the line were read with `,` as the decimal separator.
+    CommaDecimal { line_number: usize },
+    /// Numbers on the line were clamped, see [`OutOfRangePolicy::Clamp`].
+    ClampedNumber { line_number: usize },
+    /// The line was skipped for being malformed, see [`LoadConfig::strict`].
+    ///
+    /// `error` describes the [`ObjError`] that reading the line gave.
+    SkippedLine { line_number: usize, error: String },
+    /// A summary comment, such as `# 8 vertices`, doesn't match the number of statements since
+    /// the previous summary of the same kind, see [`LoadConfig::faithful`].
+    ///
+    /// `expected` is the number given by the comment, or `None` if statements follow the last
+    /// summary of their kind at the end of the file, which happens if it was cut off. `found` is
+    /// the number of statements read.
+    SummaryMismatch {
+        line_number: usize,
+        summary: String,
+        expected: Option<usize>,
+        found: usize,
+    },
+}
+
+/// The words following the count in summary comments, with the statements they count: `v`, `vt`,
+/// `vn`, `f` and `l`, or only `f`.
+///
+/// The writer uses the first summary of each of the first four kinds.
+pub(crate) const SUMMARIES: [(&str, usize); 6] = [
+    ("vertices", 0),
+    ("texture coords", 1),
+    ("texture vertices", 1),
+    ("vertex normals", 2),
+    ("elements", 3),
+    ("faces", 4),
+];
+
+/// Write a summary comment for `count` statements, unless there are none.
+pub(crate) fn write_summary(out: &mut impl fmt::Write, count: usize, summary: &str) -> fmt::Result {
+    if count > 0 {
+        writeln!(out, "# {} {}", count, summary)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadWarning::CommaDecimal { line_number } => {
+                write!(f, "Numbers use a decimal comma. (line: {})", line_number)
+            }
+            LoadWarning::ClampedNumber { line_number } => {
+                write!(f, "Out of range numbers were clamped. (line: {})", line_number)
+            }
+            LoadWarning::SkippedLine { line_number, error } => {
+                write!(
+                    f,
+                    "Malformed line was skipped. (line: {}, error: {})",
+                    line_number, error
+                )
+            }
+            LoadWarning::SummaryMismatch {
+                line_number,
+                summary,
+                expected: Some(expected),
+                found,
+            } => write!(
+                f,
+                "Summary comment doesn't match the statements before it. (line: {}, summary: {}, expected: {}, found: {})",
+                line_number, summary, expected, found
+            ),
+            LoadWarning::SummaryMismatch {
+                line_number,
+                summary,
+                expected: None,
+                found,
+            } => write!(
+                f,
+                "File ends without a summary comment, it may be cut off. (line: {}, summary: {}, found: {})",
+                line_number, summary, found
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl From<io::Error> for ObjError {
     fn from(e: Error) -> Self {
         Self::Io(e)
@@ -220,17 +1182,21 @@ impl From<io::Error> for ObjError {
 ///
 /// The `Vec` items are tuples with first component being the the .mtl file, and the second its
 /// corresponding error.
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub struct MtlLibsLoadError(pub Vec<(String, MtlError)>);
 
+#[cfg(feature = "std")]
 impl std::error::Error for MtlLibsLoadError {}
 
+#[cfg(feature = "std")]
 impl fmt::Display for MtlLibsLoadError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "One of the material libraries failed to load: {:?}", self.0)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<Vec<(String, MtlError)>> for MtlLibsLoadError {
     fn from(e: Vec<(String, MtlError)>) -> Self {
         MtlLibsLoadError(e)
@@ -238,33 +1204,82 @@ impl From<Vec<(String, MtlError)>> for MtlLibsLoadError {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Object {
     /// Name of the object assigned by the `o ...` command in the `.obj` file.
-    pub name: String,
+    pub name: Arc<str>,
     /// Groups belonging to this object.
     pub groups: Vec<Group>,
 }
 
 impl Object {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
         Object {
-            name,
+            name: name.into(),
             groups: Vec::new(),
         }
     }
+
+    /// Recompute group indices after groups were removed or reordered.
+    ///
+    /// A group continues its predecessor (and gets the next index) only if both share a name.
+    #[cfg(feature = "std")]
+    pub(crate) fn renumber_groups(&mut self) {
+        let mut prev: Option<(&str, usize)> = None;
+        let mut indices = Vec::with_capacity(self.groups.len());
+        for group in &self.groups {
+            let index = match prev {
+                Some((name, index)) if name == &*group.name => index + 1,
+                _ => 0,
+            };
+            indices.push(index);
+            prev = Some((&group.name, index));
+        }
+        for (group, index) in self.groups.iter_mut().zip(indices) {
+            group.index = index;
+        }
+    }
 }
 
-impl WriteToBuf for Object {
-    type Error = ObjError;
+impl WriteToFmt for Object {
     /// Serialize this `Object` into the given writer.
-    fn write_to_buf<W: Write>(&self, out: &mut W) -> Result<(), ObjError> {
-        if self.name.as_str() != DEFAULT_OBJECT {
+    fn write_to_fmt<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        self.write_header(out)?;
+        self.write_groups(None, None, out)
+    }
+}
+
+impl Object {
+    /// Write the `o` statement, which is omitted for the default object.
+    pub(crate) fn write_header<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        if &*self.name != DEFAULT_OBJECT {
             writeln!(out, "o {}", self.name)?;
         }
+        Ok(())
+    }
 
+    /// The number of polygons and lines of all groups.
+    pub(crate) fn element_count(&self) -> usize {
+        self.groups.iter().map(|g| g.polys.len() + g.lines.len()).sum()
+    }
+
+    /// Write the groups of this object, given the render state in effect before them, see
+    /// [`Group::write_header`].
+    ///
+    /// With `relative_to` set, indices are written relative to the ends of attribute arrays of
+    /// the given lengths, see [`Group::write_elements`].
+    pub(crate) fn write_groups<W: fmt::Write>(
+        &self,
+        state: Option<&RenderState>,
+        relative_to: Option<[usize; 3]>,
+        out: &mut W,
+    ) -> fmt::Result {
         let mut group_iter = self.groups.iter().peekable();
+        let mut previous = None;
         while let Some(group) = group_iter.next() {
-            group.write_to_buf(out)?;
+            group.write_header(previous, previous.map(Group::end_render_state).or(state), out)?;
+            group.write_elements(relative_to, out)?;
+            previous = Some(group);
 
             // Below we check that groups with `index > 0` have the same name as their predecessors
             // which enables us to merge the two by omitting the additional `g ...` command.
@@ -282,27 +1297,43 @@ impl WriteToBuf for Object {
 ///
 /// The material name is replaced by the actual material data when the material libraries are
 /// laoded if a match is found.
+///
+/// With the `serde` feature, loaded materials are serialized by value, so deserialized groups no
+/// longer share them with the material libraries.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjMaterial {
     /// A reference to a material as a material name.
-    Ref(String),
+    Ref(Arc<str>),
     /// A complete `Material` object loaded from a .mtl file in place of the material reference.
     Mtl(Arc<Material>),
 }
 
 impl ObjMaterial {
-    fn name(&self) -> &str {
+    /// The name of the referenced material, whether or not it has been loaded.
+    pub fn name(&self) -> &str {
         match self {
-            ObjMaterial::Ref(name) => name.as_str(),
+            ObjMaterial::Ref(name) => name,
             ObjMaterial::Mtl(material) => material.name.as_str(),
         }
     }
+
+    /// The loaded material, if it has been bound to this reference.
+    ///
+    /// This borrows the material without touching the reference count of the `Arc`.
+    pub fn as_material(&self) -> Option<&Material> {
+        match self {
+            ObjMaterial::Ref(_) => None,
+            ObjMaterial::Mtl(material) => Some(material),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Group {
     /// Name of the group assigned by the `g ...` command in the `.obj` file.
-    pub name: String,
+    pub name: Arc<str>,
     /// An index is used to tell groups apart that share the same name.
     ///
     /// This doesn't appear explicitly in the `.obj` file, but is used here to simplify groups by
@@ -310,52 +1341,443 @@ pub struct Group {
     pub index: usize,
     /// Material assigned to this group via the `usemtl ...` command in the `.obj` file.
     ///
-    /// After material libs are loaded, this will point to the loaded `Material` struct.
+    /// After material libs are loaded, this will point to the loaded `Material` struct. `None`
+    /// before any `usemtl` and after one that clears the material, see
+    /// [`LoadConfig::none_clears_material`].
     pub material: Option<ObjMaterial>,
     /// A list of polygons appearing as `f ...` in the `.obj` file.
     pub polys: Vec<SimplePolygon>,
+    /// A list of polylines appearing as `l ...` in the `.obj` file.
+    pub lines: Vec<Line>,
+    /// The `s`, `bevel`, `c_interp`, `d_interp`, `lod` and `usemap` settings at the start of the
+    /// group, see [`RenderState`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub render_state: RenderState,
+    /// The changes of the render state between the polygons and lines of the group, ordered by
+    /// position, see [`StateChange`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub state_changes: Vec<StateChange>,
+}
+
+/// The render attributes set by stateful statements, which apply to all following polygons and
+/// lines until they are changed again.
+///
+/// While loading, the current state carries over into groups started by `g` as well as into
+/// following objects. A change inside a group is recorded in [`Group::state_changes`] without
+/// affecting the group structure. When writing, the statements whose value differs from the state
+/// in effect before, or from the default state at the start of the file, are emitted after `g` and
+/// `usemtl` or between the polygons they apply to.
+///
+/// Statements with arguments that can't be parsed leave the state unchanged and are reported as
+/// [`LoadWarning::SkippedLine`].
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RenderState {
+    /// The smoothing group given by `s`, or 0 for `s off`.
+    pub smoothing_group: u32,
+    /// Whether `bevel on` applies bevel interpolation.
+    pub bevel: bool,
+    /// Whether `c_interp on` applies color interpolation.
+    pub c_interp: bool,
+    /// Whether `d_interp on` applies dissolve interpolation.
+    pub d_interp: bool,
+    /// The level of detail given by `lod`, or 0 to display everything.
+    pub lod: u32,
+    /// The texture map given by `usemap`, or `None` for `usemap off`.
+    pub texture_map: Option<Arc<str>>,
+}
+
+/// A change of the render state inside a group, see [`Group::state_changes`].
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateChange {
+    /// The number of polygons of the group before the change.
+    pub polys: usize,
+    /// The number of lines of the group before the change.
+    pub lines: usize,
+    /// The render state of the polygons and lines from this position on.
+    pub state: RenderState,
+}
+
+/// Change the render state after `polys` polygons and `lines` lines of a group with the initial
+/// state `initial` and the changes `changes`.
+///
+/// A change at the start replaces the initial state, a change at the position of the last one
+/// replaces it, and changes to the state already in effect are dropped.
+pub(crate) fn change_render_state(
+    initial: &mut RenderState,
+    changes: &mut Vec<StateChange>,
+    polys: usize,
+    lines: usize,
+    state: RenderState,
+) {
+    if changes.last().is_some_and(|c| c.polys == polys && c.lines == lines) {
+        changes.pop();
+    }
+    if changes.is_empty() && polys == 0 && lines == 0 {
+        *initial = state;
+    } else if changes.last().map_or(&*initial, |c| &c.state) != &state {
+        changes.push(StateChange { polys, lines, state });
+    }
+}
+
+impl RenderState {
+    /// Apply the statement `keyword value`, naming texture maps with `name`.
+    ///
+    /// Returns `false` without changing the state if `keyword` is not one of the statements of
+    /// the render state.
+    pub(crate) fn apply(
+        &mut self,
+        line_number: usize,
+        keyword: &str,
+        value: Option<&str>,
+        name: impl FnOnce(&str) -> Arc<str>,
+    ) -> Result<bool, ObjError> {
+        let invalid = || ObjError::ArgumentListFailure {
+            line_number,
+            list: String::from(value.unwrap_or_default()),
+        };
+        let flag = || match value {
+            Some("on") => Ok(true),
+            Some("off") => Ok(false),
+            _ => Err(invalid()),
+        };
+        match keyword {
+            "s" => {
+                self.smoothing_group = match value {
+                    Some("off") => 0,
+                    value => value.and_then(|v| v.parse().ok()).ok_or_else(invalid)?,
+                }
+            }
+            "bevel" => self.bevel = flag()?,
+            "c_interp" => self.c_interp = flag()?,
+            "d_interp" => self.d_interp = flag()?,
+            "lod" => self.lod = value.and_then(|v| v.parse().ok()).ok_or_else(invalid)?,
+            "usemap" => {
+                self.texture_map = match value.ok_or_else(invalid)? {
+                    "off" => None,
+                    map => Some(name(map)),
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Write the statements of the settings that differ from `previous`.
+    pub(crate) fn write_changes<W: fmt::Write>(&self, previous: &RenderState, out: &mut W) -> fmt::Result {
+        let flag = |on: bool| if on { "on" } else { "off" };
+        if self.smoothing_group != previous.smoothing_group {
+            match self.smoothing_group {
+                0 => writeln!(out, "s off")?,
+                id => writeln!(out, "s {}", id)?,
+            }
+        }
+        if self.bevel != previous.bevel {
+            writeln!(out, "bevel {}", flag(self.bevel))?;
+        }
+        if self.c_interp != previous.c_interp {
+            writeln!(out, "c_interp {}", flag(self.c_interp))?;
+        }
+        if self.d_interp != previous.d_interp {
+            writeln!(out, "d_interp {}", flag(self.d_interp))?;
+        }
+        if self.lod != previous.lod {
+            writeln!(out, "lod {}", self.lod)?;
+        }
+        if self.texture_map != previous.texture_map {
+            writeln!(out, "usemap {}", self.texture_map.as_deref().unwrap_or("off"))?;
+        }
+        Ok(())
+    }
 }
 
 impl Group {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
         Group {
-            name,
+            name: name.into(),
             index: 0,
             material: None,
             polys: Vec::new(),
+            lines: Vec::new(),
+            render_state: RenderState::default(),
+            state_changes: Vec::new(),
+        }
+    }
+
+    /// Check whether this group contains neither polygons nor lines.
+    pub fn is_empty(&self) -> bool {
+        self.polys.is_empty() && self.lines.is_empty()
+    }
+
+    /// The render state of the polygon at `index`.
+    pub fn poly_render_state(&self, index: usize) -> &RenderState {
+        let change = self.state_changes.iter().rev().find(|c| c.polys <= index);
+        change.map_or(&self.render_state, |c| &c.state)
+    }
+
+    /// The render state of the line at `index`.
+    pub fn line_render_state(&self, index: usize) -> &RenderState {
+        let change = self.state_changes.iter().rev().find(|c| c.lines <= index);
+        change.map_or(&self.render_state, |c| &c.state)
+    }
+
+    /// The render state in effect after the last polygon and line of this group.
+    pub fn end_render_state(&self) -> &RenderState {
+        self.state_changes.last().map_or(&self.render_state, |c| &c.state)
+    }
+
+    /// Set the render state of the polygons and lines pushed to this group from now on.
+    pub fn set_render_state(&mut self, state: RenderState) {
+        let (polys, lines) = (self.polys.len(), self.lines.len());
+        change_render_state(&mut self.render_state, &mut self.state_changes, polys, lines, state);
+    }
+
+    /// Iterate over the runs of polygons and lines sharing a render state, in order.
+    ///
+    /// There is one run for the start of the group and one for every change, so runs can be empty.
+    pub fn state_runs(&self) -> impl Iterator<Item = (&RenderState, &[SimplePolygon], &[Line])> + '_ {
+        let starts = iter::once((0, 0, &self.render_state))
+            .chain(self.state_changes.iter().map(|c| (c.polys, c.lines, &c.state)));
+        let ends = self.state_changes.iter().map(|c| (c.polys, c.lines));
+        let ends = ends.chain(iter::once((self.polys.len(), self.lines.len())));
+        starts.zip(ends).map(move |((p0, l0, state), (p1, l1))| {
+            let (p0, l0) = (p0.min(self.polys.len()), l0.min(self.lines.len()));
+            let (p1, l1) = (p1.min(self.polys.len()).max(p0), l1.min(self.lines.len()).max(l0));
+            (state, &self.polys[p0..p1], &self.lines[l0..l1])
+        })
+    }
+
+    /// Empty this group to go on with it as the group with the next index, starting with the render
+    /// state in effect at its end.
+    pub(crate) fn continue_empty(&mut self) {
+        self.index += 1;
+        self.render_state = self.end_render_state().clone();
+        self.state_changes.clear();
+        self.polys.clear();
+        self.lines.clear();
+    }
+
+    /// Replace every polygon by the polygons `f` pushes for it, moving the render state changes
+    /// along so they stay between the same polygons.
+    #[cfg(feature = "std")]
+    pub(crate) fn map_polys(&mut self, mut f: impl FnMut(SimplePolygon, &mut Vec<SimplePolygon>)) {
+        let mut polys = Vec::with_capacity(self.polys.len());
+        let mut starts = Vec::with_capacity(self.polys.len() + 1);
+        for poly in core::mem::take(&mut self.polys) {
+            starts.push(polys.len());
+            f(poly, &mut polys);
+        }
+        starts.push(polys.len());
+        self.polys = polys;
+        for change in core::mem::take(&mut self.state_changes) {
+            let polys = starts[change.polys.min(starts.len() - 1)];
+            change_render_state(
+                &mut self.render_state,
+                &mut self.state_changes,
+                polys,
+                change.lines,
+                change.state,
+            );
+        }
+    }
+
+    /// Keep only the polygons for which `keep` returns `true`, see [`Group::map_polys`].
+    #[cfg(feature = "std")]
+    pub(crate) fn retain_polys(&mut self, mut keep: impl FnMut(&SimplePolygon) -> bool) {
+        self.map_polys(|poly, out| {
+            if keep(&poly) {
+                out.push(poly)
+            }
+        })
+    }
+
+    /// Move the polygons, lines and render state changes of `other` to the end of this group.
+    #[cfg(feature = "std")]
+    pub(crate) fn append(&mut self, other: Group) {
+        let (polys, lines) = (self.polys.len(), self.lines.len());
+        self.set_render_state(other.render_state);
+        self.polys.extend(other.polys);
+        self.lines.extend(other.lines);
+        for change in other.state_changes {
+            let (p, l) = (polys + change.polys, lines + change.lines);
+            change_render_state(&mut self.render_state, &mut self.state_changes, p, l, change.state);
         }
     }
 }
 
-impl WriteToBuf for Group {
-    type Error = ObjError;
-    /// Serialize this `Group` into the given writer.
-    fn write_to_buf<W: Write>(&self, out: &mut W) -> Result<(), ObjError> {
-        // When index is greater than 0, we know that this group is the same as the previous group,
-        // so don't bother declaring a new one.
-        if self.index == 0 {
-            writeln!(out, "g {}", self.name)?;
-        }
+impl WriteToFmt for Group {
+    /// Serialize this `Group` into the given writer.
+    fn write_to_fmt<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        self.write_header(None, None, out)?;
+        self.write_elements(None, out)
+    }
+}
+
+impl Group {
+    /// Write the `g`, `usemtl` and render state statements preceding the polygons and lines of
+    /// this group.
+    ///
+    /// `previous` is the group written before in the same object, whose material is cleared with
+    /// a bare `usemtl` if this group has none. `state` is the render state at the end of the group
+    /// written before in any object, or `None` at the start of the file, and only the changes to
+    /// it are written.
+    pub(crate) fn write_header<W: fmt::Write>(
+        &self,
+        previous: Option<&Group>,
+        state: Option<&RenderState>,
+        out: &mut W,
+    ) -> fmt::Result {
+        // When index is greater than 0, we know that this group is the same as the previous group,
+        // so don't bother declaring a new one.
+        if self.index == 0 {
+            writeln!(out, "g {}", self.name)?;
+        }
+
+        match self.material {
+            Some(ObjMaterial::Ref(ref name)) => writeln!(out, "usemtl {}", name)?,
+            Some(ObjMaterial::Mtl(ref mtl)) => writeln!(out, "usemtl {}", mtl.name)?,
+            None if previous.is_some_and(|g| g.material.is_some()) => writeln!(out, "usemtl")?,
+            None => {}
+        }
+
+        self.render_state
+            .write_changes(state.unwrap_or(&RenderState::default()), out)
+    }
+
+    /// Write the polygons and lines of this group, with the changes of the render state between
+    /// them.
+    ///
+    /// With `relative_to` set, indices are written as negative numbers counting back from the
+    /// ends of attribute arrays of the given lengths.
+    pub(crate) fn write_elements<W: fmt::Write>(&self, relative_to: Option<[usize; 3]>, out: &mut W) -> fmt::Result {
+        let mut previous = &self.render_state;
+        for (state, polys, lines) in self.state_runs() {
+            state.write_changes(previous, out)?;
+            write_elements(polys, lines, relative_to, out)?;
+            previous = state;
+        }
+        Ok(())
+    }
+}
+
+/// Write `polys` followed by `lines`, see [`Group::write_elements`].
+pub(crate) fn write_elements<W: fmt::Write>(
+    polys: &[SimplePolygon],
+    lines: &[Line],
+    relative_to: Option<[usize; 3]>,
+    out: &mut W,
+) -> fmt::Result {
+    let lens = match relative_to {
+        Some(lens) => lens,
+        None => {
+            for poly in polys {
+                poly.write_to_fmt(out)?;
+            }
+            for line in lines {
+                line.write_to_fmt(out)?;
+            }
+            return Ok(());
+        }
+    };
+
+    let polys = polys.iter().map(|poly| ("f", &poly.0[..]));
+    for (keyword, tuples) in polys.chain(lines.iter().map(|line| ("l", &line.0[..]))) {
+        write!(out, "{}", keyword)?;
+        for tuple in tuples {
+            write!(out, " -{}", lens[0] - tuple.position)?;
+            match (tuple.texture, tuple.normal) {
+                (Some(t), Some(n)) => write!(out, "/-{}/-{}", lens[1] - t, lens[2] - n)?,
+                (Some(t), None) => write!(out, "/-{}", lens[1] - t)?,
+                (None, Some(n)) => write!(out, "//-{}", lens[2] - n)?,
+                (None, None) => (),
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// A floating point type vertex positions can be stored as.
+///
+/// Implemented for `f32` and `f64`.
+pub trait Scalar: Copy + Default + FromStr + fmt::Display + fmt::Debug + PartialEq {
+    /// Whether the value is infinite or subnormal, see [`LoadConfig::out_of_range`].
+    ///
+    /// Types that have neither can keep the default, which is always `false`.
+    fn is_out_of_range(self) -> bool {
+        false
+    }
+
+    /// The closest value that is neither infinite nor subnormal.
+    fn clamp_to_range(self) -> Self {
+        self
+    }
+
+    /// A bit pattern identifying the value, used to find equal positions for
+    /// [`LoadConfig::dedup_attributes`].
+    ///
+    /// Types that can't provide one can keep the default, which is always `None`, so that their
+    /// positions are never merged.
+    fn exact_bits(self) -> Option<u64> {
+        None
+    }
+
+    /// The value as an `f64`, used to report positions to [`LoadConfig::on_statement`].
+    ///
+    /// Types that can't provide one can keep the default, which is always `None`.
+    fn to_f64(self) -> Option<f64> {
+        None
+    }
+}
+
+macro_rules! impl_scalar {
+    ($t:ident) => {
+        impl Scalar for $t {
+            fn is_out_of_range(self) -> bool {
+                self.is_infinite() || self.is_subnormal()
+            }
 
-        match self.material {
-            Some(ObjMaterial::Ref(ref name)) => writeln!(out, "usemtl {}", name)?,
-            Some(ObjMaterial::Mtl(ref mtl)) => writeln!(out, "usemtl {}", mtl.name)?,
-            None => {}
-        }
+            fn clamp_to_range(self) -> Self {
+                if self.is_infinite() {
+                    $t::MAX.copysign(self)
+                } else if self.is_subnormal() {
+                    (0.0 as $t).copysign(self)
+                } else {
+                    self
+                }
+            }
 
-        for poly in &self.polys {
-            poly.write_to_buf(out)?;
-        }
+            fn exact_bits(self) -> Option<u64> {
+                Some(self.to_bits() as u64)
+            }
 
-        Ok(())
-    }
+            fn to_f64(self) -> Option<f64> {
+                Some(self.into())
+            }
+        }
+    };
 }
 
+impl_scalar!(f32);
+impl_scalar!(f64);
+
 /// The data model associated with each `Obj` file.
-#[derive(Clone, Debug, PartialEq)]
-pub struct ObjData {
+///
+/// Positions are stored as `f32` by default. Use [`ObjData64`] to keep them in double precision,
+/// e.g. for survey-scale coordinates that `f32` cannot represent accurately.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjData<S = f32> {
     /// Vertex positions.
-    pub position: Vec<[f32; 3]>,
+    pub position: Vec<[S; 3]>,
+    /// RGB vertex colors given by the common `v x y z r g b` extension.
+    ///
+    /// Either empty or as long as `position`, with each entry belonging to the position of the
+    /// same index. Positions listed without a color get white.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color: Vec<[f32; 3]>,
     /// 2D texture coordinates.
     pub texture: Vec<[f32; 2]>,
     /// A set of normals.
@@ -365,22 +1787,219 @@ pub struct ObjData {
     pub objects: Vec<Object>,
     /// The set of all `mtllib` references to .mtl files.
     pub material_libs: Vec<Mtl>,
+    /// The file given by `shadow_obj`, whose geometry casts the shadows of this one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shadow_obj: Option<String>,
+    /// The file given by `trace_obj`, whose geometry is used instead of this one for ray traced
+    /// reflections and refractions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub trace_obj: Option<String>,
+    /// The material of groups without a `usemtl` statement, see [`Group::material_ref`].
+    ///
+    /// This is not written to .obj files, set it here or with [`LoadConfig::default_material`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub default_material: Option<Arc<Material>>,
+    /// Units, up axis and authoring application, as given by the header comments of the file.
+    ///
+    /// Written back as comments, see [`ObjMetadata`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: ObjMetadata,
+    /// The lines of the source file, if it was loaded with [`LoadConfig::faithful`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub layout: Option<SourceLayout>,
 }
 
-impl Default for ObjData {
-    fn default() -> Self {
-        ObjData {
-            position: Vec::new(),
-            texture: Vec::new(),
-            normal: Vec::new(),
-            objects: Vec::new(),
-            material_libs: Vec::new(),
+/// Obj data with double precision vertex positions.
+///
+/// Everything except the positions is the same as in [`ObjData`]. Parse it with
+/// [`ObjData::load_buf_f64`] and convert back with [`ObjData::to_f32`] to use the mesh
+/// processing functions.
+pub type ObjData64 = ObjData<f64>;
+
+/// A signed coordinate axis.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+/// Unit names recognized in comments, with the length of one unit in meters.
+const UNITS: &[(&str, &[&str], f32)] = &[
+    ("millimeters", &["mm", "millimeter", "millimetre", "millimetres"], 0.001),
+    ("centimeters", &["cm", "centimeter", "centimetre", "centimetres"], 0.01),
+    ("meters", &["m", "meter", "metre", "metres"], 1.0),
+    ("kilometers", &["km", "kilometer", "kilometre", "kilometres"], 1000.0),
+    ("inches", &["in", "inch"], 0.0254),
+    ("feet", &["ft", "foot"], 0.3048),
+];
+
+/// Conventions of a file that the format itself has no statements for.
+///
+/// When parsing, these are taken from the comments at the top of the file, before the first
+/// statement. Besides the `Units:`, `Up axis:` and `Application:` comments emitted by the writer,
+/// the headers of Blender, 3ds Max and Maya as well as `Exported by ...` lines are recognized.
+/// Nothing is converted, use e.g. [`ObjData::convert_coordinates`] with the values found here.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjMetadata {
+    /// The length of one unit in meters, e.g. `0.01` for centimeters.
+    pub meters_per_unit: Option<f32>,
+    /// The axis pointing up.
+    pub up_axis: Option<Axis>,
+    /// The application that wrote the file, e.g. `Blender v2.93.1`.
+    pub application: Option<String>,
+}
+
+impl ObjMetadata {
+    /// Whether none of the values are known.
+    pub fn is_empty(&self) -> bool {
+        *self == ObjMetadata::default()
+    }
+
+    /// Set the length of one unit in meters.
+    pub fn meters_per_unit(mut self, meters: f32) -> Self {
+        self.meters_per_unit = Some(meters);
+        self
+    }
+
+    /// Set the axis pointing up.
+    pub fn up_axis(mut self, axis: Axis) -> Self {
+        self.up_axis = Some(axis);
+        self
+    }
+
+    /// Set the application that wrote the file.
+    pub fn application(mut self, application: impl Into<String>) -> Self {
+        self.application = Some(application.into());
+        self
+    }
+
+    /// Take whatever the given header comment, without its leading `#`, tells about the file.
+    /// Values already known are kept.
+    pub(crate) fn read_comment(&mut self, comment: &str) {
+        let comment = comment.trim();
+        if comment.starts_with(GENERATED_BY) {
+            return;
+        }
+        let lower = comment.to_ascii_lowercase();
+
+        if let Some((key, value)) = comment.split_once(':') {
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "units" | "unit" => {
+                    if self.meters_per_unit.is_none() {
+                        self.meters_per_unit = parse_units(value);
+                    }
+                    return;
+                }
+                "up axis" | "upaxis" | "up" => {
+                    if self.up_axis.is_none() {
+                        self.up_axis = parse_axis(value);
+                    }
+                    return;
+                }
+                "application" | "generator" | "exporter" => {
+                    self.set_application(value);
+                    return;
+                }
+                _ => (),
+            }
+        }
+
+        if lower.starts_with("blender ") {
+            // `Blender v2.93.1 OBJ File: ''` up to 2.9x, `Blender 4.1.0` since.
+            self.set_application(comment.split(" OBJ File").next().unwrap_or(comment));
+        } else if lower.starts_with("3ds max ") {
+            // `3ds Max Wavefront OBJ Exporter v0.97b - (c)2007 guruware`
+            self.set_application(comment.split(" - ").next().unwrap_or(comment));
+        } else if let Some(units) = lower
+            .strip_prefix("this file uses ")
+            .and_then(|rest| rest.split_once(" as units"))
+        {
+            // Maya: `This file uses centimeters as units for non-parametric coordinates.`
+            if self.meters_per_unit.is_none() {
+                self.meters_per_unit = parse_units(units.0);
+            }
+        } else if let Some(prefix) = ["exported by ", "created by ", "generated by "]
+            .iter()
+            .find(|prefix| lower.starts_with(*prefix))
+        {
+            self.set_application(&comment[prefix.len()..]);
+        }
+    }
+
+    fn set_application(&mut self, application: &str) {
+        let application = application.trim();
+        if self.application.is_none() && !application.is_empty() {
+            self.application = Some(application.into());
+        }
+    }
+
+    /// Write the known values as comments that [`ObjMetadata::read_comment`] picks up again.
+    pub(crate) fn write(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        if let Some(ref application) = self.application {
+            writeln!(out, "# Application: {}", application)?;
+        }
+        if let Some(meters) = self.meters_per_unit {
+            match UNITS.iter().find(|unit| unit.2 == meters) {
+                Some(unit) => writeln!(out, "# Units: {}", unit.0)?,
+                None => writeln!(out, "# Units: {} m", meters)?,
+            }
+        }
+        if let Some(axis) = self.up_axis {
+            let name = match axis {
+                Axis::PosX => "+X",
+                Axis::NegX => "-X",
+                Axis::PosY => "+Y",
+                Axis::NegY => "-Y",
+                Axis::PosZ => "+Z",
+                Axis::NegZ => "-Z",
+            };
+            writeln!(out, "# Up axis: {}", name)?;
         }
+        Ok(())
+    }
+}
+
+/// Parse a unit name like `centimeters` or a length like `0.5 m`.
+fn parse_units(value: &str) -> Option<f32> {
+    let value = value.trim().to_ascii_lowercase();
+    if let Some(unit) = UNITS
+        .iter()
+        .find(|unit| value == unit.0 || unit.1.contains(&value.as_str()))
+    {
+        return Some(unit.2);
+    }
+    let meters: f32 = value.strip_suffix('m')?.trim().parse().ok()?;
+    Some(meters).filter(|m| m.is_finite() && *m > 0.0)
+}
+
+/// Parse an axis like `Y`, `+Z` or `-X`.
+fn parse_axis(value: &str) -> Option<Axis> {
+    let value = value.trim();
+    let (negative, name) = match value.strip_prefix('-') {
+        Some(name) => (true, name),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    match (name.to_ascii_uppercase().as_str(), negative) {
+        ("X", false) => Some(Axis::PosX),
+        ("X", true) => Some(Axis::NegX),
+        ("Y", false) => Some(Axis::PosY),
+        ("Y", true) => Some(Axis::NegY),
+        ("Z", false) => Some(Axis::PosZ),
+        ("Z", true) => Some(Axis::NegZ),
+        _ => None,
     }
 }
 
 /// A struct used to store `Obj` data as well as its source directory used to load the referenced
 /// .mtl files.
+#[cfg(feature = "std")]
 #[derive(Clone, Debug)]
 pub struct Obj {
     /// The data associated with this `Obj` file.
@@ -404,6 +2023,7 @@ fn normalize(idx: isize, len: usize) -> Option<usize> {
     }
 }
 
+#[cfg(feature = "std")]
 impl Obj {
     /// Save the current `Obj` at the given file path as well as any associated .mtl files.
     ///
@@ -413,20 +2033,35 @@ impl Obj {
     }
 }
 
+#[cfg(feature = "std")]
 impl Obj {
     /// Load an `Obj` file from the given path with the default load configuration.
     pub fn load(path: impl AsRef<Path>) -> Result<Obj, ObjError> {
-        Self::load_with_config(path, LoadConfig::default())
+        Self::load_with(path, LoadConfig::default())
     }
 
     /// Load an `Obj` file from the given path using a custom load configuration.
-    pub fn load_with_config(path: impl AsRef<Path>, config: LoadConfig) -> Result<Obj, ObjError> {
+    pub fn load_with(path: impl AsRef<Path>, config: LoadConfig) -> Result<Obj, ObjError> {
         Obj::load_impl(path.as_ref(), config)
     }
 
-    fn load_impl(path: &Path, config: LoadConfig) -> Result<Obj, ObjError> {
+    #[deprecated(note = "use `Obj::load_with`")]
+    pub fn load_with_config(path: impl AsRef<Path>, config: LoadConfig) -> Result<Obj, ObjError> {
+        Obj::load_with(path, config)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "load_file", level = "debug", skip(config))
+    )]
+    fn load_impl(path: &Path, mut config: LoadConfig) -> Result<Obj, ObjError> {
+        if config.implicit_object == ImplicitObjectName::FileStem {
+            if let Some(stem) = path.file_stem() {
+                config.implicit_object = ImplicitObjectName::Named(stem.to_string_lossy().into_owned());
+            }
+        }
         let f = File::open(path)?;
-        let data = ObjData::load_buf_with_config(&f, config)?;
+        let data = ObjData::load_buf_with(&f, config)?;
 
         // unwrap is safe since we've read this file before.
         let path = path.parent().unwrap().to_owned();
@@ -439,7 +2074,7 @@ impl Obj {
     /// If it encounters an error for an .mtl, it appends its error to the
     /// returning Vec, and tries the rest.
     pub fn load_mtls(&mut self) -> Result<(), MtlLibsLoadError> {
-        self.load_mtls_fn(|obj_dir, mtllib| File::open(&obj_dir.join(mtllib)).map(BufReader::new))
+        self.load_mtls_fn(|obj_dir, mtllib| File::open(obj_dir.join(mtllib)).map(BufReader::new))
     }
 
     /// Loads the .mtl files referenced in the .obj file with user provided loading logic.
@@ -467,6 +2102,8 @@ impl Obj {
         let mut materials = HashMap::new();
 
         for mtl_lib in &mut self.data.material_libs {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("load_mtl", file = %mtl_lib.filename).entered();
             match mtl_lib.reload_with(&self.path, &mut resolve) {
                 Ok(mtl_lib) => {
                     for m in &mtl_lib.materials {
@@ -501,9 +2138,25 @@ impl Obj {
             Err(errs.into())
         }
     }
+
+    /// Use an already parsed material library for the `mtllib` reference `name`.
+    ///
+    /// The library replaces a referenced library with that name, or is added if there is none.
+    /// Material references are then bound like in [`Obj::load_mtls`], and the ones no library
+    /// defines are returned, see [`ObjData::resolve_materials`].
+    pub fn attach_mtl(&mut self, name: impl Into<String>, mut mtl: Mtl) -> Vec<UnresolvedMaterial> {
+        mtl.filename = name.into();
+        let libs = &mut self.data.material_libs;
+        match libs.iter_mut().find(|lib| lib.filename == mtl.filename) {
+            Some(lib) => *lib = mtl,
+            None => libs.push(mtl),
+        }
+        self.data.resolve_materials()
+    }
 }
 
-impl ObjData {
+#[cfg(feature = "std")]
+impl<S: Scalar> ObjData<S> {
     /// Save the current `ObjData` at the given file path as well as any associated .mtl files.
     ///
     /// If a file already exists, it will be overwritten.
@@ -540,34 +2193,324 @@ impl ObjData {
     }
 
     /// Serialize this `Obj` into the given writer.
+    ///
+    /// Nothing is written if [`ObjData::validate_indices`] fails. Numbers are written with `.` as
+    /// the decimal point regardless of the locale, see [`WriteConfig::decimal_separator`] for
+    /// other separators.
     pub fn write_to_buf(&self, out: &mut impl Write) -> Result<(), ObjError> {
-        writeln!(
-            out,
-            "# Generated by the obj Rust library (https://crates.io/crates/obj)."
-        )?;
+        self.validate_indices()?;
+        write_io(out, |out| self.write_to_fmt(out))?;
+        Ok(())
+    }
+}
+
+impl<S: Scalar> ObjData<S> {
+    /// Serialize this `Obj` into the given formatter or string, without requiring `std`.
+    ///
+    /// Unlike [`ObjData::write_to_buf`], this doesn't check the indices of polygons and lines.
+    ///
+    /// Data loaded with [`LoadConfig::faithful`] is written line by line as it was read, see
+    /// [`SourceLayout`]. Otherwise free-form geometry, such as `cstype`, `curv` and `surf` blocks,
+    /// is not written, since it isn't parsed into the data.
+    pub fn write_to_fmt(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        if let Some(layout) = self.layout.as_ref().filter(|layout| layout.covers(self)) {
+            return layout.write(self, out);
+        }
+        self.write_statements(false, out)
+    }
+
+    /// Write all attributes followed by the objects, with summary comments if `summaries` is set,
+    /// see [`WriteConfig::summary_comments`].
+    pub(crate) fn write_statements(&self, summaries: bool, out: &mut impl fmt::Write) -> fmt::Result {
+        self.write_header(out)?;
+        self.write_attributes(summaries, out)?;
+        let mut state = None;
+        for object in &self.objects {
+            object.write_header(out)?;
+            object.write_groups(state, None, out)?;
+            if summaries {
+                write_summary(out, object.element_count(), SUMMARIES[4].0)?;
+            }
+            state = object.groups.last().map(Group::end_render_state).or(state);
+        }
+        self.write_references(out)
+    }
+
+    /// Write all positions, texture coordinates and normals, each followed by a summary comment if
+    /// `summaries` is set.
+    pub(crate) fn write_attributes(&self, summaries: bool, out: &mut impl fmt::Write) -> fmt::Result {
+        self.write_positions(0..self.position.len(), out)?;
+        if summaries {
+            write_summary(out, self.position.len(), SUMMARIES[0].0)?;
+        }
+        self.write_textures(0..self.texture.len(), out)?;
+        if summaries {
+            write_summary(out, self.texture.len(), SUMMARIES[1].0)?;
+        }
+        self.write_normals(0..self.normal.len(), out)?;
+        if summaries {
+            write_summary(out, self.normal.len(), SUMMARIES[3].0)?;
+        }
+        Ok(())
+    }
+
+    /// Check that all polygons and lines have vertices and only refer to existing attributes.
+    ///
+    /// Other tools reject files with such elements, so this is done before writing.
+    pub fn validate_indices(&self) -> Result<(), ObjError> {
+        let lens = [self.position.len(), self.texture.len(), self.normal.len()];
+        for (o, object) in self.objects.iter().enumerate() {
+            for (g, group) in object.groups.iter().enumerate() {
+                let polys = group.polys.iter().map(|poly| &poly.0[..]);
+                let elements = polys.chain(group.lines.iter().map(|line| &line.0[..]));
+                for (e, tuples) in elements.enumerate() {
+                    if tuples.is_empty() {
+                        return Err(ObjError::EmptyElement {
+                            object: o,
+                            group: g,
+                            element: e,
+                        });
+                    }
+                    for tuple in tuples {
+                        let indices = [Some(tuple.position), tuple.texture, tuple.normal];
+                        for (index, &count) in indices.iter().zip(&lens) {
+                            match *index {
+                                Some(index) if index >= count => {
+                                    return Err(ObjError::ElementIndexOutOfRange {
+                                        object: o,
+                                        group: g,
+                                        element: e,
+                                        index,
+                                        count,
+                                    })
+                                }
+                                _ => (),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_header(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        writeln!(out, "# {} (https://crates.io/crates/obj).", GENERATED_BY)?;
+        self.metadata.write(out)
+    }
 
-        for pos in &self.position {
-            writeln!(out, "v {} {} {}", pos[0], pos[1], pos[2])?;
+    pub(crate) fn write_positions(&self, range: Range<usize>, out: &mut impl fmt::Write) -> fmt::Result {
+        for (i, pos) in self.position[range.clone()].iter().enumerate() {
+            match self.color.get(range.start + i) {
+                Some(c) => writeln!(out, "v {} {} {} {} {} {}", pos[0], pos[1], pos[2], c[0], c[1], c[2])?,
+                None => writeln!(out, "v {} {} {}", pos[0], pos[1], pos[2])?,
+            }
         }
-        for uv in &self.texture {
+        Ok(())
+    }
+
+    pub(crate) fn write_textures(&self, range: Range<usize>, out: &mut impl fmt::Write) -> fmt::Result {
+        for uv in &self.texture[range] {
             writeln!(out, "vt {} {}", uv[0], uv[1])?;
         }
-        for nml in &self.normal {
+        Ok(())
+    }
+
+    pub(crate) fn write_normals(&self, range: Range<usize>, out: &mut impl fmt::Write) -> fmt::Result {
+        for nml in &self.normal[range] {
             writeln!(out, "vn {} {} {}", nml[0], nml[1], nml[2])?;
         }
-        for object in &self.objects {
-            object.write_to_buf(out)?;
-        }
+        Ok(())
+    }
+
+    /// Write the `mtllib`, `shadow_obj` and `trace_obj` statements that follow the geometry.
+    pub(crate) fn write_references(&self, out: &mut impl fmt::Write) -> fmt::Result {
         for mtl_lib in &self.material_libs {
             writeln!(out, "mtllib {}", mtl_lib.filename)?;
         }
+        if let Some(shadow_obj) = &self.shadow_obj {
+            writeln!(out, "shadow_obj {}", shadow_obj)?;
+        }
+        if let Some(trace_obj) = &self.trace_obj {
+            writeln!(out, "trace_obj {}", trace_obj)?;
+        }
 
         Ok(())
     }
 }
 
+#[cfg(feature = "std")]
 impl ObjData {
-    fn parse_two(line_number: usize, n0: Option<&str>, n1: Option<&str>) -> Result<[f32; 2], ObjError> {
+    /// Serialize this `Obj` into the given writer according to `config`.
+    ///
+    /// Welding and pruning work on a copy of the data, which is left unchanged. Nothing is
+    /// written if [`ObjData::validate_indices`] fails.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn write_to_buf_with(&self, out: &mut impl Write, config: WriteConfig) -> Result<(), ObjError> {
+        if config.weld || config.prune_unreferenced {
+            // Both rewrite indices, which have to be valid.
+            self.validate_indices()?;
+            let mut copy = self.clone();
+            if config.weld {
+                copy.weld_identical();
+            }
+            if config.prune_unreferenced {
+                copy.prune_unreferenced();
+            }
+            let config = WriteConfig {
+                weld: false,
+                prune_unreferenced: false,
+                ..config
+            };
+            return copy.write_to_buf_with(out, config);
+        }
+        self.validate_indices()?;
+        write_io(out, |out| match config.decimal_separator.filter(|&c| c != '.') {
+            Some(separator) => {
+                let mut out = DecimalSeparator {
+                    inner: out,
+                    separator,
+                    line: String::new(),
+                };
+                self.write_with(&config, &mut out)?;
+                out.finish()
+            }
+            None => self.write_with(&config, out),
+        })?;
+        Ok(())
+    }
+
+    /// Write the data in the form given by `config`, once welding and pruning are done.
+    fn write_with(&self, config: &WriteConfig, out: &mut impl fmt::Write) -> fmt::Result {
+        if config.relative_indices {
+            self.write_object_blocks(config.summary_comments, out)
+        } else if config.summary_comments {
+            self.write_statements(true, out)
+        } else {
+            self.write_to_fmt(out)
+        }
+    }
+
+    /// Iterate over the polygons of all objects and groups.
+    pub(crate) fn polys(&self) -> impl Iterator<Item = &SimplePolygon> {
+        self.objects
+            .iter()
+            .flat_map(|object| object.groups.iter())
+            .flat_map(|group| group.polys.iter())
+    }
+
+    /// Iterate mutably over the polygons of all objects and groups.
+    pub(crate) fn polys_mut(&mut self) -> impl Iterator<Item = &mut SimplePolygon> {
+        self.objects
+            .iter_mut()
+            .flat_map(|object| object.groups.iter_mut())
+            .flat_map(|group| group.polys.iter_mut())
+    }
+
+    /// Iterate over the vertices of each polygon and line.
+    pub(crate) fn element_tuples(&self) -> impl Iterator<Item = &[IndexTuple]> {
+        self.objects
+            .iter()
+            .flat_map(|object| object.groups.iter())
+            .flat_map(|group| {
+                let polys = group.polys.iter().map(|poly| poly.0.as_slice());
+                polys.chain(group.lines.iter().map(|line| line.0.as_slice()))
+            })
+    }
+
+    /// Iterate over the index tuples of all polygons and lines.
+    pub(crate) fn index_tuples(&self) -> impl Iterator<Item = &IndexTuple> {
+        self.element_tuples().flatten()
+    }
+
+    /// Whether all attributes `tuples` refer to exist, see [`ObjData::validate_indices`].
+    pub(crate) fn has_attributes(&self, tuples: &[IndexTuple]) -> bool {
+        tuples.iter().all(|t| {
+            t.position < self.position.len()
+                && t.texture.is_none_or(|t| t < self.texture.len())
+                && t.normal.is_none_or(|n| n < self.normal.len())
+        })
+    }
+
+    /// Iterate mutably over the index tuples of all polygons and lines.
+    pub(crate) fn index_tuples_mut(&mut self) -> impl Iterator<Item = &mut IndexTuple> {
+        self.objects
+            .iter_mut()
+            .flat_map(|object| object.groups.iter_mut())
+            .flat_map(|group| {
+                let polys = group.polys.iter_mut().flat_map(|poly| poly.0.iter_mut());
+                polys.chain(group.lines.iter_mut().flat_map(|line| line.0.iter_mut()))
+            })
+    }
+
+    /// Rewrite every index referencing the attribute arrays using the given old-to-new tables.
+    ///
+    /// A `None` table leaves the corresponding indices untouched. The attribute arrays are
+    /// expected to be rewritten already, and indices past the end of a table, which
+    /// [`ObjData::validate_indices`] reports, are moved to stay past the end of its array.
+    pub(crate) fn remap_indices(
+        &mut self,
+        position: Option<&[usize]>,
+        texture: Option<&[usize]>,
+        normal: Option<&[usize]>,
+    ) {
+        let lens = [self.position.len(), self.texture.len(), self.normal.len()];
+        let remap = |map: &[usize], len: usize, index: usize| match map.get(index) {
+            Some(&index) => index,
+            None => len + (index - map.len()),
+        };
+        for IndexTuple {
+            position: p,
+            texture: t,
+            normal: n,
+        } in self.index_tuples_mut()
+        {
+            if let Some(map) = position {
+                *p = remap(map, lens[0], *p);
+            }
+            if let (Some(map), Some(t)) = (texture, t.as_mut()) {
+                *t = remap(map, lens[1], *t);
+            }
+            if let (Some(map), Some(n)) = (normal, n.as_mut()) {
+                *n = remap(map, lens[2], *n);
+            }
+        }
+    }
+}
+
+/// Parse a `/` separated vertex of a face or line, such as `1/2/3` or `-1//2`, into 0-based
+/// indices.
+///
+/// `lens` holds the number of positions, texture coordinates and normals read so far, which
+/// negative indices are relative to.
+pub fn parse_index_tuple(line_number: usize, group: &str, lens: [usize; 3]) -> Result<IndexTuple, ObjError> {
+    let mut group_split = group.split('/');
+    let p: Option<isize> = group_split.next().and_then(|idx| FromStr::from_str(idx).ok());
+    let t: Option<isize> = group_split.next().and_then(|idx| {
+        if !idx.is_empty() {
+            FromStr::from_str(idx).ok()
+        } else {
+            None
+        }
+    });
+    let n: Option<isize> = group_split.next().and_then(|idx| FromStr::from_str(idx).ok());
+
+    match (p, t, n) {
+        (Some(p), t, n) => Ok(IndexTuple::new(
+            normalize(p, lens[0]).ok_or(ObjError::ZeroVertexNumber { line_number })?,
+            // Zero indices are silently ignored for tangent and normal indices.
+            t.and_then(|t| normalize(t, lens[1])),
+            n.and_then(|n| normalize(n, lens[2])),
+        )),
+        _ => Err(ObjError::MalformedFaceGroup {
+            line_number,
+            group: String::from(group),
+        }),
+    }
+}
+
+impl<S: Scalar> ObjData<S> {
+    pub(crate) fn parse_two(line_number: usize, n0: Option<&str>, n1: Option<&str>) -> Result<[f32; 2], ObjError> {
         let (n0, n1) = match (n0, n1) {
             (Some(n0), Some(n1)) => (n0, n1),
             _ => {
@@ -589,12 +2532,12 @@ impl ObjData {
         Ok(normal)
     }
 
-    fn parse_three(
+    pub(crate) fn parse_three<T: FromStr>(
         line_number: usize,
         n0: Option<&str>,
         n1: Option<&str>,
         n2: Option<&str>,
-    ) -> Result<[f32; 3], ObjError> {
+    ) -> Result<[T; 3], ObjError> {
         let (n0, n1, n2) = match (n0, n1, n2) {
             (Some(n0), Some(n1), Some(n2)) => (n0, n1, n2),
             _ => {
@@ -616,183 +2559,226 @@ impl ObjData {
         Ok(normal)
     }
 
-    fn parse_group(&self, line_number: usize, group: &str) -> Result<IndexTuple, ObjError> {
-        let mut group_split = group.split('/');
-        let p: Option<isize> = group_split.next().and_then(|idx| FromStr::from_str(idx).ok());
-        let t: Option<isize> = group_split.next().and_then(|idx| {
-            if !idx.is_empty() {
-                FromStr::from_str(idx).ok()
-            } else {
-                None
-            }
-        });
-        let n: Option<isize> = group_split.next().and_then(|idx| FromStr::from_str(idx).ok());
-
-        match (p, t, n) {
-            (Some(p), t, n) => Ok(IndexTuple(
-                normalize(p, self.position.len()).ok_or(ObjError::ZeroVertexNumber { line_number })?,
-                // Zero indices are silently ignored for tangent and normal indices.
-                t.map(|t| normalize(t, self.texture.len())).flatten(),
-                n.map(|n| normalize(n, self.normal.len())).flatten(),
-            )),
-            _ => Err(ObjError::MalformedFaceGroup {
-                line_number,
-                group: String::from(group),
-            }),
-        }
-    }
-
-    fn parse_face<'b, I>(&self, line_number: usize, groups: &mut I) -> Result<SimplePolygon, ObjError>
+    /// Parse the vertices of a face, resolving relative indices against the attribute counts
+    /// `lens`.
+    pub(crate) fn parse_face<'b, I>(
+        line_number: usize,
+        groups: &mut I,
+        lens: [usize; 3],
+    ) -> Result<SimplePolygon, ObjError>
     where
         I: Iterator<Item = &'b str>,
     {
         let mut ret = Vec::with_capacity(4);
         for g in groups {
-            let ituple = self.parse_group(line_number, g)?;
+            let ituple = parse_index_tuple(line_number, g, lens)?;
             ret.push(ituple);
         }
         Ok(SimplePolygon(ret))
     }
 
+    /// Parse the vertices of a line, like [`ObjData::parse_face`].
+    pub(crate) fn parse_line<'b, I>(line_number: usize, groups: &mut I, lens: [usize; 3]) -> Result<Line, ObjError>
+    where
+        I: Iterator<Item = &'b str>,
+    {
+        let mut ret = Vec::with_capacity(2);
+        for g in groups {
+            let IndexTuple {
+                position: p,
+                texture: t,
+                ..
+            } = parse_index_tuple(line_number, g, lens)?;
+            ret.push(IndexTuple::new(p, t, None));
+        }
+        Ok(Line(ret))
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "load", level = "debug", skip_all))]
+    fn parse<R: Read>(input: R, config: LoadConfig) -> Result<(Self, Vec<LoadWarning>), ObjError> {
+        let mut times = PhaseTimes::default();
+        if config.faithful || !config.strict || config.max_line_length.is_some() {
+            // Reading lines would drop the `\r` of line endings, which the layout keeps, fail on
+            // invalid UTF-8, which lenient parsing skips, and read lines of any length.
+            let mut reader = BufReader::new(input);
+            let mut parser = PushParser::new(config);
+            loop {
+                let chunk = times.io(|| reader.fill_buf())?;
+                if chunk.is_empty() {
+                    times.report();
+                    return parser.finish_with_warnings();
+                }
+                let len = chunk.len();
+                times.parse(|| parser.push_bytes(chunk))?;
+                reader.consume(len);
+            }
+        }
+        let mut lines = BufReader::new(input).lines();
+        let mut parser = PushParser::new(config);
+        while let Some(line) = times.io(|| lines.next()) {
+            let line = line.map_err(|err| {
+                ObjError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("failed to readline {}", err),
+                ))
+            })?;
+            times.parse(|| parser.push_line(&line))?;
+        }
+        times.report();
+        parser.finish_with_warnings()
+    }
+
+    /// Parse .obj source from a string slice, without requiring `std`.
+    pub fn parse_str(input: &str, config: LoadConfig) -> Result<Self, ObjError> {
+        if config.faithful {
+            return Self::parse_slice(input.as_bytes(), config);
+        }
+        Self::parse_lines(input.lines().map(Ok), config).map(|(data, _)| data)
+    }
+
+    /// Parse .obj source from a byte slice, without requiring `std`.
+    ///
+    /// Lines are separated by `\n` or `\r\n` and each must be valid UTF-8.
+    pub fn parse_slice(input: &[u8], config: LoadConfig) -> Result<Self, ObjError> {
+        let mut parser = PushParser::new(config);
+        parser.push_bytes(input)?;
+        parser.finish()
+    }
+
+    fn parse_lines<L: AsRef<str>>(
+        lines: impl Iterator<Item = Result<L, ObjError>>,
+        config: LoadConfig,
+    ) -> Result<(Self, Vec<LoadWarning>), ObjError> {
+        let mut parser = PushParser::new(config);
+        for line in lines {
+            parser.push_line(line?.as_ref())?;
+        }
+        parser.finish_with_warnings()
+    }
+}
+
+/// Formats the data as .obj source, see [`ObjData::write_to_fmt`].
+impl<S: Scalar> fmt::Display for ObjData<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_to_fmt(f)
+    }
+}
+
+/// Parses .obj source with the default [`LoadConfig`], see [`ObjData::parse_str`].
+impl<S: Scalar> FromStr for ObjData<S> {
+    type Err = ObjError;
+
+    fn from_str(s: &str) -> Result<Self, ObjError> {
+        Self::parse_str(s, LoadConfig::default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl ObjData {
     pub fn load_buf<R: Read>(input: R) -> Result<Self, ObjError> {
-        Self::load_buf_with_config(input, LoadConfig::default())
+        Self::load_buf_with(input, LoadConfig::default())
+    }
+
+    /// Parse an .obj file using a custom load configuration.
+    pub fn load_buf_with<R: Read>(input: R, config: LoadConfig) -> Result<Self, ObjError> {
+        Self::parse(input, config).map(|(data, _)| data)
+    }
+
+    /// Like [`ObjData::load_buf_with`], also returning the problems that were worked around.
+    pub fn load_buf_with_warnings<R: Read>(input: R, config: LoadConfig) -> Result<(Self, Vec<LoadWarning>), ObjError> {
+        Self::parse(input, config)
     }
 
+    #[deprecated(note = "use `ObjData::load_buf_with`")]
     pub fn load_buf_with_config<R: Read>(input: R, config: LoadConfig) -> Result<Self, ObjError> {
-        let input = BufReader::new(input);
-        let mut dat = ObjData::default();
-        let mut object = Object::new(DEFAULT_OBJECT.to_string());
-        let mut group: Option<Group> = None;
-
-        for (idx, line) in input.lines().enumerate() {
-            let (line, mut words) = match line {
-                Ok(ref line) => (line.clone(), line.split_whitespace().filter(|s| !s.is_empty())),
-                Err(err) => {
-                    return Err(ObjError::Io(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("failed to readline {}", err),
-                    )));
-                }
-            };
-            let first = words.next();
+        Self::load_buf_with(input, config)
+    }
 
-            match first {
-                Some("v") => {
-                    let (v0, v1, v2) = (words.next(), words.next(), words.next());
-                    dat.position.push(Self::parse_three(idx, v0, v1, v2)?);
-                }
-                Some("vt") => {
-                    let (t0, t1) = (words.next(), words.next());
-                    dat.texture.push(Self::parse_two(idx, t0, t1)?);
-                }
-                Some("vn") => {
-                    let (n0, n1, n2) = (words.next(), words.next(), words.next());
-                    dat.normal.push(Self::parse_three(idx, n0, n1, n2)?);
-                }
-                Some("f") => {
-                    let poly = dat.parse_face(idx, &mut words)?;
-                    group = Some(match group {
-                        None => {
-                            let mut g = Group::new(DEFAULT_GROUP.to_string());
-                            g.polys.push(poly);
-                            g
-                        }
-                        Some(mut g) => {
-                            g.polys.push(poly);
-                            g
-                        }
-                    });
-                }
-                Some("o") => {
-                    group = match group {
-                        Some(val) => {
-                            object.groups.push(val);
-                            dat.objects.push(object);
-                            None
-                        }
-                        None => None,
-                    };
-                    object = if line.len() > 2 {
-                        let name = line[1..].trim();
-                        Object::new(name.to_string())
-                    } else {
-                        Object::new(DEFAULT_OBJECT.to_string())
-                    };
-                }
-                Some("g") => {
-                    let current_material = if group.is_some() {
-                        let g = group.as_ref().unwrap();
-                        g.material.clone()
-                    } else {
-                        None
-                    };
-
-                    object.groups.extend(group.take());
-
-                    if line.len() > 2 {
-                        let name = line[2..].trim();
-                        let mut g = Group::new(name.to_string());
-                        g.material = current_material;
-                        group = Some(g);
-                    }
-                }
-                Some("mtllib") => {
-                    // Obj strictly does not allow spaces in filenames.
-                    // "mtllib Some File.mtl" is forbidden.
-                    // However, everyone does it anyway and if we want to ingest blender-outputted files, we need to support it.
-                    // This works by walking word by word and combining them with a space in between. This may not be a totally
-                    // accurate way to do it, but until the parser can be re-worked, this is good-enough, better-than-before solution.
-                    let first_word = words
-                        .next()
-                        .ok_or(ObjError::MissingMTLName { line_number: idx })?
-                        .to_string();
-                    let name = words.fold(first_word, |mut existing, next| {
-                        existing.push(' ');
-                        existing.push_str(next);
-                        existing
-                    });
-                    dat.material_libs.push(Mtl::new(name));
-                }
-                Some("usemtl") => {
-                    let mut g = group.unwrap_or_else(|| Group::new(DEFAULT_GROUP.to_string()));
-                    // we found a new material that was applied to an existing
-                    // non-empty object. It is treated as a new group.
-                    if !g.polys.is_empty() {
-                        object.groups.push(g.clone());
-                        g.index += 1;
-                        g.polys.clear();
-                    }
-                    g.material = words.next().map(|w| ObjMaterial::Ref(w.to_string()));
-                    group = Some(g);
-                }
-                Some("s") => (),
-                Some("l") => (),
-                Some(other) => {
-                    if config.strict && !other.starts_with('#') {
-                        return Err(ObjError::UnexpectedCommand {
-                            line_number: idx,
-                            command: other.to_string(),
-                        });
-                    }
+    /// Parse an .obj file keeping the vertex positions in double precision.
+    pub fn load_buf_f64<R: Read>(input: R) -> Result<ObjData64, ObjError> {
+        Self::load_buf_f64_with(input, LoadConfig::default())
+    }
+
+    /// Like [`ObjData::load_buf_f64`], with the given configuration.
+    pub fn load_buf_f64_with<R: Read>(input: R, config: LoadConfig) -> Result<ObjData64, ObjError> {
+        ObjData64::parse(input, config).map(|(data, _)| data)
+    }
+
+    #[deprecated(note = "use `ObjData::load_buf_f64_with`")]
+    pub fn load_buf_f64_with_config<R: Read>(input: R, config: LoadConfig) -> Result<ObjData64, ObjError> {
+        Self::load_buf_f64_with(input, config)
+    }
+}
+
+impl ObjData {
+    /// Convert the positions to double precision.
+    pub fn to_f64(&self) -> ObjData64 {
+        self.map_positions(|p| p as f64)
+    }
+
+    /// Make all equal object, group and material reference names share a single allocation.
+    ///
+    /// Parsing already does this, but names of deserialized or programmatically built data may
+    /// be separate copies.
+    pub fn intern_names(&mut self) {
+        let mut names = Interner::default();
+        for object in &mut self.objects {
+            object.name = names.intern(&object.name);
+            for group in &mut object.groups {
+                group.name = names.intern(&group.name);
+                if let Some(ObjMaterial::Ref(name)) = &mut group.material {
+                    *name = names.intern(name);
                 }
-                None => (),
             }
         }
+    }
+}
 
-        if let Some(g) = group {
-            object.groups.push(g);
+impl ObjData64 {
+    /// Convert the positions to single precision, rounding them to the nearest `f32`.
+    ///
+    /// To keep the precision of large coordinates, move them close to the origin with
+    /// [`ObjData64::translate`] first.
+    pub fn to_f32(&self) -> ObjData {
+        self.map_positions(|p| p as f32)
+    }
+
+    /// Add `offset` to every position, computed in double precision.
+    pub fn translate(&mut self, offset: [f64; 3]) {
+        for p in &mut self.position {
+            for (c, o) in p.iter_mut().zip(&offset) {
+                *c += o;
+            }
         }
+    }
+}
 
-        dat.objects.push(object);
-        Ok(dat)
+impl<S: Scalar> ObjData<S> {
+    fn map_positions<T>(&self, f: impl Fn(S) -> T) -> ObjData<T> {
+        ObjData {
+            position: self.position.iter().map(|p| [f(p[0]), f(p[1]), f(p[2])]).collect(),
+            color: self.color.clone(),
+            texture: self.texture.clone(),
+            normal: self.normal.clone(),
+            objects: self.objects.clone(),
+            material_libs: self.material_libs.clone(),
+            shadow_obj: self.shadow_obj.clone(),
+            trace_obj: self.trace_obj.clone(),
+            default_material: self.default_material.clone(),
+            metadata: self.metadata.clone(),
+            layout: self.layout.clone(),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     // Test that given zero vertex numbers, the loader throws an error instead of crashing.
+    #[cfg(feature = "std")]
     #[test]
     fn load_error_on_zero_vertex_numbers() {
         let test = b"v 0 1 2\nv 3 4 5\nf 0 1 2";
@@ -807,9 +2793,9 @@ mod tests {
     /// [`IndexTuple`].
     #[test]
     fn index_tuple_display() {
-        assert_eq!(IndexTuple(0, None, None).to_string(), "1");
-        assert_eq!(IndexTuple(0, Some(0), None).to_string(), "1/1");
-        assert_eq!(IndexTuple(0, Some(0), Some(0)).to_string(), "1/1/1");
-        assert_eq!(IndexTuple(0, None, Some(0)).to_string(), "1//1");
+        assert_eq!(IndexTuple::new(0, None, None).to_string(), "1");
+        assert_eq!(IndexTuple::new(0, Some(0), None).to_string(), "1/1");
+        assert_eq!(IndexTuple::new(0, Some(0), Some(0)).to_string(), "1/1/1");
+        assert_eq!(IndexTuple::new(0, None, Some(0)).to_string(), "1//1");
     }
 }
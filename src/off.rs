@@ -0,0 +1,243 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Reading and writing OFF files.
+//!
+//! Vertex positions and polygons are supported, along with the optional per-vertex texture
+//! coordinates, colors and normals announced by the `ST`, `C` and `N` prefixes of the `OFF`
+//! keyword. Faces keep their winding and become polygons of a single group, face colors are
+//! skipped.
+//!
+//! ```
+//! use obj::{off, ObjData};
+//!
+//! let source = "OFF\n# a triangle\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+//! let data = off::load_buf(source.as_bytes()).unwrap();
+//! assert_eq!(data.position.len(), 3);
+//!
+//! let mut buf = Vec::new();
+//! off::write(&data, &mut buf).unwrap();
+//! assert_eq!(off::load_buf(&buf[..]).unwrap(), data);
+//! ```
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::obj::{Group, IndexTuple, ObjData, Object, SimplePolygon, DEFAULT_GROUP, DEFAULT_OBJECT};
+
+/// Errors reading or writing an OFF file.
+#[derive(Debug)]
+pub enum OffError {
+    Io(io::Error),
+    /// The file does not start with a supported `OFF` keyword.
+    InvalidHeader(String),
+    /// A value could not be parsed.
+    InvalidValue(String),
+    /// The file ended before all announced vertices and faces were read.
+    UnexpectedEof,
+    /// A face references a vertex that does not exist.
+    IndexOutOfRange(usize),
+}
+
+impl std::error::Error for OffError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OffError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffError::Io(err) => write!(f, "I/O error reading or writing an OFF file: {}", err),
+            OffError::InvalidHeader(keyword) => write!(f, "Unsupported OFF keyword: {}", keyword),
+            OffError::InvalidValue(val) => write!(f, "Attempted to parse the value '{}' but failed.", val),
+            OffError::UnexpectedEof => write!(f, "OFF file ended before all vertices and faces were read."),
+            OffError::IndexOutOfRange(index) => write!(f, "Face references the missing vertex {}.", index),
+        }
+    }
+}
+
+impl From<io::Error> for OffError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+fn parse<T: std::str::FromStr>(token: &str) -> Result<T, OffError> {
+    token.parse().map_err(|_| OffError::InvalidValue(token.to_string()))
+}
+
+fn parse_array<const N: usize>(tokens: &[&str]) -> Result<[f32; N], OffError> {
+    if tokens.len() < N {
+        return Err(OffError::UnexpectedEof);
+    }
+    let mut values = [0.0; N];
+    for (v, token) in values.iter_mut().zip(tokens) {
+        *v = parse(token)?;
+    }
+    Ok(values)
+}
+
+/// Load an OFF file from the given path.
+pub fn load(path: impl AsRef<Path>) -> Result<ObjData, OffError> {
+    load_buf(File::open(path)?)
+}
+
+/// Load an OFF file from a reader.
+///
+/// Colors given as integers are scaled from `0..=255` to `0.0..=1.0`, and an alpha channel is
+/// dropped. Texture coordinates are read from the end of the vertex line.
+pub fn load_buf<R: Read>(mut input: R) -> Result<ObjData, OffError> {
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+    // Vertices and faces take one line each, which is the only way to tell optional values apart.
+    let mut lines = text
+        .lines()
+        .map(|line| {
+            line.split('#')
+                .next()
+                .unwrap_or("")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+        })
+        .filter(|tokens| !tokens.is_empty());
+
+    let mut header = lines.next().ok_or(OffError::UnexpectedEof)?;
+    let keyword = header.remove(0);
+    let flags = keyword
+        .strip_suffix("OFF")
+        .ok_or_else(|| OffError::InvalidHeader(keyword.to_string()))?;
+    let (has_texture, flags) = match flags.strip_prefix("ST") {
+        Some(rest) => (true, rest),
+        None => (false, flags),
+    };
+    let (has_color, flags) = match flags.strip_prefix('C') {
+        Some(rest) => (true, rest),
+        None => (false, flags),
+    };
+    let (has_normal, flags) = match flags.strip_prefix('N') {
+        Some(rest) => (true, rest),
+        None => (false, flags),
+    };
+    if !flags.is_empty() {
+        return Err(OffError::InvalidHeader(keyword.to_string()));
+    }
+
+    // The counts may follow the keyword on the same line.
+    let counts = if header.is_empty() {
+        lines.next().ok_or(OffError::UnexpectedEof)?
+    } else {
+        header
+    };
+    let vertex_count: usize = parse(counts.first().ok_or(OffError::UnexpectedEof)?)?;
+    let face_count: usize = parse(counts.get(1).ok_or(OffError::UnexpectedEof)?)?;
+
+    let mut data = ObjData::default();
+    for _ in 0..vertex_count {
+        let mut tokens = &lines.next().ok_or(OffError::UnexpectedEof)?[..];
+        data.position.push(parse_array(tokens)?);
+        tokens = &tokens[3..];
+        if has_normal {
+            data.normal.push(parse_array(tokens)?);
+            tokens = &tokens[3..];
+        }
+        if has_texture {
+            if tokens.len() < 2 {
+                return Err(OffError::UnexpectedEof);
+            }
+            let (rest, texture) = tokens.split_at(tokens.len() - 2);
+            data.texture.push(parse_array(texture)?);
+            tokens = rest;
+        }
+        if has_color {
+            let integer = tokens.iter().all(|c| !c.contains(['.', 'e', 'E']));
+            let mut color: [f32; 3] = parse_array(tokens)?;
+            if integer {
+                color = color.map(|c| c / 255.0);
+            }
+            data.color.push(color);
+        }
+    }
+
+    let mut group = Group::new(DEFAULT_GROUP.to_string());
+    for _ in 0..face_count {
+        let tokens = lines.next().ok_or(OffError::UnexpectedEof)?;
+        let count: usize = parse(tokens[0])?;
+        let indices = tokens.get(1..count + 1).ok_or(OffError::UnexpectedEof)?;
+        let poly = indices
+            .iter()
+            .map(|token| {
+                let i: usize = parse(token)?;
+                if i >= vertex_count {
+                    return Err(OffError::IndexOutOfRange(i));
+                }
+                Ok(IndexTuple::new(
+                    i,
+                    Some(i).filter(|_| has_texture),
+                    Some(i).filter(|_| has_normal),
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+        group.polys.push(SimplePolygon(poly));
+    }
+
+    let mut object = Object::new(DEFAULT_OBJECT.to_string());
+    object.groups.push(group);
+    data.objects.push(object);
+    Ok(data)
+}
+
+/// Save the data as an OFF file.
+pub fn save(data: &ObjData, path: impl AsRef<Path>) -> Result<(), OffError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write(data, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the positions and the polygons of all groups as an OFF file.
+///
+/// OFF polygons index positions only, so texture coordinates and normals are not written. Vertex
+/// colors are written as `COFF` if present. Lines are not written.
+pub fn write(data: &ObjData, out: &mut impl Write) -> Result<(), OffError> {
+    let has_color = !data.color.is_empty();
+    writeln!(out, "{}", if has_color { "COFF" } else { "OFF" })?;
+    writeln!(
+        out,
+        "# Generated by the obj Rust library (https://crates.io/crates/obj)."
+    )?;
+    writeln!(out, "{} {} 0", data.position.len(), data.polys().count())?;
+    for (i, [x, y, z]) in data.position.iter().enumerate() {
+        match data.color.get(i) {
+            Some([r, g, b]) => {
+                // Debug formatting keeps the decimal point, so colors are not read back as integers.
+                writeln!(out, "{} {} {} {:?} {:?} {:?} 1.0", x, y, z, r, g, b)?
+            }
+            None => writeln!(out, "{} {} {}", x, y, z)?,
+        }
+    }
+    for poly in data.polys() {
+        write!(out, "{}", poly.0.len())?;
+        for IndexTuple { position: p, .. } in &poly.0 {
+            write!(out, " {}", p)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
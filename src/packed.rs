@@ -0,0 +1,239 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! A compact 32-bit representation of polygon indices.
+//!
+//! [`IndexTuple`] stores `usize` indices with `Option` wrappers, which takes 40 bytes per
+//! polygon vertex on 64-bit targets. [`PackedIndexTuple`] takes 12, at the cost of limiting the
+//! number of attributes to `u32::MAX - 1`. [`CompactPolygon`] uses them to store triangles and
+//! quads without a heap allocation of their own.
+
+use crate::obj::{Group, IndexTuple, ObjData, SimplePolygon};
+
+/// An [`IndexTuple`] stored as three `u32`.
+///
+/// Missing texture and normal indices are stored as [`PackedIndexTuple::NONE`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, PartialOrd, Eq, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct PackedIndexTuple {
+    pub position: u32,
+    pub texture: u32,
+    pub normal: u32,
+}
+
+impl PackedIndexTuple {
+    /// The value marking a missing texture or normal index.
+    pub const NONE: u32 = u32::MAX;
+
+    /// Pack the tuple, or return `None` if one of its indices does not fit.
+    pub fn new(tuple: IndexTuple) -> Option<Self> {
+        fn pack(idx: usize) -> Option<u32> {
+            if idx < PackedIndexTuple::NONE as usize {
+                Some(idx as u32)
+            } else {
+                None
+            }
+        }
+        Some(PackedIndexTuple {
+            position: pack(tuple.position)?,
+            texture: match tuple.texture {
+                Some(t) => pack(t)?,
+                None => Self::NONE,
+            },
+            normal: match tuple.normal {
+                Some(n) => pack(n)?,
+                None => Self::NONE,
+            },
+        })
+    }
+
+    /// The texture coordinate index, if any.
+    pub fn texture(self) -> Option<usize> {
+        Some(self.texture).filter(|&t| t != Self::NONE).map(|t| t as usize)
+    }
+
+    /// The normal index, if any.
+    pub fn normal(self) -> Option<usize> {
+        Some(self.normal).filter(|&n| n != Self::NONE).map(|n| n as usize)
+    }
+
+    /// Convert back to an [`IndexTuple`].
+    pub fn unpack(self) -> IndexTuple {
+        IndexTuple::new(self.position as usize, self.texture(), self.normal())
+    }
+}
+
+impl From<PackedIndexTuple> for IndexTuple {
+    fn from(tuple: PackedIndexTuple) -> Self {
+        tuple.unpack()
+    }
+}
+
+/// Polygons with packed indices, flattened into a single buffer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackedPolygons {
+    /// The vertices of all polygons.
+    pub tuples: Vec<PackedIndexTuple>,
+    /// The start of each polygon in `tuples`, followed by the length of `tuples`.
+    pub offsets: Vec<u32>,
+}
+
+impl PackedPolygons {
+    fn pack<'a>(polys: impl Iterator<Item = &'a SimplePolygon>) -> Option<Self> {
+        let mut packed = PackedPolygons {
+            tuples: Vec::new(),
+            offsets: vec![0],
+        };
+        for poly in polys {
+            for &tuple in &poly.0 {
+                packed.tuples.push(PackedIndexTuple::new(tuple)?);
+            }
+            if packed.tuples.len() > u32::MAX as usize {
+                return None;
+            }
+            packed.offsets.push(packed.tuples.len() as u32);
+        }
+        Some(packed)
+    }
+
+    /// Number of polygons.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The vertices of the polygon with the given index.
+    pub fn polygon(&self, index: usize) -> &[PackedIndexTuple] {
+        &self.tuples[self.offsets[index] as usize..self.offsets[index + 1] as usize]
+    }
+
+    /// Iterate over the vertices of each polygon.
+    pub fn iter(&self) -> impl Iterator<Item = &[PackedIndexTuple]> + '_ {
+        self.offsets
+            .windows(2)
+            .map(move |w| &self.tuples[w[0] as usize..w[1] as usize])
+    }
+
+    /// Convert back to simple polygons.
+    pub fn unpack(&self) -> Vec<SimplePolygon> {
+        self.iter()
+            .map(|poly| SimplePolygon(poly.iter().map(|t| t.unpack()).collect()))
+            .collect()
+    }
+}
+
+/// A polygon storing the vertices of triangles and quads inline, with packed indices.
+///
+/// Most files consist of triangles and quads, which take 56 bytes each on 64-bit targets this
+/// way, where a [`SimplePolygon`] takes 24 bytes plus a heap allocation of 40 bytes per vertex.
+/// Other polygons, and those with an index that doesn't fit into a [`PackedIndexTuple`], keep
+/// their vertices in a `Vec`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompactPolygon {
+    Triangle([PackedIndexTuple; 3]),
+    Quad([PackedIndexTuple; 4]),
+    Other(Vec<IndexTuple>),
+}
+
+impl CompactPolygon {
+    /// Store the vertices of `poly`, inline if it is a triangle or a quad whose indices fit.
+    pub fn new(poly: &SimplePolygon) -> Self {
+        match poly.0.len() {
+            3 => pack_array(&poly.0).map(CompactPolygon::Triangle),
+            4 => pack_array(&poly.0).map(CompactPolygon::Quad),
+            _ => None,
+        }
+        .unwrap_or_else(|| CompactPolygon::Other(poly.0.clone()))
+    }
+
+    /// Number of vertices.
+    pub fn len(&self) -> usize {
+        match self {
+            CompactPolygon::Triangle(_) => 3,
+            CompactPolygon::Quad(_) => 4,
+            CompactPolygon::Other(tuples) => tuples.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the vertices.
+    pub fn iter(&self) -> impl Iterator<Item = IndexTuple> + '_ {
+        let (packed, other): (&[PackedIndexTuple], &[IndexTuple]) = match self {
+            CompactPolygon::Triangle(tuples) => (tuples, &[]),
+            CompactPolygon::Quad(tuples) => (tuples, &[]),
+            CompactPolygon::Other(tuples) => (&[], tuples),
+        };
+        packed.iter().map(|t| t.unpack()).chain(other.iter().copied())
+    }
+
+    /// Convert back to a simple polygon.
+    pub fn unpack(&self) -> SimplePolygon {
+        SimplePolygon(self.iter().collect())
+    }
+}
+
+/// Pack the first `N` of `tuples`, or return `None` if an index does not fit.
+fn pack_array<const N: usize>(tuples: &[IndexTuple]) -> Option<[PackedIndexTuple; N]> {
+    let mut packed = [PackedIndexTuple {
+        position: 0,
+        texture: PackedIndexTuple::NONE,
+        normal: PackedIndexTuple::NONE,
+    }; N];
+    for (packed, &tuple) in packed.iter_mut().zip(tuples) {
+        *packed = PackedIndexTuple::new(tuple)?;
+    }
+    Some(packed)
+}
+
+impl From<&SimplePolygon> for CompactPolygon {
+    fn from(poly: &SimplePolygon) -> Self {
+        CompactPolygon::new(poly)
+    }
+}
+
+impl From<&CompactPolygon> for SimplePolygon {
+    fn from(poly: &CompactPolygon) -> Self {
+        poly.unpack()
+    }
+}
+
+impl Group {
+    /// Pack the polygons of this group, or return `None` if an index does not fit into `u32`.
+    pub fn packed_polygons(&self) -> Option<PackedPolygons> {
+        PackedPolygons::pack(self.polys.iter())
+    }
+
+    /// The polygons of this group as [`CompactPolygon`]s, in order.
+    pub fn compact_polygons(&self) -> Vec<CompactPolygon> {
+        self.polys.iter().map(CompactPolygon::new).collect()
+    }
+}
+
+impl ObjData {
+    /// Pack the polygons of all objects and groups, in order, or return `None` if an index does
+    /// not fit into `u32`.
+    pub fn packed_polygons(&self) -> Option<PackedPolygons> {
+        PackedPolygons::pack(self.polys())
+    }
+}
@@ -0,0 +1,136 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Writing .obj source with the formatting spread across threads.
+
+use std::fmt;
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::thread;
+
+use crate::obj::{Group, Line, ObjData, ObjError, Object, RenderState, Scalar, SimplePolygon, WriteToFmt};
+
+/// The number of statements formatted as one unit of work.
+const CHUNK_LEN: usize = 16 * 1024;
+
+/// A part of the output that is formatted independently of the others.
+enum Block<'a> {
+    Header,
+    Positions(Range<usize>),
+    Textures(Range<usize>),
+    Normals(Range<usize>),
+    Object(&'a Object),
+    /// A group, the one before it in the same object and the render state before it.
+    Group(&'a Group, Option<&'a Group>, Option<&'a RenderState>),
+    /// A change of the render state inside a group, and the state before it.
+    State(&'a RenderState, &'a RenderState),
+    Polys(&'a [SimplePolygon]),
+    Lines(&'a [Line]),
+    References,
+}
+
+fn ranges(len: usize) -> impl Iterator<Item = Range<usize>> {
+    (0..len)
+        .step_by(CHUNK_LEN)
+        .map(move |start| start..len.min(start + CHUNK_LEN))
+}
+
+impl<S: Scalar + Sync> ObjData<S> {
+    /// Serialize this `Obj` into the given writer, formatting on up to `threads` threads.
+    ///
+    /// The output is the same as that of [`ObjData::write_to_buf`]. Vertex attributes, polygons
+    /// and lines are split into chunks which are formatted into buffers on worker threads and
+    /// written in order, so only a bounded number of chunks is buffered at a time. A `threads`
+    /// count of zero uses the available parallelism of the machine.
+    ///
+    /// Data written line by line from its [`ObjData::layout`] is formatted on the calling thread.
+    pub fn write_to_buf_parallel(&self, out: &mut impl Write, threads: usize) -> Result<(), ObjError> {
+        if self.layout.as_ref().is_some_and(|layout| layout.covers(self)) {
+            return self.write_to_buf(out);
+        }
+        self.validate_indices()?;
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            n => n,
+        };
+        let blocks = self.blocks();
+        // A few blocks per thread evens out differences in how long blocks take to format.
+        for window in blocks.chunks(threads * 4) {
+            let per_thread = window.len().div_ceil(threads);
+            let texts: Vec<String> = thread::scope(|scope| {
+                let workers: Vec<_> = window
+                    .chunks(per_thread)
+                    .map(|blocks| scope.spawn(move || self.format_blocks(blocks)))
+                    .collect();
+                // Formatting into a `String` can't fail, other than by panicking.
+                workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+            });
+            for text in texts {
+                out.write_all(text.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn blocks(&self) -> Vec<Block<'_>> {
+        let mut blocks = vec![Block::Header];
+        blocks.extend(ranges(self.position.len()).map(Block::Positions));
+        blocks.extend(ranges(self.texture.len()).map(Block::Textures));
+        blocks.extend(ranges(self.normal.len()).map(Block::Normals));
+        let mut state = None;
+        for object in &self.objects {
+            blocks.push(Block::Object(object));
+            let mut previous = None;
+            for group in &object.groups {
+                blocks.push(Block::Group(group, previous, state));
+                previous = Some(group);
+                let mut before = &group.render_state;
+                for (run_state, polys, lines) in group.state_runs() {
+                    blocks.push(Block::State(run_state, before));
+                    blocks.extend(polys.chunks(CHUNK_LEN).map(Block::Polys));
+                    blocks.extend(lines.chunks(CHUNK_LEN).map(Block::Lines));
+                    before = run_state;
+                }
+                state = Some(before);
+            }
+        }
+        blocks.push(Block::References);
+        blocks
+    }
+
+    fn format_blocks(&self, blocks: &[Block<'_>]) -> String {
+        let mut text = String::new();
+        for block in blocks {
+            // Writing into a `String` can't fail.
+            let _ = self.format_block(block, &mut text);
+        }
+        text
+    }
+
+    fn format_block(&self, block: &Block<'_>, out: &mut String) -> fmt::Result {
+        match block {
+            Block::Header => self.write_header(out),
+            Block::Positions(range) => self.write_positions(range.clone(), out),
+            Block::Textures(range) => self.write_textures(range.clone(), out),
+            Block::Normals(range) => self.write_normals(range.clone(), out),
+            Block::Object(object) => object.write_header(out),
+            Block::Group(group, previous, state) => group.write_header(*previous, *state, out),
+            Block::State(state, before) => state.write_changes(before, out),
+            Block::Polys(polys) => polys.iter().try_for_each(|poly| poly.write_to_fmt(out)),
+            Block::Lines(lines) => lines.iter().try_for_each(|line| line.write_to_fmt(out)),
+            Block::References => self.write_references(out),
+        }
+    }
+}
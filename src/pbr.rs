@@ -0,0 +1,136 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversion of .mtl materials to the metallic-roughness model used by PBR renderers.
+
+use crate::mtl::{strip_texture_options, Material};
+
+/// How the color values of a .mtl file are to be interpreted.
+///
+/// The format doesn't say, and exporters disagree: some write the linear values used for
+/// shading, others the sRGB-encoded values shown in color pickers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// The values are sRGB-encoded and converted to linear for shading.
+    Srgb,
+    /// The values are linear and used as they are.
+    Linear,
+}
+
+impl ColorSpace {
+    /// Convert a color given in this color space to linear values.
+    ///
+    /// This uses the piecewise sRGB transfer function rather than a plain power of 2.2.
+    pub fn to_linear(self, color: [f32; 3]) -> [f32; 3] {
+        match self {
+            ColorSpace::Srgb => color.map(|c| {
+                if c <= 0.04045 {
+                    c / 12.92
+                } else {
+                    ((c + 0.055) / 1.055).powf(2.4)
+                }
+            }),
+            ColorSpace::Linear => color,
+        }
+    }
+
+    /// Convert a linear color to this color space, the inverse of [`ColorSpace::to_linear`].
+    pub fn from_linear(self, color: [f32; 3]) -> [f32; 3] {
+        match self {
+            ColorSpace::Srgb => color.map(|c| {
+                if c <= 0.0031308 {
+                    c * 12.92
+                } else {
+                    1.055 * c.powf(1.0 / 2.4) - 0.055
+                }
+            }),
+            ColorSpace::Linear => color,
+        }
+    }
+}
+
+/// A material in the metallic-roughness model, as used by glTF.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PbrMaterial {
+    pub name: String,
+    /// Linear RGBA base color; alpha holds the opacity.
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+    pub base_color_texture: Option<String>,
+    pub emissive_texture: Option<String>,
+    pub normal_texture: Option<String>,
+    /// Whether the material needs alpha blending, because it is not fully opaque or has an
+    /// opacity map.
+    pub transparent: bool,
+    /// The color space the colors of the source material were read in before conversion to
+    /// linear values.
+    pub source_color_space: ColorSpace,
+}
+
+impl Material {
+    /// The ambient color `Ka` in linear values, reading it in the given color space.
+    pub fn ka_linear(&self, space: ColorSpace) -> Option<[f32; 3]> {
+        self.ka.map(|color| space.to_linear(color))
+    }
+
+    /// The diffuse color `Kd` in linear values, reading it in the given color space.
+    pub fn kd_linear(&self, space: ColorSpace) -> Option<[f32; 3]> {
+        self.kd.map(|color| space.to_linear(color))
+    }
+
+    /// The specular color `Ks` in linear values, reading it in the given color space.
+    pub fn ks_linear(&self, space: ColorSpace) -> Option<[f32; 3]> {
+        self.ks.map(|color| space.to_linear(color))
+    }
+
+    /// The emissive color `Ke` in linear values, reading it in the given color space.
+    pub fn ke_linear(&self, space: ColorSpace) -> Option<[f32; 3]> {
+        self.ke.map(|color| space.to_linear(color))
+    }
+
+    /// Approximate this material in the metallic-roughness model, reading its colors as linear.
+    ///
+    /// See [`Material::to_pbr_in`].
+    pub fn to_pbr(&self) -> PbrMaterial {
+        self.to_pbr_in(ColorSpace::Linear)
+    }
+
+    /// Approximate this material in the metallic-roughness model, reading its colors in the given
+    /// color space.
+    ///
+    /// The base color is taken from `Kd` and the opacity from `d`, or `Tr` if `d` is missing.
+    /// The roughness is derived from the specular exponent `Ns` as `sqrt(2 / (Ns + 2))`, matching
+    /// the Blinn-Phong lobe to a GGX one, and the material is assumed to be a dielectric. The bump
+    /// map is used as the normal texture. Texture paths are stripped of their options.
+    pub fn to_pbr_in(&self, space: ColorSpace) -> PbrMaterial {
+        let [r, g, b] = self.kd_linear(space).unwrap_or([1.0; 3]);
+        let opacity = self.d.or(self.tr.map(|tr| 1.0 - tr)).unwrap_or(1.0).clamp(0.0, 1.0);
+        let texture = |map: &Option<String>| map.as_deref().map(|map| strip_texture_options(map).to_string());
+        let roughness = self.ns.map_or(1.0, |ns| (2.0 / (ns.max(0.0) + 2.0)).sqrt());
+        PbrMaterial {
+            name: self.name.clone(),
+            base_color: [r, g, b, opacity],
+            metallic: 0.0,
+            roughness,
+            emissive: self.ke_linear(space).unwrap_or([0.0; 3]),
+            base_color_texture: texture(&self.map_kd),
+            emissive_texture: texture(&self.map_ke),
+            normal_texture: texture(&self.map_bump),
+            transparent: opacity < 1.0 || self.map_d.is_some(),
+            source_color_space: space,
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversion of large .obj files in chunks, without loading them whole.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::obj::{write_io, Group, ObjData, ObjError, RenderState};
+use crate::split::{read_budget_parts, SplitBudget};
+use crate::weld::WeldConfig;
+
+/// A processing pass of a [`Pipeline`].
+#[derive(Copy, Clone, Debug)]
+enum Pass {
+    Triangulate,
+    Weld(WeldConfig),
+    GenerateNormals,
+}
+
+/// A chain of processing passes applied to a .obj file one chunk at a time, writing each chunk as
+/// soon as it is processed.
+///
+/// The input is read as by [`ObjData::split_file_by_budget`]: polygons and lines are collected in
+/// chunks that stay within the budget, each turned into a standalone mesh with only the
+/// attributes it uses. The passes run on every chunk in the order they were added, and the chunk
+/// is appended to the output, so at most one chunk of polygons is held in memory along with the
+/// vertex attributes of the input.
+///
+/// Passes only see the chunk they run on. Passes that need the whole mesh are bounded by the
+/// chunk: [`Pipeline::weld`] doesn't merge attributes of different chunks, and
+/// [`Pipeline::generate_normals`] doesn't smooth across the edges between chunks. Attributes
+/// used by several chunks are written once for each.
+///
+/// ```no_run
+/// use obj::{Pipeline, SplitBudget, WeldConfig};
+///
+/// let budget = SplitBudget {
+///     max_elements: Some(100_000),
+///     ..SplitBudget::default()
+/// };
+/// Pipeline::new(budget)
+///     .triangulate()
+///     .weld(WeldConfig::default())
+///     .generate_normals()
+///     .run_file("huge.obj", "converted.obj")?;
+/// # Ok::<(), obj::ObjError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    budget: SplitBudget,
+    passes: Vec<Pass>,
+}
+
+impl Pipeline {
+    /// A pipeline without passes that reads chunks within `budget`.
+    ///
+    /// With the default budget, which has no limits, the whole file is a single chunk.
+    pub fn new(budget: SplitBudget) -> Self {
+        Pipeline {
+            budget,
+            passes: Vec::new(),
+        }
+    }
+
+    /// Add a pass splitting polygons into triangles, see [`ObjData::to_triangles`].
+    pub fn triangulate(mut self) -> Self {
+        self.passes.push(Pass::Triangulate);
+        self
+    }
+
+    /// Add a pass merging the attributes of each chunk according to `config`, see
+    /// [`ObjData::weld_vertices_with_config`].
+    ///
+    /// Attributes are only merged within a chunk.
+    pub fn weld(mut self, config: WeldConfig) -> Self {
+        self.passes.push(Pass::Weld(config));
+        self
+    }
+
+    /// Add a pass replacing the normals of each chunk with one smooth normal per position,
+    /// averaging the normals of the polygons around it weighted by their area.
+    ///
+    /// Only the polygons of the chunk are averaged, so normals may differ along the edges between
+    /// chunks. Lines lose their normals.
+    pub fn generate_normals(mut self) -> Self {
+        self.passes.push(Pass::GenerateNormals);
+        self
+    }
+
+    /// Run the passes on the .obj file read from `reader`, writing the result to `out` chunk by
+    /// chunk, and return the number of chunks.
+    ///
+    /// Each chunk is written as a block of vertex attributes followed by its polygons and lines,
+    /// using negative indices relative to the end of that block. Material libraries are referred
+    /// to before the first chunk following their `mtllib` statement. The input is read as with
+    /// [`LoadConfig::strict`](crate::LoadConfig::strict) turned off, so unknown statements are
+    /// ignored.
+    pub fn run(&self, reader: impl BufRead, out: &mut impl Write) -> Result<usize, ObjError> {
+        let mut chunks = 0;
+        let mut libraries = 0;
+        let mut object: Option<Arc<str>> = None;
+        let mut previous: Option<Group> = None;
+        let mut state: Option<RenderState> = None;
+        read_budget_parts(
+            reader,
+            self.budget,
+            |_| Ok(()),
+            |mut chunk| {
+                for pass in &self.passes {
+                    pass.apply(&mut chunk);
+                }
+                write_io(out, |out| {
+                    if chunks == 0 {
+                        chunk.write_header(out)?;
+                    }
+                    for mtl_lib in &chunk.material_libs[libraries..] {
+                        writeln!(out, "mtllib {}", mtl_lib.filename)?;
+                    }
+                    chunk.write_attributes(false, out)?;
+                    let lens = [chunk.position.len(), chunk.texture.len(), chunk.normal.len()];
+                    for o in &chunk.objects {
+                        if object.as_ref() != Some(&o.name) {
+                            o.write_header(out)?;
+                            object = Some(o.name.clone());
+                            previous = None;
+                        }
+                        for group in &o.groups {
+                            group.write_header(previous.as_ref(), state.as_ref(), out)?;
+                            group.write_elements(Some(lens), out)?;
+                            // Only the material of the previous group is needed for the next one.
+                            let mut header = Group::new(group.name.clone());
+                            header.material = group.material.clone();
+                            previous = Some(header);
+                            state = Some(group.end_render_state().clone());
+                        }
+                    }
+                    Ok(())
+                })?;
+                libraries = chunk.material_libs.len();
+                chunks += 1;
+                Ok(())
+            },
+        )?;
+        Ok(chunks)
+    }
+
+    /// Run the passes on the .obj file at `input`, writing the result to the file at `output`,
+    /// which is overwritten if it exists, see [`Pipeline::run`].
+    ///
+    /// Material libraries are referred to by the same names, and aren't copied.
+    pub fn run_file(&self, input: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<usize, ObjError> {
+        let reader = BufReader::new(File::open(input)?);
+        let mut out = BufWriter::new(File::create(output)?);
+        let chunks = self.run(reader, &mut out)?;
+        out.flush()?;
+        Ok(chunks)
+    }
+}
+
+impl Pass {
+    fn apply(self, chunk: &mut ObjData) {
+        match self {
+            Pass::Triangulate => {
+                chunk.to_triangles();
+            }
+            Pass::Weld(config) => {
+                chunk.weld_vertices_with_config(config);
+            }
+            Pass::GenerateNormals => {
+                chunk.normal = chunk.smooth_normals();
+                for tuple in chunk.polys_mut().flat_map(|poly| poly.0.iter_mut()) {
+                    tuple.normal = Some(tuple.position);
+                }
+                let lines = chunk
+                    .objects
+                    .iter_mut()
+                    .flat_map(|o| &mut o.groups)
+                    .flat_map(|g| &mut g.lines);
+                for tuple in lines.flat_map(|line| line.0.iter_mut()) {
+                    tuple.normal = None;
+                }
+            }
+        }
+    }
+}
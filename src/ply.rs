@@ -0,0 +1,464 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Reading and writing PLY files.
+//!
+//! ASCII as well as little and big-endian binary files are supported. Vertex positions, normals
+//! (`nx`, `ny`, `nz`), texture coordinates (`u`, `v` or `s`, `t`) and colors (`red`, `green`,
+//! `blue`) are mapped onto `ObjData`, and faces become polygons of a single group. Other elements
+//! and properties are skipped.
+//!
+//! ```
+//! use obj::{ply, ObjData};
+//!
+//! let data = ObjData::load_buf("v 0 0 0 1 0 0\nv 1 0 0 0 1 0\nv 0 1 0 0 0 1\nf 1 2 3\n".as_bytes()).unwrap();
+//! let mut buf = Vec::new();
+//! ply::write(&data, &mut buf, ply::PlyFormat::BinaryLittleEndian).unwrap();
+//! let loaded = ply::load_buf(&buf[..]).unwrap();
+//! assert_eq!(loaded.position, data.position);
+//! assert_eq!(loaded.color, data.color);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::obj::{Group, IndexTuple, ObjData, Object, SimplePolygon, DEFAULT_GROUP, DEFAULT_OBJECT};
+
+/// The encoding of a PLY file body.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// Errors reading or writing a PLY file.
+#[derive(Debug)]
+pub enum PlyError {
+    Io(io::Error),
+    /// The header is missing, malformed or uses an unsupported feature.
+    InvalidHeader(String),
+    /// A value in the body could not be parsed.
+    InvalidValue(String),
+    /// The body ended before all elements announced by the header were read.
+    UnexpectedEof,
+    /// A face references a vertex that does not exist.
+    IndexOutOfRange(usize),
+}
+
+impl std::error::Error for PlyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PlyError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlyError::Io(err) => write!(f, "I/O error reading or writing a PLY file: {}", err),
+            PlyError::InvalidHeader(line) => write!(f, "Invalid or unsupported PLY header line: {}", line),
+            PlyError::InvalidValue(val) => write!(f, "Attempted to parse the value '{}' but failed.", val),
+            PlyError::UnexpectedEof => write!(f, "PLY file ended before all elements were read."),
+            PlyError::IndexOutOfRange(index) => write!(f, "Face references the missing vertex {}.", index),
+        }
+    }
+}
+
+impl From<io::Error> for PlyError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Scalar> {
+        Some(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+}
+
+enum Property {
+    Scalar(Scalar, String),
+    /// A list with the count and the item type.
+    List(Scalar, Scalar, String),
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+enum Body<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary { bytes: &'a [u8], big_endian: bool },
+}
+
+impl Body<'_> {
+    fn read(&mut self, ty: Scalar) -> Result<f64, PlyError> {
+        match self {
+            Body::Ascii(tokens) => {
+                let token = tokens.next().ok_or(PlyError::UnexpectedEof)?;
+                token.parse().map_err(|_| PlyError::InvalidValue(token.to_string()))
+            }
+            Body::Binary { bytes, big_endian } => {
+                if bytes.len() < ty.size() {
+                    return Err(PlyError::UnexpectedEof);
+                }
+                let (value, rest) = bytes.split_at(ty.size());
+                *bytes = rest;
+                let mut buf = [0u8; 8];
+                buf[..value.len()].copy_from_slice(value);
+                if *big_endian {
+                    buf[..value.len()].reverse();
+                }
+                let [b0, b1, b2, b3, ..] = buf;
+                Ok(match ty {
+                    Scalar::I8 => b0 as i8 as f64,
+                    Scalar::U8 => b0 as f64,
+                    Scalar::I16 => i16::from_le_bytes([b0, b1]) as f64,
+                    Scalar::U16 => u16::from_le_bytes([b0, b1]) as f64,
+                    Scalar::I32 => i32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    Scalar::U32 => u32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    Scalar::F32 => f32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    Scalar::F64 => f64::from_le_bytes(buf),
+                })
+            }
+        }
+    }
+
+    /// Read a list count or index, which has to be a non-negative integer.
+    fn read_index(&mut self, ty: Scalar) -> Result<usize, PlyError> {
+        let value = self.read(ty)?;
+        if value.fract() != 0.0 || value < 0.0 || value >= usize::MAX as f64 {
+            return Err(PlyError::InvalidValue(value.to_string()));
+        }
+        Ok(value as usize)
+    }
+}
+
+/// Load a PLY file from the given path.
+pub fn load(path: impl AsRef<Path>) -> Result<ObjData, PlyError> {
+    load_buf(File::open(path)?)
+}
+
+/// Load a PLY file from a reader.
+///
+/// Every vertex becomes one position and, if the file has the respective properties, one normal,
+/// texture coordinate and color, all with the index of the vertex. Integer colors are scaled from
+/// their type's range to `0.0..=1.0`.
+pub fn load_buf<R: Read>(mut input: R) -> Result<ObjData, PlyError> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+
+    if !bytes.starts_with(b"ply") {
+        return Err(PlyError::InvalidHeader("missing magic number".to_string()));
+    }
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut rest = &bytes[..];
+    loop {
+        let end = rest.iter().position(|&b| b == b'\n').ok_or(PlyError::UnexpectedEof)?;
+        let line = String::from_utf8_lossy(&rest[..end]).trim().to_string();
+        rest = &rest[end + 1..];
+        let invalid = || PlyError::InvalidHeader(line.clone());
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["ply"] | [] | ["comment", ..] | ["obj_info", ..] => {}
+            ["format", name, "1.0"] => {
+                format = Some(match *name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return Err(invalid()),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse().map_err(|_| invalid())?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let (count, item) = Scalar::parse(count).zip(Scalar::parse(item)).ok_or_else(invalid)?;
+                let element = elements.last_mut().ok_or_else(invalid)?;
+                element.properties.push(Property::List(count, item, name.to_string()));
+            }
+            ["property", ty, name] => {
+                let ty = Scalar::parse(ty).ok_or_else(invalid)?;
+                let element = elements.last_mut().ok_or_else(invalid)?;
+                element.properties.push(Property::Scalar(ty, name.to_string()));
+            }
+            ["end_header"] => break,
+            _ => return Err(invalid()),
+        }
+    }
+
+    let mut body = match format.ok_or_else(|| PlyError::InvalidHeader("missing format".to_string()))? {
+        PlyFormat::Ascii => Body::Ascii(
+            std::str::from_utf8(rest)
+                .map_err(|e| PlyError::InvalidValue(e.to_string()))?
+                .split_ascii_whitespace(),
+        ),
+        PlyFormat::BinaryLittleEndian => Body::Binary {
+            bytes: rest,
+            big_endian: false,
+        },
+        PlyFormat::BinaryBigEndian => Body::Binary {
+            bytes: rest,
+            big_endian: true,
+        },
+    };
+
+    let mut data = ObjData::default();
+    let mut group = Group::new(DEFAULT_GROUP.to_string());
+    let mut vertex_count = 0;
+    for element in &elements {
+        let has = |names: &[&str]| {
+            element.properties.iter().any(|p| match p {
+                Property::Scalar(_, name) => names.contains(&name.as_str()),
+                Property::List(..) => false,
+            })
+        };
+        let (has_normal, has_texture, has_color) =
+            (has(&["nx"]), has(&["u", "s", "texture_u", "texture_s"]), has(&["red"]));
+
+        for _ in 0..element.count {
+            let (mut position, mut normal, mut texture, mut color) = ([0.0; 3], [0.0; 3], [0.0; 2], [1.0; 3]);
+            for property in &element.properties {
+                match property {
+                    Property::Scalar(ty, name) => {
+                        let value = body.read(*ty)?;
+                        let color_value = || match ty {
+                            Scalar::U8 => value as f32 / u8::MAX as f32,
+                            Scalar::U16 => value as f32 / u16::MAX as f32,
+                            _ => value as f32,
+                        };
+                        match (element.name.as_str(), name.as_str()) {
+                            ("vertex", "x") => position[0] = value as f32,
+                            ("vertex", "y") => position[1] = value as f32,
+                            ("vertex", "z") => position[2] = value as f32,
+                            ("vertex", "nx") => normal[0] = value as f32,
+                            ("vertex", "ny") => normal[1] = value as f32,
+                            ("vertex", "nz") => normal[2] = value as f32,
+                            ("vertex", "u" | "s" | "texture_u" | "texture_s") => texture[0] = value as f32,
+                            ("vertex", "v" | "t" | "texture_v" | "texture_t") => texture[1] = value as f32,
+                            ("vertex", "red") => color[0] = color_value(),
+                            ("vertex", "green") => color[1] = color_value(),
+                            ("vertex", "blue") => color[2] = color_value(),
+                            _ => {}
+                        }
+                    }
+                    Property::List(count_ty, item_ty, name) => {
+                        // The count comes from the file, so nothing is allocated for it upfront.
+                        let count = body.read_index(*count_ty)?;
+                        let mut items = Vec::new();
+                        for _ in 0..count {
+                            items.push(body.read_index(*item_ty)?);
+                        }
+                        if element.name == "face" && (name == "vertex_indices" || name == "vertex_index") {
+                            let poly = items
+                                .into_iter()
+                                .map(|i| {
+                                    if i >= vertex_count {
+                                        return Err(PlyError::IndexOutOfRange(i));
+                                    }
+                                    Ok(IndexTuple::new(
+                                        i,
+                                        Some(i).filter(|_| !data.texture.is_empty()),
+                                        Some(i).filter(|_| !data.normal.is_empty()),
+                                    ))
+                                })
+                                .collect::<Result<_, _>>()?;
+                            group.polys.push(SimplePolygon(poly));
+                        }
+                    }
+                }
+            }
+            if element.name == "vertex" {
+                data.position.push(position);
+                if has_normal {
+                    data.normal.push(normal);
+                }
+                if has_texture {
+                    data.texture.push(texture);
+                }
+                if has_color {
+                    data.color.push(color);
+                }
+            }
+        }
+        if element.name == "vertex" {
+            vertex_count = data.position.len();
+        }
+    }
+
+    let mut object = Object::new(DEFAULT_OBJECT.to_string());
+    object.groups.push(group);
+    data.objects.push(object);
+    Ok(data)
+}
+
+/// Save the data as a PLY file in the given format.
+pub fn save(data: &ObjData, path: impl AsRef<Path>, format: PlyFormat) -> Result<(), PlyError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write(data, &mut out, format)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the polygons of all groups as a PLY file in the given format.
+///
+/// PLY vertices carry all their attributes, so one vertex is written per distinct index tuple.
+/// Normals and texture coordinates are written if any polygon references them, vertices lacking
+/// them get zeros. Colors are written as `uchar`. Lines are not written.
+pub fn write(data: &ObjData, out: &mut impl Write, format: PlyFormat) -> Result<(), PlyError> {
+    let mut vertices: Vec<IndexTuple> = Vec::new();
+    let mut lookup: HashMap<IndexTuple, usize> = HashMap::new();
+    let faces: Vec<Vec<usize>> = data
+        .polys()
+        .map(|poly| {
+            poly.0
+                .iter()
+                .map(|&tuple| {
+                    *lookup.entry(tuple).or_insert_with(|| {
+                        vertices.push(tuple);
+                        vertices.len() - 1
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    let has_texture = vertices.iter().any(|t| t.texture.is_some());
+    let has_normal = vertices.iter().any(|t| t.normal.is_some());
+    let has_color = !data.color.is_empty();
+
+    let name = match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        PlyFormat::BinaryBigEndian => "binary_big_endian",
+    };
+    writeln!(out, "ply")?;
+    writeln!(out, "format {} 1.0", name)?;
+    writeln!(
+        out,
+        "comment Generated by the obj Rust library (https://crates.io/crates/obj)."
+    )?;
+    writeln!(out, "element vertex {}", vertices.len())?;
+    let mut components = vec!["x", "y", "z"];
+    if has_normal {
+        components.extend(&["nx", "ny", "nz"]);
+    }
+    if has_texture {
+        components.extend(&["u", "v"]);
+    }
+    for component in &components {
+        writeln!(out, "property float {}", component)?;
+    }
+    if has_color {
+        writeln!(out, "property uchar red")?;
+        writeln!(out, "property uchar green")?;
+        writeln!(out, "property uchar blue")?;
+    }
+    writeln!(out, "element face {}", faces.len())?;
+    writeln!(out, "property list uchar int vertex_indices")?;
+    writeln!(out, "end_header")?;
+
+    let write_float = |out: &mut dyn Write, value: f32| match format {
+        PlyFormat::Ascii => write!(out, "{} ", value),
+        PlyFormat::BinaryLittleEndian => out.write_all(&value.to_le_bytes()),
+        PlyFormat::BinaryBigEndian => out.write_all(&value.to_be_bytes()),
+    };
+    let write_int = |out: &mut dyn Write, value: u32| match format {
+        PlyFormat::Ascii => write!(out, "{} ", value),
+        PlyFormat::BinaryLittleEndian => out.write_all(&value.to_le_bytes()),
+        PlyFormat::BinaryBigEndian => out.write_all(&value.to_be_bytes()),
+    };
+    let write_byte = |out: &mut dyn Write, value: u8| match format {
+        PlyFormat::Ascii => write!(out, "{} ", value),
+        _ => out.write_all(&[value]),
+    };
+    let end_line = |out: &mut dyn Write| match format {
+        PlyFormat::Ascii => writeln!(out),
+        _ => Ok(()),
+    };
+
+    for &IndexTuple {
+        position: p,
+        texture: t,
+        normal: n,
+    } in &vertices
+    {
+        let mut values = data.position[p].to_vec();
+        if has_normal {
+            values.extend(&n.map_or([0.0; 3], |n| data.normal[n]));
+        }
+        if has_texture {
+            values.extend(&t.map_or([0.0; 2], |t| data.texture[t]));
+        }
+        for value in values {
+            write_float(out, value)?;
+        }
+        if has_color {
+            for &c in &data.color[p] {
+                write_byte(out, (c.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8)?;
+            }
+        }
+        end_line(out)?;
+    }
+    for face in &faces {
+        // Faces with more vertices than the count type can hold cannot be represented.
+        write_byte(out, face.len().min(u8::MAX as usize) as u8)?;
+        for &i in face.iter().take(u8::MAX as usize) {
+            write_int(out, i as u32)?;
+        }
+        end_line(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Geometric queries on single polygons.
+
+use crate::math::{add, cross, dot, length, newell_normal, normalize, scale, sub};
+use crate::obj::{Group, ObjData, SimplePolygon};
+
+/// A polygon together with the data its indices refer to.
+///
+/// Returned by [`ObjData::polygons`] and [`Group::polygons`], or created with
+/// [`PolygonView::new`]. The queries panic if a position index of the polygon is out of range.
+#[derive(Clone, Copy, Debug)]
+pub struct PolygonView<'a> {
+    pub data: &'a ObjData,
+    pub polygon: &'a SimplePolygon,
+}
+
+impl<'a> PolygonView<'a> {
+    pub fn new(data: &'a ObjData, polygon: &'a SimplePolygon) -> Self {
+        PolygonView { data, polygon }
+    }
+
+    /// The positions of the vertices, in order.
+    pub fn positions(&self) -> impl Iterator<Item = [f32; 3]> + 'a {
+        let position = &self.data.position;
+        self.polygon.0.iter().map(move |t| position[t.position])
+    }
+
+    fn newell_normal(&self) -> [f32; 3] {
+        let points: Vec<_> = self.positions().collect();
+        newell_normal(&points)
+    }
+
+    /// The area of the polygon, projected onto its plane if it isn't planar.
+    pub fn area(&self) -> f32 {
+        0.5 * length(self.newell_normal())
+    }
+
+    /// The unit normal of the polygon by Newell's method, facing the side from which the vertices
+    /// wind counter-clockwise.
+    ///
+    /// Returns `None` for polygons without area.
+    pub fn face_normal(&self) -> Option<[f32; 3]> {
+        normalize(self.newell_normal())
+    }
+
+    /// The center of mass of the polygon's surface, or `None` if it has no vertices.
+    ///
+    /// This is exact for planar polygons, including concave ones. The average of the vertices is
+    /// returned for polygons without area.
+    pub fn centroid(&self) -> Option<[f32; 3]> {
+        let points: Vec<_> = self.positions().collect();
+        let first = *points.first()?;
+        let average = scale(
+            points.iter().fold([0.0; 3], |sum, &p| add(sum, p)),
+            1.0 / points.len() as f32,
+        );
+        let normal = match normalize(newell_normal(&points)) {
+            Some(normal) => normal,
+            None => return Some(average),
+        };
+        // Fan triangles weighted by their signed area, so that the parts of concave polygons
+        // covered twice cancel out.
+        let (mut total, mut weighted) = (0.0, [0.0; 3]);
+        for pair in points[1..].windows(2) {
+            let area = 0.5 * dot(cross(sub(pair[0], first), sub(pair[1], first)), normal);
+            let center = scale(add(first, add(pair[0], pair[1])), 1.0 / 3.0);
+            total += area;
+            weighted = add(weighted, scale(center, area));
+        }
+        Some(scale(weighted, 1.0 / total))
+    }
+}
+
+impl Group {
+    /// Iterate over views of this group's polygons, which index into `data`.
+    pub fn polygons<'a>(&'a self, data: &'a ObjData) -> impl Iterator<Item = PolygonView<'a>> + 'a {
+        self.polys.iter().map(move |polygon| PolygonView::new(data, polygon))
+    }
+}
+
+impl ObjData {
+    /// Iterate over views of all polygons, in order.
+    pub fn polygons(&self) -> impl Iterator<Item = PolygonView<'_>> {
+        self.polys().map(move |polygon| PolygonView::new(self, polygon))
+    }
+}
@@ -0,0 +1,108 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Polyline queries and construction for `l` elements.
+
+use crate::math::{add, length, scale, sub};
+use crate::obj::{IndexTuple, Line, ObjData};
+
+impl Line {
+    /// Append the given points to the positions of `data` and return a line through them, in
+    /// order.
+    ///
+    /// The points get the default white vertex color if `data` has vertex colors.
+    pub fn from_points(data: &mut ObjData, points: &[[f32; 3]]) -> Line {
+        let first = data.position.len();
+        data.position.extend_from_slice(points);
+        if !data.color.is_empty() {
+            data.color.resize(data.position.len(), [1.0; 3]);
+        }
+        Line(
+            (first..data.position.len())
+                .map(|p| IndexTuple::new(p, None, None))
+                .collect(),
+        )
+    }
+
+    /// The positions of the vertices in `data`, in order.
+    ///
+    /// Panics if a position index is out of range, like the other queries.
+    pub fn points(&self, data: &ObjData) -> Vec<[f32; 3]> {
+        self.0.iter().map(|t| data.position[t.position]).collect()
+    }
+
+    /// The total length of all segments.
+    pub fn length(&self, data: &ObjData) -> f32 {
+        let points = self.points(data);
+        points.windows(2).map(|w| length(sub(w[1], w[0]))).sum()
+    }
+
+    /// Whether the line returns to its start, ending at the first vertex or at the same
+    /// coordinates.
+    ///
+    /// Lines with fewer than three vertices are never closed.
+    pub fn is_closed(&self, data: &ObjData) -> bool {
+        match (self.0.first(), self.0.last()) {
+            (Some(first), Some(last)) if self.0.len() >= 3 => {
+                first.position == last.position || data.position[first.position] == data.position[last.position]
+            }
+            _ => false,
+        }
+    }
+
+    /// `count` points spaced evenly along the line, including both ends.
+    ///
+    /// A single point is the start of the line. Lines without length yield `count` copies of
+    /// their first point, and lines without vertices no points.
+    pub fn resample(&self, data: &ObjData, count: usize) -> Vec<[f32; 3]> {
+        let points = self.points(data);
+        let first = match points.first() {
+            Some(&first) if count > 0 => first,
+            _ => return Vec::new(),
+        };
+        let total = self.length(data);
+        if count == 1 || total == 0.0 {
+            return vec![first; count];
+        }
+
+        let step = total / (count - 1) as f32;
+        let mut samples = Vec::with_capacity(count);
+        let mut segments = points.windows(2);
+        // The current segment and the distance along the line at which it starts.
+        let (mut a, mut b) = (first, first);
+        let (mut start, mut end) = (0.0, 0.0);
+        for i in 0..count - 1 {
+            let target = i as f32 * step;
+            while end < target || a == b {
+                match segments.next() {
+                    Some(w) => {
+                        a = w[0];
+                        b = w[1];
+                        start = end;
+                        end += length(sub(b, a));
+                    }
+                    None => break,
+                }
+            }
+            let t = if end > start {
+                ((target - start) / (end - start)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            samples.push(add(a, scale(sub(b, a), t)));
+        }
+        samples.push(*points.last().unwrap());
+        samples
+    }
+}
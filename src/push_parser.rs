@@ -0,0 +1,973 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Incremental parsing of .obj source that arrives in chunks.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{mem, str::FromStr};
+
+use crate::layout::{SourceElement, SourceLayout, SourceLine};
+use crate::mtl::Mtl;
+use crate::obj::{
+    DuplicateObjectPolicy, Group, Interner, LoadConfig, LoadWarning, ObjData, ObjError, ObjMaterial, Object,
+    OutOfRangePolicy, RenderState, Scalar, SimplePolygon, TracedStatement, TracedValue, WriteToFmt, DEFAULT_GROUP,
+    DEFAULT_OBJECT, SUMMARIES,
+};
+use crate::raw::Statement;
+
+#[cfg(feature = "memchr")]
+fn find_newline(bytes: &[u8]) -> Option<usize> {
+    memchr::memchr(b'\n', bytes)
+}
+
+#[cfg(not(feature = "memchr"))]
+fn find_newline(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|&b| b == b'\n')
+}
+
+/// The stored attributes seen so far with [`LoadConfig::dedup_attributes`].
+#[derive(Default)]
+struct AttributeDedup {
+    /// The stored index of every position, texture coordinate and normal, in the order of the
+    /// source.
+    stored: [Vec<usize>; 3],
+    /// The bits of the positions and their colors.
+    position: BTreeMap<([u64; 3], [u32; 3]), usize>,
+    texture: BTreeMap<[u32; 2], usize>,
+    normal: BTreeMap<[u32; 3], usize>,
+}
+
+/// Record the attribute with the given key, which is stored at `next` unless an equal attribute
+/// was stored before. Returns whether it is such a duplicate.
+fn dedup<K: Ord>(map: &mut BTreeMap<K, usize>, stored: &mut Vec<usize>, key: Option<K>, next: usize) -> bool {
+    let index = match key {
+        Some(key) => *map.entry(key).or_insert(next),
+        None => next,
+    };
+    stored.push(index);
+    index != next
+}
+
+impl AttributeDedup {
+    /// The number of attributes of each kind read so far, which indices in the source refer to.
+    fn lens(&self) -> [usize; 3] {
+        [self.stored[0].len(), self.stored[1].len(), self.stored[2].len()]
+    }
+
+    /// Map an index in the source to the stored attribute, or past the stored attributes if it
+    /// is out of range.
+    fn map(&self, attr: usize, index: usize, len: usize) -> usize {
+        let stored = &self.stored[attr];
+        stored
+            .get(index)
+            .copied()
+            .unwrap_or_else(|| len + (index - stored.len()))
+    }
+}
+
+/// The statements counted since the last summary comment of each kind, see
+/// [`LoadWarning::SummaryMismatch`].
+#[derive(Default)]
+struct Summaries {
+    /// `v`, `vt`, `vn`, `f` and `l`, and `f` statements, like in [`SUMMARIES`].
+    counts: [usize; 5],
+    /// The words of the last summary comment of each kind.
+    seen: [Option<&'static str>; 5],
+}
+
+impl Summaries {
+    /// Count the parsed `statement`, returning a warning if it is a summary comment that doesn't
+    /// match the count.
+    fn check(&mut self, statement: &Statement<'_>) -> Option<LoadWarning> {
+        let kinds: &[usize] = match statement.keyword() {
+            "v" => &[0],
+            "vt" => &[1],
+            "vn" => &[2],
+            "f" => &[3, 4],
+            "l" => &[3],
+            "#" => {
+                let (count, words) = statement.rest().split_once(' ')?;
+                let &(summary, kind) = SUMMARIES.iter().find(|(summary, _)| *summary == words.trim())?;
+                let expected = count.parse().ok()?;
+                let found = mem::take(&mut self.counts[kind]);
+                self.seen[kind] = Some(summary);
+                return Some(LoadWarning::SummaryMismatch {
+                    line_number: statement.line_number(),
+                    summary: summary.to_string(),
+                    expected: Some(expected),
+                    found,
+                })
+                .filter(|_| expected != found);
+            }
+            _ => &[],
+        };
+        for &kind in kinds {
+            self.counts[kind] += 1;
+        }
+        None
+    }
+
+    /// Warnings for the statements following the last summary comment of their kind at the end of
+    /// the file, given its number of lines.
+    fn finish(&self, lines: usize) -> impl Iterator<Item = LoadWarning> + '_ {
+        (0..5).filter_map(move |kind| {
+            let summary = self.seen[kind].filter(|_| self.counts[kind] > 0)?;
+            Some(LoadWarning::SummaryMismatch {
+                line_number: lines,
+                summary: summary.to_string(),
+                expected: None,
+                found: self.counts[kind],
+            })
+        })
+    }
+}
+
+/// Builds an `ObjData` from lines or byte chunks as they become available.
+///
+/// Statements may be split across the chunks given to [`PushParser::push_bytes`], incomplete
+/// lines are kept until the rest arrives or [`PushParser::finish`] is called. Loading from a
+/// reader or a slice uses this parser, so the result is the same.
+pub struct PushParser<S = f32> {
+    config: LoadConfig,
+    data: ObjData<S>,
+    names: Interner,
+    object: Object,
+    group: Option<Group>,
+    /// The render state set by the statements so far, which new groups start with.
+    state: RenderState,
+    line_number: usize,
+    partial: Vec<u8>,
+    /// References past the elements read so far as `(line number, attribute, 1-based index)`,
+    /// checked against the final counts by [`PushParser::finish`].
+    forward_refs: Vec<(usize, usize, isize)>,
+    warnings: Vec<LoadWarning>,
+    /// The lines read so far with [`LoadConfig::faithful`].
+    layout: Option<SourceLayout>,
+    /// The line of the `o` statement of the current object in `layout`.
+    object_line: Option<usize>,
+    /// The attributes read so far with [`LoadConfig::dedup_attributes`].
+    dedup: Option<AttributeDedup>,
+    /// Whether only comments were read so far, which may describe the file, see [`ObjData::metadata`].
+    header: bool,
+    /// The statements counted for checking summary comments with [`LoadConfig::faithful`].
+    summaries: Option<Summaries>,
+}
+
+impl<S: Scalar> Default for PushParser<S> {
+    fn default() -> Self {
+        Self::new(LoadConfig::default())
+    }
+}
+
+impl<S: Scalar> PushParser<S> {
+    pub fn new(config: LoadConfig) -> Self {
+        let mut names = Interner::default();
+        let data = ObjData {
+            default_material: config.default_material.clone(),
+            ..ObjData::default()
+        };
+        PushParser {
+            object: Object::new(names.intern(config.implicit_object_name())),
+            layout: if config.faithful && !config.filters() && !config.geometry_only && !config.dedup_attributes {
+                Some(SourceLayout::default())
+            } else {
+                None
+            },
+            object_line: None,
+            summaries: if config.faithful {
+                Some(Summaries::default())
+            } else {
+                None
+            },
+            dedup: if config.dedup_attributes {
+                Some(AttributeDedup::default())
+            } else {
+                None
+            },
+            config,
+            data,
+            names,
+            group: None,
+            state: RenderState::default(),
+            line_number: 0,
+            partial: Vec::new(),
+            forward_refs: Vec::new(),
+            warnings: Vec::new(),
+            header: true,
+        }
+    }
+
+    /// The 0-based number of the next line to be parsed.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// The problems that were worked around so far, such as decimal commas.
+    pub fn warnings(&self) -> &[LoadWarning] {
+        &self.warnings
+    }
+
+    /// Parse all complete lines of `bytes`, keeping a trailing incomplete line for later.
+    ///
+    /// Lines are separated by `\n` or `\r\n` and each must be valid UTF-8.
+    pub fn push_bytes(&mut self, mut bytes: &[u8]) -> Result<(), ObjError> {
+        while let Some(end) = find_newline(bytes) {
+            if self.partial.is_empty() {
+                self.push_byte_line(&bytes[..end])?;
+            } else {
+                let mut line = mem::take(&mut self.partial);
+                line.extend_from_slice(&bytes[..end]);
+                self.push_byte_line(&line)?;
+            }
+            bytes = &bytes[end + 1..];
+        }
+        self.partial.extend_from_slice(bytes);
+        let pending = self.partial.strip_suffix(b"\r").unwrap_or(&self.partial);
+        self.check_line_length(pending.len())
+    }
+
+    /// Return [`ObjError::LineTooLong`] for the current line if it has more than
+    /// [`LoadConfig::max_line_length`] bytes.
+    fn check_line_length(&self, len: usize) -> Result<(), ObjError> {
+        match self.config.max_line_length {
+            Some(max) if len > max => Err(ObjError::LineTooLong {
+                line_number: self.line_number,
+                max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn push_byte_line(&mut self, line: &[u8]) -> Result<(), ObjError> {
+        let first = self.line_number == 0;
+        if let Some(layout) = self.layout.as_mut().filter(|_| first) {
+            layout.crlf = line.ends_with(b"\r");
+        }
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        self.check_line_length(line.len())?;
+        match core::str::from_utf8(line) {
+            Ok(line) => self.push_line(line),
+            Err(_) => {
+                let line_number = self.line_number;
+                self.line_number += 1;
+                self.skip_line(ObjError::InvalidUtf8 { line_number })
+            }
+        }
+    }
+
+    /// Go on with the next line after `err`, recording it as a warning, unless parsing is strict
+    /// or the error isn't about the syntax of the line, see [`LoadConfig::strict`].
+    fn skip_line(&mut self, err: ObjError) -> Result<(), ObjError> {
+        if self.config.strict || !err.is_malformed_line() {
+            return Err(err);
+        }
+        self.warnings.push(LoadWarning::SkippedLine {
+            line_number: self.line_number - 1,
+            error: err.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Parse a single complete line, without its line terminator.
+    ///
+    /// An incomplete line left by [`PushParser::push_bytes`] is ended and parsed first.
+    pub fn push_line(&mut self, line: &str) -> Result<(), ObjError> {
+        if !self.partial.is_empty() {
+            let partial = mem::take(&mut self.partial);
+            self.push_byte_line(&partial)?;
+        }
+        self.check_line_length(line.len())?;
+        let line_number = self.line_number;
+        self.line_number += 1;
+        let statement = match Statement::parse(line_number, line) {
+            Some(statement) => statement,
+            None => {
+                if let Some(layout) = &mut self.layout {
+                    layout.lines.push(SourceLine::Verbatim(line.to_string()));
+                }
+                return Ok(());
+            }
+        };
+        let objects = self.data.objects.len();
+        let elements = self.group.as_ref().map_or((0, 0), |g| (g.polys.len(), g.lines.len()));
+        let (warnings, forward_refs) = (self.warnings.len(), self.forward_refs.len());
+        let lens = self.source_lens();
+        match self.parse_statement(&statement) {
+            Ok(()) => {
+                if let Some(hook) = &self.config.on_statement {
+                    hook.call(&self.trace(&statement, lens, elements));
+                }
+                if let Some(warning) = self.summaries.as_mut().and_then(|s| s.check(&statement)) {
+                    self.warnings.push(warning);
+                }
+                self.record(&statement, line, objects, elements)
+            }
+            Err(err) => {
+                // Statements fail before adding their element, but may have noted some of it.
+                self.warnings.truncate(warnings);
+                self.forward_refs.truncate(forward_refs);
+                self.skip_line(err)?;
+                if let Some(layout) = &mut self.layout {
+                    layout.lines.push(SourceLine::Verbatim(line.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of attributes of each kind read so far, which indices in the source refer to.
+    fn source_lens(&self) -> [usize; 3] {
+        match &self.dedup {
+            Some(dedup) => dedup.lens(),
+            None => [
+                self.data.position.len(),
+                self.data.texture.len(),
+                self.data.normal.len(),
+            ],
+        }
+    }
+
+    /// Describe what the parsed `statement` resolved to, given the number of attributes of each
+    /// kind and the number of polygons and lines of the current group before it.
+    fn trace<'a>(
+        &'a self,
+        statement: &Statement<'a>,
+        lens: [usize; 3],
+        elements: (usize, usize),
+    ) -> TracedStatement<'a> {
+        let data = &self.data;
+        // The index of the attribute the statement added, and where it is stored.
+        let added = |attr: usize| {
+            let index = lens[attr];
+            let stored = match &self.dedup {
+                Some(dedup) => dedup.stored[attr].get(index).copied(),
+                None => Some(index).filter(|_| self.source_lens()[attr] > index),
+            };
+            stored.map(|stored| (index, stored))
+        };
+        let value = match statement.keyword() {
+            "v" => added(0).map_or(TracedValue::Other, |(index, stored)| {
+                let p = data.position[stored];
+                let position = (|| Some([p[0].to_f64()?, p[1].to_f64()?, p[2].to_f64()?]))();
+                let color = data.color.get(stored).copied();
+                TracedValue::Position { index, position, color }
+            }),
+            "vt" => added(1).map_or(TracedValue::Other, |(index, stored)| TracedValue::Texture {
+                index,
+                texture: data.texture[stored],
+            }),
+            "vn" => added(2).map_or(TracedValue::Other, |(index, stored)| TracedValue::Normal {
+                index,
+                normal: data.normal[stored],
+            }),
+            "f" => TracedValue::Polygons(self.group.as_ref().map_or(&[], |g| &g.polys[elements.0..])),
+            "l" => TracedValue::Lines(self.group.as_ref().map_or(&[], |g| &g.lines[elements.1..])),
+            "o" => TracedValue::Object(&self.object.name),
+            "g" | "usemtl" | "s" | "bevel" | "c_interp" | "d_interp" | "lod" | "usemap" => {
+                TracedValue::Group(self.group.as_ref())
+            }
+            "mtllib" if !self.config.geometry_only => data
+                .material_libs
+                .last()
+                .map_or(TracedValue::Other, |mtl| TracedValue::MaterialLib(&mtl.filename)),
+            _ => TracedValue::Other,
+        };
+        TracedStatement {
+            line_number: statement.line_number(),
+            keyword: statement.keyword(),
+            args: statement.rest(),
+            value,
+        }
+    }
+
+    /// Add the parsed `statement` to the layout, given the number of finished objects and the
+    /// number of polygons and lines of the current group before it.
+    fn record(&mut self, statement: &Statement<'_>, line: &str, objects: usize, elements: (usize, usize)) {
+        let PushParser {
+            data,
+            object,
+            group,
+            layout,
+            object_line,
+            ..
+        } = self;
+        let layout = match layout {
+            Some(layout) => layout,
+            None => return,
+        };
+        let (o, g) = (data.objects.len(), object.groups.len());
+        let element = match statement.keyword() {
+            "v" => Some(SourceElement::Position(data.position.len() - 1)),
+            "vt" => Some(SourceElement::Texture(data.texture.len() - 1)),
+            "vn" => Some(SourceElement::Normal(data.normal.len() - 1)),
+            "f" => {
+                let polys = &group.as_ref().unwrap().polys;
+                if polys.len() - elements.0 > 1 {
+                    // A triangulated face is written as its triangles.
+                    for (index, poly) in polys.iter().enumerate().skip(elements.0) {
+                        let mut text = String::new();
+                        let _ = poly.write_to_fmt(&mut text);
+                        text.pop();
+                        let element = SourceElement::Poly {
+                            object: o,
+                            group: g,
+                            index,
+                        };
+                        layout.lines.push(SourceLine::Element { element, text });
+                    }
+                    return;
+                }
+                Some(SourceElement::Poly {
+                    object: o,
+                    group: g,
+                    index: elements.0,
+                })
+            }
+            "l" => Some(SourceElement::Line {
+                object: o,
+                group: g,
+                index: elements.1,
+            }),
+            "o" => {
+                // The previous object was dropped for being empty, so its line has no object.
+                if let Some(i) = object_line.take().filter(|_| o == objects) {
+                    if let SourceLine::Element { text, .. } = &mut layout.lines[i] {
+                        layout.lines[i] = SourceLine::Verbatim(mem::take(text));
+                    }
+                }
+                *object_line = Some(layout.lines.len());
+                Some(SourceElement::Object(o))
+            }
+            "g" if group.is_some() => Some(SourceElement::Group { object: o, group: g }),
+            "usemtl" => Some(SourceElement::Material { object: o, group: g }),
+            "mtllib" => Some(SourceElement::MaterialLib(data.material_libs.len() - 1)),
+            keyword @ ("shadow_obj" | "trace_obj") => {
+                let element = if keyword == "shadow_obj" {
+                    SourceElement::ShadowObj
+                } else {
+                    SourceElement::TraceObj
+                };
+                // Only the last statement sets the file, earlier ones are kept as they were.
+                for line in &mut layout.lines {
+                    if let SourceLine::Element { element: e, text } = line {
+                        if *e == element {
+                            *line = SourceLine::Verbatim(mem::take(text));
+                        }
+                    }
+                }
+                Some(element)
+            }
+            _ => None,
+        };
+        let text = line.to_string();
+        layout.lines.push(match element {
+            Some(element) => SourceLine::Element { element, text },
+            None => SourceLine::Verbatim(text),
+        });
+    }
+
+    /// Parse the remaining incomplete line, if any, and return the parsed data.
+    pub fn finish(self) -> Result<ObjData<S>, ObjError> {
+        self.finish_with_warnings().map(|(data, _)| data)
+    }
+
+    /// Like [`PushParser::finish`], also returning all [`PushParser::warnings`].
+    pub fn finish_with_warnings(mut self) -> Result<(ObjData<S>, Vec<LoadWarning>), ObjError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("resolve").entered();
+        if !self.partial.is_empty() {
+            let partial = mem::take(&mut self.partial);
+            self.push_byte_line(&partial)?;
+        }
+        if let Some(summaries) = &self.summaries {
+            self.warnings.extend(summaries.finish(self.line_number));
+        }
+        let counts = self.source_lens();
+        if let Some(&(line_number, attr, index)) = self
+            .forward_refs
+            .iter()
+            .find(|&&(_, attr, index)| index as usize > counts[attr])
+        {
+            return Err(ObjError::IndexOutOfRange {
+                line_number,
+                index,
+                count: counts[attr],
+            });
+        }
+        if let Some(g) = self.group {
+            self.object.groups.push(g);
+        }
+        if filter_groups(&self.config, &mut self.object) {
+            self.data.objects.push(self.object);
+        }
+        if let Some(dedup) = &self.dedup {
+            let data = &mut self.data;
+            let lens = [data.position.len(), data.texture.len(), data.normal.len()];
+            let groups = data.objects.iter_mut().flat_map(|o| &mut o.groups);
+            for group in groups {
+                let polys = group.polys.iter_mut().flat_map(|p| p.0.iter_mut());
+                for tuple in polys.chain(group.lines.iter_mut().flat_map(|l| &mut l.0)) {
+                    tuple.position = dedup.map(0, tuple.position, lens[0]);
+                    tuple.texture = tuple.texture.map(|t| dedup.map(1, t, lens[1]));
+                    tuple.normal = tuple.normal.map(|n| dedup.map(2, n, lens[2]));
+                }
+            }
+        }
+        if self.config.duplicate_objects == DuplicateObjectPolicy::Merge {
+            let map = merge_duplicate_objects(&mut self.data.objects);
+            if let Some(layout) = &mut self.layout {
+                layout.remap_objects(&map);
+            }
+        }
+        self.data.layout = self.layout;
+        Ok((self.data, self.warnings))
+    }
+
+    fn parse_statement(&mut self, statement: &Statement<'_>) -> Result<(), ObjError> {
+        if self.config.comma_decimals && matches!(statement.keyword(), "v" | "vt" | "vn") {
+            // A number with a comma never parses, so the statement can be fixed before reading it.
+            if let Some(fixed) = replace_decimal_commas(statement) {
+                if let Some(fixed_statement) = Statement::parse(statement.line_number(), &fixed) {
+                    self.parse_statement_impl(&fixed_statement)?;
+                    self.warnings.push(LoadWarning::CommaDecimal {
+                        line_number: statement.line_number(),
+                    });
+                    return Ok(());
+                }
+            }
+        }
+        self.parse_statement_impl(statement)
+    }
+
+    fn parse_statement_impl(&mut self, statement: &Statement<'_>) -> Result<(), ObjError> {
+        if !statement.is_comment() {
+            self.header = false;
+        } else if self.header {
+            let comment = format!("{} {}", &statement.keyword()[1..], statement.rest());
+            self.data.metadata.read_comment(&comment);
+        }
+
+        let idx = statement.line_number();
+        let mut words = statement.args();
+        let PushParser {
+            config,
+            data: dat,
+            names,
+            object,
+            group,
+            state,
+            forward_refs,
+            warnings,
+            dedup: attributes_seen,
+            ..
+        } = self;
+        let policy = config.out_of_range;
+        // Only position indices refer to stored attributes when the others are skipped.
+        let attributes = if config.geometry_only { 1 } else { 3 };
+        // Indices refer to the attributes of the source, which are only all stored without dedup.
+        let lens = match attributes_seen {
+            Some(seen) => seen.lens(),
+            None => [dat.position.len(), dat.texture.len(), dat.normal.len()],
+        };
+        let mut new_group = |name: &str| {
+            let mut g = Group::new(names.intern(name));
+            g.render_state = state.clone();
+            g
+        };
+
+        match statement.keyword() {
+            "v" => {
+                let (v0, v1, v2) = (words.next(), words.next(), words.next());
+                let mut position = ObjData::<S>::parse_three(idx, v0, v1, v2)?;
+                check_range(policy, idx, &[v0, v1, v2], &mut position, warnings)?;
+                let (r, g, b) = (words.next(), words.next(), words.next());
+                let color = if r.is_some() && g.is_some() {
+                    let mut color = ObjData::<S>::parse_three(idx, r, g, b)?;
+                    check_range(policy, idx, &[r, g, b], &mut color, warnings)?;
+                    Some(color)
+                } else {
+                    None
+                };
+                if let Some(seen) = attributes_seen {
+                    let bits = |p: [S; 3]| Some([p[0].exact_bits()?, p[1].exact_bits()?, p[2].exact_bits()?]);
+                    let key = bits(position).map(|p| (p, color.unwrap_or([1.0; 3]).map(f32::to_bits)));
+                    if dedup(&mut seen.position, &mut seen.stored[0], key, dat.position.len()) {
+                        return Ok(());
+                    }
+                }
+                dat.position.push(position);
+                if let Some(color) = color {
+                    dat.color.resize(dat.position.len() - 1, [1.0; 3]);
+                    dat.color.push(color);
+                } else if !dat.color.is_empty() {
+                    dat.color.push([1.0; 3]);
+                }
+            }
+            "vt" | "vn" | "mtllib" | "usemtl" if config.geometry_only => (),
+            "vt" => {
+                let (t0, t1) = (words.next(), words.next());
+                let mut texture = ObjData::<S>::parse_two(idx, t0, t1)?;
+                check_range(policy, idx, &[t0, t1], &mut texture, warnings)?;
+                if let Some(seen) = attributes_seen {
+                    let key = Some(texture.map(f32::to_bits));
+                    if dedup(&mut seen.texture, &mut seen.stored[1], key, dat.texture.len()) {
+                        return Ok(());
+                    }
+                }
+                dat.texture.push(texture);
+            }
+            "vn" => {
+                let (n0, n1, n2) = (words.next(), words.next(), words.next());
+                let mut normal = ObjData::<S>::parse_three(idx, n0, n1, n2)?;
+                check_range(policy, idx, &[n0, n1, n2], &mut normal, warnings)?;
+                if let Some(seen) = attributes_seen {
+                    let key = Some(normal.map(f32::to_bits));
+                    if dedup(&mut seen.normal, &mut seen.stored[2], key, dat.normal.len()) {
+                        return Ok(());
+                    }
+                }
+                dat.normal.push(normal);
+            }
+            "f" | "l" if !keeps(config, object, group) => (),
+            "f" => {
+                if policy != OutOfRangePolicy::Allow {
+                    check_index_range(idx, statement)?;
+                }
+                let mut poly = ObjData::<S>::parse_face(idx, &mut words, lens)?;
+                if config.geometry_only {
+                    for tuple in &mut poly.0 {
+                        tuple.texture = None;
+                        tuple.normal = None;
+                    }
+                }
+                if config.check_indices {
+                    check_indices(lens, idx, statement, attributes, forward_refs)?;
+                }
+                let polys = &mut group.get_or_insert_with(|| new_group(DEFAULT_GROUP)).polys;
+                match config.max_face_vertices {
+                    Some(max) if poly.0.len() > max => {
+                        if !config.triangulate_large_faces || max < 3 {
+                            return Err(ObjError::TooManyFaceVertices {
+                                line_number: idx,
+                                count: poly.0.len(),
+                                max,
+                            });
+                        }
+                        let first = poly.0[0];
+                        polys.extend(
+                            poly.0[1..]
+                                .windows(2)
+                                .map(|w| [first, w[0], w[1]].iter().copied().collect::<SimplePolygon>()),
+                        );
+                    }
+                    _ => polys.push(poly),
+                }
+            }
+            "o" => {
+                let name = match statement.rest() {
+                    "" => DEFAULT_OBJECT,
+                    name => name,
+                };
+                let mut finished = mem::replace(object, Object::new(names.intern(name)));
+                finished.groups.extend(group.take());
+                filter_groups(config, &mut finished);
+                if !finished.groups.is_empty() {
+                    dat.objects.push(finished);
+                }
+                let is_taken = |name: &str| dat.objects.iter().any(|o| *o.name == *name);
+                if is_taken(name) {
+                    match config.duplicate_objects {
+                        DuplicateObjectPolicy::Error => {
+                            return Err(ObjError::DuplicateObject {
+                                line_number: idx,
+                                name: name.to_string(),
+                            })
+                        }
+                        DuplicateObjectPolicy::Suffix => {
+                            let unique = (1..)
+                                .map(|n| format!("{}.{:03}", name, n))
+                                .find(|unique| !is_taken(unique))
+                                .unwrap();
+                            object.name = names.intern(&unique);
+                        }
+                        // Merged when finishing, so the objects keep the order of their first
+                        // appearance.
+                        DuplicateObjectPolicy::Keep | DuplicateObjectPolicy::Merge => (),
+                    }
+                }
+            }
+            "g" => {
+                let current_material = group.as_ref().and_then(|g| g.material.clone());
+
+                object.groups.extend(group.take());
+
+                let name = match statement.rest() {
+                    "" if !config.bare_group_keeps_material => return Ok(()),
+                    // The default group is only created once it is needed if it has no material.
+                    "" if current_material.is_none() => return Ok(()),
+                    "" => DEFAULT_GROUP,
+                    name => name,
+                };
+                let mut g = new_group(name);
+                g.material = current_material;
+                *group = Some(g);
+            }
+            "mtllib" => {
+                // Obj strictly does not allow spaces in filenames.
+                // "mtllib Some File.mtl" is forbidden.
+                // However, everyone does it anyway and if we want to ingest blender-outputted files, we need to support it.
+                // This works by walking word by word and combining them with a space in between. This may not be a totally
+                // accurate way to do it, but until the parser can be re-worked, this is good-enough, better-than-before solution.
+                let first_word = words
+                    .next()
+                    .ok_or(ObjError::MissingMTLName { line_number: idx })?
+                    .to_string();
+                let name: String = words.fold(first_word, |mut existing, next| {
+                    existing.push(' ');
+                    existing.push_str(next);
+                    existing
+                });
+                dat.material_libs.push(Mtl::new(name));
+            }
+            "shadow_obj" | "trace_obj" => {
+                let file = match statement.rest() {
+                    "" => {
+                        return Err(ObjError::ArgumentListFailure {
+                            line_number: idx,
+                            list: String::new(),
+                        })
+                    }
+                    file => Some(file.to_string()),
+                };
+                if statement.keyword() == "shadow_obj" {
+                    dat.shadow_obj = file;
+                } else {
+                    dat.trace_obj = file;
+                }
+            }
+            "usemtl" => {
+                let mut g = group.take().unwrap_or_else(|| new_group(DEFAULT_GROUP));
+                // we found a new material that was applied to an existing
+                // non-empty object. It is treated as a new group.
+                continue_group(object, &mut g);
+                g.material = config
+                    .usemtl_name(words.next())
+                    .map(|w| ObjMaterial::Ref(names.intern(w)));
+                *group = Some(g);
+            }
+            "l" => {
+                if policy != OutOfRangePolicy::Allow {
+                    check_index_range(idx, statement)?;
+                }
+                let mut line = ObjData::<S>::parse_line(idx, &mut words, lens)?;
+                if config.geometry_only {
+                    line.0.iter_mut().for_each(|tuple| tuple.texture = None);
+                }
+                if config.check_indices {
+                    check_indices(lens, idx, statement, attributes, forward_refs)?;
+                }
+                group.get_or_insert_with(|| new_group(DEFAULT_GROUP)).lines.push(line);
+            }
+            keyword @ ("s" | "bevel" | "c_interp" | "d_interp" | "lod" | "usemap") => {
+                match state.apply(idx, keyword, words.next(), |map| names.intern(map)) {
+                    Ok(_) => {
+                        if let Some(g) = group.as_mut() {
+                            g.set_render_state(state.clone());
+                        }
+                    }
+                    // Malformed settings don't affect the geometry, so they aren't fatal even when
+                    // parsing is strict.
+                    Err(err) => warnings.push(LoadWarning::SkippedLine {
+                        line_number: idx,
+                        error: err.to_string(),
+                    }),
+                }
+            }
+            other if config.strict && !statement.is_comment() => {
+                return Err(ObjError::UnexpectedCommand {
+                    line_number: idx,
+                    command: other.to_string(),
+                });
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Whether the elements that would be added to `group` of `object` are kept, see
+/// [`LoadConfig::include_objects`].
+fn keeps(config: &LoadConfig, object: &Object, group: &Option<Group>) -> bool {
+    let group = group.as_ref().map_or(DEFAULT_GROUP, |g| &g.name);
+    config.keeps(&object.name, group)
+}
+
+/// Remove the groups of `object` that are left out by `config`, returning whether it is kept.
+///
+/// Their elements were already skipped while parsing, so only empty groups are removed.
+fn filter_groups(config: &LoadConfig, object: &mut Object) -> bool {
+    if !config.filters() {
+        return true;
+    }
+    let Object { name, groups } = object;
+    groups.retain(|g| config.keeps(name, &g.name));
+    !groups.is_empty()
+}
+
+/// Continue `group` as a new group with the next index if it already has polygons or lines, moving
+/// those into `object`.
+fn continue_group(object: &mut Object, group: &mut Group) {
+    if !group.is_empty() {
+        object.groups.push(group.clone());
+        group.continue_empty();
+    }
+}
+
+/// Check the vertex references of an already parsed `f` or `l` statement.
+///
+/// Zero indices and relative indices before the first element are errors right away, references
+/// past the `lens` elements read so far are recorded in `forward_refs`. Only the first
+/// `attributes` of the position, texture and normal indices are checked.
+fn check_indices(
+    lens: [usize; 3],
+    line_number: usize,
+    statement: &Statement<'_>,
+    attributes: usize,
+    forward_refs: &mut Vec<(usize, usize, isize)>,
+) -> Result<(), ObjError> {
+    for group in statement.args() {
+        for (attr, index) in group.split('/').enumerate().take(attributes) {
+            let index = match isize::from_str(index) {
+                Ok(index) => index,
+                // Empty and unparsable indices are treated the same way as by `parse_index_tuple`.
+                Err(_) => continue,
+            };
+            if index == 0 {
+                return Err(ObjError::ZeroVertexNumber { line_number });
+            } else if index < 0 && index.unsigned_abs() > lens[attr] {
+                return Err(ObjError::IndexOutOfRange {
+                    line_number,
+                    index,
+                    count: lens[attr],
+                });
+            } else if index > 0 && index as usize > lens[attr] {
+                forward_refs.push((line_number, attr, index));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The statement with the comma of every number like `0,5` replaced by a decimal point, or `None`
+/// if there are no such numbers.
+fn replace_decimal_commas(statement: &Statement<'_>) -> Option<String> {
+    let mut fixed = String::from(statement.keyword());
+    let mut replaced = false;
+    for word in statement.args() {
+        fixed.push(' ');
+        if word.matches(',').count() == 1 && !word.contains('.') {
+            fixed.push_str(&word.replace(',', "."));
+            replaced = true;
+        } else {
+            fixed.push_str(word);
+        }
+    }
+    if replaced {
+        Some(fixed)
+    } else {
+        None
+    }
+}
+
+/// Apply `policy` to the `values` parsed from `words`, see [`LoadConfig::out_of_range`].
+fn check_range<T: Scalar>(
+    policy: OutOfRangePolicy,
+    line_number: usize,
+    words: &[Option<&str>],
+    values: &mut [T],
+    warnings: &mut Vec<LoadWarning>,
+) -> Result<(), ObjError> {
+    if policy == OutOfRangePolicy::Allow {
+        return Ok(());
+    }
+    let mut clamped = false;
+    for (word, value) in words.iter().zip(values) {
+        let word = word.unwrap_or_default();
+        let mantissa = word.split(['e', 'E']).next().unwrap_or_default();
+        // Literal infinities, such as `inf`, have no digits and are kept.
+        let overflow = value.is_out_of_range() && word.bytes().any(|b| b.is_ascii_digit());
+        let underflow = *value == T::default() && mantissa.bytes().any(|b| (b'1'..=b'9').contains(&b));
+        if overflow || underflow {
+            if policy == OutOfRangePolicy::Error {
+                return Err(ObjError::NumberOutOfRange {
+                    line_number,
+                    number: word.to_string(),
+                });
+            }
+            *value = value.clamp_to_range();
+            clamped = true;
+        }
+    }
+    let warning = LoadWarning::ClampedNumber { line_number };
+    if clamped && warnings.last() != Some(&warning) {
+        warnings.push(warning);
+    }
+    Ok(())
+}
+
+/// Return an error for indices of an `f` or `l` statement that don't fit in an `isize`.
+fn check_index_range(line_number: usize, statement: &Statement<'_>) -> Result<(), ObjError> {
+    for group in statement.args() {
+        for index in group.split('/') {
+            let digits = index.strip_prefix('-').unwrap_or(index);
+            if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) && isize::from_str(index).is_err() {
+                return Err(ObjError::NumberOutOfRange {
+                    line_number,
+                    number: index.to_string(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Append the groups of objects to the first object with the same name.
+///
+/// Returns the new place of each object as its index and the index of its first group.
+fn merge_duplicate_objects(objects: &mut Vec<Object>) -> Vec<(usize, usize)> {
+    let mut first: BTreeMap<Arc<str>, usize> = BTreeMap::new();
+    let mut merged: Vec<Object> = Vec::with_capacity(objects.len());
+    let mut map = Vec::with_capacity(objects.len());
+    for object in objects.drain(..) {
+        match first.get(&object.name) {
+            Some(&index) => {
+                map.push((index, merged[index].groups.len()));
+                merged[index].groups.extend(object.groups);
+            }
+            None => {
+                first.insert(object.name.clone(), merged.len());
+                map.push((merged.len(), 0));
+                merged.push(object);
+            }
+        }
+    }
+    *objects = merged;
+    map
+}
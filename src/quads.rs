@@ -0,0 +1,254 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversion between triangles and quads.
+
+use std::collections::HashMap;
+
+use crate::math::{angle_between, cross, dot, newell_normal, sub, Vec3};
+use crate::obj::{IndexTuple, ObjData, SimplePolygon};
+
+/// Polygons of one group that could not be converted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupConversion {
+    /// Index of the object in [`ObjData::objects`].
+    pub object: usize,
+    /// Index of the group in [`Object::groups`](crate::Object::groups).
+    pub group: usize,
+    /// Number of polygons that were converted.
+    pub converted: usize,
+    /// Indices into the group's polygon list, after conversion, of the polygons left unchanged.
+    pub unconverted: Vec<usize>,
+}
+
+/// Per-group outcome of [`ObjData::to_triangles`] and [`ObjData::to_quads`].
+///
+/// Only groups with at least one unconverted polygon are listed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub groups: Vec<GroupConversion>,
+}
+
+impl ConversionReport {
+    /// Check whether every polygon was converted.
+    pub fn is_complete(&self) -> bool {
+        self.groups.iter().all(|g| g.unconverted.is_empty())
+    }
+}
+
+/// Triangulate a simple polygon by ear clipping in its best-fit plane.
+///
+/// Returns `None` for polygons that are degenerate, self-intersecting or not finite, or refer to a missing
+/// position.
+pub(crate) fn ear_clip(poly: &SimplePolygon, position: &[[f32; 3]]) -> Option<Vec<[IndexTuple; 3]>> {
+    let corners = &poly.0;
+    if corners.len() < 3 {
+        return None;
+    }
+    let points: Vec<Vec3> = corners
+        .iter()
+        .map(|t| position.get(t.position).copied())
+        .collect::<Option<_>>()?;
+    if corners.len() == 3 {
+        return Some(vec![[corners[0], corners[1], corners[2]]]);
+    }
+
+    let normal = newell_normal(&points);
+    if !normal.iter().all(|x| x.is_finite()) {
+        return None;
+    }
+    // Project onto the plane most perpendicular to the normal, keeping counter-clockwise order.
+    let axis = (0..3)
+        .max_by(|&a, &b| normal[a].abs().total_cmp(&normal[b].abs()))
+        .unwrap();
+    if normal[axis] == 0.0 {
+        return None;
+    }
+    let (u, v) = match axis {
+        0 => (1, 2),
+        1 => (2, 0),
+        _ => (0, 1),
+    };
+    let flip = normal[axis] < 0.0;
+    let pts: Vec<[f32; 2]> = points
+        .iter()
+        .map(|p| if flip { [p[v], p[u]] } else { [p[u], p[v]] })
+        .collect();
+
+    let area = |a: [f32; 2], b: [f32; 2], c: [f32; 2]| (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+    let inside = |p: [f32; 2], a, b, c| area(a, b, p) >= 0.0 && area(b, c, p) >= 0.0 && area(c, a, p) >= 0.0;
+
+    let mut remaining: Vec<usize> = (0..corners.len()).collect();
+    let mut tris = Vec::with_capacity(corners.len() - 2);
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+            if area(pts[a], pts[b], pts[c]) <= 0.0 {
+                return false;
+            }
+            remaining
+                .iter()
+                .filter(|&&j| j != a && j != b && j != c)
+                .all(|&j| !inside(pts[j], pts[a], pts[b], pts[c]))
+        })?;
+        let (a, b, c) = (remaining[(ear + n - 1) % n], remaining[ear], remaining[(ear + 1) % n]);
+        tris.push([corners[a], corners[b], corners[c]]);
+        remaining.remove(ear);
+    }
+    let (a, b, c) = (remaining[0], remaining[1], remaining[2]);
+    if area(pts[a], pts[b], pts[c]) <= 0.0 {
+        return None;
+    }
+    tris.push([corners[a], corners[b], corners[c]]);
+    Some(tris)
+}
+
+/// Check that the quad `a b c d` is planar within the given angle and strictly convex.
+fn is_convex_quad(quad: [Vec3; 4], max_angle: f32) -> bool {
+    let normal = newell_normal(&quad);
+    (0..4).all(|i| {
+        let (prev, p, next) = (quad[(i + 3) % 4], quad[i], quad[(i + 1) % 4]);
+        let corner = cross(sub(p, prev), sub(next, p));
+        dot(corner, normal) > 0.0 && angle_between(corner, normal) <= max_angle
+    })
+}
+
+impl ObjData {
+    /// Split every polygon with more than three vertices into triangles.
+    ///
+    /// Polygons are triangulated by ear clipping, which handles concave polygons. Polygons that
+    /// are degenerate or self-intersecting, or refer to a missing position, which
+    /// [`ObjData::validate_indices`] reports, are left unchanged and listed in the report.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn to_triangles(&mut self) -> ConversionReport {
+        let mut report = ConversionReport::default();
+        let position = &self.position;
+        for (o, object) in self.objects.iter_mut().enumerate() {
+            for (g, group) in object.groups.iter_mut().enumerate() {
+                let mut stats = GroupConversion {
+                    object: o,
+                    group: g,
+                    ..GroupConversion::default()
+                };
+                group.map_polys(|poly, polys| {
+                    if poly.0.len() == 3 {
+                        polys.push(poly);
+                        return;
+                    }
+                    match ear_clip(&poly, position) {
+                        Some(tris) => {
+                            stats.converted += 1;
+                            polys.extend(tris.iter().map(|t| SimplePolygon(t.to_vec())));
+                        }
+                        None => {
+                            stats.unconverted.push(polys.len());
+                            polys.push(poly);
+                        }
+                    }
+                });
+                if !stats.unconverted.is_empty() {
+                    report.groups.push(stats);
+                }
+            }
+        }
+        report
+    }
+
+    /// Merge pairs of adjacent triangles into quads.
+    ///
+    /// Two triangles of the same group and render state are merged if they share an edge with
+    /// identical index tuples on both sides, and the resulting quad is convex with the normals at
+    /// its corners deviating by at most `max_angle` radians. Triangles that found no partner,
+    /// including those referring to a missing position, are listed in the report, other polygons
+    /// are left unchanged.
+    pub fn to_quads(&mut self, max_angle: f32) -> ConversionReport {
+        let mut report = ConversionReport::default();
+        let position = &self.position;
+        for (o, object) in self.objects.iter_mut().enumerate() {
+            for (g, group) in object.groups.iter_mut().enumerate() {
+                // Map each directed edge of a triangle to the triangle and the opposite corner.
+                let mut edges: HashMap<(IndexTuple, IndexTuple), (usize, usize)> = HashMap::new();
+                for (t, poly) in group.polys.iter().enumerate() {
+                    if poly.0.len() == 3 {
+                        for i in 0..3 {
+                            edges.insert((poly.0[i], poly.0[(i + 1) % 3]), (t, (i + 2) % 3));
+                        }
+                    }
+                }
+
+                let mut partner: Vec<Option<(usize, [IndexTuple; 4])>> = vec![None; group.polys.len()];
+                let mut paired = vec![false; group.polys.len()];
+                for (t, poly) in group.polys.iter().enumerate() {
+                    if poly.0.len() != 3 || paired[t] {
+                        continue;
+                    }
+                    for i in 0..3 {
+                        let (a, b, c) = (poly.0[i], poly.0[(i + 1) % 3], poly.0[(i + 2) % 3]);
+                        // The neighbour traverses the shared edge in the opposite direction.
+                        let (other, opposite) = match edges.get(&(b, a)) {
+                            Some(&(other, opposite))
+                                if other != t
+                                    && !paired[other]
+                                    && group.poly_render_state(other) == group.poly_render_state(t) =>
+                            {
+                                (other, opposite)
+                            }
+                            _ => continue,
+                        };
+                        let d = group.polys[other].0[opposite];
+                        let quad = [a, d, b, c];
+                        let points = match quad.map(|t| position.get(t.position).copied()) {
+                            [Some(a), Some(d), Some(b), Some(c)] => [a, d, b, c],
+                            _ => continue,
+                        };
+                        if is_convex_quad(points, max_angle) {
+                            paired[t] = true;
+                            paired[other] = true;
+                            partner[t] = Some((other, quad));
+                            break;
+                        }
+                    }
+                }
+
+                let mut stats = GroupConversion {
+                    object: o,
+                    group: g,
+                    ..GroupConversion::default()
+                };
+                let mut t = 0;
+                group.map_polys(|poly, polys| {
+                    match partner[t] {
+                        Some((_, quad)) => {
+                            stats.converted += 2;
+                            polys.push(SimplePolygon(quad.to_vec()));
+                        }
+                        None if paired[t] => {}
+                        None => {
+                            if poly.0.len() == 3 {
+                                stats.unconverted.push(polys.len());
+                            }
+                            polys.push(poly);
+                        }
+                    }
+                    t += 1;
+                });
+                if !stats.unconverted.is_empty() {
+                    report.groups.push(stats);
+                }
+            }
+        }
+        report
+    }
+}
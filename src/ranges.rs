@@ -0,0 +1,98 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! The parts of the attribute arrays referenced by objects and groups.
+
+use std::ops::Range;
+
+use crate::obj::{Group, IndexTuple, ObjData, Object};
+
+/// The ranges of [`ObjData::position`], [`ObjData::texture`] and [`ObjData::normal`] holding all
+/// attributes referenced by an object or group.
+///
+/// Each range spans from the smallest to the largest referenced index and is empty if nothing
+/// is referenced. Exporters usually write the vertices of each object right before its faces, so
+/// the ranges of different objects don't overlap and can be uploaded or extracted as slices
+/// without looking at the polygons again.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttributeRanges {
+    pub position: Range<usize>,
+    pub texture: Range<usize>,
+    pub normal: Range<usize>,
+}
+
+impl AttributeRanges {
+    fn of<'a>(groups: impl IntoIterator<Item = &'a Group>) -> Self {
+        let mut ranges = AttributeRanges::default();
+        let tuples = groups.into_iter().flat_map(|group| {
+            let polys = group.polys.iter().flat_map(|poly| poly.0.iter());
+            polys.chain(group.lines.iter().flat_map(|line| line.0.iter()))
+        });
+        for &IndexTuple {
+            position,
+            texture,
+            normal,
+        } in tuples
+        {
+            extend(&mut ranges.position, position);
+            if let Some(texture) = texture {
+                extend(&mut ranges.texture, texture);
+            }
+            if let Some(normal) = normal {
+                extend(&mut ranges.normal, normal);
+            }
+        }
+        ranges
+    }
+
+    /// Whether any of the ranges shares an index with the corresponding range of `other`.
+    ///
+    /// Objects or groups whose ranges don't overlap own their attributes exclusively.
+    pub fn overlaps(&self, other: &AttributeRanges) -> bool {
+        let overlap = |a: &Range<usize>, b: &Range<usize>| a.start < b.end && b.start < a.end;
+        overlap(&self.position, &other.position)
+            || overlap(&self.texture, &other.texture)
+            || overlap(&self.normal, &other.normal)
+    }
+}
+
+fn extend(range: &mut Range<usize>, index: usize) {
+    if range.start == range.end {
+        *range = index..index + 1;
+    } else {
+        range.start = range.start.min(index);
+        range.end = range.end.max(index + 1);
+    }
+}
+
+impl ObjData {
+    /// The attribute ranges of every object, in the order of [`ObjData::objects`].
+    pub fn object_attribute_ranges(&self) -> Vec<AttributeRanges> {
+        self.objects.iter().map(Object::attribute_ranges).collect()
+    }
+}
+
+impl Object {
+    /// The attributes referenced by this object's polygons and lines.
+    pub fn attribute_ranges(&self) -> AttributeRanges {
+        AttributeRanges::of(&self.groups)
+    }
+}
+
+impl Group {
+    /// The attributes referenced by this group's polygons and lines.
+    pub fn attribute_ranges(&self) -> AttributeRanges {
+        AttributeRanges::of(std::iter::once(self))
+    }
+}
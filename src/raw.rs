@@ -0,0 +1,177 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Low-level tokenization of .obj statements.
+//!
+//! This is what [`ObjData`] parsing is built on. It allows consuming .obj
+//! source without building an `ObjData`, e.g. to convert or filter files while streaming them.
+//!
+//! Line numbers are 0-based, like the ones in [`ObjError`].
+
+use core::str::{FromStr, SplitWhitespace};
+
+pub use crate::obj::parse_index_tuple;
+use crate::obj::{IndexTuple, ObjData, ObjError};
+
+/// A single non-empty line of .obj source, split into its keyword and arguments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Statement<'a> {
+    line_number: usize,
+    keyword: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Statement<'a> {
+    /// Split `line` into a statement, or return `None` if it is blank.
+    pub fn parse(line_number: usize, line: &'a str) -> Option<Self> {
+        let line = line.trim_start();
+        let end = line.find(char::is_whitespace).unwrap_or(line.len());
+        if end == 0 {
+            return None;
+        }
+        Some(Statement {
+            line_number,
+            keyword: &line[..end],
+            rest: line[end..].trim(),
+        })
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// The first word of the line, such as `v` or `usemtl`.
+    pub fn keyword(&self) -> &'a str {
+        self.keyword
+    }
+
+    /// Whether this line is a `#` comment.
+    pub fn is_comment(&self) -> bool {
+        self.keyword.starts_with('#')
+    }
+
+    /// Everything after the keyword with surrounding whitespace removed.
+    ///
+    /// This is useful for names, which may contain spaces in files found in the wild.
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    /// The whitespace separated arguments following the keyword.
+    pub fn args(&self) -> Args<'a> {
+        Args {
+            line_number: self.line_number,
+            words: Words::new(self.rest),
+        }
+    }
+}
+
+/// Iterate over the statements of .obj source, skipping blank lines.
+pub fn statements(input: &str) -> impl Iterator<Item = Statement<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(line_number, line)| Statement::parse(line_number, line))
+}
+
+/// Splits arguments at whitespace, as `str::split_whitespace` does.
+#[derive(Clone, Debug)]
+enum Words<'a> {
+    /// With the `memchr` feature, ASCII-only arguments are split byte by byte instead of
+    /// decoding characters, giving the same words.
+    #[cfg(feature = "memchr")]
+    Ascii(&'a str),
+    Unicode(SplitWhitespace<'a>),
+}
+
+/// Whether `b` is an ASCII character for which `char::is_whitespace` holds.
+///
+/// Unlike `u8::is_ascii_whitespace`, this includes the vertical tab.
+#[cfg(feature = "memchr")]
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | 0x0B | 0x0C | b'\r')
+}
+
+impl<'a> Words<'a> {
+    fn new(s: &'a str) -> Self {
+        #[cfg(feature = "memchr")]
+        {
+            if s.is_ascii() {
+                return Words::Ascii(s);
+            }
+        }
+        Words::Unicode(s.split_whitespace())
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self {
+            #[cfg(feature = "memchr")]
+            Words::Ascii(rest) => {
+                let bytes = rest.as_bytes();
+                let start = bytes.iter().position(|&b| !is_whitespace(b))?;
+                let len = bytes[start..].iter().position(|&b| is_whitespace(b));
+                let end = len.map_or(bytes.len(), |len| start + len);
+                // Any byte offset of an ASCII string is a character boundary.
+                let word = &rest[start..end];
+                *rest = &rest[end..];
+                Some(word)
+            }
+            Words::Unicode(words) => words.next(),
+        }
+    }
+}
+
+/// The arguments of a [`Statement`], with typed readers.
+///
+/// As an iterator, this yields the remaining arguments as strings.
+#[derive(Clone, Debug)]
+pub struct Args<'a> {
+    line_number: usize,
+    words: Words<'a>,
+}
+
+impl<'a> Iterator for Args<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.words.next()
+    }
+}
+
+impl<'a> Args<'a> {
+    /// Read two numbers, as given by `vt`.
+    pub fn vec2(&mut self) -> Result<[f32; 2], ObjError> {
+        ObjData::<f32>::parse_two(self.line_number, self.words.next(), self.words.next())
+    }
+
+    /// Read three numbers, as given by `v` and `vn`.
+    pub fn vec3<T: FromStr>(&mut self) -> Result<[T; 3], ObjError> {
+        let (n0, n1, n2) = (self.words.next(), self.words.next(), self.words.next());
+        ObjData::<f32>::parse_three(self.line_number, n0, n1, n2)
+    }
+
+    /// Read the next `/` separated vertex of an `f` or `l` statement, or `None` if there are no
+    /// arguments left.
+    ///
+    /// `lens` holds the number of positions, texture coordinates and normals read so far, which
+    /// negative indices are relative to.
+    pub fn index_tuple(&mut self, lens: [usize; 3]) -> Option<Result<IndexTuple, ObjError>> {
+        let word = self.words.next()?;
+        Some(parse_index_tuple(self.line_number, word, lens))
+    }
+}
@@ -0,0 +1,62 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Parallel iteration over polygons and groups with [`rayon`].
+
+use rayon::prelude::*;
+
+use crate::obj::{Group, ObjData, Object, Scalar, SimplePolygon};
+
+impl<S: Scalar + Send + Sync> ObjData<S> {
+    /// Iterate in parallel over the groups of all objects.
+    pub fn par_groups(&self) -> impl ParallelIterator<Item = &Group> {
+        self.objects.par_iter().flat_map(|object| object.groups.par_iter())
+    }
+
+    /// Iterate mutably in parallel over the groups of all objects.
+    pub fn par_groups_mut(&mut self) -> impl ParallelIterator<Item = &mut Group> {
+        self.objects
+            .par_iter_mut()
+            .flat_map(|object| object.groups.par_iter_mut())
+    }
+
+    /// Iterate in parallel over the polygons of all groups of all objects.
+    pub fn par_polys(&self) -> impl ParallelIterator<Item = &SimplePolygon> {
+        self.par_groups().flat_map(|group| group.polys.par_iter())
+    }
+
+    /// Iterate mutably in parallel over the polygons of all groups of all objects.
+    pub fn par_polys_mut(&mut self) -> impl ParallelIterator<Item = &mut SimplePolygon> {
+        self.par_groups_mut().flat_map(|group| group.polys.par_iter_mut())
+    }
+}
+
+impl Object {
+    /// Iterate in parallel over the polygons of all groups of this object.
+    pub fn par_polys(&self) -> impl ParallelIterator<Item = &SimplePolygon> {
+        self.groups.par_iter().flat_map(|group| group.polys.par_iter())
+    }
+}
+
+impl Group {
+    /// Iterate in parallel over the polygons of this group.
+    pub fn par_polys(&self) -> impl IndexedParallelIterator<Item = &SimplePolygon> {
+        self.polys.par_iter()
+    }
+
+    /// Iterate mutably in parallel over the polygons of this group.
+    pub fn par_polys_mut(&mut self) -> impl IndexedParallelIterator<Item = &mut SimplePolygon> {
+        self.polys.par_iter_mut()
+    }
+}
@@ -0,0 +1,75 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Bounds-checked lookup of the attribute values an index tuple refers to.
+
+use crate::obj::{IndexTuple, ObjData, Scalar};
+
+/// The attribute values of a single polygon or line vertex.
+///
+/// Returned by [`ObjData::resolve`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VertexData<S = f32> {
+    /// The position of the vertex.
+    pub position: [S; 3],
+    /// The texture coordinates, if the tuple has a texture index.
+    pub texture: Option<[f32; 2]>,
+    /// The normal, if the tuple has a normal index.
+    pub normal: Option<[f32; 3]>,
+}
+
+impl<S: Scalar> ObjData<S> {
+    /// Look up the values the indices of `tuple` refer to.
+    ///
+    /// Returns `None` if any of the indices is out of range.
+    pub fn resolve(&self, tuple: &IndexTuple) -> Option<VertexData<S>> {
+        Some(VertexData {
+            position: *self.position.get(tuple.position)?,
+            texture: match tuple.texture {
+                Some(t) => Some(*self.texture.get(t)?),
+                None => None,
+            },
+            normal: match tuple.normal {
+                Some(n) => Some(*self.normal.get(n)?),
+                None => None,
+            },
+        })
+    }
+
+    /// Look up the values of every tuple in `tuples`, in order.
+    ///
+    /// Returns `None` if any of the indices is out of range.
+    pub fn resolve_all(&self, tuples: &[IndexTuple]) -> Option<Vec<VertexData<S>>> {
+        let mut out = Vec::with_capacity(tuples.len());
+        self.resolve_into(tuples, &mut out)?;
+        Some(out)
+    }
+
+    /// Append the values of every tuple in `tuples` to `out`, reusing its allocation.
+    ///
+    /// If any of the indices is out of range, `None` is returned and `out` is left unchanged.
+    pub fn resolve_into(&self, tuples: &[IndexTuple], out: &mut Vec<VertexData<S>>) -> Option<()> {
+        let len = out.len();
+        for tuple in tuples {
+            match self.resolve(tuple) {
+                Some(vertex) => out.push(vertex),
+                None => {
+                    out.truncate(len);
+                    return None;
+                }
+            }
+        }
+        Some(())
+    }
+}
@@ -0,0 +1,125 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Generation of Rust source code embedding a mesh as constant arrays.
+
+use std::io::{self, Write};
+
+use crate::obj::ObjData;
+
+/// Options for [`ObjData::write_rust_source`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RustSourceConfig {
+    /// Prefix of the generated constant names, e.g. `MESH` for `MESH_POSITIONS`.
+    pub prefix: String,
+    /// Whether to emit the `_NORMALS` array.
+    pub normals: bool,
+    /// Whether to emit the `_UVS` array.
+    pub texture_coords: bool,
+    /// Whether to emit `u16` instead of `u32` indices. Ignored if the mesh has more vertices
+    /// than `u16` can index.
+    pub small_indices: bool,
+}
+
+impl Default for RustSourceConfig {
+    fn default() -> Self {
+        RustSourceConfig {
+            prefix: "MESH".to_string(),
+            normals: true,
+            texture_coords: true,
+            small_indices: false,
+        }
+    }
+}
+
+/// Write a float so that it reads back as the same `f32` literal.
+fn write_float(out: &mut impl Write, x: f32) -> io::Result<()> {
+    if x.is_nan() {
+        write!(out, "f32::NAN")
+    } else if x.is_infinite() {
+        write!(out, "{}f32::INFINITY", if x < 0.0 { "-" } else { "" })
+    } else {
+        // Debug formatting is the shortest representation that round trips and keeps the dot.
+        write!(out, "{:?}", x)
+    }
+}
+
+fn write_array<const N: usize>(out: &mut impl Write, name: &str, values: &[[f32; N]]) -> io::Result<()> {
+    writeln!(out, "pub const {}: [[f32; {}]; {}] = [", name, N, values.len())?;
+    for v in values {
+        write!(out, "    [")?;
+        for (i, &x) in v.iter().enumerate() {
+            if i > 0 {
+                write!(out, ", ")?;
+            }
+            write_float(out, x)?;
+        }
+        writeln!(out, "],")?;
+    }
+    writeln!(out, "];")
+}
+
+impl ObjData {
+    /// Write the mesh as Rust source declaring `const` arrays, for use with `include!` where
+    /// parsing at runtime is not an option, e.g. in `no_std` targets.
+    ///
+    /// The arrays hold the vertices of [`ObjData::to_indexed_buffers`], with all groups in one
+    /// `_INDICES` array of three indices per triangle. The generated code only uses core types.
+    pub fn write_rust_source(&self, out: &mut impl Write, config: &RustSourceConfig) -> io::Result<()> {
+        let buffers = self.to_indexed_buffers();
+        let prefix = &config.prefix;
+        writeln!(
+            out,
+            "// Generated by the obj Rust library (https://crates.io/crates/obj)."
+        )?;
+        writeln!(out)?;
+
+        let positions: Vec<_> = buffers.vertices.iter().map(|v| v.position).collect();
+        write_array(out, &format!("{}_POSITIONS", prefix), &positions)?;
+        if config.normals {
+            let normals: Vec<_> = buffers.vertices.iter().map(|v| v.normal).collect();
+            writeln!(out)?;
+            write_array(out, &format!("{}_NORMALS", prefix), &normals)?;
+        }
+        if config.texture_coords {
+            let uvs: Vec<_> = buffers.vertices.iter().map(|v| v.texture).collect();
+            writeln!(out)?;
+            write_array(out, &format!("{}_UVS", prefix), &uvs)?;
+        }
+
+        let indices: Vec<u32> = buffers
+            .submeshes
+            .iter()
+            .flat_map(|s| s.indices.iter().copied())
+            .collect();
+        let index_type = if config.small_indices && buffers.vertices.len() <= u16::MAX as usize + 1 {
+            "u16"
+        } else {
+            "u32"
+        };
+        writeln!(out)?;
+        writeln!(
+            out,
+            "pub const {}_INDICES: [{}; {}] = [",
+            prefix,
+            index_type,
+            indices.len()
+        )?;
+        for triangle in indices.chunks(3) {
+            let triangle: Vec<String> = triangle.iter().map(|i| i.to_string()).collect();
+            writeln!(out, "    {},", triangle.join(", "))?;
+        }
+        writeln!(out, "];")
+    }
+}
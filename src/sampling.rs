@@ -0,0 +1,203 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Random point sampling of mesh surfaces.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::math::{add, cross, length, normalize, scale, sub, Vec3};
+use crate::obj::ObjData;
+
+/// How [`ObjData::sample_surface`] distributes points over the surface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplingMode {
+    /// Independent points with the same density everywhere.
+    Uniform,
+    /// Evenly spaced points without clusters, chosen from a larger uniform sample by removing
+    /// the points closest to others.
+    PoissonDisk,
+}
+
+/// A point on the surface of a mesh, see [`ObjData::sample_surface`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfaceSample {
+    pub position: [f32; 3],
+    /// The interpolated vertex normal, or the normal of the triangle if its vertices have none.
+    pub normal: [f32; 3],
+    /// The interpolated texture coordinates, if all vertices of the triangle have them.
+    pub texture: Option<[f32; 2]>,
+}
+
+/// Candidates generated per point for [`SamplingMode::PoissonDisk`].
+const POISSON_CANDIDATES: usize = 5;
+
+/// The SplitMix64 generator, which is plenty for placing points.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform value in `[0, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+impl ObjData {
+    /// Sample `n` points on the triangulated surface of all polygons.
+    ///
+    /// The result only depends on the mesh, use [`ObjData::sample_surface_with_seed`] for other
+    /// sets of points. Meshes without area give no points.
+    pub fn sample_surface(&self, n: usize, mode: SamplingMode) -> Vec<SurfaceSample> {
+        self.sample_surface_with_seed(n, mode, 0)
+    }
+
+    /// Like [`ObjData::sample_surface`], with points chosen by the given random seed.
+    pub fn sample_surface_with_seed(&self, n: usize, mode: SamplingMode, seed: u64) -> Vec<SurfaceSample> {
+        let triangles: Vec<_> = self.triangles().collect();
+        let mut total = 0.0;
+        let cumulative: Vec<f32> = triangles
+            .iter()
+            .map(|tri| {
+                let [a, b, c] = tri.positions;
+                total += length(cross(sub(b, a), sub(c, a))) / 2.0;
+                total
+            })
+            .collect();
+        if n == 0 || total <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut rng = SplitMix64(seed);
+        let count = match mode {
+            SamplingMode::Uniform => n,
+            SamplingMode::PoissonDisk => n * POISSON_CANDIDATES,
+        };
+        let samples: Vec<SurfaceSample> = (0..count)
+            .map(|_| {
+                let target = rng.next_f32() * total;
+                let i = cumulative
+                    .partition_point(|&area| area <= target)
+                    .min(triangles.len() - 1);
+                let tri = &triangles[i];
+                // Uniform barycentric coordinates, see Osada et al., "Shape Distributions".
+                let (r1, r2) = (rng.next_f32().sqrt(), rng.next_f32());
+                let w = [1.0 - r1, r1 * (1.0 - r2), r1 * r2];
+                let blend = |v: [Vec3; 3]| add(add(scale(v[0], w[0]), scale(v[1], w[1])), scale(v[2], w[2]));
+                let [a, b, c] = tri.positions;
+                let face_normal = normalize(cross(sub(b, a), sub(c, a))).unwrap_or([0.0, 0.0, 1.0]);
+                SurfaceSample {
+                    position: blend(tri.positions),
+                    normal: tri.normals.and_then(|n| normalize(blend(n))).unwrap_or(face_normal),
+                    texture: tri.texture.map(|t| {
+                        let [u, v, _] = blend(t.map(|t| [t[0], t[1], 0.0]));
+                        [u, v]
+                    }),
+                }
+            })
+            .collect();
+
+        match mode {
+            SamplingMode::Uniform => samples,
+            SamplingMode::PoissonDisk => eliminate(samples, n, total),
+        }
+    }
+}
+
+/// A point and its weight in the elimination heap, ordered by weight.
+#[derive(PartialEq)]
+struct Weighted(f32, usize);
+
+impl Eq for Weighted {}
+
+impl PartialOrd for Weighted {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Weighted {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// Reduce `samples` to `n` evenly spaced ones by weighted sample elimination.
+///
+/// See Yuksel, "Sample Elimination for Generating Poisson Disk Sample Sets".
+fn eliminate(samples: Vec<SurfaceSample>, n: usize, area: f32) -> Vec<SurfaceSample> {
+    // Twice the largest possible spacing of `n` points on a surface of the given area.
+    let radius = 2.0 * (area / (2.0 * 3f32.sqrt() * n as f32)).sqrt();
+    let cell = |p: [f32; 3]| p.map(|x| (x / radius).floor() as i64);
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    for (i, sample) in samples.iter().enumerate() {
+        grid.entry(cell(sample.position)).or_default().push(i);
+    }
+    let neighbours = |i: usize| {
+        let p = samples[i].position;
+        let home = cell(p);
+        let mut found = Vec::new();
+        for offset in 0..27 {
+            let key = [
+                home[0] + offset % 3 - 1,
+                home[1] + (offset / 3) % 3 - 1,
+                home[2] + offset / 9 - 1,
+            ];
+            for &j in grid.get(&key).into_iter().flatten() {
+                let d = length(sub(samples[j].position, p));
+                if j != i && d < radius {
+                    found.push((j, (1.0 - d / radius).powi(8)));
+                }
+            }
+        }
+        found
+    };
+
+    let neighbourhoods: Vec<_> = (0..samples.len()).map(neighbours).collect();
+    let mut weights: Vec<f32> = neighbourhoods.iter().map(|n| n.iter().map(|&(_, w)| w).sum()).collect();
+    let mut heap: BinaryHeap<Weighted> = weights.iter().enumerate().map(|(i, &w)| Weighted(w, i)).collect();
+    let mut removed = vec![false; samples.len()];
+    let mut left = samples.len();
+    while left > n {
+        let Weighted(weight, i) = match heap.pop() {
+            Some(top) => top,
+            None => break,
+        };
+        // Entries of removed points and outdated weights are skipped.
+        if removed[i] || weight != weights[i] {
+            continue;
+        }
+        removed[i] = true;
+        left -= 1;
+        for &(j, w) in &neighbourhoods[i] {
+            if !removed[j] {
+                weights[j] -= w;
+                heap.push(Weighted(weights[j], j));
+            }
+        }
+    }
+    samples
+        .into_iter()
+        .zip(removed)
+        .filter(|(_, removed)| !removed)
+        .map(|(sample, _)| sample)
+        .collect()
+}
@@ -0,0 +1,100 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Canned sequences of cleanup passes.
+
+use crate::cleanup::{DegenerateReport, DuplicateReport};
+use crate::compact::AttributeRemap;
+use crate::manifold::{ManifoldReport, WindingReport};
+use crate::normals::NormalRepairReport;
+use crate::obj::ObjData;
+use crate::quads::ConversionReport;
+use crate::weld::{WeldConfig, WeldReport};
+
+/// The passes run by [`ObjData::sanitize`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// Prepare the mesh for real-time rendering: split polygons into triangles, repair normals,
+    /// merge identical positions, texture coordinates and normals, and drop unreferenced
+    /// attributes.
+    Rendering,
+    /// Prepare the mesh for 3D printing: merge identical positions so that neighboring polygons
+    /// share their edges, remove degenerate and duplicate polygons, make the winding of connected
+    /// polygons consistent, and drop unreferenced attributes, then check whether the result is
+    /// watertight.
+    Printing,
+}
+
+/// What [`ObjData::sanitize`] did, with the report of every pass that was run and `None` for the
+/// others.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SanitizeReport {
+    /// The number of polygons removed for referring to missing attributes.
+    pub invalid: usize,
+    /// See [`ObjData::to_triangles`].
+    pub triangulated: Option<ConversionReport>,
+    /// See [`ObjData::fix_normals`].
+    pub normals: Option<NormalRepairReport>,
+    /// See [`ObjData::weld_vertices_with_config`].
+    pub welded: Option<WeldReport>,
+    /// See [`ObjData::remove_degenerate_faces`].
+    pub degenerate: Option<DegenerateReport>,
+    /// See [`ObjData::dedup_faces`].
+    pub duplicates: Option<DuplicateReport>,
+    /// See [`ObjData::make_winding_consistent`].
+    pub winding: Option<WindingReport>,
+    /// See [`ObjData::compact`].
+    pub compacted: Option<AttributeRemap>,
+    /// The state of the result, for presets that need a closed surface, see
+    /// [`ObjData::check_manifold`].
+    pub manifold: Option<ManifoldReport>,
+}
+
+impl ObjData {
+    /// Run the cleanup passes of `preset` in order, see [`Preset`].
+    ///
+    /// Polygons referring to missing attributes, which [`ObjData::validate_indices`] reports, are
+    /// removed first. Lines are left as they are.
+    ///
+    /// Welding only merges exactly equal attributes and only polygons without area are
+    /// degenerate, so that no detail is lost. Run the passes one by one for other tolerances.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn sanitize(&mut self, preset: Preset) -> SanitizeReport {
+        let mut report = SanitizeReport {
+            invalid: self.remove_invalid_faces(),
+            ..SanitizeReport::default()
+        };
+        match preset {
+            Preset::Rendering => {
+                report.triangulated = Some(self.to_triangles());
+                report.normals = Some(self.fix_normals());
+                report.welded = Some(self.weld_vertices_with_config(WeldConfig {
+                    texture: true,
+                    normal: true,
+                    ..WeldConfig::default()
+                }));
+                report.compacted = Some(self.compact());
+            }
+            Preset::Printing => {
+                report.welded = Some(self.weld_vertices_with_config(WeldConfig::default()));
+                report.degenerate = Some(self.remove_degenerate_faces(0.0));
+                report.duplicates = Some(self.dedup_faces());
+                report.winding = Some(self.make_winding_consistent());
+                report.compacted = Some(self.compact());
+                report.manifold = Some(self.check_manifold());
+            }
+        }
+        report
+    }
+}
@@ -0,0 +1,167 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Loading numbered sequences of .obj files, such as per-frame animation caches.
+//!
+//! All frames must share the same topology, so only the first frame is kept in full and the
+//! others contribute their vertex positions.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::obj::{LoadConfig, ObjData, ObjError};
+
+/// Errors loading a mesh sequence.
+#[derive(Debug)]
+pub enum SequenceError {
+    Io(io::Error),
+    /// The file name of the pattern has no run of `#` for the frame number.
+    InvalidPattern(String),
+    /// No file matches the pattern.
+    NoFrames,
+    /// A frame could not be loaded.
+    Obj {
+        path: PathBuf,
+        error: ObjError,
+    },
+    /// The objects, groups, elements or attribute counts of a frame differ from the first frame.
+    TopologyMismatch {
+        frame: u64,
+    },
+}
+
+impl std::error::Error for SequenceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SequenceError::Io(err) => Some(err),
+            SequenceError::Obj { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceError::Io(err) => write!(f, "I/O error loading a mesh sequence: {}", err),
+            SequenceError::InvalidPattern(pattern) => {
+                write!(f, "The pattern has no `#` for the frame number: {}", pattern)
+            }
+            SequenceError::NoFrames => write!(f, "No file matches the pattern."),
+            SequenceError::Obj { path, error } => write!(f, "Failed to load {}: {}", path.display(), error),
+            SequenceError::TopologyMismatch { frame } => {
+                write!(f, "Frame {} has a different topology than the first frame.", frame)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for SequenceError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// The vertex positions of one frame of a [`Sequence`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// The number of the frame in its file name.
+    pub number: u64,
+    pub position: Vec<[f32; 3]>,
+}
+
+/// A loaded mesh sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequence {
+    /// The first frame, whose objects and attributes other than positions are shared by all
+    /// frames.
+    pub topology: ObjData,
+    /// All frames in order of their numbers, including the first.
+    pub frames: Vec<Frame>,
+}
+
+/// Load the files matching `pattern` as a sequence, see [`load_pattern_with`].
+pub fn load_pattern(pattern: impl AsRef<Path>) -> Result<Sequence, SequenceError> {
+    load_pattern_with(pattern, LoadConfig::default())
+}
+
+/// Load the files matching `pattern` as a sequence, parsing each with `config`.
+///
+/// The file name of the pattern holds a run of `#` standing for the frame number, such as
+/// `frame_####.obj`, which matches `frame_0001.obj` as well as `frame_12345.obj`. Frames are
+/// ordered by number and must have the same objects, groups, polygons and lines as the first,
+/// as well as the same numbers of positions, texture coordinates and normals.
+pub fn load_pattern_with(pattern: impl AsRef<Path>, config: LoadConfig) -> Result<Sequence, SequenceError> {
+    let pattern = pattern.as_ref();
+    let invalid = || SequenceError::InvalidPattern(pattern.display().to_string());
+    let name = pattern.file_name().and_then(|name| name.to_str()).ok_or_else(invalid)?;
+    let start = name.find('#').ok_or_else(invalid)?;
+    let digits = name[start..].bytes().take_while(|&b| b == b'#').count();
+    let (prefix, suffix) = (&name[..start], &name[start + digits..]);
+    if suffix.contains('#') {
+        return Err(invalid());
+    }
+
+    let dir = match pattern.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let number = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(prefix)?.strip_suffix(suffix))
+            .filter(|number| number.len() >= digits && number.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|number| number.parse::<u64>().ok());
+        if let Some(number) = number {
+            files.push((number, entry.path()));
+        }
+    }
+    files.sort();
+
+    let mut files = files.into_iter();
+    let (first_number, first_path) = files.next().ok_or(SequenceError::NoFrames)?;
+    let topology = load_frame(&first_path, config.clone())?;
+    let mut frames = vec![Frame {
+        number: first_number,
+        position: topology.position.clone(),
+    }];
+    for (number, path) in files {
+        let data = load_frame(&path, config.clone())?;
+        let same = data.position.len() == topology.position.len()
+            && data.texture.len() == topology.texture.len()
+            && data.normal.len() == topology.normal.len()
+            && data.objects == topology.objects;
+        if !same {
+            return Err(SequenceError::TopologyMismatch { frame: number });
+        }
+        frames.push(Frame {
+            number,
+            position: data.position,
+        });
+    }
+    Ok(Sequence { topology, frames })
+}
+
+fn load_frame(path: &Path, config: LoadConfig) -> Result<ObjData, SequenceError> {
+    let file = File::open(path)?;
+    ObjData::load_buf_with(file, config).map_err(|error| SequenceError::Obj {
+        path: path.to_path_buf(),
+        error,
+    })
+}
@@ -0,0 +1,340 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Mesh simplification by quadric error metric edge collapses.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    ops::Range,
+};
+
+use crate::compact::{AttributeRemap, RemapReport};
+use crate::math::{cross, dot, length, normalize, sub, Vec3};
+use crate::obj::{IndexTuple, ObjData, SimplePolygon};
+use crate::quads::ear_clip;
+
+/// A symmetric 4x4 matrix measuring the squared distance of a point to a set of planes.
+#[derive(Copy, Clone, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(n: [f64; 3], d: f64, weight: f64) -> Self {
+        let [a, b, c] = n;
+        Quadric([a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d].map(|x| x * weight))
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (x, y) in self.0.iter_mut().zip(&other.0) {
+            *x += y;
+        }
+    }
+
+    fn error(&self, p: Vec3) -> f64 {
+        let [a2, ab, ac, ad, b2, bc, bd, c2, cd, d2] = self.0;
+        let [x, y, z] = p.map(f64::from);
+        x * (a2 * x + 2.0 * (ab * y + ac * z + ad)) + y * (b2 * y + 2.0 * (bc * z + bd)) + z * (c2 * z + 2.0 * cd) + d2
+    }
+}
+
+/// A candidate collapse of position `from` onto position `to`.
+struct Candidate {
+    cost: f64,
+    from: usize,
+    to: usize,
+    /// The versions of both positions when the candidate was computed.
+    stamps: (u32, u32),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // Reversed, so that the cheapest collapse is at the top of the heap.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// What becomes of a polygon of a group, either kept as it is or replaced by a range of triangles.
+enum Source {
+    Kept,
+    Triangles(Range<usize>),
+}
+
+struct Simplifier<'a> {
+    position: &'a [[f32; 3]],
+    faces: Vec<[IndexTuple; 3]>,
+    alive: Vec<bool>,
+    /// The faces around each position, including dead ones.
+    incident: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    locked: Vec<bool>,
+    /// The index tuple shared by all corners at each unlocked position.
+    tuple: Vec<Option<IndexTuple>>,
+    stamps: Vec<u32>,
+    heap: BinaryHeap<Candidate>,
+}
+
+impl<'a> Simplifier<'a> {
+    fn neighbours(&self, v: usize) -> Vec<usize> {
+        let mut out: Vec<usize> = self.incident[v]
+            .iter()
+            .filter(|&&f| self.alive[f])
+            .flat_map(|&f| self.faces[f].iter().map(|t| t.position))
+            .filter(|&p| p != v)
+            .collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+
+    fn push_candidates(&mut self, v: usize) {
+        if self.locked[v] {
+            return;
+        }
+        for n in self.neighbours(v) {
+            if self.locked[n] {
+                continue;
+            }
+            let mut q = self.quadrics[v];
+            q.add(&self.quadrics[n]);
+            for (from, to) in [(v, n), (n, v)] {
+                self.heap.push(Candidate {
+                    cost: q.error(self.position[to]),
+                    from,
+                    to,
+                    stamps: (self.stamps[from], self.stamps[to]),
+                });
+            }
+        }
+    }
+
+    /// Check whether moving `from` onto `to` keeps the mesh manifold and doesn't flip any face.
+    fn can_collapse(&self, from: usize, to: usize) -> bool {
+        let shared = self.incident[from]
+            .iter()
+            .filter(|&&f| self.alive[f] && self.faces[f].iter().any(|t| t.position == to))
+            .count();
+        let (a, b) = (self.neighbours(from), self.neighbours(to));
+        let common = a.iter().filter(|v| b.binary_search(v).is_ok()).count();
+        if shared != 2 || common != 2 {
+            return false;
+        }
+
+        let normal = |p: [usize; 3]| {
+            let [a, b, c] = p.map(|i| self.position[i]);
+            normalize(cross(sub(b, a), sub(c, a)))
+        };
+        self.incident[from].iter().filter(|&&f| self.alive[f]).all(|&f| {
+            let before = self.faces[f].map(|t| t.position);
+            if before.contains(&to) {
+                return true;
+            }
+            let after = before.map(|p| if p == from { to } else { p });
+            match (normal(before), normal(after)) {
+                (Some(n0), Some(n1)) => dot(n0, n1) > 0.0,
+                _ => false,
+            }
+        })
+    }
+
+    /// Collapse `from` onto `to`, returning the number of removed faces.
+    fn collapse(&mut self, from: usize, to: usize) -> usize {
+        let target = self.tuple[to].unwrap();
+        let mut removed = 0;
+        for f in std::mem::take(&mut self.incident[from]) {
+            if !self.alive[f] {
+                continue;
+            }
+            if self.faces[f].iter().any(|t| t.position == to) {
+                self.alive[f] = false;
+                removed += 1;
+            } else {
+                for corner in &mut self.faces[f] {
+                    if corner.position == from {
+                        *corner = target;
+                    }
+                }
+                self.incident[to].push(f);
+            }
+        }
+        let q = self.quadrics[from];
+        self.quadrics[to].add(&q);
+        self.locked[from] = true;
+        self.stamps[from] += 1;
+        self.stamps[to] += 1;
+        for n in self.neighbours(to) {
+            self.stamps[n] += 1;
+        }
+        self.push_candidates(to);
+        for n in self.neighbours(to) {
+            self.push_candidates(n);
+        }
+        removed
+    }
+}
+
+impl ObjData {
+    /// Reduce the number of triangles to about `target_ratio` times the original count by
+    /// collapsing edges in order of increasing quadric error.
+    ///
+    /// Polygons are triangulated first. To preserve the appearance of the mesh, vertices on UV or
+    /// normal seams, on boundaries between groups (and thus materials) and on mesh boundaries are
+    /// never moved, and collapses that would flip a face or make the mesh non-manifold are
+    /// skipped. The target may therefore not be reached. Collapses move vertices onto existing
+    /// ones, so the attribute arrays are left untouched; use [`ObjData::compact`] to drop the
+    /// entries no longer referenced. Lines are left unchanged, as are polygons referring to
+    /// missing positions, which [`ObjData::validate_indices`] reports.
+    ///
+    /// Returns the number of triangles left.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn simplify(&mut self, target_ratio: f32) -> usize {
+        self.simplify_with_remap(target_ratio).0
+    }
+
+    /// Like [`ObjData::simplify`], also returning the polygons each polygon became, which keeps
+    /// all attributes in place.
+    pub fn simplify_with_remap(&mut self, target_ratio: f32) -> (usize, RemapReport) {
+        let identity = AttributeRemap::identity(self);
+        let mut counts = Vec::new();
+        let ObjData { position, objects, .. } = self;
+        let mut sources: Vec<Vec<Source>> = Vec::new();
+        let mut faces: Vec<[IndexTuple; 3]> = Vec::new();
+        let mut locked = vec![false; position.len()];
+        let mut tuple: Vec<Option<IndexTuple>> = vec![None; position.len()];
+        let mut group_of: Vec<Option<usize>> = vec![None; position.len()];
+
+        for (g, group) in objects.iter_mut().flat_map(|o| o.groups.iter_mut()).enumerate() {
+            let mut group_sources = Vec::new();
+            for poly in &group.polys {
+                let start = faces.len();
+                match ear_clip(poly, position) {
+                    Some(tris) => faces.extend(tris),
+                    None => {
+                        // Missing positions, which make `ear_clip` fail as well, have nothing to lock.
+                        for &IndexTuple { position: p, .. } in &poly.0 {
+                            if let Some(locked) = locked.get_mut(p) {
+                                *locked = true;
+                            }
+                        }
+                        group_sources.push(Source::Kept);
+                        continue;
+                    }
+                }
+                for &corner in faces[start..].iter().flatten() {
+                    let p = corner.position;
+                    if *tuple[p].get_or_insert(corner) != corner || *group_of[p].get_or_insert(g) != g {
+                        locked[p] = true;
+                    }
+                }
+                group_sources.push(Source::Triangles(start..faces.len()));
+            }
+            sources.push(group_sources);
+        }
+
+        let mut edges: HashMap<[usize; 2], usize> = HashMap::new();
+        let mut incident = vec![Vec::new(); position.len()];
+        let mut quadrics = vec![Quadric::default(); position.len()];
+        for (f, face) in faces.iter().enumerate() {
+            let idx = face.map(|t| t.position);
+            for k in 0..3 {
+                let (a, b) = (idx[k], idx[(k + 1) % 3]);
+                *edges.entry([a.min(b), a.max(b)]).or_default() += 1;
+                incident[idx[k]].push(f);
+            }
+            let [a, b, c] = idx.map(|i| position[i]);
+            let n = cross(sub(b, a), sub(c, a));
+            if let Some(unit) = normalize(n) {
+                // Weight the plane by the triangle area, so that small faces count less.
+                let area = 0.5 * f64::from(length(n));
+                let unit = unit.map(f64::from);
+                let d = -(unit[0] * f64::from(a[0]) + unit[1] * f64::from(a[1]) + unit[2] * f64::from(a[2]));
+                let q = Quadric::from_plane(unit, d, area);
+                for &i in &idx {
+                    quadrics[i].add(&q);
+                }
+            }
+        }
+        for (&[a, b], &count) in &edges {
+            if count != 2 || a == b {
+                locked[a] = true;
+                locked[b] = true;
+            }
+        }
+
+        let total = faces.len();
+        let target = (total as f32 * target_ratio.clamp(0.0, 1.0)).ceil() as usize;
+        let mut s = Simplifier {
+            position,
+            alive: vec![true; total],
+            faces,
+            incident,
+            quadrics,
+            locked,
+            tuple,
+            stamps: vec![0; position.len()],
+            heap: BinaryHeap::new(),
+        };
+        for v in 0..position.len() {
+            s.push_candidates(v);
+        }
+
+        let mut remaining = total;
+        while remaining > target {
+            let candidate = match s.heap.pop() {
+                Some(candidate) => candidate,
+                None => break,
+            };
+            let (from, to) = (candidate.from, candidate.to);
+            if candidate.stamps != (s.stamps[from], s.stamps[to]) || s.locked[from] || s.locked[to] {
+                continue;
+            }
+            if s.can_collapse(from, to) {
+                remaining -= s.collapse(from, to);
+            }
+        }
+
+        for (group, group_sources) in objects.iter_mut().flat_map(|o| o.groups.iter_mut()).zip(sources) {
+            let mut group_sources = group_sources.into_iter();
+            group.map_polys(|poly, polys| match group_sources.next() {
+                Some(Source::Triangles(range)) => {
+                    let len = polys.len();
+                    polys.extend(
+                        range
+                            .filter(|&f| s.alive[f])
+                            .map(|f| SimplePolygon(s.faces[f].to_vec())),
+                    );
+                    counts.push(polys.len() - len);
+                }
+                _ => {
+                    polys.push(poly);
+                    counts.push(1);
+                }
+            });
+        }
+        (remaining, RemapReport::new(identity, counts))
+    }
+}
@@ -0,0 +1,134 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Cross-sections of a mesh with a plane.
+
+use std::collections::HashMap;
+
+use crate::math::{add, cross, dot, newell_normal, scale, sub, Vec3};
+use crate::obj::ObjData;
+
+/// A polyline in a cross-section.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contour {
+    pub points: Vec<[f32; 3]>,
+    /// Whether the last point connects back to the first one.
+    pub closed: bool,
+}
+
+/// Identifies a point of the cross-section by the mesh element it lies on, so that segments
+/// from neighbouring polygons can be joined.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    Vertex(usize),
+    Edge(usize, usize),
+}
+
+impl ObjData {
+    /// Intersect the mesh with the plane through `origin` perpendicular to `normal`.
+    ///
+    /// Returns one contour per connected polyline of the cross-section. Contours of closed meshes
+    /// are closed; open contours start and end on the mesh boundary. Polygons are joined through
+    /// shared position indices, so vertices that merely coincide break contours apart; see
+    /// [`ObjData::weld_vertices`].
+    ///
+    /// Geometry lying exactly on the plane is treated as being on the side `normal` points to.
+    /// Polygons within the plane therefore do not contribute, and a mesh touching the plane from
+    /// that side has no cross-section.
+    pub fn slice(&self, origin: [f32; 3], normal: [f32; 3]) -> Vec<Contour> {
+        let distance: Vec<f32> = self.position.iter().map(|&p| dot(sub(p, origin), normal)).collect();
+        let mut points: HashMap<Key, Vec3> = HashMap::new();
+        let mut segments: Vec<[Key; 2]> = Vec::new();
+
+        for poly in self.polys() {
+            let n = poly.0.len();
+            // Vertices on the plane count as being above it, so that every crossing is found on
+            // exactly one side.
+            let mut crossings = Vec::new();
+            for k in 0..n {
+                let (a, b) = (poly.0[k].position, poly.0[(k + 1) % n].position);
+                let (da, db) = (distance[a], distance[b]);
+                if (da >= 0.0) == (db >= 0.0) {
+                    continue;
+                }
+                let key = if da == 0.0 {
+                    Key::Vertex(a)
+                } else if db == 0.0 {
+                    Key::Vertex(b)
+                } else {
+                    Key::Edge(a.min(b), a.max(b))
+                };
+                let (pa, pb) = (self.position[a], self.position[b]);
+                let point = *points
+                    .entry(key)
+                    .or_insert_with(|| add(pa, scale(sub(pb, pa), da / (da - db))));
+                crossings.push((key, point));
+            }
+            if crossings.len() > 2 {
+                // A concave polygon is crossed more than twice. Its intersections with the plane
+                // alternate between entering and leaving along the line of intersection.
+                let points: Vec<_> = poly.0.iter().map(|t| self.position[t.position]).collect();
+                let dir = cross(normal, newell_normal(&points));
+                crossings.sort_by(|a, b| dot(a.1, dir).partial_cmp(&dot(b.1, dir)).unwrap());
+            }
+            for pair in crossings.chunks_exact(2) {
+                if pair[0].0 != pair[1].0 {
+                    segments.push([pair[0].0, pair[1].0]);
+                }
+            }
+        }
+
+        let mut adjacent: HashMap<Key, Vec<usize>> = HashMap::new();
+        for (i, segment) in segments.iter().enumerate() {
+            adjacent.entry(segment[0]).or_default().push(i);
+            adjacent.entry(segment[1]).or_default().push(i);
+        }
+        let mut used = vec![false; segments.len()];
+        // Follow unused segments from the end of `chain`, appending the visited points to `chain`.
+        let walk = |chain: &mut Vec<Key>, used: &mut Vec<bool>| loop {
+            let key = *chain.last().unwrap();
+            let next = adjacent[&key].iter().copied().find(|&s| !used[s]);
+            match next {
+                Some(s) => {
+                    used[s] = true;
+                    let [a, b] = segments[s];
+                    chain.push(if a == key { b } else { a });
+                }
+                None => break,
+            }
+        };
+
+        let mut contours = Vec::new();
+        for start in 0..segments.len() {
+            if used[start] {
+                continue;
+            }
+            used[start] = true;
+            let mut chain = segments[start].to_vec();
+            walk(&mut chain, &mut used);
+            let closed = chain.len() > 2 && chain.first() == chain.last();
+            if closed {
+                chain.pop();
+            } else {
+                chain.reverse();
+                walk(&mut chain, &mut used);
+            }
+            contours.push(Contour {
+                points: chain.iter().map(|key| points[key]).collect(),
+                closed,
+            });
+        }
+        contours
+    }
+}
@@ -0,0 +1,127 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Derivation of smoothing groups from crease angles.
+
+use std::collections::HashMap;
+
+use crate::math::{angle_between, newell_normal, normalize};
+use crate::obj::{Group, ObjData, Object};
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+impl ObjData {
+    /// Assign smoothing groups to the polygons of every object, separated at hard edges.
+    ///
+    /// Two polygons sharing an edge, by position indices, are smoothed together if their normals
+    /// differ by at most `crease_angle` radians. Boundary and non-manifold edges are hard. Each
+    /// connected patch of smoothed polygons gets its own smoothing group, numbered from 1 in order
+    /// of first appearance, and polygons without smooth neighbours get none. The smoothing groups
+    /// are set as [`Group::state_changes`] between the polygons, keeping the groups as they are.
+    ///
+    /// Returns the number of smoothing groups.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn derive_smoothing_groups(&mut self, crease_angle: f32) -> u32 {
+        let mut next = 1;
+        for object in &mut self.objects {
+            let ids = patch_ids(&self.position, object, crease_angle, &mut next);
+            let mut ids = ids.into_iter();
+            for group in &mut object.groups {
+                assign_smoothing_groups(group, &mut ids);
+            }
+        }
+        next - 1
+    }
+}
+
+/// The smoothing group of every polygon of `object`, in order, continuing the numbering at `next`.
+fn patch_ids(position: &[[f32; 3]], object: &Object, crease_angle: f32, next: &mut u32) -> Vec<u32> {
+    let polys: Vec<_> = object.groups.iter().flat_map(|g| &g.polys).collect();
+    let normals: Vec<_> = polys
+        .iter()
+        .map(|poly| {
+            let points: Vec<_> = poly.0.iter().map(|t| position[t.position]).collect();
+            normalize(newell_normal(&points))
+        })
+        .collect();
+
+    let mut edges: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+    for (face, poly) in polys.iter().enumerate() {
+        let n = poly.0.len();
+        for k in 0..n {
+            let (a, b) = (poly.0[k].position, poly.0[(k + 1) % n].position);
+            if a != b {
+                edges.entry([a.min(b), a.max(b)]).or_default().push(face);
+            }
+        }
+    }
+
+    let mut parent: Vec<usize> = (0..polys.len()).collect();
+    let mut smooth = vec![false; polys.len()];
+    for faces in edges.values() {
+        if let &[a, b] = &faces[..] {
+            match (normals[a], normals[b]) {
+                (Some(na), Some(nb)) if a != b && angle_between(na, nb) <= crease_angle => {
+                    let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+                    parent[ra] = rb;
+                    smooth[a] = true;
+                    smooth[b] = true;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut numbers: HashMap<usize, u32> = HashMap::new();
+    (0..polys.len())
+        .map(|face| {
+            if !smooth[face] {
+                return 0;
+            }
+            let root = find(&mut parent, face);
+            *numbers.entry(root).or_insert_with(|| {
+                *next += 1;
+                *next - 1
+            })
+        })
+        .collect()
+}
+
+/// Set the smoothing group of every polygon of `group` to the next one of `ids`.
+///
+/// Smoothing doesn't apply to lines, so they keep the smoothing group in effect before them to
+/// avoid extra `s` statements.
+fn assign_smoothing_groups(group: &mut Group, ids: &mut impl Iterator<Item = u32>) {
+    let source = std::mem::replace(group, Group::new(group.name.clone()));
+    group.index = source.index;
+    group.material = source.material.clone();
+    for (state, polys, lines) in source.state_runs() {
+        let mut state = state.clone();
+        for (poly, id) in polys.iter().zip(ids.by_ref()) {
+            state.smoothing_group = id;
+            group.set_render_state(state.clone());
+            group.polys.push(poly.clone());
+        }
+        if !lines.is_empty() {
+            group.set_render_state(state);
+            group.lines.extend_from_slice(lines);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! A uniform grid over the polygons of a mesh for proximity queries.
+
+use crate::bounds::BoundingBox;
+use crate::buffers::fan;
+use crate::math::{closest_point_on_triangle, length, sub, Vec3};
+use crate::obj::ObjData;
+use crate::quads::ear_clip;
+
+/// The point on the mesh nearest to a query point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClosestPoint {
+    pub point: [f32; 3],
+    pub distance: f32,
+    /// The polygon containing the point, numbered consecutively across all groups of all objects.
+    pub polygon: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Triangle {
+    points: [Vec3; 3],
+    bbox: BoundingBox,
+    polygon: usize,
+}
+
+/// A uniform grid over the triangulated polygons of an `ObjData`, see
+/// [`ObjData::build_spatial_index`].
+///
+/// Like the hierarchy of the `bvh` module, the index holds a copy of the geometry, so it stays valid when
+/// the mesh is modified afterwards, but won't reflect the changes. Cells are sized so that there
+/// are about as many of them as triangles, which suits evenly tessellated meshes best.
+#[derive(Clone, Debug)]
+pub struct SpatialIndex {
+    triangles: Vec<Triangle>,
+    /// The bounds of all triangles, covered by the cells.
+    bbox: BoundingBox,
+    /// The number of cells along each axis.
+    dims: [usize; 3],
+    /// The triangles overlapping each cell, in x-major order.
+    cells: Vec<Vec<usize>>,
+}
+
+impl SpatialIndex {
+    /// Build an index over all polygons of the mesh.
+    ///
+    /// Polygons are triangulated, and those with fewer than three vertices are ignored.
+    pub fn new(data: &ObjData) -> SpatialIndex {
+        let mut triangles = Vec::new();
+        for (polygon, poly) in data.polys().enumerate() {
+            let tris = ear_clip(poly, &data.position).unwrap_or_else(|| fan(poly).collect());
+            for tri in tris {
+                let points = tri.map(|t| data.position[t.position]);
+                triangles.push(Triangle {
+                    points,
+                    bbox: BoundingBox::from_points(&points).unwrap(),
+                    polygon,
+                });
+            }
+        }
+
+        let bbox = match triangles.iter().map(|t| t.bbox).reduce(|a, b| a.union(&b)) {
+            Some(bbox) => bbox,
+            None => {
+                return SpatialIndex {
+                    triangles,
+                    bbox: BoundingBox {
+                        min: [0.0; 3],
+                        max: [0.0; 3],
+                    },
+                    dims: [0; 3],
+                    cells: Vec::new(),
+                }
+            }
+        };
+        // Spread about one cell per triangle over the axes the mesh extends along.
+        let size = bbox.size();
+        let axes = size.iter().filter(|&&s| s > 0.0).count().max(1) as f32;
+        let per_axis = (triangles.len() as f32).powf(1.0 / axes).ceil() as usize;
+        let dims = size.map(|s| if s > 0.0 { per_axis.max(1) } else { 1 });
+
+        let mut index = SpatialIndex {
+            triangles,
+            bbox,
+            dims,
+            cells: vec![Vec::new(); dims[0] * dims[1] * dims[2]],
+        };
+        for i in 0..index.triangles.len() {
+            let (lo, hi) = index.cell_range(&index.triangles[i].bbox);
+            for cell in index.cells_in(lo, hi) {
+                index.cells[cell].push(i);
+            }
+        }
+        index
+    }
+
+    /// The cell containing the given point, clamped to the grid along every axis.
+    fn cell_of(&self, p: Vec3) -> [usize; 3] {
+        [0, 1, 2].map(|i| {
+            let size = self.bbox.max[i] - self.bbox.min[i];
+            if size > 0.0 {
+                let t = (p[i] - self.bbox.min[i]) / size * self.dims[i] as f32;
+                (t.max(0.0) as usize).min(self.dims[i] - 1)
+            } else {
+                0
+            }
+        })
+    }
+
+    /// The first and last cell along each axis overlapping the box.
+    fn cell_range(&self, bbox: &BoundingBox) -> ([usize; 3], [usize; 3]) {
+        (self.cell_of(bbox.min), self.cell_of(bbox.max))
+    }
+
+    /// The indices into `cells` of the cells between `lo` and `hi`, inclusively.
+    fn cells_in(&self, lo: [usize; 3], hi: [usize; 3]) -> impl Iterator<Item = usize> {
+        let dims = self.dims;
+        (lo[0]..=hi[0]).flat_map(move |x| {
+            (lo[1]..=hi[1]).flat_map(move |y| (lo[2]..=hi[2]).map(move |z| (x * dims[1] + y) * dims[2] + z))
+        })
+    }
+
+    /// The bounds of the cell with the given coordinates.
+    fn cell_box(&self, cell: [usize; 3]) -> BoundingBox {
+        let BoundingBox { min, max } = self.bbox;
+        let step = [0, 1, 2].map(|i| (max[i] - min[i]) / self.dims[i] as f32);
+        BoundingBox {
+            min: [0, 1, 2].map(|i| min[i] + step[i] * cell[i] as f32),
+            max: [0, 1, 2].map(|i| {
+                if cell[i] + 1 == self.dims[i] {
+                    max[i]
+                } else {
+                    min[i] + step[i] * (cell[i] + 1) as f32
+                }
+            }),
+        }
+    }
+
+    /// Find the polygons with a triangle whose bounding box overlaps `bbox`.
+    ///
+    /// Polygons are numbered consecutively across all groups of all objects and returned in
+    /// ascending order.
+    pub fn query_box(&self, bbox: &BoundingBox) -> Vec<usize> {
+        if self.cells.is_empty() || !self.bbox.intersects(bbox) {
+            return Vec::new();
+        }
+        let (lo, hi) = self.cell_range(bbox);
+        let mut polygons: Vec<usize> = self
+            .cells_in(lo, hi)
+            .flat_map(|cell| &self.cells[cell])
+            .map(|&i| &self.triangles[i])
+            .filter(|tri| tri.bbox.intersects(bbox))
+            .map(|tri| tri.polygon)
+            .collect();
+        polygons.sort_unstable();
+        polygons.dedup();
+        polygons
+    }
+
+    /// Find the point of the mesh closest to `point`.
+    ///
+    /// Returns `None` if the mesh has no polygons.
+    pub fn closest_point(&self, point: [f32; 3]) -> Option<ClosestPoint> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        // Search shells of cells around the one nearest to the point. Cells of a shell are no
+        // closer than those of the shell inside it, so once a whole shell is farther away than
+        // the best point found so far, so is everything beyond.
+        let center = self.cell_of(point);
+        let mut best: Option<ClosestPoint> = None;
+        for radius in 0..=*self.dims.iter().max().unwrap() {
+            let lo = center.map(|c| c.saturating_sub(radius));
+            let hi = [0, 1, 2].map(|i| (center[i] + radius).min(self.dims[i] - 1));
+            let mut any_closer = false;
+            for x in lo[0]..=hi[0] {
+                for y in lo[1]..=hi[1] {
+                    for z in lo[2]..=hi[2] {
+                        let cell = [x, y, z];
+                        if (0..3).all(|i| cell[i].abs_diff(center[i]) < radius) {
+                            continue;
+                        }
+                        if best.is_some_and(|best| self.cell_box(cell).distance(point) > best.distance) {
+                            continue;
+                        }
+                        any_closer = true;
+                        let index = (x * self.dims[1] + y) * self.dims[2] + z;
+                        for &i in &self.cells[index] {
+                            let tri = &self.triangles[i];
+                            let candidate = closest_point_on_triangle(point, tri.points);
+                            let distance = length(sub(candidate, point));
+                            if best.is_none_or(|best| distance < best.distance) {
+                                best = Some(ClosestPoint {
+                                    point: candidate,
+                                    distance,
+                                    polygon: tri.polygon,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            if best.is_some() && !any_closer {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl ObjData {
+    /// Build a [`SpatialIndex`] over all polygons, for finding the polygons near a point or
+    /// within a box.
+    pub fn build_spatial_index(&self) -> SpatialIndex {
+        SpatialIndex::new(self)
+    }
+}
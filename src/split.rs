@@ -0,0 +1,681 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Splitting of an `ObjData` into several independent meshes.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::mtl::{Material, Mtl};
+use crate::obj::{
+    matches_pattern, write_summary, Group, IndexTuple, Line, LoadConfig, ObjData, ObjError, ObjMaterial, Object,
+    RenderState, SimplePolygon, DEFAULT_GROUP, DEFAULT_OBJECT, SUMMARIES,
+};
+use crate::raw::Statement;
+
+/// How [`ObjData::save_split`] divides a mesh into files.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SplitBy {
+    /// One file per object.
+    Object,
+    /// One file per material, as given by [`ObjData::split_by_material`].
+    Material,
+}
+
+/// Which material libraries the files written by [`ObjData::save_split`] refer to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SplitMtl {
+    /// Every file refers to all material libraries of the mesh, which are written once.
+    Shared,
+    /// Every file refers to a library of its own, named after the file and holding only the
+    /// materials it uses.
+    PerFile,
+}
+
+/// The largest parts written by [`ObjData::split_file_by_budget`].
+///
+/// Limits that are `None` don't apply. Both default to `None`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SplitBudget {
+    /// The largest number of distinct vertices, counted as distinct combinations of position,
+    /// texture and normal indices, as renderers do once attributes are unified.
+    pub max_vertices: Option<usize>,
+    /// The largest number of polygons and lines.
+    pub max_elements: Option<usize>,
+}
+
+/// The polygons and lines of the part [`ObjData::split_file_by_budget`] is collecting.
+#[derive(Default)]
+struct BudgetPart {
+    /// The groups of the part with the names of their objects, in order.
+    groups: Vec<(Arc<str>, Group)>,
+    vertices: HashSet<IndexTuple>,
+    elements: usize,
+}
+
+impl BudgetPart {
+    /// Whether adding an element with the vertices `tuples` would exceed `budget`. An element
+    /// always fits into an empty part.
+    fn exceeds(&self, budget: SplitBudget, tuples: &[IndexTuple]) -> bool {
+        if self.elements == 0 {
+            return false;
+        }
+        let new: HashSet<_> = tuples.iter().filter(|t| !self.vertices.contains(t)).collect();
+        budget
+            .max_vertices
+            .is_some_and(|max| self.vertices.len() + new.len() > max)
+            || budget.max_elements.is_some_and(|max| self.elements + 1 > max)
+    }
+
+    /// The group of `object` to add elements with the given group name, material and render
+    /// state to.
+    fn group(
+        &mut self,
+        object: &Arc<str>,
+        name: &Arc<str>,
+        material: &Option<ObjMaterial>,
+        state: &RenderState,
+    ) -> &mut Group {
+        let matches = |(o, g): &(Arc<str>, Group)| o == object && g.name == *name && g.material == *material;
+        if !self.groups.last().is_some_and(matches) {
+            let mut group = Group::new(name.clone());
+            group.material = material.clone();
+            self.groups.push((object.clone(), group));
+        }
+        let group = &mut self.groups.last_mut().unwrap().1;
+        group.set_render_state(state.clone());
+        group
+    }
+
+    fn add(&mut self, tuples: &[IndexTuple]) {
+        self.vertices.extend(tuples.iter().copied());
+        self.elements += 1;
+    }
+}
+
+/// A file stem for `name` made of ASCII letters, digits, `-`, `_` and `.`, other characters being
+/// replaced by `_`.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+    match stem.trim_start_matches('.') {
+        "" => "unnamed".to_string(),
+        _ => stem,
+    }
+}
+
+/// Read the .obj file from `reader` in parts that each stay within `budget`, as described for
+/// [`ObjData::split_file_by_budget`], passing each part to `part` as a standalone mesh as soon as
+/// it is complete.
+///
+/// `mtllib` is called with the name of each material library the first time it is named.
+pub(crate) fn read_budget_parts(
+    reader: impl BufRead,
+    budget: SplitBudget,
+    mut mtllib: impl FnMut(&str) -> Result<(), ObjError>,
+    mut part: impl FnMut(ObjData) -> Result<(), ObjError>,
+) -> Result<(), ObjError> {
+    let config = LoadConfig::default();
+
+    let mut attributes = ObjData::default();
+    let mut object: Arc<str> = config.implicit_object_name().into();
+    let mut group: Arc<str> = DEFAULT_GROUP.into();
+    let mut material = None;
+    let mut state = RenderState::default();
+    let mut current = BudgetPart::default();
+
+    let mut finish_part = |current: BudgetPart, attributes: &ObjData| {
+        let mut builder = SubsetBuilder::new(attributes);
+        for (object, group) in &current.groups {
+            builder.push_group(object, group);
+        }
+        part(builder.finish())
+    };
+
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let statement = match Statement::parse(line_number, &line) {
+            Some(statement) => statement,
+            None => continue,
+        };
+        let mut words = statement.args();
+        let lens = [
+            attributes.position.len(),
+            attributes.texture.len(),
+            attributes.normal.len(),
+        ];
+        match statement.keyword() {
+            "v" => {
+                let (v0, v1, v2) = (words.next(), words.next(), words.next());
+                attributes
+                    .position
+                    .push(ObjData::<f32>::parse_three(line_number, v0, v1, v2)?);
+                let (r, g, b) = (words.next(), words.next(), words.next());
+                if r.is_some() && g.is_some() {
+                    let color = ObjData::<f32>::parse_three(line_number, r, g, b)?;
+                    attributes.color.resize(attributes.position.len() - 1, [1.0; 3]);
+                    attributes.color.push(color);
+                } else if !attributes.color.is_empty() {
+                    attributes.color.push([1.0; 3]);
+                }
+            }
+            "vt" => attributes
+                .texture
+                .push(ObjData::<f32>::parse_two(line_number, words.next(), words.next())?),
+            "vn" => {
+                let (n0, n1, n2) = (words.next(), words.next(), words.next());
+                attributes
+                    .normal
+                    .push(ObjData::<f32>::parse_three(line_number, n0, n1, n2)?);
+            }
+            keyword @ ("f" | "l") => {
+                let (poly, line) = if keyword == "f" {
+                    (Some(ObjData::<f32>::parse_face(line_number, &mut words, lens)?), None)
+                } else {
+                    (None, Some(ObjData::<f32>::parse_line(line_number, &mut words, lens)?))
+                };
+                let tuples = poly
+                    .as_ref()
+                    .map_or_else(|| &line.as_ref().unwrap().0[..], |p| &p.0[..]);
+                if current.exceeds(budget, tuples) {
+                    finish_part(std::mem::take(&mut current), &attributes)?;
+                }
+                current.add(tuples);
+                let target = current.group(&object, &group, &material, &state);
+                target.polys.extend(poly);
+                target.lines.extend(line);
+            }
+            "o" => {
+                object = match statement.rest() {
+                    "" => DEFAULT_OBJECT,
+                    name => name,
+                }
+                .into();
+                group = DEFAULT_GROUP.into();
+            }
+            "g" => {
+                group = match statement.rest() {
+                    "" => DEFAULT_GROUP,
+                    name => name,
+                }
+                .into();
+            }
+            "usemtl" => {
+                material = config
+                    .usemtl_name(words.next())
+                    .map(|name| ObjMaterial::Ref(name.into()));
+            }
+            "mtllib" => {
+                let name = statement.rest();
+                if !name.is_empty() && attributes.material_libs.iter().all(|m| m.filename != name) {
+                    attributes.material_libs.push(Mtl::new(name.to_string()));
+                    mtllib(name)?;
+                }
+            }
+            keyword @ ("s" | "bevel" | "c_interp" | "d_interp" | "lod" | "usemap") => {
+                // Malformed settings are skipped, see `RenderState`.
+                let _ = state.apply(line_number, keyword, words.next(), |map| map.into());
+            }
+            _ => (),
+        }
+    }
+    if current.elements > 0 {
+        finish_part(current, &attributes)?;
+    }
+    Ok(())
+}
+
+/// Incrementally builds an `ObjData` from a subset of another one's polygons and lines, copying
+/// over only the referenced attributes.
+pub(crate) struct SubsetBuilder<'a> {
+    source: &'a ObjData,
+    data: ObjData,
+    position: HashMap<usize, usize>,
+    texture: HashMap<usize, usize>,
+    normal: HashMap<usize, usize>,
+}
+
+fn remap<T: Copy>(map: &mut HashMap<usize, usize>, out: &mut Vec<T>, src: &[T], idx: usize) -> usize {
+    *map.entry(idx).or_insert_with(|| {
+        out.push(src[idx]);
+        out.len() - 1
+    })
+}
+
+impl<'a> SubsetBuilder<'a> {
+    pub(crate) fn new(source: &'a ObjData) -> Self {
+        SubsetBuilder {
+            source,
+            data: ObjData {
+                material_libs: source.material_libs.clone(),
+                shadow_obj: source.shadow_obj.clone(),
+                trace_obj: source.trace_obj.clone(),
+                default_material: source.default_material.clone(),
+                metadata: source.metadata.clone(),
+                ..ObjData::default()
+            },
+            position: HashMap::new(),
+            texture: HashMap::new(),
+            normal: HashMap::new(),
+        }
+    }
+
+    /// Copy the given polygon, returning it with indices into the new attribute arrays.
+    pub(crate) fn copy_poly(&mut self, poly: &SimplePolygon) -> SimplePolygon {
+        SimplePolygon::from(self.copy_tuples(&poly.0))
+    }
+
+    /// Copy the given line, returning it with indices into the new attribute arrays.
+    pub(crate) fn copy_line(&mut self, line: &Line) -> Line {
+        Line(self.copy_tuples(&line.0))
+    }
+
+    fn copy_tuples(&mut self, tuples: &[IndexTuple]) -> Vec<IndexTuple> {
+        let SubsetBuilder {
+            source: src,
+            data,
+            position,
+            texture,
+            normal,
+        } = self;
+        tuples
+            .iter()
+            .map(|tuple| {
+                let p = tuple.position;
+                let copied = data.position.len();
+                let new_p = remap(position, &mut data.position, &src.position, p);
+                if !src.color.is_empty() && data.position.len() > copied {
+                    data.color.push(src.color[p]);
+                }
+                IndexTuple::new(
+                    new_p,
+                    tuple
+                        .texture
+                        .map(|t| remap(texture, &mut data.texture, &src.texture, t)),
+                    tuple.normal.map(|n| remap(normal, &mut data.normal, &src.normal, n)),
+                )
+            })
+            .collect()
+    }
+
+    /// Append a copy of the given group to the object named `object`.
+    ///
+    /// A new object is started unless the most recently added object has the same name.
+    pub(crate) fn push_group(&mut self, object: &str, group: &Group) {
+        self.push_group_where(object, group, |_| true, |_| true);
+    }
+
+    /// Like [`SubsetBuilder::push_group`], copying only the polygons and lines whose indices
+    /// `keep_poly` and `keep_line` return `true` for, each with its render state.
+    ///
+    /// Polygons and lines referring to missing attributes, which [`ObjData::validate_indices`]
+    /// reports, are skipped.
+    pub(crate) fn push_group_where(
+        &mut self,
+        object: &str,
+        group: &Group,
+        mut keep_poly: impl FnMut(usize) -> bool,
+        mut keep_line: impl FnMut(usize) -> bool,
+    ) {
+        let mut copy = Group::new(group.name.clone());
+        copy.material = group.material.clone();
+        let (mut p, mut l) = (0, 0);
+        for (state, polys, lines) in group.state_runs() {
+            copy.set_render_state(state.clone());
+            for poly in polys {
+                if keep_poly(p) && self.source.has_attributes(&poly.0) {
+                    let poly = self.copy_poly(poly);
+                    copy.polys.push(poly);
+                }
+                p += 1;
+            }
+            for line in lines {
+                if keep_line(l) && self.source.has_attributes(&line.0) {
+                    let line = self.copy_line(line);
+                    copy.lines.push(line);
+                }
+                l += 1;
+            }
+        }
+        if self.data.objects.last().map(|o| &*o.name != object).unwrap_or(true) {
+            self.data.objects.push(Object::new(object.to_string()));
+        }
+        self.data.objects.last_mut().unwrap().groups.push(copy);
+    }
+
+    pub(crate) fn finish(mut self) -> ObjData {
+        for object in &mut self.data.objects {
+            object.renumber_groups();
+        }
+        self.data
+    }
+}
+
+impl ObjData {
+    /// Copy the objects and groups whose names match one of `patterns` into a standalone mesh.
+    ///
+    /// In patterns, `*` matches any run of characters and `?` any single character. All groups of
+    /// a matching object are copied, as well as matching groups of other objects, keeping their
+    /// object. Attribute arrays are compacted to the entries the copied polygons and lines
+    /// reference, while materials and material libraries are carried over.
+    pub fn extract<P: AsRef<str>>(&self, patterns: &[P]) -> ObjData {
+        let matches = |name: &str| patterns.iter().any(|p| matches_pattern(p.as_ref(), name));
+        let mut builder = SubsetBuilder::new(self);
+        for object in &self.objects {
+            let whole = matches(&object.name);
+            for group in object.groups.iter().filter(|g| whole || matches(&g.name)) {
+                builder.push_group(&object.name, group);
+            }
+        }
+        builder.finish()
+    }
+
+    /// Serialize only the groups for which `keep` returns `true` into the given writer, e.g. to
+    /// export a selection.
+    ///
+    /// Like for [`ObjData::extract`], only the attributes the kept polygons and lines reference
+    /// are written, while all material libraries are referenced. The mesh itself is left as is.
+    ///
+    /// Nothing is written if [`ObjData::validate_indices`] fails.
+    pub fn write_filtered(
+        &self,
+        out: &mut impl Write,
+        mut keep: impl FnMut(&Object, &Group) -> bool,
+    ) -> Result<(), ObjError> {
+        self.validate_indices()?;
+        let mut builder = SubsetBuilder::new(self);
+        for object in &self.objects {
+            for group in object.groups.iter().filter(|g| keep(object, g)) {
+                builder.push_group(&object.name, group);
+            }
+        }
+        builder.finish().write_to_buf(out)
+    }
+
+    /// Write every object followed by the attributes it uses, see [`crate::WriteConfig::relative_indices`].
+    ///
+    /// Every block is followed by summary comments if `summaries` is set, see
+    /// [`crate::WriteConfig::summary_comments`].
+    pub(crate) fn write_object_blocks(&self, summaries: bool, out: &mut impl fmt::Write) -> fmt::Result {
+        self.write_header(out)?;
+        let mut state = None;
+        for object in &self.objects {
+            let mut builder = SubsetBuilder::new(self);
+            for group in &object.groups {
+                builder.push_group(&object.name, group);
+            }
+            let part = builder.finish();
+            object.write_header(out)?;
+            part.write_attributes(summaries, out)?;
+            let lens = [part.position.len(), part.texture.len(), part.normal.len()];
+            for object in &part.objects {
+                object.write_groups(state, Some(lens), out)?;
+            }
+            if summaries {
+                write_summary(out, object.element_count(), SUMMARIES[4].0)?;
+            }
+            state = object.groups.last().map(Group::end_render_state).or(state);
+        }
+        self.write_references(out)
+    }
+
+    /// Write this mesh to one .obj file per object or material in `dir`, which is created if
+    /// needed, returning the paths of the written files in order.
+    ///
+    /// Files are named after their object or material, with characters unsuitable for file
+    /// names replaced by `_`, groups without a material going to `default.obj`. A `.1`, `.2`, ...
+    /// suffix is added to names that are already taken. Existing files are overwritten.
+    ///
+    /// Material libraries are written next to the files as given by `mtl`. For
+    /// [`SplitMtl::PerFile`], materials that are only referenced by name are looked up in the
+    /// loaded libraries and left out if they are missing.
+    ///
+    /// Nothing is written if [`ObjData::validate_indices`] fails.
+    pub fn save_split(&self, dir: impl AsRef<Path>, by: SplitBy, mtl: SplitMtl) -> Result<Vec<PathBuf>, ObjError> {
+        self.validate_indices()?;
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let parts: Vec<(String, ObjData)> = match by {
+            SplitBy::Object => self
+                .objects
+                .iter()
+                .map(|object| {
+                    let mut builder = SubsetBuilder::new(self);
+                    for group in &object.groups {
+                        builder.push_group(&object.name, group);
+                    }
+                    (object.name.to_string(), builder.finish())
+                })
+                .collect(),
+            SplitBy::Material => self
+                .split_by_material()
+                .into_iter()
+                .map(|(material, part)| {
+                    let name = material.as_ref().map_or("default", |m| m.name()).to_string();
+                    (name, part)
+                })
+                .collect(),
+        };
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for (name, mut part) in parts {
+            let stem = file_stem(&name);
+            let stem = (0..)
+                .map(|n| {
+                    if n == 0 {
+                        stem.clone()
+                    } else {
+                        format!("{}.{}", stem, n)
+                    }
+                })
+                .find(|stem| !paths.iter().any(|p| p.file_stem() == Some(OsStr::new(stem))))
+                .unwrap();
+            if mtl == SplitMtl::PerFile {
+                part.material_libs = self
+                    .used_materials(&part)
+                    .map(|materials| Mtl {
+                        filename: format!("{}.mtl", stem),
+                        materials,
+                    })
+                    .into_iter()
+                    .collect();
+            }
+
+            let path = dir.join(format!("{}.obj", stem));
+            part.write_to_buf(&mut BufWriter::new(File::create(&path)?))?;
+            if mtl == SplitMtl::PerFile {
+                part.save_mtls(dir)?;
+            }
+            paths.push(path);
+        }
+        if mtl == SplitMtl::Shared {
+            self.save_mtls(dir)?;
+        }
+        Ok(paths)
+    }
+
+    /// Split the .obj file at `path` into files in `dir` that each stay within `budget`, for
+    /// engines that limit the size of a mesh, returning the paths of the written files in order.
+    ///
+    /// Polygons and lines are read in order and collected until the next one would exceed the
+    /// budget, when the collected ones are written to a file with only the attributes they use,
+    /// keeping their objects, groups, materials and render states. An element that exceeds the
+    /// budget by itself gets a file of its own. Files are named after the input with a `_0`,
+    /// `_1`, ... suffix, and existing files are overwritten. Every file refers to the material
+    /// libraries named so far, which are copied to `dir` if they exist next to the input.
+    /// Libraries named by absolute paths or paths containing `..` are not copied.
+    ///
+    /// Only the vertex attributes are kept for the whole file, as elements may refer to any of
+    /// them, so memory use doesn't grow with the number of elements. The file is read as with
+    /// [`LoadConfig::strict`] turned off, so unknown statements are ignored.
+    pub fn split_file_by_budget(
+        path: impl AsRef<Path>,
+        dir: impl AsRef<Path>,
+        budget: SplitBudget,
+    ) -> Result<Vec<PathBuf>, ObjError> {
+        let (path, dir) = (path.as_ref(), dir.as_ref());
+        let reader = BufReader::new(File::open(path)?);
+        fs::create_dir_all(dir)?;
+        let stem = file_stem(&path.file_stem().unwrap_or_default().to_string_lossy());
+        let mut paths = Vec::new();
+        read_budget_parts(
+            reader,
+            budget,
+            |name| {
+                // Names leaving `dir` are not copied, so the output stays inside it.
+                if !Path::new(name)
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+                {
+                    return Ok(());
+                }
+                // Libraries are resolved relative to the file referring to them.
+                let (source, target) = (path.with_file_name(name), dir.join(name));
+                if source.is_file() && source.canonicalize()? != target.canonicalize().unwrap_or_default() {
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::copy(&source, &target)?;
+                }
+                Ok(())
+            },
+            |part| {
+                let path = dir.join(format!("{}_{}.obj", stem, paths.len()));
+                part.write_to_buf(&mut BufWriter::new(File::create(&path)?))?;
+                paths.push(path);
+                Ok(())
+            },
+        )?;
+        Ok(paths)
+    }
+
+    /// The distinct materials used by the groups of `part`, looking up references by name in
+    /// this data, or `None` if there are none.
+    fn used_materials(&self, part: &ObjData) -> Option<Vec<Arc<Material>>> {
+        let mut materials: Vec<Arc<Material>> = Vec::new();
+        for group in part.objects.iter().flat_map(|o| &o.groups) {
+            let material = match &group.material {
+                Some(ObjMaterial::Mtl(material)) => Some(material),
+                Some(ObjMaterial::Ref(name)) => self.material(name),
+                None => None,
+            };
+            if let Some(material) = material {
+                if materials.iter().all(|m| m.name != material.name) {
+                    materials.push(material.clone());
+                }
+            }
+        }
+        Some(materials).filter(|m| !m.is_empty())
+    }
+
+    /// Split this mesh into one `ObjData` per material.
+    ///
+    /// Each part contains the objects and groups using one material, with attribute arrays
+    /// compacted to the entries its polygons reference. Material libraries are shared by all
+    /// parts. Groups without a material are collected in a part keyed by `None`. Polygons and
+    /// lines referring to missing attributes, which [`ObjData::validate_indices`] reports, are
+    /// left out.
+    ///
+    /// Parts are returned in the order in which their material is first used.
+    pub fn split_by_material(&self) -> Vec<(Option<ObjMaterial>, ObjData)> {
+        let mut parts: Vec<(Option<ObjMaterial>, SubsetBuilder)> = Vec::new();
+        for object in &self.objects {
+            for group in &object.groups {
+                let name = group.material.as_ref().map(|m| m.name());
+                let part = match parts
+                    .iter()
+                    .position(|(material, _)| material.as_ref().map(|m| m.name()) == name)
+                {
+                    Some(part) => part,
+                    None => {
+                        parts.push((group.material.clone(), SubsetBuilder::new(self)));
+                        parts.len() - 1
+                    }
+                };
+                parts[part].1.push_group(&object.name, group);
+            }
+        }
+        parts
+            .into_iter()
+            .map(|(material, builder)| (material, builder.finish()))
+            .collect()
+    }
+
+    /// Split this mesh into one `ObjData` per connected component.
+    ///
+    /// Polygons and lines are connected if they share a position index, so shells touching only
+    /// through coincident but distinct vertices end up in different parts; see
+    /// [`ObjData::weld_vertices`]. Each part keeps the object and group structure of its elements,
+    /// with attribute arrays compacted to the entries they reference. Polygons and lines referring
+    /// to missing attributes, which [`ObjData::validate_indices`] reports, are left out.
+    ///
+    /// Parts are returned in order of their first element.
+    pub fn split_connected_components(&self) -> Vec<ObjData> {
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        let mut parent: Vec<usize> = (0..self.position.len()).collect();
+        for tuples in self.element_tuples().filter(|tuples| self.has_attributes(tuples)) {
+            for pair in tuples.windows(2) {
+                let (a, b) = (find(&mut parent, pair[0].position), find(&mut parent, pair[1].position));
+                parent[a] = b;
+            }
+        }
+
+        // Number the components in order of first use.
+        let mut numbers: HashMap<usize, usize> = HashMap::new();
+        let mut component = |tuples: &[IndexTuple]| {
+            if !self.has_attributes(tuples) {
+                return None;
+            }
+            let root = find(&mut parent, tuples.first()?.position);
+            let next = numbers.len();
+            Some(*numbers.entry(root).or_insert(next))
+        };
+
+        let mut parts: Vec<SubsetBuilder> = Vec::new();
+        for object in &self.objects {
+            for group in &object.groups {
+                let polys: Vec<_> = group.polys.iter().map(|poly| component(&poly.0)).collect();
+                let lines: Vec<_> = group.lines.iter().map(|line| component(&line.0)).collect();
+                let mut order: Vec<usize> = polys.iter().chain(&lines).flatten().copied().collect();
+                order.sort_unstable();
+                order.dedup();
+                for c in order {
+                    while parts.len() <= c {
+                        parts.push(SubsetBuilder::new(self));
+                    }
+                    parts[c].push_group_where(&object.name, group, |i| polys[i] == Some(c), |i| lines[i] == Some(c));
+                }
+            }
+        }
+        parts.into_iter().map(SubsetBuilder::finish).collect()
+    }
+}
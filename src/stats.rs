@@ -0,0 +1,236 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Summary statistics of a loaded mesh.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+
+use crate::bounds::BoundingBox;
+use crate::obj::{IndexTuple, ObjData, ObjError};
+use crate::raw::Statement;
+
+/// The keywords of vertex attributes, polygons, lines and their organization, which are not
+/// reported as [`ScanReport::extensions`].
+const CORE_KEYWORDS: [&str; 10] = ["v", "vt", "vn", "f", "l", "o", "g", "s", "usemtl", "mtllib"];
+
+/// Element counts and index usage of an `ObjData`, as returned by [`ObjData::stats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MeshStats {
+    /// Number of `v` entries.
+    pub positions: usize,
+    /// Number of `vt` entries.
+    pub texture_coords: usize,
+    /// Number of `vn` entries.
+    pub normals: usize,
+    /// Number of polygons with three vertices.
+    pub triangles: usize,
+    /// Number of polygons with four vertices.
+    pub quads: usize,
+    /// Number of polygons with more than four vertices.
+    pub ngons: usize,
+    /// Number of polygons with fewer than three vertices.
+    pub degenerate_polygons: usize,
+    /// Number of `l` polylines.
+    pub lines: usize,
+    pub objects: usize,
+    pub groups: usize,
+    /// Number of distinct material names assigned with `usemtl`.
+    pub materials: usize,
+    /// Number of `mtllib` references.
+    pub material_libs: usize,
+    /// Smallest and largest position index referenced by any polygon or line.
+    pub position_index_range: Option<(usize, usize)>,
+    /// Smallest and largest texture coordinate index referenced by any polygon or line.
+    pub texture_index_range: Option<(usize, usize)>,
+    /// Smallest and largest normal index referenced by any polygon.
+    pub normal_index_range: Option<(usize, usize)>,
+}
+
+impl MeshStats {
+    /// Total number of polygons.
+    pub fn polygons(&self) -> usize {
+        self.triangles + self.quads + self.ngons + self.degenerate_polygons
+    }
+}
+
+/// Counts and extent of .obj source, as returned by [`scan`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScanReport {
+    /// Number of `v` statements.
+    pub positions: usize,
+    /// Number of `vt` statements.
+    pub texture_coords: usize,
+    /// Number of `vn` statements.
+    pub normals: usize,
+    /// Number of `f` statements.
+    pub polygons: usize,
+    /// Number of `l` statements.
+    pub lines: usize,
+    /// Number of `o` statements.
+    pub objects: usize,
+    /// Number of distinct group names given to `g`.
+    pub groups: usize,
+    /// Number of distinct material names given to `usemtl`.
+    pub materials: usize,
+    /// The file names given to `mtllib`, in order.
+    pub material_libs: Vec<String>,
+    /// The bounding box of all positions, or `None` if there are none.
+    pub bounding_box: Option<BoundingBox>,
+    /// Whether any position has a vertex color.
+    pub vertex_colors: bool,
+    /// The keywords of the statements besides attributes, polygons, lines, objects, groups,
+    /// smoothing groups and materials, such as free-form geometry, render attributes or
+    /// third-party extensions, sorted.
+    pub extensions: Vec<String>,
+}
+
+/// Count the statements of .obj source and find the extent of its positions, without keeping
+/// the data.
+///
+/// This reads the source once, line by line, so it takes little memory even for huge files.
+/// Only the numbers of positions are parsed, so errors in other statements aren't found.
+pub fn scan(input: impl Read) -> Result<ScanReport, ObjError> {
+    let mut report = ScanReport::default();
+    let mut groups = HashSet::new();
+    let mut materials = HashSet::new();
+    let mut extensions = BTreeSet::new();
+    for (line_number, line) in BufReader::new(input).lines().enumerate() {
+        let line = line?;
+        let statement = match Statement::parse(line_number, &line) {
+            Some(statement) => statement,
+            None => continue,
+        };
+        match statement.keyword() {
+            "v" => {
+                let mut args = statement.args();
+                let position = args.vec3()?;
+                match &mut report.bounding_box {
+                    Some(bbox) => bbox.insert(position),
+                    None => {
+                        report.bounding_box = Some(BoundingBox {
+                            min: position,
+                            max: position,
+                        })
+                    }
+                }
+                report.vertex_colors |= args.count() >= 2;
+                report.positions += 1;
+            }
+            "vt" => report.texture_coords += 1,
+            "vn" => report.normals += 1,
+            "f" => report.polygons += 1,
+            "l" => report.lines += 1,
+            "o" => report.objects += 1,
+            "g" => {
+                groups.extend(statement.args().map(str::to_string));
+            }
+            "usemtl" => {
+                materials.extend(statement.args().next().map(str::to_string));
+            }
+            "mtllib" => report.material_libs.push(statement.rest().to_string()),
+            keyword if statement.is_comment() || CORE_KEYWORDS.contains(&keyword) => (),
+            keyword => {
+                if !extensions.contains(keyword) {
+                    extensions.insert(keyword.to_string());
+                }
+            }
+        }
+    }
+    report.groups = groups.len();
+    report.materials = materials.len();
+    report.extensions = extensions.into_iter().collect();
+    Ok(report)
+}
+
+fn extend_range(range: &mut Option<(usize, usize)>, idx: usize) {
+    *range = Some(match *range {
+        Some((lo, hi)) => (lo.min(idx), hi.max(idx)),
+        None => (idx, idx),
+    });
+}
+
+impl fmt::Display for MeshStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "vertices: {} positions, {} texture coordinates, {} normals",
+            self.positions, self.texture_coords, self.normals
+        )?;
+        writeln!(
+            f,
+            "polygons: {} ({} triangles, {} quads, {} n-gons, {} degenerate)",
+            self.polygons(),
+            self.triangles,
+            self.quads,
+            self.ngons,
+            self.degenerate_polygons
+        )?;
+        writeln!(f, "lines: {}", self.lines)?;
+        write!(
+            f,
+            "{} objects, {} groups, {} materials, {} material libraries",
+            self.objects, self.groups, self.materials, self.material_libs
+        )
+    }
+}
+
+impl ObjData {
+    /// Compute element counts and index usage for this mesh.
+    pub fn stats(&self) -> MeshStats {
+        let mut stats = MeshStats {
+            positions: self.position.len(),
+            texture_coords: self.texture.len(),
+            normals: self.normal.len(),
+            objects: self.objects.len(),
+            material_libs: self.material_libs.len(),
+            ..MeshStats::default()
+        };
+
+        let mut materials = HashSet::new();
+        for group in self.objects.iter().flat_map(|o| o.groups.iter()) {
+            stats.groups += 1;
+            if let Some(material) = &group.material {
+                materials.insert(material.name());
+            }
+            for poly in &group.polys {
+                match poly.0.len() {
+                    0..=2 => stats.degenerate_polygons += 1,
+                    3 => stats.triangles += 1,
+                    4 => stats.quads += 1,
+                    _ => stats.ngons += 1,
+                }
+            }
+            stats.lines += group.lines.len();
+        }
+        for &IndexTuple {
+            position: p,
+            texture: t,
+            normal: n,
+        } in self.index_tuples()
+        {
+            extend_range(&mut stats.position_index_range, p);
+            if let Some(t) = t {
+                extend_range(&mut stats.texture_index_range, t);
+            }
+            if let Some(n) = n {
+                extend_range(&mut stats.normal_index_range, n);
+            }
+        }
+        stats.materials = materials.len();
+
+        stats
+    }
+}
@@ -0,0 +1,303 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Reading and writing STL files.
+//!
+//! Both the binary and the ASCII flavor are supported. STL only stores triangles with a facet
+//! normal each, so loading yields an `ObjData` with positions and one normal per triangle and
+//! writing requires every polygon to be a triangle, see [`ObjData::to_triangles`].
+//!
+//! ```
+//! use obj::{stl, ObjData};
+//!
+//! let data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).unwrap();
+//! let mut buf = Vec::new();
+//! stl::write(&data, &mut buf, stl::StlFormat::Binary).unwrap();
+//! let loaded = stl::load_buf(&buf[..]).unwrap();
+//! assert_eq!(loaded.position, data.position);
+//! assert_eq!(loaded.normal, vec![[0.0, 0.0, 1.0]]);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::math::{newell_normal, normalize, Vec3};
+use crate::obj::{Group, IndexTuple, ObjData, Object, SimplePolygon, DEFAULT_GROUP, DEFAULT_OBJECT};
+
+const HEADER_LEN: usize = 80;
+const TRIANGLE_LEN: usize = 50;
+
+/// The flavor of STL to write.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StlFormat {
+    /// The compact little-endian binary format.
+    Binary,
+    /// The human readable text format.
+    Ascii,
+}
+
+/// Errors reading or writing an STL file.
+#[derive(Debug)]
+pub enum StlError {
+    Io(io::Error),
+    /// A line of an ASCII STL file could not be parsed.
+    Malformed {
+        line_number: usize,
+        line: String,
+    },
+    /// A binary STL file holds fewer triangles than its header announces.
+    UnexpectedEof,
+    /// Only triangles can be written, but the polygon with the given index has a different
+    /// number of vertices. Polygons are numbered consecutively across all groups of all objects.
+    NotTriangulated {
+        polygon: usize,
+    },
+}
+
+impl std::error::Error for StlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StlError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StlError::Io(err) => write!(f, "I/O error reading or writing an STL file: {}", err),
+            StlError::Malformed { line_number, line } => {
+                write!(
+                    f,
+                    "Malformed ASCII STL statement. (line: {}, statement: {})",
+                    line_number, line
+                )
+            }
+            StlError::UnexpectedEof => write!(f, "Binary STL file ended before all triangles were read."),
+            StlError::NotTriangulated { polygon } => {
+                write!(f, "STL only supports triangles. (polygon: {})", polygon)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for StlError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Load an STL file from the given path, detecting whether it is binary or ASCII.
+pub fn load(path: impl AsRef<Path>) -> Result<ObjData, StlError> {
+    load_buf(File::open(path)?)
+}
+
+/// Load an STL file from a reader, detecting whether it is binary or ASCII.
+///
+/// Bitwise identical positions are shared, while every triangle gets its own normal. The stored
+/// facet normal is used if it is valid, otherwise the normal is computed from the triangle's
+/// winding. Degenerate triangles without either get no normal.
+pub fn load_buf<R: Read>(mut input: R) -> Result<ObjData, StlError> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    // Binary files may start with "solid" too, so the announced size takes precedence.
+    let binary_size = bytes.get(HEADER_LEN..HEADER_LEN + 4).map(|count| {
+        HEADER_LEN + 4 + TRIANGLE_LEN * u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize
+    });
+    let is_ascii = bytes.trim_ascii_start().starts_with(b"solid") && binary_size != Some(bytes.len());
+    if is_ascii {
+        load_ascii(&String::from_utf8_lossy(&bytes))
+    } else {
+        load_binary(&bytes)
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    data: ObjData,
+    positions: HashMap<[u32; 3], usize>,
+}
+
+impl Builder {
+    fn begin_object(&mut self, name: &str) {
+        let name = if name.is_empty() { DEFAULT_OBJECT } else { name };
+        let mut object = Object::new(name.to_string());
+        object.groups.push(Group::new(DEFAULT_GROUP.to_string()));
+        self.data.objects.push(object);
+    }
+
+    fn push_triangle(&mut self, normal: Vec3, vertices: [Vec3; 3]) {
+        if self.data.objects.is_empty() {
+            self.begin_object("");
+        }
+        let normal = normalize(normal)
+            .or_else(|| normalize(newell_normal(&vertices)))
+            .map(|n| {
+                self.data.normal.push(n);
+                self.data.normal.len() - 1
+            });
+        let ObjData { position, objects, .. } = &mut self.data;
+        let positions = &mut self.positions;
+        let poly = vertices
+            .iter()
+            .map(|&v| {
+                let key = [v[0].to_bits(), v[1].to_bits(), v[2].to_bits()];
+                let index = *positions.entry(key).or_insert_with(|| {
+                    position.push(v);
+                    position.len() - 1
+                });
+                IndexTuple::new(index, None, normal)
+            })
+            .collect();
+        let group = &mut objects.last_mut().unwrap().groups[0];
+        group.polys.push(SimplePolygon(poly));
+    }
+}
+
+fn load_binary(bytes: &[u8]) -> Result<ObjData, StlError> {
+    let count = bytes.get(HEADER_LEN..HEADER_LEN + 4).ok_or(StlError::UnexpectedEof)?;
+    let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize;
+    let triangles = &bytes[HEADER_LEN + 4..];
+    if triangles.len() / TRIANGLE_LEN < count {
+        return Err(StlError::UnexpectedEof);
+    }
+
+    let mut builder = Builder::default();
+    builder.begin_object("");
+    for chunk in triangles.chunks_exact(TRIANGLE_LEN).take(count) {
+        let float = |i: usize| f32::from_le_bytes([chunk[i * 4], chunk[i * 4 + 1], chunk[i * 4 + 2], chunk[i * 4 + 3]]);
+        let vec = |i: usize| [float(i), float(i + 1), float(i + 2)];
+        builder.push_triangle(vec(0), [vec(3), vec(6), vec(9)]);
+    }
+    Ok(builder.data)
+}
+
+fn load_ascii(text: &str) -> Result<ObjData, StlError> {
+    let mut builder = Builder::default();
+    let mut normal = [0.0; 3];
+    let mut vertices = Vec::with_capacity(3);
+
+    for (idx, line) in text.lines().enumerate() {
+        let malformed = || StlError::Malformed {
+            line_number: idx + 1,
+            line: line.to_string(),
+        };
+        let parse_vec = |args: &[&str]| -> Result<Vec3, StlError> {
+            match args {
+                [x, y, z] => match (x.parse(), y.parse(), z.parse()) {
+                    (Ok(x), Ok(y), Ok(z)) => Ok([x, y, z]),
+                    _ => Err(malformed()),
+                },
+                _ => Err(malformed()),
+            }
+        };
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            ["solid", name @ ..] => builder.begin_object(&name.join(" ")),
+            ["facet", "normal", args @ ..] => {
+                normal = parse_vec(args)?;
+                vertices.clear();
+            }
+            ["outer", "loop"] | ["endloop"] => {}
+            ["vertex", args @ ..] => vertices.push(parse_vec(args)?),
+            ["endfacet"] => {
+                if vertices.len() != 3 {
+                    return Err(malformed());
+                }
+                builder.push_triangle(normal, [vertices[0], vertices[1], vertices[2]]);
+                vertices.clear();
+            }
+            ["endsolid", ..] => {}
+            _ => return Err(malformed()),
+        }
+    }
+    Ok(builder.data)
+}
+
+/// Save the data as an STL file in the given format.
+pub fn save(data: &ObjData, path: impl AsRef<Path>, format: StlFormat) -> Result<(), StlError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write(data, &mut out, format)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Write the data as an STL file in the given format.
+///
+/// Facet normals are computed from the triangles' winding. In ASCII files every object becomes a
+/// separate solid, binary files hold all triangles in one list. Lines are not written.
+pub fn write(data: &ObjData, out: &mut impl Write, format: StlFormat) -> Result<(), StlError> {
+    let mut triangles = Vec::new();
+    let mut polygon = 0;
+    for (o, object) in data.objects.iter().enumerate() {
+        for group in &object.groups {
+            for poly in &group.polys {
+                match poly.0.as_slice() {
+                    [a, b, c] => triangles.push((
+                        o,
+                        [
+                            data.position[a.position],
+                            data.position[b.position],
+                            data.position[c.position],
+                        ],
+                    )),
+                    _ => return Err(StlError::NotTriangulated { polygon }),
+                }
+                polygon += 1;
+            }
+        }
+    }
+    let normal = |vertices: &[Vec3; 3]| normalize(newell_normal(vertices)).unwrap_or([0.0; 3]);
+
+    match format {
+        StlFormat::Binary => {
+            let mut header = [0u8; HEADER_LEN];
+            let title = b"binary STL";
+            header[..title.len()].copy_from_slice(title);
+            out.write_all(&header)?;
+            out.write_all(&(triangles.len() as u32).to_le_bytes())?;
+            for (_, vertices) in &triangles {
+                for v in std::iter::once(normal(vertices)).chain(vertices.iter().cloned()) {
+                    for c in &v {
+                        out.write_all(&c.to_le_bytes())?;
+                    }
+                }
+                out.write_all(&[0, 0])?;
+            }
+        }
+        StlFormat::Ascii => {
+            for (o, object) in data.objects.iter().enumerate() {
+                writeln!(out, "solid {}", object.name)?;
+                for (_, vertices) in triangles.iter().filter(|&&(t, _)| t == o) {
+                    let [nx, ny, nz] = normal(vertices);
+                    writeln!(out, "  facet normal {} {} {}", nx, ny, nz)?;
+                    writeln!(out, "    outer loop")?;
+                    for [x, y, z] in vertices {
+                        writeln!(out, "      vertex {} {} {}", x, y, z)?;
+                    }
+                    writeln!(out, "    endloop")?;
+                    writeln!(out, "  endfacet")?;
+                }
+                writeln!(out, "endsolid {}", object.name)?;
+            }
+        }
+    }
+    Ok(())
+}
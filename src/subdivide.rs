@@ -0,0 +1,295 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Subdivision surface refinement.
+
+use std::collections::HashMap;
+
+use crate::buffers::fan;
+use crate::math::{add, scale, Vec3};
+use crate::obj::{IndexTuple, ObjData, SimplePolygon};
+use crate::quads::ear_clip;
+
+/// The refinement rules used by [`ObjData::subdivide`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SubdivisionScheme {
+    /// Loop subdivision, splitting every triangle into four. Polygons with more vertices are
+    /// triangulated first.
+    Loop,
+    /// Catmull-Clark subdivision, splitting every polygon into one quad per vertex.
+    CatmullClark,
+}
+
+#[derive(Default)]
+struct Edge {
+    /// The polygons containing the edge.
+    faces: Vec<usize>,
+    /// For each polygon, the vertex opposite to the edge. Only used for triangles.
+    opposite: Vec<usize>,
+}
+
+fn average(points: impl Iterator<Item = Vec3>) -> Vec3 {
+    let (sum, n) = points.fold(([0.0; 3], 0), |(sum, n), p| (add(sum, p), n + 1));
+    scale(sum, 1.0 / n.max(1) as f32)
+}
+
+fn midpoint<const N: usize>(a: [f32; N], b: [f32; N]) -> [f32; N] {
+    let mut out = a;
+    for (x, y) in out.iter_mut().zip(&b) {
+        *x = (*x + y) * 0.5;
+    }
+    out
+}
+
+fn edge_key(a: usize, b: usize) -> [usize; 2] {
+    [a.min(b), a.max(b)]
+}
+
+/// Apply one level of refinement to the given polygons, which must have at least three
+/// vertices, returning the refined polygons.
+///
+/// New positions and texture coordinates are appended to `data`, while existing vertices are
+/// moved in place to their smoothed location.
+fn refine(data: &mut ObjData, faces: &[Vec<IndexTuple>], scheme: SubdivisionScheme) -> Vec<Vec<Vec<IndexTuple>>> {
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut edge_index: HashMap<[usize; 2], usize> = HashMap::new();
+    let mut vertex_edges: Vec<Vec<usize>> = vec![Vec::new(); data.position.len()];
+    let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); data.position.len()];
+    for (f, face) in faces.iter().enumerate() {
+        let n = face.len();
+        for k in 0..n {
+            let (a, b) = (face[k].position, face[(k + 1) % n].position);
+            let e = *edge_index.entry(edge_key(a, b)).or_insert_with(|| {
+                edges.push(Edge::default());
+                vertex_edges[a].push(edges.len() - 1);
+                vertex_edges[b].push(edges.len() - 1);
+                edges.len() - 1
+            });
+            edges[e].faces.push(f);
+            edges[e].opposite.push(face[(k + 2) % n].position);
+            vertex_faces[a].push(f);
+        }
+    }
+    let endpoints: Vec<[usize; 2]> = {
+        let mut endpoints = vec![[0; 2]; edges.len()];
+        for (&key, &e) in &edge_index {
+            endpoints[e] = key;
+        }
+        endpoints
+    };
+    let position = &data.position;
+    let face_points: Vec<Vec3> = faces
+        .iter()
+        .map(|face| average(face.iter().map(|t| position[t.position])))
+        .collect();
+
+    let edge_points: Vec<Vec3> = edges
+        .iter()
+        .zip(&endpoints)
+        .map(|(edge, &[a, b])| {
+            let mid = midpoint(position[a], position[b]);
+            if edge.faces.len() != 2 {
+                return mid;
+            }
+            match scheme {
+                SubdivisionScheme::CatmullClark => {
+                    let faces = midpoint(face_points[edge.faces[0]], face_points[edge.faces[1]]);
+                    midpoint(mid, faces)
+                }
+                SubdivisionScheme::Loop => {
+                    let opposite = midpoint(position[edge.opposite[0]], position[edge.opposite[1]]);
+                    add(scale(mid, 0.75), scale(opposite, 0.25))
+                }
+            }
+        })
+        .collect();
+
+    let vertex_points: Vec<Vec3> = (0..position.len())
+        .map(|v| {
+            let p = position[v];
+            let other = |e: usize| {
+                let [a, b] = endpoints[e];
+                if a == v {
+                    b
+                } else {
+                    a
+                }
+            };
+            let boundary: Vec<usize> = vertex_edges[v]
+                .iter()
+                .copied()
+                .filter(|&e| edges[e].faces.len() != 2)
+                .collect();
+            match boundary.len() {
+                0 if !vertex_edges[v].is_empty() => {
+                    let n = vertex_edges[v].len() as f32;
+                    match scheme {
+                        SubdivisionScheme::CatmullClark => {
+                            let f = average(vertex_faces[v].iter().map(|&f| face_points[f]));
+                            let r = average(vertex_edges[v].iter().map(|&e| midpoint(p, position[other(e)])));
+                            scale(add(add(f, scale(r, 2.0)), scale(p, n - 3.0)), 1.0 / n)
+                        }
+                        SubdivisionScheme::Loop => {
+                            let beta = if n == 3.0 { 3.0 / 16.0 } else { 3.0 / (8.0 * n) };
+                            let sum = vertex_edges[v]
+                                .iter()
+                                .fold([0.0; 3], |sum, &e| add(sum, position[other(e)]));
+                            add(scale(p, 1.0 - n * beta), scale(sum, beta))
+                        }
+                    }
+                }
+                2 => {
+                    let neighbours = midpoint(position[other(boundary[0])], position[other(boundary[1])]);
+                    add(scale(p, 0.75), scale(neighbours, 0.25))
+                }
+                // Corners and non-manifold vertices stay in place.
+                _ => p,
+            }
+        })
+        .collect();
+
+    // Vertex colors are interpolated linearly.
+    if !data.color.is_empty() {
+        let color = &data.color;
+        let edge_colors: Vec<Vec3> = endpoints.iter().map(|&[a, b]| midpoint(color[a], color[b])).collect();
+        let face_colors: Vec<Vec3> = match scheme {
+            SubdivisionScheme::CatmullClark => faces
+                .iter()
+                .map(|face| average(face.iter().map(|t| color[t.position])))
+                .collect(),
+            SubdivisionScheme::Loop => Vec::new(),
+        };
+        data.color.extend(edge_colors);
+        data.color.extend(face_colors);
+    }
+
+    let edge_base = data.position.len();
+    data.position = vertex_points;
+    data.position.extend(edge_points);
+    let face_base = data.position.len();
+    if scheme == SubdivisionScheme::CatmullClark {
+        data.position.extend(face_points);
+    }
+
+    // Texture coordinates are interpolated linearly, keeping seams intact.
+    let mut edge_uvs: HashMap<[usize; 2], usize> = HashMap::new();
+    let mut edge_uv = |data: &mut ObjData, a: Option<usize>, b: Option<usize>| {
+        let (a, b) = (a?, b?);
+        Some(*edge_uvs.entry(edge_key(a, b)).or_insert_with(|| {
+            data.texture.push(midpoint(data.texture[a], data.texture[b]));
+            data.texture.len() - 1
+        }))
+    };
+
+    let mut refined = Vec::with_capacity(faces.len());
+    for (f, face) in faces.iter().enumerate() {
+        let n = face.len();
+        let mids: Vec<IndexTuple> = (0..n)
+            .map(|k| {
+                let (a, b) = (face[k], face[(k + 1) % n]);
+                IndexTuple::new(
+                    edge_base + edge_index[&edge_key(a.position, b.position)],
+                    edge_uv(data, a.texture, b.texture),
+                    None,
+                )
+            })
+            .collect();
+        let corner = |k: usize| IndexTuple::new(face[k].position, face[k].texture, None);
+        refined.push(match scheme {
+            SubdivisionScheme::CatmullClark => {
+                let uvs: Option<Vec<_>> = face.iter().map(|c| c.texture.map(|t| data.texture[t])).collect();
+                let center_uv = uvs.map(|uvs| {
+                    let sum = uvs.iter().fold([0.0; 2], |sum, uv| [sum[0] + uv[0], sum[1] + uv[1]]);
+                    data.texture.push([sum[0] / n as f32, sum[1] / n as f32]);
+                    data.texture.len() - 1
+                });
+                let center = IndexTuple::new(face_base + f, center_uv, None);
+                (0..n)
+                    .map(|k| vec![corner(k), mids[k], center, mids[(k + n - 1) % n]])
+                    .collect()
+            }
+            SubdivisionScheme::Loop => vec![
+                vec![corner(0), mids[0], mids[2]],
+                vec![corner(1), mids[1], mids[0]],
+                vec![corner(2), mids[2], mids[1]],
+                vec![mids[0], mids[1], mids[2]],
+            ],
+        });
+    }
+    refined
+}
+
+impl ObjData {
+    /// Refine all polygons `levels` times using the given subdivision scheme.
+    ///
+    /// Mesh boundaries are kept as creases, and texture coordinates are interpolated linearly.
+    /// Afterwards, the normals are replaced by smooth normals, one per position. Existing
+    /// positions keep their index, so lines stay attached to the mesh. Polygons with fewer
+    /// than three vertices or referring to missing attributes, which
+    /// [`ObjData::validate_indices`] reports, are left unchanged.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn subdivide(&mut self, scheme: SubdivisionScheme, levels: usize) {
+        if levels == 0 {
+            return;
+        }
+        for _ in 0..levels {
+            // The polygons to refine, with the number of them coming from each polygon of each group,
+            // or `None` for polygons that are kept as they are.
+            let mut faces = Vec::new();
+            let mut counts: Vec<Vec<Option<usize>>> = Vec::new();
+            for group in self.objects.iter().flat_map(|o| &o.groups) {
+                let mut group_counts = Vec::with_capacity(group.polys.len());
+                for poly in &group.polys {
+                    let start = faces.len();
+                    match (scheme, poly.0.len()) {
+                        (_, 0..=2) => {
+                            group_counts.push(None);
+                            continue;
+                        }
+                        _ if !self.has_attributes(&poly.0) => {
+                            group_counts.push(None);
+                            continue;
+                        }
+                        (SubdivisionScheme::Loop, 3) | (SubdivisionScheme::CatmullClark, _) => {
+                            faces.push(poly.0.to_vec())
+                        }
+                        (SubdivisionScheme::Loop, _) => match ear_clip(poly, &self.position) {
+                            Some(tris) => faces.extend(tris.iter().map(|t| t.to_vec())),
+                            None => faces.extend(fan(poly).map(|t| t.to_vec())),
+                        },
+                    }
+                    group_counts.push(Some(faces.len() - start));
+                }
+                counts.push(group_counts);
+            }
+
+            let mut refined = refine(self, &faces, scheme).into_iter();
+            for (group, counts) in self.objects.iter_mut().flat_map(|o| o.groups.iter_mut()).zip(counts) {
+                let mut counts = counts.into_iter();
+                group.map_polys(|poly, polys| match counts.next().flatten() {
+                    Some(count) => {
+                        for children in refined.by_ref().take(count) {
+                            polys.extend(children.into_iter().map(SimplePolygon::from));
+                        }
+                    }
+                    None => polys.push(poly),
+                });
+            }
+        }
+        self.normal = self.smooth_normals();
+        for tuple in self.polys_mut().flat_map(|poly| poly.0.iter_mut()) {
+            tuple.normal = Some(tuple.position);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Tangent space generation for normal-mapped rendering.
+
+use crate::math::{add, angle_between, any_perpendicular, cross, dot, normalize, scale, sub};
+
+/// Generate per-vertex tangents for an indexed triangle mesh.
+///
+/// The inputs describe one vertex per entry of `positions`, `normals` and `texcoords`, and
+/// `indices` lists three vertices per triangle, which is the layout produced when building
+/// indexed vertex buffers for GPU upload.
+///
+/// The result is one `[x, y, z, w]` tangent per vertex, orthogonalized against the vertex normal,
+/// where `w` is `1.0` or `-1.0` and the bitangent is reconstructed in the shader as
+/// `w * cross(normal, tangent)`. The tangents of the faces around a vertex are accumulated,
+/// weighted by the corner angle, so the result does not depend on how polygons were triangulated.
+///
+/// This uses the same encoding as MikkTSpace, but not its algorithm, so tangents differ slightly
+/// from those of tools baking normal maps with MikkTSpace. Use `generate_mikktspace_tangents` with
+/// the `mikktspace` feature to match them exactly.
+///
+/// Vertices without a usable texture mapping receive an arbitrary tangent perpendicular to the
+/// normal.
+///
+/// # Panics
+///
+/// This function will panic if the attribute slices have different lengths or if an index is out
+/// of bounds.
+pub fn generate_tangents(
+    positions: &[[f32; 3]],
+    normals: &[[f32; 3]],
+    texcoords: &[[f32; 2]],
+    indices: &[u32],
+) -> Vec<[f32; 4]> {
+    assert_eq!(positions.len(), normals.len(), "each vertex requires a normal");
+    assert_eq!(
+        positions.len(),
+        texcoords.len(),
+        "each vertex requires a texture coordinate"
+    );
+
+    let mut tangents = vec![[0.0f32; 3]; positions.len()];
+    let mut bitangents = vec![[0.0f32; 3]; positions.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let idx = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        let [p0, p1, p2] = [positions[idx[0]], positions[idx[1]], positions[idx[2]]];
+        let [t0, t1, t2] = [texcoords[idx[0]], texcoords[idx[1]], texcoords[idx[2]]];
+
+        let e1 = sub(p1, p0);
+        let e2 = sub(p2, p0);
+        let (du1, dv1) = (t1[0] - t0[0], t1[1] - t0[1]);
+        let (du2, dv2) = (t2[0] - t0[0], t2[1] - t0[1]);
+
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() <= f32::EPSILON {
+            // Degenerate texture mapping, this face carries no tangent information.
+            continue;
+        }
+        let r = 1.0 / det;
+        let sdir = scale(sub(scale(e1, dv2), scale(e2, dv1)), r);
+        let tdir = scale(sub(scale(e2, du1), scale(e1, du2)), r);
+
+        let corners = [(p0, p1, p2), (p1, p2, p0), (p2, p0, p1)];
+        for (&vtx, &(p, next, prev)) in idx.iter().zip(corners.iter()) {
+            let weight = angle_between(sub(next, p), sub(prev, p));
+            let n = normals[vtx];
+            // Project the face tangent into the tangent plane of the vertex before accumulating.
+            let t = sub(sdir, scale(n, dot(n, sdir)));
+            let t = normalize(t).unwrap_or(t);
+            tangents[vtx] = add(tangents[vtx], scale(t, weight));
+            bitangents[vtx] = add(bitangents[vtx], scale(tdir, weight));
+        }
+    }
+
+    tangents
+        .iter()
+        .zip(bitangents.iter())
+        .zip(normals.iter())
+        .map(|((&t, &b), &n)| {
+            let n = normalize(n).unwrap_or([0.0, 0.0, 1.0]);
+            // Gram-Schmidt orthogonalize.
+            let t = normalize(sub(t, scale(n, dot(n, t)))).unwrap_or_else(|| any_perpendicular(n));
+            let w = if dot(cross(n, t), b) < 0.0 { -1.0 } else { 1.0 };
+            [t[0], t[1], t[2], w]
+        })
+        .collect()
+}
+
+/// Generate per-vertex tangents for an indexed triangle mesh with the MikkTSpace algorithm.
+///
+/// Takes the same inputs and returns the same layout as [`generate_tangents`], but produces the
+/// tangents of the reference MikkTSpace implementation, which normal maps baked by most tools
+/// expect. Corners of different faces sharing a vertex get the tangent of the last face, so
+/// vertices should be split where MikkTSpace would split their tangent space, e.g. at mirrored
+/// texture mappings. Vertices without a usable texture mapping receive an arbitrary tangent
+/// perpendicular to the normal.
+///
+/// # Panics
+///
+/// This function will panic if the attribute slices have different lengths or if an index is out
+/// of bounds.
+#[cfg(feature = "mikktspace")]
+pub fn generate_mikktspace_tangents(
+    positions: &[[f32; 3]],
+    normals: &[[f32; 3]],
+    texcoords: &[[f32; 2]],
+    indices: &[u32],
+) -> Vec<[f32; 4]> {
+    struct Mesh<'a> {
+        positions: &'a [[f32; 3]],
+        normals: &'a [[f32; 3]],
+        texcoords: &'a [[f32; 2]],
+        indices: &'a [u32],
+        tangents: Vec<Option<[f32; 4]>>,
+    }
+
+    impl Mesh<'_> {
+        fn vertex(&self, face: usize, vert: usize) -> usize {
+            self.indices[face * 3 + vert] as usize
+        }
+    }
+
+    impl bevy_mikktspace::Geometry for Mesh<'_> {
+        fn num_faces(&self) -> usize {
+            self.indices.len() / 3
+        }
+
+        fn num_vertices_of_face(&self, _face: usize) -> usize {
+            3
+        }
+
+        fn position(&self, face: usize, vert: usize) -> [f32; 3] {
+            self.positions[self.vertex(face, vert)]
+        }
+
+        fn normal(&self, face: usize, vert: usize) -> [f32; 3] {
+            self.normals[self.vertex(face, vert)]
+        }
+
+        fn tex_coord(&self, face: usize, vert: usize) -> [f32; 2] {
+            self.texcoords[self.vertex(face, vert)]
+        }
+
+        fn set_tangent(&mut self, tangent_space: Option<bevy_mikktspace::TangentSpace>, face: usize, vert: usize) {
+            if let Some(tangent_space) = tangent_space {
+                let vertex = self.vertex(face, vert);
+                self.tangents[vertex] = Some(tangent_space.tangent_encoded());
+            }
+        }
+    }
+
+    assert_eq!(positions.len(), normals.len(), "each vertex requires a normal");
+    assert_eq!(
+        positions.len(),
+        texcoords.len(),
+        "each vertex requires a texture coordinate"
+    );
+    let mut mesh = Mesh {
+        positions,
+        normals,
+        texcoords,
+        indices,
+        tangents: vec![None; positions.len()],
+    };
+    // Generating tangents has no failure cases yet.
+    let _ = bevy_mikktspace::generate_tangents(&mut mesh);
+
+    mesh.tangents
+        .iter()
+        .zip(normals)
+        .map(|(tangent, &n)| {
+            tangent.unwrap_or_else(|| {
+                let t = any_perpendicular(normalize(n).unwrap_or([0.0, 0.0, 1.0]));
+                [t[0], t[1], t[2], 1.0]
+            })
+        })
+        .collect()
+}
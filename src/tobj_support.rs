@@ -0,0 +1,290 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversions from and to the data structures of the [`tobj`] crate.
+//!
+//! Material properties `tobj` has no field for are kept in its `unknown_param` map.
+
+// `tobj` switches its floats to `f64` with its `use_f64` feature, so the casts are not always no-ops.
+#![allow(clippy::unnecessary_cast)]
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::mtl::{Material, Mtl};
+use crate::obj::{Group, IndexTuple, ObjData, ObjMaterial, Object, SimplePolygon, DEFAULT_GROUP, DEFAULT_OBJECT};
+
+fn parse_floats<const N: usize>(value: &str) -> Option<[f32; N]> {
+    let mut out = [0.0; N];
+    let mut words = value.split_whitespace();
+    for v in &mut out {
+        *v = f32::from_str(words.next()?).ok()?;
+    }
+    Some(out)
+}
+
+/// Append `f32` values to a `tobj` array, whose element type depends on its `use_f64` feature.
+fn extend_floats<T: From<f32>>(out: &mut Vec<T>, values: &[f32]) {
+    out.extend(values.iter().map(|&v| T::from(v)));
+}
+
+impl From<&tobj::Material> for Material {
+    fn from(m: &tobj::Material) -> Self {
+        let unknown = |key: &str| m.unknown_param.get(key);
+        let unknown_map = |keys: &[&str]| keys.iter().find_map(|&key| unknown(key)).cloned();
+        let three = |v: [_; 3]| [v[0] as f32, v[1] as f32, v[2] as f32];
+        Material {
+            ka: m.ambient.map(three),
+            kd: m.diffuse.map(three),
+            ks: m.specular.map(three),
+            ke: m.emissive.map(three),
+            km: unknown("Km").and_then(|v| parse_floats::<1>(v)).map(|[v]| v),
+            tf: unknown("Tf").and_then(|v| parse_floats(v)),
+            ns: m.shininess.map(|v| v as f32),
+            ni: m.optical_density.map(|v| v as f32),
+            tr: unknown("Tr").and_then(|v| parse_floats::<1>(v)).map(|[v]| v),
+            d: m.dissolve.map(|v| v as f32),
+            illum: m.illumination_model.map(i32::from),
+            map_ka: m.ambient_texture.clone(),
+            map_kd: m.diffuse_texture.clone(),
+            map_ks: m.specular_texture.clone(),
+            map_ke: unknown_map(&["map_Ke"]),
+            map_ns: m.shininess_texture.clone(),
+            map_d: m.dissolve_texture.clone(),
+            map_bump: m.normal_texture.clone(),
+            map_refl: unknown_map(&["map_refl", "refl"]),
+            map_disp: unknown_map(&["map_disp", "disp"]),
+            map_aat: unknown("map_aat").is_some_and(|v| v.trim() == "on"),
+            ..Material::new(m.name.clone())
+        }
+    }
+}
+
+impl From<&Material> for tobj::Material {
+    fn from(m: &Material) -> Self {
+        let three = |v: [f32; 3]| [v[0] as _, v[1] as _, v[2] as _];
+        let mut unknown_param = HashMap::new();
+        let mut extra = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                unknown_param.insert(key.to_string(), value);
+            }
+        };
+        extra("Km", m.km.map(|v| v.to_string()));
+        extra("Tf", m.tf.map(|v| format!("{} {} {}", v[0], v[1], v[2])));
+        extra("Tr", m.tr.map(|v| v.to_string()));
+        extra("map_Ke", m.map_ke.clone());
+        extra("map_refl", m.map_refl.clone());
+        extra("map_disp", m.map_disp.clone());
+        extra("map_aat", m.map_aat.then(|| "on".to_string()));
+        tobj::Material {
+            name: m.name.clone(),
+            ambient: m.ka.map(three),
+            diffuse: m.kd.map(three),
+            specular: m.ks.map(three),
+            emissive: m.ke.map(three),
+            shininess: m.ns.map(|v| v as _),
+            dissolve: m.d.map(|v| v as _),
+            optical_density: m.ni.map(|v| v as _),
+            ambient_texture: m.map_ka.clone(),
+            diffuse_texture: m.map_kd.clone(),
+            specular_texture: m.map_ks.clone(),
+            normal_texture: m.map_bump.clone(),
+            shininess_texture: m.map_ns.clone(),
+            dissolve_texture: m.map_d.clone(),
+            illumination_model: m.illum.and_then(|v| u8::try_from(v).ok()),
+            unknown_param: unknown_param.into_iter().collect(),
+        }
+    }
+}
+
+impl Mtl {
+    /// Build a material library named `filename` from materials loaded by `tobj`.
+    pub fn from_tobj(filename: String, materials: &[tobj::Material]) -> Self {
+        Mtl {
+            filename,
+            materials: materials.iter().map(|m| Arc::new(Material::from(m))).collect(),
+        }
+    }
+
+    /// Convert the materials of this library to `tobj` materials.
+    pub fn to_tobj(&self) -> Vec<tobj::Material> {
+        self.materials.iter().map(|m| tobj::Material::from(&**m)).collect()
+    }
+}
+
+impl ObjData {
+    /// Convert models and materials loaded by `tobj`.
+    ///
+    /// Each model becomes an object with a single group, both named after the model, assigned
+    /// the model's material. The materials are collected into a single material library named
+    /// `mtl_filename`, which is omitted if there are none.
+    ///
+    /// Both meshes loaded with `single_index` and with separate texture and normal indices are
+    /// supported.
+    pub fn from_tobj(models: &[tobj::Model], materials: &[tobj::Material], mtl_filename: &str) -> Self {
+        let mut data = ObjData::default();
+        let mtl = Mtl::from_tobj(mtl_filename.to_string(), materials);
+        for model in models {
+            let mesh = &model.mesh;
+            let (p0, t0, n0) = (data.position.len(), data.texture.len(), data.normal.len());
+            data.position.extend(
+                mesh.positions
+                    .chunks_exact(3)
+                    .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32]),
+            );
+            data.texture
+                .extend(mesh.texcoords.chunks_exact(2).map(|t| [t[0] as f32, t[1] as f32]));
+            data.normal.extend(
+                mesh.normals
+                    .chunks_exact(3)
+                    .map(|n| [n[0] as f32, n[1] as f32, n[2] as f32]),
+            );
+            if !mesh.vertex_color.is_empty() {
+                data.color.resize(p0, [1.0; 3]);
+                data.color.extend(
+                    mesh.vertex_color
+                        .chunks_exact(3)
+                        .map(|c| [c[0] as f32, c[1] as f32, c[2] as f32]),
+                );
+            } else if !data.color.is_empty() {
+                data.color.resize(data.position.len(), [1.0; 3]);
+            }
+
+            // Without separate indices, texture coordinates and normals are indexed like positions.
+            let attribute = |values: &[_], indices: &'_ [u32], i: usize| {
+                if values.is_empty() {
+                    None
+                } else if indices.is_empty() {
+                    Some(mesh.indices[i] as usize)
+                } else {
+                    Some(indices[i] as usize)
+                }
+            };
+            let tuple = |i: usize| {
+                IndexTuple::new(
+                    p0 + mesh.indices[i] as usize,
+                    attribute(&mesh.texcoords, &mesh.texcoord_indices, i).map(|t| t0 + t),
+                    attribute(&mesh.normals, &mesh.normal_indices, i).map(|n| n0 + n),
+                )
+            };
+
+            let mut group = Group::new(model.name.as_str());
+            let arities = if mesh.face_arities.is_empty() {
+                vec![3; mesh.indices.len() / 3]
+            } else {
+                mesh.face_arities.iter().map(|&a| a as usize).collect()
+            };
+            let mut start = 0;
+            for arity in arities {
+                group
+                    .polys
+                    .push(SimplePolygon((start..start + arity).map(tuple).collect()));
+                start += arity;
+            }
+            group.material = mesh
+                .material_id
+                .and_then(|id| mtl.materials.get(id))
+                .map(|m| ObjMaterial::Mtl(m.clone()));
+
+            let mut object = Object::new(model.name.as_str());
+            object.groups.push(group);
+            data.objects.push(object);
+        }
+        if !mtl.materials.is_empty() {
+            data.material_libs.push(mtl);
+        }
+        data.intern_names();
+        data
+    }
+
+    /// Convert to `tobj` models and materials.
+    ///
+    /// Every group becomes a model named after the group, or after its object for the default
+    /// group. Models use separate texture and normal indices, like `tobj` does without
+    /// `single_index`, and keep the faces as they are, with arities listed in `face_arities`
+    /// unless all of them are triangles. Texture coordinates and normals are only included if
+    /// every face vertex of the group has them. Lines are skipped, since `tobj` does not support
+    /// them.
+    ///
+    /// The materials of all material libraries are returned in order; groups whose material
+    /// was not loaded from one of them get no material id.
+    pub fn to_tobj(&self) -> (Vec<tobj::Model>, Vec<tobj::Material>) {
+        let materials: Vec<&Arc<Material>> = self.material_libs.iter().flat_map(|mtl| &mtl.materials).collect();
+        let mut models = Vec::new();
+        for object in &self.objects {
+            for group in &object.groups {
+                if group.polys.is_empty() {
+                    continue;
+                }
+                let mut mesh = tobj::Mesh::default();
+                let tuples = || group.polys.iter().flat_map(|poly| &poly.0);
+                let has_texture = tuples().all(|t| t.texture.is_some());
+                let has_normal = tuples().all(|t| t.normal.is_some());
+
+                // Only copy the attributes the group refers to.
+                let mut remap = [HashMap::new(), HashMap::new(), HashMap::new()];
+                let mut index = |attribute: usize, old: usize| {
+                    let next = remap[attribute].len();
+                    *remap[attribute].entry(old).or_insert(next) as u32
+                };
+                for IndexTuple {
+                    position: p,
+                    texture: t,
+                    normal: n,
+                } in tuples()
+                {
+                    let new = index(0, *p);
+                    if new as usize * 3 == mesh.positions.len() {
+                        extend_floats(&mut mesh.positions, &self.position[*p]);
+                        if let Some(c) = self.color.get(*p) {
+                            extend_floats(&mut mesh.vertex_color, c);
+                        }
+                    }
+                    mesh.indices.push(new);
+                    if let (true, Some(t)) = (has_texture, *t) {
+                        let new = index(1, t);
+                        if new as usize * 2 == mesh.texcoords.len() {
+                            extend_floats(&mut mesh.texcoords, &self.texture[t]);
+                        }
+                        mesh.texcoord_indices.push(new);
+                    }
+                    if let (true, Some(n)) = (has_normal, *n) {
+                        let new = index(2, n);
+                        if new as usize * 3 == mesh.normals.len() {
+                            extend_floats(&mut mesh.normals, &self.normal[n]);
+                        }
+                        mesh.normal_indices.push(new);
+                    }
+                }
+                if group.polys.iter().any(|poly| poly.0.len() != 3) {
+                    mesh.face_arities = group.polys.iter().map(|poly| poly.0.len() as u32).collect();
+                }
+                mesh.material_id = match &group.material {
+                    Some(ObjMaterial::Mtl(m)) => materials.iter().position(|o| Arc::ptr_eq(o, m)),
+                    _ => None,
+                };
+
+                let name = if &*group.name == DEFAULT_GROUP && &*object.name != DEFAULT_OBJECT {
+                    &object.name
+                } else {
+                    &group.name
+                };
+                models.push(tobj::Model::new(mesh, name.to_string()));
+            }
+        }
+        (models, materials.iter().map(|m| tobj::Material::from(&***m)).collect())
+    }
+}
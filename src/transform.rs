@@ -0,0 +1,375 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Affine transformations of vertex positions and normals.
+
+use crate::math::{add, mat3_inverse_transpose, mat3_mul_vec, normalize, Mat3};
+use crate::obj::{Axis, IndexTuple, ObjData};
+
+impl ObjData {
+    /// Apply an affine transformation to all positions and normals.
+    ///
+    /// The matrix is given in column-major order, i.e. `matrix[3]` holds the translation, which is
+    /// the layout used by most graphics math libraries. Only the upper 3x4 part of the matrix is
+    /// used, projective transformations are not supported.
+    ///
+    /// Normals are transformed by the inverse transpose of the linear part and renormalized. If
+    /// the linear part is singular, normals are left untouched.
+    ///
+    /// The winding order of polygons is not changed, even if the transformation is a reflection.
+    pub fn transform(&mut self, matrix: &[[f32; 4]; 4]) {
+        let linear: Mat3 = [
+            [matrix[0][0], matrix[0][1], matrix[0][2]],
+            [matrix[1][0], matrix[1][1], matrix[1][2]],
+            [matrix[2][0], matrix[2][1], matrix[2][2]],
+        ];
+        let translation = [matrix[3][0], matrix[3][1], matrix[3][2]];
+
+        for p in &mut self.position {
+            *p = add(mat3_mul_vec(&linear, *p), translation);
+        }
+
+        if let Some(normal_matrix) = mat3_inverse_transpose(&linear) {
+            for n in &mut self.normal {
+                let t = mat3_mul_vec(&normal_matrix, *n);
+                *n = normalize(t).unwrap_or(t);
+            }
+        }
+    }
+
+    /// Scale all positions by the given per-axis factors.
+    pub fn scale(&mut self, factors: [f32; 3]) {
+        self.transform(&[
+            [factors[0], 0.0, 0.0, 0.0],
+            [0.0, factors[1], 0.0, 0.0],
+            [0.0, 0.0, factors[2], 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+    }
+
+    /// Translate all positions by the given offset.
+    pub fn translate(&mut self, offset: [f32; 3]) {
+        for p in &mut self.position {
+            *p = add(*p, offset);
+        }
+    }
+
+    /// Move and uniformly scale the geometry as given by `mode`, returning the applied
+    /// transformation in the layout taken by [`ObjData::transform`].
+    ///
+    /// All positions are taken into account, whether or not they are referenced. Geometry without
+    /// extent, such as a single point, is only moved, and without positions nothing is changed and
+    /// the identity is returned.
+    pub fn normalize(&mut self, mode: NormalizeMode) -> [[f32; 4]; 4] {
+        let fit = match mode {
+            NormalizeMode::UnitCube => self.bounding_box().map(|bbox| {
+                let size = bbox.size();
+                (bbox.center(), 1.0 / size[0].max(size[1]).max(size[2]))
+            }),
+            NormalizeMode::CenterOrigin => self.bounding_box().map(|bbox| (bbox.center(), 1.0)),
+            NormalizeMode::FitSphere(radius) => self
+                .bounding_sphere()
+                .map(|sphere| (sphere.center, radius / sphere.radius)),
+        };
+        let (center, factor) = fit.unwrap_or(([0.0; 3], 1.0));
+        let factor = if factor.is_finite() { factor } else { 1.0 };
+        let matrix = [
+            [factor, 0.0, 0.0, 0.0],
+            [0.0, factor, 0.0, 0.0],
+            [0.0, 0.0, factor, 0.0],
+            [-center[0] * factor, -center[1] * factor, -center[2] * factor, 1.0],
+        ];
+        self.transform(&matrix);
+        matrix
+    }
+
+    /// Rotate all positions and normals by `angle` radians counter-clockwise around `axis`.
+    ///
+    /// The axis does not need to be normalized. A zero axis leaves the geometry untouched.
+    pub fn rotate(&mut self, axis: [f32; 3], angle: f32) {
+        let [x, y, z] = match normalize(axis) {
+            Some(axis) => axis,
+            None => return,
+        };
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        self.transform(&[
+            [t * x * x + c, t * x * y + s * z, t * x * z - s * y, 0.0],
+            [t * x * y - s * z, t * y * y + c, t * y * z + s * x, 0.0],
+            [t * x * z + s * y, t * y * z - s * x, t * z * z + c, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+    }
+}
+
+/// How [`ObjData::normalize`] places and sizes the geometry.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NormalizeMode {
+    /// Center the bounding box at the origin and scale it uniformly so that its longest side has
+    /// length 1, fitting it into the cube from -0.5 to 0.5.
+    UnitCube,
+    /// Move the center of the bounding box to the origin without scaling.
+    CenterOrigin,
+    /// Center the bounding sphere at the origin and scale it uniformly to the given radius.
+    FitSphere(f32),
+}
+
+impl Axis {
+    /// The unit vector pointing along this axis.
+    pub fn unit_vector(self) -> [f32; 3] {
+        match self {
+            Axis::PosX => [1.0, 0.0, 0.0],
+            Axis::NegX => [-1.0, 0.0, 0.0],
+            Axis::PosY => [0.0, 1.0, 0.0],
+            Axis::NegY => [0.0, -1.0, 0.0],
+            Axis::PosZ => [0.0, 0.0, 1.0],
+            Axis::NegZ => [0.0, 0.0, -1.0],
+        }
+    }
+
+    fn dimension(self) -> usize {
+        match self {
+            Axis::PosX | Axis::NegX => 0,
+            Axis::PosY | Axis::NegY => 1,
+            Axis::PosZ | Axis::NegZ => 2,
+        }
+    }
+}
+
+/// Handedness of a coordinate system.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    Right,
+    Left,
+}
+
+/// Describes how a coordinate system maps the semantic directions of a scene onto axes, as well
+/// as the size of one unit.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CoordinateConvention {
+    /// The axis pointing to the right of a viewer facing forward.
+    pub right: Axis,
+    /// The axis pointing up.
+    pub up: Axis,
+    /// The axis pointing forward, away from the viewer.
+    pub forward: Axis,
+    /// The length of one unit in meters, e.g. `0.01` for centimeters.
+    pub meters_per_unit: f32,
+}
+
+impl CoordinateConvention {
+    /// Y-up right-handed coordinates in meters, as used by OpenGL, glTF and most .obj exporters.
+    pub const Y_UP_RIGHT_HANDED: CoordinateConvention = CoordinateConvention {
+        right: Axis::PosX,
+        up: Axis::PosY,
+        forward: Axis::NegZ,
+        meters_per_unit: 1.0,
+    };
+    /// Z-up right-handed coordinates in meters, as used by Blender and many CAD tools.
+    pub const Z_UP_RIGHT_HANDED: CoordinateConvention = CoordinateConvention {
+        right: Axis::PosX,
+        up: Axis::PosZ,
+        forward: Axis::PosY,
+        meters_per_unit: 1.0,
+    };
+    /// Y-up left-handed coordinates in meters, as used by Direct3D and Unity.
+    pub const Y_UP_LEFT_HANDED: CoordinateConvention = CoordinateConvention {
+        right: Axis::PosX,
+        up: Axis::PosY,
+        forward: Axis::PosZ,
+        meters_per_unit: 1.0,
+    };
+    /// Z-up left-handed coordinates in meters, as used by Unreal Engine.
+    pub const Z_UP_LEFT_HANDED: CoordinateConvention = CoordinateConvention {
+        right: Axis::PosY,
+        up: Axis::PosZ,
+        forward: Axis::PosX,
+        meters_per_unit: 1.0,
+    };
+
+    /// The same convention with a different unit size.
+    pub fn with_meters_per_unit(self, meters_per_unit: f32) -> Self {
+        CoordinateConvention {
+            meters_per_unit,
+            ..self
+        }
+    }
+
+    /// Check that the right, up and forward axes are mutually perpendicular.
+    pub fn is_valid(&self) -> bool {
+        let (r, u, f) = (self.right.dimension(), self.up.dimension(), self.forward.dimension());
+        r != u && u != f && f != r
+    }
+
+    /// The handedness of this coordinate system.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the convention is not valid.
+    pub fn handedness(&self) -> Handedness {
+        assert!(self.is_valid(), "coordinate axes must be perpendicular");
+        let basis = self.basis();
+        if crate::math::mat3_determinant(&basis) > 0.0 {
+            // The semantic frame (right, up, backward) is right-handed.
+            Handedness::Left
+        } else {
+            Handedness::Right
+        }
+    }
+
+    /// The matrix with columns `right`, `up` and `forward`.
+    fn basis(&self) -> Mat3 {
+        [
+            self.right.unit_vector(),
+            self.up.unit_vector(),
+            self.forward.unit_vector(),
+        ]
+    }
+
+    /// The column-major affine matrix converting coordinates from this convention into `target`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if either convention is not valid.
+    pub fn conversion_matrix(&self, target: &CoordinateConvention) -> [[f32; 4]; 4] {
+        assert!(
+            self.is_valid() && target.is_valid(),
+            "coordinate axes must be perpendicular"
+        );
+        let from = self.basis();
+        let to = target.basis();
+        let s = self.meters_per_unit / target.meters_per_unit;
+
+        // The source basis is a signed permutation, so its inverse is its transpose.
+        let mut m = [[0.0; 4]; 4];
+        for (col, m_col) in m.iter_mut().take(3).enumerate() {
+            for row in 0..3 {
+                m_col[row] = s * (0..3).map(|k| to[k][row] * from[k][col]).sum::<f32>();
+            }
+        }
+        m[3][3] = 1.0;
+        m
+    }
+}
+
+impl ObjData {
+    /// Convert positions and normals from one coordinate convention to another.
+    ///
+    /// Axes are remapped, positions are rescaled according to the unit sizes and, if the
+    /// handedness changes, the winding order of all polygons is reversed so that front faces stay
+    /// front faces. The up axis and unit size of [`ObjData::metadata`] are set to those of `to`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if either convention is not valid.
+    pub fn convert_coordinates(&mut self, from: &CoordinateConvention, to: &CoordinateConvention) {
+        let matrix = from.conversion_matrix(to);
+        self.transform(&matrix);
+        if from.handedness() != to.handedness() {
+            self.flip_winding();
+        }
+        self.metadata.up_axis = Some(to.up);
+        self.metadata.meters_per_unit = Some(to.meters_per_unit);
+    }
+
+    /// Reverse the vertex order of every polygon, turning front faces into back faces.
+    ///
+    /// The first vertex of each polygon is kept in place.
+    pub fn flip_winding(&mut self) {
+        for poly in self.polys_mut() {
+            if let Some((_, rest)) = poly.0.split_first_mut() {
+                rest.reverse();
+            }
+        }
+    }
+
+    /// Negate all vertex normals.
+    pub fn invert_normals(&mut self) {
+        for n in &mut self.normal {
+            *n = [-n[0], -n[1], -n[2]];
+        }
+    }
+
+    /// Mirror all texture coordinates vertically, mapping `v` to `1 - v`.
+    ///
+    /// This converts between the bottom-left texture origin used by OBJ and OpenGL and the
+    /// top-left origin used by DirectX, Metal and most image formats.
+    pub fn flip_uv_v(&mut self) {
+        for uv in &mut self.texture {
+            uv[1] = 1.0 - uv[1];
+        }
+    }
+
+    /// Scale all texture coordinates component-wise, then add `offset`.
+    pub fn transform_uvs(&mut self, offset: [f32; 2], scale: [f32; 2]) {
+        for uv in &mut self.texture {
+            *uv = [uv[0] * scale[0] + offset[0], uv[1] * scale[1] + offset[1]];
+        }
+    }
+
+    /// Duplicate all polygons and lines, mirrored across the plane through the origin
+    /// perpendicular to `axis`.
+    ///
+    /// Mirrored elements are added to the groups of their originals, with reversed winding order
+    /// so that they keep facing outwards. Texture coordinates are shared with the originals.
+    ///
+    /// With `merge_seam_epsilon` set, positions within that distance of the plane are snapped onto
+    /// it and shared by both halves instead of being duplicated, joining them along the seam.
+    pub fn mirror(&mut self, axis: Axis, merge_seam_epsilon: Option<f32>) {
+        let dim = axis.dimension();
+        let reflect = |mut v: [f32; 3]| {
+            v[dim] = -v[dim];
+            v
+        };
+
+        let (position_count, normal_count) = (self.position.len(), self.normal.len());
+        let mut position_map = Vec::with_capacity(position_count);
+        for i in 0..position_count {
+            match merge_seam_epsilon {
+                Some(eps) if self.position[i][dim].abs() <= eps => {
+                    self.position[i][dim] = 0.0;
+                    position_map.push(i);
+                }
+                _ => {
+                    self.position.push(reflect(self.position[i]));
+                    if !self.color.is_empty() {
+                        self.color.push(self.color[i]);
+                    }
+                    position_map.push(self.position.len() - 1);
+                }
+            }
+        }
+        self.normal.extend_from_within(..);
+        for n in &mut self.normal[normal_count..] {
+            *n = reflect(*n);
+        }
+
+        let map =
+            |t: &IndexTuple| IndexTuple::new(position_map[t.position], t.texture, t.normal.map(|n| n + normal_count));
+        for group in self.objects.iter_mut().flat_map(|o| o.groups.iter_mut()) {
+            // The copies keep the render states of their originals.
+            let mut mirrored = group.clone();
+            for poly in &mut mirrored.polys {
+                poly.0.iter_mut().for_each(|idx| *idx = map(idx));
+                if let Some((_, rest)) = poly.0.split_first_mut() {
+                    rest.reverse();
+                }
+            }
+            for line in &mut mirrored.lines {
+                line.0.iter_mut().for_each(|idx| *idx = map(idx));
+            }
+            group.append(mirrored);
+        }
+    }
+}
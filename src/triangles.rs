@@ -0,0 +1,87 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Iteration over triangles with their attribute values looked up.
+
+use crate::buffers::fan;
+use crate::math::{cross, sub};
+use crate::obj::{Group, IndexTuple, ObjData, ObjMaterial};
+
+/// A triangle of a polygon with the values of its vertex attributes.
+///
+/// Returned by [`ObjData::triangles`] and [`Group::triangles`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedTriangle<'a> {
+    /// The positions of the three corners.
+    pub positions: [[f32; 3]; 3],
+    /// The texture coordinates of the corners, if all of them have one.
+    pub texture: Option<[[f32; 2]; 3]>,
+    /// The normals of the corners, if all of them have one.
+    pub normals: Option<[[f32; 3]; 3]>,
+    /// The group the triangle belongs to.
+    pub group: &'a Group,
+    /// The material of the group.
+    pub material: Option<&'a ObjMaterial>,
+}
+
+fn resolve<T: Copy>(values: &[T], indices: [Option<usize>; 3]) -> Option<[T; 3]> {
+    let value = |index: Option<usize>| values.get(index?).copied();
+    Some([value(indices[0])?, value(indices[1])?, value(indices[2])?])
+}
+
+impl Group {
+    /// Iterate over the triangles of this group's polygons, which index into `data`.
+    ///
+    /// Polygons with more than three vertices are split into a fan around their first vertex,
+    /// polygons with fewer are skipped. Indices referring to missing attributes, which
+    /// [`ObjData::validate_indices`] reports, are treated like absent ones, and triangles with a
+    /// missing position are skipped.
+    pub fn triangles<'a>(&'a self, data: &'a ObjData) -> impl Iterator<Item = ResolvedTriangle<'a>> + 'a {
+        self.polys.iter().flat_map(fan).filter_map(move |tri: [IndexTuple; 3]| {
+            Some(ResolvedTriangle {
+                positions: resolve(&data.position, tri.map(|t| Some(t.position)))?,
+                texture: resolve(&data.texture, tri.map(|t| t.texture)),
+                normals: resolve(&data.normal, tri.map(|t| t.normal)),
+                group: self,
+                material: self.material.as_ref(),
+            })
+        })
+    }
+}
+
+impl ObjData {
+    /// Iterate over the triangles of all polygons, in order, see [`Group::triangles`].
+    pub fn triangles(&self) -> impl Iterator<Item = ResolvedTriangle<'_>> {
+        self.objects
+            .iter()
+            .flat_map(|object| &object.groups)
+            .flat_map(move |group| group.triangles(self))
+    }
+
+    /// The positions of all triangles as a flat list of corners, three per triangle.
+    ///
+    /// Polygons are split as by [`ObjData::triangles`], and triangles with zero area are left
+    /// out, as physics cookers and CSG libraries taking unindexed triangle soup tend to reject
+    /// them.
+    pub fn as_triangle_soup(&self) -> Vec<[f32; 3]> {
+        let mut soup = Vec::new();
+        for triangle in self.triangles() {
+            let [a, b, c] = triangle.positions;
+            if cross(sub(b, a), sub(c, a)) != [0.0; 3] {
+                soup.extend_from_slice(&triangle.positions);
+            }
+        }
+        soup
+    }
+}
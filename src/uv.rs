@@ -0,0 +1,221 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Generation of texture coordinates by projection.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::bounds::{BoundingBox, UvBounds};
+use crate::math::{length, newell_normal, sub};
+use crate::mtl::Material;
+use crate::obj::{Axis, ObjData, ObjMaterial};
+
+/// How [`ObjData::generate_uvs`] projects positions onto the texture.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Projection {
+    /// Project along the given axis, as seen by a viewer looking against it, so that the texture
+    /// is not mirrored on faces pointing along the axis.
+    Planar(Axis),
+    /// Project every polygon along the axis closest to its normal.
+    Box,
+    /// Project onto a sphere around the center of the bounding box, with `u` running around the
+    /// Y axis and `v` from the bottom to the top.
+    Spherical,
+}
+
+/// The result of [`ObjData::apply_atlas`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AtlasReport {
+    /// The number of groups that were moved to the atlas material.
+    pub groups: usize,
+    /// Polygons with texture coordinates outside the unit square, whose texture was repeated or
+    /// clamped before and which now reach into neighboring rectangles of the atlas.
+    ///
+    /// Polygons are numbered consecutively across all groups of all objects, in order.
+    pub tiled_polygons: Vec<usize>,
+}
+
+/// The texture coordinates of `p` projected along `axis`, scaled so that the bounding box covers
+/// the unit square.
+fn planar(axis: Axis, bbox: &BoundingBox, p: [f32; 3]) -> [f32; 2] {
+    let (u, flip_u, v, flip_v) = match axis {
+        Axis::PosX => (2, true, 1, false),
+        Axis::NegX => (2, false, 1, false),
+        Axis::PosY => (0, false, 2, true),
+        Axis::NegY => (0, false, 2, false),
+        Axis::PosZ => (0, false, 1, false),
+        Axis::NegZ => (0, true, 1, false),
+    };
+    let size = bbox.size();
+    let coordinate = |d: usize, flip: bool| {
+        let t = if size[d] > 0.0 {
+            (p[d] - bbox.min[d]) / size[d]
+        } else {
+            0.0
+        };
+        if flip {
+            1.0 - t
+        } else {
+            t
+        }
+    };
+    [coordinate(u, flip_u), coordinate(v, flip_v)]
+}
+
+fn spherical(center: [f32; 3], p: [f32; 3]) -> [f32; 2] {
+    let d = sub(p, center);
+    let r = length(d);
+    if r == 0.0 {
+        return [0.5, 0.5];
+    }
+    let u = 0.5 + d[0].atan2(d[2]) / (2.0 * PI);
+    let v = 0.5 + (d[1] / r).clamp(-1.0, 1.0).asin() / PI;
+    [u, v]
+}
+
+/// The signed axis closest to the given normal.
+fn dominant_axis(n: [f32; 3]) -> Axis {
+    let d = (0..3).fold(0, |best, d| if n[d].abs() > n[best].abs() { d } else { best });
+    match (d, n[d] < 0.0) {
+        (0, false) => Axis::PosX,
+        (0, true) => Axis::NegX,
+        (1, false) => Axis::PosY,
+        (1, true) => Axis::NegY,
+        (_, false) => Axis::PosZ,
+        (_, true) => Axis::NegZ,
+    }
+}
+
+impl ObjData {
+    /// Replace the texture coordinates with ones projected from the positions.
+    ///
+    /// Every polygon corner gets a texture index into the new texture array, which is scaled so
+    /// that the bounding box of the positions maps onto the unit square, or for
+    /// [`Projection::Spherical`], so that the sphere covers it once. Positions share a texture
+    /// coordinate unless a box projection maps them along different axes. Texture indices are
+    /// removed from lines, as the previous texture array is discarded.
+    ///
+    /// The projections are meant for quick texturing rather than seam-free unwrapping: spherical
+    /// coordinates wrap around behind the center, where polygons crossing the seam stretch over
+    /// the whole texture.
+    pub fn generate_uvs(&mut self, projection: Projection) {
+        let ObjData {
+            position,
+            texture,
+            objects,
+            ..
+        } = self;
+        texture.clear();
+        let bbox = match BoundingBox::from_points(position.iter()) {
+            Some(bbox) => bbox,
+            None => return,
+        };
+
+        let mut uvs: HashMap<(usize, Option<Axis>), usize> = HashMap::new();
+        for group in objects.iter_mut().flat_map(|o| &mut o.groups) {
+            for line in &mut group.lines {
+                for tuple in &mut line.0 {
+                    tuple.texture = None;
+                }
+            }
+            for poly in &mut group.polys {
+                let axis = match projection {
+                    Projection::Box => {
+                        let points: Vec<_> = poly.0.iter().map(|t| position[t.position]).collect();
+                        Some(dominant_axis(newell_normal(&points)))
+                    }
+                    _ => None,
+                };
+                for tuple in poly.0.iter_mut() {
+                    let p = tuple.position;
+                    let index = *uvs.entry((p, axis)).or_insert_with(|| {
+                        texture.push(match (projection, axis) {
+                            (Projection::Planar(axis), _) | (_, Some(axis)) => planar(axis, &bbox, position[p]),
+                            _ => spherical(bbox.center(), position[p]),
+                        });
+                        texture.len() - 1
+                    });
+                    tuple.texture = Some(index);
+                }
+            }
+        }
+    }
+
+    /// Move the groups using the materials in `atlas` to the single `material`, rewriting their
+    /// texture coordinates into the rectangle of the atlas texture their material was packed
+    /// into.
+    ///
+    /// `atlas` maps material names to rectangles in texture coordinates of the atlas, where the
+    /// unit square of each original texture is scaled into its rectangle. Groups using other
+    /// materials keep their material and texture coordinates. Texture coordinates used with
+    /// several materials are stored once for each, and ones that no polygon or line uses are
+    /// removed. Together with
+    /// [`Object::merge_groups_by_material`](crate::Object::merge_groups_by_material), this gives
+    /// one range of polygons per object that can be drawn at once.
+    ///
+    /// `material` has to be added to a material library for files written afterwards to refer
+    /// to it.
+    pub fn apply_atlas(&mut self, atlas: &HashMap<String, UvBounds>, material: Arc<Material>) -> AtlasReport {
+        let rects: Vec<_> = atlas.iter().collect();
+        let mut report = AtlasReport::default();
+        let old = std::mem::take(&mut self.texture);
+        // The new index of every texture coordinate used in each rectangle, or as it was.
+        let mut uvs: HashMap<(usize, Option<usize>), usize> = HashMap::new();
+        let mut first_poly = 0;
+        for group in self.objects.iter_mut().flat_map(|o| &mut o.groups) {
+            let rect = group
+                .material
+                .as_ref()
+                .and_then(|m| rects.iter().position(|(name, _)| **name == m.name()));
+            if rect.is_some() {
+                group.material = Some(ObjMaterial::Mtl(material.clone()));
+                report.groups += 1;
+            }
+
+            let texture = &mut self.texture;
+            let mut place = |t: usize| {
+                *uvs.entry((t, rect)).or_insert_with(|| {
+                    texture.push(match rect {
+                        Some(r) => {
+                            let bounds = rects[r].1;
+                            let uv = old[t];
+                            [0, 1].map(|i| bounds.min[i] + uv[i] * (bounds.max[i] - bounds.min[i]))
+                        }
+                        None => old[t],
+                    });
+                    texture.len() - 1
+                })
+            };
+            for (i, poly) in group.polys.iter_mut().enumerate() {
+                let mut tiled = false;
+                for tuple in poly.0.iter_mut() {
+                    if let Some(t) = tuple.texture {
+                        tiled |= old[t].iter().any(|&x| !(0.0..=1.0).contains(&x));
+                        tuple.texture = Some(place(t));
+                    }
+                }
+                if tiled && rect.is_some() {
+                    report.tiled_polygons.push(first_poly + i);
+                }
+            }
+            for tuple in group.lines.iter_mut().flat_map(|l| &mut l.0) {
+                tuple.texture = tuple.texture.map(&mut place);
+            }
+            first_poly += group.polys.len();
+        }
+        report
+    }
+}
@@ -0,0 +1,184 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Post-transform vertex cache optimization of triangle index buffers.
+//!
+//! This implements Tom Forsyth's "Linear-Speed Vertex Cache Optimisation".
+
+use crate::buffers::IndexedBuffers;
+
+const CACHE_SIZE: usize = 32;
+const CACHE_DECAY_POWER: f32 = 1.5;
+const LAST_TRI_SCORE: f32 = 0.75;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+#[derive(Clone, Default)]
+struct VertexState {
+    /// Triangles using this vertex that have not been emitted yet.
+    triangles: Vec<usize>,
+    /// Position in the simulated LRU cache.
+    cache_pos: Option<usize>,
+    score: f32,
+}
+
+fn vertex_score(v: &VertexState) -> f32 {
+    if v.triangles.is_empty() {
+        return -1.0;
+    }
+    let cache_score = match v.cache_pos {
+        None => 0.0,
+        // The vertices of the last triangle are fixed, so they shouldn't be favored too much.
+        Some(pos) if pos < 3 => LAST_TRI_SCORE,
+        Some(pos) => (1.0 - (pos - 3) as f32 / (CACHE_SIZE - 3) as f32).powf(CACHE_DECAY_POWER),
+    };
+    let valence_boost = VALENCE_BOOST_SCALE * (v.triangles.len() as f32).powf(-VALENCE_BOOST_POWER);
+    cache_score + valence_boost
+}
+
+/// Reorder the triangles of an index buffer to reduce post-transform vertex cache misses.
+///
+/// `indices` holds three indices per triangle into a vertex buffer of `vertex_count` vertices.
+/// The set of triangles is unchanged and each triangle keeps its winding.
+pub fn optimize_vertex_cache(indices: &mut [u32], vertex_count: usize) {
+    let tri_count = indices.len() / 3;
+    if tri_count == 0 {
+        return;
+    }
+
+    let mut vertices = vec![VertexState::default(); vertex_count];
+    for (t, tri) in indices.chunks_exact(3).enumerate() {
+        for &v in tri {
+            vertices[v as usize].triangles.push(t);
+        }
+    }
+    for v in &mut vertices {
+        v.score = vertex_score(v);
+    }
+
+    let tri_score =
+        |vertices: &[VertexState], tri: &[u32]| tri.iter().map(|&v| vertices[v as usize].score).sum::<f32>();
+    let mut emitted = vec![false; tri_count];
+    let mut scores: Vec<f32> = indices.chunks_exact(3).map(|tri| tri_score(&vertices, tri)).collect();
+
+    let mut output = Vec::with_capacity(tri_count * 3);
+    let mut cache: Vec<u32> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut best = (0..tri_count).max_by(|&a, &b| scores[a].partial_cmp(&scores[b]).unwrap());
+    // Fallback for when no triangle in the cache is left to emit.
+    let mut next_unemitted = 0;
+
+    while let Some(t) = best {
+        emitted[t] = true;
+        let tri = [indices[t * 3], indices[t * 3 + 1], indices[t * 3 + 2]];
+        output.extend_from_slice(&tri);
+
+        for &v in &tri {
+            vertices[v as usize].triangles.retain(|&other| other != t);
+        }
+
+        // Move the triangle's vertices to the front of the LRU cache.
+        let mut new_cache: Vec<u32> = tri.to_vec();
+        new_cache.extend(cache.iter().filter(|v| !tri.contains(v)));
+        for (pos, &v) in new_cache.iter().enumerate() {
+            vertices[v as usize].cache_pos = if pos < CACHE_SIZE { Some(pos) } else { None };
+        }
+
+        // Update the scores of all affected vertices and their triangles.
+        best = None;
+        let mut best_score = f32::NEG_INFINITY;
+        for &v in &new_cache {
+            let state = &mut vertices[v as usize];
+            state.score = vertex_score(state);
+        }
+        for &v in &new_cache {
+            for &other in &vertices[v as usize].triangles {
+                let score = tri_score(&vertices, &indices[other * 3..other * 3 + 3]);
+                scores[other] = score;
+                if score > best_score {
+                    best_score = score;
+                    best = Some(other);
+                }
+            }
+        }
+        new_cache.truncate(CACHE_SIZE);
+        cache = new_cache;
+
+        if best.is_none() {
+            while next_unemitted < tri_count && emitted[next_unemitted] {
+                next_unemitted += 1;
+            }
+            if next_unemitted < tri_count {
+                best = Some(next_unemitted);
+            }
+        }
+    }
+
+    indices.copy_from_slice(&output);
+}
+
+/// The average number of vertex cache misses per triangle for a FIFO cache of the given size.
+///
+/// Lower is better, the theoretical optimum for large regular meshes is about 0.5.
+pub fn average_cache_miss_ratio(indices: &[u32], cache_size: usize) -> f32 {
+    let tri_count = indices.len() / 3;
+    if tri_count == 0 {
+        return 0.0;
+    }
+    let mut cache = std::collections::VecDeque::with_capacity(cache_size);
+    let mut misses = 0;
+    for &v in indices {
+        if !cache.contains(&v) {
+            misses += 1;
+            if cache.len() == cache_size {
+                cache.pop_front();
+            }
+            cache.push_back(v);
+        }
+    }
+    misses as f32 / tri_count as f32
+}
+
+impl<V: Copy> IndexedBuffers<V> {
+    /// Optimize the buffers for the post-transform vertex cache and vertex fetch.
+    ///
+    /// Triangles of each submesh are reordered with [`optimize_vertex_cache`], then vertices are
+    /// reordered by first use and all indices are rewritten accordingly. Submeshes keep their
+    /// order and triangles.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn optimize_vertex_cache(&mut self) {
+        let vertex_count = self.vertices.len();
+        for submesh in &mut self.submeshes {
+            optimize_vertex_cache(&mut submesh.indices, vertex_count);
+        }
+
+        let mut remap = vec![u32::MAX; vertex_count];
+        let mut vertices = Vec::with_capacity(vertex_count);
+        for submesh in &mut self.submeshes {
+            for idx in &mut submesh.indices {
+                if remap[*idx as usize] == u32::MAX {
+                    remap[*idx as usize] = vertices.len() as u32;
+                    vertices.push(self.vertices[*idx as usize]);
+                }
+                *idx = remap[*idx as usize];
+            }
+        }
+        // Keep vertices no triangle refers to at the end.
+        for (old, &new) in remap.iter().enumerate() {
+            if new == u32::MAX {
+                vertices.push(self.vertices[old]);
+            }
+        }
+        self.vertices = vertices;
+    }
+}
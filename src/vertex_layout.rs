@@ -0,0 +1,95 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Conversion between per-corner and per-vertex attribute layouts.
+
+use std::collections::HashMap;
+use std::mem;
+
+use crate::math::{add, normalize, Vec3};
+use crate::obj::{IndexTuple, ObjData};
+
+impl ObjData {
+    /// Replace the normals of every position by their average, so that each position has a
+    /// single normal.
+    ///
+    /// The normals of all polygon corners at a position are summed and renormalized, giving one
+    /// normal per position that has any, in the order of the positions. Corners without a normal
+    /// keep having none. This smooths over hard edges, see [`ObjData::split_vertices`] for the
+    /// opposite direction.
+    pub fn average_normals_by_position(&mut self) {
+        let mut sums: Vec<Option<Vec3>> = vec![None; self.position.len()];
+        for tuple in self.index_tuples() {
+            if let Some(n) = tuple.normal {
+                let sum = sums[tuple.position].get_or_insert([0.0; 3]);
+                *sum = add(*sum, self.normal[n]);
+            }
+        }
+
+        let mut table = vec![0; self.position.len()];
+        self.normal.clear();
+        for (p, sum) in sums.into_iter().enumerate() {
+            if let Some(sum) = sum {
+                table[p] = self.normal.len();
+                // Opposite normals cancel out, leaving nothing to normalize.
+                self.normal.push(normalize(sum).unwrap_or(sum));
+            }
+        }
+        for tuple in self.index_tuples_mut() {
+            if tuple.normal.is_some() {
+                tuple.normal = Some(table[tuple.position]);
+            }
+        }
+    }
+
+    /// Give every distinct combination of position, texture coordinate and normal used by
+    /// polygons and lines its own vertex, so that one index addresses all of its attributes.
+    ///
+    /// Afterwards, the texture and normal arrays are either empty or as long as the positions,
+    /// and every index tuple refers to the same index for each of its attributes, as needed by
+    /// engines with a single index buffer. Positions are duplicated along seams and hard edges,
+    /// with their colors. Vertices without a texture coordinate or normal get zeros in those
+    /// arrays, and attributes no element refers to are dropped.
+    pub fn split_vertices(&mut self) {
+        let has_texture = self.index_tuples().any(|t| t.texture.is_some());
+        let has_normal = self.index_tuples().any(|t| t.normal.is_some());
+        let position = mem::take(&mut self.position);
+        let color = mem::take(&mut self.color);
+        let texture = mem::take(&mut self.texture);
+        let normal = mem::take(&mut self.normal);
+
+        let mut split = ObjData::default();
+        let mut vertices: HashMap<IndexTuple, usize> = HashMap::new();
+        for tuple in self.index_tuples_mut() {
+            let i = *vertices.entry(*tuple).or_insert_with(|| {
+                split.position.push(position[tuple.position]);
+                if !color.is_empty() {
+                    split.color.push(color[tuple.position]);
+                }
+                if has_texture {
+                    split.texture.push(tuple.texture.map_or([0.0; 2], |t| texture[t]));
+                }
+                if has_normal {
+                    split.normal.push(tuple.normal.map_or([0.0; 3], |n| normal[n]));
+                }
+                split.position.len() - 1
+            });
+            *tuple = IndexTuple::new(i, tuple.texture.map(|_| i), tuple.normal.map(|_| i));
+        }
+        self.position = split.position;
+        self.color = split.color;
+        self.texture = split.texture;
+        self.normal = split.normal;
+    }
+}
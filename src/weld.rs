@@ -0,0 +1,253 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Merging of nearly coincident vertex attributes.
+
+use std::collections::HashMap;
+
+use crate::compact::{AttributeRemap, RemapReport};
+use crate::obj::ObjData;
+
+/// Vertex welding configuration options.
+#[derive(Copy, Clone, Debug)]
+pub struct WeldConfig {
+    /// Maximum distance between two attribute values for them to be merged.
+    ///
+    /// A value of zero only merges exactly identical values.
+    pub epsilon: f32,
+    /// Merge vertex positions (default `true`).
+    pub position: bool,
+    /// Merge texture coordinates (default `false`).
+    pub texture: bool,
+    /// Merge normals (default `false`).
+    pub normal: bool,
+}
+
+impl Default for WeldConfig {
+    fn default() -> Self {
+        WeldConfig {
+            epsilon: 0.0,
+            position: true,
+            texture: false,
+            normal: false,
+        }
+    }
+}
+
+/// The number of attribute entries removed by welding.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WeldReport {
+    pub position: usize,
+    pub texture: usize,
+    pub normal: usize,
+}
+
+impl WeldReport {
+    /// Total number of merged attributes.
+    pub fn total(&self) -> usize {
+        self.position + self.texture + self.normal
+    }
+}
+
+impl ObjData {
+    /// Merge vertex positions that lie within `epsilon` of each other.
+    ///
+    /// All polygon indices are rewritten to reference the remaining positions.
+    pub fn weld_vertices(&mut self, epsilon: f32) -> WeldReport {
+        self.weld_vertices_with_config(WeldConfig {
+            epsilon,
+            ..WeldConfig::default()
+        })
+    }
+
+    /// Merge vertex attributes according to the given configuration.
+    ///
+    /// The first occurrence of each merged value is kept and the relative order of the remaining
+    /// attributes is preserved. Indices referring to missing attributes, which
+    /// [`ObjData::validate_indices`] reports, keep referring to missing ones.
+    pub fn weld_vertices_with_config(&mut self, config: WeldConfig) -> WeldReport {
+        self.weld_vertices_with_remap(config).0
+    }
+
+    /// Like [`ObjData::weld_vertices_with_config`], also returning the new index of every
+    /// attribute, which keeps all polygons in place.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn weld_vertices_with_remap(&mut self, config: WeldConfig) -> (WeldReport, RemapReport) {
+        let mut report = WeldReport::default();
+        let mut remap = AttributeRemap::identity(self);
+        let table = |map: &[usize]| map.iter().copied().map(Some).collect();
+
+        let position = if config.position {
+            let (merged, map) = weld(&self.position, config.epsilon);
+            report.position = self.position.len() - merged.len();
+            if !self.color.is_empty() {
+                // Merged positions keep the color of their first occurrence.
+                let mut color = vec![[0.0; 3]; merged.len()];
+                for (old, &new) in map.iter().enumerate().rev() {
+                    color[new] = self.color[old];
+                }
+                self.color = color;
+            }
+            self.position = merged;
+            Some(map)
+        } else {
+            None
+        };
+        let texture = if config.texture {
+            let (merged, map) = weld(&self.texture, config.epsilon);
+            report.texture = self.texture.len() - merged.len();
+            self.texture = merged;
+            Some(map)
+        } else {
+            None
+        };
+        let normal = if config.normal {
+            let (merged, map) = weld(&self.normal, config.epsilon);
+            report.normal = self.normal.len() - merged.len();
+            self.normal = merged;
+            Some(map)
+        } else {
+            None
+        };
+
+        self.remap_indices(position.as_deref(), texture.as_deref(), normal.as_deref());
+        if let Some(map) = &position {
+            remap.position = table(map);
+        }
+        if let Some(map) = &texture {
+            remap.texture = table(map);
+        }
+        if let Some(map) = &normal {
+            remap.normal = table(map);
+        }
+        let polygons = self.polys().count();
+        (report, RemapReport::new(remap, vec![1; polygons]))
+    }
+
+    /// Snap vertex positions to a grid with cells of size `grid_size`, merging the resulting
+    /// duplicates.
+    ///
+    /// See [`ObjData::quantize_with_config`].
+    pub fn quantize(&mut self, grid_size: f32) -> WeldReport {
+        self.quantize_with_config(grid_size, WeldConfig::default())
+    }
+
+    /// Snap the attributes selected by `config` to a grid with cells of size `grid_size`, then
+    /// weld them according to `config`.
+    ///
+    /// Every coordinate is rounded to the nearest multiple of `grid_size`, which makes the mesh
+    /// independent of small noise, e.g. for stable hashes. Snapped normals are not
+    /// renormalized. A `grid_size` of zero or less only welds.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn quantize_with_config(&mut self, grid_size: f32, config: WeldConfig) -> WeldReport {
+        if grid_size > 0.0 {
+            // Adding zero turns negative zeros into positive ones.
+            let snap = |x: f32| (x / grid_size).round() * grid_size + 0.0;
+            if config.position {
+                self.position.iter_mut().flatten().for_each(|x| *x = snap(*x));
+            }
+            if config.texture {
+                self.texture.iter_mut().flatten().for_each(|x| *x = snap(*x));
+            }
+            if config.normal {
+                self.normal.iter_mut().flatten().for_each(|x| *x = snap(*x));
+            }
+        }
+        self.weld_vertices_with_config(config)
+    }
+
+    /// Merge exactly identical attributes for [`WriteConfig::weld`](crate::WriteConfig::weld),
+    /// keeping positions with different colors apart.
+    pub(crate) fn weld_identical(&mut self) {
+        let position = if self.color.is_empty() {
+            let (merged, map) = weld(&self.position, 0.0);
+            self.position = merged;
+            map
+        } else {
+            let colored: Vec<[f32; 6]> = self
+                .position
+                .iter()
+                .zip(&self.color)
+                .map(|(p, c)| [p[0], p[1], p[2], c[0], c[1], c[2]])
+                .collect();
+            let (merged, map) = weld(&colored, 0.0);
+            self.position = merged.iter().map(|v| [v[0], v[1], v[2]]).collect();
+            self.color = merged.iter().map(|v| [v[3], v[4], v[5]]).collect();
+            map
+        };
+        let (texture, texture_map) = weld(&self.texture, 0.0);
+        self.texture = texture;
+        let (normal, normal_map) = weld(&self.normal, 0.0);
+        self.normal = normal;
+        self.remap_indices(Some(&position), Some(&texture_map), Some(&normal_map));
+    }
+}
+
+/// Merge values within `epsilon` of each other, returning the merged values and a table mapping
+/// each original index to its new index.
+///
+/// Values are hashed into a grid with cells of size `epsilon`, so only neighbouring cells need to
+/// be searched for merge candidates.
+pub(crate) fn weld<const N: usize>(values: &[[f32; N]], epsilon: f32) -> (Vec<[f32; N]>, Vec<usize>) {
+    let mut merged: Vec<[f32; N]> = Vec::new();
+    let mut map = Vec::with_capacity(values.len());
+
+    if epsilon <= 0.0 {
+        let mut seen = HashMap::new();
+        for v in values {
+            let key = v.map(|x| if x == 0.0 { 0 } else { x.to_bits() });
+            let idx = *seen.entry(key).or_insert_with(|| {
+                merged.push(*v);
+                merged.len() - 1
+            });
+            map.push(idx);
+        }
+        return (merged, map);
+    }
+
+    let cell = |v: &[f32; N]| v.map(|x| (x / epsilon).floor() as i64);
+    let mut grid: HashMap<[i64; N], Vec<usize>> = HashMap::new();
+    let eps_sq = epsilon * epsilon;
+    let neighbours = 3usize.pow(N as u32);
+
+    for v in values {
+        let home = cell(v);
+        let mut found = None;
+        'search: for offset in 0..neighbours {
+            let mut key = home;
+            let mut rem = offset;
+            for k in key.iter_mut() {
+                *k += (rem % 3) as i64 - 1;
+                rem /= 3;
+            }
+            if let Some(candidates) = grid.get(&key) {
+                for &c in candidates {
+                    let dist_sq: f32 = merged[c].iter().zip(v.iter()).map(|(a, b)| (a - b) * (a - b)).sum();
+                    if dist_sq <= eps_sq {
+                        found = Some(c);
+                        break 'search;
+                    }
+                }
+            }
+        }
+        let idx = found.unwrap_or_else(|| {
+            merged.push(*v);
+            grid.entry(home).or_default().push(merged.len() - 1);
+            merged.len() - 1
+        });
+        map.push(idx);
+    }
+
+    (merged, map)
+}
@@ -0,0 +1,58 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! [`wgpu`] vertex buffer layouts for the vertex formats of this crate.
+//!
+//! Attributes use shader locations 0 for positions, 1 for texture coordinates and 2 for normals.
+
+use std::mem::size_of;
+
+use crate::buffers::{Position, PositionTexture, Vertex};
+
+impl Vertex {
+    /// The attributes of this format, see the [module documentation](self).
+    pub const WGPU_ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Float32x3];
+
+    /// The layout of a per-vertex buffer of this format.
+    pub const WGPU_LAYOUT: wgpu::VertexBufferLayout<'static> = wgpu::VertexBufferLayout {
+        array_stride: size_of::<Self>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &Self::WGPU_ATTRIBUTES,
+    };
+}
+
+impl Position {
+    /// The attributes of this format, see the [module documentation](self).
+    pub const WGPU_ATTRIBUTES: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x3];
+
+    /// The layout of a per-vertex buffer of this format.
+    pub const WGPU_LAYOUT: wgpu::VertexBufferLayout<'static> = wgpu::VertexBufferLayout {
+        array_stride: size_of::<Self>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &Self::WGPU_ATTRIBUTES,
+    };
+}
+
+impl PositionTexture {
+    /// The attributes of this format, see the [module documentation](self).
+    pub const WGPU_ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2];
+
+    /// The layout of a per-vertex buffer of this format.
+    pub const WGPU_LAYOUT: wgpu::VertexBufferLayout<'static> = wgpu::VertexBufferLayout {
+        array_stride: size_of::<Self>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &Self::WGPU_ATTRIBUTES,
+    };
+}
@@ -0,0 +1,89 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+fn load(input: &str) -> ObjData {
+    ObjData::load_buf(input.as_bytes()).unwrap()
+}
+
+const QUAD: &str = "\
+o quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 1
+f 1/1 2/1 3/2 4/2
+o line
+l 1 3
+";
+
+#[test]
+fn equal_within_epsilon() {
+    let a = load(QUAD);
+    let mut b = a.clone();
+    b.position[2][1] += 1e-6;
+    assert!(a.approx_eq(&b, 1e-5));
+    assert!(!a.approx_eq(&b, 1e-7));
+}
+
+#[test]
+fn ignores_attribute_and_object_order() {
+    let a = load(QUAD);
+    let b = load(
+        "\
+o line
+v 1 1 0
+v 0 1 0
+v 0 0 0
+v 1 0 0
+vt 1 1
+vt 0 0
+l 3 1
+o quad
+f 3/2 4/2 1/1 2/1
+",
+    );
+    assert!(a.approx_eq(&b, 0.0));
+    assert!(b.approx_eq(&a, 0.0));
+}
+
+#[test]
+fn detects_topology_changes() {
+    let a = load(QUAD);
+
+    let mut reversed = a.clone();
+    reversed.objects[0].groups[0].polys[0].0.reverse();
+    assert!(!a.approx_eq(&reversed, 0.0));
+
+    let mut untextured = a.clone();
+    untextured.objects[0].groups[0].polys[0].0[0].texture = None;
+    assert!(!a.approx_eq(&untextured, 0.0));
+
+    let mut renamed = a.clone();
+    renamed.objects[1].name = "other".into();
+    assert!(!a.approx_eq(&renamed, 0.0));
+
+    let mut out_of_range = a.clone();
+    out_of_range.objects[1].groups[0].lines[0].0[0].position = 10;
+    assert!(!a.approx_eq(&out_of_range, 0.0));
+
+    let mut colored = a.clone();
+    colored.color = vec![[1.0, 0.0, 0.0]; 4];
+    assert!(!a.approx_eq(&colored, 0.0));
+}
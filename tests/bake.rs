@@ -0,0 +1,71 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+#[test]
+fn bake_vertex_colors() {
+    let source = "
+v 0 0 0 1 0 0
+v 1 0 0 1 0 0
+v 1 1 0 0.9 0 0
+v 0 1 0 0 0 1
+v 0 2 0 0 0 1
+g red_and_blue
+usemtl old
+f 1 2 3
+f 3 4 5
+f 1 2 3
+l 4 5
+g red
+f 2 3 1
+";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let materials = obj.bake_vertex_colors(2);
+    assert_eq!(materials.len(), 2);
+    assert_eq!(materials[0].name, "vertex_color_0");
+    let kd: Vec<_> = materials.iter().map(|m| m.kd.unwrap()).collect();
+    // The face using both colors is closer to blue.
+    assert_eq!(kd[0][0], 2.9 / 3.0);
+    assert!((kd[1][0] - 0.15).abs() < 1e-6 && (kd[1][2] - 5.0 / 6.0).abs() < 1e-6);
+
+    let groups = &obj.objects[0].groups;
+    let summary: Vec<_> = groups
+        .iter()
+        .map(|g| {
+            (
+                &*g.name,
+                g.index,
+                g.material.as_ref().unwrap().name(),
+                g.polys.len(),
+                g.lines.len(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("red_and_blue", 0, "vertex_color_0", 2, 0),
+            ("red_and_blue", 1, "vertex_color_1", 1, 1),
+            ("red", 0, "vertex_color_0", 1, 0),
+        ]
+    );
+
+    let mut plain = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).unwrap();
+    let before = plain.clone();
+    assert!(plain.bake_vertex_colors(4).is_empty());
+    assert_eq!(plain, before);
+}
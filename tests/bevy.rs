@@ -0,0 +1,82 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "bevy")]
+
+use bevy::app::{App, TaskPoolPlugin};
+use bevy::asset::{AssetApp, AssetPlugin, AssetServer, Assets, Handle, LoadState};
+use bevy::color::Color;
+use bevy::image::Image;
+use bevy::mesh::{Mesh, VertexAttributeValues};
+use bevy::pbr::StandardMaterial;
+use obj::{ObjAsset, ObjPlugin};
+
+#[test]
+fn load_obj_asset() {
+    let dir = std::env::temp_dir().join("obj_bevy_load_obj_asset");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("quad.obj"),
+        "mtllib quad.mtl\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+         usemtl red\nf 1/1 2/2 3/3\nusemtl green\nf 1/1 3/3 4/4\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("quad.mtl"),
+        "newmtl red\nKd 1 0 0\nnewmtl green\nKd 0 1 0\nd 0.5\n",
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        AssetPlugin {
+            file_path: dir.to_string_lossy().into_owned(),
+            ..AssetPlugin::default()
+        },
+        ObjPlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<StandardMaterial>()
+    .init_asset::<Image>();
+
+    let handle: Handle<ObjAsset> = app.world().resource::<AssetServer>().load("quad.obj");
+    for _ in 0..1000 {
+        app.update();
+        match app.world().resource::<AssetServer>().load_state(&handle) {
+            LoadState::Loaded => break,
+            LoadState::Failed(err) => panic!("failed to load: {}", err),
+            _ => std::thread::sleep(std::time::Duration::from_millis(5)),
+        }
+    }
+
+    let asset = app.world().resource::<Assets<ObjAsset>>().get(&handle).unwrap();
+    assert_eq!(asset.meshes.len(), 2);
+    assert_eq!(asset.materials.len(), 2);
+
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    let mesh = meshes.get(&asset.meshes[1]).unwrap();
+    assert_eq!(mesh.count_vertices(), 3);
+    assert_eq!(mesh.indices().unwrap().len(), 3);
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_some());
+    match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => assert_eq!(uvs[2], [0.0, 0.0]),
+        other => panic!("unexpected texture coordinates: {:?}", other),
+    }
+
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    let green = materials.get(&asset.materials[1]).unwrap();
+    assert_eq!(green.base_color, Color::linear_rgba(0.0, 1.0, 0.0, 0.5));
+    assert_eq!(green.alpha_mode, bevy::material::AlphaMode::Blend);
+}
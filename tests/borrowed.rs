@@ -0,0 +1,62 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::borrow::Cow;
+
+use obj::{ObjData, ObjRef};
+
+static MESH: &str = "
+mtllib my  materials.mtl
+v 0 0 0 1 0 0
+v 1 0 0
+v 1 1 0
+vt 0 0
+o thing
+g side
+usemtl red
+f 1/1 2/1 3/1
+usemtl blue
+f 3 2 1
+l 1 2
+";
+
+#[test]
+fn same_as_owned() {
+    let borrowed = ObjRef::parse(MESH).unwrap();
+    assert_eq!(borrowed.into_owned(), ObjData::load_buf(MESH.as_bytes()).unwrap());
+
+    let sponza = std::fs::read_to_string("test_assets/sponza.obj").unwrap();
+    let borrowed = ObjRef::parse(&sponza).unwrap();
+    assert_eq!(borrowed.into_owned(), ObjData::load_buf(sponza.as_bytes()).unwrap());
+}
+
+#[test]
+fn names_borrow_from_source() {
+    let data = ObjRef::parse(MESH).unwrap();
+    let group = &data.objects[0].groups[1];
+    assert!(matches!(data.objects[0].name, Cow::Borrowed("thing")));
+    assert!(matches!(group.name, Cow::Borrowed("side")));
+    assert_eq!(group.index, 1);
+    assert!(matches!(group.material, Some(Cow::Borrowed("blue"))));
+    // Irregular spacing in a file name is normalized, which needs a copy.
+    assert!(matches!(&data.material_libs[0], Cow::Owned(name) if name == "my materials.mtl"));
+}
+
+#[test]
+fn parse_error() {
+    assert!(ObjRef::parse("v 0 0\n").is_err());
+    assert!(ObjRef::parse("q 0 0\n").is_err());
+}
@@ -0,0 +1,109 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static TWO_BOXES: &str = "
+v 0 0 0
+v 1 0 0
+v 1 2 0
+v 0 2 0
+v 10 10 10
+v 11 10 10
+v 11 11 10
+o first
+f 1 2 3 4
+o second
+g a
+f 5 6 7
+";
+
+#[test]
+fn mesh_bounds() {
+    let obj = ObjData::load_buf(TWO_BOXES.as_bytes()).unwrap();
+    let bbox = obj.bounding_box().unwrap();
+    assert_eq!(bbox.min, [0., 0., 0.]);
+    assert_eq!(bbox.max, [11., 11., 10.]);
+
+    let sphere = obj.bounding_sphere().unwrap();
+    for p in &obj.position {
+        let d: f32 = (0..3).map(|i| (p[i] - sphere.center[i]).powi(2)).sum::<f32>().sqrt();
+        assert!(d <= sphere.radius + 1e-4);
+    }
+}
+
+#[test]
+fn object_and_group_bounds() {
+    let obj = ObjData::load_buf(TWO_BOXES.as_bytes()).unwrap();
+    let first = &obj.objects[0];
+    assert_eq!(&*first.name, "first");
+    let bbox = first.bounding_box(&obj).unwrap();
+    assert_eq!(bbox.min, [0., 0., 0.]);
+    assert_eq!(bbox.max, [1., 2., 0.]);
+    assert_eq!(bbox.center(), [0.5, 1., 0.]);
+
+    let group = &obj.objects[1].groups[0];
+    let bbox = group.bounding_box(&obj).unwrap();
+    assert_eq!(bbox.min, [10., 10., 10.]);
+    assert_eq!(bbox.size(), [1., 1., 0.]);
+    let sphere = group.bounding_sphere(&obj).unwrap();
+    assert!(sphere.radius >= 0.5_f32.sqrt() - 1e-5);
+}
+
+#[test]
+fn empty_bounds() {
+    let obj = ObjData::default();
+    assert!(obj.bounding_box().is_none());
+    assert!(obj.bounding_sphere().is_none());
+}
+
+#[test]
+fn uv_bounds() {
+    let data = ObjData::load_buf(
+        "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vt 1 0.5
+vt -0.5 2.5
+vt 9 9
+g inside
+f 1/1 2/2 3/2
+g tiled
+f 1/1 2/3 3
+g plain
+f 1 2 3
+"
+        .as_bytes(),
+    )
+    .unwrap();
+    let groups = &data.objects[0].groups;
+
+    let inside = groups[0].uv_bounds(&data).unwrap();
+    assert_eq!(inside.min, [0.0, 0.0]);
+    assert_eq!(inside.max, [1.0, 0.5]);
+    assert!(!inside.exceeds_unit_square());
+    assert_eq!(inside.tiles(), [1.0, 1.0]);
+
+    let tiled = groups[1].uv_bounds(&data).unwrap();
+    assert!(tiled.exceeds_unit_square());
+    assert_eq!(tiled.tiles(), [1.0, 3.0]);
+
+    assert_eq!(groups[2].uv_bounds(&data), None);
+    // The unused `vt 9 9` is not included.
+    assert_eq!(data.uv_bounds().unwrap().max, [1.0, 2.5]);
+}
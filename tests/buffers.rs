@@ -0,0 +1,116 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, Vertex};
+
+static SEAM: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vt 0.5 0.5
+vn 0 0 1
+usemtl a
+f 1/1/1 2/2/1 3/3/1 4/4/1
+g other
+usemtl b
+f 1/5/1 3/3/1 4/4/1
+o second
+usemtl a
+f 1/1/1 2/2/1 3/3/1
+";
+
+#[test]
+fn indexed_buffers_per_group() {
+    let obj = ObjData::load_buf(SEAM.as_bytes()).unwrap();
+    let buffers = obj.to_indexed_buffers();
+
+    // Vertex 1 is split at the UV seam, all other corners are shared.
+    assert_eq!(buffers.vertices.len(), 5);
+    assert_eq!(
+        buffers.vertices[0],
+        Vertex {
+            position: [0., 0., 0.],
+            texture: [0., 0.],
+            normal: [0., 0., 1.],
+        }
+    );
+    assert_eq!(buffers.submeshes.len(), 3);
+    assert_eq!(buffers.submeshes[0].indices, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(buffers.submeshes[1].indices, vec![4, 2, 3]);
+    assert_eq!(buffers.submeshes[1].group, "other");
+    assert_eq!(buffers.submeshes[2].object, "second");
+    assert_eq!(buffers.submeshes[2].indices, vec![0, 1, 2]);
+}
+
+#[test]
+fn indexed_buffers_per_material() {
+    let obj = ObjData::load_buf(SEAM.as_bytes()).unwrap();
+    let buffers = obj.to_indexed_buffers_by_material();
+    assert_eq!(buffers.submeshes.len(), 2);
+    assert_eq!(buffers.submeshes[0].material.as_ref().unwrap().name(), "a");
+    assert_eq!(buffers.submeshes[0].indices, vec![0, 1, 2, 0, 2, 3, 0, 1, 2]);
+    assert_eq!(buffers.submeshes[1].indices, vec![4, 2, 3]);
+}
+
+#[test]
+fn polygons_with_missing_attributes_are_left_out() {
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 9\nf 1 2 3\n";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let buffers = obj.to_indexed_buffers();
+    assert_eq!(buffers.vertices.len(), 3);
+    assert_eq!(buffers.submeshes[0].indices, vec![0, 1, 2]);
+}
+
+#[test]
+fn tangents_align_with_vertices() {
+    let obj = ObjData::load_buf(SEAM.as_bytes()).unwrap();
+    let buffers = obj.to_indexed_buffers();
+    let tangents = buffers.generate_tangents();
+    assert_eq!(tangents.len(), buffers.vertices.len());
+    assert!((tangents[1][0] - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn vertex_formats() {
+    use obj::{Position, PositionTexture};
+
+    let obj = ObjData::load_buf(SEAM.as_bytes()).unwrap();
+    let buffers = obj.to_indexed_buffers();
+    let vertex = buffers.vertices[0];
+    let positions = buffers.clone().into_format::<Position>();
+    assert_eq!(positions.vertices[0].position, vertex.position);
+    assert_eq!(positions.submeshes, buffers.submeshes);
+    let textured = buffers.into_format::<PositionTexture>();
+    assert_eq!(textured.vertices[0].texture, vertex.texture);
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn vertex_formats_are_pod() {
+    use obj::PositionTextureNormal;
+
+    let obj = ObjData::load_buf(SEAM.as_bytes()).unwrap();
+    let buffers = obj.to_indexed_buffers().into_format::<PositionTextureNormal>();
+    let bytes: &[u8] = bytemuck::cast_slice(&buffers.vertices);
+    assert_eq!(bytes.len(), buffers.vertices.len() * 32);
+    let floats: &[f32] = bytemuck::cast_slice(bytes);
+    assert_eq!(floats[..3], buffers.vertices[0].position);
+}
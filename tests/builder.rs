@@ -0,0 +1,80 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, ObjBuilder, ObjData, ObjMaterial};
+
+#[test]
+fn build_quad() {
+    let mut builder = ObjBuilder::new();
+    builder.object("quad").group("front").material("red");
+    let corners = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    let vertices: Vec<_> = corners
+        .iter()
+        .map(|&p| builder.vertex(p, None, Some([0.0, 0.0, 1.0])))
+        .collect();
+    builder.face(vertices.clone());
+    // Adding an existing vertex again reuses its attributes.
+    let again = builder.vertex(corners[2], None, Some([0.0, 0.0, 1.0]));
+    assert_eq!(again, vertices[2]);
+    assert_eq!(IndexTuple::from(again), IndexTuple::new(2, None, Some(0)));
+
+    let data = builder.build();
+    assert_eq!(data.position.len(), 4);
+    assert_eq!(data.normal.len(), 1);
+    assert_eq!(data.objects.len(), 1);
+    let group = &data.objects[0].groups[0];
+    assert_eq!(&*group.name, "front");
+    assert_eq!(group.material.as_ref().map(ObjMaterial::name), Some("red"));
+    assert_eq!(group.polys[0].0.len(), 4);
+}
+
+#[test]
+fn same_structure_as_parser() {
+    let source = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+f 1/1 2/1 3/1
+o second
+g walls
+usemtl brick
+f 3/1 2/1 1/1
+usemtl stone
+l 1/1 2/1
+g floor
+f 1 2 3
+";
+    let parsed = ObjData::load_buf(source.as_bytes()).unwrap();
+
+    let mut b = ObjBuilder::new();
+    let uv = Some([0.0, 0.0]);
+    let v1 = b.vertex([0.0, 0.0, 0.0], uv, None);
+    let v2 = b.vertex([1.0, 0.0, 0.0], uv, None);
+    let v3 = b.vertex([0.0, 1.0, 0.0], uv, None);
+    b.face(vec![v1, v2, v3]);
+    b.object("second")
+        .group("walls")
+        .material("brick")
+        .face(vec![v3, v2, v1]);
+    b.material("stone").line(vec![v1, v2]);
+    let p1 = b.vertex([0.0, 0.0, 0.0], None, None);
+    let p2 = b.vertex([1.0, 0.0, 0.0], None, None);
+    let p3 = b.vertex([0.0, 1.0, 0.0], None, None);
+    b.group("floor").face(vec![p1, p2, p3]);
+
+    assert_eq!(b.build(), parsed);
+}
@@ -0,0 +1,94 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "bvh")]
+
+use obj::bvh::{Bvh, Ray};
+use obj::ObjData;
+
+fn cube_grid() -> ObjData {
+    // A row of unit cubes along the X axis, so that the hierarchy has several levels.
+    let mut source = String::new();
+    for i in 0..8 {
+        let x = (2 * i) as f32;
+        for &(dx, dy, dz) in &[
+            (0, 0, 0),
+            (1, 0, 0),
+            (1, 1, 0),
+            (0, 1, 0),
+            (0, 0, 1),
+            (1, 0, 1),
+            (1, 1, 1),
+            (0, 1, 1),
+        ] {
+            source += &format!("v {} {} {}\n", x + dx as f32, dy, dz);
+        }
+        let b = 8 * i + 1;
+        for face in &[
+            [0, 3, 2, 1],
+            [4, 5, 6, 7],
+            [0, 1, 5, 4],
+            [1, 2, 6, 5],
+            [2, 3, 7, 6],
+            [3, 0, 4, 7],
+        ] {
+            source += &format!("f {} {} {} {}\n", b + face[0], b + face[1], b + face[2], b + face[3]);
+        }
+    }
+    ObjData::load_buf(source.as_bytes()).unwrap()
+}
+
+#[test]
+fn ray_casts() {
+    let bvh = Bvh::new(&cube_grid());
+
+    // Straight down onto the fourth cube.
+    let ray = Ray {
+        origin: [6.5, 0.25, 5.],
+        direction: [0., 0., -1.],
+    };
+    let hit = bvh.cast_ray(&ray, f32::INFINITY).unwrap();
+    assert!((hit.distance - 4.).abs() < 1e-6);
+    assert_eq!(hit.polygon, 3 * 6 + 1);
+    assert!(bvh.occluded(&ray, 10.));
+    assert!(!bvh.occluded(&ray, 3.));
+    assert!(bvh.cast_ray(&ray, 3.).is_none());
+
+    // Along the row, hitting the near side of the first cube.
+    let ray = Ray {
+        origin: [-3., 0.5, 0.5],
+        direction: [1., 0., 0.],
+    };
+    let hit = bvh.cast_ray(&ray, f32::INFINITY).unwrap();
+    assert!((hit.distance - 3.).abs() < 1e-6);
+    assert_eq!(hit.polygon, 5);
+
+    // Between the cubes.
+    let ray = Ray {
+        origin: [1.5, 0.5, 5.],
+        direction: [0., 0., -1.],
+    };
+    assert!(bvh.cast_ray(&ray, f32::INFINITY).is_none());
+}
+
+#[test]
+fn closest_points() {
+    let bvh = Bvh::new(&cube_grid());
+    let closest = bvh.closest_point([8.5, 0.5, 3.]).unwrap();
+    assert!((closest.distance - 2.).abs() < 1e-6);
+    assert_eq!(closest.point, [8.5, 0.5, 1.]);
+    assert_eq!(closest.polygon, 4 * 6 + 1);
+
+    assert!(Bvh::new(&ObjData::default()).closest_point([0.; 3]).is_none());
+}
@@ -0,0 +1,90 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::sync::Arc;
+
+use obj::{CacheError, Mtl, ObjData, ObjMaterial};
+
+static SOURCE: &str = "
+mtllib scene.mtl
+shadow_obj shadow.obj
+v 0 0 0 1 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 1
+vn 0 0 1
+o quad
+g front
+usemtl red
+s 2
+f 1/1/1 2/2/1 3//1 4/2
+usemtl missing
+lod 3
+f 1 3 4
+o wire
+l 1/1 2 3
+";
+
+fn load() -> ObjData {
+    let mut data = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let mut mtl = Mtl::new("scene.mtl".to_string());
+    mtl.reload_str("newmtl red\nKd 1 0 0\nNs 12.5\nmap_Kd -s 2 2 red.png\n")
+        .unwrap();
+    data.material_libs = vec![mtl];
+    data.resolve_materials();
+    data
+}
+
+#[test]
+fn round_trip() {
+    let data = load();
+    let mut cache = Vec::new();
+    data.write_cache(&mut cache).unwrap();
+    let loaded = ObjData::read_cache(cache.as_slice()).unwrap();
+    assert_eq!(loaded, data);
+
+    // Resolved materials are shared with the library again.
+    match &loaded.objects[0].groups[0].material {
+        Some(ObjMaterial::Mtl(red)) => assert!(Arc::ptr_eq(red, &loaded.material_libs[0].materials[0])),
+        other => panic!("unexpected material {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_other_input() {
+    let data = load();
+    let mut cache = Vec::new();
+    data.write_cache(&mut cache).unwrap();
+
+    assert!(matches!(
+        ObjData::read_cache(SOURCE.as_bytes()),
+        Err(CacheError::NotACache)
+    ));
+    assert!(matches!(ObjData::read_cache(&b"OBJ"[..]), Err(CacheError::NotACache)));
+
+    let mut newer = cache.clone();
+    newer[8] = 4;
+    assert!(matches!(
+        ObjData::read_cache(newer.as_slice()),
+        Err(CacheError::UnsupportedVersion(4))
+    ));
+
+    for len in [12, cache.len() / 2, cache.len() - 1] {
+        assert!(matches!(ObjData::read_cache(&cache[..len]), Err(CacheError::Malformed)));
+    }
+}
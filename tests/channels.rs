@@ -0,0 +1,71 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{AttributeChannels, ObjData, ObjError, WeldConfig};
+
+static SOURCE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 0 0\nv 5 5 5\nf 1 2 3\nf 3 4 1\n";
+
+#[test]
+fn survive_weld_and_compact() {
+    let mut obj = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let mut channels = obj.attribute_channels();
+    let ao = channels.register("ao", 1);
+    ao.values.copy_from_slice(&[0.0, 0.1, 0.2, 0.3, 0.4]);
+    let weights = channels.register("weights", 2);
+    weights.vertex_mut(4).copy_from_slice(&[0.5, 0.5]);
+    assert_eq!(channels.len(), 5);
+
+    let (_, report) = obj.weld_vertices_with_remap(WeldConfig::default());
+    channels.apply(&obj, &report);
+    // The duplicate of the second position keeps the values of the first of them.
+    assert_eq!(channels.get("ao").unwrap().values, [0.0, 0.1, 0.2, 0.4]);
+
+    let report = obj.compact_with_remap();
+    channels.apply(&obj, &report);
+    assert_eq!(channels.len(), 3);
+    assert_eq!(channels.get("ao").unwrap().values, [0.0, 0.1, 0.2]);
+    assert_eq!(channels.get("weights").unwrap().values, [0.0; 6]);
+}
+
+#[test]
+fn round_trip_as_comments() {
+    let obj = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let mut channels = obj.attribute_channels();
+    channels
+        .register("weights", 2)
+        .vertex_mut(1)
+        .copy_from_slice(&[0.25, 0.75]);
+    channels.register("ao", 1).values[4] = 1.0;
+
+    let mut out = Vec::new();
+    obj.write_with_channels(&mut out, &channels).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("#channel weights 2\n#cv 0 0\n#cv 0.25 0.75\n"));
+
+    assert_eq!(ObjData::load_buf(text.as_bytes()).unwrap(), obj);
+    let read = AttributeChannels::parse(&text).unwrap();
+    assert_eq!(read, channels);
+    assert_eq!(read.iter().map(|c| &*c.name).collect::<Vec<_>>(), ["weights", "ao"]);
+
+    assert!(matches!(
+        AttributeChannels::parse("#channel ao 1\n#cv 1\n#channel w 2\n#cv 1 2\n#cv 3 4\n"),
+        Err(ObjError::ArgumentListFailure { line_number: 2, .. })
+    ));
+    assert!(matches!(
+        AttributeChannels::parse("#channel ao 1\n#cv 1 2\n"),
+        Err(ObjError::ArgumentListFailure { line_number: 1, .. })
+    ));
+}
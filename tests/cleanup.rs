@@ -0,0 +1,76 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static DEGENERATE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 2 0 0
+v 0.5 0.000001 0
+f 1 2 3
+f 1 2 1
+f 1 2 4
+f 1 5 2
+f 1 2
+";
+
+#[test]
+fn remove_degenerate_faces() {
+    let mut obj = ObjData::load_buf(DEGENERATE.as_bytes()).unwrap();
+    let report = obj.remove_degenerate_faces(1e-5);
+    assert_eq!(report.too_few_vertices, 1);
+    assert_eq!(report.repeated_indices, 1);
+    // A collinear triangle and a sliver.
+    assert_eq!(report.zero_area, 2);
+    assert_eq!(report.total(), 4);
+    assert_eq!(obj.objects[0].groups[0].polys.len(), 1);
+}
+
+#[test]
+fn zero_epsilon_keeps_slivers() {
+    let mut obj = ObjData::load_buf(DEGENERATE.as_bytes()).unwrap();
+    let report = obj.remove_degenerate_faces(0.0);
+    assert_eq!(report.zero_area, 1);
+    assert_eq!(obj.objects[0].groups[0].polys.len(), 2);
+}
+
+#[test]
+fn dedup_faces() {
+    let source = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+f 1 2 3 4
+f 3 4 1 2
+f 1 3 2 4
+g other
+f 4 3 2 1
+f 1 2 3
+f 2/1 3/1 1/1
+";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let report = obj.dedup_faces();
+    assert_eq!(report.total(), 3);
+    assert_eq!(report.groups.len(), 2);
+    assert_eq!((report.groups[0].group, &report.groups[0].polygons), (0, &vec![1]));
+    assert_eq!((report.groups[1].group, &report.groups[1].polygons), (1, &vec![0, 2]));
+    assert_eq!(obj.objects[0].groups[0].polys.len(), 2);
+    assert_eq!(obj.objects[0].groups[1].polys.len(), 1);
+}
@@ -0,0 +1,76 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "cli")]
+
+use std::path::Path;
+use std::process::Command;
+
+use obj::ObjData;
+
+fn obj_tool(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_obj-tool"))
+        .args(args)
+        .output()
+        .unwrap();
+    (output.status.success(), String::from_utf8(output.stdout).unwrap())
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn cli_commands() {
+    let dir = std::env::temp_dir().join("obj_cli_commands");
+    std::fs::create_dir_all(&dir).unwrap();
+    let quad = dir.join("quad.obj");
+    std::fs::write(&quad, "v 0 0 0\nv 2 0 0\nv 2 2 0\nv 0 2 0\nf 1 2 3 4\n").unwrap();
+    let broken = dir.join("broken.obj");
+    std::fs::write(&broken, "v 0 0 0\nf 1 2 3\n").unwrap();
+
+    let (ok, out) = obj_tool(&["validate", path_str(&quad)]);
+    assert!(ok);
+    assert_eq!(out, "ok\n");
+    let (ok, out) = obj_tool(&["validate", path_str(&broken)]);
+    assert!(!ok);
+    assert!(out.contains("position index 3 is out of range"));
+
+    let (ok, out) = obj_tool(&["stats", path_str(&quad)]);
+    assert!(ok);
+    assert!(out.contains("1 quads"));
+
+    let triangles = dir.join("triangles.obj");
+    assert!(obj_tool(&["triangulate", path_str(&quad), path_str(&triangles)]).0);
+    assert_eq!(
+        ObjData::load_buf(std::fs::File::open(&triangles).unwrap())
+            .unwrap()
+            .stats()
+            .triangles,
+        2
+    );
+
+    let stl = dir.join("quad.stl");
+    assert!(obj_tool(&["convert", path_str(&quad), path_str(&stl)]).0);
+    assert_eq!(obj::stl::load(&stl).unwrap().stats().triangles, 2);
+
+    let normalized = dir.join("normalized.ply");
+    assert!(obj_tool(&["normalize", path_str(&quad), path_str(&normalized)]).0);
+    let bounds = obj::ply::load(&normalized).unwrap().bounding_box().unwrap();
+    assert_eq!(bounds.center(), [0.0; 3]);
+    assert_eq!(bounds.size(), [1.0, 1.0, 0.0]);
+
+    assert!(!obj_tool(&["convert", path_str(&quad)]).0);
+    assert!(!obj_tool(&["unknown", path_str(&quad)]).0);
+}
@@ -0,0 +1,87 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "collada")]
+
+use std::path::PathBuf;
+
+use obj::{Obj, ObjData};
+
+static SCENE: &str = "
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 5 5 5
+vt 0 0
+vt 1 0
+vt 1 1
+vn 0 0 1
+o quad
+usemtl red
+f 1/1/1 2/2/1 3/3/1 4/1/1
+usemtl <blue>
+f 1 3 4
+o wire
+l 1 5
+";
+
+static MTL: &str = "
+newmtl red
+Kd 1 0 0
+map_Kd -s 2 2 red.png
+";
+
+fn write(data: &ObjData) -> String {
+    let mut out = Vec::new();
+    data.write_collada(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn geometry() {
+    let data = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    let dae = write(&data);
+    assert!(dae.starts_with("<?xml"));
+    assert!(dae.contains("<geometry id=\"geometry0\" name=\"quad\">"));
+    // The quad only uses the first four positions.
+    assert!(dae
+        .contains("<float_array id=\"geometry0-positions-array\" count=\"12\">0 0 0 1 0 0 1 1 0 0 1 0</float_array>"));
+    assert!(dae.contains("<input semantic=\"NORMAL\" source=\"#geometry0-normals\" offset=\"1\"/>"));
+    assert!(dae.contains("<vcount>4</vcount>"));
+    assert!(dae.contains("<p>0 0 0 1 0 1 2 0 2 3 0 0</p>"));
+    // The triangle has no texture coordinates or normals.
+    assert!(dae.contains("<polylist material=\"material1\" count=\"1\">\n          <input semantic=\"VERTEX\" source=\"#geometry0-vertices\" offset=\"0\"/>\n          <vcount>3</vcount>"));
+    assert!(dae.contains("<linestrips count=\"1\">"));
+    assert!(dae.contains("<float_array id=\"geometry1-positions-array\" count=\"6\">0 0 0 5 5 5</float_array>"));
+    assert!(dae.contains("name=\"&lt;blue&gt;\""));
+    assert!(dae.contains("<instance_material symbol=\"material0\" target=\"#material0\">"));
+    assert_eq!(dae.matches("<node ").count(), 2);
+}
+
+#[test]
+fn materials() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(SCENE.as_bytes()).unwrap(),
+        path: PathBuf::new(),
+    };
+    // Only `red` is defined, `<blue>` stays a reference.
+    let _ = obj.load_mtls_fn(|_, _| Ok(MTL.as_bytes()));
+    let dae = write(&obj.data);
+    assert!(dae.contains("<image id=\"image0\"><init_from>red.png</init_from></image>"));
+    assert!(dae.contains("<texture texture=\"sampler0\" texcoord=\"UVMap\"/>"));
+    assert!(dae.contains("<material id=\"material0\" name=\"red\"><instance_effect url=\"#effect0\"/></material>"));
+    assert!(dae.contains("<diffuse><color>0.8 0.8 0.8 1</color></diffuse>"));
+}
@@ -0,0 +1,193 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, ObjData, WriteConfig};
+
+static ORPHANS: &str = "
+v 0 0 0
+v 9 9 9
+v 1 0 0
+v 1 1 0
+vt 0 0
+vt 0.5 0.5
+vn 0 0 1
+vn 1 0 0
+vn 0 1 0
+f 1/1/3 3/1/3 4/1/3
+";
+
+#[test]
+fn compact_drops_unreferenced_attributes() {
+    let mut obj = ObjData::load_buf(ORPHANS.as_bytes()).unwrap();
+    let remap = obj.compact();
+
+    assert_eq!(obj.position, vec![[0., 0., 0.], [1., 0., 0.], [1., 1., 0.]]);
+    assert_eq!(obj.texture, vec![[0., 0.]]);
+    assert_eq!(obj.normal, vec![[0., 1., 0.]]);
+
+    assert_eq!(remap.position, vec![Some(0), None, Some(1), Some(2)]);
+    assert_eq!(remap.texture, vec![Some(0), None]);
+    assert_eq!(remap.normal, vec![None, None, Some(0)]);
+
+    assert_eq!(
+        obj.objects[0].groups[0].polys[0].0,
+        vec![
+            IndexTuple::new(0, Some(0), Some(0)),
+            IndexTuple::new(1, Some(0), Some(0)),
+            IndexTuple::new(2, Some(0), Some(0)),
+        ]
+    );
+}
+
+#[test]
+fn missing_attributes_stay_missing() {
+    let mut obj = ObjData::load_buf(format!("{}f 1/1/3 3/4/3 6/1/3\n", ORPHANS).as_bytes()).unwrap();
+    obj.compact();
+    assert_eq!(obj.position.len(), 3);
+    assert_eq!(
+        obj.objects[0].groups[0].polys[1].0,
+        vec![
+            IndexTuple::new(0, Some(0), Some(0)),
+            IndexTuple::new(1, Some(2), Some(0)),
+            IndexTuple::new(4, Some(0), Some(0)),
+        ]
+    );
+    assert!(obj.validate_indices().is_err());
+}
+
+#[test]
+fn compact_is_idempotent() {
+    let mut obj = ObjData::load_buf(ORPHANS.as_bytes()).unwrap();
+    obj.compact();
+    let compacted = obj.clone();
+    let remap = obj.compact();
+    assert_eq!(obj, compacted);
+    assert!(remap.position.iter().enumerate().all(|(i, &j)| j == Some(i)));
+}
+
+#[test]
+fn reorder_spatially() {
+    let source = "
+v 1 1 1
+v 0 0 0
+v 1 0 0
+v 0 1 1
+vn 0 0 1
+vn 0 0 -1
+f 1//1 2//2 3//1
+f 4 2 3
+";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let before = obj.clone();
+    let remap = obj.reorder_spatially();
+    assert_eq!(remap.position, vec![Some(3), Some(0), Some(1), Some(2)]);
+    assert_eq!(remap.normal, vec![Some(1), Some(0)]);
+    assert_eq!(obj.position[0], [0., 0., 0.]);
+
+    // Polygons still refer to the same values.
+    let resolve = |obj: &ObjData| {
+        obj.objects[0].groups[0]
+            .polys
+            .iter()
+            .flat_map(|p| {
+                p.0.iter()
+                    .map(|t| (obj.position[t.position], t.normal.map(|n| obj.normal[n])))
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(resolve(&obj), resolve(&before));
+}
+
+#[test]
+fn vertex_colors_follow_positions() {
+    let source = "v 5 5 5 0 0 0\nv 0 0 0 1 0 0\nv 1 0 0 0 1 0\nv 0 1 0 0 0 1\nf 2 3 4\n";
+    let mut data = ObjData::load_buf(source.as_bytes()).unwrap();
+    data.compact();
+    assert_eq!(data.color, vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
+    let before: Vec<_> = data.position.iter().copied().zip(data.color.iter().copied()).collect();
+    data.reorder_spatially();
+    for (p, c) in data.position.iter().zip(&data.color) {
+        assert!(before.contains(&(*p, *c)));
+    }
+}
+
+static HELPERS: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 5 5 5
+v 6 5 5
+v 6 6 5
+o body
+g visible
+f 1 2 3
+g collision
+f 4 5 6
+o helper_lod1
+f 3 2 1
+";
+
+#[test]
+fn retain_groups_drops_unreferenced_vertices() {
+    let mut obj = ObjData::load_buf(HELPERS.as_bytes()).unwrap();
+    let remap = obj.retain_groups(|_, group| &*group.name != "collision");
+    assert_eq!(obj.position.len(), 3);
+    assert_eq!(remap.position[3], None);
+    let names: Vec<_> = obj.objects[0].groups.iter().map(|g| &*g.name).collect();
+    assert_eq!(names, ["visible"]);
+    assert_eq!(obj.objects.len(), 2);
+}
+
+#[test]
+fn retain_objects_by_name() {
+    let mut obj = ObjData::load_buf(HELPERS.as_bytes()).unwrap();
+    obj.objects[0].retain_groups(|group| &*group.name == "collision");
+    assert_eq!(obj.position.len(), 6);
+
+    obj.retain_objects(|object| !object.name.starts_with("helper"));
+    assert_eq!(obj.objects.len(), 1);
+    assert_eq!(obj.position, [[5.0, 5.0, 5.0], [6.0, 5.0, 5.0], [6.0, 6.0, 5.0]]);
+    let first = obj.objects[0].groups[0].polys[0].0[0];
+    assert_eq!(first, IndexTuple::new(0, None, None));
+}
+
+#[test]
+fn prune_unreferenced_on_write() {
+    let obj = ObjData::load_buf(ORPHANS.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    obj.write_to_buf_with(&mut out, WriteConfig::new().prune_unreferenced(true))
+        .unwrap();
+    let pruned = ObjData::load_buf(out.as_slice()).unwrap();
+
+    // Positions are kept, even the unreferenced second one.
+    assert_eq!(pruned.position, obj.position);
+    assert_eq!(pruned.texture, vec![[0., 0.]]);
+    assert_eq!(pruned.normal, vec![[0., 1., 0.]]);
+    assert_eq!(
+        pruned.objects[0].groups[0].polys[0].0,
+        vec![
+            IndexTuple::new(0, Some(0), Some(0)),
+            IndexTuple::new(2, Some(0), Some(0)),
+            IndexTuple::new(3, Some(0), Some(0)),
+        ]
+    );
+    assert_eq!(obj.texture.len(), 2);
+
+    let broken = ObjData::load_buf("v 0 0 0\nf 1/1 1/1 1/1\n".as_bytes()).unwrap();
+    let config = WriteConfig::new().prune_unreferenced(true);
+    assert!(broken.write_to_buf_with(&mut Vec::new(), config).is_err());
+}
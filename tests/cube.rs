@@ -12,6 +12,8 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
+#![cfg(feature = "std")]
+
 #[cfg(feature = "genmesh")]
 use genmesh::{MapToVertices, Polygon};
 #[cfg(feature = "genmesh")]
@@ -19,7 +21,7 @@ use obj::IndexTuple;
 use obj::ObjData;
 use std::io::BufReader;
 
-static SQUARE: &'static str = "
+static SQUARE: &str = "
 v 0 1 0
 v 0 0 0
 v 1 0 0
@@ -27,7 +29,10 @@ v 1 1 0
 f 1 2 3 4
 ";
 
-static SQUARE_VBO: &'static [[f32; 3]] = &[[0., 1., 0.], [0., 0., 0.], [1., 0., 0.], [1., 1., 0.]];
+#[cfg(feature = "genmesh")]
+type Vertex = ([f32; 3], [f32; 2], [f32; 3]);
+
+static SQUARE_VBO: &[[f32; 3]] = &[[0., 1., 0.], [0., 0., 0.], [1., 0., 0.], [1., 1., 0.]];
 
 #[test]
 #[cfg(feature = "genmesh")]
@@ -43,16 +48,16 @@ fn test_load_square() {
 
     for o in &obj.objects {
         for g in &o.groups {
-            let p: Vec<Polygon<([f32; 3], [f32; 2], [f32; 3])>> = g
+            let p: Vec<Polygon<Vertex>> = g
                 .polys
                 .iter()
                 .cloned()
                 .map(|x| x.into_genmesh())
-                .vertex(|IndexTuple(p, t, n)| {
+                .vertex(|v: IndexTuple| {
                     (
-                        obj.position[p],
-                        t.map_or([0., 0.], |t| obj.texture[t]),
-                        n.map_or([1., 0., 0.], |n| obj.normal[n]),
+                        obj.position[v.position],
+                        v.texture.map_or([0., 0.], |t| obj.texture[t]),
+                        v.normal.map_or([1., 0., 0.], |n| obj.normal[n]),
                     )
                 })
                 .collect();
@@ -73,7 +78,7 @@ fn test_load_square_nodeps() {
     }
 }
 
-static CUBE: &'static str = "
+static CUBE: &str = "
 v 0 1 1
 v 0 0 1
 v 1 0 1
@@ -100,7 +105,7 @@ f 2 6 7 3
 # 6 elements
 ";
 
-static CUBE_VBO: &'static [[f32; 3]] = &[
+static CUBE_VBO: &[[f32; 3]] = &[
     [0., 1., 1.],
     [0., 0., 1.],
     [1., 0., 1.],
@@ -111,7 +116,7 @@ static CUBE_VBO: &'static [[f32; 3]] = &[
     [1., 1., 0.],
 ];
 
-static CUBE_NAMES: &'static [&'static str] = &[
+static CUBE_NAMES: &[&str] = &[
     "front cube",
     "back cube",
     "right cube",
@@ -133,9 +138,9 @@ fn test_load_cube() {
     }
 
     for obj in &obj.objects {
-        assert_eq!(obj.name, "cube");
+        assert_eq!(&*obj.name, "cube");
         for (g, &name) in obj.groups.iter().zip(CUBE_NAMES.iter()) {
-            assert_eq!(name, g.name);
+            assert_eq!(name, &*g.name);
         }
     }
 }
@@ -152,14 +157,14 @@ fn test_load_cube_nodeps() {
     }
 
     for obj in &obj.objects {
-        assert_eq!(obj.name, "cube");
+        assert_eq!(&*obj.name, "cube");
         for (g, &name) in obj.groups.iter().zip(CUBE_NAMES.iter()) {
-            assert_eq!(name, g.name);
+            assert_eq!(name, &*g.name);
         }
     }
 }
 
-static CUBE_NEGATIVE_VBO: &'static [[f32; 3]] = &[
+static CUBE_NEGATIVE_VBO: &[[f32; 3]] = &[
     [0., 1., 1.],
     [0., 0., 1.],
     [1., 0., 1.],
@@ -186,7 +191,7 @@ static CUBE_NEGATIVE_VBO: &'static [[f32; 3]] = &[
     [1., 0., 1.],
 ];
 
-static CUBE_NEGATIVE: &'static str = "
+static CUBE_NEGATIVE: &str = "
 v 0 1 1
 v 0 0 1
 v 1 0 1
@@ -0,0 +1,83 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, LoadConfig, ObjData, ObjData64};
+
+static SOURCE: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vn 0 0 1
+f 1/1/1 2/1/1 3/1/1
+v 1 0 0
+v 0 1 0
+v 1 1 0
+v 1 1 0 1 0 0
+vt 0 0
+vn 0 0 1
+f -4/-1/-1 -2/2/2 -3/1/1 -1
+l 7/2 6 1
+";
+
+#[test]
+fn dedup_attributes() {
+    let config = LoadConfig::new().dedup_attributes(true).check_indices(true);
+    let data = ObjData::load_buf_with(SOURCE.as_bytes(), config.clone()).unwrap();
+    assert_eq!(
+        data.position,
+        vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [1., 1., 0.], [1., 1., 0.]]
+    );
+    assert_eq!(data.color[3], [1., 1., 1.]);
+    assert_eq!(data.color[4], [1., 0., 0.]);
+    assert_eq!(data.texture.len(), 1);
+    assert_eq!(data.normal.len(), 1);
+
+    let group = &data.objects[0].groups[0];
+    assert_eq!(
+        group.polys[1].0.to_vec(),
+        vec![
+            IndexTuple::new(1, Some(0), Some(0)),
+            IndexTuple::new(3, Some(0), Some(0)),
+            IndexTuple::new(2, Some(0), Some(0)),
+            IndexTuple::new(4, None, None),
+        ]
+    );
+    // `l 7/2 6 1` refers to the last two positions by their number in the source.
+    assert_eq!(
+        group.lines[0].0,
+        vec![
+            IndexTuple::new(4, Some(0), None),
+            IndexTuple::new(3, None, None),
+            IndexTuple::new(0, None, None),
+        ]
+    );
+    assert!(data.layout.is_none());
+
+    let plain = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    assert_eq!(plain.position.len(), 7);
+    assert_eq!(plain.as_triangle_soup(), data.as_triangle_soup());
+
+    let wide = ObjData64::parse_str(SOURCE, config).unwrap();
+    assert_eq!(wide.position.len(), 5);
+}
+
+#[test]
+fn dedup_checks_source_indices() {
+    let config = LoadConfig::new().dedup_attributes(true).check_indices(true);
+    let source = "v 0 0 0\nv 0 0 0\nv 0 0 0\nf 1 2 3\nf 1 2 4\n";
+    assert!(ObjData::load_buf_with(source.as_bytes(), config).is_err());
+}
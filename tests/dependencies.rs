@@ -0,0 +1,136 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{Dependency, DependencyKind, Obj, ObjData, TextureSlot};
+use std::path::PathBuf;
+
+#[test]
+fn list_dependencies() {
+    let dir = std::env::temp_dir().join("obj_list_dependencies");
+    std::fs::create_dir_all(dir.join("maps")).unwrap();
+    std::fs::write(dir.join("maps/red.png"), b"").unwrap();
+    std::fs::write(
+        dir.join("maps/scene.mtl"),
+        "newmtl red\nmap_Kd -s 1 1 red.png\nmap_Ks missing.png\nnewmtl other\nmap_Kd red.png\n",
+    )
+    .unwrap();
+
+    let input =
+        "mtllib maps/scene.mtl\nshadow_obj shadow.obj\ntrace_obj trace.obj\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    let mut obj = Obj {
+        data: ObjData::load_buf(input.as_bytes()).unwrap(),
+        path: dir.clone(),
+    };
+    assert_eq!(obj.data.shadow_obj.as_deref(), Some("shadow.obj"));
+    assert_eq!(obj.data.trace_obj.as_deref(), Some("trace.obj"));
+
+    // Without loaded libraries no texture maps are known.
+    assert_eq!(obj.dependencies().len(), 3);
+
+    obj.load_mtls().unwrap();
+    let dependencies = obj.dependencies();
+    let canonical_dir = dir.canonicalize().unwrap();
+    assert_eq!(
+        dependencies,
+        vec![
+            Dependency {
+                kind: DependencyKind::MaterialLibrary,
+                reference: "maps/scene.mtl".to_string(),
+                path: canonical_dir.join("maps/scene.mtl"),
+            },
+            Dependency {
+                kind: DependencyKind::Texture(TextureSlot::Diffuse),
+                reference: "red.png".to_string(),
+                path: canonical_dir.join("maps/red.png"),
+            },
+            Dependency {
+                kind: DependencyKind::Texture(TextureSlot::Specular),
+                reference: "missing.png".to_string(),
+                path: dir.join("maps/missing.png"),
+            },
+            Dependency {
+                kind: DependencyKind::ShadowObject,
+                reference: "shadow.obj".to_string(),
+                path: dir.join("shadow.obj"),
+            },
+            Dependency {
+                kind: DependencyKind::TraceObject,
+                reference: "trace.obj".to_string(),
+                path: dir.join("trace.obj"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn shadow_and_trace_round_trip() {
+    let input = "shadow_obj shadow.obj\ntrace_obj trace.obj\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    let data = ObjData::load_buf_with(input.as_bytes(), Default::default()).unwrap();
+    let written = data.to_string();
+    assert!(written.contains("shadow_obj shadow.obj\n"));
+    assert!(written.contains("trace_obj trace.obj\n"));
+    let reparsed: ObjData = written.parse().unwrap();
+    assert_eq!(reparsed.shadow_obj, data.shadow_obj);
+    assert_eq!(reparsed.trace_obj, data.trace_obj);
+}
+
+#[test]
+fn verify_assets() {
+    let dir = std::env::temp_dir().join("obj_verify_assets");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("Maps")).unwrap();
+    std::fs::write(dir.join("Maps/stone.png"), b"").unwrap();
+    std::fs::write(dir.join("Maps/wood.png"), b"").unwrap();
+    std::fs::write(dir.join("Maps/Wood.PNG"), b"").unwrap();
+    std::fs::write(
+        dir.join("Maps/scene.mtl"),
+        "newmtl a\nmap_Kd stone.png\nmap_Ks WOOD.png\nmap_Ns missing.png\nmap_d ..\\Maps\\stone.png\n",
+    )
+    .unwrap();
+
+    let input = "mtllib maps/scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    let mut obj = Obj {
+        data: ObjData::load_buf(input.as_bytes()).unwrap(),
+        path: PathBuf::from("somewhere/else"),
+    };
+    let report = obj.verify_assets(&dir);
+    assert!(!report.is_ok());
+    assert!(report.found.is_empty());
+    assert_eq!(report.ambiguous.len(), 1);
+    assert_eq!(report.ambiguous[0].dependency.kind, DependencyKind::MaterialLibrary);
+    assert_eq!(report.ambiguous[0].candidates, [dir.join("Maps/scene.mtl")]);
+
+    obj.data.material_libs[0].filename = "Maps/scene.mtl".to_string();
+    obj.path = dir.clone();
+    obj.load_mtls().unwrap();
+    let report = obj.verify_assets(&dir);
+    let found: Vec<&str> = report.found.iter().map(|d| &*d.reference).collect();
+    assert_eq!(found, ["Maps/scene.mtl", "stone.png"]);
+    let missing: Vec<&str> = report.missing.iter().map(|d| &*d.reference).collect();
+    assert_eq!(missing, ["missing.png"]);
+    let ambiguous: Vec<(&str, Vec<PathBuf>)> = report
+        .ambiguous
+        .iter()
+        .map(|a| (&*a.dependency.reference, a.candidates.clone()))
+        .collect();
+    assert_eq!(
+        ambiguous,
+        [
+            ("WOOD.png", vec![dir.join("Maps/Wood.PNG"), dir.join("Maps/wood.png")]),
+            ("..\\Maps\\stone.png", vec![dir.join("Maps/../Maps/stone.png")]),
+        ]
+    );
+}
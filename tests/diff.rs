@@ -0,0 +1,105 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{diff, GroupId, MaterialChange, ObjData};
+
+fn load(input: &str) -> ObjData {
+    ObjData::load_buf(input.as_bytes()).unwrap()
+}
+
+const BEFORE: &str = "\
+o kept
+v 0 0 0
+v 1 0 0
+v 0 1 0
+g body
+usemtl red
+f 1 2 3
+g old
+f 1 2 3
+o gone
+l 1 2
+";
+
+#[test]
+fn identical_meshes() {
+    let data = load(BEFORE);
+    let report = diff(&data, &data);
+    assert!(report.is_empty());
+    assert_eq!(report.to_string(), "no changes\n");
+}
+
+#[test]
+fn structural_changes() {
+    let before = load(BEFORE);
+    let after = load(
+        "\
+o kept
+v 0 0 0
+v 1 0 0
+v 0 2 0
+v 1 1 0
+g body
+usemtl blue
+f 1 2 3
+g new
+f 2 4 3
+o added
+f 1 2 4
+",
+    );
+    let report = diff(&before, &after);
+    assert!(!report.is_empty());
+    assert_eq!(report.added_objects, vec!["added".into()]);
+    assert_eq!(report.removed_objects, vec!["gone".into()]);
+    let id = |group: &str| GroupId {
+        object: "kept".into(),
+        group: group.into(),
+        index: 0,
+    };
+    assert_eq!(report.added_groups, vec![id("new")]);
+    assert_eq!(report.removed_groups, vec![id("old")]);
+    assert_eq!(
+        report.material_changes,
+        vec![MaterialChange {
+            group: id("body"),
+            before: Some("red".to_string()),
+            after: Some("blue".to_string()),
+        }]
+    );
+    assert_eq!(report.polygons, (2, 3));
+    assert_eq!(report.lines, (1, 0));
+
+    assert_eq!((report.position.before, report.position.after), (3, 4));
+    assert_eq!(report.position.changed, 1);
+    assert_eq!(report.position.max_delta, 1.0);
+    assert!((report.position.mean_delta - 1.0 / 3.0).abs() < 1e-6);
+    assert!(!report.normal.is_changed());
+
+    assert_eq!(
+        report.to_string(),
+        "\
++ object added
+- object gone
++ group kept/new
+- group kept/old
+~ material kept/body: red -> blue
+polygons: 2 -> 3
+lines: 1 -> 0
+positions: 3 -> 4, 1 changed, max delta 1, mean delta 0.33333334
+"
+    );
+}
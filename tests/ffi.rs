@@ -0,0 +1,110 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "ffi")]
+
+use std::os::raw::c_char;
+
+use obj::ffi::*;
+
+static SQUARE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+";
+
+#[test]
+fn load_and_read() {
+    unsafe {
+        let obj = obj_load_buf(SQUARE.as_ptr(), SQUARE.len());
+        assert!(!obj.is_null());
+        assert_eq!(obj_position_count(obj), 4);
+        let positions = std::slice::from_raw_parts(obj_positions(obj), 12);
+        assert_eq!(&positions[3..6], &[1.0, 0.0, 0.0]);
+        assert_eq!(obj_normal_count(obj), 1);
+        assert_eq!(obj_texture_count(obj), 0);
+        assert_eq!(obj_material_count(obj), 0);
+        assert!(obj_material(obj, 0).is_null());
+
+        let buffers = obj_buffers_new(obj);
+        assert!(!buffers.is_null());
+        assert_eq!(obj_buffers_vertex_count(buffers), 4);
+        assert_eq!(obj_buffers_submesh_count(buffers), 1);
+        let mut count = 0;
+        let indices = obj_buffers_submesh_indices(buffers, 0, &mut count);
+        assert_eq!(count, 6);
+        let indices = std::slice::from_raw_parts(indices, count);
+        let vertices = std::slice::from_raw_parts(obj_buffers_vertices(buffers), 4);
+        assert!(indices.iter().all(|&i| vertices[i as usize].normal == [0.0, 0.0, 1.0]));
+        assert_eq!(obj_buffers_submesh_material(buffers, 0), -1);
+        assert!(obj_buffers_submesh_indices(buffers, 1, &mut count).is_null());
+        assert_eq!(count, 0);
+
+        obj_buffers_free(buffers);
+        obj_free(obj);
+    }
+}
+
+#[test]
+fn load_error() {
+    let source = "v 0 zero 0\n";
+    unsafe {
+        let obj = obj_load_buf(source.as_ptr(), source.len());
+        assert!(obj.is_null());
+        assert!(!obj_last_error().is_null());
+    }
+}
+
+#[test]
+fn buffers_error() {
+    let source = "v 0 0 0\nv 1 0 0\nf 1 2 3\n";
+    unsafe {
+        let obj = obj_load_buf(source.as_ptr(), source.len());
+        assert!(!obj.is_null());
+        assert!(obj_buffers_new(obj).is_null());
+        let message = std::ffi::CStr::from_ptr(obj_last_error()).to_str().unwrap();
+        assert!(message.contains("missing vertex attribute"), "{}", message);
+        obj_free(obj);
+    }
+}
+
+#[test]
+fn material_properties() {
+    let path = std::ffi::CString::new("test_assets/sponza.obj").unwrap();
+    unsafe {
+        let obj = obj_load(path.as_ptr());
+        assert!(!obj.is_null());
+        assert!(obj_material_count(obj) > 0);
+        let material = obj_material(obj, 0);
+
+        let mut buf = [0 as c_char; 64];
+        let len = obj_material_name(material, buf.as_mut_ptr(), buf.len());
+        let name = std::ffi::CStr::from_ptr(buf.as_ptr()).to_str().unwrap();
+        assert_eq!(name.len(), len);
+
+        // A truncated copy still ends with a NUL.
+        let mut short = [1 as c_char; 2];
+        assert_eq!(obj_material_name(material, short.as_mut_ptr(), short.len()), len);
+        assert_eq!(short[1], 0);
+
+        let mut color = [0.0f32; 3];
+        assert!(obj_material_color(material, ObjColor::Diffuse, color.as_mut_ptr()));
+        assert_eq!(color, [0.588, 0.588, 0.588]);
+
+        obj_free(obj);
+    }
+}
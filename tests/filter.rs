@@ -0,0 +1,80 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{LoadConfig, ObjData};
+
+static SCENE: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+f 1 2 3
+o wheel_front
+g rim
+f 1 2 3
+g tire
+f 1 3 2
+l 1 2
+o wheel_back
+g rim
+f 1 2 3
+o body
+g chassis
+f 3 2 1
+";
+
+fn summary(data: &ObjData) -> Vec<(&str, &str, usize, usize)> {
+    data.objects
+        .iter()
+        .flat_map(|o| {
+            o.groups
+                .iter()
+                .map(move |g| (&*o.name, &*g.name, g.polys.len(), g.lines.len()))
+        })
+        .collect()
+}
+
+#[test]
+fn include_objects() {
+    let config = LoadConfig::new().include_objects(["wheel_*", "bod?"]);
+    let data = ObjData::load_buf_with(SCENE.as_bytes(), config).unwrap();
+    assert_eq!(
+        summary(&data),
+        [
+            ("wheel_front", "rim", 1, 0),
+            ("wheel_front", "tire", 1, 1),
+            ("wheel_back", "rim", 1, 0),
+            ("body", "chassis", 1, 0)
+        ]
+    );
+    assert_eq!(data.position.len(), 3);
+
+    let config = LoadConfig::new()
+        .include_objects(["wheel_front"])
+        .exclude_groups(["ti*"]);
+    let data = ObjData::load_buf_with(SCENE.as_bytes(), config).unwrap();
+    assert_eq!(summary(&data), [("wheel_front", "rim", 1, 0)]);
+}
+
+#[test]
+fn exclude_groups() {
+    let config = LoadConfig::new().exclude_groups(["rim", "default"]).faithful(true);
+    let data = ObjData::load_buf_with(SCENE.as_bytes(), config).unwrap();
+    assert_eq!(
+        summary(&data),
+        [("wheel_front", "tire", 1, 1), ("body", "chassis", 1, 0)]
+    );
+    assert!(data.layout.is_none());
+}
@@ -0,0 +1,61 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, LoadConfig, Obj, ObjData};
+
+static SOURCE: &str = "
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vn 0 0 1
+usemtl red
+f 1/1/1 2/2/1 3/2/1
+usemtl blue
+f -3//-1 -2//-1 -1//-1
+l 1/1 2/2
+";
+
+#[test]
+fn geometry_only() {
+    let config = LoadConfig::new().geometry_only(true).check_indices(true);
+    let data = ObjData::load_buf_with(SOURCE.as_bytes(), config).unwrap();
+    assert_eq!(data.position.len(), 3);
+    assert!(data.texture.is_empty() && data.normal.is_empty());
+    assert!(data.material_libs.is_empty());
+
+    let groups = &data.objects[0].groups;
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].material, None);
+    let tuples: Vec<_> = (0..3).map(|p| IndexTuple::new(p, None, None)).collect();
+    assert_eq!(groups[0].polys[0].0.as_slice(), tuples.as_slice());
+    assert_eq!(groups[0].polys[1].0.as_slice(), tuples.as_slice());
+    assert_eq!(groups[0].lines[0].0, tuples[..2]);
+
+    let full = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    assert_eq!(full.objects[0].groups.len(), 2);
+}
+
+#[test]
+fn geometry_only_sponza() {
+    let config = LoadConfig::new().geometry_only(true);
+    let sponza = Obj::load_with("test_assets/sponza.obj", config).unwrap();
+    let full = Obj::load("test_assets/sponza.obj").unwrap();
+    assert_eq!(sponza.data.position, full.data.position);
+    assert_eq!(sponza.data.stats().polygons(), full.data.stats().polygons());
+}
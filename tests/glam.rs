@@ -0,0 +1,34 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "glam")]
+
+use glam::{Mat4, Quat, Vec2, Vec3};
+use obj::ObjData;
+
+#[test]
+fn glam_accessors_and_transform() {
+    let mut obj = ObjData::load_buf("v 1 0 0\nvt 0.5 1\nvn 1 0 0\n".as_bytes()).unwrap();
+    assert_eq!(obj.glam_positions().collect::<Vec<_>>(), vec![Vec3::X]);
+    assert_eq!(obj.glam_texture_coords().next(), Some(Vec2::new(0.5, 1.)));
+
+    let matrix = Mat4::from_rotation_translation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2), Vec3::Z);
+    obj.transform_glam(&matrix);
+    assert!(obj
+        .glam_positions()
+        .next()
+        .unwrap()
+        .abs_diff_eq(Vec3::new(0., 1., 1.), 1e-6));
+    assert!(obj.glam_normals().next().unwrap().abs_diff_eq(Vec3::Y, 1e-6));
+}
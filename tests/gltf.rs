@@ -0,0 +1,107 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "gltf")]
+
+use obj::{Obj, ObjData, ObjError};
+use serde_json::Value;
+
+static TEXTURED: &str = "
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+usemtl red
+f 1/1/1 2/2/1 3/3/1
+usemtl blue
+f 1/1/1 3/3/1 4/4/1
+";
+
+static MTL: &str = "
+newmtl red
+Kd 1 0 0
+map_Kd shared.png
+newmtl blue
+Kd 0 0 1
+map_Kd shared.png
+";
+
+#[test]
+fn export_primitives_per_material() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(TEXTURED.as_bytes()).unwrap(),
+        path: std::path::PathBuf::new(),
+    };
+    obj.load_mtls_fn(|_, _| Ok(MTL.as_bytes())).unwrap();
+
+    let (mut gltf, mut bin) = (Vec::new(), Vec::new());
+    obj.data.write_gltf(&mut gltf, &mut bin, "scene.bin").unwrap();
+    let doc: Value = serde_json::from_slice(&gltf).unwrap();
+
+    assert_eq!(doc["asset"]["version"], "2.0");
+    assert_eq!(doc["buffers"][0]["byteLength"], bin.len());
+    assert_eq!(doc["buffers"][0]["uri"], "scene.bin");
+    // Four distinct vertices of 32 bytes each and six indices of 4 bytes each.
+    assert_eq!(bin.len(), 4 * 32 + 6 * 4);
+
+    let primitives = doc["meshes"][0]["primitives"].as_array().unwrap();
+    assert_eq!(primitives.len(), 2);
+    let attributes = &primitives[0]["attributes"];
+    assert!(attributes.get("TEXCOORD_0").is_some() && attributes.get("NORMAL").is_some());
+    assert_eq!(
+        doc["accessors"][primitives[1]["indices"].as_u64().unwrap() as usize]["byteOffset"],
+        12
+    );
+    assert_eq!(doc["accessors"][0]["max"], serde_json::json!([1.0, 1.0, 0.0]));
+
+    let materials = doc["materials"].as_array().unwrap();
+    assert_eq!(materials[1]["name"], "blue");
+    assert_eq!(
+        materials[1]["pbrMetallicRoughness"]["baseColorFactor"],
+        serde_json::json!([0.0, 0.0, 1.0, 1.0])
+    );
+    // Both materials share one texture.
+    assert_eq!(doc["images"], serde_json::json!([{ "uri": "shared.png" }]));
+    assert_eq!(materials[0]["pbrMetallicRoughness"]["baseColorTexture"]["index"], 0);
+    assert_eq!(materials[1]["pbrMetallicRoughness"]["baseColorTexture"]["index"], 0);
+}
+
+#[test]
+fn export_positions_only() {
+    let data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).unwrap();
+    let (mut gltf, mut bin) = (Vec::new(), Vec::new());
+    data.write_gltf(&mut gltf, &mut bin, "mesh.bin").unwrap();
+    let doc: Value = serde_json::from_slice(&gltf).unwrap();
+    let primitive = &doc["meshes"][0]["primitives"][0];
+    assert_eq!(primitive["attributes"], serde_json::json!({ "POSITION": 0 }));
+    assert!(primitive.get("material").is_none());
+    assert!(doc.get("materials").is_none());
+}
+
+#[test]
+fn save_without_file_name() {
+    let data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).unwrap();
+    for path in &["..", "/"] {
+        match data.save_gltf(path) {
+            Err(ObjError::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
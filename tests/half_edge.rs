@@ -0,0 +1,69 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+#[test]
+fn quad_of_two_triangles() {
+    let obj = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 9 9 9\nf 1 2 3\nf 1 3 4\n".as_bytes()).unwrap();
+    let mesh = obj.to_half_edge();
+    assert_eq!(mesh.vertex_count(), 5);
+    assert_eq!(mesh.face_count(), 2);
+    assert_eq!(mesh.half_edges().len(), 6);
+
+    // The diagonal from 3 to 1 in the first face is the twin of 1 to 3 in the second.
+    let diagonal = mesh.half_edge(2);
+    assert_eq!((diagonal.tuple.position, mesh.end(2)), (2, 0));
+    assert_eq!(diagonal.twin, Some(3));
+    assert_eq!(mesh.half_edge(3).twin, Some(2));
+    assert_eq!(mesh.half_edge(diagonal.next).tuple.position, 0);
+    assert_eq!(mesh.half_edge(diagonal.prev).tuple.position, 1);
+
+    assert_eq!(mesh.face_half_edges(1), 3..6);
+    assert_eq!(mesh.face_neighbors(0).collect::<Vec<_>>(), [1]);
+    assert_eq!(mesh.edges().count(), 5);
+    assert_eq!((0..6).filter(|&h| mesh.is_boundary(h)).count(), 4);
+
+    assert_eq!(mesh.outgoing(0), [0, 3]);
+    assert_eq!(mesh.vertex_neighbors(0), [1, 2, 3]);
+    assert!(mesh.outgoing(4).is_empty());
+    assert!(mesh.vertex_neighbors(4).is_empty());
+}
+
+#[test]
+fn non_manifold_edges_have_no_twin() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 -1 0\nv 0 0 1\nf 1 2 3\nf 2 1 4\nf 2 1 5\n";
+    let mesh = ObjData::load_buf(source.as_bytes()).unwrap().to_half_edge();
+    assert!(mesh.half_edges().iter().all(|h| h.twin.is_none()));
+    // The shared edge is returned once for each of the three faces.
+    let shared = mesh.edges().filter(|&h| {
+        let mut ends = [mesh.half_edge(h).tuple.position, mesh.end(h)];
+        ends.sort();
+        ends == [0, 1]
+    });
+    assert_eq!(shared.count(), 3);
+}
+
+#[test]
+fn missing_positions_leave_no_vertex() {
+    let data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nf 2 1 9\n".as_bytes()).unwrap();
+    let mesh = data.to_half_edge();
+    assert_eq!(mesh.face_count(), 2);
+    assert_eq!(mesh.vertex_count(), 3);
+    assert_eq!(mesh.face_neighbors(0).collect::<Vec<_>>(), [1]);
+    let outgoing: usize = mesh.vertices().map(|v| mesh.outgoing(v).len()).sum();
+    assert_eq!(outgoing, 5);
+}
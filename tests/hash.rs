@@ -0,0 +1,87 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+const CUBE_CORNER: &str = "\
+mtllib scene.mtl
+o corner
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vn 0 0 1
+usemtl red
+f 1/1/1 2/1/1 3/1/1
+l 1 2
+";
+
+#[test]
+fn hash_ignores_formatting() {
+    let data = ObjData::load_buf(CUBE_CORNER.as_bytes()).unwrap();
+    let reformatted = "\
+# the same corner
+o corner
+v 0.0 0.000 0
+v   1 0 0
+v 0 1.0 0
+
+vt 0.0 0.0
+vn 0 0 1.0
+usemtl red
+f 1/1/1  2/1/1 3/1/1
+l 1 2
+";
+    let other = ObjData::load_buf(reformatted.as_bytes()).unwrap();
+    assert_eq!(data.content_hash(), other.content_hash());
+
+    let written: ObjData = data.to_string().parse().unwrap();
+    assert_eq!(data.content_hash(), written.content_hash());
+    assert_eq!(data.content_hash(), data.clone().content_hash());
+}
+
+#[test]
+fn hash_detects_changes() {
+    let data = ObjData::load_buf(CUBE_CORNER.as_bytes()).unwrap();
+    let hash = data.content_hash();
+
+    let mut moved = data.clone();
+    moved.position[1][0] = 2.0;
+    assert_ne!(moved.content_hash(), hash);
+
+    let mut flipped = data.clone();
+    flipped.objects[0].groups[0].polys[0].0.swap(1, 2);
+    assert_ne!(flipped.content_hash(), hash);
+
+    let mut untextured = data.clone();
+    untextured.objects[0].groups[0].polys[0].0[0].texture = None;
+    assert_ne!(untextured.content_hash(), hash);
+
+    let mut renamed = data.clone();
+    renamed.objects[0].name = "other".into();
+    assert_ne!(renamed.content_hash(), hash);
+
+    let mut no_lines = data;
+    no_lines.objects[0].groups[0].lines.clear();
+    assert_ne!(no_lines.content_hash(), hash);
+}
+
+#[test]
+fn hash_is_stable() {
+    let data = ObjData::load_buf(CUBE_CORNER.as_bytes()).unwrap();
+    // Hashes stored by caches must stay valid across releases.
+    assert_eq!(data.content_hash(), 4311377274952369181);
+}
@@ -0,0 +1,60 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static SOURCE: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+g car/body
+f 1 2 3
+g car/wheel/rim
+f 1 2 3
+g car/wheel/tire
+f 1 2 3
+g car
+f 1 2 3
+o other
+g lamp
+f 1 2 3
+g car/wheel/rim
+f 1 2 3
+";
+
+#[test]
+fn group_tree() {
+    let data = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let roots = data.group_tree("/");
+    let names: Vec<_> = roots.iter().map(|n| n.name).collect();
+    assert_eq!(names, ["car", "lamp"]);
+
+    let car = &roots[0];
+    assert_eq!(car.groups, [(0, 3)]);
+    assert_eq!(car.children.len(), 2);
+    let wheel = &car.children[1];
+    assert_eq!((wheel.name, wheel.path), ("wheel", "car/wheel"));
+    assert!(wheel.groups.is_empty());
+    let rim = &wheel.children[0];
+    assert_eq!((rim.name, rim.path), ("rim", "car/wheel/rim"));
+    assert_eq!(rim.groups, [(0, 1), (1, 1)]);
+    assert_eq!(car.all_groups(), [(0, 3), (0, 0), (0, 1), (1, 1), (0, 2)]);
+
+    let flat = data.group_tree("");
+    assert_eq!(flat.len(), 5);
+    assert!(flat.iter().all(|n| n.children.is_empty() && n.name == n.path));
+    assert_eq!(data.group_tree(".").len(), 5);
+}
@@ -0,0 +1,83 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+fn cube_with_inner_points() -> String {
+    let mut source = String::new();
+    for i in 0..8 {
+        source += &format!("v {} {} {}\n", i & 1, (i >> 1) & 1, (i >> 2) & 1);
+    }
+    for i in 0..27 {
+        let x = 0.1 + 0.4 * (i % 3) as f32;
+        let y = 0.1 + 0.4 * ((i / 3) % 3) as f32;
+        let z = 0.1 + 0.4 * (i / 9) as f32;
+        source += &format!("v {} {} {}\n", x, y, z);
+    }
+    source += "o cube\nf 1 2 4 3\nf 5 6 8 7\nl 1 8\no inner\n";
+    for i in 0..9 {
+        source += &format!("f {} {} {}\n", 9 + i * 3, 10 + i * 3, 11 + i * 3);
+    }
+    source
+}
+
+fn volume(hull: &ObjData) -> f32 {
+    hull.objects[0].groups[0]
+        .polys
+        .iter()
+        .map(|poly| {
+            let [a, b, c] = [0, 1, 2].map(|k| hull.position[poly.0[k].position]);
+            let cross = [
+                b[1] * c[2] - b[2] * c[1],
+                b[2] * c[0] - b[0] * c[2],
+                b[0] * c[1] - b[1] * c[0],
+            ];
+            (a[0] * cross[0] + a[1] * cross[1] + a[2] * cross[2]) / 6.0
+        })
+        .sum()
+}
+
+#[test]
+fn convex_hull_of_cube() {
+    let data = ObjData::load_buf(cube_with_inner_points().as_bytes()).unwrap();
+    let hull = data.convex_hull();
+    assert_eq!(hull.position.len(), 8);
+    let group = &hull.objects[0].groups[0];
+    assert_eq!(&*group.name, "hull");
+    assert_eq!(group.polys.len(), 12);
+    // Outward facing triangles give a positive volume.
+    assert!((volume(&hull) - 1.0).abs() < 1e-5);
+
+    let inner = data.convex_hull_of(|object| &*object.name == "inner");
+    assert!(inner.position.iter().all(|p| p.iter().all(|&x| x > 0.0 && x < 1.0)));
+    assert!((volume(&inner) - 0.8f32.powi(3)).abs() < 1e-4);
+}
+
+#[test]
+fn missing_positions_are_ignored() {
+    let data = ObjData::load_buf(format!("{}f 1 2 99\n", cube_with_inner_points()).as_bytes()).unwrap();
+    let hull = data.convex_hull();
+    assert_eq!(hull.position.len(), 8);
+    assert!((volume(&hull) - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn convex_hull_of_flat_mesh() {
+    let data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n".as_bytes()).unwrap();
+    let hull = data.convex_hull();
+    assert!(hull.objects.is_empty());
+    assert!(hull.position.is_empty());
+}
@@ -0,0 +1,72 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, SimplePolygon, WeldConfig};
+
+static MESH: &str = "
+v 0 0 0
+v 9 9 9
+v 1 0 0
+v 0 1 0
+v 1 0 0
+f 1 3 4
+f 4 3 1
+f 1 5 4
+";
+
+#[test]
+fn ids_follow_edits() {
+    let mut obj = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let mut ids = obj.stable_ids();
+    let orphan = ids.vertex_id(1).unwrap();
+    let corner = ids.vertex_id(3).unwrap();
+    let twin = ids.vertex_id(4).unwrap();
+    let reversed = ids.poly_id(1).unwrap();
+    let last = ids.poly_id(2).unwrap();
+    assert!(ids.vertex_id(5).is_none());
+
+    let (_, report) = obj.weld_vertices_with_remap(WeldConfig::default());
+    ids.apply(&obj, &report);
+    assert_eq!(ids.vertex_index(twin), Some(2));
+    assert_eq!(ids.vertex_index(corner), Some(3));
+
+    let report = obj.compact_with_remap();
+    ids.apply(&obj, &report);
+    assert_eq!(ids.vertex_index(orphan), None);
+    assert_eq!(ids.vertex_index(corner), Some(2));
+    assert_eq!(ids.vertex_id(2), Some(corner));
+
+    let (_, report) = obj.dedup_faces_with_remap();
+    ids.apply(&obj, &report);
+    assert_eq!(ids.poly_index(reversed), None);
+    assert_eq!(ids.poly_index(last), None);
+    assert_eq!(obj.polygons().count(), 1);
+
+    // New elements reuse the slots of removed ones without reviving their handles.
+    obj.position.push([5.0, 5.0, 5.0]);
+    obj.objects[0].groups[0].polys.push(SimplePolygon::from(vec![
+        (0, None, None).into(),
+        (1, None, None).into(),
+        (3, None, None).into(),
+    ]));
+    ids.extend(&obj);
+    let added = ids.vertex_id(3).unwrap();
+    assert_ne!(added, orphan);
+    assert_eq!(ids.vertex_index(added), Some(3));
+    assert_eq!(ids.vertex_index(orphan), None);
+    assert_eq!(ids.poly_index(ids.poly_id(1).unwrap()), Some(1));
+    assert_eq!(ids.poly_index(reversed), None);
+}
@@ -0,0 +1,103 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static SCENE: &str = "
+vn 0 0 1
+o a
+v 0 0 0
+v 1 0 0
+v 1 2 0
+v 0 0 1
+usemtl m
+f 1//1 2//1 3//1
+f 1 2 4
+o rotated
+v 10 0 0
+v 10 1 0
+v 8 1 0
+v 10 0 1
+usemtl m
+f 5//1 6//1 7//1
+f 5 6 8
+o mirrored
+v 0 0 0
+v -1 0 0
+v -1 2 0
+v 0 0 1
+usemtl m
+f 9//1 10//1 11//1
+f 9 10 12
+o other_material
+v 0 0 5
+v 1 0 5
+v 1 2 5
+v 0 0 6
+usemtl n
+f 13//1 14//1 15//1
+f 13 14 16
+o raised
+v 0 0 5
+v 1 0 5
+v 1 2 5
+v 0 0 6
+usemtl m
+f 13//1 14//1 15//1
+f 13 14 16
+";
+
+fn assert_matrix_eq(actual: [[f32; 4]; 4], expected: [[f32; 4]; 4]) {
+    let flat = actual.iter().flatten().zip(expected.iter().flatten());
+    assert!(flat.into_iter().all(|(a, e)| (a - e).abs() < 1e-5), "{:?}", actual);
+}
+
+#[test]
+fn find_instances() {
+    let obj = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    let groups = obj.find_instances(1e-4);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].prototype, 0);
+    let instances: Vec<_> = groups[0].instances.iter().map(|(object, _)| *object).collect();
+    assert_eq!(instances, [1, 4]);
+
+    assert_matrix_eq(
+        groups[0].instances[0].1,
+        [
+            [0.0, 1.0, 0.0, 0.0],
+            [-1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [10.0, 0.0, 0.0, 1.0],
+        ],
+    );
+    assert_matrix_eq(
+        groups[0].instances[1].1,
+        [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 5.0, 1.0],
+        ],
+    );
+
+    // Applying the transformation to the prototype reproduces the instance.
+    let mut moved = obj.extract(&["a"]);
+    moved.transform(&groups[0].instances[0].1);
+    let rotated = obj.extract(&["rotated"]);
+    for (a, b) in moved.position.iter().zip(&rotated.position) {
+        assert!((0..3).all(|i| (a[i] - b[i]).abs() < 1e-5));
+    }
+}
@@ -0,0 +1,70 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::sync::Arc;
+
+use obj::{Group, ObjData, ObjMaterial, Object};
+
+static MESH: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+o part
+g wall
+usemtl stone
+f 1 2 3
+usemtl wall
+f 3 2 1
+o part
+g wall
+usemtl stone
+f 1 3 2
+";
+
+fn material_name(group: &Group) -> &Arc<str> {
+    match &group.material {
+        Some(ObjMaterial::Ref(name)) => name,
+        _ => panic!("expected a material reference"),
+    }
+}
+
+#[test]
+fn parsed_names_are_shared() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let (a, b) = (&data.objects[0], &data.objects[1]);
+    assert!(Arc::ptr_eq(&a.name, &b.name));
+    assert!(Arc::ptr_eq(&a.groups[0].name, &b.groups[0].name));
+    // A material name equal to a group name is shared, too.
+    assert!(Arc::ptr_eq(material_name(&a.groups[1]), &a.groups[0].name));
+    assert!(Arc::ptr_eq(material_name(&a.groups[0]), material_name(&b.groups[0])));
+}
+
+#[test]
+fn intern_built_names() {
+    let mut data = ObjData::default();
+    for _ in 0..2 {
+        let mut object = Object::new("part".to_string());
+        object.groups.push(Group::new("wall"));
+        data.objects.push(object);
+    }
+    assert!(!Arc::ptr_eq(&data.objects[0].name, &data.objects[1].name));
+    data.intern_names();
+    assert!(Arc::ptr_eq(&data.objects[0].name, &data.objects[1].name));
+    assert!(Arc::ptr_eq(
+        &data.objects[0].groups[0].name,
+        &data.objects[1].groups[0].name
+    ));
+}
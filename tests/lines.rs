@@ -0,0 +1,161 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, Line, ObjData, ObjRef, PushParser};
+
+static CUBE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+v 1 0 1
+v 1 1 1
+v 0 1 1
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+
+#[test]
+fn lines_round_trip() {
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nvt 0 0\nvt 1 0\ng wire\nl 1/1 2/2 3\n";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let group = &obj.objects[0].groups[0];
+    assert_eq!(&*group.name, "wire");
+    assert!(group.polys.is_empty());
+    assert_eq!(
+        group.lines,
+        vec![Line(vec![
+            IndexTuple::new(0, Some(0), None),
+            IndexTuple::new(1, Some(1), None),
+            IndexTuple::new(2, None, None),
+        ])]
+    );
+
+    let mut out = Vec::new();
+    obj.write_to_buf(&mut out).unwrap();
+    assert!(String::from_utf8_lossy(&out).contains("l 1/1 2/2 3\n"));
+    assert_eq!(ObjData::load_buf(out.as_slice()).unwrap(), obj);
+}
+
+#[test]
+fn relative_line_indices() {
+    // Streaming exporters write each element right after its vertices, using relative indices.
+    let source = "\
+v 0 0 0
+v 1 0 0
+vt 0 0
+vt 1 0
+l -2/-2 -1/-1
+v 1 1 0
+v 0 1 0
+l -4 -3 -2 -1
+f -3 -2 -1
+";
+    let expected = vec![
+        Line(vec![
+            IndexTuple::new(0, Some(0), None),
+            IndexTuple::new(1, Some(1), None),
+        ]),
+        Line((0..4).map(|p| IndexTuple::new(p, None, None)).collect()),
+    ];
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let group = &obj.objects[0].groups[0];
+    assert_eq!(group.lines, expected);
+    assert_eq!(
+        group.polys[0].0.iter().map(|t| t.position).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+
+    let mut parser = PushParser::<f32>::default();
+    for chunk in source.as_bytes().chunks(5) {
+        parser.push_bytes(chunk).unwrap();
+    }
+    assert_eq!(parser.finish().unwrap(), obj);
+
+    let borrowed = ObjRef::parse(source).unwrap();
+    assert_eq!(borrowed.objects[0].groups[0].lines, expected);
+
+    // Written indices are absolute, so the lines survive a round trip.
+    let written: ObjData = obj.to_string().parse().unwrap();
+    assert_eq!(written.objects[0].groups[0].lines, expected);
+}
+
+#[test]
+fn extract_all_edges() {
+    let quad = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3\nf 1 3 4\n".as_bytes()).unwrap();
+    let edges = quad.extract_edges(None);
+    assert_eq!(edges.lines.len(), 5);
+    // Only the boundary remains when flat edges are excluded.
+    assert_eq!(quad.extract_edges(Some(0.1)).lines.len(), 4);
+}
+
+#[test]
+fn extract_feature_edges() {
+    let mut obj = ObjData::load_buf(CUBE.as_bytes()).unwrap();
+    obj.to_triangles();
+    assert_eq!(obj.extract_edges(None).lines.len(), 18);
+
+    let edges = obj.extract_edges(Some(0.5));
+    assert_eq!(edges.lines.len(), 12);
+    obj.objects[0].groups.push(edges);
+    assert_eq!(obj.stats().lines, 12);
+}
+
+#[cfg(feature = "genmesh")]
+#[test]
+fn genmesh_primitives_include_lines() {
+    use obj::GenmeshPrimitive;
+
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\nl 1 2 3\n";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let group = &obj.objects[0].groups[0];
+    let segments = group.lines[0].clone().into_genmesh();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(
+        segments[1],
+        genmesh::Line::new(IndexTuple::new(1, None, None), IndexTuple::new(2, None, None))
+    );
+
+    let primitives = group.genmesh_primitives().unwrap();
+    assert_eq!(primitives.len(), 3);
+    assert!(matches!(
+        primitives[0],
+        GenmeshPrimitive::Polygon(genmesh::Polygon::PolyTri(_))
+    ));
+    assert_eq!(primitives[2], GenmeshPrimitive::Line(segments[1]));
+}
+
+#[cfg(feature = "genmesh")]
+#[test]
+fn genmesh_polygons_keep_materials() {
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3\nusemtl red\nf 1 2 3 4\ng other\nl 1 2\nusemtl blue\nf 3 2 1\ng third\nusemtl red\nf 1 3 4\n";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let (polygons, materials) = obj.genmesh_polygons().unwrap();
+    let names: Vec<_> = materials.iter().map(|m| m.name()).collect();
+    assert_eq!(names, ["red", "blue"]);
+    let tags: Vec<_> = polygons.iter().map(|p| p.material).collect();
+    assert_eq!(tags, [None, Some(0), Some(1), Some(0)]);
+    assert!(matches!(polygons[1].polygon, genmesh::Polygon::PolyQuad(_)));
+
+    let pentagon = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 2 0\nf 1 2 3 4 5\n";
+    let obj = ObjData::load_buf(pentagon.as_bytes()).unwrap();
+    assert!(obj.genmesh_polygons().is_err());
+}
@@ -12,12 +12,14 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
-use obj::{LoadConfig, ObjData};
+#![cfg(feature = "std")]
+
+use obj::{DuplicateObjectPolicy, LoadConfig, LoadWarning, ObjData, ObjError, OutOfRangePolicy, PushParser};
 use std::io::BufReader;
 
 /// This is an example of an obj file augmented with additional custom commands.
 /// We expect to be able to load the recognizable parts of these kinds of files.
-static SQUARE_EXTENDED: &'static str = "
+static SQUARE_EXTENDED: &str = "
 scale 1
 vt 0 0
 adjf 0 1
@@ -49,7 +51,7 @@ f 3/4 1/1 4/3
 ";
 
 /// This is the strictly spec compliant version of `SQUARE_EXTENDED`.
-static SQUARE_STRICT: &'static str = "
+static SQUARE_STRICT: &str = "
 vt 0 0
 vt 1 0
 vt 1 1
@@ -64,20 +66,271 @@ f 3/4 1/1 4/3
 
 #[test]
 fn load_square_non_compliant() {
-    let permissive_config = LoadConfig { strict: false };
+    let permissive_config = LoadConfig::new().strict(false);
 
     // Load the extended version of the square
     let mut reader = BufReader::new(SQUARE_EXTENDED.as_bytes());
-    let obj_ext = ObjData::load_buf_with_config(&mut reader, permissive_config).unwrap();
+    let obj_ext = ObjData::load_buf_with(&mut reader, permissive_config.clone()).unwrap();
 
     // Load the vanilla version of the square
     let mut reader = BufReader::new(SQUARE_STRICT.as_bytes());
-    let obj_basic = ObjData::load_buf_with_config(&mut reader, permissive_config).unwrap();
+    let obj_basic = ObjData::load_buf_with(&mut reader, permissive_config).unwrap();
 
     assert_eq!(obj_basic, obj_ext);
 
-    let strict_config = LoadConfig { strict: true };
+    let strict_config = LoadConfig::new().strict(true);
 
     let mut reader = BufReader::new(SQUARE_EXTENDED.as_bytes());
-    assert!(ObjData::load_buf_with_config(&mut reader, strict_config).is_err());
+    assert!(ObjData::load_buf_with(&mut reader, strict_config).is_err());
+}
+
+#[test]
+fn load_config_builder() {
+    assert!(LoadConfig::new().strict);
+    assert!(!LoadConfig::new().strict(false).strict);
+
+    let data = ObjData::load_buf_with(SQUARE_EXTENDED.as_bytes(), LoadConfig::new().strict(false)).unwrap();
+    assert_eq!(data, ObjData::load_buf(SQUARE_STRICT.as_bytes()).unwrap());
+
+    let precise = ObjData::load_buf_f64_with(SQUARE_EXTENDED.as_bytes(), LoadConfig::new().strict(false)).unwrap();
+    assert_eq!(precise.position.len(), data.position.len());
+}
+
+#[test]
+fn check_indices() {
+    let check = LoadConfig::new().check_indices(true);
+    let load = |src: &str| ObjData::load_buf_with(src.as_bytes(), check.clone());
+
+    assert!(matches!(
+        load("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nf 1 2 10\n"),
+        Err(ObjError::IndexOutOfRange {
+            line_number: 4,
+            index: 10,
+            count: 4
+        })
+    ));
+    assert!(matches!(
+        load("v 0 0 0\nv 1 0 0\nf 1 2 -3\n"),
+        Err(ObjError::IndexOutOfRange {
+            line_number: 2,
+            index: -3,
+            count: 2
+        })
+    ));
+    assert!(matches!(
+        load("v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nf 1/0 2/1 3/1\n"),
+        Err(ObjError::ZeroVertexNumber { line_number: 4 })
+    ));
+    assert!(matches!(
+        load("v 0 0 0\nv 1 0 0\nvn 0 0 1\nl 1 2\nf 1//2 2//1 1//1\n"),
+        Err(ObjError::IndexOutOfRange {
+            line_number: 4,
+            index: 2,
+            count: 1
+        })
+    ));
+
+    // References to elements that only appear later in the file are fine.
+    let forward = "f 1 2 3\nv 0 0 0\nv 1 0 0\nv 0 1 0\n";
+    assert_eq!(load(forward).unwrap(), ObjData::load_buf(forward.as_bytes()).unwrap());
+
+    // Without the option out of range references are kept.
+    let data = ObjData::load_buf("v 0 0 0\nf 1 2 10\n".as_bytes()).unwrap();
+    assert_eq!(data.objects[0].groups[0].polys[0].0[2].position, 9);
+}
+
+#[test]
+fn comma_decimals() {
+    let src = "v 0,5 1 -2,25\nv 1 0 0 0,5 0,5 1\nvt 0,5 0\nvn 0 0 1\nf 1/1/1 2/1/1 1/1/1\n";
+    assert!(matches!(
+        ObjData::load_buf(src.as_bytes()),
+        Err(ObjError::ArgumentListFailure { line_number: 0, .. })
+    ));
+
+    let config = LoadConfig::new().comma_decimals(true);
+    let (data, warnings) = ObjData::load_buf_with_warnings(src.as_bytes(), config.clone()).unwrap();
+    assert_eq!(data.position, vec![[0.5, 1.0, -2.25], [1.0, 0.0, 0.0]]);
+    assert_eq!(data.color, vec![[1.0; 3], [0.5, 0.5, 1.0]]);
+    assert_eq!(data.texture, vec![[0.5, 0.0]]);
+    assert_eq!(
+        warnings,
+        vec![
+            LoadWarning::CommaDecimal { line_number: 0 },
+            LoadWarning::CommaDecimal { line_number: 1 },
+            LoadWarning::CommaDecimal { line_number: 2 },
+        ]
+    );
+    assert_eq!(ObjData::load_buf_with(src.as_bytes(), config.clone()).unwrap(), data);
+
+    let mut parser: PushParser = PushParser::new(config.clone());
+    parser.push_bytes(src.as_bytes()).unwrap();
+    assert_eq!(parser.warnings().len(), 3);
+    assert_eq!(parser.finish().unwrap(), data);
+
+    // Commas that can't be decimal separators are still errors.
+    assert!(ObjData::load_buf_with("v 1,5,2 0 0\n".as_bytes(), config).is_err());
+}
+
+#[test]
+fn out_of_range_numbers() {
+    let src = "v 1e40 0 -1e40\nv 1e-50 inf 0\nvt 0 0\nf 1 2 1/1\n";
+    let with = |policy| LoadConfig::new().out_of_range(policy);
+
+    let data = ObjData::load_buf(src.as_bytes()).unwrap();
+    assert_eq!(data.position[0], [f32::INFINITY, 0.0, f32::NEG_INFINITY]);
+
+    assert!(matches!(
+        ObjData::load_buf_with(src.as_bytes(), with(OutOfRangePolicy::Error)),
+        Err(ObjError::NumberOutOfRange { line_number: 0, ref number }) if number == "1e40"
+    ));
+
+    let (clamped, warnings) = ObjData::load_buf_with_warnings(src.as_bytes(), with(OutOfRangePolicy::Clamp)).unwrap();
+    assert_eq!(
+        clamped.position,
+        vec![[f32::MAX, 0.0, -f32::MAX], [0.0, f32::INFINITY, 0.0]]
+    );
+    assert_eq!(
+        warnings,
+        vec![
+            LoadWarning::ClampedNumber { line_number: 0 },
+            LoadWarning::ClampedNumber { line_number: 1 }
+        ]
+    );
+
+    // Subnormal values are clamped to zero, double precision positions have more range.
+    let (data, _) =
+        ObjData::load_buf_with_warnings("vn 1e-40 0 1\n".as_bytes(), with(OutOfRangePolicy::Clamp)).unwrap();
+    assert_eq!(data.normal[0], [0.0, 0.0, 1.0]);
+    let precise = ObjData::load_buf_f64_with("v 1e40 0 0\n".as_bytes(), with(OutOfRangePolicy::Error)).unwrap();
+    assert_eq!(precise.position[0][0], 1e40);
+
+    let huge_index = "v 0 0 0\nvt 0 0\nf 1/99999999999999999999 1 1\n";
+    assert!(ObjData::load_buf(huge_index.as_bytes()).is_ok());
+    for policy in [OutOfRangePolicy::Clamp, OutOfRangePolicy::Error] {
+        assert!(matches!(
+            ObjData::load_buf_with(huge_index.as_bytes(), with(policy)),
+            Err(ObjError::NumberOutOfRange { line_number: 2, .. })
+        ));
+    }
+}
+
+#[test]
+fn max_face_vertices() {
+    let src = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv -1 1 0\nf 1 2 3 4\nf 1 2 3 4 5\n";
+    let limited = LoadConfig::new().max_face_vertices(4);
+    assert!(ObjData::load_buf_with(src.as_bytes(), LoadConfig::new()).is_ok());
+    assert!(matches!(
+        ObjData::load_buf_with(src.as_bytes(), limited.clone()),
+        Err(ObjError::TooManyFaceVertices {
+            line_number: 6,
+            count: 5,
+            max: 4
+        })
+    ));
+
+    let data = ObjData::load_buf_with(src.as_bytes(), limited.triangulate_large_faces(true)).unwrap();
+    let polys: Vec<Vec<usize>> = data.objects[0].groups[0]
+        .polys
+        .iter()
+        .map(|p| p.0.iter().map(|t| t.position).collect())
+        .collect();
+    assert_eq!(polys, [vec![0, 1, 2, 3], vec![0, 1, 2], vec![0, 2, 3], vec![0, 3, 4]]);
+}
+
+#[test]
+fn max_line_length() {
+    use std::io::Read;
+
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\r\n";
+    assert!(ObjData::load_buf_with(src.as_bytes(), LoadConfig::new().max_line_length(7)).is_ok());
+    assert!(matches!(
+        ObjData::load_buf_with(src.as_bytes(), LoadConfig::new().max_line_length(6)),
+        Err(ObjError::LineTooLong { line_number: 0, max: 6 })
+    ));
+
+    // A line that never ends is cut off instead of being read into memory.
+    let endless = "v 0 0 0\nf 1".as_bytes().chain(std::io::repeat(b' '));
+    assert!(matches!(
+        ObjData::load_buf_with(endless, LoadConfig::new().max_line_length(1 << 16)),
+        Err(ObjError::LineTooLong { line_number: 1, .. })
+    ));
+
+    let mut parser = PushParser::<f32>::new(LoadConfig::new().strict(false).max_line_length(4));
+    assert!(parser.push_line("v 0 0 0").is_err());
+}
+
+#[test]
+fn duplicate_objects() {
+    let src =
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\no Body\nf 1 2 3\no Wheel\nf 1 2 3\no Body\ng lid\nf 3 2 1\ng\no Body\nf 1 3 2\n";
+    let load = |policy| ObjData::load_buf_with(src.as_bytes(), LoadConfig::new().duplicate_objects(policy));
+    let names = |data: &ObjData| -> Vec<String> { data.objects.iter().map(|o| o.name.to_string()).collect() };
+    let face_counts = |data: &ObjData| -> Vec<usize> {
+        data.objects
+            .iter()
+            .map(|o| o.groups.iter().map(|g| g.polys.len()).sum())
+            .collect()
+    };
+
+    let kept = load(DuplicateObjectPolicy::Keep).unwrap();
+    assert_eq!(names(&kept), ["Body", "Wheel", "Body", "Body"]);
+    assert_eq!(face_counts(&kept), [1, 1, 1, 1]);
+
+    let merged = load(DuplicateObjectPolicy::Merge).unwrap();
+    assert_eq!(names(&merged), ["Body", "Wheel"]);
+    assert_eq!(face_counts(&merged), [3, 1]);
+    let reloaded = ObjData::load_buf_with(
+        merged.to_string().as_bytes(),
+        LoadConfig::new().duplicate_objects(DuplicateObjectPolicy::Error),
+    )
+    .unwrap();
+    assert_eq!(face_counts(&reloaded), [3, 1]);
+
+    let suffixed = load(DuplicateObjectPolicy::Suffix).unwrap();
+    assert_eq!(names(&suffixed), ["Body", "Wheel", "Body.001", "Body.002"]);
+
+    assert!(matches!(
+        load(DuplicateObjectPolicy::Error),
+        Err(ObjError::DuplicateObject { line_number: 7, ref name }) if name == "Body"
+    ));
+}
+
+#[test]
+fn lenient_skips_malformed_lines() {
+    let mut source = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nf 1 2\x00\xff 3\nf 1 2 /\nvt 0 garbage\n".to_vec();
+    source.extend_from_slice(b"\xde\xad\xbe\xef\x01\nf 1 2 x\nf 0 1 2\nf 2 4 3\nvt 1 1\n");
+    let lenient = LoadConfig::new().strict(false);
+
+    let (data, warnings) = ObjData::load_buf_with_warnings(source.as_slice(), lenient.clone()).unwrap();
+    let skipped: Vec<_> = warnings
+        .iter()
+        .map(|w| match w {
+            LoadWarning::SkippedLine { line_number, .. } => *line_number,
+            other => panic!("unexpected warning {:?}", other),
+        })
+        .collect();
+    assert_eq!(skipped, [4, 5, 6, 7, 8, 9]);
+    assert_eq!(data.texture, vec![[1., 1.]]);
+    assert_eq!(data.objects[0].groups[0].polys.len(), 1);
+    assert_eq!(data.objects[0].groups[0].polys[0].0[0].position, 1);
+
+    // The same through the other entry points.
+    assert_eq!(ObjData::parse_slice(&source, lenient.clone()).unwrap(), data);
+    let mut parser = PushParser::new(lenient.clone());
+    for chunk in source.chunks(5) {
+        parser.push_bytes(chunk).unwrap();
+    }
+    assert_eq!(parser.warnings().len(), 6);
+    assert_eq!(parser.finish().unwrap(), data);
+
+    // Strict parsing stops at the first malformed line, and errors due to options are kept.
+    assert!(matches!(
+        ObjData::<f32>::parse_slice(&source, LoadConfig::new()),
+        Err(ObjError::InvalidUtf8 { line_number: 4 })
+    ));
+    let checked = lenient.check_indices(true);
+    assert!(matches!(
+        ObjData::load_buf_with("v 0 0 0\nf 1 2 3\n".as_bytes(), checked),
+        Err(ObjError::IndexOutOfRange { line_number: 1, .. })
+    ));
 }
@@ -12,10 +12,35 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
-use obj::Obj;
+#![cfg(feature = "std")]
+
+use obj::{ImplicitObjectName, LoadConfig, Obj, ObjData};
 
 #[test]
 fn load_test_file() {
     let mut sponza = Obj::load("test_assets/sponza.obj").unwrap();
-    let _ = sponza.load_mtls().unwrap();
+    sponza.load_mtls().unwrap();
+}
+
+#[test]
+fn implicit_object_name() {
+    let sponza = Obj::load("test_assets/sponza.obj").unwrap();
+    assert_eq!(&*sponza.data.objects[0].name, "default");
+
+    let config = LoadConfig::new().implicit_object(ImplicitObjectName::FileStem);
+    let named = Obj::load_with("test_assets/sponza.obj", config.clone()).unwrap();
+    assert_eq!(named.data.objects.len(), 1);
+    assert_eq!(&*named.data.objects[0].name, "sponza");
+    assert_eq!(named.data.objects[0].groups, sponza.data.objects[0].groups);
+
+    // Without a path to take the name from, the default is kept.
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\no second\nf 3 2 1\n";
+    let data = ObjData::load_buf_with(src.as_bytes(), config).unwrap();
+    assert_eq!(&*data.objects[0].name, "default");
+
+    let config = LoadConfig::new().implicit_object(ImplicitObjectName::Named("first".to_string()));
+    let data = ObjData::load_buf_with(src.as_bytes(), config).unwrap();
+    let names: Vec<&str> = data.objects.iter().map(|o| &*o.name).collect();
+    assert_eq!(names, ["first", "second"]);
+    assert!(data.to_string().contains("\no first\n"));
 }
@@ -0,0 +1,217 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::path::PathBuf;
+
+use obj::{LoadConfig, Material, Mtl, Obj, ObjData, ObjMaterial, UnresolvedMaterial};
+
+static SCENE: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+o car
+g wheel_front_left
+usemtl rubber
+f 1 2 3
+usemtl chrome
+f 3 2 1
+g body
+f 1 3 2
+o tree
+g trunk
+f 1 2 3
+";
+
+static MTL: &str = "
+newmtl rubber
+Kd 0.1 0.1 0.1
+newmtl chrome
+Ks 1 1 1
+";
+
+#[test]
+fn lookup_by_name() {
+    let data = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    let car = data.object("car").unwrap();
+    assert_eq!(car.groups.len(), 3);
+    assert!(data.object("boat").is_none());
+
+    let wheel = car.group("wheel_front_left").unwrap();
+    assert_eq!(wheel.index, 0);
+    assert_eq!(car.groups_named("wheel_front_left").count(), 2);
+    assert!(car.group("trunk").is_none());
+
+    let index = data.name_index();
+    assert_eq!(index.object("tree").map(|o| o.groups.len()), Some(1));
+    assert_eq!(index.group("car", "body"), car.group("body"));
+    assert_eq!(index.group("car", "wheel_front_left"), Some(wheel));
+    assert!(index.group("tree", "body").is_none());
+}
+
+#[test]
+fn material_by_name() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(format!("mtllib car.mtl\n{}", SCENE).as_bytes()).unwrap(),
+        path: PathBuf::new(),
+    };
+    obj.load_mtls_fn(|_, _| Ok(MTL.as_bytes())).unwrap();
+    assert_eq!(obj.material("chrome").and_then(|m| m.ks), Some([1.0, 1.0, 1.0]));
+    assert!(obj.material("glass").is_none());
+    let index = obj.data.name_index();
+    assert_eq!(index.material("rubber"), obj.material("rubber"));
+}
+
+#[test]
+fn borrowed_group_material() {
+    let mut data = ObjData::load_buf(format!("mtllib car.mtl\n{}", SCENE).as_bytes()).unwrap();
+    data.material_libs[0].reload_str(MTL).unwrap();
+    let car = data.object("car").unwrap();
+    let wheel = car.group("wheel_front_left").unwrap();
+    assert!(wheel.material.as_ref().unwrap().as_material().is_none());
+    assert_eq!(wheel.material_ref(&data).and_then(|m| m.kd), Some([0.1, 0.1, 0.1]));
+    assert!(data.object("tree").unwrap().groups[0].material_ref(&data).is_none());
+
+    let mut bound = data.clone();
+    bound.resolve_materials();
+    let wheel = bound.object("car").unwrap().group("wheel_front_left").unwrap();
+    let material = wheel.material_ref(&bound).unwrap();
+    assert!(std::ptr::eq(
+        material,
+        wheel.material.as_ref().unwrap().as_material().unwrap()
+    ));
+    assert_eq!(material.name, "rubber");
+}
+
+#[test]
+fn default_material() {
+    let mut fallback = Material::new("fallback".to_string());
+    fallback.kd = Some([0.5, 0.5, 0.5]);
+    let config = LoadConfig::new().default_material(fallback);
+    let data = ObjData::load_buf_with(format!("mtllib car.mtl\n{}", SCENE).as_bytes(), config).unwrap();
+    let tree = &data.object("tree").unwrap().groups[0];
+    assert!(tree.material.is_none());
+    assert_eq!(tree.material_ref(&data).map(|m| m.name.as_str()), Some("fallback"));
+
+    // Groups with a `usemtl` keep their own material, even when it can't be found.
+    let wheel = data.object("car").unwrap().group("wheel_front_left").unwrap();
+    assert!(wheel.material_ref(&data).is_none());
+
+    let mut plain = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    assert!(plain.object("tree").unwrap().groups[0].material_ref(&plain).is_none());
+    plain.default_material = data.default_material.clone();
+    assert_eq!(
+        plain.object("tree").unwrap().groups[0]
+            .material_ref(&plain)
+            .and_then(|m| m.kd),
+        Some([0.5; 3])
+    );
+}
+
+#[test]
+fn rename_updates_references() {
+    let mut data = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    assert_eq!(data.rename_object("car", "truck"), 1);
+    assert!(data.object("car").is_none());
+    let truck = data.object_mut("truck").unwrap();
+    assert_eq!(truck.rename_group("wheel_front_left", "wheel"), 2);
+    assert_eq!(truck.groups_named("wheel").count(), 2);
+
+    assert!(data.rename_material("chrome", "steel"));
+    assert!(!data.rename_material("glass", "steel"));
+    let wheel = &data.objects[0].groups[1];
+    assert_eq!(wheel.material, Some(ObjMaterial::Ref("steel".into())));
+}
+
+#[test]
+fn rename_loaded_material() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(format!("mtllib car.mtl\n{}", SCENE).as_bytes()).unwrap(),
+        path: PathBuf::new(),
+    };
+    obj.load_mtls_fn(|_, _| Ok(MTL.as_bytes())).unwrap();
+    assert!(obj.rename_material("chrome", "steel"));
+    assert!(obj.material("chrome").is_none());
+    let steel = obj.material("steel").unwrap().clone();
+    assert_eq!(steel.ks, Some([1.0, 1.0, 1.0]));
+
+    // The groups share the renamed material with the library.
+    let groups = &obj.data.objects[0].groups;
+    assert_eq!(groups[1].material.as_ref().map(|m| m.name()), Some("steel"));
+    assert!(groups
+        .iter()
+        .any(|g| matches!(&g.material, Some(ObjMaterial::Mtl(m)) if std::sync::Arc::ptr_eq(m, &steel))));
+
+    let mut out = Vec::new();
+    obj.data.write_to_buf(&mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("usemtl steel"));
+    assert!(!text.contains("chrome"));
+}
+
+#[test]
+fn resolve_materials_reports_missing() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(format!("mtllib car.mtl\n{}usemtl glass\nf 1 2 3\n", SCENE).as_bytes()).unwrap(),
+        path: PathBuf::new(),
+    };
+    let mut mtl = Mtl::new("car.mtl".to_string());
+    mtl.reload(MTL.as_bytes()).unwrap();
+    obj.data.material_libs[0] = mtl;
+
+    let unresolved = obj.resolve_materials();
+    assert_eq!(
+        unresolved,
+        [UnresolvedMaterial {
+            material: "glass".into(),
+            object: "tree".into(),
+            group: "trunk".into(),
+        }]
+    );
+    let wheel = &obj.data.objects[0].groups;
+    assert_eq!(
+        wheel[0].material,
+        Some(ObjMaterial::Mtl(obj.material("rubber").unwrap().clone()))
+    );
+    assert_eq!(wheel[1].material.as_ref().map(|m| m.name()), Some("chrome"));
+    assert!(matches!(wheel[1].material, Some(ObjMaterial::Mtl(_))));
+
+    // Resolving again rebinds to the same materials.
+    assert_eq!(obj.resolve_materials(), unresolved);
+}
+
+#[test]
+fn attach_parsed_mtl() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(format!("mtllib car.mtl\n{}", SCENE).as_bytes()).unwrap(),
+        path: PathBuf::new(),
+    };
+    let mut mtl = Mtl::new(String::new());
+    mtl.reload_str("newmtl rubber\nKd 0.1 0.1 0.1\n").unwrap();
+    let unresolved = obj.attach_mtl("car.mtl", mtl);
+    assert_eq!(obj.data.material_libs.len(), 1);
+    assert_eq!(obj.data.material_libs[0].filename, "car.mtl");
+    let missing: Vec<_> = unresolved.iter().map(|u| (&*u.material, &*u.group)).collect();
+    assert_eq!(missing, [("chrome", "wheel_front_left"), ("chrome", "body")]);
+
+    let mut extra = Mtl::new(String::new());
+    extra.reload_str(MTL).unwrap();
+    assert!(obj.attach_mtl("extra.mtl", extra).is_empty());
+    assert_eq!(obj.data.material_libs.len(), 2);
+    let wheel = &obj.data.objects[0].groups;
+    assert!(matches!(&wheel[1].material, Some(ObjMaterial::Mtl(m)) if m.ks == Some([1.0, 1.0, 1.0])));
+    // The first library defining a material wins.
+    assert!(matches!(&wheel[0].material, Some(ObjMaterial::Mtl(m)) if m.kd == Some([0.1, 0.1, 0.1])));
+}
@@ -0,0 +1,142 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{EdgeIssue, ObjData, WindingReport};
+
+static TETRAHEDRON: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+f 1 3 2
+f 1 2 4
+f 2 3 4
+f 3 1 4
+";
+
+#[test]
+fn closed_mesh_is_watertight() {
+    let obj = ObjData::load_buf(TETRAHEDRON.as_bytes()).unwrap();
+    let report = obj.check_manifold();
+    assert!(report.is_watertight());
+}
+
+#[test]
+fn open_and_flipped_faces_are_reported() {
+    // Drop the last face and flip the first one.
+    let source = TETRAHEDRON.replace("f 1 3 2", "f 1 2 3").replace("f 3 1 4\n", "");
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let report = obj.check_manifold();
+    assert!(!report.is_manifold());
+    assert_eq!(
+        report.boundary_edges,
+        vec![
+            EdgeIssue {
+                edge: [0, 2],
+                polygons: vec![0]
+            },
+            EdgeIssue {
+                edge: [0, 3],
+                polygons: vec![1]
+            },
+            EdgeIssue {
+                edge: [2, 3],
+                polygons: vec![2]
+            },
+        ]
+    );
+    assert_eq!(
+        report.inconsistent_winding,
+        vec![
+            EdgeIssue {
+                edge: [0, 1],
+                polygons: vec![0, 1]
+            },
+            EdgeIssue {
+                edge: [1, 2],
+                polygons: vec![0, 2]
+            },
+        ]
+    );
+}
+
+#[test]
+fn non_manifold_edges_are_reported() {
+    let source = format!("{}v 1 1 1\nf 1 2 5\n", TETRAHEDRON);
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let report = obj.check_manifold();
+    assert_eq!(report.non_manifold_edges.len(), 1);
+    assert_eq!(report.non_manifold_edges[0].edge, [0, 1]);
+    assert_eq!(report.non_manifold_edges[0].polygons, vec![0, 1, 4]);
+}
+
+#[test]
+fn make_winding_consistent() {
+    // The flipped face is the minority and gets turned around.
+    let source = TETRAHEDRON.replace("f 1 3 2", "f 1 2 3");
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let report = obj.make_winding_consistent();
+    assert_eq!(report.flipped, 1);
+    assert_eq!(report.components, 1);
+    assert!(report.ambiguous.is_empty());
+    assert_eq!(obj, ObjData::load_buf(TETRAHEDRON.as_bytes()).unwrap());
+
+    // With most faces flipped the other one is reversed instead, which also makes it watertight.
+    let source = TETRAHEDRON.replace("f 1 2 4", "f 1 4 2").replace("f 2 3 4", "f 2 4 3");
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    assert_eq!(obj.make_winding_consistent().flipped, 2);
+    assert!(obj.check_manifold().is_watertight());
+    assert_eq!(obj.make_winding_consistent().flipped, 0);
+}
+
+#[test]
+fn non_orientable_components_are_reported() {
+    // A Möbius strip of four quads, followed by a pair of triangles with opposite windings.
+    let source = "
+v 0 0 0
+v 1 0 0
+v 2 0 0
+v 3 0 0
+v 0 1 0
+v 1 1 0
+v 2 1 0
+v 3 1 0
+v 0 0 5
+v 1 0 5
+v 0 1 5
+v 1 1 5
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 5 1 8
+f 9 10 11
+f 10 11 12
+";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let before = obj.clone();
+    let report = obj.make_winding_consistent();
+    assert_eq!(
+        report,
+        WindingReport {
+            flipped: 1,
+            components: 2,
+            ambiguous: vec![vec![0, 1, 2, 3]],
+        }
+    );
+    let (polys, original) = (&obj.objects[0].groups[0].polys, &before.objects[0].groups[0].polys);
+    assert_eq!(polys[..5], original[..5]);
+    assert_ne!(polys[5], original[5]);
+}
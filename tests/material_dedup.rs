@@ -0,0 +1,78 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{Mtl, Obj, ObjData, ObjMaterial};
+
+const OBJ: &str = "
+mtllib a.mtl
+mtllib b.mtl
+v 0 0 0
+v 1 0 0
+v 0 1 0
+usemtl red
+f 1 2 3
+usemtl crimson
+f 1 3 2
+usemtl blue
+f 2 1 3
+usemtl scarlet
+f 3 2 1
+";
+
+#[test]
+fn dedup_materials() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(OBJ.as_bytes()).unwrap(),
+        path: std::path::PathBuf::new(),
+    };
+    let a = "newmtl red\nKd 1 0 0\nNs 10\nnewmtl blue\nKd 0 0 1\n";
+    let b = "newmtl crimson\nKd 1 0 0.0001\nNs 10\nnewmtl blue\nKd 0 0 1\nnewmtl scarlet\nKd 1 0 0\nNs 20\n";
+    obj.load_mtls_fn(|_, name| Ok(if name == "a.mtl" { a.as_bytes() } else { b.as_bytes() }))
+        .unwrap();
+
+    let merges = obj.dedup_materials(0.001);
+    let merges: Vec<_> = merges
+        .iter()
+        .map(|m| (m.library.as_str(), m.name.as_str(), m.merged_into.as_str()))
+        .collect();
+    assert_eq!(merges, [("b.mtl", "crimson", "red"), ("b.mtl", "blue", "blue")]);
+
+    let names = |mtl: &Mtl| -> Vec<String> { mtl.materials.iter().map(|m| m.name.clone()).collect() };
+    assert_eq!(names(&obj.data.material_libs[0]), ["red", "blue"]);
+    assert_eq!(names(&obj.data.material_libs[1]), ["scarlet"]);
+
+    let used: Vec<_> = obj.data.objects[0]
+        .groups
+        .iter()
+        .filter(|g| !g.is_empty())
+        .map(|g| match &g.material {
+            Some(ObjMaterial::Mtl(m)) => m.name.clone(),
+            other => panic!("unexpected material {:?}", other),
+        })
+        .collect();
+    assert_eq!(used, ["red", "red", "blue", "scarlet"]);
+
+    // Nothing is left to merge, and a strict tolerance keeps everything apart.
+    assert!(obj.dedup_materials(0.001).is_empty());
+    let mut strict = Obj {
+        data: ObjData::load_buf(OBJ.as_bytes()).unwrap(),
+        path: std::path::PathBuf::new(),
+    };
+    strict
+        .load_mtls_fn(|_, name| Ok(if name == "a.mtl" { a.as_bytes() } else { b.as_bytes() }))
+        .unwrap();
+    assert_eq!(strict.dedup_materials(0.0).len(), 1);
+}
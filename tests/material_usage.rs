@@ -0,0 +1,70 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{MaterialUse, Mtl, ObjData, UnusedMaterial};
+
+static SOURCE: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+o a
+usemtl red
+f 1 2 3
+f 3 2 1
+usemtl ghost
+l 1 2
+o b
+usemtl red
+f 1 2 3
+g plain
+usemtl
+f 1 2 3
+";
+
+#[test]
+fn material_usage() {
+    let mut data = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let mut mtl = Mtl::new("scene.mtl".to_string());
+    mtl.reload_str("newmtl red\nnewmtl blue\nnewmtl red\n").unwrap();
+    data.material_libs.push(mtl);
+    data.resolve_materials();
+
+    let usage = data.material_usage();
+    assert_eq!(
+        usage.used,
+        vec![
+            MaterialUse {
+                name: "red".to_string(),
+                defined: true,
+                groups: vec![(0, 0), (1, 0)],
+                polygons: 3,
+                lines: 0,
+            },
+            MaterialUse {
+                name: "ghost".to_string(),
+                defined: false,
+                groups: vec![(0, 1)],
+                polygons: 0,
+                lines: 1,
+            },
+        ]
+    );
+    let unused = |name: &str| UnusedMaterial {
+        library: "scene.mtl".to_string(),
+        name: name.to_string(),
+    };
+    assert_eq!(usage.unused, vec![unused("blue"), unused("red")]);
+}
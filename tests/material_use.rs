@@ -12,10 +12,12 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
-use obj::ObjData;
+#![cfg(feature = "std")]
+
+use obj::{LoadConfig, ObjData, ObjRef};
 use std::io::BufReader;
 
-static SQUARE: &'static str = "
+static SQUARE: &str = "
 v 0 0 0
 v 1 1 1
 v 1 0 1
@@ -27,7 +29,6 @@ g group_b
 f 1 4 2
 ";
 
-
 #[test]
 fn test_material_use_persistence() {
     let mut reader = BufReader::new(SQUARE.as_bytes());
@@ -72,6 +73,244 @@ fn test_partial_material_use() {
     let group_a = obj.groups.first().unwrap();
     let group_b = obj.groups.last().unwrap();
 
-    assert!(group_a.material.is_none(), "Group A should not have a material assigned.");
+    assert!(
+        group_a.material.is_none(),
+        "Group A should not have a material assigned."
+    );
     assert!(group_b.material.is_some(), "Group B should have a material assigned.");
 }
+
+#[test]
+fn material_to_pbr() {
+    let mut material = obj::Material::new("glass".to_string());
+    material.kd = Some([0.5, 0.25, 1.0]);
+    material.ns = Some(0.0);
+    material.tr = Some(0.75);
+    material.map_kd = Some("glass.png".to_string());
+    let pbr = material.to_pbr();
+    assert_eq!(pbr.base_color, [0.5, 0.25, 1.0, 0.25]);
+    assert_eq!(pbr.roughness, 1.0);
+    assert_eq!(pbr.metallic, 0.0);
+    assert_eq!(pbr.base_color_texture.as_deref(), Some("glass.png"));
+    assert!(pbr.transparent);
+
+    let default = obj::Material::new("default".to_string()).to_pbr();
+    assert_eq!(default.base_color, [1.0; 4]);
+    assert!(!default.transparent);
+}
+
+#[test]
+fn material_color_space() {
+    use obj::ColorSpace;
+
+    let mut material = obj::Material::new("brick".to_string());
+    material.kd = Some([0.5, 0.0, 1.0]);
+    material.ke = Some([0.04, 0.04, 0.04]);
+    let [r, g, b] = material.kd_linear(ColorSpace::Srgb).unwrap();
+    assert!((r - 0.214_041).abs() < 1e-5);
+    assert_eq!((g, b), (0.0, 1.0));
+    assert_eq!(material.kd_linear(ColorSpace::Linear), material.kd);
+    assert_eq!(material.ks_linear(ColorSpace::Srgb), None);
+
+    let back = ColorSpace::Srgb.from_linear([r, g, b]);
+    assert!((back[0] - 0.5).abs() < 1e-5);
+
+    let pbr = material.to_pbr_in(ColorSpace::Srgb);
+    assert_eq!(pbr.base_color[0], r);
+    assert!((pbr.emissive[0] - 0.04 / 12.92).abs() < 1e-7);
+    assert_eq!(pbr.source_color_space, ColorSpace::Srgb);
+    assert_eq!(material.to_pbr().source_color_space, ColorSpace::Linear);
+}
+
+#[test]
+fn texture_paths_skip_options() {
+    let mut material = obj::Material::new("stone".to_string());
+    material.map_kd = Some("-s 2 2 -clamp on textures/stone diffuse.png".to_string());
+    material.map_bump = Some("-bm 0.5 -o 0.1 stone_bump.png".to_string());
+    material.map_d = Some("-mm 0 1 alpha.png".to_string());
+    assert_eq!(
+        material.texture_paths().collect::<Vec<_>>(),
+        vec![
+            (obj::TextureSlot::Diffuse, "textures/stone diffuse.png"),
+            (obj::TextureSlot::Dissolve, "alpha.png"),
+            (obj::TextureSlot::Bump, "stone_bump.png"),
+        ]
+    );
+}
+
+static MATERIAL_RESET: &str = "
+v 0 0 0
+v 1 1 1
+v 1 0 1
+v 0 1 0
+usemtl test
+f 1 2 3
+usemtl
+f 1 4 2
+usemtl test
+g group_b
+f 1 2 3
+usemtl None
+g group_c
+f 1 4 2
+";
+
+#[test]
+fn material_reset() {
+    let obj_data = ObjData::load_buf(MATERIAL_RESET.as_bytes()).unwrap();
+    let materials: Vec<_> = obj_data.objects[0]
+        .groups
+        .iter()
+        .map(|g| (&*g.name, g.material.as_ref().map(|m| m.name().to_string())))
+        .collect();
+    let test = Some("test".to_string());
+    assert_eq!(
+        materials,
+        [
+            ("default", test.clone()),
+            ("default", None),
+            ("default", test.clone()),
+            ("group_b", test.clone()),
+            ("group_b", None),
+            ("group_c", None),
+        ]
+    );
+
+    // Cleared materials survive a round trip.
+    let reloaded = ObjData::load_buf(obj_data.to_string().as_bytes()).unwrap();
+    let polys_with_material = |data: &ObjData| -> Vec<_> {
+        data.objects[0]
+            .groups
+            .iter()
+            .flat_map(|g| g.polys.iter().map(move |_| g.material.is_some()))
+            .collect()
+    };
+    assert_eq!(polys_with_material(&reloaded), [true, false, true, false]);
+
+    let borrowed = ObjRef::parse(MATERIAL_RESET).unwrap();
+    assert_eq!(borrowed.objects[0].groups[5].material, None);
+
+    let named =
+        ObjData::load_buf_with(MATERIAL_RESET.as_bytes(), LoadConfig::new().none_clears_material(false)).unwrap();
+    let group_c = named.objects[0].groups.last().unwrap();
+    assert_eq!(group_c.material.as_ref().map(|m| m.name()), Some("None"));
+}
+
+static BARE_GROUP: &str = "
+v 0 0 0
+v 1 1 1
+v 1 0 1
+usemtl test
+g group_a
+f 1 2 3
+g
+f 1 3 2
+g  
+l 1 2
+";
+
+#[test]
+fn bare_group_switches_to_default() {
+    let obj_data = ObjData::load_buf(BARE_GROUP.as_bytes()).unwrap();
+    let groups: Vec<_> = obj_data.objects[0]
+        .groups
+        .iter()
+        .map(|g| {
+            (
+                &*g.name,
+                g.material.as_ref().map(|m| m.name()),
+                g.polys.len(),
+                g.lines.len(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("default", Some("test"), 0, 0),
+            ("group_a", Some("test"), 1, 0),
+            ("default", Some("test"), 1, 0),
+            ("default", Some("test"), 0, 1),
+        ]
+    );
+
+    let borrowed = ObjRef::parse(BARE_GROUP).unwrap();
+    let names: Vec<_> = borrowed.objects[0]
+        .groups
+        .iter()
+        .map(|g| (&*g.name, g.material.as_deref()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("default", Some("test")),
+            ("group_a", Some("test")),
+            ("default", Some("test")),
+            ("default", Some("test"))
+        ]
+    );
+
+    let legacy = ObjData::load_buf_with(
+        BARE_GROUP.as_bytes(),
+        LoadConfig::new().bare_group_keeps_material(false),
+    )
+    .unwrap();
+    let materials: Vec<_> = legacy.objects[0].groups.iter().map(|g| g.material.is_some()).collect();
+    assert_eq!(materials, [true, true, false, false]);
+}
+
+#[test]
+fn map_aat_directive() {
+    let mut mtl = obj::Mtl::new("aat.mtl".to_string());
+    mtl.reload_str("newmtl smooth\nmap_Kd a.png\nmap_aat on\nnewmtl sharp\nmap_aat off\nnewmtl plain\n")
+        .unwrap();
+    let aat: Vec<bool> = mtl.materials.iter().map(|m| m.map_aat).collect();
+    assert_eq!(aat, [true, false, false]);
+
+    let mut text = String::new();
+    mtl.write_to_fmt(&mut text).unwrap();
+    assert_eq!(text.matches("map_aat on").count(), 1);
+    let mut reloaded = obj::Mtl::new("aat.mtl".to_string());
+    reloaded.reload_str(&text).unwrap();
+    assert_eq!(reloaded, mtl);
+
+    assert!(obj::Mtl::new(String::new())
+        .reload_str("newmtl bad\nmap_aat maybe\n")
+        .is_err());
+}
+
+#[test]
+fn material_predicates() {
+    let mut material = obj::Material::new("plain".to_string());
+    material.kd = Some([0.5; 3]);
+    material.ke = Some([0.0; 3]);
+    material.tf = Some([1.0; 3]);
+    assert!(!material.is_transparent());
+    assert!(!material.is_emissive());
+    assert!(!material.has_textures());
+
+    let transparent = |edit: fn(&mut obj::Material)| {
+        let mut m = material.clone();
+        edit(&mut m);
+        m.is_transparent()
+    };
+    assert!(transparent(|m| m.d = Some(0.5)));
+    assert!(transparent(|m| m.tr = Some(0.25)));
+    assert!(!transparent(|m| {
+        m.d = Some(1.0);
+        m.tr = Some(0.25);
+    }));
+    assert!(transparent(|m| m.tf = Some([1.0, 0.5, 1.0])));
+    assert!(transparent(|m| m.map_d = Some("alpha.png".to_string())));
+
+    material.ke = Some([0.0, 0.0, 2.0]);
+    assert!(material.is_emissive());
+    material.ke = None;
+    material.map_ke = Some("-bm 1 glow.png".to_string());
+    assert!(material.is_emissive());
+    assert!(material.has_textures());
+    assert_eq!(
+        material.texture_paths().collect::<Vec<_>>(),
+        [(obj::TextureSlot::Emissive, "glow.png")]
+    );
+}
@@ -0,0 +1,69 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static CUBE: &str = "
+o cube
+v 0 0 0
+v 2 0 0
+v 2 2 0
+v 0 2 0
+v 0 0 2
+v 2 0 2
+v 2 2 2
+v 0 2 2
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+o triangle
+v 0 0 5
+v 1 0 5
+v 0 1 5
+f 9 10 11
+";
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+}
+
+#[test]
+fn area_and_volume() {
+    let mut obj = ObjData::load_buf(CUBE.as_bytes()).unwrap();
+    assert_close(obj.surface_area(), 24.5);
+    assert_close(obj.objects[0].surface_area(&obj), 24.);
+    assert_close(obj.objects[0].signed_volume(&obj), 8.);
+
+    // Volume does not depend on the position of the origin.
+    obj.translate([10., -3., 7.]);
+    assert_close(obj.objects[0].signed_volume(&obj), 8.);
+
+    obj.flip_winding();
+    assert_close(obj.objects[0].signed_volume(&obj), -8.);
+}
+
+#[test]
+fn polygons_with_missing_positions_are_left_out() {
+    let valid = ObjData::load_buf(CUBE.as_bytes()).unwrap();
+    let obj = ObjData::load_buf(format!("{}f 1 2 3 99\n", CUBE).as_bytes()).unwrap();
+    assert!(obj.validate_indices().is_err());
+    assert_close(obj.surface_area(), 24.5);
+    assert_close(obj.signed_volume(), valid.signed_volume());
+    assert_close(obj.objects[1].surface_area(&obj), 0.5);
+}
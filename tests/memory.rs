@@ -0,0 +1,66 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, ObjData};
+
+#[test]
+fn memory_breakdown() {
+    let input = "\
+o a
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vn 0 0 1
+g shared
+f 1//1 2//1 3//1
+o b
+g shared
+l 1 2
+";
+    let mut data = ObjData::load_buf(input.as_bytes()).unwrap();
+    data.position.shrink_to_fit();
+    data.normal.shrink_to_fit();
+    for group in data.objects.iter_mut().flat_map(|o| o.groups.iter_mut()) {
+        group.polys.shrink_to_fit();
+        group.lines.shrink_to_fit();
+    }
+
+    let usage = data.memory_usage();
+    assert_eq!(usage.positions, 3 * 12);
+    assert_eq!(usage.normals, 12);
+    assert_eq!(usage.texture_coords, 0);
+    assert_eq!(usage.colors, 0);
+    let tuple = std::mem::size_of::<IndexTuple>();
+    assert!(usage.polygons >= 3 * tuple);
+    assert!(usage.lines >= 2 * tuple);
+    assert_eq!(
+        usage.materials,
+        data.material_libs.capacity() * std::mem::size_of::<obj::Mtl>()
+    );
+    // "a", "b" and the interned "shared", each behind an `Arc` with two counters.
+    let counters = 2 * std::mem::size_of::<usize>();
+    assert_eq!(usage.strings, 3 * counters + 1 + 1 + 6);
+    assert_eq!(
+        usage.total(),
+        usage.positions
+            + usage.normals
+            + usage.polygons
+            + usage.lines
+            + usage.objects
+            + usage.strings
+            + usage.materials
+    );
+}
@@ -0,0 +1,188 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{Material, Mtl, NameCollision, ObjData};
+use std::sync::Arc;
+
+static SCENE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+o a
+g wall
+usemtl brick
+f 1 2 3
+g floor
+usemtl wood
+f 1 2 3
+o b
+g door
+usemtl wood
+f 1 2 3
+f 1 3 2
+g trim
+usemtl brick
+f 3 2 1
+";
+
+fn load() -> ObjData {
+    ObjData::load_buf(SCENE.as_bytes()).unwrap()
+}
+
+fn round_trip(obj: &ObjData) -> ObjData {
+    let mut out = Vec::new();
+    obj.write_to_buf(&mut out).unwrap();
+    ObjData::load_buf(out.as_slice()).unwrap()
+}
+
+#[test]
+fn merge_objects_keeps_groups() {
+    let mut obj = load();
+    obj.merge_objects("scene".to_string());
+    assert_eq!(obj.objects.len(), 1);
+    assert_eq!(&*obj.objects[0].name, "scene");
+    let names: Vec<_> = obj.objects[0].groups.iter().map(|g| &*g.name).collect();
+    assert_eq!(names, ["wall", "floor", "door", "trim"]);
+}
+
+#[test]
+fn merge_groups_by_material_after_merging_objects() {
+    let mut obj = load();
+    obj.merge_objects("scene".to_string());
+    obj.merge_groups_by_material();
+
+    let groups = &obj.objects[0].groups;
+    assert_eq!(groups.len(), 2);
+    assert_eq!(&*groups[0].name, "wall");
+    assert_eq!(groups[0].material.as_ref().unwrap().name(), "brick");
+    assert_eq!(groups[0].polys.len(), 2);
+    assert_eq!(&*groups[1].name, "floor");
+    assert_eq!(groups[1].material.as_ref().unwrap().name(), "wood");
+    assert_eq!(groups[1].polys.len(), 3);
+
+    assert_eq!(round_trip(&obj), obj);
+}
+
+#[test]
+fn merge_groups_by_predicate_preserves_material_ranges() {
+    let mut obj = load();
+    obj.merge_objects("scene".to_string());
+    obj.objects[0].merge_groups_by(|_, _| true);
+
+    let groups = &obj.objects[0].groups;
+    assert_eq!(groups.len(), 2);
+    assert!(groups.iter().all(|g| &*g.name == "wall"));
+    assert_eq!(groups[0].index, 0);
+    assert_eq!(groups[1].index, 1);
+    assert_eq!(groups[0].polys.len(), 2);
+    assert_eq!(groups[1].polys.len(), 3);
+
+    assert_eq!(round_trip(&obj), obj);
+}
+
+static PART: &str = "
+v 5 5 5
+v 6 5 5
+v 6 6 5
+vn 0 0 1
+o b
+g door
+usemtl wood
+f 1//1 2//1 3//1
+g handle
+usemtl metal
+l 1 2
+o c
+f 3 2 1
+";
+
+fn load_part() -> ObjData {
+    let mut part = ObjData::load_buf(PART.as_bytes()).unwrap();
+    let mut lib = Mtl::new("part.mtl".to_string());
+    for name in &["wood", "metal"] {
+        lib.materials.push(Arc::new(Material::new(name.to_string())));
+    }
+    part.material_libs.push(lib);
+    part
+}
+
+#[test]
+fn append_offsets_indices() {
+    let mut obj = load();
+    obj.append_with(load_part(), NameCollision::Keep);
+    assert_eq!(obj.position.len(), 6);
+    assert_eq!(obj.normal.len(), 1);
+    let names: Vec<_> = obj.objects.iter().map(|o| &*o.name).collect();
+    assert_eq!(names, ["a", "b", "b", "c"]);
+
+    let door = &obj.objects[2].groups[0];
+    for (tuple, p) in door.polys[0].0.iter().zip(3..) {
+        assert_eq!(tuple.position, p);
+        assert_eq!(tuple.normal, Some(0));
+    }
+    assert_eq!(obj.position[5], [6.0, 6.0, 5.0]);
+    assert_eq!(obj.objects[2].groups[1].lines[0].0[1].position, 4);
+    assert_eq!(obj.objects[3].groups[0].polys[0].0[0].position, 5);
+    assert_eq!(obj.material_libs.len(), 1);
+    assert_eq!(round_trip(&obj).position, obj.position);
+}
+
+#[test]
+fn append_renames_colliding_objects() {
+    let mut obj = load();
+    obj.append(load_part());
+    obj.append(load_part());
+    let names: Vec<_> = obj.objects.iter().map(|o| &*o.name).collect();
+    assert_eq!(names, ["a", "b", "b.1", "c", "b.2", "c.1"]);
+    assert_eq!(obj.position.len(), 9);
+}
+
+#[test]
+fn append_merges_colliding_objects() {
+    let mut obj = load();
+    obj.append_with(load_part(), NameCollision::Merge);
+    let names: Vec<_> = obj.objects.iter().map(|o| &*o.name).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+
+    let groups: Vec<_> = obj.objects[1].groups.iter().map(|g| (&*g.name, g.index)).collect();
+    assert_eq!(groups, [("door", 0), ("trim", 0), ("handle", 0)]);
+    let door = &obj.objects[1].groups[0];
+    assert_eq!(door.polys.len(), 3);
+    assert_eq!(door.polys[2].0[0].position, 3);
+}
+
+#[test]
+fn append_pads_colors_and_unions_materials() {
+    let mut obj = ObjData::load_buf("v 0 0 0 1 0 0\nf 1 1 1\n".as_bytes()).unwrap();
+    let mut lib = Mtl::new("part.mtl".to_string());
+    let mut wood = Material::new("wood".to_string());
+    wood.ns = Some(10.0);
+    lib.materials.push(Arc::new(wood));
+    obj.material_libs.push(lib);
+
+    obj.append(load_part());
+    assert_eq!(obj.color, [[1.0, 0.0, 0.0], [1.0; 3], [1.0; 3], [1.0; 3]]);
+    assert_eq!(obj.material_libs.len(), 1);
+    let names: Vec<_> = obj.material_libs[0].materials.iter().map(|m| &*m.name).collect();
+    assert_eq!(names, ["wood", "metal"]);
+    assert_eq!(obj.material_libs[0].materials[0].ns, Some(10.0));
+
+    let mut other = load_part();
+    other.material_libs[0].filename = "other.mtl".to_string();
+    obj.append(other);
+    assert_eq!(obj.material_libs.len(), 2);
+    assert_eq!(obj.color.len(), obj.position.len());
+}
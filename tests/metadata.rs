@@ -0,0 +1,93 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{Axis, CoordinateConvention, LoadConfig, ObjData, ObjMetadata};
+
+#[test]
+fn exporter_headers() {
+    let blender = "# Blender v2.93.1 OBJ File: ''\n# www.blender.org\nmtllib cube.mtl\nv 0 0 0\n";
+    let obj = ObjData::load_buf(blender.as_bytes()).unwrap();
+    assert_eq!(obj.metadata.application.as_deref(), Some("Blender v2.93.1"));
+    assert_eq!(obj.metadata.meters_per_unit, None);
+
+    let max = "# 3ds Max Wavefront OBJ Exporter v0.97b - (c)2007 guruware\n# File Created: 16.03.2015\n";
+    let obj = ObjData::load_buf(max.as_bytes()).unwrap();
+    assert_eq!(
+        obj.metadata.application.as_deref(),
+        Some("3ds Max Wavefront OBJ Exporter v0.97b")
+    );
+
+    let maya = "# This file uses centimeters as units for non-parametric coordinates.\nv 0 0 0\n";
+    let obj = ObjData::load_buf(maya.as_bytes()).unwrap();
+    assert_eq!(obj.metadata.meters_per_unit, Some(0.01));
+
+    let generic = "#Exported by MeshTool 2.1\n# Units: 0.5 m\n# UpAxis: -z\n";
+    let obj = ObjData::load_buf(generic.as_bytes()).unwrap();
+    assert_eq!(
+        obj.metadata,
+        ObjMetadata::default()
+            .application("MeshTool 2.1")
+            .meters_per_unit(0.5)
+            .up_axis(Axis::NegZ)
+    );
+}
+
+#[test]
+fn only_header_comments() {
+    let source = "v 0 0 0\n# Units: mm\n# Up axis: Z\n";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    assert!(obj.metadata.is_empty());
+}
+
+#[test]
+fn round_trip() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    obj.write_to_buf(&mut out).unwrap();
+    assert!(ObjData::load_buf(out.as_slice()).unwrap().metadata.is_empty());
+
+    for meters in [0.0254, 0.25] {
+        obj.metadata = ObjMetadata::default()
+            .application("Scanner")
+            .meters_per_unit(meters)
+            .up_axis(Axis::PosY);
+        let mut out = Vec::new();
+        obj.write_to_buf(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("# Up axis: +Y\n"));
+        assert_eq!(ObjData::load_buf(text.as_bytes()).unwrap(), obj);
+    }
+
+    // Faithful writes reproduce the original comments instead.
+    let faithful = "# Units: cm\nv 0 0 0\n";
+    let mut obj = ObjData::load_buf_with(faithful.as_bytes(), LoadConfig::default().faithful(true)).unwrap();
+    assert_eq!(obj.metadata.meters_per_unit, Some(0.01));
+    obj.metadata.meters_per_unit = None;
+    let mut out = Vec::new();
+    obj.write_to_buf(&mut out).unwrap();
+    assert_eq!(out, faithful.as_bytes());
+}
+
+#[test]
+fn convert_coordinates() {
+    let mut obj = ObjData::load_buf("# Units: cm\n# Up axis: Z\nv 0 0 100\n".as_bytes()).unwrap();
+    let from = CoordinateConvention::Z_UP_RIGHT_HANDED.with_meters_per_unit(obj.metadata.meters_per_unit.unwrap());
+    obj.convert_coordinates(&from, &CoordinateConvention::Y_UP_RIGHT_HANDED);
+    assert_eq!(obj.position, vec![[0., 1., 0.]]);
+    assert_eq!(obj.metadata.up_axis, Some(Axis::PosY));
+    assert_eq!(obj.metadata.meters_per_unit, Some(1.0));
+}
@@ -0,0 +1,32 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "mint")]
+
+use obj::ObjData;
+
+#[test]
+fn mint_accessors() {
+    let mut obj = ObjData::load_buf("v 1 2 3\nvt 0.5 1\nvn 0 0 1\n".as_bytes()).unwrap();
+    let p: Vec<mint::Point3<f32>> = obj.mint_positions().collect();
+    assert_eq!(p, vec![mint::Point3 { x: 1., y: 2., z: 3. }]);
+    assert_eq!(obj.mint_texture_coords().next(), Some(mint::Vector2 { x: 0.5, y: 1. }));
+    assert_eq!(obj.mint_normals().len(), 1);
+
+    assert_eq!(obj.push_mint_position(mint::Point3 { x: 4., y: 5., z: 6. }), 1);
+    assert_eq!(obj.push_mint_texture_coord([0., 0.]), 1);
+    assert_eq!(obj.push_mint_normal(mint::Vector3 { x: 1., y: 0., z: 0. }), 1);
+    assert_eq!(obj.position[1], [4., 5., 6.]);
+    assert_eq!(obj.normal[1], [1., 0., 0.]);
+}
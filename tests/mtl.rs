@@ -0,0 +1,33 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use obj::{IllumModel, Mtl};
+
+#[test]
+fn illum_model() {
+    let mut mtl = Mtl::new("scene.mtl".to_string());
+    mtl.reload_str("newmtl glass\nillum 4\nnewmtl odd\nillum 42\nnewmtl plain\n")
+        .unwrap();
+    let models: Vec<_> = mtl.materials.iter().map(|m| m.illum_model()).collect();
+    assert_eq!(
+        models,
+        [Some(IllumModel::GlassRayTrace), Some(IllumModel::Other(42)), None]
+    );
+    assert!(IllumModel::GlassRayTrace.is_ray_traced());
+    assert!(!IllumModel::Glass.is_ray_traced());
+
+    for value in -1..=11 {
+        assert_eq!(i32::from(IllumModel::from(value)), value);
+    }
+}
@@ -0,0 +1,34 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "nalgebra")]
+
+use nalgebra::{Matrix4, Point3, Vector2, Vector3};
+use obj::ObjData;
+
+#[test]
+fn nalgebra_accessors_and_transform() {
+    let mut obj = ObjData::load_buf("v 1 0 0\nvt 0.5 1\nvn 1 0 0\n".as_bytes()).unwrap();
+    assert_eq!(
+        obj.nalgebra_positions().collect::<Vec<_>>(),
+        vec![Point3::new(1., 0., 0.)]
+    );
+    assert_eq!(obj.nalgebra_texture_coords().next(), Some(Vector2::new(0.5, 1.)));
+
+    let matrix = Matrix4::new_translation(&Vector3::new(0., 0., 2.))
+        * Matrix4::new_nonuniform_scaling(&Vector3::new(3., 1., 1.));
+    obj.transform_nalgebra(&matrix);
+    assert_eq!(obj.nalgebra_positions().next(), Some(Point3::new(3., 0., 2.)));
+    assert_eq!(obj.nalgebra_normals().next(), Some(Vector3::new(1., 0., 0.)));
+}
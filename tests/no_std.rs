@@ -0,0 +1,62 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+// Only uses the API that is available without the `std` feature, so that these tests also run with
+// `--no-default-features`.
+
+use obj::{LoadConfig, Mtl, ObjData, ObjError, ObjMaterial};
+
+static SQUARE: &str = "mtllib square.mtl\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nusemtl red\nf 1 2 3 4\nl 1 3\n";
+
+#[test]
+fn parse_and_write() {
+    let data: ObjData = ObjData::parse_str(SQUARE, LoadConfig::default()).unwrap();
+    assert_eq!(data.position.len(), 4);
+    assert_eq!(data.material_libs[0].filename, "square.mtl");
+    let group = &data.objects[0].groups[0];
+    assert_eq!(group.polys[0].0.len(), 4);
+    assert_eq!(group.lines[0].0.len(), 2);
+    assert!(matches!(&group.material, Some(ObjMaterial::Ref(name)) if &**name == "red"));
+
+    let mut text = String::new();
+    data.write_to_fmt(&mut text).unwrap();
+    let reparsed: ObjData = ObjData::parse_slice(text.as_bytes(), LoadConfig::default()).unwrap();
+    assert_eq!(reparsed.position, data.position);
+    assert_eq!(reparsed.objects, data.objects);
+}
+
+#[test]
+fn parse_errors() {
+    assert!(matches!(
+        ObjData::<f32>::parse_str("v 0 0 0\nf 0 1 1\n", LoadConfig::default()),
+        Err(ObjError::ZeroVertexNumber { line_number: 1 })
+    ));
+    assert!(matches!(
+        ObjData::<f32>::parse_slice(b"o \xff\n", LoadConfig::default()),
+        Err(ObjError::InvalidUtf8 { line_number: 0 })
+    ));
+}
+
+#[test]
+fn mtl_from_str() {
+    let mut mtl = Mtl::new("square.mtl".to_string());
+    mtl.reload_str("newmtl red\nKd 1 0 0\n").unwrap();
+    assert_eq!(mtl.materials[0].kd, Some([1.0, 0.0, 0.0]));
+
+    let mut text = String::new();
+    mtl.write_to_fmt(&mut text).unwrap();
+    let mut reloaded = Mtl::new(mtl.filename.clone());
+    reloaded.reload_str(&text).unwrap();
+    assert_eq!(reloaded, mtl);
+}
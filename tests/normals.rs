@@ -0,0 +1,75 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, NormalRepairConfig, ObjData};
+
+static QUAD: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 2
+vn 0 0 0
+vn 0 0 -1
+f 1//1 2//2 3//3
+f 1//1 3//2 4//3
+";
+
+#[test]
+fn fix_normals_renormalizes_and_replaces() {
+    let mut obj = ObjData::load_buf(QUAD.as_bytes()).unwrap();
+    let report = obj.fix_normals();
+    assert_eq!(report.renormalized, 1);
+    assert_eq!(report.replaced, 2);
+    assert_eq!(report.reoriented, 0);
+    assert_eq!(obj.normal[0], [0., 0., 1.]);
+
+    // Each face gets its own replacement normal.
+    assert_eq!(obj.normal.len(), 5);
+    assert_eq!(obj.normal[3], [0., 0., 1.]);
+    let polys = &obj.objects[0].groups[0].polys;
+    assert_eq!(polys[0].0[1], IndexTuple::new(1, None, Some(3)));
+    assert_eq!(polys[1].0[1], IndexTuple::new(2, None, Some(4)));
+    // The flipped normal is kept without reorientation.
+    assert_eq!(polys[0].0[2], IndexTuple::new(2, None, Some(2)));
+}
+
+#[test]
+fn fix_normals_reorients() {
+    let mut obj = ObjData::load_buf(QUAD.as_bytes()).unwrap();
+    let report = obj.fix_normals_with_config(NormalRepairConfig { reorient: true });
+    assert_eq!(report.reoriented, 2);
+    assert_eq!(report.total(), 5);
+
+    // Both faces share a single flipped copy.
+    let polys = &obj.objects[0].groups[0].polys;
+    let flipped = polys[0].0[2].normal.unwrap();
+    assert_eq!(polys[1].0[2].normal, Some(flipped));
+    assert_eq!(obj.normal[flipped], [0., 0., 1.]);
+}
+
+#[test]
+fn check_normal_orientation() {
+    let mut obj = ObjData::load_buf(QUAD.as_bytes()).unwrap();
+    let inverted = obj.check_normal_orientation();
+    assert_eq!(inverted.normals, [2]);
+    assert_eq!(inverted.positions, [2, 3]);
+
+    assert_eq!(obj.fix_normal_orientation(), inverted);
+    assert_eq!(obj.normal[2], [0., 0., 1.]);
+    assert_eq!(obj.normal.len(), 3);
+    assert!(obj.check_normal_orientation().is_empty());
+}
@@ -0,0 +1,65 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::off::{self, OffError};
+use obj::{IndexTuple, ObjData};
+
+#[test]
+fn load_with_counts_on_keyword_line() {
+    let source = "OFF 4 2 0\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n3 0 1 2 255 0 0\n3 0 2 3\n";
+    let data = off::load_buf(source.as_bytes()).unwrap();
+    assert_eq!(data.position.len(), 4);
+    let polys = &data.objects[0].groups[0].polys;
+    assert_eq!(polys.len(), 2);
+    assert_eq!(polys[1].0[2], IndexTuple::new(3, None, None));
+}
+
+#[test]
+fn load_optional_vertex_attributes() {
+    let source = "STCNOFF\n3 1 3\n0 0 0 0 0 1 255 0 0 255 0 0\n1 0 0 0 0 1 0 255 0 255 1 0\n0 1 0 0 0 1 0 0 255 255 0 1\n3 0 1 2\n";
+    let data = off::load_buf(source.as_bytes()).unwrap();
+    assert_eq!(data.normal, vec![[0.0, 0.0, 1.0]; 3]);
+    assert_eq!(data.color[1], [0.0, 1.0, 0.0]);
+    assert_eq!(data.texture, vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+    assert_eq!(
+        data.objects[0].groups[0].polys[0].0[2],
+        IndexTuple::new(2, Some(2), Some(2))
+    );
+}
+
+#[test]
+fn round_trip_colors() {
+    let data = ObjData::load_buf("v 0 0 0 1 0 0\nv 1 0 0 0 1 0\nv 0 1 0 0 0 1\nf 1 2 3\n".as_bytes()).unwrap();
+    let mut buf = Vec::new();
+    off::write(&data, &mut buf).unwrap();
+    assert!(buf.starts_with(b"COFF\n"));
+    let loaded = off::load_buf(&buf[..]).unwrap();
+    assert_eq!(loaded.position, data.position);
+    assert_eq!(loaded.color, data.color);
+}
+
+#[test]
+fn invalid_input() {
+    assert!(matches!(off::load_buf(&b"PLY\n"[..]), Err(OffError::InvalidHeader(_))));
+    assert!(matches!(
+        off::load_buf(&b"OFF\n1 0 0\n"[..]),
+        Err(OffError::UnexpectedEof)
+    ));
+    assert!(matches!(
+        off::load_buf(&b"OFF\n1 1 0\n0 0 0\n3 0 1 2\n"[..]),
+        Err(OffError::IndexOutOfRange(1))
+    ));
+}
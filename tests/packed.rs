@@ -0,0 +1,90 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{CompactPolygon, IndexTuple, ObjData, PackedIndexTuple, SimplePolygon};
+
+static MESH: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vn 0 0 1
+f 1/1/1 2/1/1 3/1/1 4/1/1
+g second
+f 1 3 4
+";
+
+#[test]
+fn size() {
+    assert_eq!(std::mem::size_of::<PackedIndexTuple>(), 12);
+}
+
+#[test]
+fn pack_tuple() {
+    let tuple = IndexTuple::new(3, None, Some(7));
+    let packed = PackedIndexTuple::new(tuple).unwrap();
+    assert_eq!(packed.texture, PackedIndexTuple::NONE);
+    assert_eq!(packed.normal(), Some(7));
+    assert_eq!(IndexTuple::from(packed), tuple);
+    assert_eq!(
+        PackedIndexTuple::new(IndexTuple::new(0, Some(u32::MAX as usize), None)),
+        None
+    );
+}
+
+#[test]
+fn pack_polygons() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let packed = data.packed_polygons().unwrap();
+    assert_eq!(packed.len(), 2);
+    assert_eq!(packed.offsets, vec![0, 4, 7]);
+    assert_eq!(packed.polygon(1).len(), 3);
+
+    let polys: Vec<_> = data.objects[0].groups.iter().flat_map(|g| g.polys.clone()).collect();
+    assert_eq!(packed.unpack(), polys);
+
+    let second = data.objects[0].groups[1].packed_polygons().unwrap();
+    assert_eq!(
+        second.iter().next().unwrap()[0].unpack(),
+        IndexTuple::new(0, None, None)
+    );
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn compact_polygon_size() {
+    assert_eq!(std::mem::size_of::<CompactPolygon>(), 56);
+}
+
+#[test]
+fn compact_polygons() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let first = data.objects[0].groups[0].compact_polygons();
+    assert!(matches!(first[0], CompactPolygon::Quad(_)));
+    assert_eq!(first[0].unpack(), data.objects[0].groups[0].polys[0]);
+    let second = data.objects[0].groups[1].compact_polygons();
+    assert!(matches!(second[0], CompactPolygon::Triangle(_)));
+    assert_eq!(second[0].len(), 3);
+
+    let pentagon = SimplePolygon((0..5).map(|p| IndexTuple::new(p, None, None)).collect());
+    let compact = CompactPolygon::from(&pentagon);
+    assert!(matches!(compact, CompactPolygon::Other(_)));
+    assert_eq!(SimplePolygon::from(&compact), pentagon);
+    let large = SimplePolygon(vec![IndexTuple::new(u32::MAX as usize, None, None); 3]);
+    assert_eq!(CompactPolygon::new(&large).unpack(), large);
+    assert!(matches!(CompactPolygon::new(&large), CompactPolygon::Other(_)));
+}
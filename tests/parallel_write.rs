@@ -0,0 +1,62 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, ObjMaterial};
+
+#[test]
+fn parallel_output_matches_serial() {
+    let mut source = String::new();
+    for i in 0..40_000 {
+        source.push_str(&format!(
+            "v {} {} 0.5 0.1 0.2 0.3\nvt 0.{} 1\nvn 0 0 1\n",
+            i,
+            i % 7,
+            i % 10
+        ));
+    }
+    source.push_str("o first\ng a\nusemtl red\n");
+    for i in 1..39_000 {
+        source.push_str(&format!(
+            "f {}/{}/1 {}/{}/1 {}/{}/1\n",
+            i,
+            i,
+            i + 1,
+            i + 1,
+            i + 2,
+            i + 2
+        ));
+    }
+    source.push_str("usemtl blue\nf 1 2 3 4\nl 1 2 3\no second\nf 3 2 1\n");
+    let mut data = ObjData::load_buf(source.as_bytes()).unwrap();
+    data.material_libs.push(obj::Mtl::new("scene.mtl".to_string()));
+    data.shadow_obj = Some("shadow.obj".to_string());
+    assert!(matches!(data.objects[0].groups[1].material, Some(ObjMaterial::Ref(_))));
+
+    let mut serial = Vec::new();
+    data.write_to_buf(&mut serial).unwrap();
+    for threads in [0, 1, 3, 8] {
+        let mut parallel = Vec::new();
+        data.write_to_buf_parallel(&mut parallel, threads).unwrap();
+        assert!(parallel == serial, "output differs with {} threads", threads);
+    }
+
+    let data: ObjData = ObjData::default();
+    let mut empty = Vec::new();
+    data.write_to_buf_parallel(&mut empty, 2).unwrap();
+    let mut expected = Vec::new();
+    data.write_to_buf(&mut expected).unwrap();
+    assert_eq!(empty, expected);
+}
@@ -0,0 +1,102 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, Pipeline, SplitBudget, WeldConfig};
+
+static QUADS: &str = "
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 1 0 0
+v 2 0 0
+v 2 1 0
+v 1 1 0
+o left
+usemtl brick
+s 1
+f 1 2 3 4
+o right
+usemtl wood
+f 5 6 7 8
+";
+
+fn budget(max_elements: usize) -> SplitBudget {
+    SplitBudget {
+        max_elements: Some(max_elements),
+        ..SplitBudget::default()
+    }
+}
+
+#[test]
+fn chunks_are_processed_and_written_in_order() {
+    let mut out = Vec::new();
+    let chunks = Pipeline::new(budget(1))
+        .triangulate()
+        .generate_normals()
+        .run(QUADS.as_bytes(), &mut out)
+        .unwrap();
+    assert_eq!(chunks, 2);
+
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.matches("mtllib scene.mtl").count(), 1);
+    let data = ObjData::load_buf(text.as_bytes()).unwrap();
+    // Each chunk brings its own copy of the vertices it uses.
+    assert_eq!(data.position.len(), 8);
+    assert_eq!(data.normal, vec![[0., 0., 1.]; 8]);
+    let objects: Vec<_> = data
+        .objects
+        .iter()
+        .map(|o| {
+            let group = &o.groups[0];
+            (&*o.name, group.material.as_ref().unwrap().name(), group.polys.len())
+        })
+        .collect();
+    assert_eq!(objects, [("left", "brick", 2), ("right", "wood", 2)]);
+    let mut polys = data.objects.iter().flat_map(|o| &o.groups).flat_map(|g| &g.polys);
+    assert!(polys.all(|poly| poly.0.len() == 3));
+    assert_eq!(data.objects[1].groups[0].render_state.smoothing_group, 1);
+}
+
+#[test]
+fn weld_is_bounded_by_chunks() {
+    let weld = |max_elements| {
+        let mut out = Vec::new();
+        Pipeline::new(budget(max_elements))
+            .weld(WeldConfig::default())
+            .run(QUADS.as_bytes(), &mut out)
+            .unwrap();
+        ObjData::load_buf(&out[..]).unwrap().position.len()
+    };
+    // The shared edge is only merged when both quads are in the same chunk.
+    assert_eq!(weld(2), 6);
+    assert_eq!(weld(1), 8);
+}
+
+#[test]
+fn run_without_passes_keeps_the_mesh() {
+    let mut out = Vec::new();
+    let chunks = Pipeline::new(SplitBudget::default())
+        .run(QUADS.as_bytes(), &mut out)
+        .unwrap();
+    assert_eq!(chunks, 1);
+    let data = ObjData::load_buf(&out[..]).unwrap();
+    let original = ObjData::load_buf(QUADS.as_bytes()).unwrap();
+    assert_eq!(data.position, original.position);
+    assert_eq!(data.objects.len(), 2);
+    assert_eq!(data.objects[0].groups[0].polys, original.objects[0].groups[0].polys);
+}
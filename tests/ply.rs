@@ -0,0 +1,132 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ply::{self, PlyError, PlyFormat};
+use obj::{IndexTuple, ObjData};
+
+static QUAD: &str = "
+v 0 0 0 1 0 0
+v 1 0 0 0 1 0
+v 1 1 0 0 0 1
+v 0 1 0 1 1 1
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+f 1/1/1 2/2/1 3/3/1 4/4/1
+";
+
+#[test]
+fn round_trip_all_formats() {
+    let data = ObjData::load_buf(QUAD.as_bytes()).unwrap();
+    for &format in &[
+        PlyFormat::Ascii,
+        PlyFormat::BinaryLittleEndian,
+        PlyFormat::BinaryBigEndian,
+    ] {
+        let mut buf = Vec::new();
+        ply::write(&data, &mut buf, format).unwrap();
+        let loaded = ply::load_buf(&buf[..]).unwrap();
+        assert_eq!(loaded.position, data.position, "{:?}", format);
+        assert_eq!(loaded.texture, data.texture, "{:?}", format);
+        assert_eq!(loaded.normal, vec![[0.0, 0.0, 1.0]; 4], "{:?}", format);
+        assert_eq!(loaded.color, data.color, "{:?}", format);
+        let poly = &loaded.objects[0].groups[0].polys[0];
+        assert_eq!(poly.0[2], IndexTuple::new(2, Some(2), Some(2)));
+    }
+}
+
+#[test]
+fn load_ascii_with_extra_elements() {
+    let source = "ply
+format ascii 1.0
+comment made by hand
+element vertex 3
+property double x
+property double y
+property double z
+property float confidence
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar uint vertex_indices
+element material 1
+property uchar kind
+end_header
+0 0 0 0.5 255 0 0
+1 0 0 0.5 0 255 0
+0 1 0 0.5 0 0 255
+3 0 1 2
+7
+";
+    let loaded = ply::load_buf(source.as_bytes()).unwrap();
+    assert_eq!(loaded.position, vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+    assert_eq!(loaded.color[2], [0.0, 0.0, 1.0]);
+    assert!(loaded.texture.is_empty() && loaded.normal.is_empty());
+    assert_eq!(
+        loaded.objects[0].groups[0].polys[0].0[1],
+        IndexTuple::new(1, None, None)
+    );
+}
+
+#[test]
+fn splits_vertices_with_distinct_attributes() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 1\nf 1/1 2/1 3/1\nf 1/2 3/2 2/2\n";
+    let data = ObjData::load_buf(source.as_bytes()).unwrap();
+    let mut buf = Vec::new();
+    ply::write(&data, &mut buf, PlyFormat::Ascii).unwrap();
+    let text = String::from_utf8(buf).unwrap();
+    assert!(text.contains("element vertex 6\n"));
+    assert!(text.contains("element face 2\n"));
+}
+
+#[test]
+fn invalid_input() {
+    assert!(matches!(
+        ply::load_buf(&b"solid\n"[..]),
+        Err(PlyError::InvalidHeader(_))
+    ));
+
+    let truncated = "ply\nformat binary_little_endian 1.0\nelement vertex 1\nproperty float x\nend_header\n\0\0";
+    assert!(matches!(
+        ply::load_buf(truncated.as_bytes()),
+        Err(PlyError::UnexpectedEof)
+    ));
+
+    let out_of_range = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nelement face 1\nproperty list uchar int vertex_indices\nend_header\n0\n3 0 1 2\n";
+    assert!(matches!(
+        ply::load_buf(out_of_range.as_bytes()),
+        Err(PlyError::IndexOutOfRange(1))
+    ));
+
+    let header = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nelement face 1\nproperty list uchar int vertex_indices\nend_header\n0\n";
+    for face in ["1e30 0", "-1 0", "1.5 0", "nan 0", "1 -1", "1 0.5"] {
+        assert!(matches!(
+            ply::load_buf(format!("{}{}\n", header, face).as_bytes()),
+            Err(PlyError::InvalidValue(_))
+        ));
+    }
+
+    // A huge count is only limited by the bytes that follow.
+    let mut huge =
+        b"ply\nformat binary_little_endian 1.0\nelement face 1\nproperty list uint int vertex_indices\nend_header\n"
+            .to_vec();
+    huge.extend_from_slice(&0xF000_0000u32.to_le_bytes());
+    huge.extend_from_slice(&0i32.to_le_bytes());
+    assert!(matches!(ply::load_buf(&huge[..]), Err(PlyError::UnexpectedEof)));
+}
@@ -0,0 +1,63 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static SOURCE: &str = "
+v 0 0 0
+v 4 0 0
+v 4 1 0
+v 1 1 0
+v 1 3 0
+v 0 3 0
+f 1 2 3 4 5 6
+f 1 6 5
+f 1 2 1
+";
+
+fn assert_close(a: [f32; 3], b: [f32; 3]) {
+    for (x, y) in a.iter().zip(b.iter()) {
+        assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn polygon_queries() {
+    let data = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let polygons: Vec<_> = data.polygons().collect();
+    assert_eq!(polygons.len(), 3);
+
+    // An L shape made of a 4x1 and a 1x2 rectangle.
+    let l = polygons[0];
+    assert!((l.area() - 6.0).abs() < 1e-5);
+    assert_close(l.face_normal().unwrap(), [0., 0., 1.]);
+    assert_close(
+        l.centroid().unwrap(),
+        [(4. * 2. + 2. * 0.5) / 6., (4. * 0.5 + 2. * 2.) / 6., 0.],
+    );
+
+    let clockwise = polygons[1];
+    assert_close(clockwise.face_normal().unwrap(), [0., 0., -1.]);
+    assert!((clockwise.area() - 1.5).abs() < 1e-5);
+
+    let degenerate = polygons[2];
+    assert_eq!(degenerate.area(), 0.);
+    assert_eq!(degenerate.face_normal(), None);
+    assert_close(degenerate.centroid().unwrap(), [4. / 3., 0., 0.]);
+
+    let group = &data.objects[0].groups[0];
+    assert_eq!(group.polygons(&data).count(), 3);
+}
@@ -0,0 +1,66 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, Line, ObjData};
+
+static PATHS: &str = "
+v 0 0 0
+v 2 0 0
+v 2 1 0
+v 0 0 0
+l 1 2 3
+l 1 2 3 1
+l 1 2 3 4
+l 2 2
+";
+
+#[test]
+fn length_and_closed() {
+    let data = ObjData::load_buf(PATHS.as_bytes()).unwrap();
+    let lines = &data.objects[0].groups[0].lines;
+    assert_eq!(lines[0].length(&data), 3.0);
+    assert!(!lines[0].is_closed(&data));
+    assert!(lines[1].is_closed(&data));
+    assert!(lines[2].is_closed(&data));
+    assert!(!lines[3].is_closed(&data));
+    assert_eq!(lines[3].length(&data), 0.0);
+}
+
+#[test]
+fn resample() {
+    let data = ObjData::load_buf(PATHS.as_bytes()).unwrap();
+    let lines = &data.objects[0].groups[0].lines;
+    assert_eq!(
+        lines[0].resample(&data, 4),
+        vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0], [2.0, 1.0, 0.0]]
+    );
+    assert_eq!(lines[0].resample(&data, 1), vec![[0.0, 0.0, 0.0]]);
+    assert!(lines[0].resample(&data, 0).is_empty());
+    assert_eq!(lines[3].resample(&data, 3), vec![[2.0, 0.0, 0.0]; 3]);
+}
+
+#[test]
+fn from_points() {
+    let mut data = ObjData::load_buf("v 0 0 0 1 0 0\n".as_bytes()).unwrap();
+    let points = [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0]];
+    let line = Line::from_points(&mut data, &points);
+    assert_eq!(
+        line.0,
+        vec![IndexTuple::new(1, None, None), IndexTuple::new(2, None, None)]
+    );
+    assert_eq!(line.points(&data), points);
+    assert_eq!(data.color.len(), 3);
+}
@@ -0,0 +1,50 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, ObjData64};
+
+static SURVEY: &str = "
+v 512345.125 6789012.375 102.25
+v 512346.5 6789012.875 102.5
+v 512345.75 6789013.625 103.125
+f 1 2 3
+";
+
+#[test]
+fn load_keeps_double_precision() {
+    let data = ObjData::load_buf_f64(SURVEY.as_bytes()).unwrap();
+    assert_eq!(data.position[0], [512345.125, 6789012.375, 102.25]);
+    assert_ne!(data.to_f32().position[0][1] as f64, 6789012.375);
+}
+
+#[test]
+fn round_trip_f64() {
+    let data = ObjData::load_buf_f64(SURVEY.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    data.write_to_buf(&mut out).unwrap();
+    let reloaded = ObjData::load_buf_f64(&out[..]).unwrap();
+    assert_eq!(data, reloaded);
+}
+
+#[test]
+fn recenter_before_rounding() {
+    let mut data: ObjData64 = ObjData::load_buf_f64(SURVEY.as_bytes()).unwrap();
+    data.translate([-512345.0, -6789012.0, -100.0]);
+    let local = data.to_f32();
+    assert_eq!(local.position[0], [0.125, 0.375, 2.25]);
+    assert_eq!(local.to_f64().position, data.position);
+    assert_eq!(local.objects, data.objects);
+}
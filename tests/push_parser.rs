@@ -0,0 +1,122 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::sync::{Arc, Mutex};
+
+use obj::{IndexTuple, LoadConfig, ObjData, ObjError, PushParser, TracedValue};
+
+static SOURCE: &str = "mtllib scene.mtl\r\nv 0 0 0\r\nv 1 0 0\r\nv 1 1 0\r\nvt 0 0\r\nvn 0 0 1\r\no Würfel\r\ng side\r\nusemtl red\r\nf 1/1/1 2/1/1 3/1/1\r\nl 1 2\r\no other\r\nf 3 2 1";
+
+#[test]
+fn chunks_of_any_size() {
+    let expected = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    for size in 1..SOURCE.len() {
+        let mut parser = PushParser::default();
+        for chunk in SOURCE.as_bytes().chunks(size) {
+            parser.push_bytes(chunk).unwrap();
+        }
+        assert_eq!(parser.finish().unwrap(), expected, "chunk size {}", size);
+    }
+}
+
+#[test]
+fn push_lines() {
+    let mut parser = PushParser::new(LoadConfig::default());
+    for line in SOURCE.lines() {
+        parser.push_line(line).unwrap();
+    }
+    assert_eq!(parser.line_number(), 13);
+    let data: ObjData = parser.finish().unwrap();
+    assert_eq!(data, SOURCE.parse::<ObjData>().unwrap());
+    assert_eq!(&*data.objects[0].name, "Würfel");
+}
+
+#[test]
+fn incomplete_line_is_ended_by_push_line() {
+    let mut parser = PushParser::default();
+    parser.push_bytes(b"v 0 0 0\nv 1 0 0").unwrap();
+    parser.push_line("v 1 1 0").unwrap();
+    let data: ObjData = parser.finish().unwrap();
+    assert_eq!(data.position, [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]);
+}
+
+#[test]
+fn errors_report_line_numbers() {
+    match PushParser::<f64>::default().push_bytes(b"v 0 0 0\n\nbogus 1\n") {
+        Err(ObjError::UnexpectedCommand { line_number, command }) => {
+            assert_eq!(line_number, 2);
+            assert_eq!(command, "bogus");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut parser = PushParser::<f32>::default();
+    parser.push_bytes(b"v 0 0 0\no \xff").unwrap();
+    assert!(matches!(parser.finish(), Err(ObjError::InvalidUtf8 { line_number: 1 })));
+}
+
+#[test]
+fn on_statement() {
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    let seen = trace.clone();
+    let config = LoadConfig::new().on_statement(move |statement| {
+        let value = match &statement.value {
+            TracedValue::Position { index, position, .. } => format!("{} {:?}", index, position.unwrap()),
+            TracedValue::Polygons(polys) => format!("{:?}", polys.iter().map(|p| &p.0).collect::<Vec<_>>()),
+            TracedValue::Object(name) => name.to_string(),
+            TracedValue::Group(group) => format!("{:?}", group.map(|g| (&*g.name, g.index))),
+            TracedValue::MaterialLib(file) => file.to_string(),
+            value => format!("{:?}", value),
+        };
+        seen.lock()
+            .unwrap()
+            .push(format!("{} {}: {}", statement.line_number, statement.keyword, value));
+    });
+    ObjData::load_buf_with(SOURCE.as_bytes(), config).unwrap();
+
+    let trace = trace.lock().unwrap();
+    assert_eq!(trace.len(), 13);
+    assert_eq!(trace[0], "0 mtllib: scene.mtl");
+    assert_eq!(trace[2], "2 v: 1 [1.0, 0.0, 0.0]");
+    assert_eq!(trace[4], "4 vt: Texture { index: 0, texture: [0.0, 0.0] }");
+    assert_eq!(trace[6], "6 o: Würfel");
+    assert_eq!(trace[8], "8 usemtl: Some((\"side\", 0))");
+    let face = [
+        IndexTuple::new(0, Some(0), Some(0)),
+        IndexTuple::new(1, Some(0), Some(0)),
+        IndexTuple::new(2, Some(0), Some(0)),
+    ];
+    assert_eq!(trace[9], format!("9 f: [{:?}]", face));
+    assert_eq!(
+        trace[12],
+        format!("12 f: [{:?}]", [2, 1, 0].map(|p| IndexTuple::new(p, None, None)))
+    );
+}
+
+#[test]
+fn on_statement_with_dedup() {
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    let seen = trace.clone();
+    let config = LoadConfig::new().dedup_attributes(true).on_statement(move |statement| {
+        if let TracedValue::Position { index, position, .. } = statement.value {
+            seen.lock().unwrap().push((index, position.unwrap()));
+        }
+    });
+    ObjData::load_buf_with("v 1 2 3\nv 4 5 6\nv 1 2 3\n".as_bytes(), config).unwrap();
+    // Indices count every statement, while the values are those of the stored positions.
+    let expected = vec![(0, [1.0, 2.0, 3.0]), (1, [4.0, 5.0, 6.0]), (2, [1.0, 2.0, 3.0])];
+    assert_eq!(*trace.lock().unwrap(), expected);
+}
@@ -0,0 +1,109 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static MIXED: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0.5 0.25 0
+v 2 0 0
+v 2 1 1
+f 1 2 3 4
+f 1 2 5 3 4
+f 2 6 7
+";
+
+static TRIS: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 2 0 1
+f 1 2 3
+f 1 3 4
+f 2 5 3
+";
+
+#[test]
+fn to_triangles_handles_concave_polygons() {
+    let mut obj = ObjData::load_buf(MIXED.as_bytes()).unwrap();
+    let report = obj.to_triangles();
+    assert!(report.is_complete());
+
+    let polys = &obj.objects[0].groups[0].polys;
+    assert_eq!(polys.len(), 2 + 3 + 1);
+    assert!(polys.iter().all(|p| p.0.len() == 3));
+    // The concave pentagon must not produce a triangle covering the reflex vertex 5.
+    for p in &polys[2..5] {
+        let idx: Vec<_> = p.0.iter().map(|t| t.position).collect();
+        assert_ne!(idx, vec![0, 1, 2]);
+    }
+}
+
+#[test]
+fn to_triangles_reports_degenerate_polygons() {
+    let mut obj = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 2 0 0\nv 3 0 0\nf 1 2 3 4\n".as_bytes()).unwrap();
+    let report = obj.to_triangles();
+    assert!(!report.is_complete());
+    assert_eq!(report.groups[0].unconverted, vec![0]);
+}
+
+#[test]
+fn to_triangles_reports_non_finite_polygons() {
+    let mut obj = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n".as_bytes()).unwrap();
+    obj.position[2] = [f32::NAN, 1.0, 0.0];
+    let report = obj.to_triangles();
+    assert_eq!(report.groups[0].unconverted, vec![0]);
+}
+
+#[test]
+fn polygons_with_missing_positions_are_reported() {
+    let mut obj = ObjData::load_buf(format!("{}f 1 2 3 99\n", MIXED).as_bytes()).unwrap();
+    let report = obj.to_triangles();
+    assert_eq!(report.groups[0].unconverted, vec![6]);
+
+    let mut obj = ObjData::load_buf(format!("{}f 3 1 99\n", TRIS).as_bytes()).unwrap();
+    let report = obj.to_quads(1e-3);
+    assert_eq!(report.groups[0].unconverted, vec![1, 2]);
+}
+
+#[test]
+fn to_quads_pairs_coplanar_triangles() {
+    let mut obj = ObjData::load_buf(TRIS.as_bytes()).unwrap();
+    let report = obj.to_quads(1e-3);
+    let polys = &obj.objects[0].groups[0].polys;
+    assert_eq!(polys.len(), 2);
+    assert_eq!(polys[0].0.len(), 4);
+    assert_eq!(report.groups.len(), 1);
+    assert_eq!(report.groups[0].converted, 2);
+    // The tilted triangle stays a triangle.
+    assert_eq!(report.groups[0].unconverted, vec![1]);
+}
+
+#[test]
+fn quads_round_trip() {
+    let mut obj = ObjData::load_buf(TRIS.as_bytes()).unwrap();
+    let poly_count = |obj: &ObjData| obj.objects[0].groups[0].polys.len();
+    obj.to_quads(1.0);
+    // With a generous tolerance one of the pairs is merged.
+    assert_eq!(poly_count(&obj), 2);
+    let report = obj.to_triangles();
+    assert!(report.is_complete());
+    assert_eq!(poly_count(&obj), 3);
+}
@@ -0,0 +1,66 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{AttributeRanges, ObjData};
+
+static SOURCE: &str = "
+o first
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vn 0 0 1
+f 1/1/1 2/1/1 3/1/1
+o second
+v 0 0 1
+v 1 0 1
+v 0 1 1
+v 1 1 1
+g lines
+l 4 7
+g faces
+f 5 6 7
+o empty
+g nothing
+";
+
+#[test]
+fn attribute_ranges() {
+    let data = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let ranges = data.object_attribute_ranges();
+    assert_eq!(
+        ranges,
+        [
+            AttributeRanges {
+                position: 0..3,
+                texture: 0..1,
+                normal: 0..1
+            },
+            AttributeRanges {
+                position: 3..7,
+                ..AttributeRanges::default()
+            },
+            AttributeRanges::default(),
+        ]
+    );
+    assert!(!ranges[0].overlaps(&ranges[1]));
+    assert!(!ranges[2].overlaps(&ranges[0]));
+
+    let groups = &data.objects[1].groups;
+    assert_eq!(groups[0].attribute_ranges().position, 3..7);
+    assert_eq!(groups[1].attribute_ranges().position, 4..7);
+    assert!(groups[0].attribute_ranges().overlaps(&groups[1].attribute_ranges()));
+}
@@ -0,0 +1,86 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use obj::raw::{self, Statement};
+use obj::{IndexTuple, ObjError};
+
+#[test]
+fn split_statements() {
+    let source = "# a comment\n\n  v 1 2 3\no  My Object  \nf 1/2/3 -1//1\n";
+    let statements: Vec<_> = raw::statements(source).collect();
+    assert_eq!(statements.len(), 4);
+    assert!(statements[0].is_comment());
+    assert_eq!(statements[1].line_number(), 2);
+    assert_eq!(statements[1].keyword(), "v");
+    assert_eq!(statements[2].rest(), "My Object");
+    assert_eq!(statements[3].args().collect::<Vec<_>>(), ["1/2/3", "-1//1"]);
+    assert_eq!(Statement::parse(7, " \t"), None);
+}
+
+#[test]
+fn typed_arguments() {
+    let statement = Statement::parse(3, "v 1.5 -2 3e2 extra").unwrap();
+    let mut args = statement.args();
+    assert_eq!(args.vec3::<f64>().unwrap(), [1.5, -2.0, 300.0]);
+    assert_eq!(args.next(), Some("extra"));
+
+    let mut args = Statement::parse(4, "vt 0.5").unwrap().args();
+    match args.vec2() {
+        Err(ObjError::ArgumentListFailure { line_number, .. }) => assert_eq!(line_number, 4),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn index_tuples() {
+    let statement = Statement::parse(0, "f 1/2/3 -1//1 4/-2 x").unwrap();
+    let mut args = statement.args();
+    let lens = [4, 2, 3];
+    assert_eq!(
+        args.index_tuple(lens).unwrap().unwrap(),
+        IndexTuple::new(0, Some(1), Some(2))
+    );
+    assert_eq!(
+        args.index_tuple(lens).unwrap().unwrap(),
+        IndexTuple::new(3, None, Some(0))
+    );
+    assert_eq!(
+        args.index_tuple(lens).unwrap().unwrap(),
+        IndexTuple::new(3, Some(0), None)
+    );
+    assert!(matches!(
+        args.index_tuple(lens),
+        Some(Err(ObjError::MalformedFaceGroup { .. }))
+    ));
+    assert!(args.index_tuple(lens).is_none());
+
+    assert!(matches!(
+        raw::parse_index_tuple(9, "0/1", lens),
+        Err(ObjError::ZeroVertexNumber { line_number: 9 })
+    ));
+}
+
+#[test]
+fn unusual_whitespace() {
+    let statement = Statement::parse(0, "g a\x0Bb\x0Cc\td\r e\u{3000}f\u{a0}g  ").unwrap();
+    assert_eq!(statement.rest(), "a\x0Bb\x0Cc\td\r e\u{3000}f\u{a0}g");
+    assert_eq!(
+        statement.args().collect::<Vec<_>>(),
+        ["a", "b", "c", "d", "e", "f", "g"]
+    );
+
+    let statement = Statement::parse(0, "f 1\x0B2\x0C3").unwrap();
+    assert_eq!(statement.args().collect::<Vec<_>>(), ["1", "2", "3"]);
+    assert_eq!(Statement::parse(0, "v").unwrap().args().next(), None);
+}
@@ -0,0 +1,49 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "rayon")]
+
+use obj::ObjData;
+use rayon::prelude::*;
+
+#[test]
+fn parallel_iteration() {
+    let input = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+o a
+f 1 2 3
+g b
+f 2 4 3
+f 1 2 4
+o c
+f 1 2 3 4
+";
+    let mut data = ObjData::load_buf(input.as_bytes()).unwrap();
+    assert_eq!(
+        data.par_groups().count(),
+        data.objects.iter().map(|o| o.groups.len()).sum::<usize>()
+    );
+    assert_eq!(data.par_polys().map(|p| p.0.len()).sum::<usize>(), 13);
+    assert_eq!(data.objects[0].par_polys().count(), 3);
+    assert_eq!(data.objects[0].groups[1].par_polys().count(), 2);
+
+    data.par_polys_mut().for_each(|p| p.0.reverse());
+    assert_eq!(data.objects[1].groups[0].polys[0].0[0].position, 3);
+
+    data.par_groups_mut().for_each(|g| g.polys.retain(|p| p.0.len() == 3));
+    assert_eq!(data.par_polys().count(), 3);
+}
@@ -0,0 +1,97 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, WeldConfig};
+
+fn load(src: &str) -> ObjData {
+    ObjData::load_buf(src.as_bytes()).unwrap()
+}
+
+#[test]
+fn weld_remap() {
+    let mut obj = load(
+        "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 0 0
+v 1 1 0
+f 1 2 3
+f 4 5 3
+",
+    );
+    let (report, remap) = obj.weld_vertices_with_remap(WeldConfig::default());
+    assert_eq!(report.position, 1);
+    assert_eq!(
+        remap.attributes.position,
+        vec![Some(0), Some(1), Some(2), Some(1), Some(3)]
+    );
+    assert!(remap.attributes.texture.is_empty());
+    assert_eq!(remap.polygons, vec![0..1, 1..2]);
+    assert_eq!(remap.polygon_table(), vec![Some(0), Some(1)]);
+}
+
+#[test]
+fn compact_remap() {
+    let mut obj = load("v 0 0 0\nv 9 9 9\nv 1 0 0\nv 0 1 0\nf 1 3 4\n");
+    let remap = obj.compact_with_remap();
+    assert_eq!(remap.attributes.position, vec![Some(0), None, Some(1), Some(2)]);
+    assert_eq!(remap.polygons, vec![0..1]);
+}
+
+#[test]
+fn dedup_remap() {
+    let mut obj = load(
+        "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+g a
+f 1 2 3
+f 2 3 1
+g b
+f 2 4 3
+f 3 2 4
+f 1 2 3
+",
+    );
+    let (report, remap) = obj.dedup_faces_with_remap();
+    assert_eq!(report.groups.len(), 2);
+    assert_eq!(remap.polygons, vec![0..1, 1..1, 1..2, 2..2, 2..2]);
+    assert_eq!(remap.polygon_table(), vec![Some(0), None, Some(1), None, None]);
+    assert_eq!(remap.attributes.position, vec![Some(0), Some(1), Some(2), Some(3)]);
+}
+
+#[test]
+fn simplify_remap() {
+    let mut obj = load(
+        "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 5 5 5
+f 1 2 3 4
+l 1 5
+",
+    );
+    let (remaining, remap) = obj.simplify_with_remap(1.0);
+    assert_eq!(remaining, 2);
+    assert_eq!(remap.polygons, vec![0..2]);
+    assert_eq!(remap.polygon_table(), vec![None]);
+    assert_eq!(remap.attributes.position.len(), 5);
+}
@@ -0,0 +1,109 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{LoadConfig, LoadWarning, ObjData, ObjRef, RenderState, StateChange};
+
+static SOURCE: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+s 1
+bevel on
+g a
+f 1 2 3
+lod 5
+usemap brick
+f 1 3 2
+g b
+c_interp on
+d_interp on
+f 1 2 3
+s off
+usemap off
+o next
+f 1 2 3
+";
+
+#[test]
+fn load_render_state() {
+    let data = ObjData::load_buf(SOURCE.as_bytes()).unwrap();
+    let states: Vec<_> = data
+        .objects
+        .iter()
+        .flat_map(|o| &o.groups)
+        .map(|g| (&*g.name, g.index, g.render_state.clone(), g.state_changes.clone()))
+        .collect();
+    let a = RenderState {
+        smoothing_group: 1,
+        bevel: true,
+        ..RenderState::default()
+    };
+    let a1 = RenderState {
+        lod: 5,
+        texture_map: Some("brick".into()),
+        ..a.clone()
+    };
+    let b = RenderState {
+        c_interp: true,
+        d_interp: true,
+        ..a1.clone()
+    };
+    let next = RenderState {
+        smoothing_group: 0,
+        texture_map: None,
+        ..b.clone()
+    };
+    let change = |polys, state: &RenderState| StateChange {
+        polys,
+        lines: 0,
+        state: state.clone(),
+    };
+    assert_eq!(
+        states,
+        [
+            ("a", 0, a.clone(), vec![change(1, &a1)]),
+            ("b", 0, b.clone(), vec![change(1, &next)]),
+            ("default", 0, next.clone(), vec![])
+        ]
+    );
+    let group = &data.objects[0].groups[0];
+    assert_eq!(group.poly_render_state(0), &a);
+    assert_eq!(group.poly_render_state(1), &a1);
+    assert_eq!(group.end_render_state(), &a1);
+
+    let borrowed = ObjRef::parse(SOURCE).unwrap().into_owned();
+    assert_eq!(borrowed.objects, data.objects);
+
+    let text = data.to_string();
+    assert!(text.contains("g a\ns 1\nbevel on\nf 1 2 3\nlod 5\nusemap brick\nf 1 3 2\n"));
+    assert!(text.contains("g b\nc_interp on\nd_interp on\nf 1 2 3\ns off\nusemap off\no next\ng default\nf"));
+    assert_eq!(ObjData::load_buf(text.as_bytes()).unwrap().objects, data.objects);
+}
+
+#[test]
+fn malformed_render_state() {
+    for source in ["s", "s x", "s 1.5", "bevel 1", "lod off", "usemap"] {
+        let text = format!("s 2\n{}\nf 1 1 1\n", source);
+        let (data, warnings) = ObjData::load_buf_with_warnings(text.as_bytes(), LoadConfig::default()).unwrap();
+        assert!(matches!(
+            warnings[..],
+            [LoadWarning::SkippedLine { line_number: 1, .. }]
+        ));
+        let group = &data.objects[0].groups[0];
+        assert_eq!(group.render_state.smoothing_group, 2);
+        assert!(group.state_changes.is_empty());
+    }
+}
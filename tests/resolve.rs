@@ -0,0 +1,62 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, ObjData, VertexData};
+
+static MESH: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+vt 0.5 1
+vn 0 0 1
+f 1/1/1 2//1 3
+";
+
+#[test]
+fn resolve_tuples() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let poly = &data.objects[0].groups[0].polys[0].0;
+    assert_eq!(
+        data.resolve(&poly[0]),
+        Some(VertexData {
+            position: [0.0, 0.0, 0.0],
+            texture: Some([0.5, 1.0]),
+            normal: Some([0.0, 0.0, 1.0]),
+        })
+    );
+    let all = data.resolve_all(poly).unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[1].texture, None);
+    assert_eq!(all[1].normal, Some([0.0, 0.0, 1.0]));
+    assert_eq!(all[2].position, [1.0, 1.0, 0.0]);
+    assert_eq!(all[2].normal, None);
+}
+
+#[test]
+fn out_of_range() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    assert_eq!(data.resolve(&IndexTuple::new(3, None, None)), None);
+    assert_eq!(data.resolve(&IndexTuple::new(0, Some(1), None)), None);
+    assert_eq!(data.resolve(&IndexTuple::new(0, None, Some(1))), None);
+
+    let mut out = data.resolve_all(&[IndexTuple::new(1, None, None)]).unwrap();
+    let tuples = [IndexTuple::new(0, None, None), IndexTuple::new(9, None, None)];
+    assert_eq!(data.resolve_all(&tuples), None);
+    assert_eq!(data.resolve_into(&tuples, &mut out), None);
+    assert_eq!(out.len(), 1);
+    assert_eq!(data.resolve_into(&tuples[..1], &mut out), Some(()));
+    assert_eq!(out.len(), 2);
+}
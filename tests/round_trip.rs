@@ -12,7 +12,12 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
-use obj::{Obj, ObjData};
+#![cfg(feature = "std")]
+
+use obj::{
+    DuplicateObjectPolicy, LoadConfig, LoadWarning, Obj, ObjData, ObjData64, ObjError, ObjMaterial, SimplePolygon,
+    WriteConfig,
+};
 
 #[test]
 fn round_trip_sponza_no_mtls() {
@@ -51,3 +56,203 @@ fn round_trip_sponza_with_mtl() {
 
     assert_eq!(sponza_round_trip.data, sponza.data);
 }
+
+#[test]
+fn round_trip_vertex_colors() {
+    let data = ObjData::load_buf("v 0 0 0\nv 1 0 0 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).unwrap();
+    assert_eq!(data.color, vec![[1.0, 1.0, 1.0], [1.0, 0.0, 0.0], [1.0, 1.0, 1.0]]);
+
+    let mut obj = Vec::new();
+    data.write_to_buf(&mut obj).unwrap();
+    assert_eq!(ObjData::load_buf(obj.as_slice()).unwrap(), data);
+}
+
+#[test]
+fn display_from_str() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\no tri\nf 1//1 2//1 3//1\n";
+    let data: ObjData = source.parse().unwrap();
+    let text = data.to_string();
+    assert!(text.contains("o tri\n"));
+    assert_eq!(text.parse::<ObjData>().unwrap(), data);
+
+    let precise: ObjData64 = "v 0.1 0 0\n".parse().unwrap();
+    assert_eq!(precise.position[0][0], 0.1);
+    assert!("v 0 zero 0\n".parse::<ObjData>().is_err());
+}
+
+#[test]
+fn faithful_round_trip() {
+    let source = "# exported by hand
+
+mtllib scene.mtl
+v 1.000000 0 0
+v 0 1.0 0
+v 0 0 1.0
+vt 0 0
+s 1
+o cube
+usemtl red
+f 1/1 -2/1 -1/1
+# the end
+g legs
+l 1 2
+o cube
+f 3 2 1
+";
+    let config = LoadConfig::new().faithful(true);
+    let mut sponza = Obj::load_with("test_assets/sponza.obj", config.clone()).unwrap();
+    let mut text = Vec::new();
+    sponza.data.write_to_buf(&mut text).unwrap();
+    assert_eq!(text, std::fs::read("test_assets/sponza.obj").unwrap());
+
+    let mut data = ObjData::load_buf_with(source.as_bytes(), config.clone()).unwrap();
+    assert_eq!(data.to_string(), source);
+
+    // Changed elements are written from the data, all other lines stay as they were.
+    data.objects[0].groups[0].material = Some(ObjMaterial::Ref("blue".into()));
+    data.position[1][1] = 2.0;
+    let expected = source
+        .replace("usemtl red", "usemtl blue")
+        .replace("v 0 1.0 0", "v 0 2 0");
+    assert_eq!(data.to_string(), expected);
+
+    // Without a place for new elements the regular output is written.
+    data.position.push([0.0; 3]);
+    assert!(data.to_string().starts_with("# Generated"));
+    sponza.data.objects.clear();
+    assert!(!sponza.data.layout.as_ref().unwrap().covers(&sponza.data));
+
+    let merged = ObjData::load_buf_with(
+        source.as_bytes(),
+        config.duplicate_objects(DuplicateObjectPolicy::Merge),
+    )
+    .unwrap();
+    assert_eq!(merged.objects.len(), 1);
+    assert_eq!(merged.to_string(), source);
+    assert!(ObjData::load_buf(source.as_bytes()).unwrap().layout.is_none());
+}
+
+#[test]
+fn faithful_free_form_geometry() {
+    let source = "v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vp 0.5
+cstype bspline
+deg 3
+curv 0.0 1.0 1 2 3 4
+parm u 0 0 0 0 1 1 1 1
+end
+f 1 2 3 4
+";
+    let config = LoadConfig::new().strict(false).faithful(true);
+    let mut data = ObjData::load_buf_with(source.as_bytes(), config).unwrap();
+    data.objects[0].groups[0].polys[0].0.reverse();
+    assert_eq!(data.to_string(), source.replace("f 1 2 3 4", "f 4 3 2 1"));
+}
+
+#[test]
+fn write_validates_indices() {
+    let mut data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nf 1/1 2/1 3/1\nl 1 2\n".as_bytes()).unwrap();
+    let mut out = Vec::new();
+    data.write_to_buf(&mut out).unwrap();
+
+    data.objects[0].groups[0].lines[0].0[1].texture = Some(1);
+    out.clear();
+    assert!(matches!(
+        data.write_to_buf(&mut out),
+        Err(ObjError::ElementIndexOutOfRange {
+            object: 0,
+            group: 0,
+            element: 1,
+            index: 1,
+            count: 1
+        })
+    ));
+    assert!(out.is_empty());
+    assert!(data.write_to_buf_parallel(&mut out, 2).is_err());
+
+    data.objects[0].groups[0].lines.clear();
+    data.objects[0].groups[0].polys.push(SimplePolygon(Default::default()));
+    assert!(matches!(
+        data.validate_indices(),
+        Err(ObjError::EmptyElement {
+            object: 0,
+            group: 0,
+            element: 1
+        })
+    ));
+}
+
+#[test]
+fn summary_comments() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\no a\nf 1/1 2/1 3/1\nl 1 2\no b\nf 3 2 1\n";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    obj.write_to_buf_with(&mut out, WriteConfig::new().summary_comments(true))
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("v 0 1 0\n# 3 vertices\nvt 0 0\n# 1 texture coords\no a\n"));
+    assert!(text.contains("l 1 2\n# 2 elements\no b\ng default\nf 3 2 1\n# 1 elements\n"));
+    assert!(!text.contains("vertex normals"));
+
+    let config = || LoadConfig::new().faithful(true);
+    let (reloaded, warnings) = ObjData::load_buf_with_warnings(text.as_bytes(), config()).unwrap();
+    assert_eq!((&reloaded.position, &reloaded.objects), (&obj.position, &obj.objects));
+    assert!(warnings.is_empty());
+
+    // Per-object blocks are counted separately.
+    let mut out = Vec::new();
+    let relative = WriteConfig::new().summary_comments(true).relative_indices(true);
+    obj.write_to_buf_with(&mut out, relative).unwrap();
+    let (_, warnings) = ObjData::load_buf_with_warnings(out.as_slice(), config()).unwrap();
+    assert!(warnings.is_empty());
+
+    let damaged = text.replace("v 1 0 0\n", "");
+    let damaged = damaged.replace("f 1/1 2/1 3/1", "f 1/1 2/1 2/1");
+    let (_, warnings) = ObjData::load_buf_with_warnings(damaged.as_bytes(), config()).unwrap();
+    assert_eq!(
+        warnings,
+        [LoadWarning::SummaryMismatch {
+            line_number: 3,
+            summary: "vertices".to_string(),
+            expected: Some(3),
+            found: 2,
+        }]
+    );
+
+    let cut_off = &text[..text.rfind("# 1 elements").unwrap()];
+    let (_, warnings) = ObjData::load_buf_with_warnings(cut_off.as_bytes(), config()).unwrap();
+    assert_eq!(
+        warnings,
+        [LoadWarning::SummaryMismatch {
+            line_number: cut_off.lines().count(),
+            summary: "elements".to_string(),
+            expected: None,
+            found: 1,
+        }]
+    );
+}
+
+#[test]
+fn decimal_separator() {
+    let source = "mtllib scene.mtl\nv 0.5 1 -2.25\nvt 0.125 0\no part.1\nf 1/1 1/1 1/1\n";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    obj.write_to_buf_with(&mut out, WriteConfig::new().decimal_separator(','))
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("v 0,5 1 -2,25\nvt 0,125 0\no part.1\n"));
+    assert!(text.ends_with("mtllib scene.mtl\n"));
+
+    let reloaded = ObjData::load_buf_with(text.as_bytes(), LoadConfig::new().comma_decimals(true)).unwrap();
+    assert_eq!(reloaded, obj);
+
+    let mut plain = Vec::new();
+    obj.write_to_buf(&mut plain).unwrap();
+    let mut out = Vec::new();
+    obj.write_to_buf_with(&mut out, WriteConfig::new().decimal_separator('.'))
+        .unwrap();
+    assert_eq!(out, plain);
+}
@@ -0,0 +1,63 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, RustSourceConfig};
+
+static QUAD: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 1/1 2/2 3/3 4/4
+";
+
+#[test]
+fn write_const_arrays() {
+    let data = ObjData::load_buf(QUAD.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    data.write_rust_source(&mut out, &RustSourceConfig::default()).unwrap();
+    let source = String::from_utf8(out).unwrap();
+
+    assert!(
+        source.contains("pub const MESH_POSITIONS: [[f32; 3]; 4] = [\n    [0.0, 0.0, 0.0],\n    [1.0, 0.0, 0.0],\n")
+    );
+    assert!(source.contains("pub const MESH_NORMALS: [[f32; 3]; 4] = ["));
+    assert!(source.contains("pub const MESH_UVS: [[f32; 2]; 4] = [\n    [0.0, 0.0],\n"));
+    assert!(source.contains("pub const MESH_INDICES: [u32; 6] = [\n    0, 1, 2,\n    0, 2, 3,\n];\n"));
+}
+
+#[test]
+fn write_with_config() {
+    let data = ObjData::load_buf(QUAD.as_bytes()).unwrap();
+    let config = RustSourceConfig {
+        prefix: "QUAD".to_string(),
+        normals: false,
+        small_indices: true,
+        ..RustSourceConfig::default()
+    };
+    let mut out = Vec::new();
+    data.write_rust_source(&mut out, &config).unwrap();
+    let source = String::from_utf8(out).unwrap();
+
+    assert!(source.contains("pub const QUAD_POSITIONS"));
+    assert!(!source.contains("NORMALS"));
+    assert!(source.contains("pub const QUAD_UVS"));
+    assert!(source.contains("pub const QUAD_INDICES: [u16; 6]"));
+}
@@ -0,0 +1,73 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, SamplingMode, SurfaceSample};
+
+static SQUARE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 1/1 2/2 3/3 4/4
+";
+
+fn min_spacing(samples: &[SurfaceSample]) -> f32 {
+    let mut min = f32::INFINITY;
+    for (i, a) in samples.iter().enumerate() {
+        for b in &samples[i + 1..] {
+            let d: f32 = (0..3).map(|k| (a.position[k] - b.position[k]).powi(2)).sum();
+            min = min.min(d.sqrt());
+        }
+    }
+    min
+}
+
+#[test]
+fn uniform_samples() {
+    let data = ObjData::load_buf(SQUARE.as_bytes()).unwrap();
+    let samples = data.sample_surface(1000, SamplingMode::Uniform);
+    assert_eq!(samples.len(), 1000);
+    for sample in &samples {
+        let [x, y, z] = sample.position;
+        assert!((0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) && z == 0.0);
+        assert_eq!(sample.normal, [0.0, 0.0, 1.0]);
+        let [u, v] = sample.texture.unwrap();
+        assert!((u - x).abs() < 1e-5 && (v - y).abs() < 1e-5);
+    }
+    // Both triangles of the square get about half of the points.
+    let below = samples.iter().filter(|s| s.position[0] > s.position[1]).count();
+    assert!((400..600).contains(&below), "{}", below);
+
+    assert_eq!(data.sample_surface(1000, SamplingMode::Uniform), samples);
+    assert_ne!(data.sample_surface_with_seed(1000, SamplingMode::Uniform, 1), samples);
+    assert!(ObjData::load_buf("v 0 0 0\nv 1 0 0\nf 1 2 1\n".as_bytes())
+        .unwrap()
+        .sample_surface(10, SamplingMode::Uniform)
+        .is_empty());
+}
+
+#[test]
+fn poisson_disk_samples() {
+    let data = ObjData::load_buf(SQUARE.as_bytes()).unwrap();
+    let poisson = data.sample_surface(200, SamplingMode::PoissonDisk);
+    assert_eq!(poisson.len(), 200);
+    let uniform = data.sample_surface(200, SamplingMode::Uniform);
+    assert!(min_spacing(&poisson) > 2.0 * min_spacing(&uniform));
+}
@@ -0,0 +1,85 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, Preset};
+
+#[test]
+fn rendering() {
+    let source = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 1 1 0
+v 9 9 9
+vn 0 0 2
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+f 1//2 2//2 5//2
+";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let report = obj.sanitize(Preset::Rendering);
+    assert!(report.triangulated.unwrap().is_complete());
+    assert_eq!(report.normals.unwrap().renormalized, 1);
+    let welded = report.welded.unwrap();
+    assert_eq!((welded.position, welded.normal), (1, 1));
+    // After welding the duplicate position, the unused one is at index 4.
+    assert_eq!(report.compacted.unwrap().position[4], None);
+    assert!(report.degenerate.is_none() && report.manifold.is_none());
+
+    assert_eq!(obj.position.len(), 4);
+    assert_eq!(obj.normal, vec![[0.0, 0.0, 1.0]]);
+    assert!(obj.objects[0].groups[0].polys.iter().all(|p| p.0.len() == 3));
+}
+
+#[test]
+fn printing() {
+    // A tetrahedron with one face flipped, one split at a duplicated position and a degenerate
+    // and a duplicate face on top.
+    let source = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+v 0 0 1
+f 1 3 2
+f 1 2 4
+f 2 3 5
+f 1 4 3
+f 3 1 4
+f 1 1 2
+";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let report = obj.sanitize(Preset::Printing);
+    assert_eq!(report.welded.unwrap().position, 1);
+    assert_eq!(report.degenerate.unwrap().total(), 1);
+    assert_eq!(report.duplicates.unwrap().total(), 1);
+    assert!(report.manifold.unwrap().is_watertight());
+    assert!(report.triangulated.is_none());
+    assert_eq!(obj.objects[0].groups[0].polys.len(), 4);
+}
+
+#[test]
+fn polygons_with_missing_attributes_are_removed_first() {
+    let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1 2 3\nf 1 2 9\nf 1//1 2//1 3//2\n";
+    for preset in [Preset::Rendering, Preset::Printing] {
+        let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+        let report = obj.sanitize(preset);
+        assert_eq!(report.invalid, 2);
+        assert_eq!(obj.objects[0].groups[0].polys.len(), 1);
+        assert!(obj.validate_indices().is_ok());
+    }
+}
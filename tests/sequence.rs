@@ -0,0 +1,60 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::fs;
+
+use obj::sequence::{self, SequenceError};
+
+fn frame(z: f32) -> String {
+    format!("v 0 0 {0}\nv 1 0 {0}\nv 0 1 {0}\no tri\nf 1 2 3\n", z)
+}
+
+#[test]
+fn load_pattern() {
+    let dir = std::env::temp_dir().join("obj_sequence_load_pattern");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for number in [2, 1, 10, 3] {
+        fs::write(dir.join(format!("frame_{:04}.obj", number)), frame(number as f32)).unwrap();
+    }
+    fs::write(dir.join("frame_x.obj"), "").unwrap();
+    fs::write(dir.join("frame_0001.mtl"), "").unwrap();
+
+    let sequence = sequence::load_pattern(dir.join("frame_####.obj")).unwrap();
+    let numbers: Vec<_> = sequence.frames.iter().map(|f| f.number).collect();
+    assert_eq!(numbers, [1, 2, 3, 10]);
+    assert_eq!(sequence.frames[3].position[1], [1.0, 0.0, 10.0]);
+    assert_eq!(sequence.topology.objects[0].name.as_ref(), "tri");
+    assert_eq!(sequence.topology.position, sequence.frames[0].position);
+
+    fs::write(
+        dir.join("frame_0004.obj"),
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\no tri\nf 1 3 2\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        sequence::load_pattern(dir.join("frame_####.obj")),
+        Err(SequenceError::TopologyMismatch { frame: 4 })
+    ));
+    assert!(matches!(
+        sequence::load_pattern(dir.join("shot_###.obj")),
+        Err(SequenceError::NoFrames)
+    ));
+    assert!(matches!(
+        sequence::load_pattern(dir.join("frame.obj")),
+        Err(SequenceError::InvalidPattern(_))
+    ));
+}
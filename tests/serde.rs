@@ -0,0 +1,89 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "serde")]
+
+use obj::{Obj, ObjData};
+
+static QUAD: &str = "
+mtllib quad.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vn 0 0 1
+usemtl red
+f 1/1/1 2/1/1 3/1/1 4/1/1
+l 1 3
+";
+
+static QUAD_MTL: &str = "
+newmtl red
+Kd 1 0 0
+map_Kd red.png
+";
+
+fn load() -> Obj {
+    let mut obj = Obj {
+        data: ObjData::load_buf(QUAD.as_bytes()).unwrap(),
+        path: Default::default(),
+    };
+    obj.load_mtls_fn(|_, _| Ok(QUAD_MTL.as_bytes())).unwrap();
+    obj
+}
+
+#[test]
+fn obj_data_json_round_trip() {
+    let obj = load();
+    let json = serde_json::to_string(&obj.data).unwrap();
+    let decoded: ObjData = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, obj.data);
+}
+
+#[test]
+fn mtl_json_round_trip() {
+    let obj = load();
+    let mtl = &obj.data.material_libs[0];
+    assert_eq!(mtl.materials.len(), 1);
+
+    let json = serde_json::to_string(mtl).unwrap();
+    let decoded: obj::Mtl = serde_json::from_str(&json).unwrap();
+    assert_eq!(&decoded, mtl);
+}
+
+#[test]
+fn json_mesh() {
+    let obj = load();
+    let mesh = obj.data.to_json_mesh();
+    assert_eq!(mesh.version, 1);
+    assert_eq!(mesh.positions.len(), 12);
+    assert_eq!(mesh.normals.len(), 12);
+    assert_eq!(mesh.uvs.len(), 8);
+    assert_eq!(mesh.primitives.len(), 1);
+    assert_eq!(mesh.primitives[0].material, Some(0));
+    assert_eq!(mesh.primitives[0].indices, [0, 1, 2, 0, 2, 3]);
+
+    let json: serde_json::Value = serde_json::to_value(&mesh).unwrap();
+    assert_eq!(
+        json["materials"],
+        serde_json::json!([{ "name": "red", "diffuse": [1.0, 0.0, 0.0], "diffuse_texture": "red.png" }])
+    );
+    assert_eq!(serde_json::from_value::<obj::JsonMesh>(json).unwrap(), mesh);
+
+    let bare = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).unwrap();
+    let json = serde_json::to_value(bare.to_json_mesh()).unwrap();
+    assert!(json.get("normals").is_none() && json.get("uvs").is_none());
+    assert!(json["primitives"][0].get("material").is_none());
+}
@@ -0,0 +1,94 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use obj::{IndexTuple, ObjData, SimplePolygon};
+
+const N: usize = 8;
+
+/// A flat grid of `N` by `N` unit quads in the XY plane. With `split`, the right half uses a
+/// different material than the left one.
+fn grid(split: bool) -> ObjData {
+    let mut source = String::new();
+    for y in 0..=N {
+        for x in 0..=N {
+            writeln!(source, "v {} {} 0", x, y).unwrap();
+        }
+    }
+    for half in 0..2 {
+        if split {
+            writeln!(source, "usemtl material{}", half).unwrap();
+        }
+        for y in 0..N {
+            for x in half * N / 2..(half + 1) * N / 2 {
+                let i = y * (N + 1) + x + 1;
+                writeln!(source, "f {} {} {} {}", i, i + 1, i + N + 2, i + N + 1).unwrap();
+            }
+        }
+    }
+    ObjData::load_buf(source.as_bytes()).unwrap()
+}
+
+fn referenced(obj: &ObjData) -> HashSet<usize> {
+    obj.objects
+        .iter()
+        .flat_map(|o| &o.groups)
+        .flat_map(|g| &g.polys)
+        .flat_map(|p| p.0.iter().map(|t| t.position))
+        .collect()
+}
+
+#[test]
+fn simplify_flat_grid() {
+    let mut obj = grid(false);
+    let remaining = obj.simplify(0.25);
+    assert_eq!(remaining, N * N / 2);
+    assert_eq!(obj.stats().triangles, remaining);
+    assert!((obj.surface_area() - (N * N) as f32).abs() < 1e-3);
+    assert!(obj.check_manifold().is_manifold());
+
+    // Boundary vertices are preserved.
+    let used = referenced(&obj);
+    assert!((0..=N).all(|x| used.contains(&x)));
+}
+
+#[test]
+fn polygons_with_missing_positions_are_kept() {
+    let mut obj = grid(false);
+    let invalid = SimplePolygon([0, 1, 1000].iter().map(|&p| IndexTuple::new(p, None, None)).collect());
+    obj.objects[0].groups[0].polys.push(invalid.clone());
+    obj.simplify(0.25);
+    assert_eq!(obj.objects[0].groups[0].polys.last(), Some(&invalid));
+    assert!((obj.surface_area() - (N * N) as f32).abs() < 1e-3);
+}
+
+#[test]
+fn simplify_keeps_material_boundaries() {
+    let mut obj = grid(true);
+    obj.simplify(0.0);
+    let used = referenced(&obj);
+    assert!((0..=N).all(|y| used.contains(&(y * (N + 1) + N / 2))));
+    assert!((obj.surface_area() - (N * N) as f32).abs() < 1e-3);
+}
+
+#[test]
+fn simplify_full_ratio_only_triangulates() {
+    let mut obj = grid(false);
+    assert_eq!(obj.simplify(1.0), 2 * N * N);
+    assert_eq!(referenced(&obj).len(), (N + 1) * (N + 1));
+}
@@ -0,0 +1,74 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static CUBE: &str = "
+v 0 0 0
+v 2 0 0
+v 2 2 0
+v 0 2 0
+v 0 0 2
+v 2 0 2
+v 2 2 2
+v 0 2 2
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+
+#[test]
+fn slice_closed_mesh() {
+    let obj = ObjData::load_buf(CUBE.as_bytes()).unwrap();
+    let contours = obj.slice([0., 0., 0.5], [0., 0., 1.]);
+    assert_eq!(contours.len(), 1);
+    assert!(contours[0].closed);
+    let mut points = contours[0].points.clone();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(points, vec![[0., 0., 0.5], [0., 2., 0.5], [2., 0., 0.5], [2., 2., 0.5]]);
+}
+
+#[test]
+fn slice_through_vertices() {
+    let obj = ObjData::load_buf(CUBE.as_bytes()).unwrap();
+    // The plane contains the top face and touches the side faces along its edges.
+    let contours = obj.slice([0., 0., 2.], [0., 0., 1.]);
+    assert_eq!(contours.len(), 1);
+    assert!(contours[0].closed);
+    assert_eq!(contours[0].points.len(), 4);
+    // Geometry on the plane counts as lying above it, so the bottom face does not produce a contour.
+    assert!(obj.slice([0., 0., 0.], [0., 0., 1.]).is_empty());
+
+    // A diagonal plane through two vertical edges.
+    let contours = obj.slice([0., 0., 0.], [1., -1., 0.]);
+    assert_eq!(contours.len(), 1);
+    assert!(contours[0].closed);
+    assert_eq!(contours[0].points.len(), 4);
+}
+
+#[test]
+fn slice_open_mesh() {
+    let source = CUBE.replace("f 5 6 7 8\n", "").replace("f 1 4 3 2\n", "");
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let contours = obj.slice([1., 0., 0.], [1., 0., 0.]);
+    assert_eq!(contours.len(), 2);
+    assert!(contours.iter().all(|c| !c.closed && c.points.len() == 2));
+
+    assert!(obj.slice([0., 0., 3.], [0., 0., 1.]).is_empty());
+}
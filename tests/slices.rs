@@ -0,0 +1,81 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{LoadConfig, Mtl, ObjData, ObjData64, ObjError, WriteToBuf, WriteToFmt};
+
+static SQUARE: &str = "mtllib square.mtl\r\nv 0 0 0\r\nv 1 0 0\r\nv 1 1 0\r\nv 0 1 0\r\nusemtl red\r\nf 1 2 3 4\r\n";
+
+#[test]
+fn parse_slices_like_readers() {
+    let from_buf = ObjData::load_buf(SQUARE.as_bytes()).unwrap();
+    let from_slice: ObjData = ObjData::parse_slice(SQUARE.as_bytes(), LoadConfig::default()).unwrap();
+    let from_str: ObjData = ObjData::parse_str(SQUARE, LoadConfig::default()).unwrap();
+    assert_eq!(from_slice, from_buf);
+    assert_eq!(from_str, from_buf);
+
+    let precise = ObjData64::parse_slice(SQUARE.as_bytes(), LoadConfig::default()).unwrap();
+    assert_eq!(precise.position[2], [1.0, 1.0, 0.0]);
+}
+
+#[test]
+fn invalid_utf8_line() {
+    let mut input = b"v 0 0 0\nv 1 0 0\no ".to_vec();
+    input.extend_from_slice(&[0xff, 0xfe]);
+    match ObjData::<f32>::parse_slice(&input, LoadConfig::default()) {
+        Err(ObjError::InvalidUtf8 { line_number }) => assert_eq!(line_number, 2),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn write_to_fmt_matches_write_to_buf() {
+    let data = ObjData::load_buf(SQUARE.as_bytes()).unwrap();
+    let mut buf = Vec::new();
+    data.write_to_buf(&mut buf).unwrap();
+    let mut text = String::new();
+    data.write_to_fmt(&mut text).unwrap();
+    assert_eq!(text.as_bytes(), buf.as_slice());
+}
+
+#[test]
+fn object_write_traits_match() {
+    let data = ObjData::load_buf(SQUARE.as_bytes()).unwrap();
+    let object = &data.objects[0];
+    let mut buf = Vec::new();
+    object.write_to_buf(&mut buf).unwrap();
+    let mut text = String::new();
+    object.write_to_fmt(&mut text).unwrap();
+    assert_eq!(text.as_bytes(), buf.as_slice());
+    assert!(text.ends_with("f 1 2 3 4\n"));
+}
+
+#[test]
+fn mtl_from_str() {
+    let mut mtl = Mtl::new("square.mtl".to_string());
+    mtl.reload_str("newmtl red\nKd 1 0 0\nnewmtl blue\nKd 0 0 1\n").unwrap();
+    assert_eq!(mtl.materials.len(), 2);
+    assert_eq!(mtl.materials[1].kd, Some([0.0, 0.0, 1.0]));
+
+    let mut text = String::new();
+    mtl.write_to_fmt(&mut text).unwrap();
+    let mut buf = Vec::new();
+    mtl.write_to_buf(&mut buf).unwrap();
+    assert_eq!(text.as_bytes(), buf.as_slice());
+
+    let mut reloaded = Mtl::new(mtl.filename.clone());
+    reloaded.reload_str(&text).unwrap();
+    assert_eq!(reloaded, mtl);
+}
@@ -0,0 +1,64 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::ObjData;
+
+static MESH: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 1 0 -1
+v 1 1 -1
+v 5 5 5
+v 6 5 5
+v 5 6 5
+f 1 2 3
+f 1 3 4
+g side
+f 2 5 3
+f 3 5 6
+f 7 8 9
+l 1 7
+";
+
+#[test]
+fn derive_smoothing_groups() {
+    let mut data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    assert_eq!(data.derive_smoothing_groups(30f32.to_radians()), 2);
+
+    let groups = &data.objects[0].groups;
+    let summary: Vec<_> = groups
+        .iter()
+        .map(|g| {
+            let ids: Vec<_> = (0..g.polys.len())
+                .map(|i| g.poly_render_state(i).smoothing_group)
+                .collect();
+            (&*g.name, ids, g.end_render_state().smoothing_group, g.lines.len())
+        })
+        .collect();
+    assert_eq!(summary, [("default", vec![1, 1], 1, 0), ("side", vec![2, 2, 0], 0, 1)]);
+
+    let text = data.to_string();
+    assert!(text.contains("g default\ns 1\nf 1 2 3\n"));
+    assert!(text.contains("g side\ns 2\nf 2 5 3\nf 3 5 6\ns off\nf 7 8 9\nl 1 7\n"));
+
+    // With a wide enough angle, the fold is smoothed as well.
+    let mut data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    assert_eq!(data.derive_smoothing_groups(100f32.to_radians()), 1);
+    let side = &data.objects[0].groups[1];
+    assert_eq!(side.poly_render_state(0).smoothing_group, 1);
+}
@@ -0,0 +1,89 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{BoundingBox, ObjData};
+
+/// A 10 by 10 grid of unit quads in the xy plane, and a triangle floating above it.
+fn plane() -> ObjData {
+    let mut source = String::new();
+    for y in 0..=10 {
+        for x in 0..=10 {
+            source += &format!("v {} {} 0\n", x, y);
+        }
+    }
+    for y in 0..10 {
+        for x in 0..10 {
+            let i = y * 11 + x + 1;
+            source += &format!("f {} {} {} {}\n", i, i + 1, i + 12, i + 11);
+        }
+    }
+    source += "v 20 20 5\nv 21 20 5\nv 20 21 5\nf -3 -2 -1\n";
+    ObjData::load_buf(source.as_bytes()).unwrap()
+}
+
+#[test]
+fn closest_point() {
+    let obj = plane();
+    let index = obj.build_spatial_index();
+
+    let hit = index.closest_point([2.5, 3.5, 1.0]).unwrap();
+    assert_eq!(hit.polygon, 32);
+    assert_eq!(hit.point, [2.5, 3.5, 0.0]);
+    assert_eq!(hit.distance, 1.0);
+
+    // Outside of the grid, and closest to the separate triangle.
+    let hit = index.closest_point([30.0, 20.0, 5.0]).unwrap();
+    assert_eq!(hit.polygon, 100);
+    assert_eq!(hit.point, [21.0, 20.0, 5.0]);
+
+    // The same answers as checking every polygon.
+    for p in [[-3.0, 4.0, 2.0], [9.9, 9.9, -1.0], [15.0, 15.0, 2.5], [5.0, 5.0, 0.0]] {
+        let hit = index.closest_point(p).unwrap();
+        let brute = obj
+            .position
+            .iter()
+            .take(121)
+            .map(|v| ((v[0] - p[0]).powi(2) + (v[1] - p[1]).powi(2)).sqrt())
+            .fold(f32::INFINITY, f32::min);
+        assert!(hit.distance <= ((brute * brute) + p[2] * p[2]).sqrt() + 1e-5);
+    }
+
+    assert!(ObjData::default()
+        .build_spatial_index()
+        .closest_point([0.0; 3])
+        .is_none());
+}
+
+#[test]
+fn query_box() {
+    let index = plane().build_spatial_index();
+    let bbox = BoundingBox {
+        min: [1.5, 1.5, -1.0],
+        max: [2.5, 1.9, 1.0],
+    };
+    assert_eq!(index.query_box(&bbox), [11, 12]);
+
+    let above = BoundingBox {
+        min: [0.0, 0.0, 1.0],
+        max: [30.0, 30.0, 10.0],
+    };
+    assert_eq!(index.query_box(&above), [100]);
+    let far = BoundingBox {
+        min: [50.0; 3],
+        max: [60.0; 3],
+    };
+    assert!(index.query_box(&far).is_empty());
+}
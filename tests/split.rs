@@ -0,0 +1,293 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::fs;
+
+use obj::{IndexTuple, Obj, ObjData, ObjError, SplitBudget, SplitBy, SplitMtl, WriteConfig};
+
+static SCENE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 5 5 5
+vn 0 0 1
+o a
+g wall
+usemtl brick
+f 1//1 2//1 3//1
+usemtl wood
+f 1//1 3//1 4//1
+o b
+g floor
+f 5 1 4
+";
+
+#[test]
+fn split_by_material() {
+    let obj = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    let parts = obj.split_by_material();
+    let names: Vec<_> = parts.iter().map(|(m, _)| m.as_ref().map(|m| m.name())).collect();
+    assert_eq!(names, [Some("brick"), Some("wood"), None]);
+
+    let (_, brick) = &parts[0];
+    assert_eq!(brick.position, vec![[0., 0., 0.], [1., 0., 0.], [1., 1., 0.]]);
+    assert_eq!(brick.normal.len(), 1);
+    assert_eq!(brick.objects.len(), 1);
+    assert_eq!(brick.objects[0].groups[0].index, 0);
+
+    let (_, wood) = &parts[1];
+    assert_eq!(wood.position.len(), 3);
+    assert_eq!(wood.objects.len(), 1);
+    assert_eq!(&*wood.objects[0].name, "a");
+    assert_eq!(&*wood.objects[0].groups[0].name, "wall");
+    // The second material range of `wall` becomes the first range in its own part.
+    assert_eq!(wood.objects[0].groups[0].index, 0);
+    assert_eq!(
+        wood.objects[0].groups[0].polys[0].0,
+        vec![
+            IndexTuple::new(0, None, Some(0)),
+            IndexTuple::new(1, None, Some(0)),
+            IndexTuple::new(2, None, Some(0)),
+        ]
+    );
+
+    let (_, untextured) = &parts[2];
+    assert_eq!(&*untextured.objects[0].name, "b");
+    assert_eq!(untextured.position, vec![[5., 5., 5.], [0., 0., 0.], [0., 1., 0.]]);
+    assert!(untextured.normal.is_empty());
+
+    // Every part is self-contained and survives a round trip through the writer.
+    for (_, part) in &parts {
+        let mut out = Vec::new();
+        part.write_to_buf(&mut out).unwrap();
+        assert_eq!(&ObjData::load_buf(out.as_slice()).unwrap(), part);
+    }
+}
+
+#[test]
+fn split_by_material_skips_missing_attributes() {
+    let obj = ObjData::load_buf(format!("{}f 1 2 9\nf 1//1 2//1 3//4\nf 1 2 3\n", SCENE).as_bytes()).unwrap();
+    let parts = obj.split_by_material();
+    let (_, untextured) = &parts[2];
+    assert_eq!(untextured.objects[0].groups[0].polys.len(), 2);
+    assert!(untextured.validate_indices().is_ok());
+}
+
+#[test]
+fn split_connected_components() {
+    let source = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 5 0 0
+v 6 0 0
+v 5 1 0
+v 9 9 9
+v 8 8 8
+o first
+f 4 5 6
+f 1 2 3
+g lines
+l 3 7
+o second
+f 2 1 3
+l 8 4
+";
+    let obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    let parts = obj.split_connected_components();
+    assert_eq!(parts.len(), 2);
+
+    let (a, b) = (&parts[0], &parts[1]);
+    assert_eq!(a.position, vec![[5., 0., 0.], [6., 0., 0.], [5., 1., 0.], [8., 8., 8.]]);
+    assert_eq!(a.objects.len(), 2);
+    assert_eq!(a.objects[1].groups[0].lines.len(), 1);
+    assert_eq!(b.position.len(), 4);
+    assert_eq!(
+        b.objects.iter().map(|o| &*o.name).collect::<Vec<_>>(),
+        vec!["first", "second"]
+    );
+    assert_eq!(b.objects[0].groups.len(), 2);
+    assert_eq!(&*b.objects[0].groups[1].name, "lines");
+
+    // Elements with missing attributes neither connect nor form parts.
+    let obj = ObjData::load_buf(format!("{}f 1 4 20\nl 7 2/5\n", source).as_bytes()).unwrap();
+    let parts = obj.split_connected_components();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[1].position.len(), 4);
+}
+
+#[test]
+fn extract() {
+    let obj = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+
+    let all = obj.extract(&["?"]);
+    assert_eq!(all.objects.len(), 2);
+
+    let wall = obj.extract(&["a"]);
+    assert_eq!(wall.objects.len(), 1);
+    let indices: Vec<_> = wall.objects[0].groups.iter().map(|g| g.index).collect();
+    assert_eq!(indices, [0, 1]);
+    assert_eq!(wall.objects[0].groups[1].material.as_ref().unwrap().name(), "wood");
+    assert_eq!(wall.position.len(), 4);
+    assert_eq!(wall.normal.len(), 1);
+
+    let floor = obj.extract(&["fl*".to_string()]);
+    assert_eq!(&*floor.objects[0].name, "b");
+    assert_eq!(floor.position, vec![[5., 5., 5.], [0., 0., 0.], [0., 1., 0.]]);
+    assert!(floor.normal.is_empty());
+
+    assert!(obj.extract(&["c"]).objects.is_empty());
+}
+
+#[test]
+fn save_split() {
+    let dir = std::env::temp_dir().join("obj_split_save_split");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("scene.obj"), format!("mtllib scene.mtl{}", SCENE)).unwrap();
+    fs::write(dir.join("scene.mtl"), "newmtl brick\nKd 1 0 0\nnewmtl wood\nKd 0 1 0\n").unwrap();
+    let mut obj = Obj::load(dir.join("scene.obj")).unwrap();
+    obj.load_mtls().unwrap();
+
+    let out = dir.join("objects");
+    let paths = obj.data.save_split(&out, SplitBy::Object, SplitMtl::Shared).unwrap();
+    let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(names, ["a.obj", "b.obj"]);
+    assert!(out.join("scene.mtl").exists());
+    let a = Obj::load(&paths[0]).unwrap();
+    assert_eq!(a.data.position.len(), 4);
+    assert_eq!(a.data.material_libs[0].filename, "scene.mtl");
+
+    let out = dir.join("materials");
+    let paths = obj.data.save_split(&out, SplitBy::Material, SplitMtl::PerFile).unwrap();
+    let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(names, ["brick.obj", "wood.obj", "default.obj"]);
+    assert!(!out.join("scene.mtl").exists());
+    assert!(!out.join("default.mtl").exists());
+    let mut wood = Obj::load(&paths[1]).unwrap();
+    wood.load_mtls().unwrap();
+    let lib = &wood.data.material_libs[0];
+    assert_eq!(lib.filename, "wood.mtl");
+    assert_eq!(lib.materials.len(), 1);
+    assert_eq!(lib.materials[0].name, "wood");
+
+    let invalid = ObjData::load_buf(format!("{}f 1 2 9\n", SCENE).as_bytes()).unwrap();
+    let out = dir.join("invalid");
+    let result = invalid.save_split(&out, SplitBy::Object, SplitMtl::Shared);
+    assert!(matches!(result, Err(ObjError::ElementIndexOutOfRange { .. })));
+    assert!(!out.exists());
+}
+
+#[test]
+fn write_filtered() {
+    let obj = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    obj.write_filtered(&mut out, |object, group| &*object.name == "a" && group.index == 1)
+        .unwrap();
+    let selected = ObjData::load_buf(out.as_slice()).unwrap();
+    assert_eq!(selected.position, vec![[0., 0., 0.], [1., 1., 0.], [0., 1., 0.]]);
+    assert_eq!(selected.objects.len(), 1);
+    assert_eq!(selected.objects[0].groups.len(), 1);
+    assert_eq!(selected.objects[0].groups[0].material.as_ref().unwrap().name(), "wood");
+
+    let mut out = Vec::new();
+    obj.write_filtered(&mut out, |_, _| true).unwrap();
+    let mut all = Vec::new();
+    obj.write_to_buf(&mut all).unwrap();
+    assert_eq!(out, all);
+
+    let mut out = Vec::new();
+    obj.write_filtered(&mut out, |_, _| false).unwrap();
+    assert!(ObjData::load_buf(out.as_slice()).unwrap().position.is_empty());
+}
+
+#[test]
+fn write_relative_indices() {
+    let obj = ObjData::load_buf(SCENE.as_bytes()).unwrap();
+    let mut out = Vec::new();
+    obj.write_to_buf_with(&mut out, WriteConfig::default().relative_indices(true))
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("o b\nv 5 5 5\nv 0 0 0\nv 0 1 0\ng floor\nf -3 -2 -1\n"));
+    assert!(text.contains("f -4//-1 -3//-1 -2//-1\n"));
+
+    let reloaded = ObjData::load_buf(text.as_bytes()).unwrap();
+    // Vertices shared by both objects are written twice.
+    assert_eq!(reloaded.position.len(), 7);
+    let positions = |data: &ObjData| -> Vec<[f32; 3]> {
+        let groups = data.objects.iter().flat_map(|o| &o.groups);
+        let tuples = groups.flat_map(|g| &g.polys).flat_map(|p| p.0.iter().copied());
+        tuples.map(|t| data.position[t.position]).collect()
+    };
+    assert_eq!(positions(&reloaded), positions(&obj));
+    assert_eq!(reloaded.objects[0].groups[1].material.as_ref().unwrap().name(), "wood");
+}
+
+#[test]
+fn split_file_by_budget() {
+    let dir = std::env::temp_dir().join("obj_split_file_by_budget");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("scene.obj"), format!("mtllib scene.mtl{}", SCENE)).unwrap();
+    fs::write(dir.join("scene.mtl"), "newmtl brick\nKd 1 0 0\n").unwrap();
+
+    let out = dir.join("parts");
+    let budget = SplitBudget {
+        max_vertices: Some(4),
+        ..SplitBudget::default()
+    };
+    let paths = ObjData::split_file_by_budget(dir.join("scene.obj"), &out, budget).unwrap();
+    let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(names, ["scene_0.obj", "scene_1.obj"]);
+    assert!(out.join("scene.mtl").exists());
+
+    // The two faces of `a` share two vertices, and the face of `b` doesn't fit with them.
+    let first = Obj::load(&paths[0]).unwrap();
+    assert_eq!(first.data.position.len(), 4);
+    assert_eq!(first.data.material_libs[0].filename, "scene.mtl");
+    let groups = &first.data.objects[0].groups;
+    let materials: Vec<_> = groups
+        .iter()
+        .map(|g| (g.index, g.material.as_ref().unwrap().name()))
+        .collect();
+    assert_eq!(materials, [(0, "brick"), (1, "wood")]);
+    let second = Obj::load(&paths[1]).unwrap();
+    assert_eq!(&*second.data.objects[0].name, "b");
+    assert_eq!(second.data.position, vec![[5., 5., 5.], [0., 0., 0.], [0., 1., 0.]]);
+
+    let budget = SplitBudget {
+        max_elements: Some(1),
+        ..SplitBudget::default()
+    };
+    let paths = ObjData::split_file_by_budget(dir.join("scene.obj"), &out, budget).unwrap();
+    assert_eq!(paths.len(), 3);
+}
+
+#[test]
+fn split_file_by_budget_keeps_libraries_inside_dir() {
+    let dir = std::env::temp_dir().join("obj_split_file_by_budget_outside");
+    let _ = fs::remove_dir_all(&dir);
+    let input = dir.join("input");
+    fs::create_dir_all(&input).unwrap();
+    fs::write(input.join("scene.obj"), format!("mtllib ../shared.mtl{}", SCENE)).unwrap();
+    fs::write(dir.join("shared.mtl"), "newmtl brick\nKd 1 0 0\n").unwrap();
+
+    let out = dir.join("out").join("parts");
+    let paths = ObjData::split_file_by_budget(input.join("scene.obj"), &out, SplitBudget::default()).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert!(!dir.join("out").join("shared.mtl").exists());
+}
@@ -0,0 +1,99 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{scan, BoundingBox, Obj, ObjData};
+
+static MIXED: &str = "
+mtllib a.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 2 2 0
+vt 0 0
+vn 0 0 1
+vn 0 0 1
+o first
+usemtl red
+f 1/1 2/1 3/1
+f 1//2 2//2 3//2 4//2
+g other
+usemtl blue
+f 1 2 3 4 5
+o second
+usemtl red
+f 2 3 4
+l 1 5
+";
+
+#[test]
+fn stats_counts() {
+    let obj = ObjData::load_buf(MIXED.as_bytes()).unwrap();
+    let stats = obj.stats();
+    assert_eq!(stats.positions, 5);
+    assert_eq!(stats.texture_coords, 1);
+    assert_eq!(stats.normals, 2);
+    assert_eq!(stats.triangles, 2);
+    assert_eq!(stats.quads, 1);
+    assert_eq!(stats.ngons, 1);
+    assert_eq!(stats.polygons(), 4);
+    assert_eq!(stats.lines, 1);
+    assert_eq!(stats.objects, 2);
+    assert_eq!(stats.groups, 3);
+    assert_eq!(stats.materials, 2);
+    assert_eq!(stats.material_libs, 1);
+    assert_eq!(stats.position_index_range, Some((0, 4)));
+    assert_eq!(stats.texture_index_range, Some((0, 0)));
+    assert_eq!(stats.normal_index_range, Some((1, 1)));
+}
+
+#[test]
+fn stats_display() {
+    let sponza = Obj::load("test_assets/sponza.obj").unwrap();
+    let stats = sponza.data.stats();
+    assert_eq!(stats.positions, sponza.data.position.len());
+    assert!(stats.to_string().starts_with("vertices: "));
+}
+
+#[test]
+fn scan_counts() {
+    let source = format!(
+        "{}v 0 -1 0 1 0 0\ncurv 0 1 1 2\nparm u 0 1\nend\nbevel on\n# bevel\n",
+        MIXED
+    );
+    let report = scan(source.as_bytes()).unwrap();
+    assert_eq!(report.positions, 6);
+    assert_eq!(report.texture_coords, 1);
+    assert_eq!(report.normals, 2);
+    assert_eq!(report.polygons, 4);
+    assert_eq!(report.lines, 1);
+    assert_eq!(report.objects, 2);
+    assert_eq!(report.groups, 1);
+    assert_eq!(report.materials, 2);
+    assert_eq!(report.material_libs, ["a.mtl"]);
+    assert_eq!(
+        report.bounding_box,
+        Some(BoundingBox {
+            min: [0., -1., 0.],
+            max: [2., 2., 0.],
+        })
+    );
+    assert!(report.vertex_colors);
+    assert_eq!(report.extensions, ["bevel", "curv", "end", "parm"]);
+
+    assert!(scan("v 1 x 0\n".as_bytes()).is_err());
+    assert_eq!(scan("".as_bytes()).unwrap().bounding_box, None);
+}
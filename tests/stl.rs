@@ -0,0 +1,104 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::stl::{self, StlError, StlFormat};
+use obj::ObjData;
+
+fn triangles(data: &ObjData) -> Vec<Vec<[f32; 3]>> {
+    let polys = data.objects.iter().flat_map(|o| &o.groups).flat_map(|g| &g.polys);
+    polys
+        .map(|p| p.0.iter().map(|t| data.position[t.position]).collect())
+        .collect()
+}
+
+static TETRAHEDRON: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+f 1 3 2
+f 1 2 4
+f 1 4 3
+f 2 3 4
+";
+
+#[test]
+fn binary_round_trip() {
+    let data = ObjData::load_buf(TETRAHEDRON.as_bytes()).unwrap();
+    let mut buf = Vec::new();
+    stl::write(&data, &mut buf, StlFormat::Binary).unwrap();
+    assert_eq!(buf.len(), 84 + 4 * 50);
+
+    let loaded = stl::load_buf(&buf[..]).unwrap();
+    assert_eq!(loaded.position.len(), 4);
+    assert_eq!(loaded.normal.len(), 4);
+    assert_eq!(loaded.normal[0], [0.0, 0.0, -1.0]);
+    assert_eq!(triangles(&loaded), triangles(&data));
+}
+
+#[test]
+fn ascii_round_trip() {
+    let data = ObjData::load_buf(TETRAHEDRON.as_bytes()).unwrap();
+    let mut buf = Vec::new();
+    stl::write(&data, &mut buf, StlFormat::Ascii).unwrap();
+    let text = String::from_utf8(buf.clone()).unwrap();
+    assert!(text.starts_with("solid default\n"));
+    assert_eq!(text.matches("endfacet").count(), 4);
+
+    let loaded = stl::load_buf(&buf[..]).unwrap();
+    assert_eq!(&*loaded.objects[0].name, "default");
+    assert_eq!(loaded.position.len(), 4);
+    assert_eq!(triangles(&loaded), triangles(&data));
+}
+
+#[test]
+fn ascii_without_facet_normals() {
+    let source = "solid part\nfacet normal 0 0 0\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\nendloop\nendfacet\nendsolid part\n";
+    let loaded = stl::load_buf(source.as_bytes()).unwrap();
+    assert_eq!(&*loaded.objects[0].name, "part");
+    assert_eq!(loaded.normal, vec![[0.0, 0.0, 1.0]]);
+}
+
+#[test]
+fn binary_header_starting_with_solid() {
+    let data = ObjData::load_buf(TETRAHEDRON.as_bytes()).unwrap();
+    let mut buf = Vec::new();
+    stl::write(&data, &mut buf, StlFormat::Binary).unwrap();
+    buf[..5].copy_from_slice(b"solid");
+    assert_eq!(stl::load_buf(&buf[..]).unwrap().objects[0].groups[0].polys.len(), 4);
+}
+
+#[test]
+fn malformed_input() {
+    let source = "solid part\nfacet normal 0 0\nendsolid part\n";
+    match stl::load_buf(source.as_bytes()) {
+        Err(StlError::Malformed { line_number: 2, .. }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut truncated = [0u8; 84];
+    truncated[80] = 2;
+    assert!(matches!(stl::load_buf(&truncated[..]), Err(StlError::UnexpectedEof)));
+}
+
+#[test]
+fn write_requires_triangles() {
+    let data = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3\nf 1 2 3 4\n".as_bytes()).unwrap();
+    match stl::write(&data, &mut Vec::new(), StlFormat::Binary) {
+        Err(StlError::NotTriangulated { polygon: 1 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,92 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, SubdivisionScheme};
+
+static CUBE: &str = "
+v -1 -1 -1
+v 1 -1 -1
+v 1 1 -1
+v -1 1 -1
+v -1 -1 1
+v 1 -1 1
+v 1 1 1
+v -1 1 1
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+
+fn assert_close(a: [f32; 3], b: [f32; 3]) {
+    assert!((0..3).all(|i| (a[i] - b[i]).abs() < 1e-5), "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn catmull_clark_cube() {
+    let mut obj = ObjData::load_buf(CUBE.as_bytes()).unwrap();
+    obj.subdivide(SubdivisionScheme::CatmullClark, 1);
+    let stats = obj.stats();
+    assert_eq!(stats.quads, 24);
+    assert_eq!(stats.positions, 8 + 12 + 6);
+    assert_close(obj.position[6], [5. / 9.; 3]);
+    assert!(obj.check_manifold().is_watertight());
+
+    // Normals are regenerated, one per position.
+    assert_eq!(obj.normal.len(), obj.position.len());
+    assert_close(obj.normal[6], [1. / 3f32.sqrt(); 3]);
+
+    obj.subdivide(SubdivisionScheme::CatmullClark, 2);
+    assert_eq!(obj.stats().quads, 24 * 16);
+    assert!(obj.signed_volume() > 0.);
+}
+
+#[test]
+fn loop_triangulates_and_refines() {
+    let mut obj = ObjData::load_buf(CUBE.as_bytes()).unwrap();
+    obj.subdivide(SubdivisionScheme::Loop, 1);
+    let stats = obj.stats();
+    assert_eq!(stats.triangles, 12 * 4);
+    // The triangulated cube has 18 edges.
+    assert_eq!(stats.positions, 8 + 18);
+    assert!(obj.check_manifold().is_watertight());
+    assert!(obj.signed_volume() < 8.);
+}
+
+#[test]
+fn texture_coordinates_are_interpolated() {
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\nf 1/1 2/2 3/3 4/4\n";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    obj.subdivide(SubdivisionScheme::CatmullClark, 1);
+    assert_eq!(obj.texture.len(), 4 + 4 + 1);
+    assert_eq!(obj.texture[8], [0.5, 0.5]);
+    // The center of an open quad stays in place, while its boundary is smoothed.
+    assert_close(obj.position[8], [0.5, 0.5, 0.]);
+    assert_close(obj.position[0], [0.125, 0.125, 0.]);
+    assert_close(obj.normal[8], [0., 0., 1.]);
+}
+
+#[test]
+fn polygons_with_missing_positions_are_kept() {
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 9\nf 1 2 3\n";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    obj.subdivide(SubdivisionScheme::Loop, 1);
+    assert_eq!(obj.stats().triangles, 4);
+    assert_eq!(obj.stats().quads, 1);
+    assert!(obj.validate_indices().is_err());
+}
@@ -0,0 +1,71 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::generate_tangents;
+
+static POSITIONS: &[[f32; 3]] = &[[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+static NORMALS: &[[f32; 3]] = &[[0., 0., 1.]; 4];
+static INDICES: &[u32] = &[0, 1, 2, 0, 2, 3];
+
+fn assert_close(a: [f32; 4], b: [f32; 4]) {
+    for (x, y) in a.iter().zip(b.iter()) {
+        assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn tangents_follow_u_direction() {
+    let uvs = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]];
+    let tangents = generate_tangents(POSITIONS, NORMALS, &uvs, INDICES);
+    assert_eq!(tangents.len(), 4);
+    for t in tangents {
+        assert_close(t, [1., 0., 0., 1.]);
+    }
+}
+
+#[test]
+fn mirrored_uvs_flip_handedness() {
+    // Mirror the texture along v, so the bitangent points against cross(n, t).
+    let uvs = [[0., 1.], [1., 1.], [1., 0.], [0., 0.]];
+    let tangents = generate_tangents(POSITIONS, NORMALS, &uvs, INDICES);
+    for t in tangents {
+        assert_close(t, [1., 0., 0., -1.]);
+    }
+}
+
+#[test]
+fn degenerate_uvs_produce_perpendicular_tangents() {
+    let uvs = [[0., 0.]; 4];
+    let tangents = generate_tangents(POSITIONS, NORMALS, &uvs, INDICES);
+    for t in tangents {
+        assert!(t[2].abs() < 1e-5);
+        assert!(((t[0] * t[0] + t[1] * t[1]).sqrt() - 1.0).abs() < 1e-5);
+    }
+}
+
+#[cfg(feature = "mikktspace")]
+#[test]
+fn mikktspace_tangents() {
+    let uvs = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]];
+    for t in obj::generate_mikktspace_tangents(POSITIONS, NORMALS, &uvs, INDICES) {
+        assert_close(t, [1., 0., 0., 1.]);
+    }
+
+    let mirrored = [[0., 1.], [1., 1.], [1., 0.], [0., 0.]];
+    for t in obj::generate_mikktspace_tangents(POSITIONS, NORMALS, &mirrored, INDICES) {
+        assert_close(t, [1., 0., 0., -1.]);
+    }
+}
@@ -0,0 +1,55 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "image")]
+
+use obj::{Material, Obj, ObjData, TextureSlot};
+
+#[test]
+fn load_material_textures() {
+    let dir = std::env::temp_dir().join("obj_load_material_textures");
+    std::fs::create_dir_all(dir.join("maps")).unwrap();
+    image::RgbaImage::from_pixel(2, 1, image::Rgba([255, 0, 0, 255]))
+        .save(dir.join("maps/red.png"))
+        .unwrap();
+    std::fs::write(
+        dir.join("maps/scene.mtl"),
+        "newmtl red\nmap_Kd -s 1 1 red.png\nmap_Ks missing.png\n",
+    )
+    .unwrap();
+
+    let mut material = Material::new("red".to_string());
+    material.map_kd = Some("red.png".to_string());
+    let textures = material.load_textures(dir.join("maps"));
+    assert_eq!(textures.len(), 1);
+    assert_eq!(
+        textures[0].image.as_ref().unwrap().to_rgba8().get_pixel(1, 0).0,
+        [255, 0, 0, 255]
+    );
+
+    let mut obj = Obj {
+        data: ObjData::load_buf("mtllib maps/scene.mtl\n".as_bytes()).unwrap(),
+        path: dir.clone(),
+    };
+    obj.load_mtls().unwrap();
+    let loaded = obj.load_textures();
+    assert_eq!(loaded.len(), 1);
+    let (material, textures) = &loaded[0];
+    assert_eq!(material.name, "red");
+    assert_eq!(textures[0].slot, TextureSlot::Diffuse);
+    assert_eq!(textures[0].path, dir.join("maps/red.png"));
+    assert_eq!(textures[0].image.as_ref().unwrap().width(), 2);
+    assert_eq!(textures[1].slot, TextureSlot::Specular);
+    assert!(textures[1].image.is_err());
+}
@@ -0,0 +1,117 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "tobj")]
+
+use std::path::PathBuf;
+
+use obj::{Material, Mtl, Obj, ObjData, ObjMaterial};
+
+static SOURCE: &str = "
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vn 0 0 1
+o quad
+usemtl red
+f 1/1/1 2/2/1 3/3/1 4/1/1
+o tri
+usemtl blue
+f 1/1/1 3/3/1 4/2/1
+";
+
+static MTL: &str = "
+newmtl red
+Kd 1 0 0
+Ns 10
+illum 2
+map_Kd red.png
+Tf 1 0.5 0
+newmtl blue
+Kd 0 0 1
+";
+
+fn load_tobj(options: &tobj::LoadOptions) -> (Vec<tobj::Model>, Vec<tobj::Material>) {
+    let (models, materials) = tobj::load_obj_buf(&mut SOURCE.as_bytes(), options, |_| {
+        tobj::load_mtl_buf(&mut MTL.as_bytes())
+    })
+    .unwrap();
+    (models, materials.unwrap())
+}
+
+#[test]
+fn from_tobj() {
+    for options in &[tobj::LoadOptions::default(), tobj::GPU_LOAD_OPTIONS] {
+        let (models, materials) = load_tobj(options);
+        let data = ObjData::from_tobj(&models, &materials, "scene.mtl");
+        assert_eq!(data.objects.len(), 2);
+        assert_eq!(&*data.objects[0].name, "quad");
+        let polys = &data.objects[0].groups[0].polys;
+        let corners: usize = polys.iter().map(|p| p.0.len()).sum();
+        assert_eq!(corners, if options.triangulate { 6 } else { 4 });
+        for poly in polys {
+            for t in &poly.0 {
+                assert_eq!(data.normal[t.normal.unwrap()], [0.0, 0.0, 1.0]);
+            }
+        }
+        match &data.objects[1].groups[0].material {
+            Some(ObjMaterial::Mtl(m)) => assert_eq!(m.kd, Some([0.0, 0.0, 1.0])),
+            other => panic!("unexpected material {:?}", other),
+        }
+        assert_eq!(data.material_libs[0].materials.len(), 2);
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mut obj = Obj {
+        data: ObjData::load_buf(SOURCE.as_bytes()).unwrap(),
+        path: PathBuf::new(),
+    };
+    obj.load_mtls_fn(|_, _| Ok(MTL.as_bytes())).unwrap();
+    let data = obj.data;
+    let (models, materials) = data.to_tobj();
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[1].name, "tri");
+    assert_eq!(models[0].mesh.face_arities, vec![4]);
+    assert_eq!(models[1].mesh.material_id, Some(1));
+    assert_eq!(materials[0].diffuse_texture.as_deref(), Some("red.png"));
+
+    let back = ObjData::from_tobj(&models, &materials, "scene.mtl");
+    let positions = |data: &ObjData, object: usize| -> Vec<[f32; 3]> {
+        data.objects[object].groups[0].polys[0]
+            .0
+            .iter()
+            .map(|t| data.position[t.position])
+            .collect()
+    };
+    assert_eq!(positions(&back, 0), positions(&data, 0));
+    assert_eq!(positions(&back, 1), positions(&data, 1));
+}
+
+#[test]
+fn materials() {
+    let mut mtl = Mtl::new("scene.mtl".to_string());
+    mtl.reload(MTL.as_bytes()).unwrap();
+    let red: &Material = &mtl.materials[0];
+    let converted = Material::from(&tobj::Material::from(red));
+    assert_eq!(&converted, red);
+    assert_eq!(converted.tf, Some([1.0, 0.5, 0.0]));
+    assert_eq!(Mtl::from_tobj("scene.mtl".to_string(), &mtl.to_tobj()), mtl);
+}
@@ -0,0 +1,83 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use obj::{LoadConfig, Obj, ObjData};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records the names of the spans and the messages of the events it sees.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut names = self.0.lock().unwrap();
+        names.push(span.metadata().name().to_string());
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("event {}", event.metadata().target()));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn load_and_pass_spans() {
+    let recorder = Recorder::default();
+    let src = "mtllib quad.mtl\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut obj = Obj {
+            data: ObjData::load_buf(src.as_bytes()).unwrap(),
+            path: Default::default(),
+        };
+        obj.load_mtls_fn(|_, _| Ok("newmtl red\n".as_bytes())).unwrap();
+        ObjData::load_buf_with(src.as_bytes(), LoadConfig::new().strict(false)).unwrap();
+        obj.data.to_triangles();
+        obj.data.compact();
+    });
+    let names = recorder.0.lock().unwrap().clone();
+    assert_eq!(
+        names,
+        [
+            "load",
+            "event obj::instrument",
+            "resolve",
+            "load_mtl",
+            "load",
+            "event obj::instrument",
+            "resolve",
+            "to_triangles",
+            "compact",
+        ]
+    );
+}
@@ -0,0 +1,184 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{NormalizeMode, ObjData};
+
+static TRIANGLE: &str = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vn 0 0 1
+vn 1 1 0
+f 1//1 2//1 3//2
+";
+
+fn assert_close(a: [f32; 3], b: [f32; 3]) {
+    for (x, y) in a.iter().zip(b.iter()) {
+        assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn translate_and_scale() {
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    obj.scale([2., 3., 4.]);
+    obj.translate([1., 1., 1.]);
+    assert_close(obj.position[1], [3., 1., 1.]);
+    assert_close(obj.position[2], [1., 4., 1.]);
+    assert_close(obj.normal[0], [0., 0., 1.]);
+}
+
+#[test]
+fn non_uniform_scale_uses_inverse_transpose() {
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    obj.scale([2., 1., 1.]);
+    // The normal of the plane x + y = 0 becomes the normal of the plane x / 2 + y = 0.
+    let expected = [1. / 5f32.sqrt(), 2. / 5f32.sqrt(), 0.];
+    assert_close(obj.normal[1], expected);
+}
+
+#[test]
+fn rotate_about_z() {
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    obj.rotate([0., 0., 2.], std::f32::consts::FRAC_PI_2);
+    assert_close(obj.position[1], [0., 1., 0.]);
+    assert_close(obj.position[2], [-1., 0., 0.]);
+    assert_close(obj.normal[0], [0., 0., 1.]);
+}
+
+#[test]
+fn transform_with_translation_column() {
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    obj.transform(&[[1., 0., 0., 0.], [0., 1., 0., 0.], [0., 0., 1., 0.], [5., 6., 7., 1.]]);
+    assert_close(obj.position[0], [5., 6., 7.]);
+}
+
+#[test]
+fn z_up_to_y_up() {
+    use obj::{CoordinateConvention, Handedness};
+
+    let blender = CoordinateConvention::Z_UP_RIGHT_HANDED;
+    let gl = CoordinateConvention::Y_UP_RIGHT_HANDED;
+    assert_eq!(blender.handedness(), Handedness::Right);
+    assert_eq!(gl.handedness(), Handedness::Right);
+    assert_eq!(CoordinateConvention::Y_UP_LEFT_HANDED.handedness(), Handedness::Left);
+    assert_eq!(CoordinateConvention::Z_UP_LEFT_HANDED.handedness(), Handedness::Left);
+
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    let before = obj.objects[0].groups[0].polys[0].clone();
+    obj.convert_coordinates(&blender, &gl);
+    // Blender's up (+Z) becomes +Y and its forward (+Y) becomes -Z.
+    assert_close(obj.position[2], [0., 0., -1.]);
+    assert_close(obj.normal[0], [0., 1., 0.]);
+    assert_eq!(obj.objects[0].groups[0].polys[0], before);
+}
+
+#[test]
+fn handedness_change_flips_winding_and_units() {
+    use obj::CoordinateConvention;
+
+    let from = CoordinateConvention::Y_UP_RIGHT_HANDED.with_meters_per_unit(0.01);
+    let to = CoordinateConvention::Y_UP_LEFT_HANDED;
+
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    let mut reversed = obj.objects[0].groups[0].polys[0].clone();
+    reversed.0[1..].reverse();
+    obj.convert_coordinates(&from, &to);
+    assert_close(obj.position[1], [0.01, 0., 0.]);
+    assert_close(obj.normal[0], [0., 0., -1.]);
+    assert_eq!(obj.objects[0].groups[0].polys[0], reversed);
+}
+
+#[test]
+fn flip_winding_and_invert_normals() {
+    use obj::IndexTuple;
+
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    obj.flip_winding();
+    obj.invert_normals();
+    assert_eq!(
+        obj.objects[0].groups[0].polys[0].0,
+        vec![
+            IndexTuple::new(0, None, Some(0)),
+            IndexTuple::new(2, None, Some(1)),
+            IndexTuple::new(1, None, Some(0)),
+        ]
+    );
+    assert_close(obj.normal[0], [0., 0., -1.]);
+    assert_close(obj.normal[1], [-1., -1., 0.]);
+}
+
+#[test]
+fn uv_transforms() {
+    let mut obj = ObjData::load_buf("vt 0.25 0.75\nvt 1 0\n".as_bytes()).unwrap();
+    obj.flip_uv_v();
+    assert_eq!(obj.texture, vec![[0.25, 0.25], [1., 1.]]);
+    obj.transform_uvs([1., 0.5], [2., 0.5]);
+    assert_eq!(obj.texture, vec![[1.5, 0.625], [3., 1.]]);
+}
+
+#[test]
+fn mirror_with_and_without_seam() {
+    use obj::Axis;
+
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0.001 1 0\nvn 1 0 1\nf 1//1 2//1 3//1 4//1\n";
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    obj.mirror(Axis::PosX, None);
+    assert_eq!(obj.position.len(), 8);
+    assert_eq!(obj.normal[1], [-1., 0., 1.]);
+    let polys = &obj.objects[0].groups[0].polys;
+    assert_eq!(polys.len(), 2);
+    let mirrored: Vec<_> = polys[1].0.iter().map(|t| (t.position, t.normal)).collect();
+    assert_eq!(mirrored, vec![(4, Some(1)), (7, Some(1)), (6, Some(1)), (5, Some(1))]);
+    let area = obj.surface_area();
+
+    let mut obj = ObjData::load_buf(source.as_bytes()).unwrap();
+    obj.mirror(Axis::NegX, Some(0.01));
+    assert_eq!(obj.position.len(), 6);
+    assert_eq!(obj.position[3], [0., 1., 0.]);
+    assert!((obj.surface_area() - area).abs() < 0.01);
+    // The halves share the seam edge, so only the outer boundary remains.
+    let report = obj.check_manifold();
+    assert!(report.is_manifold());
+    assert_eq!(report.boundary_edges.len(), 6);
+}
+
+#[test]
+fn normalize() {
+    let mut obj = ObjData::load_buf(TRIANGLE.as_bytes()).unwrap();
+    obj.scale([4., 2., 1.]);
+    obj.translate([1., 1., 1.]);
+    let matrix = obj.normalize(NormalizeMode::UnitCube);
+    assert_close(obj.position[0], [-0.5, -0.25, 0.]);
+    assert_close(obj.position[1], [0.5, -0.25, 0.]);
+    assert_close([matrix[3][0], matrix[3][1], matrix[3][2]], [-0.75, -0.5, -0.25]);
+    assert_close(obj.normal[0], [0., 0., 1.]);
+
+    let matrix = obj.normalize(NormalizeMode::CenterOrigin);
+    assert_eq!(matrix[0][0], 1.);
+    assert_close(obj.position[1], [0.5, -0.25, 0.]);
+
+    obj.normalize(NormalizeMode::FitSphere(2.));
+    let sphere = obj.bounding_sphere().unwrap();
+    assert_close(sphere.center, [0.; 3]);
+    assert!((sphere.radius - 2.).abs() < 1e-5);
+
+    let mut point = ObjData::load_buf("v 1 2 3".as_bytes()).unwrap();
+    point.normalize(NormalizeMode::UnitCube);
+    assert_eq!(point.position, [[0., 0., 0.]]);
+    let mut empty = ObjData::default();
+    assert_eq!(empty.normalize(NormalizeMode::FitSphere(1.))[0], [1., 0., 0., 0.]);
+}
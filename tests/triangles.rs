@@ -0,0 +1,82 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{ObjData, ObjMaterial};
+
+static MESH: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 1
+vn 0 0 1
+g quad
+usemtl red
+f 1/1/1 2/1/1 3/2/1 4/2/1
+g mixed
+f 1/1 2 3
+f 1 2
+";
+
+#[test]
+fn resolve_and_fan() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let triangles: Vec<_> = data.triangles().collect();
+    assert_eq!(triangles.len(), 3);
+
+    let first = &triangles[0];
+    assert_eq!(first.positions, [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]);
+    assert_eq!(first.texture, Some([[0.0, 0.0], [0.0, 0.0], [1.0, 1.0]]));
+    assert_eq!(first.normals, Some([[0.0, 0.0, 1.0]; 3]));
+    assert_eq!(first.material.map(ObjMaterial::name), Some("red"));
+    assert_eq!(triangles[1].positions[2], [0.0, 1.0, 0.0]);
+
+    // Missing attributes on some corners leave the whole triangle without them.
+    let mixed = &triangles[2];
+    assert_eq!(&*mixed.group.name, "mixed");
+    assert_eq!(mixed.texture, None);
+    assert_eq!(mixed.normals, None);
+}
+
+#[test]
+fn missing_attributes() {
+    let data = ObjData::load_buf(format!("{}f 1/1/1 2/1/1 3/1/7\nf 1 2 9\n", MESH).as_bytes()).unwrap();
+    let triangles: Vec<_> = data.triangles().collect();
+    assert_eq!(triangles.len(), 4);
+    assert_eq!(triangles[3].texture, Some([[0.0, 0.0]; 3]));
+    assert_eq!(triangles[3].normals, None);
+    assert_eq!(data.as_triangle_soup().len(), 12);
+}
+
+#[test]
+fn per_group() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let group = &data.objects[0].groups[0];
+    assert_eq!(group.triangles(&data).count(), 2);
+    assert!(group.triangles(&data).all(|t| t.group == group));
+}
+
+#[test]
+fn triangle_soup() {
+    let data = ObjData::load_buf(MESH.as_bytes()).unwrap();
+    let soup = data.as_triangle_soup();
+    assert_eq!(soup.len(), 9);
+    assert_eq!(&soup[3..6], &[[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]);
+
+    let collinear = ObjData::load_buf("v 0 0 0\nv 1 0 0\nv 2 0 0\nf 1 2 3\n".as_bytes()).unwrap();
+    assert!(collinear.as_triangle_soup().is_empty());
+}
@@ -0,0 +1,133 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use obj::{Axis, Material, ObjData, Projection, UvBounds};
+
+static QUAD_AND_WALL: &str = "
+v 0 0 0
+v 2 0 0
+v 2 1 0
+v 0 1 0
+v 2 0 -1
+v 2 1 -1
+vt 0.3 0.3
+f 1/1 2/1 3/1 4/1
+f 2 5 6 3
+l 1/1 5/1
+";
+
+fn uvs(obj: &ObjData, poly: usize) -> Vec<[f32; 2]> {
+    let poly = &obj.objects[0].groups[0].polys[poly];
+    poly.0.iter().map(|t| obj.texture[t.texture.unwrap()]).collect()
+}
+
+#[test]
+fn planar_uvs() {
+    let mut obj = ObjData::load_buf(QUAD_AND_WALL.as_bytes()).unwrap();
+    obj.generate_uvs(Projection::Planar(Axis::PosZ));
+    assert_eq!(obj.texture.len(), 6);
+    assert_eq!(uvs(&obj, 0), vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]]);
+    assert!(obj.objects[0].groups[0].lines[0].0.iter().all(|t| t.texture.is_none()));
+
+    obj.generate_uvs(Projection::Planar(Axis::NegZ));
+    assert_eq!(uvs(&obj, 0), vec![[1., 0.], [0., 0.], [0., 1.], [1., 1.]]);
+}
+
+#[test]
+fn box_uvs() {
+    let mut obj = ObjData::load_buf(QUAD_AND_WALL.as_bytes()).unwrap();
+    obj.generate_uvs(Projection::Box);
+    // The corners shared by both polygons get one coordinate per projection axis.
+    assert_eq!(obj.texture.len(), 8);
+    assert_eq!(uvs(&obj, 0), vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]]);
+    assert_eq!(uvs(&obj, 1), vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]]);
+}
+
+#[test]
+fn spherical_uvs() {
+    let mut obj = ObjData::load_buf("v 0 -1 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nv -1 0 -1\nf 1 2 3 4\n".as_bytes()).unwrap();
+    obj.generate_uvs(Projection::Spherical);
+    let uv = uvs(&obj, 0);
+    assert_eq!(uv[0][1], 0.0);
+    assert_eq!(uv[2][1], 1.0);
+    assert!((uv[1][0] - 0.75).abs() < 1e-6);
+    assert!((uv[3][0] - 0.5).abs() < 1e-6);
+
+    let mut out = Vec::new();
+    obj.write_to_buf(&mut out).unwrap();
+    assert_eq!(ObjData::load_buf(out.as_slice()).unwrap(), obj);
+}
+
+#[test]
+fn apply_atlas() {
+    let source = "
+vt 0 0
+vt 1 1
+vt 2 0
+o a
+usemtl brick
+f 1/1 2/2 3/2
+usemtl glass
+f 1/1 2/2 3/1
+usemtl wood
+f 1/1 2/2 3/3
+";
+    let mut obj = ObjData::load_buf(format!("v 0 0 0\nv 1 0 0\nv 1 1 0\n{}", source).as_bytes()).unwrap();
+    let mut atlas = HashMap::new();
+    atlas.insert(
+        "brick".to_string(),
+        UvBounds {
+            min: [0.0, 0.0],
+            max: [0.5, 0.5],
+        },
+    );
+    atlas.insert(
+        "wood".to_string(),
+        UvBounds {
+            min: [0.5, 0.0],
+            max: [1.0, 0.5],
+        },
+    );
+    let material = Arc::new(Material::new("atlas".to_string()));
+    let report = obj.apply_atlas(&atlas, material);
+    assert_eq!(report.groups, 2);
+    assert_eq!(report.tiled_polygons, [2]);
+
+    let groups = &obj.objects[0].groups;
+    let names: Vec<_> = groups.iter().map(|g| g.material.as_ref().unwrap().name()).collect();
+    assert_eq!(names, ["atlas", "glass", "atlas"]);
+    assert_eq!(uvs(&obj, 0), [[0.0, 0.0], [0.5, 0.5], [0.5, 0.5]]);
+    let glass: Vec<_> = groups[1].polys[0]
+        .0
+        .iter()
+        .map(|t| obj.texture[t.texture.unwrap()])
+        .collect();
+    assert_eq!(glass, [[0.0, 0.0], [1.0, 1.0], [0.0, 0.0]]);
+    let wood: Vec<_> = groups[2].polys[0]
+        .0
+        .iter()
+        .map(|t| obj.texture[t.texture.unwrap()])
+        .collect();
+    assert_eq!(wood, [[0.5, 0.0], [1.0, 0.5], [1.5, 0.0]]);
+    // Shared coordinates are stored once per rectangle.
+    assert_eq!(obj.texture.len(), 7);
+
+    obj.objects[0].merge_groups_by_material();
+    assert_eq!(obj.objects[0].groups.len(), 2);
+}
@@ -0,0 +1,105 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{average_cache_miss_ratio, optimize_vertex_cache, IndexedBuffers, ObjData};
+
+/// A regular grid of `n` by `n` quads split into triangles, emitted in a cache-hostile order.
+fn scrambled_grid(n: u32) -> Vec<u32> {
+    let mut tris = Vec::new();
+    for y in 0..n {
+        for x in 0..n {
+            let v = y * (n + 1) + x;
+            tris.push([v, v + 1, v + n + 2]);
+            tris.push([v, v + n + 2, v + n + 1]);
+        }
+    }
+    // Interleave the triangles with a large stride to destroy locality.
+    let len = tris.len();
+    let mut out = Vec::new();
+    for i in 0..len {
+        out.extend_from_slice(&tris[(i * 97) % len]);
+    }
+    out
+}
+
+fn sorted_triangles(indices: &[u32]) -> Vec<[u32; 3]> {
+    let mut tris: Vec<[u32; 3]> = indices
+        .chunks_exact(3)
+        .map(|t| {
+            // Rotate so the smallest index comes first, preserving winding.
+            let m = (0..3).min_by_key(|&i| t[i]).unwrap();
+            [t[m], t[(m + 1) % 3], t[(m + 2) % 3]]
+        })
+        .collect();
+    tris.sort_unstable();
+    tris
+}
+
+#[test]
+fn optimization_reduces_cache_misses() {
+    let n = 32;
+    let mut indices = scrambled_grid(n);
+    let original = indices.clone();
+    let before = average_cache_miss_ratio(&indices, 16);
+    optimize_vertex_cache(&mut indices, ((n + 1) * (n + 1)) as usize);
+    let after = average_cache_miss_ratio(&indices, 16);
+
+    assert!(after < before * 0.6, "{} -> {}", before, after);
+    assert!(after < 1.0);
+    assert_eq!(sorted_triangles(&indices), sorted_triangles(&original));
+}
+
+#[test]
+fn optimize_indexed_buffers() {
+    let n = 16;
+    let mut src = String::new();
+    for y in 0..=n {
+        for x in 0..=n {
+            src.push_str(&format!("v {} {} 0\nvt {} {}\n", x, y, x, y));
+        }
+    }
+    src.push_str("usemtl a\n");
+    for (i, tri) in scrambled_grid(n).chunks_exact(3).enumerate() {
+        if i == (n * n) as usize {
+            src.push_str("usemtl b\n");
+        }
+        let [a, b, c] = [tri[0] + 1, tri[1] + 1, tri[2] + 1];
+        src.push_str(&format!("f {0}/{0} {1}/{1} {2}/{2}\n", a, b, c));
+    }
+    let data = ObjData::load_buf(src.as_bytes()).unwrap();
+
+    let original = data.to_indexed_buffers();
+    let mut buffers = original.clone();
+    buffers.optimize_vertex_cache();
+
+    assert_eq!(buffers.vertices.len(), original.vertices.len());
+    assert_eq!(buffers.submeshes.len(), 2);
+    for (a, b) in buffers.submeshes.iter().zip(original.submeshes.iter()) {
+        assert!(average_cache_miss_ratio(&a.indices, 16) < average_cache_miss_ratio(&b.indices, 16));
+        // The same triangles, expressed in terms of resolved positions.
+        let resolve = |buf: &IndexedBuffers, idx: &[u32]| {
+            let mut tris: Vec<_> = idx
+                .chunks_exact(3)
+                .map(|t| format!("{:?}", [0, 1, 2].map(|i| buf.vertices[t[i] as usize].position)))
+                .collect();
+            tris.sort();
+            tris
+        };
+        assert_eq!(resolve(&buffers, &a.indices), resolve(&original, &b.indices));
+    }
+    // Vertices are ordered by first use.
+    assert_eq!(buffers.submeshes[0].indices[0], 0);
+}
@@ -0,0 +1,92 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, ObjData};
+
+/// Two triangles meeting at a hard edge, with a texture seam at position 2.
+static HINGE: &str = "
+v 0 0 0 1 0 0
+v 1 0 0 0 1 0
+v 0 1 0 0 0 1
+v 0 0 1 1 1 1
+vt 0 0
+vt 1 0
+vt 0 1
+vt 1 1
+vt 0.5 0.5
+vn 0 0 1
+vn 0 1 0
+f 1/1/1 2/2/1 3/3/1
+f 1/1/2 4/4/2 2/5/2
+l 3 4
+";
+
+#[test]
+fn average_normals_by_position() {
+    let mut obj = ObjData::load_buf(HINGE.as_bytes()).unwrap();
+    obj.average_normals_by_position();
+    let d = std::f32::consts::FRAC_1_SQRT_2;
+    assert_eq!(
+        obj.normal,
+        vec![[0.0, d, d], [0.0, d, d], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]]
+    );
+    let polys = &obj.objects[0].groups[0].polys;
+    let normals: Vec<_> = polys.iter().flat_map(|p| p.0.iter().map(|t| t.normal)).collect();
+    assert_eq!(normals, [Some(0), Some(1), Some(2), Some(0), Some(3), Some(1)]);
+    assert_eq!(obj.objects[0].groups[0].lines[0].0[0].normal, None);
+}
+
+#[test]
+fn split_vertices() {
+    let mut obj = ObjData::load_buf(HINGE.as_bytes()).unwrap();
+    let original = obj.clone();
+    obj.split_vertices();
+
+    // Positions 1 and 2 are used with two normals each, 3 also without any.
+    assert_eq!(obj.position.len(), 8);
+    assert_eq!(obj.color.len(), 8);
+    assert_eq!(obj.texture.len(), 8);
+    assert_eq!(obj.normal.len(), 8);
+    for tuple in obj.objects[0].groups[0].polys.iter().flat_map(|p| &p.0) {
+        assert_eq!(
+            *tuple,
+            IndexTuple::new(tuple.position, Some(tuple.position), Some(tuple.position))
+        );
+    }
+    assert_eq!(
+        obj.objects[0].groups[0].lines[0].0,
+        vec![IndexTuple::new(6, None, None), IndexTuple::new(7, None, None)]
+    );
+    assert_eq!(obj.texture[6], [0.0, 0.0]);
+
+    // The resolved corners are unchanged.
+    let resolve = |obj: &ObjData| -> Vec<_> {
+        obj.objects[0].groups[0]
+            .polys
+            .iter()
+            .flat_map(|p| &p.0)
+            .map(|t| {
+                (
+                    obj.position[t.position],
+                    obj.color[t.position],
+                    obj.texture[t.texture.unwrap()],
+                    obj.normal[t.normal.unwrap()],
+                )
+            })
+            .collect()
+    };
+    assert_eq!(resolve(&obj), resolve(&original));
+}
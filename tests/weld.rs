@@ -0,0 +1,132 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "std")]
+
+use obj::{IndexTuple, ObjData, WeldConfig, WriteConfig};
+
+static DUPLICATED: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 1.00001 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 1
+vn 0 0 1
+f 1//1 2//1 3//1
+f 4//2 5//2 6//2
+";
+
+fn load(src: &str) -> ObjData {
+    ObjData::load_buf(src.as_bytes()).unwrap()
+}
+
+#[test]
+fn weld_exact_duplicates() {
+    let mut obj = load(DUPLICATED);
+    let report = obj.weld_vertices(0.0);
+    assert_eq!(report.position, 1);
+    assert_eq!(obj.position.len(), 5);
+
+    let polys = &obj.objects[0].groups[0].polys;
+    assert_eq!(polys[1].0[1], IndexTuple::new(2, None, Some(1)));
+}
+
+#[test]
+fn missing_attributes_stay_missing() {
+    let mut obj = load(&format!("{}f 1//1 8//1 9//3\n", DUPLICATED));
+    obj.weld_vertices_with_config(WeldConfig {
+        normal: true,
+        ..WeldConfig::default()
+    });
+    assert_eq!(obj.position.len(), 5);
+    let poly = &obj.objects[0].groups[0].polys[2];
+    let indices: Vec<_> = poly.0.iter().map(|t| (t.position, t.normal)).collect();
+    assert_eq!(indices, [(0, Some(0)), (6, Some(0)), (7, Some(1))]);
+    assert!(obj.validate_indices().is_err());
+}
+
+#[test]
+fn weld_within_epsilon() {
+    let mut obj = load(DUPLICATED);
+    let report = obj.weld_vertices(1e-3);
+    assert_eq!(report.position, 2);
+    assert_eq!(obj.position.len(), 4);
+
+    let polys = &obj.objects[0].groups[0].polys;
+    assert_eq!(polys[1].0[0].position, 1);
+    assert_eq!(polys[1].0[1].position, 2);
+    assert_eq!(polys[1].0[2].position, 3);
+    assert_eq!(obj.position[3], [0., 1., 0.]);
+}
+
+#[test]
+fn weld_normals() {
+    let mut obj = load(DUPLICATED);
+    let report = obj.weld_vertices_with_config(WeldConfig {
+        position: false,
+        normal: true,
+        ..WeldConfig::default()
+    });
+    assert_eq!(report.position, 0);
+    assert_eq!(report.normal, 1);
+    assert_eq!(obj.normal.len(), 1);
+    for poly in &obj.objects[0].groups[0].polys {
+        assert!(poly.0.iter().all(|t| t.normal == Some(0)));
+    }
+}
+
+#[test]
+fn weld_on_write() {
+    let obj = load("v 0 0 0 1 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 0 0 1 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 0 0\nf 1/1 2/2 3/1\nf 4/2 5/1 6/2\n");
+    let mut out = Vec::new();
+    obj.write_to_buf_with(&mut out, WriteConfig::new().weld(true)).unwrap();
+    let welded = ObjData::load_buf(out.as_slice()).unwrap();
+    // The first position differs from the fourth in color only.
+    assert_eq!(welded.position.len(), 4);
+    assert_eq!(welded.texture.len(), 1);
+    assert_eq!(welded.color[3], [0.0, 1.0, 0.0]);
+    assert_eq!(
+        welded.objects[0].groups[0].polys[1].0[..],
+        [
+            IndexTuple::new(3, Some(0), None),
+            IndexTuple::new(1, Some(0), None),
+            IndexTuple::new(2, Some(0), None)
+        ]
+    );
+
+    let mut plain = Vec::new();
+    obj.write_to_buf_with(&mut plain, WriteConfig::new()).unwrap();
+    assert_eq!(ObjData::load_buf(plain.as_slice()).unwrap(), obj);
+    assert_eq!(obj.position.len(), 6);
+}
+
+#[test]
+fn quantize_to_grid() {
+    let mut obj = load("v 0.02 -0.01 0\nv 0.98 0 0\nv 1.01 0.99 0\nv 1.04 -0.04 0\nvt 0.51 0.5\nvt 0.49 0.5\nf 1/1 2/1 3/1\nf 1/2 4/2 3/2\n");
+    let report = obj.quantize(0.1);
+    assert_eq!(report.position, 1);
+    assert_eq!(report.texture, 0);
+    assert_eq!(obj.position, vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]);
+    assert!(obj.position[0][1].is_sign_positive());
+    assert_eq!(obj.objects[0].groups[0].polys[1].0[1].position, 1);
+
+    let config = WeldConfig {
+        texture: true,
+        ..WeldConfig::default()
+    };
+    assert_eq!(obj.quantize_with_config(0.1, config).texture, 1);
+    assert_eq!(obj.texture, vec![[0.5, 0.5]]);
+}
@@ -0,0 +1,36 @@
+//   Copyright 2017 GFX Developers
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+#![cfg(feature = "wgpu")]
+
+use obj::{Position, PositionTexture, Vertex};
+
+#[test]
+fn vertex_layouts() {
+    let layout = Vertex::WGPU_LAYOUT;
+    assert_eq!(layout.array_stride, 32);
+    let offsets: Vec<_> = layout
+        .attributes
+        .iter()
+        .map(|a| (a.shader_location, a.offset))
+        .collect();
+    assert_eq!(offsets, vec![(0, 0), (1, 12), (2, 20)]);
+
+    assert_eq!(Position::WGPU_LAYOUT.array_stride, 12);
+    assert_eq!(PositionTexture::WGPU_LAYOUT.array_stride, 20);
+    assert_eq!(
+        PositionTexture::WGPU_ATTRIBUTES[1].format,
+        wgpu::VertexFormat::Float32x2
+    );
+}